@@ -1 +0,0 @@
-ALTER TABLE `keys` CHANGE `valid_until` `valid_until` INT (10);
\ No newline at end of file
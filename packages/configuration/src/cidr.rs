@@ -0,0 +1,186 @@
+//! IP address ranges in CIDR notation.
+//!
+//! Some configuration options contain a list of IP address ranges, for
+//! example: `["10.0.0.0/8", "fd00::/8"]`. A single IP address without prefix
+//! length is also valid. It's equivalent to a `/32` (IPv4) or `/128` (IPv6)
+//! range.
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+
+/// An IPv4 or IPv6 address range in CIDR notation.
+#[derive(SerializeDisplay, DeserializeFromStr, PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+/// Errors that can occur when parsing a [`Cidr`].
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CidrError {
+    #[error("invalid IP address in CIDR range: {0}")]
+    InvalidAddress(String),
+
+    #[error("invalid prefix length in CIDR range: {0}")]
+    InvalidPrefixLength(String),
+}
+
+impl Cidr {
+    /// Builds a new range. Host bits in the `network` address are cleared.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the prefix length is greater than the number
+    /// of bits in the address (32 for IPv4 and 128 for IPv6).
+    pub fn new(network: IpAddr, prefix_len: u8) -> Result<Self, CidrError> {
+        let max_prefix_len = match network {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+
+        if prefix_len > max_prefix_len {
+            return Err(CidrError::InvalidPrefixLength(prefix_len.to_string()));
+        }
+
+        Ok(Self {
+            network: Self::mask(&network, prefix_len),
+            prefix_len,
+        })
+    }
+
+    #[must_use]
+    pub fn network(&self) -> IpAddr {
+        self.network
+    }
+
+    #[must_use]
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns `true` if the IP address belongs to this range.
+    ///
+    /// IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`) are compared as IPv4
+    /// addresses.
+    #[must_use]
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(ipv6) => ipv6.to_ipv4_mapped().map_or(*ip, IpAddr::V4),
+            IpAddr::V4(_) => *ip,
+        };
+
+        match (self.network, ip) {
+            (IpAddr::V4(_), IpAddr::V4(_)) | (IpAddr::V6(_), IpAddr::V6(_)) => Self::mask(&ip, self.prefix_len) == self.network,
+            _ => false,
+        }
+    }
+
+    fn mask(ip: &IpAddr, prefix_len: u8) -> IpAddr {
+        match ip {
+            IpAddr::V4(ipv4) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(prefix_len)).unwrap_or(0);
+                IpAddr::V4((u32::from(*ipv4) & mask).into())
+            }
+            IpAddr::V6(ipv6) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(prefix_len)).unwrap_or(0);
+                IpAddr::V6((u128::from(*ipv6) & mask).into())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = CidrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, prefix_len) = match s.split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (s, None),
+        };
+
+        let network = IpAddr::from_str(address.trim()).map_err(|_| CidrError::InvalidAddress(address.to_owned()))?;
+
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len
+                .trim()
+                .parse::<u8>()
+                .map_err(|_| CidrError::InvalidPrefixLength(prefix_len.to_owned()))?,
+            None => match network {
+                IpAddr::V4(_) => 32,
+                IpAddr::V6(_) => 128,
+            },
+        };
+
+        Self::new(network, prefix_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use std::str::FromStr;
+
+    use super::{Cidr, CidrError};
+
+    fn ip(ip: &str) -> IpAddr {
+        IpAddr::from_str(ip).unwrap()
+    }
+
+    #[test]
+    fn it_should_be_parsed_from_a_string_in_cidr_notation() {
+        let cidr = Cidr::from_str("192.168.1.7/16").unwrap();
+
+        assert_eq!(cidr.network(), ip("192.168.0.0"));
+        assert_eq!(cidr.prefix_len(), 16);
+        assert_eq!(cidr.to_string(), "192.168.0.0/16");
+    }
+
+    #[test]
+    fn a_single_ip_address_should_be_parsed_as_a_range_with_only_that_address() {
+        assert_eq!(Cidr::from_str("10.0.0.1").unwrap().to_string(), "10.0.0.1/32");
+        assert_eq!(Cidr::from_str("::1").unwrap().to_string(), "::1/128");
+    }
+
+    #[test]
+    fn it_should_fail_parsing_an_invalid_range() {
+        assert!(matches!(Cidr::from_str("10.0.0/8"), Err(CidrError::InvalidAddress(_))));
+        assert!(matches!(
+            Cidr::from_str("10.0.0.0/33"),
+            Err(CidrError::InvalidPrefixLength(_))
+        ));
+        assert!(matches!(Cidr::from_str("fd00::/abc"), Err(CidrError::InvalidPrefixLength(_))));
+    }
+
+    #[test]
+    fn it_should_check_if_an_ip_address_belongs_to_the_range() {
+        let ipv4_range = Cidr::from_str("10.0.0.0/8").unwrap();
+
+        assert!(ipv4_range.contains(&ip("10.1.2.3")));
+        assert!(ipv4_range.contains(&ip("::ffff:10.1.2.3")));
+        assert!(!ipv4_range.contains(&ip("11.0.0.1")));
+        assert!(!ipv4_range.contains(&ip("fd00::1")));
+
+        let ipv6_range = Cidr::from_str("fd00::/8").unwrap();
+
+        assert!(ipv6_range.contains(&ip("fd12:3456::1")));
+        assert!(!ipv6_range.contains(&ip("2001:db8::1")));
+        assert!(!ipv6_range.contains(&ip("10.1.2.3")));
+    }
+
+    #[test]
+    fn a_range_with_a_zero_prefix_length_should_contain_all_the_addresses_of_the_same_family() {
+        let any_ipv4 = Cidr::from_str("0.0.0.0/0").unwrap();
+
+        assert!(any_ipv4.contains(&ip("1.2.3.4")));
+        assert!(!any_ipv4.contains(&ip("2001:db8::1")));
+    }
+}
@@ -4,6 +4,7 @@
 //! Torrust Tracker, which is a `BitTorrent` tracker server.
 //!
 //! The current version for configuration is [`v2`].
+pub mod cidr;
 pub mod v2_0_0;
 pub mod validator;
 
@@ -35,17 +36,61 @@ const ENV_VAR_CONFIG_TOML: &str = "TORRUST_TRACKER_CONFIG_TOML";
 /// The `tracker.toml` file location.
 pub const ENV_VAR_CONFIG_TOML_PATH: &str = "TORRUST_TRACKER_CONFIG_TOML_PATH";
 
+pub type AccessLog = v2_0_0::access_log::AccessLog;
+pub type AccessLogFormat = v2_0_0::access_log::Format;
 pub type Configuration = v2_0_0::Configuration;
+pub type Abuse = v2_0_0::abuse::Abuse;
+pub type Alerts = v2_0_0::alerts::Alerts;
+pub type AlertsSmtp = v2_0_0::alerts::Smtp;
+pub type AlertsSmtpSecurity = v2_0_0::alerts::SmtpSecurity;
+pub type AlertsWebhook = v2_0_0::alerts::Webhook;
+pub type AlertsWebhookKind = v2_0_0::alerts::WebhookKind;
+pub type AnnounceBudget = v2_0_0::announce_budget::AnnounceBudget;
+pub type KeySync = v2_0_0::key_sync::KeySync;
+pub type BindAddress = v2_0_0::bind_address::BindAddress;
+pub type ClientIpHeader = v2_0_0::network::ClientIpHeader;
 pub type Core = v2_0_0::core::Core;
+pub type DynamicInterval = v2_0_0::dynamic_interval::DynamicInterval;
+pub type Experiment = v2_0_0::experiment::Experiment;
+pub type ExperimentPeerSelection = v2_0_0::experiment::PeerSelection;
+pub type ConnectionIdScheme = v2_0_0::core::ConnectionIdScheme;
+pub type ConnectionIdSecretRotation = v2_0_0::secret_rotation::SecretRotation;
+pub type FailureMessages = v2_0_0::failure_messages::FailureMessages;
+pub type FailureRetry = v2_0_0::failure_retry::FailureRetry;
 pub type HealthCheckApi = v2_0_0::health_check_api::HealthCheckApi;
 pub type HttpApi = v2_0_0::tracker_api::HttpApi;
+pub type HttpApiJwt = v2_0_0::jwt::Jwt;
+pub type HttpApiRateLimit = v2_0_0::tracker_api::RateLimit;
+pub type ClientWhitelist = v2_0_0::client_whitelist::ClientWhitelist;
 pub type HttpTracker = v2_0_0::http_tracker::HttpTracker;
+pub type HttpTrackerAcme = v2_0_0::http_tracker::Acme;
+pub type HttpTrackerRateLimit = v2_0_0::http_tracker::RateLimit;
+pub type Maintenance = v2_0_0::maintenance::Maintenance;
+pub type Network = v2_0_0::network::Network;
+pub type Overload = v2_0_0::overload::Overload;
 pub type UdpTracker = v2_0_0::udp_tracker::UdpTracker;
+pub type UdpIoBackend = v2_0_0::udp_tracker::IoBackend;
+pub type UdpLoadShedding = v2_0_0::udp_tracker::LoadShedding;
+pub type UdpRateLimit = v2_0_0::udp_tracker::RateLimit;
+pub type UdpRateLimitAction = v2_0_0::udp_tracker::RateLimitAction;
+pub type WebSocketTracker = v2_0_0::websocket_tracker::WebSocketTracker;
 pub type Database = v2_0_0::database::Database;
+pub type DatabasePool = v2_0_0::database::Pool;
+pub type PeerDiversity = v2_0_0::peer_diversity::PeerDiversity;
+pub type PeerIpReplacement = v2_0_0::network::PeerIpReplacement;
+pub type Runtime = v2_0_0::runtime::Runtime;
+pub type SwarmSnapshot = v2_0_0::swarm_snapshot::SwarmSnapshot;
+pub type SwarmStore = v2_0_0::swarm_store::SwarmStore;
+pub type SwarmStoreBackend = v2_0_0::swarm_store::Backend;
+pub type TorrentAliases = v2_0_0::torrent_aliases::TorrentAliases;
+pub type TrackerIdScheme = v2_0_0::core::TrackerIdScheme;
+pub type Whitelist = v2_0_0::whitelist::Whitelist;
 pub type Driver = v2_0_0::database::Driver;
 pub type Threshold = v2_0_0::logging::Threshold;
 
 pub type AccessTokens = HashMap<String, String>;
+pub type AccessTokenRole = v2_0_0::tracker_api::AccessTokenRole;
+pub type AccessTokenRoles = v2_0_0::tracker_api::AccessTokenRoles;
 
 pub const LATEST_VERSION: &str = "2.0.0";
 
@@ -223,6 +268,17 @@ impl Info {
             config_toml_path,
         })
     }
+
+    /// Returns the path of the config file, unless the configuration is
+    /// provided with the `TORRUST_TRACKER_CONFIG_TOML` env var.
+    #[must_use]
+    pub fn config_toml_path(&self) -> Option<&str> {
+        if self.config_toml.is_some() {
+            None
+        } else {
+            Some(&self.config_toml_path)
+        }
+    }
 }
 
 /// Announce policy
@@ -306,6 +362,9 @@ pub enum Error {
 
     #[error("Missing mandatory configuration option. Option path: {path}")]
     MissingMandatoryOption { path: String },
+
+    #[error("Included configuration file not found: {path}")]
+    MissingIncludedFile { path: String },
 }
 
 impl From<figment::Error> for Error {
@@ -327,6 +386,26 @@ pub struct TslConfig {
     /// Path to the SSL key file.
     #[serde(default = "TslConfig::default_ssl_key_path")]
     pub ssl_key_path: Utf8PathBuf,
+
+    /// Path to a PEM file with the CA certificates used to verify client
+    /// certificates. When it's set, clients must present a certificate signed
+    /// by one of these CAs (mutual TLS).
+    #[serde(default = "TslConfig::default_ssl_client_ca_path")]
+    pub ssl_client_ca_path: Option<Utf8PathBuf>,
+
+    /// Certificates for other hostnames served by the same listener. The
+    /// certificate is selected with the hostname sent by the client in the
+    /// TLS handshake (SNI). The `ssl_cert_path` certificate is used when the
+    /// client does not send a hostname, or it sends another one.
+    ///
+    /// ```toml
+    /// [[http_trackers.tsl_config.sni_certificates]]
+    /// hostname = "tracker.example.org"
+    /// ssl_cert_path = "./storage/tracker/lib/tls/tracker.example.org.crt"
+    /// ssl_key_path = "./storage/tracker/lib/tls/tracker.example.org.key"
+    /// ```
+    #[serde(default = "TslConfig::default_sni_certificates")]
+    pub sni_certificates: Vec<SniCertificate>,
 }
 
 impl TslConfig {
@@ -339,4 +418,26 @@ impl TslConfig {
     fn default_ssl_key_path() -> Utf8PathBuf {
         Utf8PathBuf::new()
     }
+
+    fn default_ssl_client_ca_path() -> Option<Utf8PathBuf> {
+        None
+    }
+
+    fn default_sni_certificates() -> Vec<SniCertificate> {
+        vec![]
+    }
+}
+
+/// Certificate served to the TLS clients requesting a hostname (SNI).
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct SniCertificate {
+    /// The hostname of the certificate, for example `tracker.example.org`.
+    /// It's compared case-insensitively with the one sent by the client.
+    pub hostname: String,
+
+    /// Path to the SSL certificate file.
+    pub ssl_cert_path: Utf8PathBuf,
+
+    /// Path to the SSL key file.
+    pub ssl_key_path: Utf8PathBuf,
 }
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+/// Mitigations applied automatically when an abuse report is received via the
+/// tracker API.
+///
+/// All mitigations are disabled by default. Reports are always accepted and
+/// forwarded to the webhooks, even when no mitigation is enabled.
+///
+/// ```toml
+/// [core.abuse]
+/// ban_ip = true
+/// blacklist_info_hash = false
+/// raised_interval = 3600
+/// webhooks = ["https://example.com/hooks/tracker-abuse"]
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Abuse {
+    /// When `true` the IP address in the report is banned. Announce requests
+    /// from banned IP addresses are rejected.
+    #[serde(default = "Abuse::default_ban_ip")]
+    pub ban_ip: bool,
+
+    /// When `true` the torrent in the report is blacklisted. Blacklisted
+    /// torrents can't be announced or scraped.
+    #[serde(default = "Abuse::default_blacklist_info_hash")]
+    pub blacklist_info_hash: bool,
+
+    /// When it's set, the announce interval (and the minimum announce
+    /// interval) for the reported torrent is raised to this value in seconds.
+    #[serde(default = "Abuse::default_raised_interval")]
+    pub raised_interval: Option<u32>,
+
+    /// URLs notified with a `POST` request containing the report and the
+    /// applied mitigations in JSON.
+    #[serde(default = "Abuse::default_webhooks")]
+    pub webhooks: Vec<String>,
+}
+
+impl Default for Abuse {
+    fn default() -> Self {
+        Self {
+            ban_ip: Self::default_ban_ip(),
+            blacklist_info_hash: Self::default_blacklist_info_hash(),
+            raised_interval: Self::default_raised_interval(),
+            webhooks: Self::default_webhooks(),
+        }
+    }
+}
+
+impl Abuse {
+    fn default_ban_ip() -> bool {
+        false
+    }
+
+    fn default_blacklist_info_hash() -> bool {
+        false
+    }
+
+    fn default_raised_interval() -> Option<u32> {
+        None
+    }
+
+    fn default_webhooks() -> Vec<String> {
+        vec![]
+    }
+
+    /// Masks secrets in the configuration.
+    pub fn mask_secrets(&mut self) {
+        for webhook in &mut self.webhooks {
+            // Webhook URLs usually contain a secret token.
+            *webhook = "***".to_string();
+        }
+    }
+}
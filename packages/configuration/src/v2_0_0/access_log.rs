@@ -0,0 +1,71 @@
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+
+/// Access log of an HTTP server: one line for each request, with the client
+/// IP, the path, the status, the latency and the infohashes of the request.
+///
+/// It can be enabled for each HTTP tracker and for the tracker API. The file
+/// is rotated when it reaches the `max_size_mib`: `access.log` is renamed to
+/// `access.log.1`, `access.log.1` to `access.log.2`, and so on, keeping up to
+/// `max_files` rotated files.
+///
+/// ```toml
+/// [[http_trackers]]
+/// bind_address = "0.0.0.0:7070"
+///
+/// [http_trackers.access_log]
+/// path = "./storage/tracker/log/http_tracker_access.log"
+/// format = "combined"
+/// max_size_mib = 100
+/// max_files = 5
+/// ```
+///
+/// > **NOTICE**: each server must use its own file.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct AccessLog {
+    /// Path of the log file. It's created if it does not exist.
+    pub path: Utf8PathBuf,
+
+    /// Format of the log lines.
+    #[serde(default = "AccessLog::default_format")]
+    pub format: Format,
+
+    /// Maximum size of the log file in MiB before it's rotated. The file is
+    /// never rotated if it's `0`.
+    #[serde(default = "AccessLog::default_max_size_mib")]
+    pub max_size_mib: u64,
+
+    /// Number of rotated files kept. The oldest one is removed when the file
+    /// is rotated again. The file is truncated when it's rotated if it's `0`.
+    #[serde(default = "AccessLog::default_max_files")]
+    pub max_files: u32,
+}
+
+impl AccessLog {
+    fn default_format() -> Format {
+        Format::Combined
+    }
+
+    fn default_max_size_mib() -> u64 {
+        100
+    }
+
+    fn default_max_files() -> u32 {
+        5
+    }
+}
+
+/// Format of the access log lines.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// The Apache Combined Log Format, followed by the latency in
+    /// milliseconds and the infohashes of the request:
+    ///
+    /// ```text
+    /// 126.0.0.1 - - [10/Oct/2024:13:55:36 +0000] "GET /announce HTTP/1.1" 200 104 "-" "qBittorrent/4.5.2" 1 3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0
+    /// ```
+    Combined,
+    /// A JSON object per line.
+    Json,
+}
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+
+/// Notifications sent to the administrators when something critical happens:
+///
+/// - The database is down (and when it's up again).
+/// - A TLS certificate of the HTTP trackers or the API is about to expire.
+/// - A service panicked.
+///
+/// Alerts are disabled if the section is not included. They are sent by email
+/// and/or to chat webhooks (Slack or Matrix):
+///
+/// ```toml
+/// [alerts]
+/// check_interval = 60
+/// certificate_expiration_days = 14
+///
+/// [alerts.smtp]
+/// server = "smtp.example.com"
+/// port = 587
+/// security = "starttls"
+/// username = "tracker@example.com"
+/// password = "MySmtpPassword"
+/// from = "Torrust Tracker <tracker@example.com>"
+/// to = ["admin@example.com"]
+///
+/// [[alerts.webhooks]]
+/// kind = "slack"
+/// url = "https://hooks.slack.com/services/T000/B000/XXXX"
+///
+/// [[alerts.webhooks]]
+/// kind = "matrix"
+/// url = "https://matrix.example.com/_matrix/client/v3/rooms/!roomid:example.com/send/m.room.message"
+/// token = "MyMatrixAccessToken"
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Alerts {
+    /// Interval in seconds between the checks of the database and the
+    /// certificates.
+    #[serde(default = "Alerts::default_check_interval")]
+    pub check_interval: u64,
+
+    /// An alert is sent when a certificate expires within this number of
+    /// days. It's repeated every day until the certificate is renewed.
+    #[serde(default = "Alerts::default_certificate_expiration_days")]
+    pub certificate_expiration_days: u64,
+
+    /// Email notifications.
+    #[serde(default = "Alerts::default_smtp")]
+    pub smtp: Option<Smtp>,
+
+    /// Chat webhooks notifications.
+    #[serde(default = "Alerts::default_webhooks")]
+    pub webhooks: Vec<Webhook>,
+}
+
+impl Default for Alerts {
+    fn default() -> Self {
+        Self {
+            check_interval: Self::default_check_interval(),
+            certificate_expiration_days: Self::default_certificate_expiration_days(),
+            smtp: Self::default_smtp(),
+            webhooks: Self::default_webhooks(),
+        }
+    }
+}
+
+impl Alerts {
+    fn default_check_interval() -> u64 {
+        60
+    }
+
+    fn default_certificate_expiration_days() -> u64 {
+        14
+    }
+
+    fn default_smtp() -> Option<Smtp> {
+        None
+    }
+
+    fn default_webhooks() -> Vec<Webhook> {
+        vec![]
+    }
+
+    pub fn mask_secrets(&mut self) {
+        if let Some(smtp) = &mut self.smtp {
+            if smtp.password.is_some() {
+                smtp.password = Some("***".to_string());
+            }
+        }
+
+        for webhook in &mut self.webhooks {
+            // Slack webhook URLs contain the secret.
+            webhook.url = "***".to_string();
+
+            if webhook.token.is_some() {
+                webhook.token = Some("***".to_string());
+            }
+        }
+    }
+}
+
+/// SMTP server the alert emails are sent through.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Smtp {
+    /// Host name of the SMTP server.
+    pub server: String,
+
+    /// Port of the SMTP server.
+    #[serde(default = "Smtp::default_port")]
+    pub port: u16,
+
+    /// How the connection to the server is secured.
+    #[serde(default = "Smtp::default_security")]
+    pub security: SmtpSecurity,
+
+    /// User name for the SMTP authentication. The tracker does not
+    /// authenticate if it's not set.
+    #[serde(default = "Smtp::default_username")]
+    pub username: Option<String>,
+
+    /// Password for the SMTP authentication.
+    #[serde(default = "Smtp::default_password")]
+    pub password: Option<String>,
+
+    /// Sender of the emails, for example, `Tracker <tracker@example.com>`.
+    pub from: String,
+
+    /// Recipients of the emails.
+    pub to: Vec<String>,
+}
+
+impl Smtp {
+    fn default_port() -> u16 {
+        587
+    }
+
+    fn default_security() -> SmtpSecurity {
+        SmtpSecurity::StartTls
+    }
+
+    fn default_username() -> Option<String> {
+        None
+    }
+
+    fn default_password() -> Option<String> {
+        None
+    }
+}
+
+/// How the connection to the SMTP server is secured.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// The connection is upgraded with `STARTTLS`. Usually on port `587`.
+    #[serde(rename = "starttls")]
+    StartTls,
+    /// The connection uses TLS from the beginning. Usually on port `465`.
+    Tls,
+    /// Plain text connection. Only for local servers.
+    None,
+}
+
+/// Chat webhook the alerts are posted to.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Webhook {
+    /// The type of webhook.
+    pub kind: WebhookKind,
+
+    /// URL of the webhook:
+    ///
+    /// - `slack`: the incoming webhook URL. Any service accepting Slack
+    ///   messages (`{"text": "..."}`) can be used.
+    /// - `matrix`: the endpoint for sending messages to a room, without the
+    ///   transaction ID: `https://<homeserver>/_matrix/client/v3/rooms/<room_id>/send/m.room.message`.
+    pub url: String,
+
+    /// Access token of the Matrix user posting the alerts. Only for `matrix`
+    /// webhooks.
+    #[serde(default = "Webhook::default_token")]
+    pub token: Option<String>,
+}
+
+impl Webhook {
+    fn default_token() -> Option<String> {
+        None
+    }
+}
+
+/// The type of chat webhook.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookKind {
+    Slack,
+    Matrix,
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// Global budget of announce requests per second, shared fairly among the
+/// peer IP addresses.
+///
+/// The budget is a token bucket refilled at `announces_per_second` tokens per
+/// second, holding up to `burst` tokens. Each accepted announce request takes
+/// one token. When the bucket is below the `saturation_threshold`, requests
+/// from IP addresses that have used more than their fair share of the budget
+/// (the rate divided by the number of active IP addresses) are rejected
+/// first. That way light users keep working while heavy sources are
+/// throttled.
+///
+/// The budget is disabled by default.
+///
+/// ```toml
+/// [core.announce_budget]
+/// announces_per_second = 10000
+/// burst = 20000
+/// saturation_threshold = 50
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct AnnounceBudget {
+    /// Announce requests accepted per second. The budget is disabled if it's
+    /// not set.
+    #[serde(default = "AnnounceBudget::default_announces_per_second")]
+    pub announces_per_second: Option<u32>,
+
+    /// Maximum number of announce requests accepted in a burst. It defaults
+    /// to `announces_per_second`.
+    #[serde(default = "AnnounceBudget::default_burst")]
+    pub burst: Option<u32>,
+
+    /// Percentage of the burst below which the budget is considered
+    /// saturated, and only IP addresses under their fair share are accepted.
+    /// It must be between `0` and `100`.
+    #[serde(default = "AnnounceBudget::default_saturation_threshold")]
+    pub saturation_threshold: u8,
+}
+
+impl Default for AnnounceBudget {
+    fn default() -> Self {
+        Self {
+            announces_per_second: Self::default_announces_per_second(),
+            burst: Self::default_burst(),
+            saturation_threshold: Self::default_saturation_threshold(),
+        }
+    }
+}
+
+impl AnnounceBudget {
+    fn default_announces_per_second() -> Option<u32> {
+        None
+    }
+
+    fn default_burst() -> Option<u32> {
+        None
+    }
+
+    fn default_saturation_threshold() -> u8 {
+        50
+    }
+}
@@ -0,0 +1,139 @@
+//! Bind addresses with hostnames.
+//!
+//! Bind addresses can be IP literals (`0.0.0.0:6969`) or hostnames
+//! (`tracker.internal:6969`), so configurations can reference internal DNS
+//! names in dynamic environments. The configuration only checks the format of
+//! the address. Hostnames are kept as they are, and resolved by the tracker
+//! when the service starts, retrying for a while if the DNS records are not
+//! ready yet (for example, in a container orchestrator).
+//!
+//! When a hostname resolves to several addresses, the first one is used.
+//!
+//! ```toml
+//! [[udp_trackers]]
+//! bind_address = "tracker.internal:6969"
+//! ```
+//!
+//! UDP trackers can also have a list of bind addresses. Refer to the
+//! [`UdpTracker`](crate::v2_0_0::udp_tracker::UdpTracker) configuration for
+//! more information.
+use std::fmt;
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A bind address: an IP literal or a hostname, and a port.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct BindAddress(String);
+
+/// Error returned when a bind address is not `host:port`.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("invalid bind address {address}: expected host:port")]
+pub struct Error {
+    address: String,
+}
+
+impl BindAddress {
+    /// The socket address, if the host is an IP literal. Hostnames have to be
+    /// resolved.
+    #[must_use]
+    pub fn socket_addr(&self) -> Option<SocketAddr> {
+        self.0.parse().ok()
+    }
+}
+
+impl FromStr for BindAddress {
+    type Err = Error;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        if address.parse::<SocketAddr>().is_ok() {
+            return Ok(Self(address.to_string()));
+        }
+
+        let error = || Error {
+            address: address.to_string(),
+        };
+
+        let (host, port) = address.rsplit_once(':').ok_or_else(error)?;
+
+        // IPv6 literals must be in brackets
+        if host.is_empty() || host.contains([':', '[', ']', '/']) || port.parse::<u16>().is_err() {
+            return Err(error());
+        }
+
+        Ok(Self(address.to_string()))
+    }
+}
+
+impl TryFrom<String> for BindAddress {
+    type Error = Error;
+
+    fn try_from(address: String) -> Result<Self, Self::Error> {
+        address.parse()
+    }
+}
+
+impl From<BindAddress> for String {
+    fn from(bind_address: BindAddress) -> Self {
+        bind_address.0
+    }
+}
+
+impl From<SocketAddr> for BindAddress {
+    fn from(socket_addr: SocketAddr) -> Self {
+        Self(socket_addr.to_string())
+    }
+}
+
+impl fmt::Display for BindAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    use super::BindAddress;
+
+    #[test]
+    fn it_should_accept_ip_literals() {
+        let bind_address: BindAddress = "0.0.0.0:6969".parse().unwrap();
+
+        assert_eq!(
+            bind_address.socket_addr(),
+            Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 6969))
+        );
+    }
+
+    #[test]
+    fn it_should_accept_ipv6_literals() {
+        let bind_address: BindAddress = "[::]:6969".parse().unwrap();
+
+        assert_eq!(
+            bind_address.socket_addr(),
+            Some(SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 6969))
+        );
+    }
+
+    #[test]
+    fn it_should_keep_the_hostnames_without_resolving_them() {
+        let bind_address: BindAddress = "tracker.internal:6969".parse().unwrap();
+
+        assert_eq!(bind_address.socket_addr(), None);
+        assert_eq!(bind_address.to_string(), "tracker.internal:6969");
+    }
+
+    #[test]
+    fn it_should_reject_the_addresses_without_a_valid_port() {
+        assert!("tracker.internal".parse::<BindAddress>().is_err());
+        assert!("tracker.internal:".parse::<BindAddress>().is_err());
+        assert!("tracker.internal:70000".parse::<BindAddress>().is_err());
+        assert!(":6969".parse::<BindAddress>().is_err());
+        assert!("::1:6969".parse::<BindAddress>().is_err());
+    }
+}
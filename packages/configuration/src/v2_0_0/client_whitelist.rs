@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// BitTorrent clients allowed to announce to a private tracker.
+///
+/// Clients are identified by the prefix of their peer ID. For example, the
+/// [Azureus-style](https://www.bittorrent.org/beps/bep_0020.html) prefix
+/// `-qB45` is used by qBittorrent 4.5.x and `-TR40` by Transmission 4.0.x.
+///
+/// The list is empty by default: all clients are allowed. It's only enforced
+/// when the tracker runs in `private` mode. The list can also be changed at
+/// runtime with the tracker API.
+///
+/// ```toml
+/// [core.client_whitelist]
+/// peer_id_prefixes = ["-qB45", "-TR40"]
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct ClientWhitelist {
+    /// Prefixes of the peer IDs of the allowed clients. Each prefix must have
+    /// between 1 and 20 ASCII characters, the length of a peer ID.
+    #[serde(default = "ClientWhitelist::default_peer_id_prefixes")]
+    pub peer_id_prefixes: Vec<String>,
+}
+
+impl Default for ClientWhitelist {
+    fn default() -> Self {
+        Self {
+            peer_id_prefixes: Self::default_peer_id_prefixes(),
+        }
+    }
+}
+
+impl ClientWhitelist {
+    fn default_peer_id_prefixes() -> Vec<String> {
+        vec![]
+    }
+
+    /// Returns `true` if all the prefixes have between 1 and 20 ASCII
+    /// characters.
+    #[must_use]
+    pub fn has_valid_prefixes(&self) -> bool {
+        self.peer_id_prefixes
+            .iter()
+            .all(|prefix| (1..=20).contains(&prefix.len()) && prefix.is_ascii())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClientWhitelist;
+
+    fn client_whitelist(peer_id_prefixes: &[&str]) -> ClientWhitelist {
+        ClientWhitelist {
+            peer_id_prefixes: peer_id_prefixes.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn it_should_only_accept_prefixes_with_up_to_20_ascii_characters() {
+        assert!(client_whitelist(&["-qB45", "-TR40"]).has_valid_prefixes());
+
+        assert!(!client_whitelist(&[""]).has_valid_prefixes());
+        assert!(!client_whitelist(&["-qB45ñ"]).has_valid_prefixes());
+        assert!(!client_whitelist(&[&"a".repeat(21)]).has_valid_prefixes());
+    }
+}
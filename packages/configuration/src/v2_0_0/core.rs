@@ -1,35 +1,138 @@
 use derive_more::{Constructor, Display};
 use serde::{Deserialize, Serialize};
 
+use super::abuse::Abuse;
+use super::announce_budget::AnnounceBudget;
+use super::client_whitelist::ClientWhitelist;
+use super::dynamic_interval::DynamicInterval;
+use super::experiment::Experiment;
+use super::failure_messages::FailureMessages;
+use super::failure_retry::FailureRetry;
+use super::key_sync::KeySync;
+use super::maintenance::Maintenance;
 use super::network::Network;
-use crate::v2_0_0::database::Database;
+use super::overload::Overload;
+use super::peer_diversity::PeerDiversity;
+use super::secret_rotation::SecretRotation;
+use super::swarm_snapshot::SwarmSnapshot;
+use super::swarm_store::SwarmStore;
+use super::torrent_aliases::TorrentAliases;
+use super::whitelist::Whitelist;
+use crate::v2_0_0::database::{Database, Driver};
 use crate::validator::{SemanticValidationError, Validator};
 use crate::{AnnouncePolicy, TrackerPolicy};
 
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct Core {
+    /// Mitigations applied when an abuse report is received.
+    #[serde(default = "Core::default_abuse")]
+    pub abuse: Abuse,
+
+    /// Global budget of announce requests per second.
+    #[serde(default = "Core::default_announce_budget")]
+    pub announce_budget: AnnounceBudget,
+
+    /// Maximum random variation, as a percentage of the announce interval,
+    /// applied to the interval returned in each announce response. For
+    /// example, with `10` and an interval of `120` seconds the returned
+    /// interval is between `108` and `132` seconds. It's never lower than
+    /// the minimum announce interval.
+    ///
+    /// It spreads the re-announces of peers that joined a swarm at the same
+    /// time. `0` disables the jitter. It must be between `0` and `100`.
+    #[serde(default = "Core::default_announce_interval_jitter")]
+    pub announce_interval_jitter: u8,
+
     /// Announce policy configuration.
     #[serde(default = "Core::default_announce_policy")]
     pub announce_policy: AnnouncePolicy,
 
+    /// Clients allowed to announce in `private` mode, by peer ID prefix.
+    #[serde(default = "Core::default_client_whitelist")]
+    pub client_whitelist: ClientWhitelist,
+
     /// Database configuration.
     #[serde(default = "Core::default_database")]
     pub database: Database,
 
+    /// Announce intervals adjusted to the size of the swarms and to the load.
+    #[serde(default = "Core::default_dynamic_interval")]
+    pub dynamic_interval: DynamicInterval,
+
+    /// Interval in seconds that the cleanup job will run to remove the
+    /// expired authentication keys from the tracker and the database. It only
+    /// runs in `private` mode. `0` disables the job.
+    #[serde(default = "Core::default_expired_keys_cleanup_interval")]
+    pub expired_keys_cleanup_interval: u64,
+
+    /// Templates of the failure messages of the HTTP tracker, by rejection
+    /// reason.
+    #[serde(default = "Core::default_failure_messages")]
+    pub failure_messages: FailureMessages,
+
+    /// Minutes the clients should wait before retrying the rejected
+    /// announces, by rejection reason.
+    #[serde(default = "Core::default_failure_retry")]
+    pub failure_retry: FailureRetry,
+
+    /// Alternative announce parameters served to a percentage of the clients.
+    #[serde(default = "Core::default_experiment")]
+    pub experiment: Option<Experiment>,
+
     /// Interval in seconds that the cleanup job will run to remove inactive
     /// peers from the torrent peer list.
     #[serde(default = "Core::default_inactive_peer_cleanup_interval")]
     pub inactive_peer_cleanup_interval: u64,
 
+    /// Identifier of this tracker instance. When it's set, the HTTP tracker
+    /// includes it as the `tracker id` in announce responses.
+    ///
+    /// It's intended for fleets of trackers behind a load balancer. Each
+    /// instance holds a shard of the swarms, so the load balancer (or the
+    /// client, using an alternate announce URL list) can use the identifier
+    /// to keep sending the announces for a torrent to the same instance.
+    #[serde(default = "Core::default_instance_id")]
+    pub instance_id: Option<String>,
+
+    /// Synchronization of the authentication keys from a remote endpoint.
+    #[serde(default = "Core::default_key_sync")]
+    pub key_sync: KeySync,
+
     /// When `true` only approved torrents can be announced in the tracker.
     #[serde(default = "Core::default_listed")]
     pub listed: bool,
 
+    /// Scheduled maintenance windows configuration.
+    #[serde(default = "Core::default_maintenance")]
+    pub maintenance: Maintenance,
+
     /// Network configuration.
     #[serde(default = "Core::default_network")]
     pub net: Network,
 
+    /// Overload control configuration.
+    #[serde(default = "Core::default_overload")]
+    pub overload: Overload,
+
+    /// Diversity constraints for the peers returned in announce responses.
+    #[serde(default = "Core::default_peer_diversity")]
+    pub peer_diversity: PeerDiversity,
+
+    /// Number of announce intervals a peer is kept without announcing again,
+    /// counting from the interval handed to it in its last announce (after
+    /// the jitter and the per-torrent adjustments). The peer is never removed
+    /// before the [`max_peer_timeout`](TrackerPolicy::max_peer_timeout), so
+    /// this only matters when the intervals are raised. `0` disables it.
+    #[serde(default = "Core::default_peer_timeout_intervals")]
+    pub peer_timeout_intervals: u32,
+
+    /// If enabled, the tracker persists hourly counters for each torrent: the
+    /// completed downloads and the peak number of seeders and leechers. They
+    /// are exposed in the torrent history endpoint of the API.
+    #[serde(default = "Core::default_persistent_torrent_history")]
+    pub persistent_torrent_history: bool,
+
     /// When `true` clients require a key to connect and use the tracker.
     #[serde(default = "Core::default_private")]
     pub private: bool,
@@ -38,6 +141,34 @@ pub struct Core {
     #[serde(default = "Core::default_private_mode")]
     pub private_mode: Option<PrivateMode>,
 
+    /// Number of seconds the `scrape` data of a torrent is cached. The
+    /// cached data is returned even if the swarm counters change in the
+    /// meantime, so popular torrents scraped thousands of times per second
+    /// are not read from the torrent repository for each request. With `0`
+    /// the data is cached until the counters change, and only for the HTTP
+    /// tracker.
+    #[serde(default = "Core::default_scrape_cache_ttl")]
+    pub scrape_cache_ttl: u64,
+
+    /// Periodic snapshots of the swarms, restored when the tracker starts.
+    #[serde(default = "Core::default_swarm_snapshot")]
+    pub swarm_snapshot: SwarmSnapshot,
+
+    /// Where the swarms are stored: in memory or shared by several tracker
+    /// instances.
+    #[serde(default = "Core::default_swarm_store")]
+    pub swarm_store: SwarmStore,
+
+    /// Aliases between the v1 and v2 infohashes of hybrid torrents.
+    #[serde(default = "Core::default_torrent_aliases")]
+    pub torrent_aliases: TorrentAliases,
+
+    /// How the HTTP tracker generates the `tracker id` of the announce
+    /// responses, and validates the one sent back by the clients. Refer to
+    /// [`TrackerIdScheme`] for the available options.
+    #[serde(default = "Core::default_tracker_id_scheme")]
+    pub tracker_id_scheme: TrackerIdScheme,
+
     /// Tracker policy configuration.
     #[serde(default = "Core::default_tracker_policy")]
     pub tracker_policy: TrackerPolicy,
@@ -49,45 +180,165 @@ pub struct Core {
     /// information about the collected metrics.
     #[serde(default = "Core::default_tracker_usage_statistics")]
     pub tracker_usage_statistics: bool,
+
+    /// Construction used to generate the UDP tracker connection IDs from the
+    /// client address, the time and the secret. Refer to
+    /// [`ConnectionIdScheme`] for the available options.
+    #[serde(default = "Core::default_udp_connection_id_scheme")]
+    pub udp_connection_id_scheme: ConnectionIdScheme,
+
+    /// Secret used to generate the UDP tracker connection IDs, as 64
+    /// hexadecimal characters (32 bytes). Persisting it keeps the connection
+    /// IDs valid across restarts. When it's not set, a random secret is
+    /// generated every time the tracker starts.
+    #[serde(default = "Core::default_udp_connection_id_secret")]
+    pub udp_connection_id_secret: Option<String>,
+
+    /// Path to the file where the UDP tracker connection ID secret is
+    /// persisted. When it's set, the secret is loaded from the file on
+    /// startup, and the rotated secrets are saved to it. If the file does not
+    /// exist, it's created with the `udp_connection_id_secret` (or a random
+    /// one).
+    #[serde(default = "Core::default_udp_connection_id_secret_file")]
+    pub udp_connection_id_secret_file: Option<String>,
+
+    /// Rotation of the UDP tracker connection ID secret.
+    #[serde(default = "Core::default_udp_connection_id_secret_rotation")]
+    pub udp_connection_id_secret_rotation: SecretRotation,
+
+    /// Rules to allow torrents in `listed` mode, besides the whitelisted
+    /// infohashes.
+    #[serde(default = "Core::default_whitelist")]
+    pub whitelist: Whitelist,
 }
 
 impl Default for Core {
     fn default() -> Self {
         Self {
+            abuse: Self::default_abuse(),
+            announce_budget: Self::default_announce_budget(),
+            announce_interval_jitter: Self::default_announce_interval_jitter(),
             announce_policy: Self::default_announce_policy(),
+            client_whitelist: Self::default_client_whitelist(),
             database: Self::default_database(),
+            dynamic_interval: Self::default_dynamic_interval(),
+            expired_keys_cleanup_interval: Self::default_expired_keys_cleanup_interval(),
+            failure_messages: Self::default_failure_messages(),
+            failure_retry: Self::default_failure_retry(),
+            experiment: Self::default_experiment(),
             inactive_peer_cleanup_interval: Self::default_inactive_peer_cleanup_interval(),
+            instance_id: Self::default_instance_id(),
+            key_sync: Self::default_key_sync(),
             listed: Self::default_listed(),
+            maintenance: Self::default_maintenance(),
             net: Self::default_network(),
+            overload: Self::default_overload(),
+            peer_diversity: Self::default_peer_diversity(),
+            peer_timeout_intervals: Self::default_peer_timeout_intervals(),
+            persistent_torrent_history: Self::default_persistent_torrent_history(),
             private: Self::default_private(),
             private_mode: Self::default_private_mode(),
+            scrape_cache_ttl: Self::default_scrape_cache_ttl(),
+            swarm_snapshot: Self::default_swarm_snapshot(),
+            swarm_store: Self::default_swarm_store(),
+            torrent_aliases: Self::default_torrent_aliases(),
+            tracker_id_scheme: Self::default_tracker_id_scheme(),
             tracker_policy: Self::default_tracker_policy(),
             tracker_usage_statistics: Self::default_tracker_usage_statistics(),
+            udp_connection_id_scheme: Self::default_udp_connection_id_scheme(),
+            udp_connection_id_secret: Self::default_udp_connection_id_secret(),
+            udp_connection_id_secret_file: Self::default_udp_connection_id_secret_file(),
+            udp_connection_id_secret_rotation: Self::default_udp_connection_id_secret_rotation(),
+            whitelist: Self::default_whitelist(),
         }
     }
 }
 
 impl Core {
+    fn default_abuse() -> Abuse {
+        Abuse::default()
+    }
+
+    fn default_announce_budget() -> AnnounceBudget {
+        AnnounceBudget::default()
+    }
+
+    fn default_announce_interval_jitter() -> u8 {
+        0
+    }
+
     fn default_announce_policy() -> AnnouncePolicy {
         AnnouncePolicy::default()
     }
 
+    fn default_client_whitelist() -> ClientWhitelist {
+        ClientWhitelist::default()
+    }
+
     fn default_database() -> Database {
         Database::default()
     }
 
+    fn default_dynamic_interval() -> DynamicInterval {
+        DynamicInterval::default()
+    }
+
+    fn default_expired_keys_cleanup_interval() -> u64 {
+        3600
+    }
+
+    fn default_failure_messages() -> FailureMessages {
+        FailureMessages::default()
+    }
+
+    fn default_failure_retry() -> FailureRetry {
+        FailureRetry::default()
+    }
+
+    fn default_experiment() -> Option<Experiment> {
+        None
+    }
+
     fn default_inactive_peer_cleanup_interval() -> u64 {
         600
     }
 
+    fn default_instance_id() -> Option<String> {
+        None
+    }
+
+    fn default_key_sync() -> KeySync {
+        KeySync::default()
+    }
+
     fn default_listed() -> bool {
         false
     }
 
+    fn default_maintenance() -> Maintenance {
+        Maintenance::default()
+    }
+
     fn default_network() -> Network {
         Network::default()
     }
 
+    fn default_overload() -> Overload {
+        Overload::default()
+    }
+
+    fn default_peer_diversity() -> PeerDiversity {
+        PeerDiversity::default()
+    }
+
+    fn default_peer_timeout_intervals() -> u32 {
+        0
+    }
+
+    fn default_persistent_torrent_history() -> bool {
+        false
+    }
+
     fn default_private() -> bool {
         false
     }
@@ -100,12 +351,71 @@ impl Core {
         }
     }
 
+    fn default_scrape_cache_ttl() -> u64 {
+        0
+    }
+
+    fn default_swarm_snapshot() -> SwarmSnapshot {
+        SwarmSnapshot::default()
+    }
+
+    fn default_swarm_store() -> SwarmStore {
+        SwarmStore::default()
+    }
+
+    fn default_torrent_aliases() -> TorrentAliases {
+        TorrentAliases::default()
+    }
+
+    fn default_tracker_id_scheme() -> TrackerIdScheme {
+        TrackerIdScheme::default()
+    }
+
     fn default_tracker_policy() -> TrackerPolicy {
         TrackerPolicy::default()
     }
     fn default_tracker_usage_statistics() -> bool {
         true
     }
+
+    fn default_udp_connection_id_scheme() -> ConnectionIdScheme {
+        ConnectionIdScheme::default()
+    }
+
+    fn default_udp_connection_id_secret() -> Option<String> {
+        None
+    }
+
+    fn default_udp_connection_id_secret_file() -> Option<String> {
+        None
+    }
+
+    fn default_udp_connection_id_secret_rotation() -> SecretRotation {
+        SecretRotation::default()
+    }
+
+    fn default_whitelist() -> Whitelist {
+        Whitelist::default()
+    }
+
+    /// Returns the decoded UDP connection ID secret, or `None` if it's not
+    /// set or it's not a valid secret.
+    #[must_use]
+    pub fn udp_connection_id_secret_bytes(&self) -> Option<[u8; 32]> {
+        let secret = self.udp_connection_id_secret.as_ref()?;
+
+        if secret.len() != 64 || !secret.is_ascii() {
+            return None;
+        }
+
+        let mut bytes = [0u8; 32];
+
+        for (byte, chunk) in bytes.iter_mut().zip(secret.as_bytes().chunks(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+        }
+
+        Some(bytes)
+    }
 }
 
 /// Configuration specific when the tracker is running in private mode.
@@ -133,12 +443,119 @@ impl PrivateMode {
     }
 }
 
+/// Construction used to generate the UDP tracker connection IDs.
+///
+/// All of them use the client socket address, the current time slot and the
+/// secret (the `udp_connection_id_secret` or a random one) as input. The
+/// output is truncated to the 8 bytes of the connection ID.
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionIdScheme {
+    /// The secret is hashed together with the rest of the input using the
+    /// standard library hasher. It's the fastest option, but it's not a
+    /// standard MAC construction.
+    #[default]
+    Hash,
+    /// HMAC ([RFC 2104](https://www.rfc-editor.org/rfc/rfc2104)) with SHA-256,
+    /// using the secret as key.
+    HmacSha256,
+    /// BLAKE3 in keyed hash mode, using the secret as key.
+    KeyedBlake3,
+    /// SipHash-2-4 keyed with the first 128 bits of the secret. It's much
+    /// cheaper than the other MACs on low-power hardware (small ARM boards,
+    /// for example), and it's enough for the anti-spoofing purpose of the
+    /// connection IDs.
+    #[serde(rename = "siphash24")]
+    SipHash24,
+}
+
+/// Construction used to generate the `tracker id` of the HTTP announce
+/// responses. The clients must send it back in the `trackerid` param of
+/// their next announces.
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackerIdScheme {
+    /// The [`instance_id`](crate::v2_0_0::core::Core::instance_id) is the
+    /// tracker id of all the peers. No tracker id is issued if it's not set.
+    #[default]
+    InstanceId,
+    /// Each peer of each torrent gets its own tracker id, generated from the
+    /// infohash, the peer ID and a random secret. It's prefixed with the
+    /// `instance_id`, if it's set. The tracker can use it to correlate the
+    /// announces of a session.
+    PerPeer,
+}
+
 impl Validator for Core {
     fn validate(&self) -> Result<(), SemanticValidationError> {
         if self.private_mode.is_some() && !self.private {
             return Err(SemanticValidationError::UselessPrivateModeSection);
         }
 
+        if self.announce_budget.saturation_threshold > 100 {
+            return Err(SemanticValidationError::InvalidAnnounceBudgetSaturationThreshold);
+        }
+
+        if self.announce_interval_jitter > 100 {
+            return Err(SemanticValidationError::InvalidAnnounceIntervalJitter);
+        }
+
+        if !self.dynamic_interval.is_valid() {
+            return Err(SemanticValidationError::InvalidDynamicIntervalSettings);
+        }
+
+        if self.experiment.as_ref().is_some_and(|experiment| !experiment.is_valid()) {
+            return Err(SemanticValidationError::InvalidExperimentSettings);
+        }
+
+        if self.peer_diversity.ipv4_subnet_prefix_len > 32 || self.peer_diversity.ipv6_subnet_prefix_len > 128 {
+            return Err(SemanticValidationError::InvalidPeerDiversitySubnetPrefixLength);
+        }
+
+        if self.torrent_aliases.confirmations == Some(0) {
+            return Err(SemanticValidationError::InvalidTorrentAliasesConfirmations);
+        }
+
+        if self.database.pool.max_size == 0 {
+            return Err(SemanticValidationError::InvalidDatabasePoolSize);
+        }
+
+        if self.database.is_encrypted() && self.database.driver != Driver::Sqlite3 {
+            return Err(SemanticValidationError::UnsupportedDatabaseEncryption);
+        }
+
+        if !self.database.is_valid_table_prefix() {
+            return Err(SemanticValidationError::InvalidDatabaseTablePrefix);
+        }
+
+        if self.key_sync.interval == 0 {
+            return Err(SemanticValidationError::InvalidKeySyncInterval);
+        }
+
+        if self.maintenance.windows.iter().any(|window| !window.is_valid()) {
+            return Err(SemanticValidationError::InvalidMaintenanceWindow);
+        }
+
+        if self.overload.recovery_threshold > 100 || self.overload.sample_interval == 0 {
+            return Err(SemanticValidationError::InvalidOverloadSettings);
+        }
+
+        if self.udp_connection_id_secret.is_some() && self.udp_connection_id_secret_bytes().is_none() {
+            return Err(SemanticValidationError::InvalidUdpConnectionIdSecret);
+        }
+
+        if self.udp_connection_id_secret_rotation.interval == Some(0) {
+            return Err(SemanticValidationError::InvalidUdpConnectionIdSecretRotationInterval);
+        }
+
+        if !self.client_whitelist.has_valid_prefixes() {
+            return Err(SemanticValidationError::InvalidClientWhitelistPrefix);
+        }
+
+        if !self.whitelist.has_valid_prefixes() {
+            return Err(SemanticValidationError::InvalidWhitelistPrefix);
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+/// Announce intervals adjusted to the size of each swarm and to the load of
+/// the tracker.
+///
+/// The configured announce interval is the interval for swarms with
+/// `reference_swarm_size` peers. It's scaled by the square root of the ratio
+/// between the size of the swarm and the reference size: peers in small swarms
+/// announce more often, so they find each other sooner, and peers in huge
+/// swarms announce less often. For example, with a reference size of `100`
+/// and an interval of `120` seconds, a swarm with `400` peers gets `240`
+/// seconds.
+///
+/// While the tracker receives more than `reference_requests_per_second`
+/// requests, the interval is also scaled by the ratio between both rates. The
+/// requests per second are sampled by the overload control, so it must be
+/// enabled for the load to be taken into account.
+///
+/// The resulting interval is always between `min_interval` and
+/// `max_interval`. The minimum announce interval is lowered when needed, so
+/// it's never greater than the interval.
+///
+/// Dynamic intervals are disabled by default.
+///
+/// ```toml
+/// [core.dynamic_interval]
+/// max_interval = 3600
+/// min_interval = 60
+/// reference_requests_per_second = 5000
+/// reference_swarm_size = 100
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct DynamicInterval {
+    /// Upper bound of the announce interval, in seconds.
+    #[serde(default = "DynamicInterval::default_max_interval")]
+    pub max_interval: u32,
+
+    /// Lower bound of the announce interval, in seconds.
+    #[serde(default = "DynamicInterval::default_min_interval")]
+    pub min_interval: u32,
+
+    /// Requests per second above which the announce intervals are raised.
+    /// The load is not taken into account if it's not set.
+    #[serde(default = "DynamicInterval::default_reference_requests_per_second")]
+    pub reference_requests_per_second: Option<u32>,
+
+    /// Number of peers of the swarms that get the configured announce
+    /// interval. The size of the swarms is not taken into account if it's not
+    /// set.
+    #[serde(default = "DynamicInterval::default_reference_swarm_size")]
+    pub reference_swarm_size: Option<u32>,
+}
+
+impl Default for DynamicInterval {
+    fn default() -> Self {
+        Self {
+            max_interval: Self::default_max_interval(),
+            min_interval: Self::default_min_interval(),
+            reference_requests_per_second: Self::default_reference_requests_per_second(),
+            reference_swarm_size: Self::default_reference_swarm_size(),
+        }
+    }
+}
+
+impl DynamicInterval {
+    fn default_reference_swarm_size() -> Option<u32> {
+        None
+    }
+
+    fn default_reference_requests_per_second() -> Option<u32> {
+        None
+    }
+
+    fn default_min_interval() -> u32 {
+        60
+    }
+
+    fn default_max_interval() -> u32 {
+        3600
+    }
+
+    /// Returns `true` if the intervals depend on the size of the swarms or
+    /// on the load.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.reference_swarm_size.is_some() || self.reference_requests_per_second.is_some()
+    }
+
+    /// Returns `true` if the references are greater than zero and the bounds
+    /// are in order.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.reference_swarm_size != Some(0)
+            && self.reference_requests_per_second != Some(0)
+            && self.min_interval <= self.max_interval
+    }
+}
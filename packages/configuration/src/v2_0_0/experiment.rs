@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+/// Alternative announce parameters served to a percentage of the clients.
+///
+/// It lets operators measure the effect of tuning changes on real traffic.
+/// The clients are assigned to the experiment group by the hash of their IP
+/// address, so each client stays in the same group across torrents and
+/// restarts. The other clients are the control group and get the regular
+/// responses. The announces of each group are counted separately in the
+/// statistics.
+///
+/// There is no experiment by default.
+///
+/// ```toml
+/// [core.experiment]
+/// name = "longer-intervals"
+/// percentage = 10
+/// announce_interval = 1800
+/// announce_interval_min = 900
+/// peer_selection = "random"
+/// ```
+///
+/// The announce intervals replace the configured ones, but they are still
+/// raised during maintenance windows and while the tracker is overloaded.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Experiment {
+    /// Name of the experiment, to tell apart the statistics of different
+    /// experiments.
+    #[serde(default = "Experiment::default_name")]
+    pub name: String,
+
+    /// Percentage of the clients in the experiment group. It must be between
+    /// `0` and `100`.
+    #[serde(default = "Experiment::default_percentage")]
+    pub percentage: u8,
+
+    /// Announce interval for the experiment group, in seconds. The regular
+    /// interval is used if it's not set.
+    #[serde(default = "Experiment::default_announce_interval")]
+    pub announce_interval: Option<u32>,
+
+    /// Minimum announce interval for the experiment group, in seconds. The
+    /// regular minimum interval is used if it's not set.
+    #[serde(default = "Experiment::default_announce_interval_min")]
+    pub announce_interval_min: Option<u32>,
+
+    /// How the peers returned to the experiment group are selected. The
+    /// regular selection is used if it's not set.
+    #[serde(default = "Experiment::default_peer_selection")]
+    pub peer_selection: Option<PeerSelection>,
+}
+
+impl Default for Experiment {
+    fn default() -> Self {
+        Self {
+            name: Self::default_name(),
+            percentage: Self::default_percentage(),
+            announce_interval: Self::default_announce_interval(),
+            announce_interval_min: Self::default_announce_interval_min(),
+            peer_selection: Self::default_peer_selection(),
+        }
+    }
+}
+
+impl Experiment {
+    fn default_name() -> String {
+        "experiment".to_string()
+    }
+
+    fn default_percentage() -> u8 {
+        0
+    }
+
+    fn default_announce_interval() -> Option<u32> {
+        None
+    }
+
+    fn default_announce_interval_min() -> Option<u32> {
+        None
+    }
+
+    fn default_peer_selection() -> Option<PeerSelection> {
+        None
+    }
+
+    /// Returns `true` if the percentage is at most `100` and the minimum
+    /// announce interval is not greater than the announce interval.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        let intervals_in_order = match (self.announce_interval, self.announce_interval_min) {
+            (Some(interval), Some(interval_min)) => interval_min <= interval,
+            _ => true,
+        };
+
+        self.percentage <= 100 && intervals_in_order
+    }
+}
+
+/// Strategy to select the peers returned in the announce responses.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum PeerSelection {
+    /// A random sample of the whole swarm, instead of the same peers for all
+    /// the clients of a large swarm.
+    Random,
+}
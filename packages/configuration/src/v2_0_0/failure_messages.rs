@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+/// Templates of the `failure reason` of the HTTP tracker error responses, by
+/// rejection reason. The tracker uses its own messages for the reasons
+/// without a template.
+///
+/// Operators can use them to translate the messages, or to include a link to
+/// their support pages. The templates can contain these placeholders:
+///
+/// - `{reason}`: the message of the tracker.
+/// - `{info_hash}`: the infohash of the announced torrent. It's empty for
+///   scrape requests.
+/// - `{key}`: the authentication key of the request. It's empty if there is
+///   none.
+/// - `{retry_in}`: the minutes the client should wait before retrying the
+///   request. It's empty if the response does not include the `retry in`
+///   key.
+///
+/// ```toml
+/// [core.failure_messages]
+/// invalid_key = "{reason}. Get a new key at https://tracker.example.com/keys"
+/// not_whitelisted = "Torrent {info_hash} is not tracked here"
+/// overloaded = "The tracker is busy, please retry in {retry_in} minutes"
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct FailureMessages {
+    /// The authentication key is missing, unknown or expired.
+    #[serde(default = "FailureMessages::default_invalid_key")]
+    pub invalid_key: Option<String>,
+
+    /// The torrent is not in the whitelist.
+    #[serde(default = "FailureMessages::default_not_whitelisted")]
+    pub not_whitelisted: Option<String>,
+
+    /// The torrent is in the blacklist.
+    #[serde(default = "FailureMessages::default_blacklisted")]
+    pub blacklisted: Option<String>,
+
+    /// The peer IP address is banned.
+    #[serde(default = "FailureMessages::default_banned_ip")]
+    pub banned_ip: Option<String>,
+
+    /// The client of the peer is not in the client whitelist.
+    #[serde(default = "FailureMessages::default_client_not_whitelisted")]
+    pub client_not_whitelisted: Option<String>,
+
+    /// The peer exceeded the rate limit.
+    #[serde(default = "FailureMessages::default_rate_limited")]
+    pub rate_limited: Option<String>,
+
+    /// The tracker is overloaded.
+    #[serde(default = "FailureMessages::default_overloaded")]
+    pub overloaded: Option<String>,
+}
+
+impl Default for FailureMessages {
+    fn default() -> Self {
+        Self {
+            invalid_key: Self::default_invalid_key(),
+            not_whitelisted: Self::default_not_whitelisted(),
+            blacklisted: Self::default_blacklisted(),
+            banned_ip: Self::default_banned_ip(),
+            client_not_whitelisted: Self::default_client_not_whitelisted(),
+            rate_limited: Self::default_rate_limited(),
+            overloaded: Self::default_overloaded(),
+        }
+    }
+}
+
+impl FailureMessages {
+    fn default_invalid_key() -> Option<String> {
+        None
+    }
+
+    fn default_not_whitelisted() -> Option<String> {
+        None
+    }
+
+    fn default_blacklisted() -> Option<String> {
+        None
+    }
+
+    fn default_banned_ip() -> Option<String> {
+        None
+    }
+
+    fn default_client_not_whitelisted() -> Option<String> {
+        None
+    }
+
+    fn default_rate_limited() -> Option<String> {
+        None
+    }
+
+    fn default_overloaded() -> Option<String> {
+        None
+    }
+}
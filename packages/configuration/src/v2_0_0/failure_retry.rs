@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// Minutes the HTTP tracker clients should wait before retrying a rejected
+/// announce. They are returned in the `retry in` key of the error responses
+/// ([BEP 31](https://www.bittorrent.org/beps/bep_0031.html)), so
+/// well-behaved clients back off instead of retrying right away.
+///
+/// The `retry in` key is omitted for a reason if its value is `0`. The
+/// maintenance windows and the overload control have their own `retry_in`
+/// options, which take precedence over these ones.
+///
+/// ```toml
+/// [core.failure_retry]
+/// invalid_key = 60
+/// unregistered_torrent = 60
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct FailureRetry {
+    /// Minutes to wait after an announce with a missing, unknown or expired
+    /// authentication key.
+    #[serde(default = "FailureRetry::default_invalid_key")]
+    pub invalid_key: u32,
+
+    /// Minutes to wait after an announce for a torrent not in the whitelist,
+    /// or in the blacklist.
+    #[serde(default = "FailureRetry::default_unregistered_torrent")]
+    pub unregistered_torrent: u32,
+}
+
+impl Default for FailureRetry {
+    fn default() -> Self {
+        Self {
+            invalid_key: Self::default_invalid_key(),
+            unregistered_torrent: Self::default_unregistered_torrent(),
+        }
+    }
+}
+
+impl FailureRetry {
+    fn default_invalid_key() -> u32 {
+        60
+    }
+
+    fn default_unregistered_torrent() -> u32 {
+        60
+    }
+}
@@ -3,16 +3,19 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+use crate::BindAddress;
+
 /// Configuration for the Health Check API.
 #[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct HealthCheckApi {
     /// The address the API will bind to.
-    /// The format is `ip:port`, for example `127.0.0.1:1313`. If you want to
+    /// The format is `ip:port`, for example `127.0.0.1:1313`. The host can also
+    /// be a hostname, resolved when the service starts. If you want to
     /// listen to all interfaces, use `0.0.0.0`. If you want the operating
     /// system to choose a random port, use port `0`.
     #[serde(default = "HealthCheckApi::default_bind_address")]
-    pub bind_address: SocketAddr,
+    pub bind_address: BindAddress,
 }
 
 impl Default for HealthCheckApi {
@@ -24,7 +27,7 @@ impl Default for HealthCheckApi {
 }
 
 impl HealthCheckApi {
-    fn default_bind_address() -> SocketAddr {
-        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 1313)
+    fn default_bind_address() -> BindAddress {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 1313).into()
     }
 }
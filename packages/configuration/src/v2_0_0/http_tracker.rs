@@ -1,24 +1,100 @@
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
+use camino::Utf8PathBuf;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-use crate::TslConfig;
+use crate::{AccessLog, BindAddress, TslConfig};
 
 /// Configuration for each HTTP tracker.
+///
+/// HTTP/2 is negotiated with the clients on TLS connections. HTTP/1.1
+/// connections are kept open between requests for the `keep_alive_timeout`.
+///
+/// ```toml
+/// [[http_trackers]]
+/// bind_address = "0.0.0.0:7070"
+/// keep_alive_timeout = 60
+/// max_connections = 10000
+/// ```
 #[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct HttpTracker {
     /// The address the tracker will bind to.
-    /// The format is `ip:port`, for example `0.0.0.0:6969`. If you want to
+    /// The format is `ip:port`, for example `0.0.0.0:6969`. The host can also
+    /// be a hostname, resolved when the service starts. If you want to
     /// listen to all interfaces, use `0.0.0.0`. If you want the operating
     /// system to choose a random port, use port `0`.
     #[serde(default = "HttpTracker::default_bind_address")]
-    pub bind_address: SocketAddr,
+    pub bind_address: BindAddress,
 
     /// TSL config.
     #[serde(default = "HttpTracker::default_tsl_config")]
     pub tsl_config: Option<TslConfig>,
+
+    /// Automatic TLS certificates. The `tsl_config` is ignored when it's set.
+    #[serde(default = "HttpTracker::default_acme")]
+    pub acme: Option<Acme>,
+
+    /// Seconds an idle connection is kept open, waiting for the next request
+    /// from the client (keep-alive). Clients announcing often reuse the
+    /// connection instead of doing the TCP and TLS handshakes again. The
+    /// connections are closed after each response if it's `0`.
+    #[serde(default = "HttpTracker::default_keep_alive_timeout")]
+    pub keep_alive_timeout: u64,
+
+    /// Maximum number of simultaneous connections, including the idle ones.
+    /// New connections over the limit are closed right away. Unlimited if not
+    /// set.
+    #[serde(default = "HttpTracker::default_max_connections")]
+    pub max_connections: Option<u32>,
+
+    /// Maximum number of seconds the tracker waits, when it's shut down, for
+    /// the requests in process to be handled and their responses sent. The
+    /// tracker stops accepting new connections as soon as it's shut down.
+    #[serde(default = "HttpTracker::default_shutdown_timeout")]
+    pub shutdown_timeout: u64,
+
+    /// Maximum number of requests handled concurrently by this tracker.
+    /// Requests over the limit are rejected with an error response.
+    /// Unlimited if not set.
+    #[serde(default = "HttpTracker::default_max_concurrent_requests")]
+    pub max_concurrent_requests: Option<u32>,
+
+    /// Minimum port of the peers. Announces with a lower port are rejected:
+    /// `1` rejects the port `0`, and `1024` also rejects the privileged
+    /// ports. All the ports are accepted with `0`.
+    #[serde(default = "HttpTracker::default_min_peer_port")]
+    pub min_peer_port: u16,
+
+    /// Whether the announce responses include the client IP address, as
+    /// observed by the tracker, in the `external ip` field
+    /// ([BEP 24](https://www.bittorrent.org/beps/bep_0024.html)). It overrides
+    /// the [`return_client_ip`](crate::v2_0_0::network::Network::return_client_ip)
+    /// option of the `[core.net]` section for this tracker. The `core.net`
+    /// option is used if not set.
+    #[serde(default = "HttpTracker::default_return_client_ip")]
+    pub return_client_ip: Option<bool>,
+
+    /// Maximum number of torrents (`info_hash` params) in a `scrape` request.
+    /// Requests with more torrents are rejected with an error response.
+    #[serde(default = "HttpTracker::default_max_scrape_torrents")]
+    pub max_scrape_torrents: u32,
+
+    /// Whether the `scrape` requests are handled when the tracker runs in
+    /// private mode. If disabled, they are rejected with an error response,
+    /// even if they include a valid key.
+    #[serde(default = "HttpTracker::default_scrape_in_private_mode")]
+    pub scrape_in_private_mode: bool,
+
+    /// Rate limits for the `announce` and `scrape` requests received by this
+    /// tracker. Unlimited if not set.
+    #[serde(default = "HttpTracker::default_rate_limit")]
+    pub rate_limit: Option<RateLimit>,
+
+    /// Access log of the requests. Disabled if not set.
+    #[serde(default = "HttpTracker::default_access_log")]
+    pub access_log: Option<AccessLog>,
 }
 
 impl Default for HttpTracker {
@@ -26,16 +102,185 @@ impl Default for HttpTracker {
         Self {
             bind_address: Self::default_bind_address(),
             tsl_config: Self::default_tsl_config(),
+            acme: Self::default_acme(),
+            keep_alive_timeout: Self::default_keep_alive_timeout(),
+            max_connections: Self::default_max_connections(),
+            shutdown_timeout: Self::default_shutdown_timeout(),
+            max_concurrent_requests: Self::default_max_concurrent_requests(),
+            min_peer_port: Self::default_min_peer_port(),
+            return_client_ip: Self::default_return_client_ip(),
+            max_scrape_torrents: Self::default_max_scrape_torrents(),
+            scrape_in_private_mode: Self::default_scrape_in_private_mode(),
+            rate_limit: Self::default_rate_limit(),
+            access_log: Self::default_access_log(),
         }
     }
 }
 
 impl HttpTracker {
-    fn default_bind_address() -> SocketAddr {
-        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 7070)
+    fn default_bind_address() -> BindAddress {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 7070).into()
     }
 
     fn default_tsl_config() -> Option<TslConfig> {
         None
     }
+
+    fn default_acme() -> Option<Acme> {
+        None
+    }
+
+    fn default_keep_alive_timeout() -> u64 {
+        5
+    }
+
+    fn default_max_connections() -> Option<u32> {
+        None
+    }
+
+    fn default_shutdown_timeout() -> u64 {
+        10
+    }
+
+    fn default_max_concurrent_requests() -> Option<u32> {
+        None
+    }
+
+    fn default_min_peer_port() -> u16 {
+        0
+    }
+
+    fn default_return_client_ip() -> Option<bool> {
+        None
+    }
+
+    fn default_max_scrape_torrents() -> u32 {
+        74
+    }
+
+    fn default_scrape_in_private_mode() -> bool {
+        true
+    }
+
+    fn default_rate_limit() -> Option<RateLimit> {
+        None
+    }
+
+    fn default_access_log() -> Option<AccessLog> {
+        None
+    }
+}
+
+/// Rate limits for the `announce` and `scrape` requests of an HTTP tracker.
+///
+/// The requests are limited per client IP address and per authentication
+/// key. Requests over any of the limits are rejected with an error response
+/// including the `retry in` field
+/// ([BEP 31](https://www.bittorrent.org/beps/bep_0031.html)).
+///
+/// ```toml
+/// [[http_trackers]]
+/// bind_address = "0.0.0.0:7070"
+///
+/// [http_trackers.rate_limit]
+/// requests_per_minute_per_ip = 60
+/// requests_per_minute_per_key = 120
+/// retry_in = 5
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct RateLimit {
+    /// Maximum number of requests per minute from the same client IP
+    /// address. Unlimited if not set.
+    #[serde(default = "RateLimit::default_requests_per_minute_per_ip")]
+    pub requests_per_minute_per_ip: Option<u32>,
+
+    /// Maximum number of requests per minute with the same authentication
+    /// key. Unlimited if not set.
+    #[serde(default = "RateLimit::default_requests_per_minute_per_key")]
+    pub requests_per_minute_per_key: Option<u32>,
+
+    /// Minutes the clients are asked to wait before retrying the requests
+    /// over the limit.
+    #[serde(default = "RateLimit::default_retry_in")]
+    pub retry_in: u32,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            requests_per_minute_per_ip: Self::default_requests_per_minute_per_ip(),
+            requests_per_minute_per_key: Self::default_requests_per_minute_per_key(),
+            retry_in: Self::default_retry_in(),
+        }
+    }
+}
+
+impl RateLimit {
+    fn default_requests_per_minute_per_ip() -> Option<u32> {
+        None
+    }
+
+    fn default_requests_per_minute_per_key() -> Option<u32> {
+        None
+    }
+
+    fn default_retry_in() -> u32 {
+        1
+    }
+}
+
+/// Automatic TLS certificates with the ACME protocol
+/// ([RFC 8555](https://www.rfc-editor.org/rfc/rfc8555)), for example from
+/// Let's Encrypt.
+///
+/// The tracker obtains the certificate for the `domains` when it starts, and
+/// renews it before it expires, without restarting. The domains are validated
+/// with the `TLS-ALPN-01` challenge, so the tracker must be reachable on port
+/// `443` for all of them.
+///
+/// ```toml
+/// [[http_trackers]]
+/// bind_address = "0.0.0.0:443"
+///
+/// [http_trackers.acme]
+/// domains = ["tracker.example.com"]
+/// contact_email = "admin@example.com"
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Acme {
+    /// Domains included in the certificate.
+    pub domains: Vec<String>,
+
+    /// Email address of the ACME account. The certificate authority uses it
+    /// to send notices about the certificates, for example, when they are
+    /// about to expire.
+    #[serde(default = "Acme::default_contact_email")]
+    pub contact_email: Option<String>,
+
+    /// Directory where the account and the certificates are cached, so they
+    /// are not requested again every time the tracker restarts. Certificate
+    /// authorities have rate limits.
+    #[serde(default = "Acme::default_cache_dir")]
+    pub cache_dir: Utf8PathBuf,
+
+    /// URL of the directory of the certificate authority. Use the Let's
+    /// Encrypt staging directory
+    /// (`https://acme-staging-v02.api.letsencrypt.org/directory`) to test the
+    /// setup.
+    #[serde(default = "Acme::default_directory_url")]
+    pub directory_url: String,
+}
+
+impl Acme {
+    fn default_contact_email() -> Option<String> {
+        None
+    }
+
+    fn default_cache_dir() -> Utf8PathBuf {
+        Utf8PathBuf::from("./storage/tracker/lib/acme")
+    }
+
+    fn default_directory_url() -> String {
+        String::from("https://acme-v02.api.letsencrypt.org/directory")
+    }
 }
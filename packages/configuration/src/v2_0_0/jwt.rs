@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+use super::tracker_api::AccessTokenRole;
+
+/// Authentication of the API requests with JSON Web Tokens
+/// ([RFC 7519](https://www.rfc-editor.org/rfc/rfc7519)), as an alternative to
+/// the static access tokens. For example, for organizations running the
+/// tracker behind an SSO provider issuing short-lived tokens.
+///
+/// The JWT is sent as a bearer token in the `Authorization` header. It must
+/// contain the `exp` claim and be signed either with the shared `secret`
+/// using an HMAC algorithm (`HS256`, `HS384` or `HS512`), or with one of the
+/// keys published by the SSO provider at the `jwks_url` using `RS256` or
+/// `ES256`. At least one of them must be set.
+///
+/// The API role of the request is the value of the `role_claim`. Tokens
+/// without it get the `default_role`, or they are rejected if it's not set.
+///
+/// ```toml
+/// [http_api.jwt]
+/// jwks_url = "https://sso.example.com/.well-known/jwks.json"
+/// issuer = "https://sso.example.com"
+/// audience = "torrust-tracker"
+/// role_claim = "tracker_role"
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Jwt {
+    /// Shared secret the tokens signed with an HMAC algorithm are verified
+    /// with.
+    #[serde(default = "Jwt::default_secret")]
+    pub secret: Option<String>,
+
+    /// URL of the JSON Web Key Set
+    /// ([RFC 7517](https://www.rfc-editor.org/rfc/rfc7517#section-5)) with the
+    /// public keys the `RS256` and `ES256` tokens are verified with.
+    #[serde(default = "Jwt::default_jwks_url")]
+    pub jwks_url: Option<String>,
+
+    /// How long the keys fetched from the `jwks_url` are cached, in seconds.
+    /// The keys are also fetched again when a token is signed with an unknown
+    /// key, so rotated keys are picked up before.
+    #[serde(default = "Jwt::default_jwks_cache_ttl")]
+    pub jwks_cache_ttl: u64,
+
+    /// Expected `iss` claim. It's not checked if it's not set.
+    #[serde(default = "Jwt::default_issuer")]
+    pub issuer: Option<String>,
+
+    /// Expected `aud` claim (or one of them). It's not checked if it's not
+    /// set.
+    #[serde(default = "Jwt::default_audience")]
+    pub audience: Option<String>,
+
+    /// Name of the claim containing the API role: `admin`, `stats` or
+    /// `key_issuer`.
+    #[serde(default = "Jwt::default_role_claim")]
+    pub role_claim: String,
+
+    /// Role of the tokens without the role claim. They are rejected if it's
+    /// not set.
+    #[serde(default = "Jwt::default_default_role")]
+    pub default_role: Option<AccessTokenRole>,
+}
+
+impl Jwt {
+    fn default_secret() -> Option<String> {
+        None
+    }
+
+    fn default_jwks_url() -> Option<String> {
+        None
+    }
+
+    fn default_jwks_cache_ttl() -> u64 {
+        3600
+    }
+
+    fn default_issuer() -> Option<String> {
+        None
+    }
+
+    fn default_audience() -> Option<String> {
+        None
+    }
+
+    fn default_role_claim() -> String {
+        "role".to_string()
+    }
+
+    fn default_default_role() -> Option<AccessTokenRole> {
+        None
+    }
+
+    pub fn mask_secrets(&mut self) {
+        if self.secret.is_some() {
+            self.secret = Some("***".to_string());
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// Periodic synchronization of the authentication keys from a remote
+/// endpoint, for example, the user database of a torrent index.
+///
+/// The tracker pulls the changes since the previous synchronization, so the
+/// remote source does not need to call the tracker API when keys are added or
+/// revoked. It's disabled by default and only used in private mode.
+///
+/// ```toml
+/// [core.key_sync]
+/// url = "https://index.example.com/api/v1/tracker/keys"
+/// token = "MySyncToken"
+/// interval = 60
+/// ```
+///
+/// Refer to the tracker documentation for the format of the endpoint
+/// responses.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct KeySync {
+    /// URL of the endpoint the keys are pulled from. The synchronization is
+    /// disabled if it's not set.
+    #[serde(default = "KeySync::default_url")]
+    pub url: Option<String>,
+
+    /// Token sent in the `Authorization` header (as a bearer token) of the
+    /// requests to the endpoint.
+    #[serde(default = "KeySync::default_token")]
+    pub token: Option<String>,
+
+    /// Interval in seconds between synchronizations.
+    #[serde(default = "KeySync::default_interval")]
+    pub interval: u64,
+}
+
+impl Default for KeySync {
+    fn default() -> Self {
+        Self {
+            url: Self::default_url(),
+            token: Self::default_token(),
+            interval: Self::default_interval(),
+        }
+    }
+}
+
+impl KeySync {
+    fn default_url() -> Option<String> {
+        None
+    }
+
+    fn default_token() -> Option<String> {
+        None
+    }
+
+    fn default_interval() -> u64 {
+        60
+    }
+
+    pub fn mask_secrets(&mut self) {
+        if self.token.is_some() {
+            self.token = Some("***".to_string());
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Scheduled maintenance windows.
+///
+/// During a maintenance window the tracker:
+///
+/// - Raises the announce intervals to `announce_interval`, so clients contact
+///   the tracker less often.
+/// - Pauses nonessential jobs (the inactive peers cleanup, the torrent
+///   history and the swarm snapshots) when `pause_jobs` is enabled. Otherwise
+///   peers would be removed because they are announcing less often.
+/// - Includes the `retry in` key (in minutes) in the HTTP tracker error
+///   responses ([BEP 31](https://www.bittorrent.org/beps/bep_0031.html)).
+///
+/// ```toml
+/// [core.maintenance]
+/// announce_interval = 1800
+/// pause_jobs = true
+/// retry_in = 5
+///
+/// [[core.maintenance.windows]]
+/// start = "2024-10-20T02:00:00Z"
+/// end = "2024-10-20T04:00:00Z"
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Maintenance {
+    /// Announce interval (and minimum announce interval) in seconds during
+    /// maintenance windows. It's only applied if it's greater than the
+    /// configured announce policy intervals.
+    #[serde(default = "Maintenance::default_announce_interval")]
+    pub announce_interval: u32,
+
+    /// When `true` nonessential jobs are paused during maintenance windows.
+    #[serde(default = "Maintenance::default_pause_jobs")]
+    pub pause_jobs: bool,
+
+    /// Minutes the clients should wait before retrying a failed request
+    /// during maintenance windows.
+    #[serde(default = "Maintenance::default_retry_in")]
+    pub retry_in: u32,
+
+    /// The scheduled maintenance windows.
+    #[serde(default = "Maintenance::default_windows")]
+    pub windows: Vec<MaintenanceWindow>,
+}
+
+impl Default for Maintenance {
+    fn default() -> Self {
+        Self {
+            announce_interval: Self::default_announce_interval(),
+            pause_jobs: Self::default_pause_jobs(),
+            retry_in: Self::default_retry_in(),
+            windows: Self::default_windows(),
+        }
+    }
+}
+
+impl Maintenance {
+    fn default_announce_interval() -> u32 {
+        1800
+    }
+
+    fn default_pause_jobs() -> bool {
+        true
+    }
+
+    fn default_retry_in() -> u32 {
+        5
+    }
+
+    fn default_windows() -> Vec<MaintenanceWindow> {
+        vec![]
+    }
+
+    /// Returns the maintenance window that contains the given time, if any.
+    #[must_use]
+    pub fn active_window(&self, now: &DateTime<Utc>) -> Option<&MaintenanceWindow> {
+        self.windows.iter().find(|window| window.contains(now))
+    }
+}
+
+/// A period of time, in UTC, where the tracker is under maintenance.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct MaintenanceWindow {
+    /// The start of the window (inclusive). ISO 8601 format.
+    pub start: DateTime<Utc>,
+
+    /// The end of the window (exclusive). ISO 8601 format.
+    pub end: DateTime<Utc>,
+}
+
+impl MaintenanceWindow {
+    /// Returns `true` if the time is inside the window.
+    #[must_use]
+    pub fn contains(&self, time: &DateTime<Utc>) -> bool {
+        self.start <= *time && *time < self.end
+    }
+
+    /// Returns `true` if the window ends after it starts.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.start < self.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use chrono::{DateTime, Utc};
+
+    use super::{Maintenance, MaintenanceWindow};
+
+    fn time(iso_8601: &str) -> DateTime<Utc> {
+        DateTime::<Utc>::from_str(iso_8601).unwrap()
+    }
+
+    fn window(start: &str, end: &str) -> MaintenanceWindow {
+        MaintenanceWindow {
+            start: time(start),
+            end: time(end),
+        }
+    }
+
+    #[test]
+    fn a_maintenance_window_should_include_its_start_but_not_its_end() {
+        let window = window("2024-10-20T02:00:00Z", "2024-10-20T04:00:00Z");
+
+        assert!(!window.contains(&time("2024-10-20T01:59:59Z")));
+        assert!(window.contains(&time("2024-10-20T02:00:00Z")));
+        assert!(window.contains(&time("2024-10-20T03:59:59Z")));
+        assert!(!window.contains(&time("2024-10-20T04:00:00Z")));
+    }
+
+    #[test]
+    fn it_should_return_the_active_maintenance_window() {
+        let maintenance = Maintenance {
+            windows: vec![
+                window("2024-10-20T02:00:00Z", "2024-10-20T04:00:00Z"),
+                window("2024-10-27T02:00:00Z", "2024-10-27T04:00:00Z"),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            maintenance.active_window(&time("2024-10-27T03:00:00Z")),
+            Some(&maintenance.windows[1])
+        );
+        assert_eq!(maintenance.active_window(&time("2024-10-21T03:00:00Z")), None);
+    }
+}
@@ -24,6 +24,7 @@
 //! - [Port binding](#port-binding)
 //! - [TSL support](#tsl-support)
 //!     - [Generating self-signed certificates](#generating-self-signed-certificates)
+//! - [Including other files](#including-other-files)
 //! - [Default configuration](#default-configuration)
 //!
 //! ## Sections
@@ -35,6 +36,8 @@
 //! > **NOTICE**: some sections are arrays of structures. For example, the
 //! > `[[udp_trackers]]` section is an array of [`UdpTracker`] since
 //! > you can have multiple running UDP trackers bound to different ports.
+//! > An entry can also have a list of bind addresses, to run the same UDP
+//! > tracker on several addresses.
 //!
 //! Please refer to the documentation of each structure for more information
 //! about each section.
@@ -43,7 +46,10 @@
 //! - [`HTTP API configuration`](crate::v2::tracker_api::HttpApi)
 //! - [`HTTP Tracker configuration`](crate::v2::http_tracker::HttpTracker)
 //! - [`UDP Tracker configuration`](crate::v2::udp_tracker::UdpTracker)
+//! - [`WebSocket Tracker configuration`](crate::v2::websocket_tracker::WebSocketTracker)
 //! - [`Health Check API configuration`](crate::v2::health_check_api::HealthCheckApi)
+//! - [`Runtime configuration`](crate::v2::runtime::Runtime)
+//! - [`Alerts configuration`](crate::v2::alerts::Alerts)
 //!
 //! ## Port binding
 //!
@@ -78,8 +84,8 @@
 //!
 //! Alternatively, you could setup a reverse proxy like Nginx or Apache to
 //! handle the SSL/TLS part and forward the requests to the tracker. If you do
-//! that, you should set [`on_reverse_proxy`](crate::v2::network::Network::on_reverse_proxy)
-//! to `true` in the configuration file. It's out of scope for this
+//! that, you should set [`trusted_proxies`](crate::v2::network::Network::trusted_proxies)
+//! to the IP addresses of the proxies in the configuration file. It's out of scope for this
 //! documentation to explain in detail how to setup a reverse proxy, but the
 //! configuration file should be something like this:
 //!
@@ -190,6 +196,38 @@
 //! ssl_key_path = "./storage/tracker/lib/tls/localhost.key"
 //! ```
 //!
+//! A single listener can serve several hostnames with their own certificates.
+//! The certificate is selected with the hostname sent by the client (SNI):
+//!
+//! ```s
+//! [[http_trackers.tsl_config.sni_certificates]]
+//! hostname = "tracker.example.org"
+//! ssl_cert_path = "./storage/tracker/lib/tls/tracker.example.org.crt"
+//! ssl_key_path = "./storage/tracker/lib/tls/tracker.example.org.key"
+//! ```
+//!
+//! ## Including other files
+//!
+//! The configuration can be split into several files with the top-level
+//! `include` option. For example, to keep the secrets apart from the rest of
+//! the configuration:
+//!
+//! ```toml
+//! include = ["trackers.toml", "secrets.toml"]
+//!
+//! [metadata]
+//! schema_version = "2.0.0"
+//! ```
+//!
+//! Relative paths are resolved from the directory of the main configuration
+//! file (or from the current directory when the configuration is provided
+//! with the `TORRUST_TRACKER_CONFIG_TOML` env var). The included files are
+//! merged in order after the main file, so values in later files overwrite
+//! values in earlier ones. Env vars still have the highest priority.
+//!
+//! > **NOTICE**: included files can't include other files, and arrays (like
+//! > `[[udp_trackers]]`) are replaced, not concatenated.
+//!
 //! ## Default configuration
 //!
 //! The default configuration is:
@@ -199,28 +237,108 @@
 //! threshold = "info"
 //!
 //! [core]
+//! announce_interval_jitter = 0
+//! expired_keys_cleanup_interval = 3600
 //! inactive_peer_cleanup_interval = 600
 //! listed = false
+//! peer_timeout_intervals = 0
+//! persistent_torrent_history = false
 //! private = false
+//! scrape_cache_ttl = 0
+//! tracker_id_scheme = "instance_id"
 //! tracker_usage_statistics = true
+//! udp_connection_id_scheme = "hash"
+//!
+//! [core.abuse]
+//! ban_ip = false
+//! blacklist_info_hash = false
+//! webhooks = []
+//!
+//! [core.announce_budget]
+//! saturation_threshold = 50
 //!
 //! [core.announce_policy]
 //! interval = 120
 //! interval_min = 120
 //!
+//! [core.client_whitelist]
+//! peer_id_prefixes = []
+//!
 //! [core.database]
+//! auto_migrate = true
+//! completed_stat_flush_interval = 0
 //! driver = "sqlite3"
 //! path = "./storage/tracker/lib/database/sqlite3.db"
 //!
+//! [core.database.pool]
+//! acquire_timeout = 30
+//! idle_timeout = 600
+//! max_lifetime = 1800
+//! max_size = 10
+//!
+//! [core.dynamic_interval]
+//! max_interval = 3600
+//! min_interval = 60
+//!
+//! [core.failure_messages]
+//!
+//! [core.failure_retry]
+//! invalid_key = 60
+//! unregistered_torrent = 60
+//!
+//! [core.key_sync]
+//! interval = 60
+//!
+//! [core.maintenance]
+//! announce_interval = 1800
+//! pause_jobs = true
+//! retry_in = 5
+//! windows = []
+//!
 //! [core.net]
+//! client_ip_headers = ["X-Forwarded-For"]
 //! external_ip = "0.0.0.0"
 //! on_reverse_proxy = false
+//! return_client_ip = false
+//! trusted_proxies = []
+//!
+//! [core.net.peer_ip_replacement]
+//! ipv4 = true
+//! ipv6 = true
+//! ranges = ["127.0.0.0/8", "::1/128"]
+//!
+//! [core.overload]
+//! announce_interval = 1800
+//! recovery_threshold = 80
+//! retry_in = 5
+//! sample_interval = 1000
+//!
+//! [core.peer_diversity]
+//! ipv4_subnet_prefix_len = 24
+//! ipv6_subnet_prefix_len = 48
+//!
+//! [core.swarm_snapshot]
+//! interval = 60
+//!
+//! [core.swarm_store]
+//! backend = "memory"
+//! redis_key_prefix = "torrust"
+//! redis_url = "redis://127.0.0.1:6379"
+//!
+//! [core.torrent_aliases]
+//! announce_gap = 60
 //!
 //! [core.tracker_policy]
 //! max_peer_timeout = 900
 //! persistent_torrent_completed_stat = false
 //! remove_peerless_torrents = true
 //!
+//! [core.udp_connection_id_secret_rotation]
+//! grace_period = 300
+//!
+//! [core.whitelist]
+//! prefixes = []
+//!
 //! [http_api]
 //! bind_address = "127.0.0.1:1212"
 //!
@@ -228,29 +346,60 @@
 //! admin = "MyAccessToken"
 //! [health_check_api]
 //! bind_address = "127.0.0.1:1313"
+//!
+//! [runtime]
+//! max_blocking_threads = 512
 //!```
+pub mod abuse;
+pub mod access_log;
+pub mod alerts;
+pub mod announce_budget;
+pub mod bind_address;
+pub mod client_whitelist;
 pub mod core;
 pub mod database;
+pub mod dynamic_interval;
+pub mod experiment;
+pub mod failure_messages;
+pub mod failure_retry;
 pub mod health_check_api;
 pub mod http_tracker;
+pub mod jwt;
+pub mod key_sync;
 pub mod logging;
+pub mod maintenance;
 pub mod network;
+pub mod overload;
+pub mod peer_diversity;
+pub mod runtime;
+pub mod secret_rotation;
+pub mod swarm_snapshot;
+pub mod swarm_store;
+pub mod torrent_aliases;
 pub mod tracker_api;
 pub mod udp_tracker;
+pub mod websocket_tracker;
+pub mod whitelist;
 
 use std::fs;
 use std::net::IpAddr;
+use std::path::{Path, PathBuf};
 
 use figment::providers::{Env, Format, Serialized, Toml};
 use figment::Figment;
 use logging::Logging;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
+use self::alerts::{Alerts, WebhookKind};
 use self::core::Core;
 use self::health_check_api::HealthCheckApi;
 use self::http_tracker::HttpTracker;
+use self::runtime::Runtime;
 use self::tracker_api::HttpApi;
 use self::udp_tracker::UdpTracker;
+use self::websocket_tracker::WebSocketTracker;
 use crate::validator::{SemanticValidationError, Validator};
 use crate::{Error, Info, Metadata, Version};
 
@@ -263,6 +412,12 @@ const CONFIG_OVERRIDE_PREFIX: &str = "TORRUST_TRACKER_CONFIG_OVERRIDE_";
 /// Path separator in env var names for nested values in configuration.
 const CONFIG_OVERRIDE_SEPARATOR: &str = "__";
 
+/// Length of the randomly generated API admin token.
+const ADMIN_TOKEN_LENGTH: usize = 32;
+
+/// Top-level option with the list of configuration files to include.
+const INCLUDE_OPTION: &str = "include";
+
 /// Core configuration for the tracker.
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Default, Clone)]
 pub struct Configuration {
@@ -277,7 +432,9 @@ pub struct Configuration {
 
     /// The list of UDP trackers the tracker is running. Each UDP tracker
     /// represents a UDP server that the tracker is running and it has its own
-    /// configuration.
+    /// configuration. Entries with several bind addresses are expanded into
+    /// one UDP tracker per address.
+    #[serde(default, deserialize_with = "udp_tracker::deserialize_trackers")]
     pub udp_trackers: Option<Vec<UdpTracker>>,
 
     /// The list of HTTP trackers the tracker is running. Each HTTP tracker
@@ -285,11 +442,23 @@ pub struct Configuration {
     /// configuration.
     pub http_trackers: Option<Vec<HttpTracker>>,
 
+    /// The list of WebSocket trackers the tracker is running, for the
+    /// `WebTorrent` clients. They share the torrents with the UDP and HTTP
+    /// trackers.
+    pub websocket_trackers: Option<Vec<WebSocketTracker>>,
+
     /// The HTTP API configuration.
     pub http_api: Option<HttpApi>,
 
     /// The Health Check API configuration.
     pub health_check_api: HealthCheckApi,
+
+    /// The asynchronous runtime configuration.
+    pub runtime: Runtime,
+
+    /// The notifications for the administrators. Alerts are disabled if it's
+    /// not set.
+    pub alerts: Option<Alerts>,
 }
 
 impl Configuration {
@@ -302,16 +471,49 @@ impl Configuration {
 
     /// Saves the default configuration at the given path.
     ///
+    /// The well-known default secrets are replaced with random ones. Refer to
+    /// [`generate_random_secrets`](Configuration::generate_random_secrets).
+    ///
     /// # Errors
     ///
     /// Will return `Err` if `path` is not a valid path or the configuration
     /// file cannot be created.
     pub fn create_default_configuration_file(path: &str) -> Result<Configuration, Error> {
-        let config = Configuration::default();
+        let mut config = Configuration::default();
+        config.generate_random_secrets();
         config.save_to_file(path)?;
         Ok(config)
     }
 
+    /// Generates cryptographically random secrets, so new installations
+    /// aren't exposed with well-known credentials:
+    ///
+    /// - The `admin` access token for the tracker API, if the API is enabled.
+    /// - The secret for the UDP tracker connection IDs.
+    pub fn generate_random_secrets(&mut self) {
+        let mut rng = rand::thread_rng();
+
+        if let Some(ref mut api) = self.http_api {
+            let token: String = (&mut rng)
+                .sample_iter(Alphanumeric)
+                .take(ADMIN_TOKEN_LENGTH)
+                .map(char::from)
+                .collect();
+
+            api.add_token("admin", &token);
+        }
+
+        let secret: [u8; 32] = rng.gen();
+
+        self.core.udp_connection_id_secret = Some(secret.iter().map(|byte| format!("{byte:02x}")).collect());
+    }
+
+    /// Returns the `admin` access token for the tracker API, if any.
+    #[must_use]
+    pub fn get_admin_token(&self) -> Option<String> {
+        self.http_api.as_ref().and_then(|api| api.access_tokens.get("admin").cloned())
+    }
+
     /// Loads the configuration from the `Info` struct. The whole
     /// configuration in toml format is included in the `info.tracker_toml`
     /// string.
@@ -322,14 +524,23 @@ impl Configuration {
     ///
     /// Will return `Err` if the environment variable does not exist or has a bad configuration.
     pub fn load(info: &Info) -> Result<Configuration, Error> {
-        // Load configuration provided by the user, prioritizing env vars
-        let figment = if let Some(config_toml) = &info.config_toml {
-            Figment::from(Toml::string(config_toml)).merge(Env::prefixed(CONFIG_OVERRIDE_PREFIX).split(CONFIG_OVERRIDE_SEPARATOR))
+        // Load configuration provided by the user
+        let (figment, base_dir) = if let Some(config_toml) = &info.config_toml {
+            (Figment::from(Toml::string(config_toml)), PathBuf::from("."))
         } else {
-            Figment::from(Toml::file(&info.config_toml_path))
-                .merge(Env::prefixed(CONFIG_OVERRIDE_PREFIX).split(CONFIG_OVERRIDE_SEPARATOR))
+            let base_dir = Path::new(&info.config_toml_path)
+                .parent()
+                .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+
+            (Figment::from(Toml::file(&info.config_toml_path)), base_dir)
         };
 
+        // Merge the included files, in order
+        let figment = Self::merge_included_files(figment, &base_dir)?;
+
+        // Prioritize env vars
+        let figment = figment.merge(Env::prefixed(CONFIG_OVERRIDE_PREFIX).split(CONFIG_OVERRIDE_SEPARATOR));
+
         // Make sure user has provided the mandatory options.
         Self::check_mandatory_options(&figment)?;
 
@@ -349,6 +560,37 @@ impl Configuration {
         Ok(config)
     }
 
+    /// It merges the files listed in the top-level `include` option. Relative
+    /// paths are resolved from the directory of the main configuration file.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the `include` option is not a list of paths or
+    /// if any of the included files does not exist.
+    fn merge_included_files(figment: Figment, base_dir: &Path) -> Result<Figment, Error> {
+        let includes: Vec<String> = match figment.extract_inner(INCLUDE_OPTION) {
+            Ok(includes) => includes,
+            Err(err) if err.missing() => return Ok(figment),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut figment = figment;
+
+        for include in includes {
+            let path = base_dir.join(&include);
+
+            if !path.is_file() {
+                return Err(Error::MissingIncludedFile {
+                    path: path.display().to_string(),
+                });
+            }
+
+            figment = figment.merge(Toml::file(path));
+        }
+
+        Ok(figment)
+    }
+
     /// Some configuration options are mandatory. The tracker will panic if
     /// the user doesn't provide an explicit value for them from one of the
     /// configuration sources: TOML or ENV VARS.
@@ -371,7 +613,9 @@ impl Configuration {
         Ok(())
     }
 
-    /// Saves the configuration to the configuration file.
+    /// Saves the configuration to the configuration file. The file is only
+    /// readable by its owner when it's created, because the configuration
+    /// can contain secrets, like the API tokens.
     ///
     /// # Errors
     ///
@@ -383,7 +627,7 @@ impl Configuration {
     ///
     /// Will panic if the configuration cannot be written into the file.
     pub fn save_to_file(&self, path: &str) -> Result<(), Error> {
-        fs::write(path, self.to_toml()).expect("Could not write to file!");
+        write_private_file(Path::new(path), self.to_toml().as_bytes()).expect("Could not write to file!");
         Ok(())
     }
 
@@ -412,29 +656,126 @@ impl Configuration {
     /// Masks secrets in the configuration.
     #[must_use]
     pub fn mask_secrets(mut self) -> Self {
+        self.core.abuse.mask_secrets();
         self.core.database.mask_secrets();
+        self.core.key_sync.mask_secrets();
+        self.core.net.mask_secrets();
+        self.core.swarm_store.mask_secrets();
+
+        if self.core.udp_connection_id_secret.is_some() {
+            self.core.udp_connection_id_secret = Some("***".to_string());
+        }
 
         if let Some(ref mut api) = self.http_api {
             api.mask_secrets();
         }
 
+        if let Some(ref mut alerts) = self.alerts {
+            alerts.mask_secrets();
+        }
+
         self
     }
 }
 
 impl Validator for Configuration {
     fn validate(&self) -> Result<(), SemanticValidationError> {
-        self.core.validate()
+        self.core.validate()?;
+
+        if self.runtime.worker_threads == Some(0) || self.runtime.max_blocking_threads == 0 {
+            return Err(SemanticValidationError::InvalidRuntimeThreads);
+        }
+
+        if self
+            .udp_trackers
+            .as_ref()
+            .is_some_and(|udp_trackers| udp_trackers.iter().any(|udp_tracker| udp_tracker.workers == 0))
+        {
+            return Err(SemanticValidationError::InvalidUdpTrackerWorkers);
+        }
+
+        if self.udp_trackers.as_ref().is_some_and(|udp_trackers| {
+            udp_trackers
+                .iter()
+                .filter_map(|udp_tracker| udp_tracker.rate_limit)
+                .any(|rate_limit| rate_limit.packets_per_second == 0 || rate_limit.burst == Some(0))
+        }) {
+            return Err(SemanticValidationError::InvalidUdpTrackerRateLimit);
+        }
+
+        if self.udp_trackers.as_ref().is_some_and(|udp_trackers| {
+            udp_trackers.iter().any(|udp_tracker| {
+                udp_tracker.max_peers_in_response == 0
+                    || udp_tracker.default_peers_in_response > udp_tracker.max_peers_in_response
+            })
+        }) {
+            return Err(SemanticValidationError::InvalidUdpTrackerPeersInResponse);
+        }
+
+        if self
+            .http_api
+            .as_ref()
+            .and_then(|http_api| http_api.jwt.as_ref())
+            .is_some_and(|jwt| jwt.secret.is_none() && jwt.jwks_url.is_none())
+        {
+            return Err(SemanticValidationError::MissingApiJwtKeys);
+        }
+
+        if let Some(alerts) = &self.alerts {
+            if alerts.check_interval == 0 {
+                return Err(SemanticValidationError::InvalidAlertsCheckInterval);
+            }
+
+            if alerts.smtp.as_ref().is_some_and(|smtp| smtp.to.is_empty()) {
+                return Err(SemanticValidationError::MissingAlertsEmailRecipients);
+            }
+
+            if alerts
+                .webhooks
+                .iter()
+                .any(|webhook| webhook.kind == WebhookKind::Matrix && webhook.token.is_none())
+            {
+                return Err(SemanticValidationError::MissingAlertsMatrixToken);
+            }
+        }
+
+        Ok(())
     }
 }
 
+#[cfg(unix)]
+fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(contents)
+}
+
+#[cfg(not(unix))]
+fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    fs::write(path, contents)
+}
+
 #[cfg(test)]
 mod tests {
 
     use std::net::{IpAddr, Ipv4Addr};
 
+    use crate::v2_0_0::access_log::{AccessLog, Format as AccessLogFormat};
+    use crate::v2_0_0::alerts::{Smtp, SmtpSecurity, WebhookKind};
+    use crate::v2_0_0::http_tracker::{self, Acme};
+    use crate::v2_0_0::secret_rotation::SecretRotation;
+    use crate::v2_0_0::tracker_api::HttpApi;
+    use crate::v2_0_0::udp_tracker::{IoBackend, LoadShedding, RateLimit, RateLimitAction};
+    use crate::v2_0_0::websocket_tracker::WebSocketTracker;
     use crate::v2_0_0::Configuration;
-    use crate::Info;
+    use crate::{ConnectionIdScheme, Error, Info, SniCertificate};
 
     #[cfg(test)]
     fn default_config_toml() -> String {
@@ -447,30 +788,113 @@ mod tests {
                                 threshold = "info"
 
                                 [core]
+                                announce_interval_jitter = 0
+                                expired_keys_cleanup_interval = 3600
                                 inactive_peer_cleanup_interval = 600
                                 listed = false
+                                peer_timeout_intervals = 0
+                                persistent_torrent_history = false
                                 private = false
+                                scrape_cache_ttl = 0
+                                tracker_id_scheme = "instance_id"
                                 tracker_usage_statistics = true
+                                udp_connection_id_scheme = "hash"
+
+                                [core.abuse]
+                                ban_ip = false
+                                blacklist_info_hash = false
+                                webhooks = []
+
+                                [core.announce_budget]
+                                saturation_threshold = 50
 
                                 [core.announce_policy]
                                 interval = 120
                                 interval_min = 120
 
+                                [core.client_whitelist]
+                                peer_id_prefixes = []
+
                                 [core.database]
+                                auto_migrate = true
+                                completed_stat_flush_interval = 0
                                 driver = "sqlite3"
                                 path = "./storage/tracker/lib/database/sqlite3.db"
 
+                                [core.database.pool]
+                                acquire_timeout = 30
+                                idle_timeout = 600
+                                max_lifetime = 1800
+                                max_size = 10
+
+                                [core.dynamic_interval]
+                                max_interval = 3600
+                                min_interval = 60
+
+                                [core.failure_messages]
+
+                                [core.failure_retry]
+                                invalid_key = 60
+                                unregistered_torrent = 60
+
+                                [core.key_sync]
+                                interval = 60
+
+                                [core.maintenance]
+                                announce_interval = 1800
+                                pause_jobs = true
+                                retry_in = 5
+                                windows = []
+
                                 [core.net]
+                                client_ip_headers = ["X-Forwarded-For"]
                                 external_ip = "0.0.0.0"
                                 on_reverse_proxy = false
+                                return_client_ip = false
+                                trusted_proxies = []
+
+                                [core.net.peer_ip_replacement]
+                                ipv4 = true
+                                ipv6 = true
+                                ranges = ["127.0.0.0/8", "::1/128"]
+
+                                [core.overload]
+                                announce_interval = 1800
+                                recovery_threshold = 80
+                                retry_in = 5
+                                sample_interval = 1000
+
+                                [core.peer_diversity]
+                                ipv4_subnet_prefix_len = 24
+                                ipv6_subnet_prefix_len = 48
+
+                                [core.swarm_snapshot]
+                                interval = 60
+
+                                [core.swarm_store]
+                                backend = "memory"
+                                redis_key_prefix = "torrust"
+                                redis_url = "redis://127.0.0.1:6379"
+
+                                [core.torrent_aliases]
+                                announce_gap = 60
 
                                 [core.tracker_policy]
                                 max_peer_timeout = 900
                                 persistent_torrent_completed_stat = false
                                 remove_peerless_torrents = true
 
+                                [core.udp_connection_id_secret_rotation]
+                                grace_period = 300
+
+                                [core.whitelist]
+                                prefixes = []
+
                                 [health_check_api]
                                 bind_address = "127.0.0.1:1313"
+
+                                [runtime]
+                                max_blocking_threads = 512
         "#
         .lines()
         .map(str::trim_start)
@@ -523,6 +947,25 @@ mod tests {
         assert_eq!(contents, default_config_toml());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn configuration_file_should_only_be_readable_by_its_owner() {
+        use std::os::unix::fs::PermissionsExt;
+        use std::{env, fs};
+
+        use uuid::Uuid;
+
+        let path = env::temp_dir().join(format!("test_config_{}.toml", Uuid::new_v4()));
+
+        Configuration::default()
+            .save_to_file(&path.to_string_lossy())
+            .expect("Could not save configuration to file");
+
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+        fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn configuration_should_use_the_default_values_when_only_the_mandatory_options_are_provided_by_the_user_via_toml_file() {
         figment::Jail::expect_with(|jail| {
@@ -584,7 +1027,7 @@ mod tests {
     }
 
     #[test]
-    fn default_configuration_could_be_overwritten_from_a_single_env_var_with_toml_contents() {
+    fn configuration_should_load_the_udp_tracker_rate_limits() {
         figment::Jail::expect_with(|_jail| {
             let config_toml = r#"
                 [metadata]
@@ -597,8 +1040,12 @@ mod tests {
                 listed = false
                 private = false
 
-                [core.database]
-                path = "OVERWRITTEN DEFAULT DB PATH"
+                [[udp_trackers]]
+                bind_address = "0.0.0.0:6969"
+
+                [udp_trackers.rate_limit]
+                packets_per_second = 10
+                action = "error"
             "#
             .to_string();
 
@@ -609,18 +1056,23 @@ mod tests {
 
             let configuration = Configuration::load(&info).expect("Could not load configuration from file");
 
-            assert_eq!(configuration.core.database.path, "OVERWRITTEN DEFAULT DB PATH".to_string());
+            assert_eq!(
+                configuration.udp_trackers.unwrap()[0].rate_limit,
+                Some(RateLimit {
+                    packets_per_second: 10,
+                    burst: None,
+                    action: RateLimitAction::Error,
+                })
+            );
 
             Ok(())
         });
     }
 
     #[test]
-    fn default_configuration_could_be_overwritten_from_a_toml_config_file() {
-        figment::Jail::expect_with(|jail| {
-            jail.create_file(
-                "tracker.toml",
-                r#"
+    fn configuration_should_load_the_acme_settings_of_the_http_trackers() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
                 [metadata]
                 schema_version = "2.0.0"
 
@@ -631,40 +1083,960 @@ mod tests {
                 listed = false
                 private = false
 
-                [core.database]
-                path = "OVERWRITTEN DEFAULT DB PATH"
-            "#,
-            )?;
+                [[http_trackers]]
+                bind_address = "0.0.0.0:443"
+
+                [http_trackers.acme]
+                domains = ["tracker.example.com"]
+                contact_email = "admin@example.com"
+            "#
+            .to_string();
 
             let info = Info {
-                config_toml: None,
-                config_toml_path: "tracker.toml".to_string(),
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
             };
 
             let configuration = Configuration::load(&info).expect("Could not load configuration from file");
 
-            assert_eq!(configuration.core.database.path, "OVERWRITTEN DEFAULT DB PATH".to_string());
+            assert_eq!(
+                configuration.http_trackers.unwrap()[0].acme,
+                Some(Acme {
+                    domains: vec!["tracker.example.com".to_string()],
+                    contact_email: Some("admin@example.com".to_string()),
+                    cache_dir: "./storage/tracker/lib/acme".into(),
+                    directory_url: "https://acme-v02.api.letsencrypt.org/directory".to_string(),
+                })
+            );
 
             Ok(())
         });
     }
 
     #[test]
-    fn configuration_should_allow_to_overwrite_the_default_tracker_api_token_for_admin_with_an_env_var() {
-        figment::Jail::expect_with(|jail| {
-            jail.set_env("TORRUST_TRACKER_CONFIG_OVERRIDE_HTTP_API__ACCESS_TOKENS__ADMIN", "NewToken");
+    fn configuration_should_load_the_connection_settings_of_the_http_trackers() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [[http_trackers]]
+                bind_address = "0.0.0.0:7070"
+                keep_alive_timeout = 60
+                max_connections = 10000
+            "#
+            .to_string();
 
             let info = Info {
-                config_toml: Some(default_config_toml()),
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            let http_tracker = &configuration.http_trackers.unwrap()[0];
+
+            assert_eq!(http_tracker.keep_alive_timeout, 60);
+            assert_eq!(http_tracker.max_connections, Some(10000));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_load_the_access_log_of_the_http_trackers() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [[http_trackers]]
+                bind_address = "0.0.0.0:7070"
+
+                [http_trackers.access_log]
+                path = "./storage/tracker/log/http_tracker_access.log"
+                format = "json"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
                 config_toml_path: String::new(),
             };
 
             let configuration = Configuration::load(&info).expect("Could not load configuration from file");
 
+            let http_tracker = &configuration.http_trackers.unwrap()[0];
+
             assert_eq!(
-                configuration.http_api.unwrap().access_tokens.get("admin"),
-                Some("NewToken".to_owned()).as_ref()
+                http_tracker.access_log,
+                Some(AccessLog {
+                    path: "./storage/tracker/log/http_tracker_access.log".into(),
+                    format: AccessLogFormat::Json,
+                    max_size_mib: 100,
+                    max_files: 5,
+                })
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_load_the_failure_message_templates() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [core.failure_messages]
+                invalid_key = "{reason}. Get a new key at https://tracker.example.com/keys"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            assert_eq!(
+                configuration.core.failure_messages.invalid_key,
+                Some("{reason}. Get a new key at https://tracker.example.com/keys".to_string())
             );
+            assert_eq!(configuration.core.failure_messages.not_whitelisted, None);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_load_the_roles_of_the_api_access_tokens() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [http_api.access_tokens]
+                admin = "MyAccessToken"
+                dashboard = "MyDashboardToken"
+
+                [http_api.access_token_roles]
+                dashboard = "stats"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            let http_api = configuration.http_api.unwrap();
+
+            assert_eq!(
+                http_api.access_token_roles.get("dashboard"),
+                Some(&crate::AccessTokenRole::Stats)
+            );
+            assert_eq!(http_api.access_token_roles.get("admin"), None);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_load_the_jwt_authentication_of_the_api() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [http_api.jwt]
+                secret = "MyJwtSecret"
+                audience = "torrust-tracker"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            let jwt = configuration.http_api.unwrap().jwt.unwrap();
+
+            assert_eq!(jwt.secret, Some("MyJwtSecret".to_string()));
+            assert_eq!(jwt.jwks_url, None);
+            assert_eq!(jwt.jwks_cache_ttl, 3600);
+            assert_eq!(jwt.audience, Some("torrust-tracker".to_string()));
+            assert_eq!(jwt.issuer, None);
+            assert_eq!(jwt.role_claim, "role");
+            assert_eq!(jwt.default_role, None);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_load_the_rate_limit_of_the_api() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [http_api.rate_limit]
+                requests_per_minute_per_ip = 120
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            let rate_limit = configuration.http_api.unwrap().rate_limit.unwrap();
+
+            assert_eq!(rate_limit.requests_per_minute_per_ip, Some(120));
+            assert_eq!(rate_limit.max_failed_auth_attempts, Some(10));
+            assert_eq!(rate_limit.lockout_duration, 900);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_load_the_sni_certificates_of_the_http_trackers() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [[http_trackers]]
+                bind_address = "0.0.0.0:7070"
+
+                [http_trackers.tsl_config]
+                ssl_cert_path = "./storage/tracker/lib/tls/localhost.crt"
+                ssl_key_path = "./storage/tracker/lib/tls/localhost.key"
+
+                [[http_trackers.tsl_config.sni_certificates]]
+                hostname = "tracker.example.org"
+                ssl_cert_path = "./storage/tracker/lib/tls/tracker.example.org.crt"
+                ssl_key_path = "./storage/tracker/lib/tls/tracker.example.org.key"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            let tsl_config = configuration.http_trackers.unwrap()[0].tsl_config.clone().unwrap();
+
+            assert_eq!(
+                tsl_config.sni_certificates,
+                vec![SniCertificate {
+                    hostname: "tracker.example.org".to_string(),
+                    ssl_cert_path: "./storage/tracker/lib/tls/tracker.example.org.crt".into(),
+                    ssl_key_path: "./storage/tracker/lib/tls/tracker.example.org.key".into(),
+                }]
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_load_the_rate_limit_of_the_http_trackers() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [[http_trackers]]
+                bind_address = "0.0.0.0:7070"
+
+                [http_trackers.rate_limit]
+                requests_per_minute_per_ip = 60
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            assert_eq!(
+                configuration.http_trackers.unwrap()[0].rate_limit,
+                Some(http_tracker::RateLimit {
+                    requests_per_minute_per_ip: Some(60),
+                    requests_per_minute_per_key: None,
+                    retry_in: 1,
+                })
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_load_the_websocket_trackers() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [[websocket_trackers]]
+                bind_address = "0.0.0.0:8000"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            assert_eq!(
+                configuration.websocket_trackers,
+                Some(vec![WebSocketTracker {
+                    bind_address: "0.0.0.0:8000".parse().unwrap(),
+                    tsl_config: None,
+                    max_offers: 10,
+                }])
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_load_the_udp_tracker_load_shedding() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [[udp_trackers]]
+                bind_address = "0.0.0.0:6969"
+
+                [udp_trackers.load_shedding]
+                max_queue_depth = 1000
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            assert_eq!(
+                configuration.udp_trackers.unwrap()[0].load_shedding,
+                Some(LoadShedding {
+                    max_queue_depth: Some(1000),
+                    shed_when_overloaded: true,
+                })
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_load_the_udp_tracker_io_backend() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [[udp_trackers]]
+                bind_address = "0.0.0.0:6969"
+                udp_io_backend = "io_uring"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            assert_eq!(configuration.udp_trackers.unwrap()[0].udp_io_backend, IoBackend::IoUring);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_expand_the_udp_trackers_with_several_bind_addresses() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [[udp_trackers]]
+                bind_address = ["[::]:6969", "0.0.0.0:1337"]
+                workers = 4
+
+                [[udp_trackers]]
+                bind_address = "0.0.0.0:6970"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            let udp_trackers = configuration.udp_trackers.unwrap();
+
+            assert_eq!(
+                udp_trackers
+                    .iter()
+                    .map(|udp_tracker| (udp_tracker.bind_address.to_string(), udp_tracker.workers))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("[::]:6969".to_string(), 4),
+                    ("0.0.0.0:1337".to_string(), 4),
+                    ("0.0.0.0:6970".to_string(), 1)
+                ]
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_keep_the_hostnames_of_the_bind_addresses_without_resolving_them() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [[udp_trackers]]
+                bind_address = ["tracker.internal:6969", "0.0.0.0:1337"]
+
+                [http_api]
+                bind_address = "api.tracker.internal:1212"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            assert_eq!(
+                configuration
+                    .udp_trackers
+                    .unwrap()
+                    .iter()
+                    .map(|udp_tracker| udp_tracker.bind_address.to_string())
+                    .collect::<Vec<_>>(),
+                vec!["tracker.internal:6969".to_string(), "0.0.0.0:1337".to_string()]
+            );
+            assert_eq!(
+                configuration.http_api.unwrap().bind_address,
+                "api.tracker.internal:1212".parse().unwrap()
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_not_be_loaded_with_a_bind_address_without_port() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [http_api]
+                bind_address = "api.tracker.internal"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            assert!(Configuration::load(&info).is_err());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_allow_overriding_the_connection_id_scheme_in_the_udp_trackers() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+                udp_connection_id_scheme = "keyed_blake3"
+
+                [[udp_trackers]]
+                bind_address = "0.0.0.0:6969"
+                connection_id_scheme = "siphash24"
+
+                [[udp_trackers]]
+                bind_address = "0.0.0.0:6970"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            let udp_trackers = configuration.udp_trackers.unwrap();
+
+            assert_eq!(configuration.core.udp_connection_id_scheme, ConnectionIdScheme::KeyedBlake3);
+            assert_eq!(udp_trackers[0].connection_id_scheme, Some(ConnectionIdScheme::SipHash24));
+            assert_eq!(udp_trackers[1].connection_id_scheme, None);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_load_the_udp_connection_id_secret_persistence_and_rotation() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+                udp_connection_id_secret_file = "./storage/tracker/lib/udp_connection_id_secret.json"
+
+                [core.udp_connection_id_secret_rotation]
+                interval = 86400
+                grace_period = 600
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            assert_eq!(
+                configuration.core.udp_connection_id_secret_file,
+                Some("./storage/tracker/lib/udp_connection_id_secret.json".to_string())
+            );
+            assert_eq!(
+                configuration.core.udp_connection_id_secret_rotation,
+                SecretRotation {
+                    interval: Some(86400),
+                    grace_period: 600,
+                }
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_load_the_alerts() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [alerts]
+                certificate_expiration_days = 7
+
+                [alerts.smtp]
+                server = "smtp.example.com"
+                password = "MySmtpPassword"
+                from = "tracker@example.com"
+                to = ["admin@example.com"]
+
+                [[alerts.webhooks]]
+                kind = "matrix"
+                url = "https://matrix.example.com/_matrix/client/v3/rooms/!room:example.com/send/m.room.message"
+                token = "MyMatrixAccessToken"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            let alerts = configuration.alerts.clone().expect("the alerts should be enabled");
+
+            assert_eq!(alerts.check_interval, 60);
+            assert_eq!(alerts.certificate_expiration_days, 7);
+            assert_eq!(
+                alerts.smtp,
+                Some(Smtp {
+                    server: "smtp.example.com".to_string(),
+                    port: 587,
+                    security: SmtpSecurity::StartTls,
+                    username: None,
+                    password: Some("MySmtpPassword".to_string()),
+                    from: "tracker@example.com".to_string(),
+                    to: vec!["admin@example.com".to_string()],
+                })
+            );
+            assert_eq!(alerts.webhooks[0].kind, WebhookKind::Matrix);
+
+            let masked = configuration.mask_secrets().alerts.expect("the alerts should be enabled");
+
+            assert_eq!(masked.smtp.and_then(|smtp| smtp.password), Some("***".to_string()));
+            assert_eq!(masked.webhooks[0].token, Some("***".to_string()));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn default_configuration_could_be_overwritten_from_a_single_env_var_with_toml_contents() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [core.database]
+                path = "OVERWRITTEN DEFAULT DB PATH"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            assert_eq!(configuration.core.database.path, "OVERWRITTEN DEFAULT DB PATH".to_string());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn default_configuration_could_be_overwritten_from_a_toml_config_file() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "tracker.toml",
+                r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [core.database]
+                path = "OVERWRITTEN DEFAULT DB PATH"
+            "#,
+            )?;
+
+            let info = Info {
+                config_toml: None,
+                config_toml_path: "tracker.toml".to_string(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            assert_eq!(configuration.core.database.path, "OVERWRITTEN DEFAULT DB PATH".to_string());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_merge_the_included_files_in_order() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_dir("etc")?;
+            jail.create_file(
+                "etc/tracker.toml",
+                r#"
+                include = ["database.toml", "secrets.toml"]
+
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [core.database]
+                path = "MAIN DB PATH"
+            "#,
+            )?;
+            jail.create_file(
+                "etc/database.toml",
+                r#"
+                [core.database]
+                path = "INCLUDED DB PATH"
+            "#,
+            )?;
+            jail.create_file(
+                "etc/secrets.toml",
+                r#"
+                [core]
+                private = true
+
+                [core.database]
+                path = "SECRET DB PATH"
+            "#,
+            )?;
+
+            let info = Info {
+                config_toml: None,
+                config_toml_path: "etc/tracker.toml".to_string(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            assert_eq!(configuration.core.database.path, "SECRET DB PATH".to_string());
+            assert!(configuration.core.private);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_fail_when_an_included_file_does_not_exist() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = format!("include = [\"missing.toml\"]\n{}", default_config_toml());
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let error = Configuration::load(&info).expect_err("Configuration should not be loaded");
+
+            assert!(matches!(error, Error::MissingIncludedFile { .. }));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_generate_random_secrets() {
+        let mut configuration = Configuration {
+            http_api: Some(HttpApi::default()),
+            ..Default::default()
+        };
+        let mut other_configuration = configuration.clone();
+
+        configuration.generate_random_secrets();
+        other_configuration.generate_random_secrets();
+
+        assert_eq!(configuration.get_admin_token().map(|token| token.len()), Some(32));
+        assert!(configuration.core.udp_connection_id_secret_bytes().is_some());
+        assert_ne!(configuration.get_admin_token(), other_configuration.get_admin_token());
+        assert_ne!(
+            configuration.core.udp_connection_id_secret,
+            other_configuration.core.udp_connection_id_secret
+        );
+    }
+
+    #[test]
+    fn configuration_should_allow_to_overwrite_the_default_tracker_api_token_for_admin_with_an_env_var() {
+        figment::Jail::expect_with(|jail| {
+            jail.set_env("TORRUST_TRACKER_CONFIG_OVERRIDE_HTTP_API__ACCESS_TOKENS__ADMIN", "NewToken");
+
+            let info = Info {
+                config_toml: Some(default_config_toml()),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            assert_eq!(
+                configuration.http_api.unwrap().access_tokens.get("admin"),
+                Some("NewToken".to_owned()).as_ref()
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn configuration_should_read_the_table_prefix_from_the_db_table_prefix_option() {
+        figment::Jail::expect_with(|_jail| {
+            let config_toml = r#"
+                [metadata]
+                schema_version = "2.0.0"
+
+                [logging]
+                threshold = "info"
+
+                [core]
+                listed = false
+                private = false
+
+                [core.database]
+                db_table_prefix = "tracker_"
+            "#
+            .to_string();
+
+            let info = Info {
+                config_toml: Some(config_toml),
+                config_toml_path: String::new(),
+            };
+
+            let configuration = Configuration::load(&info).expect("Could not load configuration from file");
+
+            assert_eq!(configuration.core.database.table_prefix(), "tracker_");
 
             Ok(())
         });
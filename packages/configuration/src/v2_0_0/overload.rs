@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+/// Overload control.
+///
+/// The tracker samples its load periodically: the requests received per
+/// second, the requests in process and the lag of the event loop (how late
+/// the sampling task is woken up). The tracker is overloaded when any of the
+/// metrics exceeds its threshold, and it stays overloaded until all of them
+/// drop below `recovery_threshold` percent of their thresholds, so it does not
+/// flap around the limits.
+///
+/// While the tracker is overloaded:
+///
+/// - The announce intervals are raised to `announce_interval`, so clients
+///   contact the tracker less often.
+/// - Low-priority requests (`scrape` requests) are rejected. The HTTP
+///   tracker error responses include the `retry in` key (in minutes)
+///   ([BEP 31](https://www.bittorrent.org/beps/bep_0031.html)), and the UDP
+///   tracker error responses include it in the message.
+///
+/// Overload control is disabled when none of the thresholds is set.
+///
+/// ```toml
+/// [core.overload]
+/// max_requests_per_second = 20000
+/// max_inflight_requests = 5000
+/// max_event_loop_lag = 100
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Overload {
+    /// Announce interval (and minimum announce interval) in seconds while
+    /// the tracker is overloaded. It's only applied if it's greater than the
+    /// configured announce policy intervals.
+    #[serde(default = "Overload::default_announce_interval")]
+    pub announce_interval: u32,
+
+    /// Lag of the event loop, in milliseconds, above which the tracker is
+    /// overloaded. It's not checked if it's not set.
+    #[serde(default = "Overload::default_max_event_loop_lag")]
+    pub max_event_loop_lag: Option<u64>,
+
+    /// Number of requests in process above which the tracker is overloaded.
+    /// It's not checked if it's not set.
+    #[serde(default = "Overload::default_max_inflight_requests")]
+    pub max_inflight_requests: Option<u32>,
+
+    /// Number of requests per second above which the tracker is overloaded.
+    /// It's not checked if it's not set.
+    #[serde(default = "Overload::default_max_requests_per_second")]
+    pub max_requests_per_second: Option<u32>,
+
+    /// Percentage of the thresholds the load has to drop below before the
+    /// tracker is no longer overloaded.
+    #[serde(default = "Overload::default_recovery_threshold")]
+    pub recovery_threshold: u8,
+
+    /// Minutes the clients should wait before retrying a rejected request
+    /// while the tracker is overloaded.
+    #[serde(default = "Overload::default_retry_in")]
+    pub retry_in: u32,
+
+    /// Milliseconds between two samples of the load.
+    #[serde(default = "Overload::default_sample_interval")]
+    pub sample_interval: u64,
+}
+
+impl Default for Overload {
+    fn default() -> Self {
+        Self {
+            announce_interval: Self::default_announce_interval(),
+            max_event_loop_lag: Self::default_max_event_loop_lag(),
+            max_inflight_requests: Self::default_max_inflight_requests(),
+            max_requests_per_second: Self::default_max_requests_per_second(),
+            recovery_threshold: Self::default_recovery_threshold(),
+            retry_in: Self::default_retry_in(),
+            sample_interval: Self::default_sample_interval(),
+        }
+    }
+}
+
+impl Overload {
+    fn default_announce_interval() -> u32 {
+        1800
+    }
+
+    fn default_max_event_loop_lag() -> Option<u64> {
+        None
+    }
+
+    fn default_max_inflight_requests() -> Option<u32> {
+        None
+    }
+
+    fn default_max_requests_per_second() -> Option<u32> {
+        None
+    }
+
+    fn default_recovery_threshold() -> u8 {
+        80
+    }
+
+    fn default_retry_in() -> u32 {
+        5
+    }
+
+    fn default_sample_interval() -> u64 {
+        1000
+    }
+
+    /// Returns `true` if any of the thresholds is set.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.max_event_loop_lag.is_some() || self.max_inflight_requests.is_some() || self.max_requests_per_second.is_some()
+    }
+}
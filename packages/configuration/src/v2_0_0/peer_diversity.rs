@@ -0,0 +1,95 @@
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+
+/// Diversity constraints applied when selecting the peers returned in
+/// announce responses.
+///
+/// They prevent responses dominated by peers from the same network, for
+/// example, a seed farm in a single hosting provider. All constraints are
+/// disabled by default.
+///
+/// ```toml
+/// [core.peer_diversity]
+/// max_peers_per_subnet = 5
+/// max_peers_per_asn = 10
+/// asn_database_path = "./storage/tracker/lib/ip2asn.tsv"
+/// ```
+///
+/// By default, subnets are `/24` networks for IPv4 peers and `/48` networks
+/// for IPv6 peers.
+///
+/// To bound the work done for large swarms, only four candidates are examined
+/// for each peer in the response. When too many of them break the
+/// constraints, the response has fewer peers than requested.
+///
+/// The ASN database is a text file with one IP range per line: the range in
+/// CIDR notation and the AS number separated by whitespace. Lines starting
+/// with `#` are ignored. Ranges must not overlap.
+///
+/// ```text
+/// 1.0.0.0/24 13335
+/// 2001:db8::/32 64496
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct PeerDiversity {
+    /// Maximum number of peers from the same subnet in an announce response.
+    #[serde(default = "PeerDiversity::default_max_peers_per_subnet")]
+    pub max_peers_per_subnet: Option<u32>,
+
+    /// Prefix length of the subnets for IPv4 peers.
+    #[serde(default = "PeerDiversity::default_ipv4_subnet_prefix_len")]
+    pub ipv4_subnet_prefix_len: u8,
+
+    /// Prefix length of the subnets for IPv6 peers.
+    #[serde(default = "PeerDiversity::default_ipv6_subnet_prefix_len")]
+    pub ipv6_subnet_prefix_len: u8,
+
+    /// Maximum number of peers from the same autonomous system in an announce
+    /// response. It's only applied if the `asn_database_path` is set.
+    #[serde(default = "PeerDiversity::default_max_peers_per_asn")]
+    pub max_peers_per_asn: Option<u32>,
+
+    /// Path to the database used to find the autonomous system of the peers.
+    #[serde(default = "PeerDiversity::default_asn_database_path")]
+    pub asn_database_path: Option<Utf8PathBuf>,
+}
+
+impl Default for PeerDiversity {
+    fn default() -> Self {
+        Self {
+            max_peers_per_subnet: Self::default_max_peers_per_subnet(),
+            ipv4_subnet_prefix_len: Self::default_ipv4_subnet_prefix_len(),
+            ipv6_subnet_prefix_len: Self::default_ipv6_subnet_prefix_len(),
+            max_peers_per_asn: Self::default_max_peers_per_asn(),
+            asn_database_path: Self::default_asn_database_path(),
+        }
+    }
+}
+
+impl PeerDiversity {
+    fn default_max_peers_per_subnet() -> Option<u32> {
+        None
+    }
+
+    fn default_ipv4_subnet_prefix_len() -> u8 {
+        24
+    }
+
+    fn default_ipv6_subnet_prefix_len() -> u8 {
+        48
+    }
+
+    fn default_max_peers_per_asn() -> Option<u32> {
+        None
+    }
+
+    fn default_asn_database_path() -> Option<Utf8PathBuf> {
+        None
+    }
+
+    /// Returns `true` if any of the constraints is enabled.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.max_peers_per_subnet.is_some() || (self.max_peers_per_asn.is_some() && self.asn_database_path.is_some())
+    }
+}
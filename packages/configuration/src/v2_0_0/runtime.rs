@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// Sizing of the asynchronous runtime that runs all the services.
+///
+/// The defaults fit most deployments. Big servers may want more blocking
+/// threads for database access, and small VPSs fewer worker threads:
+///
+/// ```toml
+/// [runtime]
+/// worker_threads = 2
+/// max_blocking_threads = 16
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Runtime {
+    /// Number of worker threads. If it's not set, there is one worker thread
+    /// per CPU core.
+    #[serde(default = "Runtime::default_worker_threads")]
+    pub worker_threads: Option<u32>,
+
+    /// Maximum number of threads for blocking operations, like database
+    /// access.
+    #[serde(default = "Runtime::default_max_blocking_threads")]
+    pub max_blocking_threads: u32,
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self {
+            worker_threads: Self::default_worker_threads(),
+            max_blocking_threads: Self::default_max_blocking_threads(),
+        }
+    }
+}
+
+impl Runtime {
+    fn default_worker_threads() -> Option<u32> {
+        None
+    }
+
+    fn default_max_blocking_threads() -> u32 {
+        512
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// Rotation of the secret used to generate the UDP tracker connection IDs.
+///
+/// After a rotation, the connection IDs generated with the previous secret are
+/// still accepted during the grace period, so clients connected just before
+/// the rotation don't have to connect again. The secret can also be rotated
+/// on demand with the tracker API.
+///
+/// ```toml
+/// [core.udp_connection_id_secret_rotation]
+/// interval = 86400
+/// grace_period = 300
+/// ```
+///
+/// > **NOTICE**: the grace period should be at least as long as the lifetime
+/// > of the connection IDs (two minutes). Otherwise, some valid connection IDs
+/// > are rejected after each rotation.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct SecretRotation {
+    /// Interval in seconds between scheduled rotations. The secret is only
+    /// rotated on demand if it's not set.
+    #[serde(default = "SecretRotation::default_interval")]
+    pub interval: Option<u64>,
+
+    /// Time in seconds the previous secret is still accepted after a rotation.
+    #[serde(default = "SecretRotation::default_grace_period")]
+    pub grace_period: u64,
+}
+
+impl Default for SecretRotation {
+    fn default() -> Self {
+        Self {
+            interval: Self::default_interval(),
+            grace_period: Self::default_grace_period(),
+        }
+    }
+}
+
+impl SecretRotation {
+    fn default_interval() -> Option<u64> {
+        None
+    }
+
+    fn default_grace_period() -> u64 {
+        300
+    }
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+/// Periodic snapshots of the swarms, restored when the tracker starts.
+///
+/// The peers are only kept in memory, so they are lost when the tracker is
+/// restarted, and the clients get empty peer lists until the peers announce
+/// again. With the snapshots, the torrents and their peers (with the time of
+/// their last announce) are saved to a file every `interval` seconds and when
+/// the tracker stops, and restored when it starts. It's disabled by default.
+///
+/// ```toml
+/// [core.swarm_snapshot]
+/// path = "./storage/tracker/lib/swarm_snapshot.json"
+/// interval = 60
+/// ```
+///
+/// The peers that have expired since the snapshot was saved are not restored.
+/// The snapshots can only be saved to a file. Refer to the
+/// [`swarm_snapshot`](https://docs.rs/torrust-tracker/latest/torrust_tracker/core/swarm_snapshot/index.html)
+/// module of the tracker for the details.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct SwarmSnapshot {
+    /// Path of the snapshot file. The snapshots are disabled if it's not
+    /// set.
+    #[serde(default = "SwarmSnapshot::default_path")]
+    pub path: Option<String>,
+
+    /// Interval in seconds between snapshots.
+    #[serde(default = "SwarmSnapshot::default_interval")]
+    pub interval: u64,
+}
+
+impl Default for SwarmSnapshot {
+    fn default() -> Self {
+        Self {
+            path: Self::default_path(),
+            interval: Self::default_interval(),
+        }
+    }
+}
+
+impl SwarmSnapshot {
+    fn default_path() -> Option<String> {
+        None
+    }
+
+    fn default_interval() -> u64 {
+        60
+    }
+}
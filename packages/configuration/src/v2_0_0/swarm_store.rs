@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Where the swarms (the peers of each torrent) are stored.
+///
+/// By default, each tracker instance keeps its swarms in memory, so it only
+/// knows the peers that announced to it. Several instances behind a load
+/// balancer can share their swarms in a `Redis` server:
+///
+/// ```toml
+/// [core.swarm_store]
+/// backend = "redis"
+/// redis_url = "redis://:MyRedisPassword@redis.example.com:6379/0"
+/// ```
+///
+/// Each instance still keeps its swarms in memory, for the API and the
+/// statistics, but the announce and scrape responses are built from the
+/// shared swarms.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct SwarmStore {
+    /// Where the swarms are stored. Possible values are: `memory` and
+    /// `redis`.
+    #[serde(default = "SwarmStore::default_backend")]
+    pub backend: Backend,
+
+    /// Prefix of the keys of the `Redis` backend, so several trackers can
+    /// use the same `Redis` database.
+    #[serde(default = "SwarmStore::default_redis_key_prefix")]
+    pub redis_key_prefix: String,
+
+    /// URL of the `Redis` server of the `Redis` backend, for example:
+    /// `redis://:password@localhost:6379/0`.
+    #[serde(default = "SwarmStore::default_redis_url")]
+    pub redis_url: String,
+}
+
+impl Default for SwarmStore {
+    fn default() -> Self {
+        Self {
+            backend: Self::default_backend(),
+            redis_key_prefix: Self::default_redis_key_prefix(),
+            redis_url: Self::default_redis_url(),
+        }
+    }
+}
+
+impl SwarmStore {
+    fn default_backend() -> Backend {
+        Backend::Memory
+    }
+
+    fn default_redis_key_prefix() -> String {
+        String::from("torrust")
+    }
+
+    fn default_redis_url() -> String {
+        String::from("redis://127.0.0.1:6379")
+    }
+
+    /// Masks secrets in the configuration.
+    pub fn mask_secrets(&mut self) {
+        if let Ok(mut url) = Url::parse(&self.redis_url) {
+            if url.password().is_some() && url.set_password(Some("***")).is_ok() {
+                self.redis_url = url.to_string();
+            }
+        }
+    }
+}
+
+/// The storage of the swarms.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// The swarms are kept in the memory of the tracker instance.
+    Memory,
+    /// The swarms are stored in a `Redis` server, shared by several tracker
+    /// instances.
+    Redis,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SwarmStore;
+
+    #[test]
+    fn it_should_allow_masking_the_redis_password() {
+        let mut swarm_store = SwarmStore {
+            redis_url: "redis://:password@localhost:6379/0".to_string(),
+            ..SwarmStore::default()
+        };
+
+        swarm_store.mask_secrets();
+
+        assert_eq!(swarm_store.redis_url, "redis://:***@localhost:6379/0".to_string());
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// Aliases between the v1 and v2 infohashes of hybrid torrents.
+///
+/// Clients announce hybrid torrents ([BEP 52](https://www.bittorrent.org/beps/bep_0052.html))
+/// twice: with the v1 infohash and with the v2 infohash truncated to 20
+/// bytes. The tracker links both infohashes when `confirmations` peers (from
+/// different IP addresses) announce them one right after the other, no more
+/// than `announce_gap` seconds apart. Then the peers of both swarms are
+/// returned to the announcers of either infohash.
+///
+/// Aliases are disabled by default.
+///
+/// ```toml
+/// [core.torrent_aliases]
+/// confirmations = 3
+/// announce_gap = 60
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TorrentAliases {
+    /// Number of peers that have to announce both infohashes before linking
+    /// them. Aliases are disabled if it's not set.
+    #[serde(default = "TorrentAliases::default_confirmations")]
+    pub confirmations: Option<u32>,
+
+    /// Maximum time in seconds between the announces of both infohashes from
+    /// the same peer.
+    #[serde(default = "TorrentAliases::default_announce_gap")]
+    pub announce_gap: u64,
+}
+
+impl Default for TorrentAliases {
+    fn default() -> Self {
+        Self {
+            confirmations: Self::default_confirmations(),
+            announce_gap: Self::default_announce_gap(),
+        }
+    }
+}
+
+impl TorrentAliases {
+    fn default_confirmations() -> Option<u32> {
+        None
+    }
+
+    fn default_announce_gap() -> u64 {
+        60
+    }
+}
@@ -4,20 +4,39 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-use crate::TslConfig;
+use super::jwt::Jwt;
+use crate::{AccessLog, BindAddress, TslConfig};
 
 pub type AccessTokens = HashMap<String, String>;
+pub type AccessTokenRoles = HashMap<String, AccessTokenRole>;
+
+/// The role of an API access token. It sets the endpoints the token can be
+/// used for.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessTokenRole {
+    /// All the endpoints.
+    #[default]
+    Admin,
+    /// Only the `GET` requests of the statistics endpoints. For example, for
+    /// a dashboard.
+    Stats,
+    /// Only the authentication keys endpoints. For example, for a service
+    /// issuing the keys to the users of a private tracker.
+    KeyIssuer,
+}
 
 /// Configuration for the HTTP API.
 #[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct HttpApi {
     /// The address the tracker will bind to.
-    /// The format is `ip:port`, for example `0.0.0.0:6969`. If you want to
+    /// The format is `ip:port`, for example `0.0.0.0:6969`. The host can also
+    /// be a hostname, resolved when the service starts. If you want to
     /// listen to all interfaces, use `0.0.0.0`. If you want the operating
     /// system to choose a random port, use port `0`.
     #[serde(default = "HttpApi::default_bind_address")]
-    pub bind_address: SocketAddr,
+    pub bind_address: BindAddress,
 
     /// TSL config. Only used if `ssl_enabled` is true.
     #[serde(default = "HttpApi::default_tsl_config")]
@@ -25,10 +44,57 @@ pub struct HttpApi {
 
     /// Access tokens for the HTTP API. The key is a label identifying the
     /// token and the value is the token itself. The token is used to
-    /// authenticate the user. All tokens are valid for all endpoints and have
-    /// all permissions.
+    /// authenticate the user. The endpoints each token can be used for depend
+    /// on its role in `access_token_roles`.
     #[serde(default = "HttpApi::default_access_tokens")]
     pub access_tokens: AccessTokens,
+
+    /// Roles of the access tokens, by token label. The tokens without a role
+    /// have the `admin` role, which allows all the endpoints.
+    ///
+    /// ```toml
+    /// [http_api.access_tokens]
+    /// admin = "MyAccessToken"
+    /// dashboard = "MyDashboardToken"
+    ///
+    /// [http_api.access_token_roles]
+    /// dashboard = "stats"
+    /// ```
+    #[serde(default = "HttpApi::default_access_token_roles")]
+    pub access_token_roles: AccessTokenRoles,
+
+    /// Number of seconds the previous token is still accepted after an
+    /// access token is rotated with the `POST /api/v1/token/rotate`
+    /// endpoint, so the clients using it can be updated.
+    #[serde(default = "HttpApi::default_access_token_rotation_overlap")]
+    pub access_token_rotation_overlap: u64,
+
+    /// Authentication with JSON Web Tokens, in addition to the access tokens.
+    /// Disabled if not set.
+    #[serde(default = "HttpApi::default_jwt")]
+    pub jwt: Option<Jwt>,
+
+    /// Maximum number of seconds the API waits, when it's shut down, for the
+    /// requests in process to be handled and their responses sent. The API
+    /// stops accepting new connections as soon as it's shut down.
+    #[serde(default = "HttpApi::default_shutdown_timeout")]
+    pub shutdown_timeout: u64,
+
+    /// Access log of the requests. Disabled if not set.
+    #[serde(default = "HttpApi::default_access_log")]
+    pub access_log: Option<AccessLog>,
+
+    /// When `true` the IP addresses of the peers are replaced with the
+    /// unspecified address (`0.0.0.0` or `::`) in the API responses. Only the
+    /// ports are returned. It allows moderating the swarms without exposing
+    /// the IPs of the users.
+    #[serde(default = "HttpApi::default_redact_peer_ips")]
+    pub redact_peer_ips: bool,
+
+    /// Rate limit and brute-force lockout of the clients of the API.
+    /// Unlimited if not set.
+    #[serde(default = "HttpApi::default_rate_limit")]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl Default for HttpApi {
@@ -37,13 +103,20 @@ impl Default for HttpApi {
             bind_address: Self::default_bind_address(),
             tsl_config: Self::default_tsl_config(),
             access_tokens: Self::default_access_tokens(),
+            access_token_roles: Self::default_access_token_roles(),
+            access_token_rotation_overlap: Self::default_access_token_rotation_overlap(),
+            jwt: Self::default_jwt(),
+            shutdown_timeout: Self::default_shutdown_timeout(),
+            access_log: Self::default_access_log(),
+            redact_peer_ips: Self::default_redact_peer_ips(),
+            rate_limit: Self::default_rate_limit(),
         }
     }
 }
 
 impl HttpApi {
-    fn default_bind_address() -> SocketAddr {
-        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 1212)
+    fn default_bind_address() -> BindAddress {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 1212).into()
     }
 
     #[allow(clippy::unnecessary_wraps)]
@@ -55,6 +128,34 @@ impl HttpApi {
         [].iter().cloned().collect()
     }
 
+    fn default_access_token_roles() -> AccessTokenRoles {
+        AccessTokenRoles::new()
+    }
+
+    fn default_access_token_rotation_overlap() -> u64 {
+        3600
+    }
+
+    fn default_jwt() -> Option<Jwt> {
+        None
+    }
+
+    fn default_shutdown_timeout() -> u64 {
+        10
+    }
+
+    fn default_access_log() -> Option<AccessLog> {
+        None
+    }
+
+    fn default_redact_peer_ips() -> bool {
+        false
+    }
+
+    fn default_rate_limit() -> Option<RateLimit> {
+        None
+    }
+
     pub fn add_token(&mut self, key: &str, token: &str) {
         self.access_tokens.insert(key.to_string(), token.to_string());
     }
@@ -63,6 +164,67 @@ impl HttpApi {
         for token in self.access_tokens.values_mut() {
             *token = "***".to_string();
         }
+
+        if let Some(jwt) = &mut self.jwt {
+            jwt.mask_secrets();
+        }
+    }
+}
+
+/// Rate limit and brute-force lockout of the clients of the API, by client IP
+/// address.
+///
+/// The requests over the limit, and all the requests from a client locked
+/// out after too many attempts with an invalid token, are rejected with a
+/// `429 Too Many Requests` response.
+///
+/// ```toml
+/// [http_api.rate_limit]
+/// requests_per_minute_per_ip = 120
+/// max_failed_auth_attempts = 10
+/// lockout_duration = 900
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct RateLimit {
+    /// Maximum number of requests per minute from the same client IP
+    /// address. Unlimited if not set.
+    #[serde(default = "RateLimit::default_requests_per_minute_per_ip")]
+    pub requests_per_minute_per_ip: Option<u32>,
+
+    /// Number of consecutive attempts with an invalid access token, or JWT,
+    /// after which the client IP address is locked out. Clients are never
+    /// locked out if not set.
+    #[serde(default = "RateLimit::default_max_failed_auth_attempts")]
+    pub max_failed_auth_attempts: Option<u32>,
+
+    /// Number of seconds a client IP address stays locked out. The failed
+    /// attempts older than this are forgotten too.
+    #[serde(default = "RateLimit::default_lockout_duration")]
+    pub lockout_duration: u64,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            requests_per_minute_per_ip: Self::default_requests_per_minute_per_ip(),
+            max_failed_auth_attempts: Self::default_max_failed_auth_attempts(),
+            lockout_duration: Self::default_lockout_duration(),
+        }
+    }
+}
+
+impl RateLimit {
+    fn default_requests_per_minute_per_ip() -> Option<u32> {
+        None
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn default_max_failed_auth_attempts() -> Option<u32> {
+        Some(10)
+    }
+
+    fn default_lockout_duration() -> u64 {
+        900
     }
 }
 
@@ -1,26 +1,359 @@
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{BindAddress, ConnectionIdScheme, TORRENT_PEERS_LIMIT};
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct UdpTracker {
     /// The address the tracker will bind to.
-    /// The format is `ip:port`, for example `0.0.0.0:6969`. If you want to
+    /// The format is `ip:port`, for example `0.0.0.0:6969`. The host can also
+    /// be a hostname, resolved when the service starts. If you want to
     /// listen to all interfaces, use `0.0.0.0`. If you want the operating
     /// system to choose a random port, use port `0`.
+    ///
+    /// Trackers bound to an IPv6 address are dual-stack. For example, a
+    /// tracker bound to `[::]:6969` serves both IPv4 and IPv6 clients.
+    ///
+    /// The `[[udp_trackers]]` entries also accept a list of addresses, for
+    /// example, `["[::]:6969", "0.0.0.0:1337"]`. The entry is expanded into
+    /// one tracker per address, all with the same options. Like any other
+    /// entry, they share the swarms and the statistics. Notice a dual-stack
+    /// address can't be combined with an IPv4 address on the same port.
     #[serde(default = "UdpTracker::default_bind_address")]
-    pub bind_address: SocketAddr,
+    pub bind_address: BindAddress,
+
+    /// Maximum number of packets processed concurrently by this tracker. When
+    /// the limit is reached, the tracker stops reading from the socket until
+    /// one of the packets in process is handled. Unlimited if not set.
+    #[serde(default = "UdpTracker::default_max_inflight_packets")]
+    pub max_inflight_packets: Option<u32>,
+
+    /// Number of tasks receiving packets from the socket concurrently.
+    #[serde(default = "UdpTracker::default_workers")]
+    pub workers: u32,
+
+    /// If enabled, each worker receives from its own socket. All the sockets
+    /// are bound to the same address with `SO_REUSEPORT`, and the kernel
+    /// load-balances the packets across them. Otherwise, all the workers
+    /// share a single socket. Only supported on Unix systems.
+    #[serde(default = "UdpTracker::default_reuse_port")]
+    pub reuse_port: bool,
+
+    /// Per-IP rate limit for the packets received by this tracker. Unlimited
+    /// if not set.
+    #[serde(default = "UdpTracker::default_rate_limit")]
+    pub rate_limit: Option<RateLimit>,
+
+    /// Backend used to receive and send the packets. The `io_uring` backend
+    /// is only available on Linux, in builds with the `io-uring` cargo
+    /// feature. Otherwise, or if the kernel does not support it, the tracker
+    /// falls back to the `tokio` backend.
+    #[serde(default = "UdpTracker::default_udp_io_backend")]
+    pub udp_io_backend: IoBackend,
+
+    /// Maximum number of seconds the tracker waits, when it's shut down, for
+    /// the packets in process to be handled and their responses sent. The
+    /// tracker stops receiving new packets as soon as it's shut down.
+    #[serde(default = "UdpTracker::default_shutdown_timeout")]
+    pub shutdown_timeout: u64,
+
+    /// Number of peers returned in the announce responses when the client
+    /// does not set the `num_want` field (or sets it to `-1`).
+    #[serde(default = "UdpTracker::default_default_peers_in_response")]
+    pub default_peers_in_response: u32,
+
+    /// Maximum number of peers returned in the announce responses. The
+    /// `num_want` field of the announce requests is clamped to this value.
+    #[serde(default = "UdpTracker::default_max_peers_in_response")]
+    pub max_peers_in_response: u32,
+
+    /// If enabled, the malformed and rejected packets are logged with a hex
+    /// dump of the payload, the source address and the rejection reason.
+    /// It's meant to debug clients failing to announce, not to be enabled
+    /// permanently: the packets may contain the keys of private trackers.
+    #[serde(default = "UdpTracker::default_debug_capture")]
+    pub debug_capture: bool,
+
+    /// Minimum port of the peers. Announces with a lower port are rejected:
+    /// `1` rejects the port `0`, and `1024` also rejects the privileged
+    /// ports. All the ports are accepted with `0`.
+    #[serde(default = "UdpTracker::default_min_peer_port")]
+    pub min_peer_port: u16,
+
+    /// Construction used to generate the connection IDs of this tracker. It
+    /// overrides the `udp_connection_id_scheme` of the `[core]` section.
+    /// Refer to [`ConnectionIdScheme`] for the available options.
+    #[serde(default = "UdpTracker::default_connection_id_scheme")]
+    pub connection_id_scheme: Option<ConnectionIdScheme>,
+
+    /// Load shedding of the `scrape` packets when the tracker is loaded.
+    /// Disabled if not set.
+    #[serde(default = "UdpTracker::default_load_shedding")]
+    pub load_shedding: Option<LoadShedding>,
+
+    /// If enabled, the announce responses include the client's address as
+    /// observed by the tracker, so clients behind a NAT can detect their
+    /// public IP, like the `external ip` key of the HTTP tracker
+    /// ([BEP 24](https://www.bittorrent.org/beps/bep_0024.html)).
+    ///
+    /// The address is returned as the first entry of the peer list, with the
+    /// IP the packet came from and the port announced by the client. Notice
+    /// it changes the layout of the responses: clients not aware of the
+    /// extension see themselves as a peer.
+    #[serde(default = "UdpTracker::default_return_client_ip")]
+    pub return_client_ip: bool,
 }
 impl Default for UdpTracker {
     fn default() -> Self {
         Self {
             bind_address: Self::default_bind_address(),
+            max_inflight_packets: Self::default_max_inflight_packets(),
+            workers: Self::default_workers(),
+            reuse_port: Self::default_reuse_port(),
+            rate_limit: Self::default_rate_limit(),
+            udp_io_backend: Self::default_udp_io_backend(),
+            shutdown_timeout: Self::default_shutdown_timeout(),
+            default_peers_in_response: Self::default_default_peers_in_response(),
+            max_peers_in_response: Self::default_max_peers_in_response(),
+            debug_capture: Self::default_debug_capture(),
+            min_peer_port: Self::default_min_peer_port(),
+            connection_id_scheme: Self::default_connection_id_scheme(),
+            load_shedding: Self::default_load_shedding(),
+            return_client_ip: Self::default_return_client_ip(),
         }
     }
 }
 
 impl UdpTracker {
-    fn default_bind_address() -> SocketAddr {
-        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 6969)
+    fn default_bind_address() -> BindAddress {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 6969).into()
+    }
+
+    fn default_max_inflight_packets() -> Option<u32> {
+        None
+    }
+
+    fn default_workers() -> u32 {
+        1
+    }
+
+    fn default_reuse_port() -> bool {
+        false
+    }
+
+    fn default_rate_limit() -> Option<RateLimit> {
+        None
+    }
+
+    fn default_udp_io_backend() -> IoBackend {
+        IoBackend::default()
+    }
+
+    fn default_shutdown_timeout() -> u64 {
+        10
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn default_default_peers_in_response() -> u32 {
+        TORRENT_PEERS_LIMIT as u32
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn default_max_peers_in_response() -> u32 {
+        TORRENT_PEERS_LIMIT as u32
+    }
+
+    fn default_debug_capture() -> bool {
+        false
+    }
+
+    fn default_min_peer_port() -> u16 {
+        0
+    }
+
+    fn default_connection_id_scheme() -> Option<ConnectionIdScheme> {
+        None
+    }
+
+    fn default_load_shedding() -> Option<LoadShedding> {
+        None
     }
+
+    fn default_return_client_ip() -> bool {
+        false
+    }
+}
+
+/// Bind address of an entry of the `[[udp_trackers]]` section: one address
+/// or a list of addresses.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BindAddresses {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Entry of the `[[udp_trackers]]` section, before expanding its bind
+/// addresses.
+#[derive(Deserialize)]
+struct Entry {
+    bind_address: Option<BindAddresses>,
+    #[serde(flatten)]
+    options: UdpTracker,
+}
+
+/// It deserializes the `[[udp_trackers]]` section. The entries with a list of
+/// bind addresses are expanded into one tracker per address.
+///
+/// # Errors
+///
+/// Will return an error if the list of bind addresses is empty, or if any of
+/// the addresses is not valid.
+pub fn deserialize_trackers<'de, D>(deserializer: D) -> Result<Option<Vec<UdpTracker>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(entries) = Option::<Vec<Entry>>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+
+    let mut udp_trackers = vec![];
+
+    for entry in entries {
+        let addresses = match entry.bind_address {
+            None => {
+                udp_trackers.push(entry.options);
+                continue;
+            }
+            Some(BindAddresses::One(address)) => vec![address],
+            Some(BindAddresses::Many(addresses)) => addresses,
+        };
+
+        if addresses.is_empty() {
+            return Err(serde::de::Error::custom(
+                "the list of bind addresses of a UDP tracker is empty",
+            ));
+        }
+
+        for address in addresses {
+            let mut udp_tracker = entry.options.clone();
+            udp_tracker.bind_address = address.parse().map_err(serde::de::Error::custom)?;
+            udp_trackers.push(udp_tracker);
+        }
+    }
+
+    Ok(Some(udp_trackers))
+}
+
+/// Backend for the UDP socket I/O.
+///
+/// ```toml
+/// [[udp_trackers]]
+/// bind_address = "0.0.0.0:6969"
+/// udp_io_backend = "io_uring"
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IoBackend {
+    /// Tokio sockets, with batched system calls on Linux.
+    #[default]
+    Tokio,
+    /// Linux `io_uring`.
+    IoUring,
+}
+
+/// Token bucket rate limit for the packets received from each IP address.
+///
+/// The bucket of each IP address is refilled at `packets_per_second` tokens
+/// per second and holds up to `burst` tokens. Each packet takes one token.
+/// Packets received when the bucket is empty are dropped or answered with an
+/// error response, depending on the `action`.
+///
+/// ```toml
+/// [[udp_trackers]]
+/// bind_address = "0.0.0.0:6969"
+///
+/// [udp_trackers.rate_limit]
+/// packets_per_second = 10
+/// burst = 50
+/// action = "drop"
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct RateLimit {
+    /// Packets per second accepted from each IP address.
+    pub packets_per_second: u32,
+
+    /// Maximum number of packets accepted in a burst from each IP address.
+    /// It defaults to `packets_per_second`.
+    #[serde(default = "RateLimit::default_burst")]
+    pub burst: Option<u32>,
+
+    /// What to do with the packets exceeding the limit.
+    #[serde(default = "RateLimit::default_action")]
+    pub action: RateLimitAction,
+}
+
+impl RateLimit {
+    fn default_burst() -> Option<u32> {
+        None
+    }
+
+    fn default_action() -> RateLimitAction {
+        RateLimitAction::default()
+    }
+}
+
+/// Load shedding of the `scrape` packets.
+///
+/// While the tracker is loaded, the `scrape` packets are dropped as soon as
+/// they are received, before parsing them, so the `connect` and `announce`
+/// requests keep being answered in time during floods. The tracker is loaded
+/// when:
+///
+/// - The packets in process exceed `max_queue_depth`.
+/// - Or, with `shed_when_overloaded`, the [overload control](crate::v2_0_0::overload::Overload)
+///   considers the tracker overloaded. For example, because the lag of the
+///   event loop shows the CPU budget is exhausted.
+///
+/// ```toml
+/// [[udp_trackers]]
+/// bind_address = "0.0.0.0:6969"
+///
+/// [udp_trackers.load_shedding]
+/// max_queue_depth = 1000
+/// shed_when_overloaded = true
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct LoadShedding {
+    /// Number of packets in process above which the `scrape` packets are
+    /// dropped. It's not checked if it's not set.
+    #[serde(default = "LoadShedding::default_max_queue_depth")]
+    pub max_queue_depth: Option<u32>,
+
+    /// Whether the `scrape` packets are dropped while the tracker is
+    /// overloaded.
+    #[serde(default = "LoadShedding::default_shed_when_overloaded")]
+    pub shed_when_overloaded: bool,
+}
+
+impl LoadShedding {
+    fn default_max_queue_depth() -> Option<u32> {
+        None
+    }
+
+    fn default_shed_when_overloaded() -> bool {
+        true
+    }
+}
+
+/// What to do with the packets exceeding the rate limit.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitAction {
+    /// The packets are silently dropped.
+    #[default]
+    Drop,
+    /// The packets are answered with an error response.
+    Error,
 }
@@ -0,0 +1,71 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::{BindAddress, TslConfig};
+
+/// Configuration for each WebSocket tracker.
+///
+/// WebSocket trackers implement the tracker protocol used by the `WebTorrent`
+/// clients running in web browsers. The peers connect to each other with
+/// `WebRTC`, and the tracker relays the offers and answers needed to set up
+/// the connections.
+///
+/// ```toml
+/// [[websocket_trackers]]
+/// bind_address = "0.0.0.0:8000"
+/// max_offers = 10
+///
+/// [websocket_trackers.tsl_config]
+/// ssl_cert_path = "./storage/tracker/lib/tls/localhost.crt"
+/// ssl_key_path = "./storage/tracker/lib/tls/localhost.key"
+/// ```
+///
+/// Web browsers only allow secure WebSocket (`wss://`) connections from pages
+/// served over HTTPS, so the TSL config is usually required, unless the
+/// tracker runs behind a reverse proxy terminating the TLS connections.
+#[serde_as]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct WebSocketTracker {
+    /// The address the tracker will bind to.
+    /// The format is `ip:port`, for example `0.0.0.0:8000`. The host can also
+    /// be a hostname, resolved when the service starts. If you want to
+    /// listen to all interfaces, use `0.0.0.0`. If you want the operating
+    /// system to choose a random port, use port `0`.
+    #[serde(default = "WebSocketTracker::default_bind_address")]
+    pub bind_address: BindAddress,
+
+    /// TSL config.
+    #[serde(default = "WebSocketTracker::default_tsl_config")]
+    pub tsl_config: Option<TslConfig>,
+
+    /// Maximum number of `WebRTC` offers relayed for each announce request.
+    /// Clients usually send one offer for each peer they want to connect to.
+    #[serde(default = "WebSocketTracker::default_max_offers")]
+    pub max_offers: u32,
+}
+
+impl Default for WebSocketTracker {
+    fn default() -> Self {
+        Self {
+            bind_address: Self::default_bind_address(),
+            tsl_config: Self::default_tsl_config(),
+            max_offers: Self::default_max_offers(),
+        }
+    }
+}
+
+impl WebSocketTracker {
+    fn default_bind_address() -> BindAddress {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 8000).into()
+    }
+
+    fn default_tsl_config() -> Option<TslConfig> {
+        None
+    }
+
+    fn default_max_offers() -> u32 {
+        10
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// Rules to allow torrents in `listed` mode, besides the infohashes added
+/// one by one to the whitelist.
+///
+/// Each prefix is a namespace: all the torrents whose infohash (in
+/// hexadecimal) starts with it are allowed. An index registering thousands of
+/// torrents per day can allow its whole namespace instead of adding every
+/// infohash to the whitelist.
+///
+/// There are no rules by default.
+///
+/// ```toml
+/// [core.whitelist]
+/// prefixes = ["c0ffee", "f00d"]
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Whitelist {
+    /// Hexadecimal prefixes of the allowed infohashes. Each prefix must have
+    /// between 1 and 40 hexadecimal characters.
+    #[serde(default = "Whitelist::default_prefixes")]
+    pub prefixes: Vec<String>,
+}
+
+impl Default for Whitelist {
+    fn default() -> Self {
+        Self {
+            prefixes: Self::default_prefixes(),
+        }
+    }
+}
+
+impl Whitelist {
+    fn default_prefixes() -> Vec<String> {
+        vec![]
+    }
+
+    /// Returns `true` if all the prefixes have between 1 and 40 hexadecimal
+    /// characters.
+    #[must_use]
+    pub fn has_valid_prefixes(&self) -> bool {
+        self.prefixes
+            .iter()
+            .all(|prefix| (1..=40).contains(&prefix.len()) && prefix.chars().all(|c| c.is_ascii_hexdigit()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Whitelist;
+
+    fn whitelist(prefixes: &[&str]) -> Whitelist {
+        Whitelist {
+            prefixes: prefixes.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn it_should_only_accept_prefixes_with_up_to_40_hexadecimal_characters() {
+        assert!(whitelist(&["c0ffee", "F00D"]).has_valid_prefixes());
+
+        assert!(!whitelist(&[""]).has_valid_prefixes());
+        assert!(!whitelist(&["not-hex"]).has_valid_prefixes());
+        assert!(!whitelist(&[&"a".repeat(41)]).has_valid_prefixes());
+    }
+}
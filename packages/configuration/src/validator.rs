@@ -9,6 +9,84 @@ use thiserror::Error;
 pub enum SemanticValidationError {
     #[error("Private mode section in configuration can only be included when the tracker is running in private mode.")]
     UselessPrivateModeSection,
+
+    #[error("Maintenance windows must end after they start.")]
+    InvalidMaintenanceWindow,
+
+    #[error("The announce budget saturation threshold must be a percentage between 0 and 100.")]
+    InvalidAnnounceBudgetSaturationThreshold,
+
+    #[error("The announce interval jitter must be a percentage between 0 and 100.")]
+    InvalidAnnounceIntervalJitter,
+
+    #[error(
+        "The dynamic announce interval references must be greater than 0, and the minimum interval must not be greater than the maximum interval."
+    )]
+    InvalidDynamicIntervalSettings,
+
+    #[error(
+        "The overload recovery threshold must be a percentage between 0 and 100, and the sample interval must be greater than 0."
+    )]
+    InvalidOverloadSettings,
+
+    #[error("The database connection pool must have at least one connection.")]
+    InvalidDatabasePoolSize,
+
+    #[error("Only the `sqlite3` database can be encrypted.")]
+    UnsupportedDatabaseEncryption,
+
+    #[error("The database table prefix must have at most 48 ASCII letters, digits and underscores.")]
+    InvalidDatabaseTablePrefix,
+
+    #[error(
+        "The experiment percentage must be between 0 and 100, and its minimum announce interval must not be greater than its announce interval."
+    )]
+    InvalidExperimentSettings,
+
+    #[error("The key synchronization interval must be greater than 0.")]
+    InvalidKeySyncInterval,
+
+    #[error("The number of confirmations to link the infohashes of hybrid torrents must be greater than 0.")]
+    InvalidTorrentAliasesConfirmations,
+
+    #[error("The peer diversity subnet prefix lengths must be at most 32 (IPv4) and 128 (IPv6).")]
+    InvalidPeerDiversitySubnetPrefixLength,
+
+    #[error("The UDP connection ID secret must be 64 hexadecimal characters.")]
+    InvalidUdpConnectionIdSecret,
+
+    #[error("The UDP connection ID secret rotation interval must be greater than 0.")]
+    InvalidUdpConnectionIdSecretRotationInterval,
+
+    #[error("The client whitelist peer ID prefixes must have between 1 and 20 ASCII characters.")]
+    InvalidClientWhitelistPrefix,
+
+    #[error("The whitelist prefixes must have between 1 and 40 hexadecimal characters.")]
+    InvalidWhitelistPrefix,
+
+    #[error("The runtime must have at least one worker thread and one blocking thread.")]
+    InvalidRuntimeThreads,
+
+    #[error("UDP trackers must have at least one worker.")]
+    InvalidUdpTrackerWorkers,
+
+    #[error("UDP tracker rate limits must accept at least one packet per second and one packet in a burst.")]
+    InvalidUdpTrackerRateLimit,
+
+    #[error("UDP trackers must return at least one peer, and the default number of peers can't exceed the maximum.")]
+    InvalidUdpTrackerPeersInResponse,
+
+    #[error("The API JWT authentication must have a secret or a JWKS URL.")]
+    MissingApiJwtKeys,
+
+    #[error("The alerts check interval must be greater than 0.")]
+    InvalidAlertsCheckInterval,
+
+    #[error("The alert emails must have at least one recipient.")]
+    MissingAlertsEmailRecipients,
+
+    #[error("Matrix alert webhooks must have an access token.")]
+    MissingAlertsMatrixToken,
 }
 
 pub trait Validator {
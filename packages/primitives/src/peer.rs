@@ -8,6 +8,7 @@
 //! use std::net::SocketAddr;
 //! use std::net::IpAddr;
 //! use std::net::Ipv4Addr;
+//! use std::time::Duration;
 //! use torrust_tracker_primitives::DurationSinceUnixEpoch;
 //!
 //!
@@ -19,12 +20,15 @@
 //!     downloaded: NumberOfBytes::new(0),
 //!     left: NumberOfBytes::new(0),
 //!     event: AnnounceEvent::Started,
+//!     timeout_extension: Duration::ZERO,
+//!     alt_peer_addr: None,
 //! };
 //! ```
 
 use std::net::{IpAddr, SocketAddr};
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
+use std::time::Duration;
 
 use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
 use serde::Serialize;
@@ -42,6 +46,7 @@ use crate::DurationSinceUnixEpoch;
 /// use std::net::SocketAddr;
 /// use std::net::IpAddr;
 /// use std::net::Ipv4Addr;
+/// use std::time::Duration;
 /// use torrust_tracker_primitives::DurationSinceUnixEpoch;
 ///
 ///
@@ -53,6 +58,8 @@ use crate::DurationSinceUnixEpoch;
 ///     downloaded: NumberOfBytes::new(0),
 ///     left: NumberOfBytes::new(0),
 ///     event: AnnounceEvent::Started,
+///     timeout_extension: Duration::ZERO,
+///     alt_peer_addr: None,
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Copy, PartialEq, Eq, Hash)]
@@ -77,6 +84,15 @@ pub struct Peer {
     /// This is an optional key which maps to started, completed, or stopped (or empty, which is the same as not being present).
     #[serde(serialize_with = "ser_announce_event")]
     pub event: AnnounceEvent,
+    /// Extra time the tracker keeps this peer after the `max_peer_timeout`,
+    /// because the announce interval handed to it is longer
+    #[serde(skip)]
+    pub timeout_extension: Duration,
+    /// The IP and port this peer is listening on in the other IP family, if
+    /// it announced one. For example, the `IPv6` endpoint of a dual-stack peer
+    /// announcing over `IPv4`. See [BEP 07](https://www.bittorrent.org/beps/bep_0007.html).
+    #[serde(skip)]
+    pub alt_peer_addr: Option<SocketAddr>,
 }
 
 /// Serializes a `DurationSinceUnixEpoch` as a Unix timestamp in milliseconds.
@@ -208,6 +224,22 @@ impl Peer {
     pub fn change_ip(&mut self, new_ip: &IpAddr) {
         self.peer_addr = SocketAddr::new(*new_ip, self.peer_addr.port());
     }
+
+    /// The `IPv4` endpoint of the peer, if any.
+    #[must_use]
+    pub fn ipv4_addr(&self) -> Option<SocketAddr> {
+        self.endpoints().find(SocketAddr::is_ipv4)
+    }
+
+    /// The `IPv6` endpoint of the peer, if any.
+    #[must_use]
+    pub fn ipv6_addr(&self) -> Option<SocketAddr> {
+        self.endpoints().find(SocketAddr::is_ipv6)
+    }
+
+    fn endpoints(&self) -> impl Iterator<Item = SocketAddr> {
+        std::iter::once(self.peer_addr).chain(self.alt_peer_addr)
+    }
 }
 
 use std::panic::Location;
@@ -389,6 +421,7 @@ impl<P: Encoding> FromIterator<Peer> for Vec<P> {
 
 pub mod fixture {
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::time::Duration;
 
     use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes};
 
@@ -420,6 +453,8 @@ pub mod fixture {
                 downloaded: NumberOfBytes::new(0),
                 left: NumberOfBytes::new(0),
                 event: AnnounceEvent::Completed,
+                timeout_extension: Duration::ZERO,
+                alt_peer_addr: None,
             };
 
             Self { peer }
@@ -436,6 +471,8 @@ pub mod fixture {
                 downloaded: NumberOfBytes::new(0),
                 left: NumberOfBytes::new(10),
                 event: AnnounceEvent::Started,
+                timeout_extension: Duration::ZERO,
+                alt_peer_addr: None,
             };
 
             Self { peer }
@@ -455,6 +492,13 @@ pub mod fixture {
             self
         }
 
+        #[allow(dead_code)]
+        #[must_use]
+        pub fn with_alt_peer_addr(mut self, alt_peer_addr: &SocketAddr) -> Self {
+            self.peer.alt_peer_addr = Some(*alt_peer_addr);
+            self
+        }
+
         #[allow(dead_code)]
         #[must_use]
         pub fn with_bytes_pending_to_download(mut self, left: i64) -> Self {
@@ -476,6 +520,13 @@ pub mod fixture {
             self
         }
 
+        #[allow(dead_code)]
+        #[must_use]
+        pub fn with_timeout_extension(mut self, timeout_extension: Duration) -> Self {
+            self.peer.timeout_extension = timeout_extension;
+            self
+        }
+
         #[allow(dead_code)]
         #[must_use]
         pub fn build(self) -> Peer {
@@ -499,6 +550,8 @@ pub mod fixture {
                 downloaded: NumberOfBytes::new(0),
                 left: NumberOfBytes::new(0),
                 event: AnnounceEvent::Started,
+                timeout_extension: Duration::ZERO,
+                alt_peer_addr: None,
             }
         }
     }
@@ -560,4 +613,31 @@ pub mod test {
             assert_eq!(id.to_string(), "0x009f9296009f9296009f9296009f9296009f9296");
         }
     }
+
+    mod torrent_peer {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+        use crate::peer::fixture::PeerBuilder;
+
+        #[test]
+        fn should_return_the_endpoint_of_each_ip_family_of_a_dual_stack_peer() {
+            let ipv4_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)), 8080);
+            let ipv6_addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)), 8080);
+
+            let peer = PeerBuilder::default()
+                .with_peer_addr(&ipv6_addr)
+                .with_alt_peer_addr(&ipv4_addr)
+                .build();
+
+            assert_eq!(peer.ipv4_addr(), Some(ipv4_addr));
+            assert_eq!(peer.ipv6_addr(), Some(ipv6_addr));
+        }
+
+        #[test]
+        fn should_not_return_an_endpoint_for_an_ip_family_the_peer_does_not_use() {
+            let peer = PeerBuilder::default().build();
+
+            assert_eq!(peer.ipv6_addr(), None);
+        }
+    }
 }
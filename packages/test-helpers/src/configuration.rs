@@ -2,7 +2,7 @@
 use std::env;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
-use torrust_tracker_configuration::{Configuration, HttpApi, HttpTracker, Threshold, UdpTracker};
+use torrust_tracker_configuration::{Configuration, Driver, HttpApi, HttpTracker, Threshold, UdpIoBackend, UdpTracker};
 
 use crate::random;
 
@@ -33,7 +33,7 @@ pub fn ephemeral() -> Configuration {
     // Ephemeral socket address for API
     let api_port = 0u16;
     let mut http_api = HttpApi {
-        bind_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), api_port),
+        bind_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), api_port).into(),
         ..Default::default()
     };
     http_api.add_token("admin", "MyAccessToken");
@@ -41,19 +41,43 @@ pub fn ephemeral() -> Configuration {
 
     // Ephemeral socket address for Health Check API
     let health_check_api_port = 0u16;
-    config.health_check_api.bind_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), health_check_api_port);
+    config.health_check_api.bind_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), health_check_api_port).into();
 
     // Ephemeral socket address for UDP tracker
     let udp_port = 0u16;
     config.udp_trackers = Some(vec![UdpTracker {
-        bind_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), udp_port),
+        bind_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), udp_port).into(),
+        max_inflight_packets: None,
+        workers: 1,
+        reuse_port: false,
+        rate_limit: None,
+        udp_io_backend: UdpIoBackend::Tokio,
+        shutdown_timeout: 10,
+        default_peers_in_response: 74,
+        max_peers_in_response: 74,
+        debug_capture: false,
+        min_peer_port: 0,
+        connection_id_scheme: None,
+        load_shedding: None,
+        return_client_ip: false,
     }]);
 
     // Ephemeral socket address for HTTP tracker
     let http_port = 0u16;
     config.http_trackers = Some(vec![HttpTracker {
-        bind_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), http_port),
+        bind_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), http_port).into(),
         tsl_config: None,
+        acme: None,
+        keep_alive_timeout: 5,
+        max_connections: None,
+        shutdown_timeout: 10,
+        max_concurrent_requests: None,
+        min_peer_port: 0,
+        return_client_ip: None,
+        max_scrape_torrents: 74,
+        scrape_in_private_mode: true,
+        rate_limit: None,
+        access_log: None,
     }]);
 
     // Ephemeral sqlite database
@@ -145,20 +169,31 @@ pub fn ephemeral_ipv6() -> Configuration {
     let ipv6 = SocketAddr::new(IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0)), 0);
 
     if let Some(ref mut http_api) = cfg.http_api {
-        http_api.bind_address.clone_from(&ipv6);
+        http_api.bind_address = ipv6.into();
     };
 
     if let Some(ref mut http_trackers) = cfg.http_trackers {
-        http_trackers[0].bind_address.clone_from(&ipv6);
+        http_trackers[0].bind_address = ipv6.into();
     }
 
     if let Some(ref mut udp_trackers) = cfg.udp_trackers {
-        udp_trackers[0].bind_address.clone_from(&ipv6);
+        udp_trackers[0].bind_address = ipv6.into();
     }
 
     cfg
 }
 
+/// Ephemeral configuration with the in-memory database, which does not create
+/// any file.
+#[must_use]
+pub fn ephemeral_with_memory_database() -> Configuration {
+    let mut cfg = ephemeral();
+
+    cfg.core.database.driver = Driver::Memory;
+
+    cfg
+}
+
 /// Ephemeral without running any services.
 #[must_use]
 pub fn ephemeral_with_no_services() -> Configuration {
@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
 
 use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
 use torrust_tracker_primitives::info_hash::InfoHash;
@@ -15,6 +16,8 @@ pub const DEFAULT_PEER: Peer = Peer {
     downloaded: NumberOfBytes(I64::ZERO),
     left: NumberOfBytes(I64::ZERO),
     event: AnnounceEvent::Started,
+    timeout_extension: Duration::ZERO,
+    alt_peer_addr: None,
 };
 
 #[must_use]
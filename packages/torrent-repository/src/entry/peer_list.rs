@@ -34,9 +34,12 @@ impl PeerList {
         self.peers.remove(key)
     }
 
+    /// It removes the peers not updated since the `current_cutoff`. Peers
+    /// with a [`timeout_extension`](peer::Peer::timeout_extension) are kept
+    /// for that extra time.
     pub fn remove_inactive_peers(&mut self, current_cutoff: DurationSinceUnixEpoch) {
         self.peers
-            .retain(|_, peer| peer::ReadInfo::get_updated(peer) > current_cutoff);
+            .retain(|_, peer| peer::ReadInfo::get_updated(peer) + peer.timeout_extension > current_cutoff);
     }
 
     #[must_use]
@@ -270,6 +273,25 @@ mod tests {
             assert_eq!(peer_list.len(), 1);
         }
 
+        #[test]
+        fn not_remove_inactive_peers_within_their_timeout_extension() {
+            let mut peer_list = PeerList::default();
+            let one_second = DurationSinceUnixEpoch::new(1, 0);
+
+            // Insert the peer
+            let last_update_time = DurationSinceUnixEpoch::new(1_669_397_478_934, 0);
+            let peer = PeerBuilder::default()
+                .last_updated_on(last_update_time)
+                .with_timeout_extension(one_second * 2)
+                .build();
+            peer_list.upsert(peer.into());
+
+            // Remove peers not updated since one second after inserting the peer
+            peer_list.remove_inactive_peers(last_update_time + one_second);
+
+            assert_eq!(peer_list.len(), 1);
+        }
+
         #[test]
         fn allow_inserting_two_identical_peers_except_for_the_id() {
             let mut peer_list = PeerList::default();
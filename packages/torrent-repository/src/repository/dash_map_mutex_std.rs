@@ -1,3 +1,4 @@
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use dashmap::DashMap;
@@ -74,6 +75,14 @@ where
         }
     }
 
+    fn get_range(&self, range: RangeInclusive<InfoHash>) -> Vec<(InfoHash, EntryMutexStd)> {
+        self.torrents
+            .iter()
+            .filter(|entry| range.contains(entry.key()))
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect()
+    }
+
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         for (info_hash, completed) in persistent_torrents {
             if self.torrents.contains_key(info_hash) {
@@ -15,11 +15,13 @@ pub mod rw_lock_tokio_mutex_tokio;
 pub mod skip_map_mutex_std;
 
 use std::fmt::Debug;
+use std::ops::RangeInclusive;
 
 pub trait Repository<T>: Debug + Default + Sized + 'static {
     fn get(&self, key: &InfoHash) -> Option<T>;
     fn get_metrics(&self) -> TorrentsMetrics;
     fn get_paginated(&self, pagination: Option<&Pagination>) -> Vec<(InfoHash, T)>;
+    fn get_range(&self, range: RangeInclusive<InfoHash>) -> Vec<(InfoHash, T)>;
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents);
     fn remove(&self, key: &InfoHash) -> Option<T>;
     fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch);
@@ -33,6 +35,7 @@ pub trait RepositoryAsync<T>: Debug + Default + Sized + 'static {
     fn get(&self, key: &InfoHash) -> impl std::future::Future<Output = Option<T>> + Send;
     fn get_metrics(&self) -> impl std::future::Future<Output = TorrentsMetrics> + Send;
     fn get_paginated(&self, pagination: Option<&Pagination>) -> impl std::future::Future<Output = Vec<(InfoHash, T)>> + Send;
+    fn get_range(&self, range: RangeInclusive<InfoHash>) -> impl std::future::Future<Output = Vec<(InfoHash, T)>> + Send;
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) -> impl std::future::Future<Output = ()> + Send;
     fn remove(&self, key: &InfoHash) -> impl std::future::Future<Output = Option<T>> + Send;
     fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) -> impl std::future::Future<Output = ()> + Send;
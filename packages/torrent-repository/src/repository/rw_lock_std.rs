@@ -1,3 +1,5 @@
+use std::ops::RangeInclusive;
+
 use torrust_tracker_configuration::TrackerPolicy;
 use torrust_tracker_primitives::info_hash::InfoHash;
 use torrust_tracker_primitives::pagination::Pagination;
@@ -91,6 +93,12 @@ where
         }
     }
 
+    fn get_range(&self, range: RangeInclusive<InfoHash>) -> Vec<(InfoHash, EntrySingle)> {
+        let db = self.get_torrents();
+
+        db.range(range).map(|(a, b)| (*a, b.clone())).collect()
+    }
+
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         let mut torrents = self.get_torrents_mut();
 
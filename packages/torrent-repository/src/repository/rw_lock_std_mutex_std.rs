@@ -1,3 +1,4 @@
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use torrust_tracker_configuration::TrackerPolicy;
@@ -86,6 +87,12 @@ where
         }
     }
 
+    fn get_range(&self, range: RangeInclusive<InfoHash>) -> Vec<(InfoHash, EntryMutexStd)> {
+        let db = self.get_torrents();
+
+        db.range(range).map(|(a, b)| (*a, b.clone())).collect()
+    }
+
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         let mut torrents = self.get_torrents_mut();
 
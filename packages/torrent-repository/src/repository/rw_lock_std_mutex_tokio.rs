@@ -1,4 +1,5 @@
 use std::iter::zip;
+use std::ops::RangeInclusive;
 use std::pin::Pin;
 use std::sync::Arc;
 
@@ -79,6 +80,12 @@ where
         }
     }
 
+    async fn get_range(&self, range: RangeInclusive<InfoHash>) -> Vec<(InfoHash, EntryMutexTokio)> {
+        let db = self.get_torrents();
+
+        db.range(range).map(|(a, b)| (*a, b.clone())).collect()
+    }
+
     async fn get_metrics(&self) -> TorrentsMetrics {
         let mut metrics = TorrentsMetrics::default();
 
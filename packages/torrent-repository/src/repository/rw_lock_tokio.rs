@@ -1,3 +1,5 @@
+use std::ops::RangeInclusive;
+
 use torrust_tracker_configuration::TrackerPolicy;
 use torrust_tracker_primitives::info_hash::InfoHash;
 use torrust_tracker_primitives::pagination::Pagination;
@@ -81,6 +83,12 @@ where
         }
     }
 
+    async fn get_range(&self, range: RangeInclusive<InfoHash>) -> Vec<(InfoHash, EntrySingle)> {
+        let db = self.get_torrents().await;
+
+        db.range(range).map(|(a, b)| (*a, b.clone())).collect()
+    }
+
     async fn get_metrics(&self) -> TorrentsMetrics {
         let mut metrics = TorrentsMetrics::default();
 
@@ -1,3 +1,4 @@
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use torrust_tracker_configuration::TrackerPolicy;
@@ -75,6 +76,12 @@ where
         }
     }
 
+    async fn get_range(&self, range: RangeInclusive<InfoHash>) -> Vec<(InfoHash, EntryMutexTokio)> {
+        let db = self.get_torrents().await;
+
+        db.range(range).map(|(a, b)| (*a, b.clone())).collect()
+    }
+
     async fn get_metrics(&self) -> TorrentsMetrics {
         let mut metrics = TorrentsMetrics::default();
 
@@ -1,3 +1,4 @@
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use crossbeam_skiplist::SkipMap;
@@ -68,6 +69,13 @@ where
         }
     }
 
+    fn get_range(&self, range: RangeInclusive<InfoHash>) -> Vec<(InfoHash, EntryMutexStd)> {
+        self.torrents
+            .range(range)
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect()
+    }
+
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         for (info_hash, completed) in persistent_torrents {
             if self.torrents.contains_key(info_hash) {
@@ -159,6 +167,13 @@ where
         }
     }
 
+    fn get_range(&self, range: RangeInclusive<InfoHash>) -> Vec<(InfoHash, EntryRwLockParkingLot)> {
+        self.torrents
+            .range(range)
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect()
+    }
+
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         for (info_hash, completed) in persistent_torrents {
             if self.torrents.contains_key(info_hash) {
@@ -250,6 +265,13 @@ where
         }
     }
 
+    fn get_range(&self, range: RangeInclusive<InfoHash>) -> Vec<(InfoHash, EntryMutexParkingLot)> {
+        self.torrents
+            .range(range)
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect()
+    }
+
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         for (info_hash, completed) in persistent_torrents {
             if self.torrents.contains_key(info_hash) {
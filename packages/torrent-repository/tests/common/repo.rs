@@ -1,3 +1,5 @@
+use std::ops::RangeInclusive;
+
 use torrust_tracker_configuration::TrackerPolicy;
 use torrust_tracker_primitives::info_hash::InfoHash;
 use torrust_tracker_primitives::pagination::Pagination;
@@ -140,6 +142,52 @@ impl Repo {
         }
     }
 
+    pub(crate) async fn get_range(&self, range: RangeInclusive<InfoHash>) -> Vec<(InfoHash, EntrySingle)> {
+        match self {
+            Repo::RwLockStd(repo) => repo.get_range(range),
+            Repo::RwLockStdMutexStd(repo) => repo
+                .get_range(range)
+                .iter()
+                .map(|(i, t)| (*i, t.lock().expect("it should get a lock").clone()))
+                .collect(),
+            Repo::RwLockStdMutexTokio(repo) => {
+                let mut v: Vec<(InfoHash, EntrySingle)> = vec![];
+
+                for (i, t) in repo.get_range(range).await {
+                    v.push((i, t.lock().await.clone()));
+                }
+                v
+            }
+            Repo::RwLockTokio(repo) => repo.get_range(range).await,
+            Repo::RwLockTokioMutexStd(repo) => repo
+                .get_range(range)
+                .await
+                .iter()
+                .map(|(i, t)| (*i, t.lock().expect("it should get a lock").clone()))
+                .collect(),
+            Repo::RwLockTokioMutexTokio(repo) => {
+                let mut v: Vec<(InfoHash, EntrySingle)> = vec![];
+
+                for (i, t) in repo.get_range(range).await {
+                    v.push((i, t.lock().await.clone()));
+                }
+                v
+            }
+            Repo::SkipMapMutexStd(repo) => repo
+                .get_range(range)
+                .iter()
+                .map(|(i, t)| (*i, t.lock().expect("it should get a lock").clone()))
+                .collect(),
+            Repo::SkipMapMutexParkingLot(repo) => repo.get_range(range).iter().map(|(i, t)| (*i, t.lock().clone())).collect(),
+            Repo::SkipMapRwLockParkingLot(repo) => repo.get_range(range).iter().map(|(i, t)| (*i, t.read().clone())).collect(),
+            Repo::DashMapMutexStd(repo) => repo
+                .get_range(range)
+                .iter()
+                .map(|(i, t)| (*i, t.lock().expect("it should get a lock").clone()))
+                .collect(),
+        }
+    }
+
     pub(crate) async fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         match self {
             Repo::RwLockStd(repo) => repo.import_persistent(persistent_torrents),
@@ -376,6 +376,58 @@ async fn it_should_get_paginated(
     }
 }
 
+#[rstest]
+#[case::empty(empty())]
+#[case::default(default())]
+#[case::started(started())]
+#[case::completed(completed())]
+#[case::downloaded(downloaded())]
+#[case::three(three())]
+#[case::out_of_order(many_out_of_order())]
+#[case::in_order(many_hashed_in_order())]
+#[tokio::test]
+async fn it_should_get_the_entries_in_a_range(
+    #[values(
+        standard(),
+        standard_mutex(),
+        standard_tokio(),
+        tokio_std(),
+        tokio_mutex(),
+        tokio_tokio(),
+        skip_list_mutex_std(),
+        skip_list_mutex_parking_lot(),
+        skip_list_rw_lock_parking_lot(),
+        dash_map_std()
+    )]
+    repo: Repo,
+    #[case] entries: Entries,
+) {
+    make(&repo, &entries).await;
+
+    let mut info_hashes = repo.get_paginated(None).await.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+    info_hashes.sort();
+
+    // The range of all the entries but the first one.
+    if let (Some(start), Some(end)) = (info_hashes.get(1), info_hashes.last()) {
+        let mut range = repo
+            .get_range(*start..=*end)
+            .await
+            .iter()
+            .map(|(i, _)| *i)
+            .collect::<Vec<_>>();
+        range.sort();
+
+        assert_eq!(range, info_hashes[1..]);
+    }
+
+    assert_eq!(
+        repo.get_range(InfoHash::default()..=InfoHash::from_bytes(&[u8::MAX; 20]))
+            .await
+            .len(),
+        info_hashes.len()
+    );
+}
+
 #[rstest]
 #[case::empty(empty())]
 #[case::default(default())]
@@ -2,9 +2,7 @@
 //!
 //! The tracker application has a global configuration for multiple jobs.
 //! It's basically a container for other services.
-//! It also check constraint and dependencies between services. For example:
-//! It's not safe to run a UDP tracker on top of a core public tracker, as UDP trackers
-//! do not allow private access to the tracker data.
+//! It also check constraint and dependencies between services.
 //!
 //! The application is responsible for:
 //!
@@ -18,16 +16,26 @@
 //! Optional jobs:
 //!
 //! - Torrent cleaner: it removes inactive peers and (optionally) peerless torrents.
+//! - Completed stats flusher: it writes the buffered number of completed downloads into the database.
+//! - Torrent history flusher: it saves the torrent history of the previous hours into the database.
+//! - Key synchronization: it pulls the authentication keys from a remote source (only in private mode).
+//! - Expired keys cleaner: it removes the expired authentication keys (only in private mode).
 //! - UDP trackers: the user can enable multiple UDP tracker on several ports.
 //! - HTTP trackers: the user can enable multiple HTTP tracker on several ports.
 //! - Tracker REST API: the tracker API can be enabled/disabled.
+//! - Alerts: it notifies the administrators when something critical happens.
+//! - Load monitor: it samples the load of the tracker for the overload control.
+//! - Swarm snapshots: they save the swarms periodically, so they are restored after a restart.
 use std::sync::Arc;
 
 use tokio::task::JoinHandle;
 use torrust_tracker_configuration::Configuration;
 use tracing::instrument;
 
-use crate::bootstrap::jobs::{health_check_api, http_tracker, torrent_cleanup, tracker_apis, udp_tracker};
+use crate::bootstrap::jobs::{
+    alerts, completed_stats_flush, connection_id_secret_rotation, health_check_api, http_tracker, key_cleanup, key_sync,
+    load_monitor, swarm_snapshot, torrent_cleanup, torrent_history_flush, tracker_apis, udp_tracker, websocket_tracker,
+};
 use crate::servers::registar::Registar;
 use crate::{core, servers};
 
@@ -42,10 +50,18 @@ pub async fn start(config: &Configuration, tracker: Arc<core::Tracker>) -> Vec<J
     if config.http_api.is_none()
         && (config.udp_trackers.is_none() || config.udp_trackers.as_ref().map_or(true, std::vec::Vec::is_empty))
         && (config.http_trackers.is_none() || config.http_trackers.as_ref().map_or(true, std::vec::Vec::is_empty))
+        && (config.websocket_trackers.is_none() || config.websocket_trackers.as_ref().map_or(true, std::vec::Vec::is_empty))
     {
         tracing::warn!("No services enabled in configuration");
     }
 
+    if config.core.net.on_reverse_proxy && config.core.net.trusted_proxies.is_empty() {
+        tracing::warn!(
+            "`on_reverse_proxy` is deprecated: without `trusted_proxies`, the client IP headers are only taken from \
+             loopback connections. Set `trusted_proxies` to the addresses of the reverse proxies instead"
+        );
+    }
+
     let mut jobs: Vec<JoinHandle<()>> = Vec::new();
 
     let registar = Registar::default();
@@ -58,6 +74,16 @@ pub async fn start(config: &Configuration, tracker: Arc<core::Tracker>) -> Vec<J
             .expect("Could not retrieve keys from database.");
     }
 
+    // Start runner to synchronize peer keys from a remote source, every interval
+    if let (true, Some(url)) = (tracker.is_private(), &config.core.key_sync.url) {
+        jobs.push(key_sync::start_job(&config.core.key_sync, url, &tracker));
+    }
+
+    // Start runner to remove the expired peer keys, every interval
+    if tracker.is_private() && config.core.expired_keys_cleanup_interval > 0 {
+        jobs.push(key_cleanup::start_job(&config.core, &tracker));
+    }
+
     // Load whitelisted torrents
     if tracker.is_listed() {
         tracker
@@ -66,17 +92,28 @@ pub async fn start(config: &Configuration, tracker: Arc<core::Tracker>) -> Vec<J
             .expect("Could not load whitelist from database.");
     }
 
+    // Restore the swarms saved before the restart, before accepting requests
+    if config.core.swarm_snapshot.path.is_some() {
+        match tracker.restore_swarm_snapshot().await {
+            Ok(restored) => tracing::info!(
+                torrents = restored.torrents,
+                peers = restored.peers,
+                "restored the swarm snapshot"
+            ),
+            Err(err) => tracing::warn!(%err, "unable to restore the swarm snapshot"),
+        }
+    }
+
     // Start the UDP blocks
     if let Some(udp_trackers) = &config.udp_trackers {
         for udp_tracker_config in udp_trackers {
-            if tracker.is_private() {
-                tracing::warn!(
-                    "Could not start UDP tracker on: {} while in private mode. UDP is not safe for private trackers!",
-                    udp_tracker_config.bind_address
-                );
-            } else {
-                jobs.push(udp_tracker::start_job(udp_tracker_config, tracker.clone(), registar.give_form()).await);
-            }
+            // Private trackers read the authentication key from the BEP 41 `URLData` option
+            jobs.push(udp_tracker::start_job(udp_tracker_config, tracker.clone(), registar.give_form()).await);
+        }
+
+        // Start runner to rotate the UDP connection ID secret, every interval
+        if config.core.udp_connection_id_secret_rotation.interval.is_some() {
+            jobs.push(connection_id_secret_rotation::start_job(&config.core, &tracker));
         }
     } else {
         tracing::info!("No UDP blocks in configuration");
@@ -100,10 +137,18 @@ pub async fn start(config: &Configuration, tracker: Arc<core::Tracker>) -> Vec<J
         tracing::info!("No HTTP blocks in configuration");
     }
 
+    // Start the WebSocket blocks
+    if let Some(websocket_trackers) = &config.websocket_trackers {
+        for websocket_tracker_config in websocket_trackers {
+            jobs.push(websocket_tracker::start_job(websocket_tracker_config, tracker.clone(), registar.give_form()).await);
+        }
+    }
+
     // Start HTTP API
     if let Some(http_api_config) = &config.http_api {
         if let Some(job) = tracker_apis::start_job(
             http_api_config,
+            Arc::new(config.clone()),
             tracker.clone(),
             registar.give_form(),
             servers::apis::Version::V1,
@@ -121,8 +166,53 @@ pub async fn start(config: &Configuration, tracker: Arc<core::Tracker>) -> Vec<J
         jobs.push(torrent_cleanup::start_job(&config.core, &tracker));
     }
 
+    // Start runner to save the torrent history of the previous hours, every interval
+    if config.core.persistent_torrent_history && config.core.inactive_peer_cleanup_interval > 0 {
+        jobs.push(torrent_history_flush::start_job(&config.core, &tracker));
+    }
+
+    // Start runner to write the buffered number of completed downloads, every interval
+    if config.core.tracker_policy.persistent_torrent_completed_stat && config.core.database.completed_stat_flush_interval > 0 {
+        jobs.push(completed_stats_flush::start_job(&config.core, &tracker));
+    }
+
+    // Start runner to save a snapshot of the swarms, every interval
+    if config.core.swarm_snapshot.path.is_some() && config.core.swarm_snapshot.interval > 0 {
+        jobs.push(swarm_snapshot::start_job(&config.core.swarm_snapshot, &tracker));
+    }
+
+    // Start runner to sample the load of the tracker, every interval
+    if config.core.overload.is_enabled() {
+        jobs.push(load_monitor::start_job(&config.core, &tracker));
+    }
+
+    // Start the alerts notifier
+    if let Some(alerts_config) = &config.alerts {
+        jobs.push(alerts::start_job(alerts_config, core::alerts::certificates(config), &tracker));
+    }
+
     // Start Health Check API
-    jobs.push(health_check_api::start_job(&config.health_check_api, registar.entries()).await);
+    jobs.push(health_check_api::start_job(&config.health_check_api, registar.entries(), tracker.clone()).await);
+
+    // Report the inherited sockets not used by any service
+    servers::socket_activation::warn_about_unused_sockets();
 
     jobs
 }
+
+/// It waits for all the jobs to shutdown and persists the stats of the
+/// torrents (and the snapshot of the swarms, if it's enabled), so they are
+/// not lost when the tracker is stopped.
+#[instrument(skip(jobs, tracker))]
+pub async fn stop(jobs: Vec<JoinHandle<()>>, tracker: &Arc<core::Tracker>) {
+    // Await for all jobs to shutdown
+    futures::future::join_all(jobs).await;
+
+    if let Err(err) = tracker.persist_all_stats().await {
+        tracing::error!("Failed to persist the torrent stats: {err}");
+    }
+
+    if let Err(err) = tracker.save_swarm_snapshot().await {
+        tracing::error!("Failed to save the swarm snapshot: {err}");
+    }
+}
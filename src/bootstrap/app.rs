@@ -13,16 +13,20 @@
 //! 4. Initialize the domain tracker.
 use std::sync::Arc;
 
+use torrust_tracker_clock::clock::Time;
 use torrust_tracker_clock::static_time;
 use torrust_tracker_configuration::validator::Validator;
 use torrust_tracker_configuration::Configuration;
 use tracing::instrument;
 
 use super::config::initialize_configuration;
-use crate::bootstrap;
-use crate::core::services::tracker_factory;
-use crate::core::Tracker;
+use crate::core::databases::driver::Driver;
+use crate::core::databases::dump::{Dump, Imported};
+use crate::core::databases::migrations::Migration;
+use crate::core::services::{connection_id_secret, tracker_factory};
+use crate::core::{databases, Tracker};
 use crate::shared::crypto::ephemeral_instance_keys;
+use crate::{bootstrap, CurrentClock};
 
 /// It loads the configuration from the environment and builds the main domain [`Tracker`] struct.
 ///
@@ -32,12 +36,30 @@ use crate::shared::crypto::ephemeral_instance_keys;
 #[must_use]
 #[instrument(skip())]
 pub fn setup() -> (Configuration, Arc<Tracker>) {
+    setup_with_configuration(load_configuration())
+}
+
+/// It loads and validates the configuration from the environment.
+///
+/// # Panics
+///
+/// Will panic if the configuration is invalid.
+#[must_use]
+pub fn load_configuration() -> Configuration {
     let configuration = initialize_configuration();
 
     if let Err(e) = configuration.validate() {
         panic!("Configuration error: {e}");
     }
 
+    configuration
+}
+
+/// It builds the main domain [`Tracker`] struct from an already loaded and
+/// validated configuration.
+#[must_use]
+#[instrument(skip(configuration))]
+pub fn setup_with_configuration(configuration: Configuration) -> (Configuration, Arc<Tracker>) {
     let tracker = initialize_with_configuration(&configuration);
 
     tracing::info!("Configuration:\n{}", configuration.clone().mask_secrets().to_json());
@@ -52,6 +74,7 @@ pub fn setup() -> (Configuration, Arc<Tracker>) {
 #[instrument(skip())]
 pub fn initialize_with_configuration(configuration: &Configuration) -> Arc<Tracker> {
     initialize_static();
+    initialize_instance_keys(configuration);
     initialize_logging(configuration);
     Arc::new(initialize_tracker(configuration))
 }
@@ -71,6 +94,19 @@ pub fn initialize_static() {
     lazy_static::initialize(&ephemeral_instance_keys::RANDOM_SEED);
 }
 
+/// It sets the persistent instance keys provided in the configuration or the
+/// secret file.
+///
+/// When none of them provide the keys, the ephemeral instance keys are used.
+///
+/// # Panics
+///
+/// Will panic if the secret file can't be loaded or created.
+#[instrument(skip(config))]
+pub fn initialize_instance_keys(config: &Configuration) {
+    connection_id_secret::initialize(&config.core).expect("it should load the UDP connection ID secret");
+}
+
 /// It builds the domain tracker
 ///
 /// The tracker is the domain layer service. It's the entrypoint to make requests to the domain layer.
@@ -81,6 +117,71 @@ pub fn initialize_tracker(config: &Configuration) -> Tracker {
     tracker_factory(config)
 }
 
+/// It applies the pending migrations of the database schema, without starting
+/// the tracker. It's run by the `migrate` command.
+///
+/// # Errors
+///
+/// Will return an error if unable to connect to the database or to apply the
+/// migrations.
+pub fn migrate_database(config: &Configuration) -> Result<Vec<&'static Migration>, databases::error::Error> {
+    let database = &config.core.database;
+    let encryption_key = databases::driver::encryption_key(database)?;
+
+    databases::driver::connect(
+        &Driver::from(&database.driver),
+        &database.path,
+        &database.pool,
+        database.table_prefix(),
+        encryption_key.as_deref(),
+    )?
+    .migrate()
+}
+
+/// It exports the contents of the database, after applying the pending
+/// migrations of its schema. It's run by the `db export` command.
+///
+/// # Errors
+///
+/// Will return an error if unable to connect to the database, to apply the
+/// migrations or to load the contents.
+pub async fn export_database(config: &Configuration) -> Result<Dump, databases::error::Error> {
+    let database = &config.core.database;
+    let encryption_key = databases::driver::encryption_key(database)?;
+
+    let database = databases::driver::build(
+        &Driver::from(&database.driver),
+        &database.path,
+        &database.pool,
+        database.table_prefix(),
+        encryption_key.as_deref(),
+    )?;
+
+    Dump::export(database.as_ref(), CurrentClock::now()).await
+}
+
+/// It imports a dump into the database, after applying the pending migrations
+/// of its schema. It's run by the `db import` command.
+///
+/// # Errors
+///
+/// Will return an error if unable to connect to the database, to apply the
+/// migrations or to write the contents.
+pub async fn import_database(config: &Configuration, dump: Dump) -> Result<Imported, databases::error::Error> {
+    let database = &config.core.database;
+    let encryption_key = databases::driver::encryption_key(database)?;
+
+    let database = databases::driver::build(
+        &Driver::from(&database.driver),
+        &database.path,
+        &database.pool,
+        database.table_prefix(),
+        encryption_key.as_deref(),
+    )?;
+
+    dump.import(database.as_ref()).await
+}
+
 /// It initializes the log threshold, format and channel.
 ///
 /// See [the logging setup](crate::bootstrap::logging::setup) for more info about logging.
@@ -2,6 +2,8 @@
 //!
 //! All environment variables are prefixed with `TORRUST_TRACKER_`.
 
+use std::path::Path;
+
 use torrust_tracker_configuration::{Configuration, Info};
 
 pub const DEFAULT_PATH_CONFIG: &str = "./share/default/config/tracker.development.sqlite3.toml";
@@ -15,6 +17,10 @@ pub const DEFAULT_PATH_CONFIG: &str = "./share/default/config/tracker.developmen
 ///
 /// Environment variable has priority over the config file.
 ///
+/// On the first run, when the config file does not exist, a default config
+/// file with random secrets is created. The generated API admin token is
+/// printed only once.
+///
 /// Refer to the [configuration documentation](https://docs.rs/torrust-tracker-configuration) for the configuration options.
 ///
 /// # Panics
@@ -24,9 +30,32 @@ pub const DEFAULT_PATH_CONFIG: &str = "./share/default/config/tracker.developmen
 #[must_use]
 pub fn initialize_configuration() -> Configuration {
     let info = Info::new(DEFAULT_PATH_CONFIG.to_string()).expect("info to load configuration is not valid");
+
+    if let Some(config_toml_path) = info.config_toml_path() {
+        if !Path::new(config_toml_path).exists() {
+            create_first_run_configuration(config_toml_path);
+        }
+    }
+
     Configuration::load(&info).expect("error loading configuration from sources")
 }
 
+/// It creates the default config file with random secrets.
+///
+/// # Panics
+///
+/// Will panic if the config file can't be created.
+fn create_first_run_configuration(path: &str) {
+    let configuration =
+        Configuration::create_default_configuration_file(path).expect("the default config file should be created");
+
+    println!("Created a new configuration file with random secrets: `{path}`");
+
+    if let Some(token) = configuration.get_admin_token() {
+        println!("Tracker API admin token (it will not be shown again): {token}");
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
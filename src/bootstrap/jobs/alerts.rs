@@ -0,0 +1,154 @@
+//! Job that notifies the administrators when something critical happens.
+//!
+//! On each interval, the job checks:
+//!
+//! - The database connection. An alert is sent when the database goes down,
+//!   and another one when it's reachable again.
+//! - The expiration date of the TLS certificates. An alert is sent every day
+//!   while a certificate expires within the configured number of days.
+//!
+//! The job also installs a panic hook, so an alert is sent when any service
+//! panics.
+//!
+//! Refer to the [`alerts`](crate::core::alerts) module for the list of alerts
+//! and to [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration)
+//! for the configuration options.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use camino::Utf8PathBuf;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_configuration::Alerts;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+use tracing::instrument;
+
+use crate::core::alerts::{days_left, expiration, Alert, Certificate};
+use crate::core::services::alerts::Notifier;
+use crate::core::{self, Tracker};
+use crate::servers::signals::global_shutdown_signal;
+use crate::CurrentClock;
+
+/// Certificate expiration alerts are repeated every day.
+const CERTIFICATE_ALERT_INTERVAL: Duration = Duration::from_secs(86_400);
+
+/// It starts a job for checking the tracker and sending the alerts.
+///
+/// # Panics
+///
+/// Will panic if the notifier can't be built from the configuration.
+#[must_use]
+#[instrument(skip(config, certificates, tracker))]
+pub fn start_job(config: &Alerts, certificates: Vec<Certificate>, tracker: &Arc<core::Tracker>) -> JoinHandle<()> {
+    let weak_tracker = std::sync::Arc::downgrade(tracker);
+    let config = config.clone();
+
+    let notifier = Notifier::new(&config, tracker.get_outbound_proxy().as_deref()).expect("it should build the alerts notifier");
+
+    let (panic_sender, mut panics) = mpsc::unbounded_channel();
+
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current().name().unwrap_or("<unnamed>").to_string();
+        let _ = panic_sender.send(Alert::ServicePanicked {
+            thread,
+            message: info.to_string(),
+        });
+        previous_hook(info);
+    }));
+
+    tokio::spawn(async move {
+        let shutdown = global_shutdown_signal();
+        tokio::pin!(shutdown);
+
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(config.check_interval));
+        let mut database_down = false;
+        let mut certificate_alerts: HashMap<Utf8PathBuf, DurationSinceUnixEpoch> = HashMap::new();
+
+        loop {
+            tokio::select! {
+                () = &mut shutdown => {
+                    tracing::info!("Stopping alerts job..");
+                    break;
+                }
+                Some(alert) = panics.recv() => {
+                    notifier.notify(&alert).await;
+                }
+                _ = interval.tick() => {
+                    if let Some(tracker) = weak_tracker.upgrade() {
+                        if let Some(alert) = check_database(&tracker, &mut database_down).await {
+                            notifier.notify(&alert).await;
+                        }
+
+                        for alert in check_certificates(&config, &certificates, &mut certificate_alerts) {
+                            notifier.notify(&alert).await;
+                        }
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// It returns an alert only when the database state changes.
+async fn check_database(tracker: &Arc<Tracker>, database_down: &mut bool) -> Option<Alert> {
+    let result = tracker.ping_database().await.map_err(|err| err.to_string());
+
+    match (result, *database_down) {
+        (Err(error), false) => {
+            *database_down = true;
+            Some(Alert::DatabaseDown { error })
+        }
+        (Ok(()), true) => {
+            *database_down = false;
+            Some(Alert::DatabaseRecovered)
+        }
+        _ => None,
+    }
+}
+
+fn check_certificates(
+    config: &Alerts,
+    certificates: &[Certificate],
+    certificate_alerts: &mut HashMap<Utf8PathBuf, DurationSinceUnixEpoch>,
+) -> Vec<Alert> {
+    let now = CurrentClock::now();
+
+    let mut alerts = vec![];
+
+    for certificate in certificates {
+        let expires_at = match expiration(&certificate.path) {
+            Ok(expires_at) => expires_at,
+            Err(err) => {
+                tracing::warn!(%err, "unable to check the certificate expiration date");
+                continue;
+            }
+        };
+
+        let days_left = days_left(expires_at, now);
+
+        if days_left >= config.certificate_expiration_days {
+            certificate_alerts.remove(&certificate.path);
+            continue;
+        }
+
+        let alerted_recently = certificate_alerts
+            .get(&certificate.path)
+            .is_some_and(|last_alert| now.saturating_sub(*last_alert) < CERTIFICATE_ALERT_INTERVAL);
+
+        if !alerted_recently {
+            certificate_alerts.insert(certificate.path.clone(), now);
+            alerts.push(Alert::CertificateExpiring {
+                certificate: certificate.clone(),
+                expires_at,
+                days_left,
+            });
+        }
+    }
+
+    alerts
+}
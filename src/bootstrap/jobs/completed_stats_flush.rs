@@ -0,0 +1,55 @@
+//! Job that runs a task on intervals to write the buffered number of
+//! completed downloads of the torrents into the database.
+//!
+//! When the core tracker configuration option `persistent_torrent_completed_stat`
+//! is enabled, the number of completed downloads is written for each
+//! `completed` announce. With the `completed_stat_flush_interval` option of
+//! the database, the changes are buffered in memory instead, and this job
+//! writes them in a single transaction on each interval.
+//!
+//! Refer to [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration) for more info about those options.
+use std::sync::Arc;
+
+use tokio::task::JoinHandle;
+use torrust_tracker_configuration::Core;
+use tracing::instrument;
+
+use crate::core;
+use crate::servers::signals::global_shutdown_signal;
+
+/// It starts a job for writing the buffered number of completed downloads.
+///
+/// The buffer is flushed every `completed_stat_flush_interval` seconds.
+#[must_use]
+#[instrument(skip(config, tracker))]
+pub fn start_job(config: &Core, tracker: &Arc<core::Tracker>) -> JoinHandle<()> {
+    let weak_tracker = std::sync::Arc::downgrade(tracker);
+    let interval = config.database.completed_stat_flush_interval;
+
+    tokio::spawn(async move {
+        let shutdown = global_shutdown_signal();
+        tokio::pin!(shutdown);
+
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval));
+        interval.tick().await;
+
+        loop {
+            tokio::select! {
+                () = &mut shutdown => {
+                    tracing::info!("Stopping completed stats flush job..");
+                    break;
+                }
+                _ = interval.tick() => {
+                    if let Some(tracker) = weak_tracker.upgrade() {
+                        match tracker.flush_completed_stats().await {
+                            Ok(torrents) => tracing::debug!(torrents, "flushed the completed stats"),
+                            Err(err) => tracing::warn!(%err, "unable to flush the completed stats"),
+                        }
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
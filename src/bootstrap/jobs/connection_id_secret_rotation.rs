@@ -0,0 +1,59 @@
+//! Job that rotates the UDP connection ID secret on intervals.
+//!
+//! The secret is rotated `interval` seconds after the current secret was
+//! generated. When the secret is persisted in a secret file, the interval
+//! keeps counting across restarts.
+//!
+//! Refer to the [`connection_id_secret`](crate::core::services::connection_id_secret)
+//! services and to [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration)
+//! for more info about the rotation options.
+use std::sync::Arc;
+
+use tokio::task::JoinHandle;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_configuration::Core;
+use tracing::instrument;
+
+use crate::core::services::connection_id_secret;
+use crate::servers::signals::global_shutdown_signal;
+use crate::{core, CurrentClock};
+
+/// It starts a job for rotating the UDP connection ID secret.
+///
+/// Refer to [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration) for more info about that option.
+#[must_use]
+#[instrument(skip(config, tracker))]
+pub fn start_job(config: &Core, tracker: &Arc<core::Tracker>) -> JoinHandle<()> {
+    let weak_tracker = std::sync::Arc::downgrade(tracker);
+    let config = config.clone();
+
+    tokio::spawn(async move {
+        let shutdown = global_shutdown_signal();
+        tokio::pin!(shutdown);
+
+        while let Some(next_rotation) = connection_id_secret::next_scheduled_rotation(&config) {
+            let delay = next_rotation.saturating_sub(CurrentClock::now());
+
+            tokio::select! {
+                () = &mut shutdown => {
+                    tracing::info!("Stopping UDP connection ID secret rotation job..");
+                    break;
+                }
+                () = tokio::time::sleep(delay) => {
+                    let Some(tracker) = weak_tracker.upgrade() else {
+                        break;
+                    };
+
+                    // The secret was rotated with the API in the meantime.
+                    if connection_id_secret::next_scheduled_rotation(&config) != Some(next_rotation) {
+                        continue;
+                    }
+
+                    if let Err(err) = connection_id_secret::rotate(&tracker) {
+                        tracing::warn!(%err, "unable to persist the rotated UDP connection ID secret");
+                    }
+                }
+            }
+        }
+    })
+}
@@ -14,16 +14,20 @@
 //! Refer to the [configuration documentation](https://docs.rs/torrust-tracker-configuration)
 //! for the API configuration options.
 
+use std::sync::Arc;
+
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 use torrust_tracker_configuration::HealthCheckApi;
 use tracing::instrument;
 
 use super::Started;
+use crate::core;
 use crate::servers::health_check_api::{server, HEALTH_CHECK_API_LOG_TARGET};
 use crate::servers::logging::STARTED_ON;
 use crate::servers::registar::ServiceRegistry;
 use crate::servers::signals::Halted;
+use crate::shared::dns::resolve_bind_address;
 
 /// This function starts a new Health Check API server with the provided
 /// configuration.
@@ -35,10 +39,13 @@ use crate::servers::signals::Halted;
 /// # Panics
 ///
 /// It would panic if unable to send the  `ApiServerJobStarted` notice.
+/// It would panic if the bind address can't be resolved.
 #[allow(clippy::async_yields_async)]
-#[instrument(skip(config, register))]
-pub async fn start_job(config: &HealthCheckApi, register: ServiceRegistry) -> JoinHandle<()> {
-    let bind_addr = config.bind_address;
+#[instrument(skip(config, register, tracker))]
+pub async fn start_job(config: &HealthCheckApi, register: ServiceRegistry, tracker: Arc<core::Tracker>) -> JoinHandle<()> {
+    let bind_addr = resolve_bind_address(&config.bind_address)
+        .await
+        .expect("it should be able to resolve the health check api bind address");
 
     let (tx_start, rx_start) = oneshot::channel::<Started>();
     let (tx_halt, rx_halt) = tokio::sync::oneshot::channel::<Halted>();
@@ -49,7 +56,7 @@ pub async fn start_job(config: &HealthCheckApi, register: ServiceRegistry) -> Jo
     let join_handle = tokio::spawn(async move {
         tracing::info!(target: HEALTH_CHECK_API_LOG_TARGET, "Starting on: {protocol}://{}", bind_addr);
 
-        let handle = server::start(bind_addr, tx_start, rx_halt, register);
+        let handle = server::start(bind_addr, tx_start, rx_halt, register, tracker);
 
         if let Ok(()) = handle.await {
             tracing::info!(target: HEALTH_CHECK_API_LOG_TARGET, "Stopped server running on: {protocol}://{}", bind_addr);
@@ -20,9 +20,14 @@ use tracing::instrument;
 
 use super::make_rust_tls;
 use crate::core;
+use crate::servers::access_log::AccessLog;
+use crate::servers::custom_axum_server::ConnectionSettings;
 use crate::servers::http::server::{HttpServer, Launcher};
+use crate::servers::http::v1::handlers::announce::AnnounceOptions;
+use crate::servers::http::v1::handlers::scrape::ScrapeOptions;
 use crate::servers::http::Version;
 use crate::servers::registar::ServiceRegistrationForm;
+use crate::shared::dns::resolve_bind_address;
 
 /// It starts a new HTTP server with the provided configuration and version.
 ///
@@ -40,29 +45,47 @@ pub async fn start_job(
     form: ServiceRegistrationForm,
     version: Version,
 ) -> Option<JoinHandle<()>> {
-    let socket = config.bind_address;
+    let socket = resolve_bind_address(&config.bind_address)
+        .await
+        .expect("it should be able to resolve the http tracker bind address");
 
     let tls = make_rust_tls(&config.tsl_config)
         .await
         .map(|tls| tls.expect("it should have a valid http tracker tls configuration"));
 
     match version {
-        Version::V1 => Some(start_v1(socket, tls, tracker.clone(), form).await),
+        Version::V1 => Some(start_v1(socket, tls, config, tracker.clone(), form).await),
     }
 }
 
 #[allow(clippy::async_yields_async)]
-#[instrument(skip(socket, tls, tracker, form))]
+#[instrument(skip(socket, tls, config, tracker, form))]
 async fn start_v1(
     socket: SocketAddr,
     tls: Option<RustlsConfig>,
+    config: &HttpTracker,
     tracker: Arc<core::Tracker>,
     form: ServiceRegistrationForm,
 ) -> JoinHandle<()> {
-    let server = HttpServer::new(Launcher::new(socket, tls))
-        .start(tracker, form)
-        .await
-        .expect("it should be able to start to the http tracker");
+    let access_log = config
+        .access_log
+        .as_ref()
+        .map(|access_log| Arc::new(AccessLog::open(access_log).expect("it should be able to open the http tracker access log")));
+
+    let server = HttpServer::new(Launcher::new(
+        socket,
+        tls,
+        config.acme.clone(),
+        ConnectionSettings::new(config),
+        config.max_concurrent_requests,
+        AnnounceOptions::new(config),
+        ScrapeOptions::new(config),
+        config.rate_limit,
+        access_log,
+    ))
+    .start(tracker, form)
+    .await
+    .expect("it should be able to start to the http tracker");
 
     tokio::spawn(async move {
         assert!(
@@ -0,0 +1,57 @@
+//! Job that runs a task on intervals to remove the expired authentication
+//! keys.
+//!
+//! Expired keys are useless: the tracker rejects them when they are used to
+//! announce. The job removes them from memory and from the database, so they
+//! do not pile up in private trackers issuing short-lived keys.
+//!
+//! The job only runs in `private` mode, every `expired_keys_cleanup_interval`
+//! seconds. Refer to [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration) for more info about that option.
+use std::sync::Arc;
+
+use tokio::task::JoinHandle;
+use torrust_tracker_configuration::Core;
+use tracing::instrument;
+
+use crate::core;
+use crate::servers::signals::global_shutdown_signal;
+
+/// It starts a job for removing the expired authentication keys.
+///
+/// The cleaning task is executed on an `expired_keys_cleanup_interval`.
+///
+/// Refer to [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration) for more info about that option.
+#[must_use]
+#[instrument(skip(config, tracker))]
+pub fn start_job(config: &Core, tracker: &Arc<core::Tracker>) -> JoinHandle<()> {
+    let weak_tracker = std::sync::Arc::downgrade(tracker);
+    let interval = config.expired_keys_cleanup_interval;
+
+    tokio::spawn(async move {
+        let shutdown = global_shutdown_signal();
+        tokio::pin!(shutdown);
+
+        let interval = std::time::Duration::from_secs(interval);
+        let mut interval = tokio::time::interval(interval);
+        interval.tick().await;
+
+        loop {
+            tokio::select! {
+                () = &mut shutdown => {
+                    tracing::info!("Stopping expired keys cleanup job..");
+                    break;
+                }
+                _ = interval.tick() => {
+                    if let Some(tracker) = weak_tracker.upgrade() {
+                        match tracker.remove_expired_auth_keys().await {
+                            Ok(removed) => tracing::info!("Removed {removed} expired authentication keys"),
+                            Err(err) => tracing::warn!(%err, "unable to remove the expired authentication keys"),
+                        }
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,61 @@
+//! Job that runs a task on intervals to synchronize the authentication keys
+//! from a remote endpoint.
+//!
+//! On each interval, the job pulls the keys added, updated and removed since
+//! the previous synchronization, and applies them to the tracker. Failed
+//! synchronizations are retried on the next interval, from the same cursor.
+//!
+//! Refer to the [`key_sync`](crate::core::services::key_sync) services for
+//! the format of the endpoint responses, and to
+//! [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration)
+//! for the configuration options.
+use std::sync::Arc;
+
+use tokio::task::JoinHandle;
+use torrust_tracker_configuration::KeySync;
+use tracing::instrument;
+
+use crate::core;
+use crate::core::services::key_sync;
+use crate::servers::signals::global_shutdown_signal;
+
+/// It starts a job for synchronizing the authentication keys.
+///
+/// The first synchronization is executed immediately, and then every
+/// `interval` seconds.
+///
+/// Refer to [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration) for more info about that option.
+#[must_use]
+#[instrument(skip(config, tracker))]
+pub fn start_job(config: &KeySync, url: &str, tracker: &Arc<core::Tracker>) -> JoinHandle<()> {
+    let weak_tracker = std::sync::Arc::downgrade(tracker);
+    let config = config.clone();
+    let url = url.to_string();
+
+    tokio::spawn(async move {
+        let shutdown = global_shutdown_signal();
+        tokio::pin!(shutdown);
+
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(config.interval));
+        let mut cursor: Option<String> = None;
+
+        loop {
+            tokio::select! {
+                () = &mut shutdown => {
+                    tracing::info!("Stopping key synchronization job..");
+                    break;
+                }
+                _ = interval.tick() => {
+                    if let Some(tracker) = weak_tracker.upgrade() {
+                        match key_sync::synchronize(&tracker, &config, &url, cursor.as_deref()).await {
+                            Ok(next_cursor) => cursor = Some(next_cursor),
+                            Err(err) => tracing::warn!(%err, "unable to synchronize the authentication keys"),
+                        }
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,57 @@
+//! Job that samples the load of the tracker on intervals.
+//!
+//! Besides the requests counted by the services, each sample measures the lag
+//! of the event loop: how late the job is woken up after the scheduled time.
+//! A busy runtime can't poll the tasks on time, so a growing lag means the
+//! tracker is falling behind.
+//!
+//! Refer to the [`load_monitor`](crate::core::load_monitor) and to
+//! [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration)
+//! for more info about the overload control options.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, MissedTickBehavior};
+use torrust_tracker_configuration::Core;
+use tracing::instrument;
+
+use crate::core;
+use crate::servers::signals::global_shutdown_signal;
+
+/// It starts a job for sampling the load of the tracker.
+///
+/// The load is sampled every `sample_interval` milliseconds.
+#[must_use]
+#[instrument(skip(config, tracker))]
+pub fn start_job(config: &Core, tracker: &Arc<core::Tracker>) -> JoinHandle<()> {
+    let weak_tracker = std::sync::Arc::downgrade(tracker);
+    let sample_interval = Duration::from_millis(config.overload.sample_interval);
+
+    tokio::spawn(async move {
+        let shutdown = global_shutdown_signal();
+        tokio::pin!(shutdown);
+
+        let mut interval = tokio::time::interval(sample_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut last_sample = interval.tick().await;
+
+        loop {
+            tokio::select! {
+                () = &mut shutdown => {
+                    tracing::info!("Stopping load monitor job..");
+                    break;
+                }
+                scheduled = interval.tick() => {
+                    let Some(tracker) = weak_tracker.upgrade() else {
+                        break;
+                    };
+
+                    let now = Instant::now();
+                    tracker.sample_load(now.duration_since(last_sample), now.duration_since(scheduled));
+                    last_sample = now;
+                }
+            }
+        }
+    })
+}
@@ -6,11 +6,20 @@
 //! 2. Launch all the application services as concurrent jobs.
 //!
 //! This modules contains all the functions needed to start those jobs.
+pub mod alerts;
+pub mod completed_stats_flush;
+pub mod connection_id_secret_rotation;
 pub mod health_check_api;
 pub mod http_tracker;
+pub mod key_cleanup;
+pub mod key_sync;
+pub mod load_monitor;
+pub mod swarm_snapshot;
 pub mod torrent_cleanup;
+pub mod torrent_history_flush;
 pub mod tracker_apis;
 pub mod udp_tracker;
+pub mod websocket_tracker;
 
 /// This is the message that the "launcher" spawned task sends to the main
 /// application process to notify the service was successfully started.
@@ -36,9 +45,43 @@ pub async fn make_rust_tls(opt_tsl_config: &Option<TslConfig>) -> Option<Result<
             tracing::info!("Using https: cert path: {cert}.");
             tracing::info!("Using https: key path: {key}.");
 
+            if let Some(client_ca) = &tsl_config.ssl_client_ca_path {
+                if !client_ca.exists() {
+                    return Some(Err(Error::MissingTlsConfig {
+                        location: Location::caller(),
+                    }));
+                }
+
+                tracing::info!("Using https: client CA path: {client_ca}.");
+            }
+
+            for sni_certificate in &tsl_config.sni_certificates {
+                if !sni_certificate.ssl_cert_path.exists() || !sni_certificate.ssl_key_path.exists() {
+                    return Some(Err(Error::MissingTlsConfig {
+                        location: Location::caller(),
+                    }));
+                }
+
+                tracing::info!(
+                    "Using https: cert path for {}: {}.",
+                    sni_certificate.hostname,
+                    sni_certificate.ssl_cert_path
+                );
+            }
+
+            if tsl_config.ssl_client_ca_path.is_none() && tsl_config.sni_certificates.is_empty() {
+                return Some(
+                    RustlsConfig::from_pem_file(cert, key)
+                        .await
+                        .map_err(|err| Error::BadTlsConfig {
+                            source: (Arc::new(err) as DynError).into(),
+                        }),
+                );
+            }
+
             Some(
-                RustlsConfig::from_pem_file(cert, key)
-                    .await
+                make_rust_tls_server_config(tsl_config)
+                    .map(|config| RustlsConfig::from_config(Arc::new(config)))
                     .map_err(|err| Error::BadTlsConfig {
                         source: (Arc::new(err) as DynError).into(),
                     }),
@@ -48,11 +91,110 @@ pub async fn make_rust_tls(opt_tsl_config: &Option<TslConfig>) -> Option<Result<
     }
 }
 
+/// It builds a TLS server configuration that requires clients to present a
+/// certificate signed by one of the CAs in the `ssl_client_ca_path` PEM file,
+/// if it's set, and selects the certificate with the hostname sent by the
+/// client (SNI), if there are `sni_certificates`.
+fn make_rust_tls_server_config(tsl_config: &TslConfig) -> Result<ServerConfig, io::Error> {
+    let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|err| invalid_data(&err))?;
+
+    let builder = match &tsl_config.ssl_client_ca_path {
+        Some(client_ca) => {
+            let mut client_roots = RootCertStore::empty();
+            for client_ca_cert in rustls_pemfile::certs(&mut BufReader::new(File::open(client_ca)?)) {
+                client_roots.add(client_ca_cert?).map_err(|err| invalid_data(&err))?;
+            }
+
+            let client_verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(client_roots), provider.clone())
+                .build()
+                .map_err(|err| invalid_data(&err))?;
+
+            builder.with_client_cert_verifier(client_verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut config = if tsl_config.sni_certificates.is_empty() {
+        let (cert_chain, private_key) = read_cert_and_key(&tsl_config.ssl_cert_path, &tsl_config.ssl_key_path)?;
+
+        builder
+            .with_single_cert(cert_chain, private_key)
+            .map_err(|err| invalid_data(&err))?
+    } else {
+        let default = load_certified_key(&tsl_config.ssl_cert_path, &tsl_config.ssl_key_path, &provider)?;
+
+        let mut certificates = HashMap::new();
+        for sni_certificate in &tsl_config.sni_certificates {
+            certificates.insert(
+                sni_certificate.hostname.to_ascii_lowercase(),
+                load_certified_key(&sni_certificate.ssl_cert_path, &sni_certificate.ssl_key_path, &provider)?,
+            );
+        }
+
+        builder.with_cert_resolver(Arc::new(SniCertResolver { certificates, default }))
+    };
+
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(config)
+}
+
+fn invalid_data(err: &dyn std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+fn read_cert_and_key(
+    cert: &Utf8Path,
+    key: &Utf8Path,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), io::Error> {
+    let cert_chain = rustls_pemfile::certs(&mut BufReader::new(File::open(cert)?)).collect::<Result<Vec<_>, _>>()?;
+
+    let private_key = rustls_pemfile::private_key(&mut BufReader::new(File::open(key)?))?
+        .ok_or_else(|| invalid_data(&format!("no private key found in {key}")))?;
+
+    Ok((cert_chain, private_key))
+}
+
+fn load_certified_key(cert: &Utf8Path, key: &Utf8Path, provider: &CryptoProvider) -> Result<Arc<CertifiedKey>, io::Error> {
+    let (cert_chain, private_key) = read_cert_and_key(cert, key)?;
+
+    let signing_key = provider
+        .key_provider
+        .load_private_key(private_key)
+        .map_err(|err| invalid_data(&err))?;
+
+    Ok(Arc::new(CertifiedKey::new(cert_chain, signing_key)))
+}
+
+/// It selects the certificate with the hostname sent by the client in the
+/// TLS handshake (SNI). The default certificate is used when the client does
+/// not send a hostname, or there is no certificate for it.
+#[derive(Debug)]
+struct SniCertResolver {
+    certificates: HashMap<String, Arc<CertifiedKey>>,
+    default: Arc<CertifiedKey>,
+}
+
+impl ResolvesServerCert for SniCertResolver {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        let certificate = client_hello
+            .server_name()
+            .and_then(|hostname| self.certificates.get(&hostname.to_ascii_lowercase()))
+            .unwrap_or(&self.default);
+
+        Some(certificate.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use camino::Utf8PathBuf;
-    use torrust_tracker_configuration::TslConfig;
+    use torrust_tracker_configuration::{SniCertificate, TslConfig};
 
     use super::{make_rust_tls, Error};
 
@@ -61,6 +203,8 @@ mod tests {
         let err = make_rust_tls(&Some(TslConfig {
             ssl_cert_path: Utf8PathBuf::from("bad cert path"),
             ssl_key_path: Utf8PathBuf::from("bad key path"),
+            ssl_client_ca_path: None,
+            sni_certificates: vec![],
         }))
         .await
         .expect("tls_was_enabled")
@@ -74,6 +218,8 @@ mod tests {
         let err = make_rust_tls(&Some(TslConfig {
             ssl_cert_path: Utf8PathBuf::from(""),
             ssl_key_path: Utf8PathBuf::from(""),
+            ssl_client_ca_path: None,
+            sni_certificates: vec![],
         }))
         .await
         .expect("tls_was_enabled")
@@ -81,12 +227,70 @@ mod tests {
 
         assert!(matches!(err, Error::MissingTlsConfig { location: _ }));
     }
+
+    #[tokio::test]
+    async fn it_should_error_on_missing_client_ca_path() {
+        let err = make_rust_tls(&Some(TslConfig {
+            ssl_cert_path: Utf8PathBuf::from("./Cargo.toml"),
+            ssl_key_path: Utf8PathBuf::from("./Cargo.toml"),
+            ssl_client_ca_path: Some(Utf8PathBuf::from("bad client ca path")),
+            sni_certificates: vec![],
+        }))
+        .await
+        .expect("tls_was_enabled")
+        .expect_err("missing_client_ca");
+
+        assert!(matches!(err, Error::MissingTlsConfig { location: _ }));
+    }
+
+    #[tokio::test]
+    async fn it_should_error_on_bad_client_auth_tls_config() {
+        let err = make_rust_tls(&Some(TslConfig {
+            ssl_cert_path: Utf8PathBuf::from("./Cargo.toml"),
+            ssl_key_path: Utf8PathBuf::from("./Cargo.toml"),
+            ssl_client_ca_path: Some(Utf8PathBuf::from("./Cargo.toml")),
+            sni_certificates: vec![],
+        }))
+        .await
+        .expect("tls_was_enabled")
+        .expect_err("bad_tls_files");
+
+        assert!(matches!(err, Error::BadTlsConfig { source: _ }));
+    }
+
+    #[tokio::test]
+    async fn it_should_error_on_missing_sni_cert_or_key_paths() {
+        let err = make_rust_tls(&Some(TslConfig {
+            ssl_cert_path: Utf8PathBuf::from("./Cargo.toml"),
+            ssl_key_path: Utf8PathBuf::from("./Cargo.toml"),
+            ssl_client_ca_path: None,
+            sni_certificates: vec![SniCertificate {
+                hostname: "tracker.example.com".to_string(),
+                ssl_cert_path: Utf8PathBuf::from("bad cert path"),
+                ssl_key_path: Utf8PathBuf::from("bad key path"),
+            }],
+        }))
+        .await
+        .expect("tls_was_enabled")
+        .expect_err("missing_sni_cert_and_key_files");
+
+        assert!(matches!(err, Error::MissingTlsConfig { location: _ }));
+    }
 }
 
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader};
 use std::panic::Location;
 use std::sync::Arc;
 
 use axum_server::tls_rustls::RustlsConfig;
+use camino::Utf8Path;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::{ClientHello, ResolvesServerCert, WebPkiClientVerifier};
+use rustls::sign::CertifiedKey;
+use rustls::{RootCertStore, ServerConfig};
 use thiserror::Error;
 use torrust_tracker_configuration::TslConfig;
 use torrust_tracker_located_error::{DynError, LocatedError};
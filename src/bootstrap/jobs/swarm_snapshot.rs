@@ -0,0 +1,66 @@
+//! Job that runs a task on intervals to save a snapshot of the swarms.
+//!
+//! The snapshot is restored when the tracker starts, so a restart does not
+//! wipe the swarms. The last snapshot is saved when the tracker stops.
+//!
+//! The job is paused during scheduled maintenance windows (if the core tracker
+//! configuration option `maintenance.pause_jobs` is true). The last snapshot
+//! is still saved when the tracker stops.
+//!
+//! Refer to the [`swarm_snapshot`](crate::core::swarm_snapshot) module and to
+//! [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration)
+//! for more info about the snapshot options.
+use std::sync::Arc;
+
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use torrust_tracker_configuration::SwarmSnapshot;
+use tracing::instrument;
+
+use crate::core;
+use crate::servers::signals::global_shutdown_signal;
+
+/// It starts a job for saving the snapshots of the swarms.
+///
+/// The snapshot is saved every `interval` seconds.
+#[must_use]
+#[instrument(skip(config, tracker))]
+pub fn start_job(config: &SwarmSnapshot, tracker: &Arc<core::Tracker>) -> JoinHandle<()> {
+    let weak_tracker = std::sync::Arc::downgrade(tracker);
+    let interval = config.interval;
+
+    tokio::spawn(async move {
+        let shutdown = global_shutdown_signal();
+        tokio::pin!(shutdown);
+
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval));
+        interval.tick().await;
+
+        loop {
+            tokio::select! {
+                () = &mut shutdown => {
+                    tracing::info!("Stopping swarm snapshot job..");
+                    break;
+                }
+                _ = interval.tick() => {
+                    if let Some(tracker) = weak_tracker.upgrade() {
+                        if tracker.should_pause_jobs() {
+                            tracing::info!("Skipping swarm snapshot during maintenance window..");
+                            continue;
+                        }
+                        let start_time = Utc::now().time();
+                        match tracker.save_swarm_snapshot().await {
+                            Ok(()) => {
+                                let elapsed = (Utc::now().time() - start_time).num_milliseconds();
+                                tracing::info!("Saved the swarm snapshot in: {elapsed}ms");
+                            }
+                            Err(err) => tracing::warn!(%err, "unable to save the swarm snapshot"),
+                        }
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
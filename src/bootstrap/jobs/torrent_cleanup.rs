@@ -3,11 +3,17 @@
 //! It removes inactive peers and (optionally) peerless torrents.
 //!
 //! **Inactive peers** are peers that have not been updated for more than `max_peer_timeout` seconds.
-//! `max_peer_timeout` is a customizable core tracker option.
+//! `max_peer_timeout` is a customizable core tracker option. When the core
+//! tracker option `peer_timeout_intervals` is set, peers are also kept for that
+//! number of announce intervals, so raising the intervals does not expire them.
 //!
 //! If the core tracker configuration option `remove_peerless_torrents` is true, the cleanup job will also
 //! remove **peerless torrents** which are torrents with an empty peer list.
 //!
+//! The job is paused during scheduled maintenance windows (if the core tracker
+//! configuration option `maintenance.pause_jobs` is true) because the announce
+//! intervals are raised and peers would be removed before they announce again.
+//!
 //! Refer to [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration) for more info about those options.
 
 use std::sync::Arc;
@@ -18,6 +24,7 @@ use torrust_tracker_configuration::Core;
 use tracing::instrument;
 
 use crate::core;
+use crate::servers::signals::global_shutdown_signal;
 
 /// It starts a jobs for cleaning up the torrent data in the tracker.
 ///
@@ -31,21 +38,28 @@ pub fn start_job(config: &Core, tracker: &Arc<core::Tracker>) -> JoinHandle<()>
     let interval = config.inactive_peer_cleanup_interval;
 
     tokio::spawn(async move {
+        let shutdown = global_shutdown_signal();
+        tokio::pin!(shutdown);
+
         let interval = std::time::Duration::from_secs(interval);
         let mut interval = tokio::time::interval(interval);
         interval.tick().await;
 
         loop {
             tokio::select! {
-                _ = tokio::signal::ctrl_c() => {
+                () = &mut shutdown => {
                     tracing::info!("Stopping torrent cleanup job..");
                     break;
                 }
                 _ = interval.tick() => {
                     if let Some(tracker) = weak_tracker.upgrade() {
+                        if tracker.should_pause_jobs() {
+                            tracing::info!("Skipping torrent cleanup during maintenance window..");
+                            continue;
+                        }
                         let start_time = Utc::now().time();
                         tracing::info!("Cleaning up torrents..");
-                        tracker.cleanup_torrents();
+                        tracker.cleanup_torrents().await;
                         tracing::info!("Cleaned up torrents in: {}ms", (Utc::now().time() - start_time).num_milliseconds());
                     } else {
                         break;
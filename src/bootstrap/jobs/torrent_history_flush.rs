@@ -0,0 +1,63 @@
+//! Job that runs a task on intervals to save the torrent history.
+//!
+//! When the core tracker configuration option `persistent_torrent_history` is
+//! enabled, the counters of the current hour of each torrent are kept in
+//! memory. This job saves the counters of the previous hours into the
+//! database, for the torrents not updated since then. It runs on the
+//! `inactive_peer_cleanup_interval`.
+//!
+//! The job is paused during scheduled maintenance windows (if the core tracker
+//! configuration option `maintenance.pause_jobs` is true). The counters are
+//! kept in memory until the next run, or until the tracker is shut down.
+//!
+//! Refer to the [`torrent_history`](crate::core::torrent_history) module and to
+//! [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration)
+//! for more info about those options.
+use std::sync::Arc;
+
+use tokio::task::JoinHandle;
+use torrust_tracker_configuration::Core;
+use tracing::instrument;
+
+use crate::core;
+use crate::servers::signals::global_shutdown_signal;
+
+/// It starts a job for saving the torrent history of the previous hours.
+///
+/// The history is saved every `inactive_peer_cleanup_interval` seconds.
+#[must_use]
+#[instrument(skip(config, tracker))]
+pub fn start_job(config: &Core, tracker: &Arc<core::Tracker>) -> JoinHandle<()> {
+    let weak_tracker = std::sync::Arc::downgrade(tracker);
+    let interval = config.inactive_peer_cleanup_interval;
+
+    tokio::spawn(async move {
+        let shutdown = global_shutdown_signal();
+        tokio::pin!(shutdown);
+
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval));
+        interval.tick().await;
+
+        loop {
+            tokio::select! {
+                () = &mut shutdown => {
+                    tracing::info!("Stopping torrent history job..");
+                    break;
+                }
+                _ = interval.tick() => {
+                    if let Some(tracker) = weak_tracker.upgrade() {
+                        if tracker.should_pause_jobs() {
+                            tracing::info!("Skipping torrent history during maintenance window..");
+                            continue;
+                        }
+                        if let Err(err) = tracker.save_closed_torrent_history().await {
+                            tracing::warn!(%err, "unable to save the torrent history");
+                        }
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
@@ -20,19 +20,20 @@
 //!
 //! Refer to the [configuration documentation](https://docs.rs/torrust-tracker-configuration)
 //! for the API configuration options.
-use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum_server::tls_rustls::RustlsConfig;
 use tokio::task::JoinHandle;
-use torrust_tracker_configuration::{AccessTokens, HttpApi};
+use torrust_tracker_configuration::{AccessTokens, Configuration, HttpApi};
 use tracing::instrument;
 
 use super::make_rust_tls;
 use crate::core;
+use crate::servers::access_log::AccessLog;
 use crate::servers::apis::server::{ApiServer, Launcher};
 use crate::servers::apis::Version;
 use crate::servers::registar::ServiceRegistrationForm;
+use crate::shared::dns::resolve_bind_address;
 
 /// This is the message that the "launcher" spawned task sends to the main
 /// application process to notify the API server was successfully started.
@@ -44,6 +45,8 @@ use crate::servers::registar::ServiceRegistrationForm;
 pub struct ApiServerJobStarted();
 
 /// This function starts a new API server with the provided configuration.
+/// The configuration of the whole tracker, `running_config`, is served by the
+/// API with the secrets masked.
 ///
 /// The functions starts a new concurrent task that will run the API server.
 /// This task will send a message to the main application process to notify
@@ -52,16 +55,20 @@ pub struct ApiServerJobStarted();
 /// # Panics
 ///
 /// It would panic if unable to send the  `ApiServerJobStarted` notice.
+/// It would panic if the bind address can't be resolved.
 ///
 ///
-#[instrument(skip(config, tracker, form))]
+#[instrument(skip(config, running_config, tracker, form))]
 pub async fn start_job(
     config: &HttpApi,
+    running_config: Arc<Configuration>,
     tracker: Arc<core::Tracker>,
     form: ServiceRegistrationForm,
     version: Version,
 ) -> Option<JoinHandle<()>> {
-    let bind_to = config.bind_address;
+    let bind_to = resolve_bind_address(&config.bind_address)
+        .await
+        .expect("it should be able to resolve the tracker api bind address");
 
     let tls = make_rust_tls(&config.tsl_config)
         .await
@@ -69,21 +76,38 @@ pub async fn start_job(
 
     let access_tokens = Arc::new(config.access_tokens.clone());
 
+    let access_log = config
+        .access_log
+        .as_ref()
+        .map(|access_log| Arc::new(AccessLog::open(access_log).expect("it should be able to open the tracker api access log")));
+
+    let launcher = Launcher::new(
+        bind_to,
+        tls,
+        access_log,
+        Duration::from_secs(config.shutdown_timeout),
+        config.redact_peer_ips,
+        Arc::new(config.access_token_roles.clone()),
+        Duration::from_secs(config.access_token_rotation_overlap),
+        config.jwt.clone().map(Arc::new),
+        config.rate_limit,
+        running_config,
+    );
+
     match version {
-        Version::V1 => Some(start_v1(bind_to, tls, tracker.clone(), form, access_tokens).await),
+        Version::V1 => Some(start_v1(launcher, tracker.clone(), form, access_tokens).await),
     }
 }
 
 #[allow(clippy::async_yields_async)]
-#[instrument(skip(socket, tls, tracker, form, access_tokens))]
+#[instrument(skip(launcher, tracker, form, access_tokens))]
 async fn start_v1(
-    socket: SocketAddr,
-    tls: Option<RustlsConfig>,
+    launcher: Launcher,
     tracker: Arc<core::Tracker>,
     form: ServiceRegistrationForm,
     access_tokens: Arc<AccessTokens>,
 ) -> JoinHandle<()> {
-    let server = ApiServer::new(Launcher::new(socket, tls))
+    let server = ApiServer::new(launcher)
         .start(tracker, form, access_tokens)
         .await
         .expect("it should be able to start to the tracker api");
@@ -112,7 +136,7 @@ mod tests {
         let tracker = initialize_with_configuration(&cfg);
         let version = Version::V1;
 
-        start_job(config, tracker, Registar::default().give_form(), version)
+        start_job(config, cfg.clone(), tracker, Registar::default().give_form(), version)
             .await
             .expect("it should be able to join to the tracker api start-job");
     }
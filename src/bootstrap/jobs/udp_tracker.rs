@@ -7,6 +7,7 @@
 //! > on different ports. Refer to the [configuration documentation](https://docs.rs/torrust-tracker-configuration)
 //! > for the configuration options.
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::task::JoinHandle;
 use torrust_tracker_configuration::UdpTracker;
@@ -14,9 +15,11 @@ use tracing::instrument;
 
 use crate::core;
 use crate::servers::registar::ServiceRegistrationForm;
+use crate::servers::udp::handlers::PeersInResponse;
 use crate::servers::udp::server::spawner::Spawner;
 use crate::servers::udp::server::Server;
 use crate::servers::udp::UDP_TRACKER_LOG_TARGET;
+use crate::shared::dns::resolve_bind_address;
 
 /// It starts a new UDP server with the provided configuration.
 ///
@@ -31,12 +34,27 @@ use crate::servers::udp::UDP_TRACKER_LOG_TARGET;
 #[allow(clippy::async_yields_async)]
 #[instrument(skip(config, tracker, form))]
 pub async fn start_job(config: &UdpTracker, tracker: Arc<core::Tracker>, form: ServiceRegistrationForm) -> JoinHandle<()> {
-    let bind_to = config.bind_address;
-
-    let server = Server::new(Spawner::new(bind_to))
-        .start(tracker, form)
+    let bind_to = resolve_bind_address(&config.bind_address)
         .await
-        .expect("it should be able to start the udp tracker");
+        .expect("it should be able to resolve the udp tracker bind address");
+
+    let server = Server::new(Spawner::new(
+        bind_to,
+        config.max_inflight_packets,
+        config.workers,
+        config.reuse_port,
+        config.rate_limit,
+        config.udp_io_backend,
+        Duration::from_secs(config.shutdown_timeout),
+        PeersInResponse::new(config),
+        config.debug_capture,
+        config.min_peer_port,
+        config.connection_id_scheme,
+        config.load_shedding,
+    ))
+    .start(tracker, form)
+    .await
+    .expect("it should be able to start the udp tracker");
 
     tokio::spawn(async move {
         tracing::debug!(target: UDP_TRACKER_LOG_TARGET, "Wait for launcher (UDP service) to finish ...");
@@ -0,0 +1,51 @@
+//! WebSocket tracker job starter.
+//!
+//! The function [`websocket_tracker::start_job`](crate::bootstrap::jobs::websocket_tracker::start_job)
+//! starts a new WebSocket tracker server for the `WebTorrent` clients.
+//!
+//! > **NOTICE**: the application can launch more than one WebSocket tracker on different ports.
+//! > Refer to the [configuration documentation](https://docs.rs/torrust-tracker-configuration) for the configuration options.
+use std::sync::Arc;
+
+use tokio::task::JoinHandle;
+use torrust_tracker_configuration::WebSocketTracker;
+use tracing::instrument;
+
+use super::make_rust_tls;
+use crate::core;
+use crate::servers::registar::ServiceRegistrationForm;
+use crate::servers::websocket::server::{Launcher, WebSocketServer};
+use crate::shared::dns::resolve_bind_address;
+
+/// It starts a new WebSocket tracker server with the provided configuration.
+///
+/// # Panics
+///
+/// It would panic if the `config::WebSocketTracker` struct would contain inappropriate values.
+#[instrument(skip(config, tracker, form))]
+pub async fn start_job(config: &WebSocketTracker, tracker: Arc<core::Tracker>, form: ServiceRegistrationForm) -> JoinHandle<()> {
+    let tls = make_rust_tls(&config.tsl_config)
+        .await
+        .map(|tls| tls.expect("it should have a valid websocket tracker tls configuration"));
+
+    let bind_to = resolve_bind_address(&config.bind_address)
+        .await
+        .expect("it should be able to resolve the websocket tracker bind address");
+
+    let server = WebSocketServer::new(Launcher::new(bind_to, tls, config.max_offers))
+        .start(tracker, form)
+        .await
+        .expect("it should be able to start the websocket tracker");
+
+    tokio::spawn(async move {
+        assert!(
+            !server.state.halt_task.is_closed(),
+            "Halt channel for WebSocket tracker should be open"
+        );
+        server
+            .state
+            .task
+            .await
+            .expect("it should be able to join to the websocket tracker task");
+    })
+}
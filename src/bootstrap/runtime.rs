@@ -0,0 +1,43 @@
+//! Setup for the asynchronous runtime.
+//!
+//! The runtime is built from the [`Runtime`] configuration before starting
+//! the application, so the number of threads can be tuned for each
+//! deployment without recompiling.
+use torrust_tracker_configuration::Runtime;
+
+/// It builds the multi-thread runtime that runs all the services.
+///
+/// # Errors
+///
+/// Will return an error if the runtime can't be built.
+pub fn build(config: &Runtime) -> std::io::Result<tokio::runtime::Runtime> {
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+
+    builder.enable_all();
+
+    if let Some(worker_threads) = config.worker_threads {
+        builder.worker_threads(usize::try_from(worker_threads).unwrap_or(usize::MAX));
+    }
+
+    builder.max_blocking_threads(usize::try_from(config.max_blocking_threads).unwrap_or(usize::MAX));
+
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use torrust_tracker_configuration::Runtime;
+
+    use super::build;
+
+    #[test]
+    fn it_should_build_a_runtime_with_the_configured_number_of_worker_threads() {
+        let runtime = build(&Runtime {
+            worker_threads: Some(2),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(runtime.metrics().num_workers(), 2);
+    }
+}
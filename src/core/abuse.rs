@@ -0,0 +1,219 @@
+//! Abuse reports and automatic mitigations.
+//!
+//! Abuse reports are submitted by the tracker operators (or by trusted third
+//! parties) using the [tracker API](crate::servers::apis::v1::context::abuse).
+//! A report can contain the torrent infohash, the peer IP address or both.
+//!
+//! When the tracker receives a report it applies the mitigations enabled in
+//! the [`Abuse`](torrust_tracker_configuration::Abuse) configuration section:
+//!
+//! Mitigation | Effect
+//! ---|---
+//! [`Mitigation::BanIp`] | Announce requests from the IP address are rejected.
+//! [`Mitigation::BlacklistInfoHash`] | The torrent can't be announced or scraped.
+//! [`Mitigation::RaiseInterval`] | The announce interval for the torrent is raised.
+//!
+//! > **NOTICE**: mitigations are only kept in memory. They are lost when the
+//! > tracker is restarted.
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::RwLock;
+
+use serde::Serialize;
+use torrust_tracker_configuration::{Abuse, AnnouncePolicy};
+use torrust_tracker_primitives::info_hash::InfoHash;
+
+/// An abuse report.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// The reported torrent.
+    pub info_hash: Option<InfoHash>,
+    /// The reported peer IP address.
+    pub ip: Option<IpAddr>,
+    /// Free-form description of the abuse.
+    pub reason: String,
+}
+
+/// A mitigation applied after receiving an abuse report.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Mitigation {
+    /// The IP address was banned.
+    BanIp { ip: IpAddr },
+    /// The torrent was blacklisted.
+    BlacklistInfoHash { info_hash: InfoHash },
+    /// The announce interval for the torrent was raised.
+    RaiseInterval { info_hash: InfoHash, interval: u32 },
+}
+
+/// In-memory registry of the applied mitigations.
+#[derive(Debug, Default)]
+pub struct Mitigations {
+    banned_ips: RwLock<HashSet<IpAddr>>,
+    blacklist: RwLock<HashSet<InfoHash>>,
+    raised_intervals: RwLock<HashMap<InfoHash, u32>>,
+}
+
+impl Mitigations {
+    /// It applies the mitigations enabled in the configuration for the report.
+    ///
+    /// It returns the list of applied mitigations. Mitigations that do not
+    /// apply to the report (for example, banning the IP when the report does
+    /// not contain an IP) are skipped.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if any of the internal locks is poisoned.
+    pub fn apply(&self, config: &Abuse, report: &Report) -> Vec<Mitigation> {
+        let mut applied = vec![];
+
+        if let Some(ip) = report.ip {
+            if config.ban_ip {
+                self.banned_ips.write().expect("it should get a write lock").insert(ip);
+                applied.push(Mitigation::BanIp { ip });
+            }
+        }
+
+        if let Some(info_hash) = report.info_hash {
+            if config.blacklist_info_hash {
+                self.blacklist.write().expect("it should get a write lock").insert(info_hash);
+                applied.push(Mitigation::BlacklistInfoHash { info_hash });
+            }
+
+            if let Some(interval) = config.raised_interval {
+                self.raised_intervals
+                    .write()
+                    .expect("it should get a write lock")
+                    .insert(info_hash, interval);
+                applied.push(Mitigation::RaiseInterval { info_hash, interval });
+            }
+        }
+
+        applied
+    }
+
+    /// # Panics
+    ///
+    /// Will panic if the internal lock is poisoned.
+    #[must_use]
+    pub fn is_ip_banned(&self, ip: &IpAddr) -> bool {
+        self.banned_ips.read().expect("it should get a read lock").contains(ip)
+    }
+
+    /// # Panics
+    ///
+    /// Will panic if the internal lock is poisoned.
+    #[must_use]
+    pub fn is_info_hash_blacklisted(&self, info_hash: &InfoHash) -> bool {
+        self.blacklist.read().expect("it should get a read lock").contains(info_hash)
+    }
+
+    /// It returns the announce policy for a torrent, raising the intervals
+    /// if a [`Mitigation::RaiseInterval`] was applied to the torrent.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the internal lock is poisoned.
+    #[must_use]
+    pub fn announce_policy_for(&self, info_hash: &InfoHash, policy: AnnouncePolicy) -> AnnouncePolicy {
+        match self
+            .raised_intervals
+            .read()
+            .expect("it should get a read lock")
+            .get(info_hash)
+        {
+            Some(interval) => AnnouncePolicy {
+                interval: policy.interval.max(*interval),
+                interval_min: policy.interval_min.max(*interval),
+            },
+            None => policy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use torrust_tracker_configuration::{Abuse, AnnouncePolicy};
+    use torrust_tracker_primitives::info_hash::InfoHash;
+
+    use super::{Mitigation, Mitigations, Report};
+
+    fn sample_report() -> Report {
+        Report {
+            info_hash: Some(InfoHash::from_bytes(&[0u8; 20])),
+            ip: Some(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1))),
+            reason: "spam".to_string(),
+        }
+    }
+
+    #[test]
+    fn it_should_not_apply_any_mitigation_by_default() {
+        let mitigations = Mitigations::default();
+
+        let applied = mitigations.apply(&Abuse::default(), &sample_report());
+
+        assert!(applied.is_empty());
+        assert!(!mitigations.is_ip_banned(&sample_report().ip.unwrap()));
+        assert!(!mitigations.is_info_hash_blacklisted(&sample_report().info_hash.unwrap()));
+    }
+
+    #[test]
+    fn it_should_apply_the_enabled_mitigations() {
+        let mitigations = Mitigations::default();
+        let config = Abuse {
+            ban_ip: true,
+            blacklist_info_hash: true,
+            raised_interval: Some(3600),
+            webhooks: vec![],
+        };
+        let report = sample_report();
+        let info_hash = report.info_hash.unwrap();
+        let ip = report.ip.unwrap();
+
+        let applied = mitigations.apply(&config, &report);
+
+        assert_eq!(
+            applied,
+            vec![
+                Mitigation::BanIp { ip },
+                Mitigation::BlacklistInfoHash { info_hash },
+                Mitigation::RaiseInterval {
+                    info_hash,
+                    interval: 3600
+                }
+            ]
+        );
+        assert!(mitigations.is_ip_banned(&ip));
+        assert!(mitigations.is_info_hash_blacklisted(&info_hash));
+        assert_eq!(
+            mitigations.announce_policy_for(&info_hash, AnnouncePolicy::new(120, 60)),
+            AnnouncePolicy::new(3600, 3600)
+        );
+    }
+
+    #[test]
+    fn it_should_skip_the_mitigations_that_do_not_apply_to_the_report() {
+        let mitigations = Mitigations::default();
+        let config = Abuse {
+            ban_ip: true,
+            blacklist_info_hash: true,
+            raised_interval: None,
+            webhooks: vec![],
+        };
+        let report = Report {
+            ip: None,
+            ..sample_report()
+        };
+
+        let applied = mitigations.apply(&config, &report);
+
+        assert_eq!(
+            applied,
+            vec![Mitigation::BlacklistInfoHash {
+                info_hash: report.info_hash.unwrap()
+            }]
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! Live feed of the tracker activity.
+//!
+//! The tracker publishes an [`Event`] for every announce request, every
+//! torrent scraped and every peer completing the download of a torrent. The
+//! events are sent to a bounded broadcast channel, so the subscribers that
+//! fall behind lose the oldest events instead of slowing down the tracker.
+//!
+//! The events are only built while there is at least one subscriber, so the
+//! feed has no cost when nobody is listening. It's used by the
+//! [events endpoint](crate::servers::apis::v1::context::events) of the API.
+use aquatic_udp_protocol::AnnounceEvent;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+/// Maximum number of events buffered for the subscribers. The subscribers
+/// lagging behind more than this number of events skip the oldest ones.
+pub const CHANNEL_CAPACITY: usize = 1024;
+
+/// The kind of activity.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    Announce,
+    Scrape,
+    Completed,
+}
+
+/// An event of the activity feed.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub kind: Kind,
+    pub info_hash: InfoHash,
+    /// The event of the announce request: `Started`, `Stopped`, `Completed`
+    /// or `None`. Only for the announce events.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub announce_event: Option<String>,
+    /// The number of seeders of the torrent, after the activity.
+    pub seeders: u32,
+    /// The number of leechers of the torrent, after the activity.
+    pub leechers: u32,
+    /// The number of peers that have ever completed downloading the torrent.
+    pub completed: u32,
+    /// Time of the activity, in seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl Event {
+    #[must_use]
+    pub fn new(
+        kind: Kind,
+        info_hash: &InfoHash,
+        announce_event: Option<AnnounceEvent>,
+        swarm_metadata: &SwarmMetadata,
+        now: DurationSinceUnixEpoch,
+    ) -> Self {
+        Self {
+            kind,
+            info_hash: *info_hash,
+            announce_event: announce_event.map(|event| format!("{event:?}")),
+            seeders: swarm_metadata.complete,
+            leechers: swarm_metadata.incomplete,
+            completed: swarm_metadata.downloaded,
+            timestamp: now.as_secs(),
+        }
+    }
+}
+
+/// The broadcast channel of the activity events.
+#[derive(Debug)]
+pub struct Feed {
+    sender: broadcast::Sender<Event>,
+}
+
+impl Default for Feed {
+    fn default() -> Self {
+        Self::new(CHANNEL_CAPACITY)
+    }
+}
+
+impl Feed {
+    /// # Panics
+    ///
+    /// Will panic if the `capacity` is zero.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _receiver) = broadcast::channel(capacity);
+
+        Self { sender }
+    }
+
+    /// It returns a receiver of the events published from now on.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+
+    /// It returns `true` if there is at least one subscriber. The events
+    /// don't need to be built otherwise.
+    #[must_use]
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    /// It sends the event to the subscribers, if any.
+    pub fn publish(&self, event: Event) {
+        // It only fails when there are no subscribers
+        drop(self.sender.send(event));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use torrust_tracker_primitives::info_hash::InfoHash;
+    use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
+
+    use super::{Event, Feed, Kind};
+
+    fn scrape_event() -> Event {
+        Event::new(
+            Kind::Scrape,
+            &"3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap(),
+            None,
+            &SwarmMetadata::zeroed(),
+            Duration::from_secs(1_700_000_000),
+        )
+    }
+
+    #[test]
+    fn it_should_send_the_events_to_the_subscribers() {
+        let feed = Feed::default();
+
+        assert!(!feed.has_subscribers());
+
+        let mut receiver = feed.subscribe();
+
+        assert!(feed.has_subscribers());
+
+        feed.publish(scrape_event());
+
+        assert_eq!(receiver.try_recv().unwrap(), scrape_event());
+    }
+
+    #[test]
+    fn it_should_drop_the_oldest_events_for_the_lagging_subscribers() {
+        let feed = Feed::new(1);
+
+        let mut receiver = feed.subscribe();
+
+        feed.publish(scrape_event());
+        feed.publish(scrape_event());
+
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(receiver.try_recv().unwrap(), scrape_event());
+    }
+}
@@ -0,0 +1,266 @@
+//! Alerts for the tracker administrators.
+//!
+//! Small deployments usually don't have a monitoring stack. The tracker can
+//! notify the administrators directly (by email or chat webhooks) when
+//! something critical happens:
+//!
+//! Alert | When
+//! ---|---
+//! [`Alert::DatabaseDown`] | The database can't be reached.
+//! [`Alert::DatabaseRecovered`] | The database can be reached again.
+//! [`Alert::CertificateExpiring`] | A TLS certificate expires within the configured number of days. It's repeated every day.
+//! [`Alert::ServicePanicked`] | A service panicked. Tokio tasks are not aborted by panics, so the other services keep running.
+//!
+//! The database and the certificates are checked periodically by the
+//! [`alerts`](crate::bootstrap::jobs::alerts) job. Refer to the
+//! [`Alerts`](torrust_tracker_configuration::Alerts) configuration section
+//! for the notification channels.
+use std::fs::File;
+use std::io::BufReader;
+use std::time::Duration;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use thiserror::Error;
+use torrust_tracker_clock::conv::convert_from_timestamp_to_datetime_utc;
+use torrust_tracker_configuration::{Configuration, TslConfig};
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// An alert for the administrators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Alert {
+    /// The database can't be reached.
+    DatabaseDown { error: String },
+    /// The database can be reached again.
+    DatabaseRecovered,
+    /// A TLS certificate is about to expire.
+    CertificateExpiring {
+        certificate: Certificate,
+        expires_at: DurationSinceUnixEpoch,
+        days_left: u64,
+    },
+    /// A service panicked.
+    ServicePanicked { thread: String, message: String },
+}
+
+impl Alert {
+    /// A one line summary of the alert. It's used as the subject of the
+    /// emails.
+    #[must_use]
+    pub fn subject(&self) -> String {
+        match self {
+            Alert::DatabaseDown { .. } => "[Torrust Tracker] Database down".to_string(),
+            Alert::DatabaseRecovered => "[Torrust Tracker] Database recovered".to_string(),
+            Alert::CertificateExpiring {
+                certificate, days_left, ..
+            } => format!(
+                "[Torrust Tracker] TLS certificate for the {} expires in {days_left} days",
+                certificate.service
+            ),
+            Alert::ServicePanicked { .. } => "[Torrust Tracker] Service panicked".to_string(),
+        }
+    }
+
+    /// The details of the alert.
+    #[must_use]
+    pub fn message(&self) -> String {
+        match self {
+            Alert::DatabaseDown { error } => format!("The tracker can't reach the database: {error}"),
+            Alert::DatabaseRecovered => "The tracker can reach the database again.".to_string(),
+            Alert::CertificateExpiring {
+                certificate, expires_at, ..
+            } => format!(
+                "The TLS certificate {} for the {} expires on {}.",
+                certificate.path,
+                certificate.service,
+                convert_from_timestamp_to_datetime_utc(*expires_at).to_rfc2822()
+            ),
+            Alert::ServicePanicked { thread, message } => format!("The thread {thread} panicked: {message}"),
+        }
+    }
+}
+
+/// A TLS certificate used by a service.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Certificate {
+    /// The service using the certificate, for example, `HTTP tracker 0.0.0.0:7070`.
+    pub service: String,
+    /// The path to the certificate file.
+    pub path: Utf8PathBuf,
+}
+
+/// Errors reading the expiration date of a certificate.
+#[derive(Error, Debug)]
+pub enum CertificateError {
+    #[error("unable to read the certificate {path}: {source}")]
+    Read { path: Utf8PathBuf, source: std::io::Error },
+
+    #[error("there is no certificate in {path}")]
+    Missing { path: Utf8PathBuf },
+
+    #[error("invalid certificate {path}: {message}")]
+    Invalid { path: Utf8PathBuf, message: String },
+}
+
+/// It returns the TLS certificates of the HTTP trackers and the tracker API,
+/// including the ones selected by the hostname of the clients (SNI).
+#[must_use]
+pub fn certificates(config: &Configuration) -> Vec<Certificate> {
+    let certificates = |service: String, tsl_config: &TslConfig| {
+        let sni_certificates = tsl_config.sni_certificates.iter().map(|sni_certificate| Certificate {
+            service: format!("{service} ({})", sni_certificate.hostname),
+            path: sni_certificate.ssl_cert_path.clone(),
+        });
+
+        std::iter::once(Certificate {
+            service: service.clone(),
+            path: tsl_config.ssl_cert_path.clone(),
+        })
+        .chain(sni_certificates)
+        .collect::<Vec<_>>()
+    };
+
+    let http_trackers = config.http_trackers.iter().flatten().flat_map(|http_tracker| {
+        http_tracker
+            .tsl_config
+            .as_ref()
+            .map(|tsl_config| certificates(format!("HTTP tracker {}", http_tracker.bind_address), tsl_config))
+            .unwrap_or_default()
+    });
+
+    let http_api = config.http_api.iter().flat_map(|http_api| {
+        http_api
+            .tsl_config
+            .as_ref()
+            .map(|tsl_config| certificates(format!("tracker API {}", http_api.bind_address), tsl_config))
+            .unwrap_or_default()
+    });
+
+    http_trackers.chain(http_api).collect()
+}
+
+/// It returns when the certificate expires. Only the first certificate in the
+/// file (the server certificate) is checked.
+///
+/// # Errors
+///
+/// Will return an error if the file can't be read or it does not contain a
+/// valid PEM certificate.
+pub fn expiration(path: &Utf8Path) -> Result<DurationSinceUnixEpoch, CertificateError> {
+    let file = File::open(path).map_err(|source| CertificateError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let der = rustls_pemfile::certs(&mut BufReader::new(file))
+        .next()
+        .ok_or_else(|| CertificateError::Missing {
+            path: path.to_path_buf(),
+        })?
+        .map_err(|source| CertificateError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+    let (_, certificate) = X509Certificate::from_der(der.as_ref()).map_err(|err| CertificateError::Invalid {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })?;
+
+    Ok(Duration::from_secs(
+        u64::try_from(certificate.validity().not_after.timestamp()).unwrap_or_default(),
+    ))
+}
+
+/// It returns the number of whole days left until the expiration date. It's
+/// `0` if the certificate has already expired.
+#[must_use]
+pub fn days_left(expires_at: DurationSinceUnixEpoch, now: DurationSinceUnixEpoch) -> u64 {
+    expires_at.saturating_sub(now).as_secs() / SECONDS_PER_DAY
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use torrust_tracker_configuration::{SniCertificate, TslConfig};
+    use torrust_tracker_test_helpers::configuration;
+
+    use super::{certificates, days_left, Alert, Certificate};
+
+    #[test]
+    fn it_should_count_the_whole_days_left_until_the_certificate_expires() {
+        let now = Duration::from_secs(1_000_000);
+
+        assert_eq!(days_left(now + Duration::from_secs(86_400 * 3 + 100), now), 3);
+        assert_eq!(days_left(now - Duration::from_secs(10), now), 0);
+    }
+
+    #[test]
+    fn it_should_list_the_certificates_of_the_http_trackers_and_the_tracker_api() {
+        let mut config = configuration::ephemeral();
+
+        let tsl_config = TslConfig {
+            ssl_cert_path: "./storage/tracker/lib/tls/localhost.crt".into(),
+            ..Default::default()
+        };
+
+        if let Some(ref mut http_api) = config.http_api {
+            http_api.tsl_config = Some(tsl_config.clone());
+        }
+
+        let certificates = certificates(&config);
+
+        assert_eq!(certificates.len(), 1);
+        assert!(certificates[0].service.starts_with("tracker API"));
+        assert_eq!(certificates[0].path, tsl_config.ssl_cert_path);
+    }
+
+    #[test]
+    fn it_should_list_the_sni_certificates_of_the_http_trackers() {
+        let mut config = configuration::ephemeral();
+
+        let sni_certificate = SniCertificate {
+            hostname: "tracker.example.com".to_string(),
+            ssl_cert_path: "./storage/tracker/lib/tls/tracker.example.com.crt".into(),
+            ssl_key_path: "./storage/tracker/lib/tls/tracker.example.com.key".into(),
+        };
+
+        if let Some(ref mut http_trackers) = config.http_trackers {
+            http_trackers[0].tsl_config = Some(TslConfig {
+                ssl_cert_path: "./storage/tracker/lib/tls/localhost.crt".into(),
+                sni_certificates: vec![sni_certificate.clone()],
+                ..Default::default()
+            });
+        }
+
+        let certificates = certificates(&config);
+
+        assert_eq!(certificates.len(), 2);
+        assert!(certificates[1].service.ends_with("(tracker.example.com)"));
+        assert_eq!(certificates[1].path, sni_certificate.ssl_cert_path);
+    }
+
+    #[test]
+    fn the_certificate_expiring_alert_should_include_the_service_and_the_expiration_date() {
+        let alert = Alert::CertificateExpiring {
+            certificate: Certificate {
+                service: "HTTP tracker 0.0.0.0:7070".to_string(),
+                path: "localhost.crt".into(),
+            },
+            expires_at: Duration::from_secs(0),
+            days_left: 3,
+        };
+
+        assert_eq!(
+            alert.subject(),
+            "[Torrust Tracker] TLS certificate for the HTTP tracker 0.0.0.0:7070 expires in 3 days"
+        );
+        assert_eq!(
+            alert.message(),
+            "The TLS certificate localhost.crt for the HTTP tracker 0.0.0.0:7070 expires on Thu, 1 Jan 1970 00:00:00 +0000."
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Global budget of announce requests per second.
+//!
+//! The budget is a token bucket shared by all the tracker services. Each
+//! accepted announce request takes one token, and the bucket is refilled at
+//! the configured rate. While there are plenty of tokens, every request is
+//! accepted. When the bucket drops below the saturation threshold, requests
+//! from IP addresses that have already used their fair share of the rate in
+//! the current second are rejected, so that the remaining tokens go to the
+//! light users instead of the sources flooding the tracker.
+//!
+//! Refer to the [`AnnounceBudget`] configuration for more information.
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use torrust_tracker_configuration::AnnounceBudget;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+/// Length of the window used to count the requests per IP address.
+const USAGE_WINDOW: Duration = Duration::from_secs(1);
+
+/// A token bucket with per-IP fairness.
+#[derive(Debug)]
+pub struct Budget {
+    /// Tokens added per second.
+    rate: f64,
+    /// Maximum number of tokens in the bucket.
+    burst: f64,
+    /// Number of tokens below which the bucket is saturated.
+    saturation: f64,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    tokens: f64,
+    last_refill: DurationSinceUnixEpoch,
+    window_start: DurationSinceUnixEpoch,
+    /// Accepted requests per IP address in the current window.
+    usage: HashMap<IpAddr, u32>,
+}
+
+impl Budget {
+    /// It builds the budget from the configuration. It returns `None` if the
+    /// budget is disabled.
+    #[must_use]
+    pub fn new(config: &AnnounceBudget, now: DurationSinceUnixEpoch) -> Option<Self> {
+        let rate = config.announces_per_second?;
+        let burst = config.burst.unwrap_or(rate);
+
+        Some(Self {
+            rate: f64::from(rate),
+            burst: f64::from(burst),
+            saturation: f64::from(burst) * f64::from(config.saturation_threshold) / 100.0,
+            state: Mutex::new(State {
+                tokens: f64::from(burst),
+                last_refill: now,
+                window_start: now,
+                usage: HashMap::new(),
+            }),
+        })
+    }
+
+    /// It takes a token for a request from the IP address. It returns `false`
+    /// if the request must be rejected.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the budget state is poisoned.
+    pub fn try_acquire(&self, ip: &IpAddr, now: DurationSinceUnixEpoch) -> bool {
+        let mut state = self.state.lock().expect("it should get a lock on the announce budget");
+
+        let elapsed = now.saturating_sub(state.last_refill);
+        state.tokens = (state.tokens + elapsed.as_secs_f64() * self.rate).min(self.burst);
+        state.last_refill = now;
+
+        if now.saturating_sub(state.window_start) >= USAGE_WINDOW {
+            state.usage.clear();
+            state.window_start = now;
+        }
+
+        if state.tokens < 1.0 {
+            return false;
+        }
+
+        let used = state.usage.get(ip).copied().unwrap_or_default();
+
+        if state.tokens < self.saturation && f64::from(used) >= self.fair_share(&state) {
+            return false;
+        }
+
+        state.tokens -= 1.0;
+        *state.usage.entry(*ip).or_default() += 1;
+
+        true
+    }
+
+    /// The requests per second each active IP address is entitled to.
+    #[allow(clippy::cast_precision_loss)]
+    fn fair_share(&self, state: &State) -> f64 {
+        self.rate / state.usage.len().max(1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Duration;
+
+    use torrust_tracker_configuration::AnnounceBudget;
+
+    use super::Budget;
+
+    fn ip(last_octet: u8) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(10, 0, 0, last_octet))
+    }
+
+    fn budget(announces_per_second: u32, saturation_threshold: u8) -> Budget {
+        let config = AnnounceBudget {
+            announces_per_second: Some(announces_per_second),
+            burst: None,
+            saturation_threshold,
+        };
+
+        Budget::new(&config, Duration::ZERO).unwrap()
+    }
+
+    #[test]
+    fn it_should_be_disabled_by_default() {
+        assert!(Budget::new(&AnnounceBudget::default(), Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn it_should_reject_requests_when_there_are_no_tokens_left() {
+        let budget = budget(2, 0);
+
+        assert!(budget.try_acquire(&ip(1), Duration::ZERO));
+        assert!(budget.try_acquire(&ip(1), Duration::ZERO));
+        assert!(!budget.try_acquire(&ip(2), Duration::ZERO));
+    }
+
+    #[test]
+    fn it_should_refill_the_tokens_over_time() {
+        let budget = budget(2, 0);
+
+        assert!(budget.try_acquire(&ip(1), Duration::ZERO));
+        assert!(budget.try_acquire(&ip(1), Duration::ZERO));
+        assert!(budget.try_acquire(&ip(1), Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn it_should_throttle_the_heavy_sources_first_when_the_budget_is_saturated() {
+        let budget = budget(10, 50);
+
+        // The heavy source uses the tokens above the saturation threshold
+        for _ in 0..5 {
+            assert!(budget.try_acquire(&ip(1), Duration::ZERO));
+        }
+
+        assert!(budget.try_acquire(&ip(2), Duration::ZERO));
+
+        // Fair share: 10 requests per second / 2 active IPs
+        assert!(!budget.try_acquire(&ip(1), Duration::ZERO));
+        assert!(budget.try_acquire(&ip(2), Duration::ZERO));
+        assert!(budget.try_acquire(&ip(3), Duration::ZERO));
+    }
+}
@@ -0,0 +1,26 @@
+//! Access tokens of the API rotated at runtime.
+//!
+//! The access tokens of the API are set in the configuration, but each of
+//! them can be replaced at runtime with the
+//! [`token`](crate::servers::apis::v1::context::token) API endpoint. The
+//! rotated tokens are saved to the database, so they survive a restart and
+//! they are shared by the tracker instances using the same database. The
+//! previous token is still accepted for the configured overlap period, to
+//! give the clients using it time to be updated.
+//!
+//! Only the tokens whose label is still in the configuration can be used.
+//! Removing a label from the configuration revokes its rotated token too.
+
+/// The token that replaced the configured access token of a label.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RotatedToken {
+    /// The label of the access token in the configuration.
+    pub label: String,
+    /// The current token.
+    pub token: String,
+    /// The token replaced by the current one.
+    pub previous_token: Option<String>,
+    /// Time until which the previous token is accepted, in seconds since the
+    /// Unix epoch.
+    pub previous_token_valid_until: u64,
+}
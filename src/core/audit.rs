@@ -0,0 +1,88 @@
+//! Audit log of the administrative actions.
+//!
+//! Every API request changing the tracker state (the `POST`, `PUT`, `PATCH`
+//! and `DELETE` requests) is recorded in the `audit_log` database table: who
+//! made it, from which IP address, the endpoint, a summary of the payload and
+//! the response status. Private trackers with several administrators can use
+//! it to find out who changed the whitelist, banned a client or issued a key.
+//!
+//! The entries are recorded by the [`audit`](crate::servers::apis::v1::middlewares::audit)
+//! middleware of the API, and they can be queried with the
+//! [`audit`](crate::servers::apis::v1::context::audit) API endpoint.
+use std::net::IpAddr;
+
+/// Maximum number of characters of the payload summary. The rest of the
+/// payload is not recorded.
+pub const MAX_PAYLOAD_SUMMARY_CHARS: usize = 256;
+
+/// An entry of the audit log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// Time of the request, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Who made the request: the label of the access token, or `jwt:` and
+    /// the subject of the JSON Web Token.
+    pub actor: String,
+    /// The IP address of the client, if it's known.
+    pub client_ip: Option<IpAddr>,
+    /// The HTTP method.
+    pub method: String,
+    /// The path and the query of the request, without the access token.
+    pub path: String,
+    /// The HTTP status code of the response.
+    pub status: u16,
+    /// The beginning of the request body, if it has one.
+    pub payload: Option<String>,
+}
+
+/// The criteria to select the audit log entries. All of them are optional.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Filter {
+    /// Only the entries of this actor.
+    pub actor: Option<String>,
+    /// Only the entries with this HTTP method.
+    pub method: Option<String>,
+    /// Only the entries whose path starts with this prefix.
+    pub path_prefix: Option<String>,
+    /// Only the entries recorded at this time or later, in seconds since the
+    /// Unix epoch.
+    pub from: Option<u64>,
+    /// Only the entries recorded at this time or earlier, in seconds since
+    /// the Unix epoch.
+    pub to: Option<u64>,
+}
+
+/// It returns the summary of a request body recorded in the audit log: the
+/// first [`MAX_PAYLOAD_SUMMARY_CHARS`] characters. It's `None` if the body is
+/// empty.
+#[must_use]
+pub fn payload_summary(body: &[u8]) -> Option<String> {
+    if body.is_empty() {
+        return None;
+    }
+
+    Some(
+        String::from_utf8_lossy(body)
+            .chars()
+            .take(MAX_PAYLOAD_SUMMARY_CHARS)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{payload_summary, MAX_PAYLOAD_SUMMARY_CHARS};
+
+    #[test]
+    fn it_should_truncate_the_payload_summary() {
+        assert_eq!(payload_summary(b""), None);
+        assert_eq!(
+            payload_summary(br#"{"seconds_valid":60}"#),
+            Some(r#"{"seconds_valid":60}"#.to_string())
+        );
+        assert_eq!(
+            payload_summary("x".repeat(1000).as_bytes()).map(|summary| summary.len()),
+            Some(MAX_PAYLOAD_SUMMARY_CHARS)
+        );
+    }
+}
@@ -20,6 +20,10 @@
 //!     pub key: Key,
 //!     /// Timestamp, the key will be no longer valid after this timestamp
 //!     pub valid_until: Option<DurationSinceUnixEpoch>,
+//!     /// Timestamp, when the key was created
+//!     pub created_at: Option<DurationSinceUnixEpoch>,
+//!     /// Who or what the key was issued for
+//!     pub label: Option<String>,
 //! }
 //! ```
 //!
@@ -85,6 +89,8 @@ pub fn generate_key(lifetime: Option<Duration>) -> PeerKey {
         PeerKey {
             key: random_id.parse::<Key>().unwrap(),
             valid_until: Some(CurrentClock::now_add(&lifetime).unwrap()),
+            created_at: Some(CurrentClock::now()),
+            label: None,
         }
     } else {
         tracing::debug!("Generated key: {}, permanent", random_id);
@@ -92,6 +98,8 @@ pub fn generate_key(lifetime: Option<Duration>) -> PeerKey {
         PeerKey {
             key: random_id.parse::<Key>().unwrap(),
             valid_until: None,
+            created_at: Some(CurrentClock::now()),
+            label: None,
         }
     }
 }
@@ -132,6 +140,16 @@ pub struct PeerKey {
     /// Timestamp, the key will be no longer valid after this timestamp.
     /// If `None` the keys will not expire (permanent key).
     pub valid_until: Option<DurationSinceUnixEpoch>,
+
+    /// Timestamp, when the key was created. It's `None` for the keys created
+    /// by versions of the tracker that didn't store it.
+    #[serde(default)]
+    pub created_at: Option<DurationSinceUnixEpoch>,
+
+    /// Optional label to attribute the key, for example, to the user of a
+    /// torrent index it was issued for.
+    #[serde(default)]
+    pub label: Option<String>,
 }
 
 impl std::fmt::Display for PeerKey {
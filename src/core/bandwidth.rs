@@ -0,0 +1,307 @@
+//! Bandwidth estimates from the announce requests.
+//!
+//! Peers report the total number of bytes they have uploaded and downloaded
+//! in every announce request. The difference between two successive announces
+//! of the same peer is the data it has transferred in between. The tracker
+//! adds up those deltas for each swarm and for all the torrents, and divides
+//! the bytes reported in the last complete window by the window length to get
+//! a throughput estimate.
+//!
+//! The window length is the announce interval, so every active peer is
+//! expected to announce once per window.
+//!
+//! > **NOTICE**: the estimates are only as accurate as the values reported by
+//! > the clients. The tracker does not verify them.
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use aquatic_udp_protocol::{AnnounceEvent, PeerId};
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
+
+/// Estimated throughput, in bytes per second.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Estimate {
+    /// Bytes uploaded per second by the peers.
+    pub uploaded_bytes_per_second: u64,
+    /// Bytes downloaded per second by the peers.
+    pub downloaded_bytes_per_second: u64,
+}
+
+/// Bytes transferred, reported or accumulated.
+#[derive(Copy, Clone, Debug, Default)]
+struct Transferred {
+    uploaded: u64,
+    downloaded: u64,
+}
+
+/// The last values reported by a peer.
+#[derive(Debug)]
+struct LastAnnounce {
+    transferred: Transferred,
+    updated: DurationSinceUnixEpoch,
+}
+
+/// Bytes reported in the current window and estimate from the previous one.
+///
+/// Windows are aligned to the Unix epoch, so all the swarms share the same
+/// windows.
+#[derive(Debug, Default)]
+struct Throughput {
+    /// Number of the current window since the Unix epoch.
+    window: u64,
+    current: Transferred,
+    estimate: Estimate,
+}
+
+impl Throughput {
+    fn starting_at(now: DurationSinceUnixEpoch, window: Duration) -> Self {
+        Self {
+            window: window_number(now, window),
+            ..Default::default()
+        }
+    }
+
+    /// It closes the current window if it has already ended.
+    fn roll(&mut self, now: DurationSinceUnixEpoch, window: Duration) {
+        let number = window_number(now, window);
+
+        if number <= self.window {
+            return;
+        }
+
+        // The estimate is zero if nothing was reported in the previous window.
+        self.estimate = if number == self.window + 1 {
+            Estimate {
+                uploaded_bytes_per_second: self.current.uploaded / window.as_secs().max(1),
+                downloaded_bytes_per_second: self.current.downloaded / window.as_secs().max(1),
+            }
+        } else {
+            Estimate::default()
+        };
+
+        self.current = Transferred::default();
+        self.window = number;
+    }
+
+    fn add(&mut self, delta: Transferred) {
+        self.current.uploaded = self.current.uploaded.saturating_add(delta.uploaded);
+        self.current.downloaded = self.current.downloaded.saturating_add(delta.downloaded);
+    }
+}
+
+fn window_number(now: DurationSinceUnixEpoch, window: Duration) -> u64 {
+    now.as_secs() / window.as_secs().max(1)
+}
+
+#[derive(Debug)]
+struct State {
+    peers: HashMap<(InfoHash, PeerId), LastAnnounce>,
+    swarms: HashMap<InfoHash, Throughput>,
+    global: Throughput,
+}
+
+/// Bandwidth estimator for all the swarms.
+#[derive(Debug)]
+pub struct Estimator {
+    window: Duration,
+    state: Mutex<State>,
+}
+
+impl Estimator {
+    #[must_use]
+    pub fn new(window: Duration, now: DurationSinceUnixEpoch) -> Self {
+        Self {
+            window,
+            state: Mutex::new(State {
+                peers: HashMap::new(),
+                swarms: HashMap::new(),
+                global: Throughput::starting_at(now, window),
+            }),
+        }
+    }
+
+    /// It adds the bytes transferred by the peer since its previous announce
+    /// to the swarm and global throughput.
+    ///
+    /// The first announce of a peer does not count. A counter lower than the
+    /// previous one (for example, after the client was restarted) is not
+    /// counted either.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the estimator state is poisoned.
+    pub fn record(&self, info_hash: &InfoHash, peer: &peer::Peer, now: DurationSinceUnixEpoch) {
+        let mut state = self.state.lock().expect("it should get a lock on the bandwidth estimator");
+
+        let key = (*info_hash, peer.peer_id);
+
+        let reported = Transferred {
+            uploaded: u64::try_from(peer.uploaded.0.get()).unwrap_or_default(),
+            downloaded: u64::try_from(peer.downloaded.0.get()).unwrap_or_default(),
+        };
+
+        let previous = if peer.event == AnnounceEvent::Stopped {
+            state.peers.remove(&key)
+        } else {
+            state.peers.insert(
+                key,
+                LastAnnounce {
+                    transferred: reported,
+                    updated: now,
+                },
+            )
+        };
+
+        let Some(previous) = previous else {
+            return;
+        };
+
+        let delta = Transferred {
+            uploaded: reported.uploaded.saturating_sub(previous.transferred.uploaded),
+            downloaded: reported.downloaded.saturating_sub(previous.transferred.downloaded),
+        };
+
+        state.global.roll(now, self.window);
+        state.global.add(delta);
+
+        let swarm = state
+            .swarms
+            .entry(*info_hash)
+            .or_insert_with(|| Throughput::starting_at(now, self.window));
+        swarm.roll(now, self.window);
+        swarm.add(delta);
+    }
+
+    /// It returns the throughput estimate for all the torrents.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the estimator state is poisoned.
+    #[must_use]
+    pub fn global_estimate(&self, now: DurationSinceUnixEpoch) -> Estimate {
+        let mut state = self.state.lock().expect("it should get a lock on the bandwidth estimator");
+
+        state.global.roll(now, self.window);
+        state.global.estimate
+    }
+
+    /// It returns the throughput estimate for one swarm.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the estimator state is poisoned.
+    #[must_use]
+    pub fn swarm_estimate(&self, info_hash: &InfoHash, now: DurationSinceUnixEpoch) -> Estimate {
+        let mut state = self.state.lock().expect("it should get a lock on the bandwidth estimator");
+
+        match state.swarms.get_mut(info_hash) {
+            Some(swarm) => {
+                swarm.roll(now, self.window);
+                swarm.estimate
+            }
+            None => Estimate::default(),
+        }
+    }
+
+    /// It forgets the peers that have not announced since the cutoff, and the
+    /// swarms without peers.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the estimator state is poisoned.
+    pub fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) {
+        let mut state = self.state.lock().expect("it should get a lock on the bandwidth estimator");
+
+        state.peers.retain(|_, last_announce| last_announce.updated > current_cutoff);
+
+        let active_swarms: HashSet<InfoHash> = state.peers.keys().map(|(info_hash, _)| *info_hash).collect();
+
+        state.swarms.retain(|info_hash, _| active_swarms.contains(info_hash));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::time::Duration;
+
+    use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
+    use torrust_tracker_primitives::info_hash::InfoHash;
+    use torrust_tracker_primitives::peer;
+
+    use super::{Estimate, Estimator};
+
+    const WINDOW: Duration = Duration::from_secs(100);
+
+    fn info_hash() -> InfoHash {
+        "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()
+    }
+
+    fn peer(id: u8, uploaded: i64, downloaded: i64, event: AnnounceEvent) -> peer::Peer {
+        peer::Peer {
+            peer_id: PeerId([id; 20]),
+            peer_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, id)), 8080),
+            updated: Duration::ZERO,
+            uploaded: NumberOfBytes::new(uploaded),
+            downloaded: NumberOfBytes::new(downloaded),
+            left: NumberOfBytes::new(0),
+            event,
+            timeout_extension: Duration::ZERO,
+            alt_peer_addr: None,
+        }
+    }
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn it_should_estimate_the_throughput_from_the_deltas_reported_in_the_previous_window() {
+        let estimator = Estimator::new(WINDOW, secs(0));
+
+        estimator.record(&info_hash(), &peer(1, 1_000, 5_000, AnnounceEvent::Started), secs(10));
+        estimator.record(&info_hash(), &peer(2, 0, 0, AnnounceEvent::Started), secs(10));
+        estimator.record(&info_hash(), &peer(1, 11_000, 25_000, AnnounceEvent::None), secs(50));
+        estimator.record(&info_hash(), &peer(2, 10_000, 0, AnnounceEvent::None), secs(60));
+
+        let expected = Estimate {
+            uploaded_bytes_per_second: 200,
+            downloaded_bytes_per_second: 200,
+        };
+
+        assert_eq!(estimator.global_estimate(secs(100)), expected);
+        assert_eq!(estimator.swarm_estimate(&info_hash(), secs(100)), expected);
+    }
+
+    #[test]
+    fn it_should_not_count_the_first_announce_of_a_peer() {
+        let estimator = Estimator::new(WINDOW, secs(0));
+
+        estimator.record(&info_hash(), &peer(1, 1_000, 5_000, AnnounceEvent::Started), secs(10));
+
+        assert_eq!(estimator.global_estimate(secs(100)), Estimate::default());
+    }
+
+    #[test]
+    fn it_should_not_count_counters_lower_than_the_previous_ones() {
+        let estimator = Estimator::new(WINDOW, secs(0));
+
+        estimator.record(&info_hash(), &peer(1, 1_000, 5_000, AnnounceEvent::Started), secs(10));
+        estimator.record(&info_hash(), &peer(1, 0, 0, AnnounceEvent::Started), secs(20));
+
+        assert_eq!(estimator.global_estimate(secs(100)), Estimate::default());
+    }
+
+    #[test]
+    fn it_should_forget_the_peers_that_stopped() {
+        let estimator = Estimator::new(WINDOW, secs(0));
+
+        estimator.record(&info_hash(), &peer(1, 0, 0, AnnounceEvent::Started), secs(10));
+        estimator.record(&info_hash(), &peer(1, 0, 0, AnnounceEvent::Stopped), secs(20));
+        estimator.record(&info_hash(), &peer(1, 10_000, 0, AnnounceEvent::Started), secs(30));
+
+        assert_eq!(estimator.global_estimate(secs(100)), Estimate::default());
+    }
+}
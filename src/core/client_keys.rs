@@ -0,0 +1,134 @@
+//! Clients recognized by the `key` param of the HTTP announce requests.
+//!
+//! [BEP 03](https://www.bittorrent.org/beps/bep_0003.html) describes the `key`
+//! as an additional identification that is not shared with any other peers,
+//! intended to allow a client to prove its identity should its IP address
+//! change.
+//!
+//! Peers are identified by their peer ID in the swarms, but some clients
+//! announce with a new peer ID after a network change, for example when a
+//! mobile device switches from Wi-Fi to LTE. Without the key the tracker
+//! would keep the previous peer until it times out: a ghost peer returned to
+//! the other clients, which can't connect to it. With the key the tracker
+//! knows it's the same client, and the previous peer is removed.
+//!
+//! > **NOTICE**: it's not an authentication key. Authentication keys are sent
+//! > in the path of the URL.
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use aquatic_udp_protocol::PeerId;
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+/// The last peer announced by a client.
+#[derive(Debug)]
+struct LastPeer {
+    peer_id: PeerId,
+    updated: DurationSinceUnixEpoch,
+}
+
+/// The last peer announced with each client key, by torrent.
+#[derive(Debug, Default)]
+pub struct ClientKeys {
+    peers: Mutex<HashMap<(InfoHash, String), LastPeer>>,
+}
+
+impl ClientKeys {
+    /// It records the peer announced by the client with the `key`. It returns
+    /// the peer ID of the previous peer announced with the same key, if any.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the client keys is poisoned.
+    pub fn record(&self, info_hash: &InfoHash, key: &str, peer_id: PeerId, now: DurationSinceUnixEpoch) -> Option<PeerId> {
+        self.peers
+            .lock()
+            .expect("it should get a lock on the client keys")
+            .insert((*info_hash, key.to_owned()), LastPeer { peer_id, updated: now })
+            .map(|previous| previous.peer_id)
+    }
+
+    /// It forgets the client with the `key`, for example when it leaves the
+    /// swarm.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the client keys is poisoned.
+    pub fn remove(&self, info_hash: &InfoHash, key: &str) {
+        self.peers
+            .lock()
+            .expect("it should get a lock on the client keys")
+            .remove(&(*info_hash, key.to_owned()));
+    }
+
+    /// It forgets the clients that have not announced since the
+    /// `current_cutoff`, like the inactive peers.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the client keys is poisoned.
+    pub fn remove_inactive(&self, current_cutoff: DurationSinceUnixEpoch) {
+        self.peers
+            .lock()
+            .expect("it should get a lock on the client keys")
+            .retain(|_, last_peer| last_peer.updated > current_cutoff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aquatic_udp_protocol::PeerId;
+    use torrust_tracker_primitives::info_hash::InfoHash;
+
+    use super::ClientKeys;
+
+    fn sample_info_hash() -> InfoHash {
+        "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()
+    }
+
+    #[test]
+    fn it_should_return_the_previous_peer_announced_with_the_same_key() {
+        let client_keys = ClientKeys::default();
+
+        let previous = client_keys.record(
+            &sample_info_hash(),
+            "1A2B3C4D",
+            PeerId(*b"-qB00000000000000001"),
+            Duration::ZERO,
+        );
+        assert_eq!(previous, None);
+
+        let previous = client_keys.record(
+            &sample_info_hash(),
+            "1A2B3C4D",
+            PeerId(*b"-qB00000000000000002"),
+            Duration::from_secs(1),
+        );
+        assert_eq!(previous, Some(PeerId(*b"-qB00000000000000001")));
+    }
+
+    #[test]
+    fn it_should_forget_the_clients_that_have_not_announced_since_the_cutoff() {
+        let client_keys = ClientKeys::default();
+
+        client_keys.record(
+            &sample_info_hash(),
+            "1A2B3C4D",
+            PeerId(*b"-qB00000000000000001"),
+            Duration::ZERO,
+        );
+
+        client_keys.remove_inactive(Duration::from_secs(1));
+
+        let previous = client_keys.record(
+            &sample_info_hash(),
+            "1A2B3C4D",
+            PeerId(*b"-qB00000000000000002"),
+            Duration::from_secs(2),
+        );
+        assert_eq!(previous, None);
+    }
+}
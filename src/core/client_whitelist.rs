@@ -0,0 +1,141 @@
+//! BitTorrent clients allowed to announce to a private tracker.
+//!
+//! Clients are identified by the prefix of their peer ID. Most clients use
+//! the [Azureus-style](https://www.bittorrent.org/beps/bep_0020.html)
+//! convention: a dash, two characters for the client and four for the
+//! version. For example, `-qB4520-` for qBittorrent 4.5.2. A prefix like
+//! `-qB45` allows all the 4.5.x versions of qBittorrent.
+//!
+//! The initial prefixes are loaded from the
+//! [`ClientWhitelist`](torrust_tracker_configuration::ClientWhitelist)
+//! configuration, and they can be changed at runtime with the
+//! [`client_whitelist`](crate::servers::apis::v1::context::client_whitelist)
+//! API context.
+//!
+//! An empty whitelist allows all the clients.
+//!
+//! > **NOTICE**: changes made at runtime are only kept in memory. They are
+//! > lost when the tracker is restarted.
+use std::collections::BTreeSet;
+use std::sync::RwLock;
+
+use aquatic_udp_protocol::PeerId;
+use torrust_tracker_configuration::ClientWhitelist;
+
+/// The maximum length of a prefix: the length of a peer ID.
+const MAX_PREFIX_LENGTH: usize = 20;
+
+/// The peer ID prefixes of the allowed clients.
+#[derive(Debug, Default)]
+pub struct Whitelist {
+    prefixes: RwLock<BTreeSet<String>>,
+}
+
+impl Whitelist {
+    #[must_use]
+    pub fn new(config: &ClientWhitelist) -> Self {
+        Self {
+            prefixes: RwLock::new(config.peer_id_prefixes.iter().cloned().collect()),
+        }
+    }
+
+    /// It adds a prefix. It returns `false` if the prefix is not valid: it
+    /// must have between 1 and 20 ASCII characters.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the prefixes is poisoned.
+    pub fn add(&self, prefix: &str) -> bool {
+        if !is_valid_prefix(prefix) {
+            return false;
+        }
+
+        self.prefixes
+            .write()
+            .expect("it should get a lock on the client whitelist")
+            .insert(prefix.to_owned());
+
+        true
+    }
+
+    /// It returns `true` if the prefix was in the whitelist.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the prefixes is poisoned.
+    pub fn remove(&self, prefix: &str) -> bool {
+        self.prefixes
+            .write()
+            .expect("it should get a lock on the client whitelist")
+            .remove(prefix)
+    }
+
+    /// It returns all the prefixes in alphabetical order.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the prefixes is poisoned.
+    #[must_use]
+    pub fn get_all(&self) -> Vec<String> {
+        self.prefixes
+            .read()
+            .expect("it should get a lock on the client whitelist")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// It returns `true` if the peer ID starts with any of the prefixes, or if
+    /// the whitelist is empty.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the prefixes is poisoned.
+    #[must_use]
+    pub fn is_allowed(&self, peer_id: &PeerId) -> bool {
+        let prefixes = self.prefixes.read().expect("it should get a lock on the client whitelist");
+
+        prefixes.is_empty() || prefixes.iter().any(|prefix| peer_id.0.starts_with(prefix.as_bytes()))
+    }
+}
+
+fn is_valid_prefix(prefix: &str) -> bool {
+    (1..=MAX_PREFIX_LENGTH).contains(&prefix.len()) && prefix.is_ascii()
+}
+
+#[cfg(test)]
+mod tests {
+    use aquatic_udp_protocol::PeerId;
+    use torrust_tracker_configuration::ClientWhitelist;
+
+    use super::Whitelist;
+
+    fn whitelist(peer_id_prefixes: &[&str]) -> Whitelist {
+        Whitelist::new(&ClientWhitelist {
+            peer_id_prefixes: peer_id_prefixes.iter().map(ToString::to_string).collect(),
+        })
+    }
+
+    #[test]
+    fn it_should_allow_all_the_clients_when_it_is_empty() {
+        assert!(whitelist(&[]).is_allowed(&PeerId(*b"-qB00000000000000001")));
+    }
+
+    #[test]
+    fn it_should_only_allow_the_clients_whose_peer_id_starts_with_a_whitelisted_prefix() {
+        let whitelist = whitelist(&["-qB45", "-TR40"]);
+
+        assert!(whitelist.is_allowed(&PeerId(*b"-qB4520-000000000001")));
+        assert!(whitelist.is_allowed(&PeerId(*b"-TR4000-000000000001")));
+        assert!(!whitelist.is_allowed(&PeerId(*b"-qB4430-000000000001")));
+    }
+
+    #[test]
+    fn it_should_not_add_prefixes_that_are_empty_or_longer_than_a_peer_id() {
+        let whitelist = whitelist(&[]);
+
+        assert!(!whitelist.add(""));
+        assert!(!whitelist.add(&"a".repeat(21)));
+        assert!(whitelist.get_all().is_empty());
+    }
+}
@@ -53,6 +53,29 @@ pub enum Error {
         source: LocatedError<'static, r2d2::Error>,
         driver: Driver,
     },
+
+    /// Unable to read the key of the encrypted database
+    #[error("Failed to read the {driver} database encryption key: {source}")]
+    EncryptionKey {
+        source: LocatedError<'static, dyn std::error::Error + Send + Sync>,
+        driver: Driver,
+    },
+
+    /// The tracker was built without support for encrypted databases
+    #[error("The {driver} database can't be encrypted: the tracker was built without the `sqlcipher` feature")]
+    EncryptionUnavailable { driver: Driver },
+
+    /// The task running the query on the blocking threads was cancelled or
+    /// panicked.
+    #[error("The {driver} query did not finish: {source}")]
+    QueryAborted {
+        source: LocatedError<'static, dyn std::error::Error + Send + Sync>,
+        driver: Driver,
+    },
+
+    /// The database schema is older than the one of the tracker.
+    #[error("The {driver} database schema version is {version}, but the tracker requires version {latest}. Run `torrust-tracker migrate` or enable `auto_migrate`")]
+    PendingMigrations { version: u32, latest: u32, driver: Driver },
 }
 
 impl From<r2d2_sqlite::rusqlite::Error> for Error {
@@ -102,3 +125,14 @@ impl From<(r2d2::Error, Driver)> for Error {
         }
     }
 }
+
+impl From<(tokio::task::JoinError, Driver)> for Error {
+    #[track_caller]
+    fn from(e: (tokio::task::JoinError, Driver)) -> Self {
+        let (err, driver) = e;
+        Self::QueryAborted {
+            source: (Arc::new(err) as DynError).into(),
+            driver,
+        }
+    }
+}
@@ -0,0 +1,385 @@
+//! The in-memory database driver.
+//!
+//! The tables are kept in the memory of the process, so nothing is persisted:
+//! the data is lost when the tracker stops. It's meant for tests and for
+//! stateless deployments, for example, containers without volumes.
+//!
+//! It has the same semantics as the other drivers, except for the schema: it
+//! has no [migrations](crate::core::databases::migrations) and the timestamps
+//! of the keys are truncated to seconds, like the columns of the other
+//! drivers.
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::panic::Location;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use torrust_tracker_configuration::DatabasePool;
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::pagination::Pagination;
+use torrust_tracker_primitives::{DurationSinceUnixEpoch, PersistentTorrents};
+
+use super::driver::Driver;
+use super::migrations::Migration;
+use super::{Database, Error, PoolStats};
+use crate::core::api_tokens::RotatedToken;
+use crate::core::audit;
+use crate::core::auth::{self, Key};
+use crate::core::torrent_history::Bucket;
+
+const DRIVER: Driver = Driver::Memory;
+
+#[derive(Default)]
+struct Tables {
+    whitelist: BTreeSet<InfoHash>,
+    torrents: PersistentTorrents,
+    torrent_history: BTreeMap<(InfoHash, u64), Bucket>,
+    keys: HashMap<Key, auth::PeerKey>,
+    /// The entries in the order they were added.
+    audit_log: Vec<audit::Entry>,
+    api_tokens: BTreeMap<String, RotatedToken>,
+}
+
+#[derive(Default)]
+pub struct Memory {
+    tables: Mutex<Tables>,
+}
+
+impl Memory {
+    fn tables(&self) -> MutexGuard<'_, Tables> {
+        self.tables.lock().expect("it should get a lock on the in-memory database")
+    }
+}
+
+#[async_trait]
+impl Database for Memory {
+    /// It instantiates a new in-memory database driver. The path, the pool
+    /// and the table prefix are ignored.
+    ///
+    /// Refer to [`databases::Database::new`](crate::core::databases::Database::new).
+    fn new(_db_path: &str, _pool: &DatabasePool, _table_prefix: &str) -> Result<Memory, Error> {
+        Ok(Memory::default())
+    }
+
+    /// Refer to [`databases::Database::pool_stats`](crate::core::databases::Database::pool_stats).
+    fn pool_stats(&self) -> PoolStats {
+        PoolStats::default()
+    }
+
+    /// The in-memory database has no migrations, so its version is always
+    /// `0`.
+    ///
+    /// Refer to [`databases::Database::schema_version`](crate::core::databases::Database::schema_version).
+    fn schema_version(&self) -> Result<u32, Error> {
+        Ok(0)
+    }
+
+    /// Refer to [`databases::Database::migrate`](crate::core::databases::Database::migrate).
+    fn migrate(&self) -> Result<Vec<&'static Migration>, Error> {
+        Ok(vec![])
+    }
+
+    /// It empties the tables.
+    ///
+    /// Refer to [`databases::Database::drop_database_tables`](crate::core::databases::Database::drop_database_tables).
+    fn drop_database_tables(&self) -> Result<(), Error> {
+        *self.tables() = Tables::default();
+
+        Ok(())
+    }
+
+    /// Refer to [`databases::Database::ping`](crate::core::databases::Database::ping).
+    async fn ping(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Refer to [`databases::Database::load_persistent_torrents`](crate::core::databases::Database::load_persistent_torrents).
+    async fn load_persistent_torrents(&self) -> Result<PersistentTorrents, Error> {
+        Ok(self.tables().torrents.clone())
+    }
+
+    /// Refer to [`databases::Database::save_persistent_torrent`](crate::core::databases::Database::save_persistent_torrent).
+    async fn save_persistent_torrent(&self, info_hash: &InfoHash, completed: u32) -> Result<(), Error> {
+        self.tables().torrents.insert(*info_hash, completed);
+
+        Ok(())
+    }
+
+    /// Refer to [`databases::Database::save_persistent_torrents`](crate::core::databases::Database::save_persistent_torrents).
+    async fn save_persistent_torrents(&self, torrents: &PersistentTorrents) -> Result<(), Error> {
+        self.tables()
+            .torrents
+            .extend(torrents.iter().map(|(info_hash, completed)| (*info_hash, *completed)));
+
+        Ok(())
+    }
+
+    /// Refer to [`databases::Database::remove_persistent_torrent`](crate::core::databases::Database::remove_persistent_torrent).
+    async fn remove_persistent_torrent(&self, info_hash: &InfoHash) -> Result<(), Error> {
+        self.tables().torrents.remove(info_hash);
+
+        Ok(())
+    }
+
+    /// Refer to [`databases::Database::load_torrent_history`](crate::core::databases::Database::load_torrent_history).
+    async fn load_torrent_history(&self, info_hash: &InfoHash, from: u64, to: u64) -> Result<Vec<Bucket>, Error> {
+        if from > to {
+            return Ok(vec![]);
+        }
+
+        Ok(self
+            .tables()
+            .torrent_history
+            .range((*info_hash, from)..=(*info_hash, to))
+            .map(|(_, bucket)| *bucket)
+            .collect())
+    }
+
+    /// Refer to [`databases::Database::save_torrent_history`](crate::core::databases::Database::save_torrent_history).
+    async fn save_torrent_history(&self, info_hash: &InfoHash, bucket: &Bucket) -> Result<(), Error> {
+        self.tables()
+            .torrent_history
+            .entry((*info_hash, bucket.start))
+            .and_modify(|saved| {
+                saved.completed += bucket.completed;
+                saved.peak_seeders = saved.peak_seeders.max(bucket.peak_seeders);
+                saved.peak_leechers = saved.peak_leechers.max(bucket.peak_leechers);
+            })
+            .or_insert(*bucket);
+
+        Ok(())
+    }
+
+    /// Refer to [`databases::Database::load_whitelist`](crate::core::databases::Database::load_whitelist).
+    async fn load_whitelist(&self) -> Result<Vec<InfoHash>, Error> {
+        Ok(self.tables().whitelist.iter().copied().collect())
+    }
+
+    /// Refer to [`databases::Database::get_info_hash_from_whitelist`](crate::core::databases::Database::get_info_hash_from_whitelist).
+    async fn get_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<Option<InfoHash>, Error> {
+        Ok(self.tables().whitelist.get(&info_hash).copied())
+    }
+
+    /// Refer to [`databases::Database::add_info_hash_to_whitelist`](crate::core::databases::Database::add_info_hash_to_whitelist).
+    async fn add_info_hash_to_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error> {
+        if self.tables().whitelist.insert(info_hash) {
+            Ok(1)
+        } else {
+            Err(Error::InsertFailed {
+                location: Location::caller(),
+                driver: DRIVER,
+            })
+        }
+    }
+
+    /// Refer to [`databases::Database::remove_info_hash_from_whitelist`](crate::core::databases::Database::remove_info_hash_from_whitelist).
+    async fn remove_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error> {
+        if self.tables().whitelist.remove(&info_hash) {
+            Ok(1)
+        } else {
+            Err(Error::DeleteFailed {
+                location: Location::caller(),
+                error_code: 0,
+                driver: DRIVER,
+            })
+        }
+    }
+
+    /// Refer to [`databases::Database::load_keys`](crate::core::databases::Database::load_keys).
+    async fn load_keys(&self) -> Result<Vec<auth::PeerKey>, Error> {
+        Ok(self.tables().keys.values().cloned().collect())
+    }
+
+    /// Refer to [`databases::Database::get_key_from_keys`](crate::core::databases::Database::get_key_from_keys).
+    async fn get_key_from_keys(&self, key: &Key) -> Result<Option<auth::PeerKey>, Error> {
+        Ok(self.tables().keys.get(key).cloned())
+    }
+
+    /// Refer to [`databases::Database::add_key_to_keys`](crate::core::databases::Database::add_key_to_keys).
+    async fn add_key_to_keys(&self, auth_key: &auth::PeerKey) -> Result<usize, Error> {
+        let mut tables = self.tables();
+
+        if tables.keys.contains_key(&auth_key.key) {
+            return Err(Error::InsertFailed {
+                location: Location::caller(),
+                driver: DRIVER,
+            });
+        }
+
+        let auth_key = auth::PeerKey {
+            valid_until: auth_key.valid_until.map(truncate_to_seconds),
+            created_at: auth_key.created_at.map(truncate_to_seconds),
+            ..auth_key.clone()
+        };
+
+        tables.keys.insert(auth_key.key.clone(), auth_key);
+
+        Ok(1)
+    }
+
+    /// Refer to [`databases::Database::remove_key_from_keys`](crate::core::databases::Database::remove_key_from_keys).
+    async fn remove_key_from_keys(&self, key: &Key) -> Result<usize, Error> {
+        if self.tables().keys.remove(key).is_some() {
+            Ok(1)
+        } else {
+            Err(Error::DeleteFailed {
+                location: Location::caller(),
+                error_code: 0,
+                driver: DRIVER,
+            })
+        }
+    }
+
+    /// Refer to [`databases::Database::remove_expired_keys`](crate::core::databases::Database::remove_expired_keys).
+    async fn remove_expired_keys(&self, now: DurationSinceUnixEpoch) -> Result<usize, Error> {
+        let now = truncate_to_seconds(now);
+
+        let mut tables = self.tables();
+
+        let before = tables.keys.len();
+
+        tables
+            .keys
+            .retain(|_, auth_key| auth_key.valid_until.map_or(true, |valid_until| valid_until >= now));
+
+        Ok(before - tables.keys.len())
+    }
+
+    /// Refer to [`databases::Database::add_audit_entry`](crate::core::databases::Database::add_audit_entry).
+    async fn add_audit_entry(&self, entry: &audit::Entry) -> Result<(), Error> {
+        self.tables().audit_log.push(entry.clone());
+
+        Ok(())
+    }
+
+    /// Refer to [`databases::Database::load_audit_entries`](crate::core::databases::Database::load_audit_entries).
+    async fn load_audit_entries(&self, filter: &audit::Filter, pagination: &Pagination) -> Result<Vec<audit::Entry>, Error> {
+        Ok(self
+            .tables()
+            .audit_log
+            .iter()
+            .rev()
+            .filter(|entry| filter.actor.as_ref().map_or(true, |actor| entry.actor == *actor))
+            .filter(|entry| filter.method.as_ref().map_or(true, |method| entry.method == *method))
+            .filter(|entry| {
+                filter
+                    .path_prefix
+                    .as_ref()
+                    .map_or(true, |prefix| entry.path.starts_with(prefix))
+            })
+            .filter(|entry| filter.from.map_or(true, |from| entry.timestamp >= from))
+            .filter(|entry| filter.to.map_or(true, |to| entry.timestamp <= to))
+            .skip(pagination.offset as usize)
+            .take(pagination.limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    /// Refer to [`databases::Database::load_rotated_api_tokens`](crate::core::databases::Database::load_rotated_api_tokens).
+    async fn load_rotated_api_tokens(&self) -> Result<Vec<RotatedToken>, Error> {
+        Ok(self.tables().api_tokens.values().cloned().collect())
+    }
+
+    /// Refer to [`databases::Database::save_rotated_api_token`](crate::core::databases::Database::save_rotated_api_token).
+    async fn save_rotated_api_token(&self, rotated_token: &RotatedToken) -> Result<(), Error> {
+        self.tables()
+            .api_tokens
+            .insert(rotated_token.label.clone(), rotated_token.clone());
+
+        Ok(())
+    }
+
+    /// There is nothing to compact in memory.
+    ///
+    /// Refer to [`databases::Database::compact`](crate::core::databases::Database::compact).
+    async fn compact(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+fn truncate_to_seconds(duration: Duration) -> Duration {
+    Duration::from_secs(duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use torrust_tracker_primitives::info_hash::InfoHash;
+
+    use super::Memory;
+    use crate::core::auth::PeerKey;
+    use crate::core::databases::Database;
+    use crate::core::torrent_history::Bucket;
+
+    fn sample_info_hash() -> InfoHash {
+        "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_adding_a_torrent_to_the_whitelist_twice() {
+        let database = Memory::default();
+
+        assert!(database.add_info_hash_to_whitelist(sample_info_hash()).await.is_ok());
+        assert!(database.add_info_hash_to_whitelist(sample_info_hash()).await.is_err());
+        assert_eq!(database.load_whitelist().await.unwrap(), vec![sample_info_hash()]);
+    }
+
+    #[tokio::test]
+    async fn it_should_only_remove_the_keys_that_expired() {
+        let database = Memory::default();
+
+        let expiring_key = |key: &str, valid_until: Option<u64>| PeerKey {
+            key: key.parse().unwrap(),
+            valid_until: valid_until.map(Duration::from_secs),
+            created_at: None,
+            label: None,
+        };
+
+        database
+            .add_key_to_keys(&expiring_key("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ", Some(100))) // cspell:disable-line
+            .await
+            .unwrap();
+        database
+            .add_key_to_keys(&expiring_key("xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6", Some(200))) // cspell:disable-line
+            .await
+            .unwrap();
+        database
+            .add_key_to_keys(&expiring_key("C2ktyP7B1xwbV5ju2xEYrPqEEHmtRy7A", None)) // cspell:disable-line
+            .await
+            .unwrap();
+
+        assert_eq!(database.remove_expired_keys(Duration::from_secs(150)).await.unwrap(), 1);
+        assert_eq!(database.load_keys().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_merge_the_buckets_of_the_same_hour_of_the_torrent_history() {
+        let database = Memory::default();
+
+        let bucket = |start: u64, completed: u32, peak_seeders: u32| Bucket {
+            start,
+            completed,
+            peak_seeders,
+            peak_leechers: 0,
+        };
+
+        database
+            .save_torrent_history(&sample_info_hash(), &bucket(3600, 1, 5))
+            .await
+            .unwrap();
+        database
+            .save_torrent_history(&sample_info_hash(), &bucket(3600, 2, 3))
+            .await
+            .unwrap();
+        database
+            .save_torrent_history(&sample_info_hash(), &bucket(7200, 1, 1))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            database.load_torrent_history(&sample_info_hash(), 0, 3600).await.unwrap(),
+            vec![bucket(3600, 3, 5)]
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! Latency and errors of the database queries.
+//!
+//! The drivers built by the [`driver`](crate::core::databases::driver) factory
+//! are wrapped in an [`Instrumented`] driver, which measures each call to the
+//! [`Database`] trait: the number of queries, the number of failed ones, and
+//! the total and maximum time they took. The statistics are kept by operation
+//! (the name of the trait method) since the tracker started.
+//!
+//! They tell whether the database is the bottleneck when the tracker is slow.
+//! The time includes the wait for a connection of the pool, so a saturated
+//! pool also shows up as slow queries.
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use torrust_tracker_configuration::DatabasePool;
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::pagination::Pagination;
+use torrust_tracker_primitives::{DurationSinceUnixEpoch, PersistentTorrents};
+
+use super::driver::Driver;
+use super::error::Error;
+use super::migrations::Migration;
+use super::{Database, PoolStats};
+use crate::core::api_tokens::RotatedToken;
+use crate::core::audit;
+use crate::core::auth::{self, Key};
+use crate::core::torrent_history::Bucket;
+
+/// The statistics of the calls to an operation of the database.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OperationStats {
+    /// Number of calls.
+    pub queries: u64,
+    /// Number of calls that returned an error.
+    pub errors: u64,
+    /// Time taken by all the calls.
+    pub total_time: Duration,
+    /// Time taken by the slowest call.
+    pub max_time: Duration,
+}
+
+impl OperationStats {
+    /// It returns the average time of the calls, or zero if there were none.
+    #[must_use]
+    pub fn average_time(&self) -> Duration {
+        u32::try_from(self.queries)
+            .ok()
+            .and_then(|queries| self.total_time.checked_div(queries))
+            .unwrap_or_default()
+    }
+
+    fn record(&mut self, elapsed: Duration, failed: bool) {
+        self.queries += 1;
+        if failed {
+            self.errors += 1;
+        }
+        self.total_time += elapsed;
+        self.max_time = self.max_time.max(elapsed);
+    }
+}
+
+/// The statistics of the queries of a database, by operation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryStats {
+    /// The driver of the database.
+    pub driver: Driver,
+    /// The statistics of each operation called at least once.
+    pub operations: BTreeMap<&'static str, OperationStats>,
+}
+
+/// A database driver that measures the queries of the driver it wraps.
+pub struct Instrumented<T> {
+    database: T,
+    operations: Mutex<BTreeMap<&'static str, OperationStats>>,
+}
+
+impl<T> From<T> for Instrumented<T> {
+    fn from(database: T) -> Self {
+        Self {
+            database,
+            operations: Mutex::default(),
+        }
+    }
+}
+
+impl<T> Instrumented<T> {
+    fn record(&self, operation: &'static str, elapsed: Duration, failed: bool) {
+        self.operations
+            .lock()
+            .expect("it should get a lock on the database query stats")
+            .entry(operation)
+            .or_default()
+            .record(elapsed, failed);
+    }
+
+    fn measure_sync<R>(&self, operation: &'static str, query: impl FnOnce() -> Result<R, Error>) -> Result<R, Error> {
+        let start = Instant::now();
+
+        let result = query();
+
+        self.record(operation, start.elapsed(), result.is_err());
+
+        result
+    }
+
+    async fn measure<R>(&self, operation: &'static str, query: impl Future<Output = Result<R, Error>>) -> Result<R, Error> {
+        let start = Instant::now();
+
+        let result = query.await;
+
+        self.record(operation, start.elapsed(), result.is_err());
+
+        result
+    }
+}
+
+#[async_trait]
+impl<T> Database for Instrumented<T>
+where
+    T: Database,
+{
+    fn new(db_path: &str, pool: &DatabasePool, table_prefix: &str) -> Result<Self, Error> {
+        Ok(Self::from(T::new(db_path, pool, table_prefix)?))
+    }
+
+    fn pool_stats(&self) -> PoolStats {
+        self.database.pool_stats()
+    }
+
+    fn query_stats(&self) -> BTreeMap<&'static str, OperationStats> {
+        self.operations
+            .lock()
+            .expect("it should get a lock on the database query stats")
+            .clone()
+    }
+
+    fn schema_version(&self) -> Result<u32, Error> {
+        self.measure_sync("schema_version", || self.database.schema_version())
+    }
+
+    fn migrate(&self) -> Result<Vec<&'static Migration>, Error> {
+        self.measure_sync("migrate", || self.database.migrate())
+    }
+
+    fn drop_database_tables(&self) -> Result<(), Error> {
+        self.measure_sync("drop_database_tables", || self.database.drop_database_tables())
+    }
+
+    async fn ping(&self) -> Result<(), Error> {
+        self.measure("ping", self.database.ping()).await
+    }
+
+    async fn load_persistent_torrents(&self) -> Result<PersistentTorrents, Error> {
+        self.measure("load_persistent_torrents", self.database.load_persistent_torrents())
+            .await
+    }
+
+    async fn save_persistent_torrent(&self, info_hash: &InfoHash, downloaded: u32) -> Result<(), Error> {
+        self.measure(
+            "save_persistent_torrent",
+            self.database.save_persistent_torrent(info_hash, downloaded),
+        )
+        .await
+    }
+
+    async fn save_persistent_torrents(&self, torrents: &PersistentTorrents) -> Result<(), Error> {
+        self.measure("save_persistent_torrents", self.database.save_persistent_torrents(torrents))
+            .await
+    }
+
+    async fn remove_persistent_torrent(&self, info_hash: &InfoHash) -> Result<(), Error> {
+        self.measure(
+            "remove_persistent_torrent",
+            self.database.remove_persistent_torrent(info_hash),
+        )
+        .await
+    }
+
+    async fn load_torrent_history(&self, info_hash: &InfoHash, from: u64, to: u64) -> Result<Vec<Bucket>, Error> {
+        self.measure(
+            "load_torrent_history",
+            self.database.load_torrent_history(info_hash, from, to),
+        )
+        .await
+    }
+
+    async fn save_torrent_history(&self, info_hash: &InfoHash, bucket: &Bucket) -> Result<(), Error> {
+        self.measure("save_torrent_history", self.database.save_torrent_history(info_hash, bucket))
+            .await
+    }
+
+    async fn load_whitelist(&self) -> Result<Vec<InfoHash>, Error> {
+        self.measure("load_whitelist", self.database.load_whitelist()).await
+    }
+
+    async fn get_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<Option<InfoHash>, Error> {
+        self.measure(
+            "get_info_hash_from_whitelist",
+            self.database.get_info_hash_from_whitelist(info_hash),
+        )
+        .await
+    }
+
+    async fn add_info_hash_to_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error> {
+        self.measure(
+            "add_info_hash_to_whitelist",
+            self.database.add_info_hash_to_whitelist(info_hash),
+        )
+        .await
+    }
+
+    async fn is_info_hash_whitelisted(&self, info_hash: InfoHash) -> Result<bool, Error> {
+        self.measure("is_info_hash_whitelisted", self.database.is_info_hash_whitelisted(info_hash))
+            .await
+    }
+
+    async fn remove_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error> {
+        self.measure(
+            "remove_info_hash_from_whitelist",
+            self.database.remove_info_hash_from_whitelist(info_hash),
+        )
+        .await
+    }
+
+    async fn load_keys(&self) -> Result<Vec<auth::PeerKey>, Error> {
+        self.measure("load_keys", self.database.load_keys()).await
+    }
+
+    async fn get_key_from_keys(&self, key: &Key) -> Result<Option<auth::PeerKey>, Error> {
+        self.measure("get_key_from_keys", self.database.get_key_from_keys(key)).await
+    }
+
+    async fn add_key_to_keys(&self, auth_key: &auth::PeerKey) -> Result<usize, Error> {
+        self.measure("add_key_to_keys", self.database.add_key_to_keys(auth_key)).await
+    }
+
+    async fn remove_key_from_keys(&self, key: &Key) -> Result<usize, Error> {
+        self.measure("remove_key_from_keys", self.database.remove_key_from_keys(key))
+            .await
+    }
+
+    async fn remove_expired_keys(&self, now: DurationSinceUnixEpoch) -> Result<usize, Error> {
+        self.measure("remove_expired_keys", self.database.remove_expired_keys(now))
+            .await
+    }
+
+    async fn add_audit_entry(&self, entry: &audit::Entry) -> Result<(), Error> {
+        self.measure("add_audit_entry", self.database.add_audit_entry(entry)).await
+    }
+
+    async fn load_audit_entries(&self, filter: &audit::Filter, pagination: &Pagination) -> Result<Vec<audit::Entry>, Error> {
+        self.measure("load_audit_entries", self.database.load_audit_entries(filter, pagination))
+            .await
+    }
+
+    async fn load_rotated_api_tokens(&self) -> Result<Vec<RotatedToken>, Error> {
+        self.measure("load_rotated_api_tokens", self.database.load_rotated_api_tokens())
+            .await
+    }
+
+    async fn save_rotated_api_token(&self, rotated_token: &RotatedToken) -> Result<(), Error> {
+        self.measure("save_rotated_api_token", self.database.save_rotated_api_token(rotated_token))
+            .await
+    }
+
+    async fn compact(&self) -> Result<(), Error> {
+        self.measure("compact", self.database.compact()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use torrust_tracker_primitives::info_hash::InfoHash;
+
+    use super::{Instrumented, OperationStats};
+    use crate::core::databases::memory::Memory;
+    use crate::core::databases::Database;
+
+    #[tokio::test]
+    async fn it_should_count_the_queries_and_the_errors_of_each_operation() {
+        let database = Instrumented::from(Memory::default());
+
+        let info_hash = "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap();
+
+        database.add_info_hash_to_whitelist(info_hash).await.unwrap();
+        database.add_info_hash_to_whitelist(info_hash).await.unwrap_err();
+        database.load_whitelist().await.unwrap();
+
+        let stats = database.query_stats();
+
+        assert_eq!(
+            stats.keys().copied().collect::<Vec<_>>(),
+            vec!["add_info_hash_to_whitelist", "load_whitelist"]
+        );
+        assert_eq!(stats["add_info_hash_to_whitelist"].queries, 2);
+        assert_eq!(stats["add_info_hash_to_whitelist"].errors, 1);
+        assert_eq!(stats["load_whitelist"].errors, 0);
+    }
+
+    #[test]
+    fn it_should_calculate_the_average_time_of_the_queries() {
+        let stats = OperationStats {
+            queries: 4,
+            errors: 0,
+            total_time: Duration::from_millis(10),
+            max_time: Duration::from_millis(7),
+        };
+
+        assert_eq!(stats.average_time(), Duration::from_micros(2500));
+        assert_eq!(OperationStats::default().average_time(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,222 @@
+//! Migrations of the database schema.
+//!
+//! The schema of each driver is defined by a list of versioned SQL scripts,
+//! embedded in the binary. The versions applied to a database are recorded in
+//! its `schema_version` table, so only the pending ones are applied:
+//!
+//! - Automatically, when the tracker starts, unless the `auto_migrate` option
+//!   of the `[core.database]` configuration is disabled.
+//! - With the `migrate` command: `torrust-tracker migrate`.
+//!
+//! When the automatic migrations are disabled, the tracker refuses to start
+//! with a database that has pending migrations.
+//!
+//! The first migration is the schema before the migrations were introduced.
+//! It only creates the tables that don't exist, so the databases created by
+//! previous versions of the tracker are migrated without changes. The changes
+//! only needed by some of those databases are applied by migrations with a
+//! [`condition`](Migration::condition).
+//!
+//! To change the schema, add a new script to both drivers with the next
+//! version. The scripts that have been released must not be modified. The
+//! names of the tables are written with the `{prefix}` placeholder, which is
+//! replaced with the `db_table_prefix` of the `[core.database]` configuration.
+//!
+//! Version | Description
+//! ---|---
+//! 1       | Initial schema
+//! 2       | Key creation time and label
+//! 3       | Nullable key expiration time, for the databases created before the permanent keys
+use super::driver::Driver;
+use super::error::Error;
+use super::{with_table_prefix, Database};
+
+/// A version of the database schema.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Migration {
+    /// The version of the schema after the migration. The versions of a
+    /// driver start at `1` and are consecutive.
+    pub version: u32,
+    /// What the migration changes.
+    pub description: &'static str,
+    /// The SQL statements, separated by `;`.
+    pub sql: &'static str,
+    /// A query returning a count. When there is one, the statements are only
+    /// applied if the count is greater than `0`. The version is recorded
+    /// anyway.
+    pub condition: Option<&'static str>,
+}
+
+impl Migration {
+    /// It returns the SQL statements of the migration for the tables with
+    /// the `table_prefix`, without the comments.
+    pub fn statements<'a>(&'a self, table_prefix: &'a str) -> impl Iterator<Item = String> + 'a {
+        self.sql
+            .split(';')
+            .map(move |statement| {
+                let statement = statement
+                    .lines()
+                    .filter(|line| !line.trim_start().starts_with("--"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                with_table_prefix(statement.trim(), table_prefix)
+            })
+            .filter(|statement| !statement.is_empty())
+    }
+
+    /// It returns the [`condition`](Migration::condition) query for the
+    /// tables with the `table_prefix`.
+    #[must_use]
+    pub fn condition(&self, table_prefix: &str) -> Option<String> {
+        self.condition.map(|condition| with_table_prefix(condition, table_prefix))
+    }
+}
+
+/// The migrations of the `SQLite3` schema.
+pub const SQLITE: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Initial schema",
+        sql: include_str!("sqlite/0001_initial_schema.sql"),
+        condition: None,
+    },
+    Migration {
+        version: 2,
+        description: "Key creation time and label",
+        sql: include_str!("sqlite/0002_key_creation_time_and_label.sql"),
+        condition: None,
+    },
+    Migration {
+        version: 3,
+        description: "Nullable key expiration time",
+        sql: include_str!("sqlite/0003_nullable_key_expiration_time.sql"),
+        condition: Some(r#"SELECT COUNT(*) FROM pragma_table_info('{prefix}keys') WHERE name = 'valid_until' AND "notnull" = 1"#),
+    },
+];
+
+/// The migrations of the `MySQL` schema.
+pub const MYSQL: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Initial schema",
+        sql: include_str!("mysql/0001_initial_schema.sql"),
+        condition: None,
+    },
+    Migration {
+        version: 2,
+        description: "Key creation time and label",
+        sql: include_str!("mysql/0002_key_creation_time_and_label.sql"),
+        condition: None,
+    },
+    Migration {
+        version: 3,
+        description: "Nullable key expiration time",
+        sql: include_str!("mysql/0003_nullable_key_expiration_time.sql"),
+        condition: Some(
+            "SELECT COUNT(*) FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = DATABASE() \
+             AND TABLE_NAME = '{prefix}keys' AND COLUMN_NAME = 'valid_until' AND IS_NULLABLE = 'NO'",
+        ),
+    },
+];
+
+/// It returns the migrations of the driver. The in-memory driver has none.
+#[must_use]
+pub fn all(driver: &Driver) -> &'static [Migration] {
+    match driver {
+        Driver::Sqlite3 => SQLITE,
+        Driver::MySQL => MYSQL,
+        Driver::Memory => &[],
+    }
+}
+
+/// It returns the migrations not applied to a database with the schema
+/// `version`.
+#[must_use]
+pub fn pending(migrations: &'static [Migration], version: u32) -> &'static [Migration] {
+    let applied = migrations.iter().take_while(|migration| migration.version <= version).count();
+
+    &migrations[applied..]
+}
+
+/// It returns the last version of the schema of the driver.
+#[must_use]
+pub fn latest_version(migrations: &[Migration]) -> u32 {
+    migrations.last().map_or(0, |migration| migration.version)
+}
+
+/// It checks the database has no pending migrations.
+///
+/// # Errors
+///
+/// Will return `Err` if the schema version can't be read or there are pending
+/// migrations.
+pub fn check(database: &dyn Database, driver: &Driver) -> Result<(), Error> {
+    let version = database.schema_version()?;
+    let latest = latest_version(all(driver));
+
+    if version < latest {
+        return Err(Error::PendingMigrations {
+            version,
+            latest,
+            driver: driver.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{latest_version, pending, Migration, MYSQL, SQLITE};
+
+    #[test]
+    fn it_should_number_the_migrations_of_each_driver_consecutively() {
+        for migrations in [SQLITE, MYSQL] {
+            for (index, migration) in migrations.iter().enumerate() {
+                assert_eq!(migration.version, u32::try_from(index + 1).unwrap());
+            }
+        }
+
+        assert_eq!(latest_version(SQLITE), latest_version(MYSQL));
+    }
+
+    #[test]
+    fn it_should_only_return_the_migrations_after_the_schema_version() {
+        assert_eq!(pending(SQLITE, 0), SQLITE);
+        assert!(pending(SQLITE, latest_version(SQLITE)).is_empty());
+    }
+
+    #[test]
+    fn it_should_split_the_migration_into_statements_without_the_comments() {
+        let migration = Migration {
+            version: 1,
+            description: "",
+            sql: "-- A comment\nCREATE TABLE a (id INTEGER);\n\n-- Another comment\nCREATE TABLE b (id INTEGER);\n",
+            condition: None,
+        };
+
+        assert_eq!(
+            migration.statements("").collect::<Vec<_>>(),
+            vec!["CREATE TABLE a (id INTEGER)", "CREATE TABLE b (id INTEGER)"]
+        );
+    }
+
+    #[test]
+    fn it_should_prefix_the_names_of_the_tables() {
+        let migration = Migration {
+            version: 1,
+            description: "",
+            sql: "CREATE TABLE {prefix}a (id INTEGER);\nALTER TABLE `{prefix}a` ADD COLUMN b TEXT;\n",
+            condition: None,
+        };
+
+        assert_eq!(
+            migration.statements("tracker_").collect::<Vec<_>>(),
+            vec![
+                "CREATE TABLE tracker_a (id INTEGER)",
+                "ALTER TABLE `tracker_a` ADD COLUMN b TEXT"
+            ]
+        );
+    }
+}
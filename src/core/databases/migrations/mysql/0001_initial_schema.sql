@@ -0,0 +1,51 @@
+-- The schema before the migrations were introduced. The tables are only
+-- created if they don't exist, so it can be applied to the databases created
+-- by previous versions of the tracker.
+
+CREATE TABLE IF NOT EXISTS {prefix}whitelist (
+    id integer PRIMARY KEY AUTO_INCREMENT,
+    info_hash VARCHAR(40) NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS `{prefix}keys` (
+    `id` INT NOT NULL AUTO_INCREMENT,
+    `key` VARCHAR(32) NOT NULL,
+    `valid_until` INT(10),
+    PRIMARY KEY (`id`),
+    UNIQUE (`key`)
+);
+
+CREATE TABLE IF NOT EXISTS {prefix}torrents (
+    id integer PRIMARY KEY AUTO_INCREMENT,
+    info_hash VARCHAR(40) NOT NULL UNIQUE,
+    completed INTEGER DEFAULT 0 NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS {prefix}torrent_history (
+    id integer PRIMARY KEY AUTO_INCREMENT,
+    info_hash VARCHAR(40) NOT NULL,
+    hour BIGINT UNSIGNED NOT NULL,
+    completed INTEGER DEFAULT 0 NOT NULL,
+    peak_seeders INTEGER DEFAULT 0 NOT NULL,
+    peak_leechers INTEGER DEFAULT 0 NOT NULL,
+    UNIQUE (info_hash, hour)
+);
+
+CREATE TABLE IF NOT EXISTS {prefix}audit_log (
+    id integer PRIMARY KEY AUTO_INCREMENT,
+    timestamp BIGINT UNSIGNED NOT NULL,
+    actor VARCHAR(255) NOT NULL,
+    client_ip VARCHAR(45),
+    method VARCHAR(16) NOT NULL,
+    path TEXT NOT NULL,
+    status SMALLINT UNSIGNED NOT NULL,
+    payload TEXT
+);
+
+CREATE TABLE IF NOT EXISTS {prefix}api_tokens (
+    id integer PRIMARY KEY AUTO_INCREMENT,
+    label VARCHAR(255) NOT NULL UNIQUE,
+    token VARCHAR(255) NOT NULL,
+    previous_token VARCHAR(255),
+    previous_token_valid_until BIGINT UNSIGNED DEFAULT 0 NOT NULL
+);
@@ -0,0 +1,6 @@
+-- The creation time and the optional label of the authentication keys. The
+-- keys created before this migration don't have a creation time.
+
+ALTER TABLE `{prefix}keys`
+    ADD COLUMN `created_at` BIGINT NULL,
+    ADD COLUMN `label` VARCHAR(255) NULL;
@@ -0,0 +1,5 @@
+-- The permanent keys don't have an expiration time. The databases created
+-- before they were introduced have a `NOT NULL` `valid_until` column. It's
+-- only applied to those databases.
+
+ALTER TABLE `{prefix}keys` MODIFY `valid_until` INT(10) NULL;
@@ -0,0 +1,49 @@
+-- The schema before the migrations were introduced. The tables are only
+-- created if they don't exist, so it can be applied to the databases created
+-- by previous versions of the tracker.
+
+CREATE TABLE IF NOT EXISTS {prefix}whitelist (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    info_hash TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS {prefix}keys (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    key TEXT NOT NULL UNIQUE,
+    valid_until INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS {prefix}torrents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    info_hash TEXT NOT NULL UNIQUE,
+    completed INTEGER DEFAULT 0 NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS {prefix}torrent_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    info_hash TEXT NOT NULL,
+    hour INTEGER NOT NULL,
+    completed INTEGER DEFAULT 0 NOT NULL,
+    peak_seeders INTEGER DEFAULT 0 NOT NULL,
+    peak_leechers INTEGER DEFAULT 0 NOT NULL,
+    UNIQUE (info_hash, hour)
+);
+
+CREATE TABLE IF NOT EXISTS {prefix}audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    actor TEXT NOT NULL,
+    client_ip TEXT,
+    method TEXT NOT NULL,
+    path TEXT NOT NULL,
+    status INTEGER NOT NULL,
+    payload TEXT
+);
+
+CREATE TABLE IF NOT EXISTS {prefix}api_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    label TEXT NOT NULL UNIQUE,
+    token TEXT NOT NULL,
+    previous_token TEXT,
+    previous_token_valid_until INTEGER DEFAULT 0 NOT NULL
+);
@@ -0,0 +1,19 @@
+-- The permanent keys don't have an expiration time. The databases created
+-- before they were introduced have a `NOT NULL` `valid_until` column, which
+-- can't be altered in SQLite, so the table is rebuilt. It's only applied to
+-- those databases.
+
+CREATE TABLE {prefix}keys_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    key TEXT NOT NULL UNIQUE,
+    valid_until INTEGER,
+    created_at INTEGER,
+    label TEXT
+);
+
+INSERT INTO {prefix}keys_new (id, key, valid_until, created_at, label)
+    SELECT id, key, valid_until, created_at, label FROM {prefix}keys;
+
+DROP TABLE {prefix}keys;
+
+ALTER TABLE {prefix}keys_new RENAME TO {prefix}keys;
@@ -2,19 +2,49 @@
 //!
 //! Persistence is currently implemented with one [`Database`] trait.
 //!
-//! There are two implementations of the trait (two drivers):
+//! There are three implementations of the trait (three drivers):
 //!
 //! - [`Mysql`](crate::core::databases::mysql::Mysql)
 //! - [`Sqlite`](crate::core::databases::sqlite::Sqlite)
+//! - [`Memory`](crate::core::databases::memory::Memory), which does not
+//!   persist anything. It's meant for tests and stateless deployments.
 //!
-//! > **NOTICE**: There are no database migrations. If there are any changes,
-//! > we will implemented them or provide a script to migrate to the new schema.
+//! The queries are async. The `MySQL` and `SQLite3` drivers keep using the
+//! blocking `r2d2` connection pools (they are not ported to an async client
+//! like `sqlx`): each query runs on the blocking threads of the runtime with
+//! [`tokio::task::spawn_blocking`], so a slow query does not stall the worker
+//! threads handling the announce and scrape requests. The schema is migrated
+//! synchronously when the driver is built, before the tracker starts serving
+//! requests, so the schema methods are not async.
+//!
+//! The `SQLite3` database can be encrypted with `SQLCipher`, setting the
+//! `encryption_key` (or the `encryption_key_file`) of the `[core.database]`
+//! configuration. It requires building the tracker with the `sqlcipher`
+//! feature:
+//!
+//! ```text
+//! cargo build --release --features sqlcipher
+//! ```
+//!
+//! The schema is versioned. The changes between releases are applied by the
+//! embedded [`migrations`](crate::core::databases::migrations), so there is
+//! no need to alter the tables by hand.
+//!
+//! The names of the tables can be prefixed with the `db_table_prefix` of the
+//! `[core.database]` configuration, so several tracker instances (or the
+//! tracker and the Torrust Index) can share the same database. For example,
+//! with `db_table_prefix = "tracker_"` the tables are `tracker_torrents`,
+//! `tracker_whitelist`, and so on.
 //!
 //! The persistent objects are:
 //!
 //! - [Torrent metrics](#torrent-metrics)
+//! - [Torrent history](#torrent-history)
 //! - [Torrent whitelist](torrent-whitelist)
 //! - [Authentication keys](authentication-keys)
+//! - [Audit log](#audit-log)
+//! - [API access tokens](#api-access-tokens)
+//! - [Schema version](#schema-version)
 //!
 //! # Torrent metrics
 //!
@@ -27,6 +57,19 @@
 //! > **NOTICE**: The peer list for a torrent is not persisted. Since peer have to re-announce themselves on intervals, the data is be
 //! > regenerated again after some minutes.
 //!
+//! # Torrent history
+//!
+//!  Field           | Sample data                              | Description
+//! ---|---|---
+//!  `id`            | 1                                        | Autoincrement id
+//!  `info_hash`     | `c1277613db1d28709b034a017ab2cae4be07ae10` | `BitTorrent` infohash V1
+//!  `hour`          | 1672419600                               | Start of the hour, in seconds since the Unix epoch
+//!  `completed`     | 3                                        | The number of peers that completed downloading the torrent in the hour
+//!  `peak_seeders`  | 12                                       | The peak number of seeders in the hour
+//!  `peak_leechers` | 5                                        | The peak number of leechers in the hour
+//!
+//! Refer to the [`torrent_history`](crate::core::torrent_history) module for more information.
+//!
 //! # Torrent whitelist
 //!
 //! Field         | Sample data                              | Description
@@ -41,20 +84,72 @@
 //! `id`          | 1                                | Autoincrement id             
 //! `key`         | `IrweYtVuQPGbG9Jzx1DihcPmJGGpVy82` | Token                        
 //! `valid_until` | 1672419840                       | Timestamp for the expiring date  
+//! `created_at`  | 1672416240                       | Timestamp for the creation date (`NULL` for older keys)
+//! `label`       | `user-1234`                      | Optional label to attribute the key
 //!
 //! > **NOTICE**: All keys must have an expiration date.
+//!
+//! # Audit log
+//!
+//! Field         | Sample data                      | Description
+//! ---|---|---
+//! `id`          | 1                                | Autoincrement id
+//! `timestamp`   | 1672419840                       | Time of the request, in seconds since the Unix epoch
+//! `actor`       | `admin`                          | Label of the access token, or `jwt:` and the subject of the JSON Web Token
+//! `client_ip`   | `203.0.113.7`                    | IP address of the client, if it's known
+//! `method`      | `POST`                           | HTTP method
+//! `path`        | `/api/v1/whitelist/c1277613db1d28709b034a017ab2cae4be07ae10` | Path and query of the request, without the access token
+//! `status`      | 200                              | HTTP status code of the response
+//! `payload`     | `{"seconds_valid":60}`           | Beginning of the request body, if it has one
+//!
+//! Refer to the [`audit`](crate::core::audit) module for more information.
+//!
+//! # API access tokens
+//!
+//! Field                        | Sample data                        | Description
+//! ---|---|---
+//! `id`                         | 1                                  | Autoincrement id
+//! `label`                      | `admin`                            | Label of the access token in the configuration
+//! `token`                      | `kyH0sLrkKWU6pWdc0jq8D0a1LkQ5Y2zV` | Current token
+//! `previous_token`             | `MyAccessToken`                    | Token replaced by the current one
+//! `previous_token_valid_until` | 1672423440                         | Time until which the previous token is accepted
+//!
+//! Refer to the [`api_tokens`](crate::core::api_tokens) module for more information.
+//!
+//! # Schema version
+//!
+//! Field         | Sample data      | Description
+//! ---|---|---
+//! `version`     | 1                | Version of the schema after the migration
+//! `description` | `Initial schema` | What the migration changes
+//! `applied_at`  | 1672419840       | Time the migration was applied, in seconds since the Unix epoch
+//!
+//! Refer to the [`migrations`](crate::core::databases::migrations) module for more information.
 pub mod driver;
+pub mod dump;
 pub mod error;
+pub mod memory;
+pub mod metrics;
+pub mod migrations;
 pub mod mysql;
 pub mod sqlite;
 
+use std::collections::BTreeMap;
 use std::marker::PhantomData;
 
+use async_trait::async_trait;
+use torrust_tracker_configuration::DatabasePool;
 use torrust_tracker_primitives::info_hash::InfoHash;
-use torrust_tracker_primitives::PersistentTorrents;
+use torrust_tracker_primitives::pagination::Pagination;
+use torrust_tracker_primitives::{DurationSinceUnixEpoch, PersistentTorrents};
 
 use self::error::Error;
+use self::metrics::OperationStats;
+use self::migrations::Migration;
+use crate::core::api_tokens::RotatedToken;
+use crate::core::audit;
 use crate::core::auth::{self, Key};
+use crate::core::torrent_history::Bucket;
 
 struct Builder<T>
 where
@@ -72,33 +167,110 @@ where
     /// # Errors
     ///
     /// Will return `r2d2::Error` if `db_path` is not able to create a database.
-    pub(self) fn build(db_path: &str) -> Result<Box<dyn Database>, Error> {
-        Ok(Box::new(T::new(db_path)?))
+    pub(self) fn build(db_path: &str, pool: &DatabasePool, table_prefix: &str) -> Result<Box<dyn Database>, Error> {
+        Ok(Box::new(T::new(db_path, pool, table_prefix)?))
+    }
+}
+
+/// It replaces the `{prefix}` placeholder of the table names in the `sql`
+/// statement with the `table_prefix`.
+fn with_table_prefix(sql: &str, table_prefix: &str) -> String {
+    sql.replace("{prefix}", table_prefix)
+}
+
+/// It returns the builder of a connection pool with the configured size and
+/// timeouts.
+fn pool_builder<M>(pool: &DatabasePool) -> r2d2::Builder<M>
+where
+    M: r2d2::ManageConnection,
+{
+    r2d2::Pool::builder()
+        .max_size(pool.max_size)
+        .connection_timeout(pool.acquire_timeout())
+        .idle_timeout(pool.idle_timeout())
+        .max_lifetime(pool.max_lifetime())
+}
+
+/// The state of the connection pool of the database.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Number of open connections, in use or idle.
+    pub connections: u32,
+    /// Number of open connections not in use.
+    pub idle_connections: u32,
+    /// Maximum number of connections of the pool.
+    pub max_size: u32,
+}
+
+impl<M> From<&r2d2::Pool<M>> for PoolStats
+where
+    M: r2d2::ManageConnection,
+{
+    fn from(pool: &r2d2::Pool<M>) -> Self {
+        let state = pool.state();
+
+        Self {
+            connections: state.connections,
+            idle_connections: state.idle_connections,
+            max_size: pool.max_size(),
+        }
     }
 }
 
 /// The persistence trait. It contains all the methods to interact with the database.
+#[async_trait]
 pub trait Database: Sync + Send {
-    /// It instantiates a new database driver.
+    /// It instantiates a new database driver. The names of its tables start
+    /// with the `table_prefix`.
     ///
     /// # Errors
     ///
     /// Will return `r2d2::Error` if `db_path` is not able to create a database.
-    fn new(db_path: &str) -> Result<Self, Error>
+    fn new(db_path: &str, pool: &DatabasePool, table_prefix: &str) -> Result<Self, Error>
     where
         Self: std::marker::Sized;
 
+    /// It returns the state of the connection pool, for the metrics.
+    fn pool_stats(&self) -> PoolStats;
+
+    /// It returns the statistics of the queries by operation, for the metrics.
+    ///
+    /// Only the [`Instrumented`](crate::core::databases::metrics::Instrumented)
+    /// driver measures its queries. The others return no statistics.
+    fn query_stats(&self) -> BTreeMap<&'static str, OperationStats> {
+        BTreeMap::new()
+    }
+
     // Schema
+    //
+    // Unlike the queries, the schema methods are synchronous. They only run
+    // when the driver is built (by the synchronous `Tracker::new`) and in
+    // the `migrate` and `db` commands, before the tracker serves any request,
+    // so there are no worker threads to stall. The migrations must also be
+    // applied before the driver is handed to the tracker.
+
+    /// It returns the version of the schema: the last
+    /// [migration](crate::core::databases::migrations) applied. It's `0` for an
+    /// empty database.
+    ///
+    /// # Context: Schema
+    ///
+    /// # Errors
+    ///
+    /// Will return `Error` if unable to read the `schema_version` table.
+    fn schema_version(&self) -> Result<u32, Error>;
 
-    /// It generates the database tables. SQL queries are hardcoded in the trait
-    /// implementation.
+    /// It applies the pending [migrations](crate::core::databases::migrations)
+    /// in order, recording each one in the `schema_version` table. It returns
+    /// the migrations applied.
     ///
     /// # Context: Schema
     ///
     /// # Errors
     ///
-    /// Will return `Error` if unable to create own tables.
-    fn create_database_tables(&self) -> Result<(), Error>;
+    /// Will return `Error` if a migration fails. The migrations applied before
+    /// it are kept.
+    fn migrate(&self) -> Result<Vec<&'static Migration>, Error>;
 
     /// It drops the database tables.
     ///
@@ -109,6 +281,17 @@ pub trait Database: Sync + Send {
     /// Will return `Err` if unable to drop tables.
     fn drop_database_tables(&self) -> Result<(), Error>;
 
+    // Health
+
+    /// It checks the database is available running a trivial query.
+    ///
+    /// # Context: Health
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to connect to the database or run the query.
+    async fn ping(&self) -> Result<(), Error>;
+
     // Torrent Metrics
 
     /// It loads the torrent metrics data from the database.
@@ -124,7 +307,7 @@ pub trait Database: Sync + Send {
     /// # Errors
     ///
     /// Will return `Err` if unable to load.
-    fn load_persistent_torrents(&self) -> Result<PersistentTorrents, Error>;
+    async fn load_persistent_torrents(&self) -> Result<PersistentTorrents, Error>;
 
     /// It saves the torrent metrics data into the database.
     ///
@@ -133,7 +316,51 @@ pub trait Database: Sync + Send {
     /// # Errors
     ///
     /// Will return `Err` if unable to save.
-    fn save_persistent_torrent(&self, info_hash: &InfoHash, downloaded: u32) -> Result<(), Error>;
+    async fn save_persistent_torrent(&self, info_hash: &InfoHash, downloaded: u32) -> Result<(), Error>;
+
+    /// It saves the torrent metrics data of several torrents into the
+    /// database, in a single transaction.
+    ///
+    /// # Context: Torrent Metrics
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to save. None of the torrents is saved
+    /// then.
+    async fn save_persistent_torrents(&self, torrents: &PersistentTorrents) -> Result<(), Error>;
+
+    /// It removes the torrent metrics data from the database. It does nothing
+    /// if the torrent is not in the database.
+    ///
+    /// # Context: Torrent Metrics
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to remove.
+    async fn remove_persistent_torrent(&self, info_hash: &InfoHash) -> Result<(), Error>;
+
+    // Torrent History
+
+    /// It loads the hourly buckets of the torrent history starting between
+    /// `from` and `to` (inclusive), in seconds since the Unix epoch.
+    ///
+    /// # Context: Torrent History
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to load.
+    async fn load_torrent_history(&self, info_hash: &InfoHash, from: u64, to: u64) -> Result<Vec<Bucket>, Error>;
+
+    /// It saves an hourly bucket of the torrent history. If the bucket of the
+    /// hour is already in the database, the completed downloads are added to
+    /// the saved ones, and the peaks are the highest of both.
+    ///
+    /// # Context: Torrent History
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to save.
+    async fn save_torrent_history(&self, info_hash: &InfoHash, bucket: &Bucket) -> Result<(), Error>;
 
     // Whitelist
 
@@ -144,7 +371,7 @@ pub trait Database: Sync + Send {
     /// # Errors
     ///
     /// Will return `Err` if unable to load.
-    fn load_whitelist(&self) -> Result<Vec<InfoHash>, Error>;
+    async fn load_whitelist(&self) -> Result<Vec<InfoHash>, Error>;
 
     /// It checks if the torrent is whitelisted.
     ///
@@ -155,7 +382,7 @@ pub trait Database: Sync + Send {
     /// # Errors
     ///
     /// Will return `Err` if unable to load.
-    fn get_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<Option<InfoHash>, Error>;
+    async fn get_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<Option<InfoHash>, Error>;
 
     /// It adds the torrent to the whitelist.
     ///
@@ -164,7 +391,7 @@ pub trait Database: Sync + Send {
     /// # Errors
     ///
     /// Will return `Err` if unable to save.
-    fn add_info_hash_to_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error>;
+    async fn add_info_hash_to_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error>;
 
     /// It checks if the torrent is whitelisted.
     ///
@@ -173,8 +400,8 @@ pub trait Database: Sync + Send {
     /// # Errors
     ///
     /// Will return `Err` if unable to load.
-    fn is_info_hash_whitelisted(&self, info_hash: InfoHash) -> Result<bool, Error> {
-        Ok(self.get_info_hash_from_whitelist(info_hash)?.is_some())
+    async fn is_info_hash_whitelisted(&self, info_hash: InfoHash) -> Result<bool, Error> {
+        Ok(self.get_info_hash_from_whitelist(info_hash).await?.is_some())
     }
 
     /// It removes the torrent from the whitelist.
@@ -184,7 +411,7 @@ pub trait Database: Sync + Send {
     /// # Errors
     ///
     /// Will return `Err` if unable to save.
-    fn remove_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error>;
+    async fn remove_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error>;
 
     // Authentication keys
 
@@ -195,7 +422,7 @@ pub trait Database: Sync + Send {
     /// # Errors
     ///
     /// Will return `Err` if unable to load.
-    fn load_keys(&self) -> Result<Vec<auth::PeerKey>, Error>;
+    async fn load_keys(&self) -> Result<Vec<auth::PeerKey>, Error>;
 
     /// It gets an expiring authentication key from the database.
     ///
@@ -207,7 +434,7 @@ pub trait Database: Sync + Send {
     /// # Errors
     ///
     /// Will return `Err` if unable to load.
-    fn get_key_from_keys(&self, key: &Key) -> Result<Option<auth::PeerKey>, Error>;
+    async fn get_key_from_keys(&self, key: &Key) -> Result<Option<auth::PeerKey>, Error>;
 
     /// It adds an expiring authentication key to the database.
     ///
@@ -216,7 +443,7 @@ pub trait Database: Sync + Send {
     /// # Errors
     ///
     /// Will return `Err` if unable to save.
-    fn add_key_to_keys(&self, auth_key: &auth::PeerKey) -> Result<usize, Error>;
+    async fn add_key_to_keys(&self, auth_key: &auth::PeerKey) -> Result<usize, Error>;
 
     /// It removes an expiring authentication key from the database.
     ///
@@ -225,5 +452,69 @@ pub trait Database: Sync + Send {
     /// # Errors
     ///
     /// Will return `Err` if unable to load.
-    fn remove_key_from_keys(&self, key: &Key) -> Result<usize, Error>;
+    async fn remove_key_from_keys(&self, key: &Key) -> Result<usize, Error>;
+
+    /// It removes the authentication keys that expired before `now`. It
+    /// returns the number of removed keys.
+    ///
+    /// # Context: Authentication Keys
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to remove.
+    async fn remove_expired_keys(&self, now: DurationSinceUnixEpoch) -> Result<usize, Error>;
+
+    // Audit Log
+
+    /// It adds an entry to the audit log.
+    ///
+    /// # Context: Audit Log
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to save.
+    async fn add_audit_entry(&self, entry: &audit::Entry) -> Result<(), Error>;
+
+    /// It loads the page of the audit log entries matching the `filter`, the
+    /// most recent first.
+    ///
+    /// # Context: Audit Log
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to load.
+    async fn load_audit_entries(&self, filter: &audit::Filter, pagination: &Pagination) -> Result<Vec<audit::Entry>, Error>;
+
+    // API Access Tokens
+
+    /// It loads the rotated access tokens of the API.
+    ///
+    /// # Context: API Access Tokens
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to load.
+    async fn load_rotated_api_tokens(&self) -> Result<Vec<RotatedToken>, Error>;
+
+    /// It saves a rotated access token of the API, replacing the saved one
+    /// with the same label.
+    ///
+    /// # Context: API Access Tokens
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to save.
+    async fn save_rotated_api_token(&self, rotated_token: &RotatedToken) -> Result<(), Error>;
+
+    // Maintenance
+
+    /// It compacts the database, to reclaim the space of the deleted rows and
+    /// defragment the tables.
+    ///
+    /// # Context: Database Maintenance
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to compact the database.
+    async fn compact(&self) -> Result<(), Error>;
 }
@@ -1,25 +1,75 @@
 //! The `MySQL` database driver.
 use std::str::FromStr;
-use std::time::Duration;
 
-use r2d2::Pool;
+use async_trait::async_trait;
+use r2d2::{Pool, PooledConnection};
 use r2d2_mysql::mysql::prelude::Queryable;
-use r2d2_mysql::mysql::{params, Opts, OptsBuilder};
+use r2d2_mysql::mysql::{params, Opts, OptsBuilder, TxOpts};
 use r2d2_mysql::MySqlConnectionManager;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_configuration::DatabasePool;
 use torrust_tracker_primitives::info_hash::InfoHash;
-use torrust_tracker_primitives::PersistentTorrents;
+use torrust_tracker_primitives::pagination::Pagination;
+use torrust_tracker_primitives::{DurationSinceUnixEpoch, PersistentTorrents};
 
 use super::driver::Driver;
-use super::{Database, Error};
+use super::migrations::{self, Migration};
+use super::{pool_builder, with_table_prefix, Database, Error, PoolStats};
+use crate::core::api_tokens::RotatedToken;
+use crate::core::audit;
 use crate::core::auth::{self, Key};
-use crate::shared::bit_torrent::common::AUTH_KEY_LENGTH;
+use crate::core::torrent_history::Bucket;
+use crate::CurrentClock;
 
 const DRIVER: Driver = Driver::MySQL;
 
+const CREATE_SCHEMA_VERSION_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS {prefix}schema_version (
+        version INT UNSIGNED PRIMARY KEY,
+        description VARCHAR(255) NOT NULL,
+        applied_at BIGINT UNSIGNED NOT NULL
+    );";
+
+/// Name of the lock held while the migrations are applied.
+const MIGRATIONS_LOCK: &str = "torrust_tracker_migrations";
+
+/// The `key, valid_until, created_at, label` columns of a key.
+type KeyRow = (String, Option<i64>, Option<i64>, Option<String>);
+
 pub struct Mysql {
     pool: Pool<MySqlConnectionManager>,
+    table_prefix: String,
+}
+
+impl Mysql {
+    /// It returns the `sql` with the table prefix of the database.
+    fn sql(&self, sql: &str) -> String {
+        with_table_prefix(sql, &self.table_prefix)
+    }
+
+    /// It runs the `query` with a connection of the pool. The driver is
+    /// blocking, so the query runs on the blocking threads of the runtime
+    /// instead of the worker threads serving the requests, with
+    /// [`tokio::task::spawn_blocking`].
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the query fails, or if the task running it
+    /// panics or is cancelled.
+    async fn run<T, F>(&self, query: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(PooledConnection<MySqlConnectionManager>) -> Result<T, Error> + Send + 'static,
+    {
+        let pool = self.pool.clone();
+
+        tokio::task::spawn_blocking(move || query(pool.get().map_err(|e| (e, DRIVER))?))
+            .await
+            .map_err(|e| (e, DRIVER))?
+    }
 }
 
+#[async_trait]
 impl Database for Mysql {
     /// It instantiates a new `MySQL` database driver.
     ///
@@ -28,68 +78,62 @@ impl Database for Mysql {
     /// # Errors
     ///
     /// Will return `r2d2::Error` if `db_path` is not able to create `MySQL` database.
-    fn new(db_path: &str) -> Result<Self, Error> {
+    fn new(db_path: &str, pool: &DatabasePool, table_prefix: &str) -> Result<Self, Error> {
         let opts = Opts::from_url(db_path)?;
         let builder = OptsBuilder::from_opts(opts);
         let manager = MySqlConnectionManager::new(builder);
-        let pool = r2d2::Pool::builder().build(manager).map_err(|e| (e, DRIVER))?;
-
-        Ok(Self { pool })
-    }
-
-    /// Refer to [`databases::Database::create_database_tables`](crate::core::databases::Database::create_database_tables).
-    fn create_database_tables(&self) -> Result<(), Error> {
-        let create_whitelist_table = "
-        CREATE TABLE IF NOT EXISTS whitelist (
-            id integer PRIMARY KEY AUTO_INCREMENT,
-            info_hash VARCHAR(40) NOT NULL UNIQUE
-        );"
-        .to_string();
-
-        let create_torrents_table = "
-        CREATE TABLE IF NOT EXISTS torrents (
-            id integer PRIMARY KEY AUTO_INCREMENT,
-            info_hash VARCHAR(40) NOT NULL UNIQUE,
-            completed INTEGER DEFAULT 0 NOT NULL
-        );"
-        .to_string();
-
-        let create_keys_table = format!(
-            "
-        CREATE TABLE IF NOT EXISTS `keys` (
-          `id` INT NOT NULL AUTO_INCREMENT,
-          `key` VARCHAR({}) NOT NULL,
-          `valid_until` INT(10),
-          PRIMARY KEY (`id`),
-          UNIQUE (`key`)
-        );",
-            i8::try_from(AUTH_KEY_LENGTH).expect("auth::Auth Key Length Should fit within a i8!")
-        );
+        let pool = pool_builder(pool).build(manager).map_err(|e| (e, DRIVER))?;
+
+        Ok(Self {
+            pool,
+            table_prefix: table_prefix.to_string(),
+        })
+    }
+
+    /// Refer to [`databases::Database::pool_stats`](crate::core::databases::Database::pool_stats).
+    fn pool_stats(&self) -> PoolStats {
+        PoolStats::from(&self.pool)
+    }
 
+    /// Refer to [`databases::Database::schema_version`](crate::core::databases::Database::schema_version).
+    fn schema_version(&self) -> Result<u32, Error> {
         let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
 
-        conn.query_drop(&create_torrents_table)
-            .expect("Could not create torrents table.");
-        conn.query_drop(&create_keys_table).expect("Could not create keys table.");
-        conn.query_drop(&create_whitelist_table)
-            .expect("Could not create whitelist table.");
+        schema_version(&mut conn, &self.table_prefix)
+    }
 
-        Ok(())
+    /// Refer to [`databases::Database::migrate`](crate::core::databases::Database::migrate).
+    ///
+    /// The `MySQL` schema changes can't be rolled back, so the migrations run
+    /// while holding a lock: the tracker instances sharing the database don't
+    /// apply the same migration twice.
+    fn migrate(&self) -> Result<Vec<&'static Migration>, Error> {
+        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+
+        conn.query_drop(format!("SELECT GET_LOCK('{MIGRATIONS_LOCK}', -1)"))?;
+
+        let result = apply_pending_migrations(&mut conn, &self.table_prefix);
+
+        conn.query_drop(format!("SELECT RELEASE_LOCK('{MIGRATIONS_LOCK}')"))?;
+
+        result
     }
 
     /// Refer to [`databases::Database::drop_database_tables`](crate::core::databases::Database::drop_database_tables).
     fn drop_database_tables(&self) -> Result<(), Error> {
-        let drop_whitelist_table = "
-        DROP TABLE `whitelist`;"
-            .to_string();
+        let drop_whitelist_table = self.sql("DROP TABLE `{prefix}whitelist`;");
 
-        let drop_torrents_table = "
-        DROP TABLE `torrents`;"
-            .to_string();
+        let drop_torrents_table = self.sql("DROP TABLE `{prefix}torrents`;");
 
-        let drop_keys_table = "
-            DROP TABLE `keys`;"
-            .to_string();
+        let drop_torrent_history_table = self.sql("DROP TABLE `{prefix}torrent_history`;");
+
+        let drop_audit_log_table = self.sql("DROP TABLE `{prefix}audit_log`;");
+
+        let drop_api_tokens_table = self.sql("DROP TABLE `{prefix}api_tokens`;");
+
+        let drop_keys_table = self.sql("DROP TABLE `{prefix}keys`;");
+
+        let drop_schema_version_table = self.sql("DROP TABLE `{prefix}schema_version`;");
 
         let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
 
@@ -97,157 +141,436 @@ impl Database for Mysql {
             .expect("Could not drop `whitelist` table.");
         conn.query_drop(&drop_torrents_table)
             .expect("Could not drop `torrents` table.");
+        conn.query_drop(&drop_torrent_history_table)
+            .expect("Could not drop `torrent_history` table.");
+        conn.query_drop(&drop_audit_log_table)
+            .expect("Could not drop `audit_log` table.");
+        conn.query_drop(&drop_api_tokens_table)
+            .expect("Could not drop `api_tokens` table.");
         conn.query_drop(&drop_keys_table).expect("Could not drop `keys` table.");
+        conn.query_drop(&drop_schema_version_table)
+            .expect("Could not drop `schema_version` table.");
 
         Ok(())
     }
 
+    /// Refer to [`databases::Database::ping`](crate::core::databases::Database::ping).
+    async fn ping(&self) -> Result<(), Error> {
+        self.run(move |mut conn| {
+            conn.query_drop("SELECT 1")?;
+
+            Ok(())
+        })
+        .await
+    }
+
     /// Refer to [`databases::Database::load_persistent_torrents`](crate::core::databases::Database::load_persistent_torrents).
-    fn load_persistent_torrents(&self) -> Result<PersistentTorrents, Error> {
-        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    async fn load_persistent_torrents(&self) -> Result<PersistentTorrents, Error> {
+        let query = self.sql("SELECT info_hash, completed FROM {prefix}torrents");
 
-        let torrents = conn.query_map(
-            "SELECT info_hash, completed FROM torrents",
-            |(info_hash_string, completed): (String, u32)| {
+        self.run(move |mut conn| {
+            let torrents = conn.query_map(&query, |(info_hash_string, completed): (String, u32)| {
                 let info_hash = InfoHash::from_str(&info_hash_string).unwrap();
                 (info_hash, completed)
-            },
-        )?;
+            })?;
 
-        Ok(torrents.iter().copied().collect())
+            Ok(torrents.iter().copied().collect())
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::load_keys`](crate::core::databases::Database::load_keys).
-    fn load_keys(&self) -> Result<Vec<auth::PeerKey>, Error> {
-        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    async fn load_keys(&self) -> Result<Vec<auth::PeerKey>, Error> {
+        let query = self.sql("SELECT `key`, valid_until, created_at, label FROM `{prefix}keys`");
 
-        let keys = conn.query_map(
-            "SELECT `key`, valid_until FROM `keys`",
-            |(key, valid_until): (String, Option<i64>)| match valid_until {
-                Some(valid_until) => auth::PeerKey {
-                    key: key.parse::<Key>().unwrap(),
-                    valid_until: Some(Duration::from_secs(valid_until.unsigned_abs())),
-                },
-                None => auth::PeerKey {
-                    key: key.parse::<Key>().unwrap(),
-                    valid_until: None,
-                },
-            },
-        )?;
+        self.run(move |mut conn| {
+            let keys = conn.query_map(&query, peer_key_from_row)?;
 
-        Ok(keys)
+            Ok(keys)
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::load_whitelist`](crate::core::databases::Database::load_whitelist).
-    fn load_whitelist(&self) -> Result<Vec<InfoHash>, Error> {
-        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    async fn load_whitelist(&self) -> Result<Vec<InfoHash>, Error> {
+        let query = self.sql("SELECT info_hash FROM {prefix}whitelist");
 
-        let info_hashes = conn.query_map("SELECT info_hash FROM whitelist", |info_hash: String| {
-            InfoHash::from_str(&info_hash).unwrap()
-        })?;
+        self.run(move |mut conn| {
+            let info_hashes = conn.query_map(&query, |info_hash: String| InfoHash::from_str(&info_hash).unwrap())?;
 
-        Ok(info_hashes)
+            Ok(info_hashes)
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::save_persistent_torrent`](crate::core::databases::Database::save_persistent_torrent).
-    fn save_persistent_torrent(&self, info_hash: &InfoHash, completed: u32) -> Result<(), Error> {
-        const COMMAND : &str = "INSERT INTO torrents (info_hash, completed) VALUES (:info_hash_str, :completed) ON DUPLICATE KEY UPDATE completed = VALUES(completed)";
-
-        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    async fn save_persistent_torrent(&self, info_hash: &InfoHash, completed: u32) -> Result<(), Error> {
+        const COMMAND : &str = "INSERT INTO {prefix}torrents (info_hash, completed) VALUES (:info_hash_str, :completed) ON DUPLICATE KEY UPDATE completed = VALUES(completed)";
 
         let info_hash_str = info_hash.to_string();
 
         tracing::debug!("{}", info_hash_str);
 
-        Ok(conn.exec_drop(COMMAND, params! { info_hash_str, completed })?)
+        let command = self.sql(COMMAND);
+
+        self.run(move |mut conn| Ok(conn.exec_drop(&command, params! { info_hash_str, completed })?))
+            .await
+    }
+
+    /// Refer to [`databases::Database::save_persistent_torrents`](crate::core::databases::Database::save_persistent_torrents).
+    async fn save_persistent_torrents(&self, torrents: &PersistentTorrents) -> Result<(), Error> {
+        const COMMAND : &str = "INSERT INTO {prefix}torrents (info_hash, completed) VALUES (:info_hash_str, :completed) ON DUPLICATE KEY UPDATE completed = VALUES(completed)";
+
+        let torrents = torrents.clone();
+
+        let command = self.sql(COMMAND);
+
+        self.run(move |mut conn| {
+            let mut tx = conn.start_transaction(TxOpts::default())?;
+
+            tx.exec_batch(
+                &command,
+                torrents
+                    .iter()
+                    .map(|(info_hash, completed)| params! { "info_hash_str" => info_hash.to_string(), "completed" => completed }),
+            )?;
+
+            tx.commit()?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::remove_persistent_torrent`](crate::core::databases::Database::remove_persistent_torrent).
+    async fn remove_persistent_torrent(&self, info_hash: &InfoHash) -> Result<(), Error> {
+        let info_hash = info_hash.to_string();
+
+        let query = self.sql("DELETE FROM {prefix}torrents WHERE info_hash = :info_hash");
+
+        self.run(move |mut conn| {
+            conn.exec_drop(&query, params! { info_hash })?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::load_torrent_history`](crate::core::databases::Database::load_torrent_history).
+    async fn load_torrent_history(&self, info_hash: &InfoHash, from: u64, to: u64) -> Result<Vec<Bucket>, Error> {
+        let info_hash = info_hash.to_string();
+
+        let query = self.sql("SELECT hour, completed, peak_seeders, peak_leechers FROM {prefix}torrent_history WHERE info_hash = :info_hash AND hour >= :from AND hour <= :to ORDER BY hour");
+
+        self.run(move |mut conn| {
+            let buckets = conn.exec_map(
+                &query,
+                params! { info_hash, from, to },
+                |(start, completed, peak_seeders, peak_leechers): (u64, u32, u32, u32)| Bucket {
+                    start,
+                    completed,
+                    peak_seeders,
+                    peak_leechers,
+                },
+            )?;
+
+            Ok(buckets)
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::save_torrent_history`](crate::core::databases::Database::save_torrent_history).
+    async fn save_torrent_history(&self, info_hash: &InfoHash, bucket: &Bucket) -> Result<(), Error> {
+        const COMMAND : &str = "INSERT INTO {prefix}torrent_history (info_hash, hour, completed, peak_seeders, peak_leechers) VALUES (:info_hash_str, :hour, :completed, :peak_seeders, :peak_leechers) ON DUPLICATE KEY UPDATE completed = completed + VALUES(completed), peak_seeders = GREATEST(peak_seeders, VALUES(peak_seeders)), peak_leechers = GREATEST(peak_leechers, VALUES(peak_leechers))";
+
+        let info_hash_str = info_hash.to_string();
+        let hour = bucket.start;
+        let completed = bucket.completed;
+        let peak_seeders = bucket.peak_seeders;
+        let peak_leechers = bucket.peak_leechers;
+
+        let command = self.sql(COMMAND);
+
+        self.run(move |mut conn| {
+            Ok(conn.exec_drop(
+                &command,
+                params! { info_hash_str, hour, completed, peak_seeders, peak_leechers },
+            )?)
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::get_info_hash_from_whitelist`](crate::core::databases::Database::get_info_hash_from_whitelist).
-    fn get_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<Option<InfoHash>, Error> {
-        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    async fn get_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<Option<InfoHash>, Error> {
+        let query = self.sql("SELECT info_hash FROM {prefix}whitelist WHERE info_hash = :info_hash");
 
-        let select = conn.exec_first::<String, _, _>(
-            "SELECT info_hash FROM whitelist WHERE info_hash = :info_hash",
-            params! { "info_hash" => info_hash.to_hex_string() },
-        )?;
+        self.run(move |mut conn| {
+            let select = conn.exec_first::<String, _, _>(&query, params! { "info_hash" => info_hash.to_hex_string() })?;
 
-        let info_hash = select.map(|f| InfoHash::from_str(&f).expect("Failed to decode InfoHash String from DB!"));
+            let info_hash = select.map(|f| InfoHash::from_str(&f).expect("Failed to decode InfoHash String from DB!"));
 
-        Ok(info_hash)
+            Ok(info_hash)
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::add_info_hash_to_whitelist`](crate::core::databases::Database::add_info_hash_to_whitelist).
-    fn add_info_hash_to_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error> {
-        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
-
+    async fn add_info_hash_to_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error> {
         let info_hash_str = info_hash.to_string();
 
-        conn.exec_drop(
-            "INSERT INTO whitelist (info_hash) VALUES (:info_hash_str)",
-            params! { info_hash_str },
-        )?;
+        let query = self.sql("INSERT INTO {prefix}whitelist (info_hash) VALUES (:info_hash_str)");
+
+        self.run(move |mut conn| {
+            conn.exec_drop(&query, params! { info_hash_str })?;
 
-        Ok(1)
+            Ok(1)
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::remove_info_hash_from_whitelist`](crate::core::databases::Database::remove_info_hash_from_whitelist).
-    fn remove_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error> {
-        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
-
+    async fn remove_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error> {
         let info_hash = info_hash.to_string();
 
-        conn.exec_drop("DELETE FROM whitelist WHERE info_hash = :info_hash", params! { info_hash })?;
+        let query = self.sql("DELETE FROM {prefix}whitelist WHERE info_hash = :info_hash");
 
-        Ok(1)
+        self.run(move |mut conn| {
+            conn.exec_drop(&query, params! { info_hash })?;
+
+            Ok(1)
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::get_key_from_keys`](crate::core::databases::Database::get_key_from_keys).
-    fn get_key_from_keys(&self, key: &Key) -> Result<Option<auth::PeerKey>, Error> {
-        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    async fn get_key_from_keys(&self, key: &Key) -> Result<Option<auth::PeerKey>, Error> {
+        let key = key.to_string();
 
-        let query = conn.exec_first::<(String, Option<i64>), _, _>(
-            "SELECT `key`, valid_until FROM `keys` WHERE `key` = :key",
-            params! { "key" => key.to_string() },
-        );
+        let select = self.sql("SELECT `key`, valid_until, created_at, label FROM `{prefix}keys` WHERE `key` = :key");
+
+        self.run(move |mut conn| {
+            let query = conn.exec_first::<KeyRow, _, _>(&select, params! { key });
 
-        let key = query?;
+            let key = query?;
 
-        Ok(key.map(|(key, opt_valid_until)| match opt_valid_until {
-            Some(valid_until) => auth::PeerKey {
-                key: key.parse::<Key>().unwrap(),
-                valid_until: Some(Duration::from_secs(valid_until.unsigned_abs())),
-            },
-            None => auth::PeerKey {
-                key: key.parse::<Key>().unwrap(),
-                valid_until: None,
-            },
-        }))
+            Ok(key.map(peer_key_from_row))
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::add_key_to_keys`](crate::core::databases::Database::add_key_to_keys).
-    fn add_key_to_keys(&self, auth_key: &auth::PeerKey) -> Result<usize, Error> {
-        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
-
+    async fn add_key_to_keys(&self, auth_key: &auth::PeerKey) -> Result<usize, Error> {
         let key = auth_key.key.to_string();
-        let valid_until = match auth_key.valid_until {
-            Some(valid_until) => valid_until.as_secs().to_string(),
-            None => todo!(),
+        let valid_until = auth_key.valid_until.map(|valid_until| valid_until.as_secs());
+        let created_at = auth_key.created_at.map(|created_at| created_at.as_secs());
+        let label = auth_key.label.clone();
+
+        let query = self.sql(
+            "INSERT INTO `{prefix}keys` (`key`, valid_until, created_at, label) VALUES (:key, :valid_until, :created_at, :label)",
+        );
+
+        self.run(move |mut conn| {
+            conn.exec_drop(&query, params! { key, valid_until, created_at, label })?;
+
+            Ok(1)
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::remove_expired_keys`](crate::core::databases::Database::remove_expired_keys).
+    async fn remove_expired_keys(&self, now: DurationSinceUnixEpoch) -> Result<usize, Error> {
+        let now = now.as_secs();
+
+        let query = self.sql("DELETE FROM `{prefix}keys` WHERE valid_until < :now");
+
+        self.run(move |mut conn| {
+            conn.exec_drop(&query, params! { now })?;
+
+            Ok(usize::try_from(conn.affected_rows()).unwrap_or(usize::MAX))
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::remove_key_from_keys`](crate::core::databases::Database::remove_key_from_keys).
+    async fn remove_key_from_keys(&self, key: &Key) -> Result<usize, Error> {
+        let key = key.to_string();
+
+        let query = self.sql("DELETE FROM `{prefix}keys` WHERE key = :key");
+
+        self.run(move |mut conn| {
+            conn.exec_drop(&query, params! { key })?;
+
+            Ok(1)
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::add_audit_entry`](crate::core::databases::Database::add_audit_entry).
+    async fn add_audit_entry(&self, entry: &audit::Entry) -> Result<(), Error> {
+        const COMMAND : &str = "INSERT INTO {prefix}audit_log (timestamp, actor, client_ip, method, path, status, payload) VALUES (:timestamp, :actor, :client_ip, :method, :path, :status, :payload)";
+
+        let timestamp = entry.timestamp;
+        let actor = entry.actor.clone();
+        let client_ip = entry.client_ip.map(|client_ip| client_ip.to_string());
+        let method = entry.method.clone();
+        let path = entry.path.clone();
+        let status = entry.status;
+        let payload = entry.payload.clone();
+
+        let command = self.sql(COMMAND);
+
+        self.run(move |mut conn| {
+            Ok(conn.exec_drop(
+                &command,
+                params! { timestamp, actor, client_ip, method, path, status, payload },
+            )?)
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::load_audit_entries`](crate::core::databases::Database::load_audit_entries).
+    async fn load_audit_entries(&self, filter: &audit::Filter, pagination: &Pagination) -> Result<Vec<audit::Entry>, Error> {
+        const QUERY : &str = "SELECT timestamp, actor, client_ip, method, path, status, payload FROM {prefix}audit_log WHERE (:actor IS NULL OR actor = :actor) AND (:method IS NULL OR method = :method) AND (:path_prefix IS NULL OR LEFT(path, CHAR_LENGTH(:path_prefix)) = :path_prefix) AND (:from IS NULL OR timestamp >= :from) AND (:to IS NULL OR timestamp <= :to) ORDER BY id DESC LIMIT :limit OFFSET :offset";
+
+        let actor = filter.actor.clone();
+        let method = filter.method.clone();
+        let path_prefix = filter.path_prefix.clone();
+        let from = filter.from;
+        let to = filter.to;
+        let limit = pagination.limit;
+        let offset = pagination.offset;
+
+        let query = self.sql(QUERY);
+
+        self.run(move |mut conn| {
+            let entries = conn.exec_map(
+                &query,
+                params! { actor, method, path_prefix, from, to, limit, offset },
+                |(timestamp, actor, client_ip, method, path, status, payload): (
+                    u64,
+                    String,
+                    Option<String>,
+                    String,
+                    String,
+                    u16,
+                    Option<String>,
+                )| audit::Entry {
+                    timestamp,
+                    actor,
+                    client_ip: client_ip.and_then(|client_ip| client_ip.parse().ok()),
+                    method,
+                    path,
+                    status,
+                    payload,
+                },
+            )?;
+
+            Ok(entries)
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::load_rotated_api_tokens`](crate::core::databases::Database::load_rotated_api_tokens).
+    async fn load_rotated_api_tokens(&self) -> Result<Vec<RotatedToken>, Error> {
+        let query = self.sql("SELECT label, token, previous_token, previous_token_valid_until FROM {prefix}api_tokens");
+
+        self.run(move |mut conn| {
+            let rotated_tokens = conn.query_map(
+                &query,
+                |(label, token, previous_token, previous_token_valid_until): (String, String, Option<String>, u64)| {
+                    RotatedToken {
+                        label,
+                        token,
+                        previous_token,
+                        previous_token_valid_until,
+                    }
+                },
+            )?;
+
+            Ok(rotated_tokens)
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::save_rotated_api_token`](crate::core::databases::Database::save_rotated_api_token).
+    async fn save_rotated_api_token(&self, rotated_token: &RotatedToken) -> Result<(), Error> {
+        const COMMAND : &str = "INSERT INTO {prefix}api_tokens (label, token, previous_token, previous_token_valid_until) VALUES (:label, :token, :previous_token, :previous_token_valid_until) ON DUPLICATE KEY UPDATE token = VALUES(token), previous_token = VALUES(previous_token), previous_token_valid_until = VALUES(previous_token_valid_until)";
+
+        let label = rotated_token.label.clone();
+        let token = rotated_token.token.clone();
+        let previous_token = rotated_token.previous_token.clone();
+        let previous_token_valid_until = rotated_token.previous_token_valid_until;
+
+        let command = self.sql(COMMAND);
+
+        self.run(move |mut conn| {
+            Ok(conn.exec_drop(&command, params! { label, token, previous_token, previous_token_valid_until })?)
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::compact`](crate::core::databases::Database::compact).
+    async fn compact(&self) -> Result<(), Error> {
+        const COMMAND: &str = "OPTIMIZE TABLE `{prefix}whitelist`, `{prefix}torrents`, `{prefix}torrent_history`, `{prefix}keys`, `{prefix}audit_log`, `{prefix}api_tokens`";
+
+        let command = self.sql(COMMAND);
+
+        self.run(move |mut conn| Ok(conn.query_drop(command)?)).await
+    }
+}
+
+fn schema_version(conn: &mut PooledConnection<MySqlConnectionManager>, table_prefix: &str) -> Result<u32, Error> {
+    conn.query_drop(with_table_prefix(CREATE_SCHEMA_VERSION_TABLE, table_prefix))?;
+
+    let version = conn.query_first::<u32, _>(with_table_prefix(
+        "SELECT COALESCE(MAX(version), 0) FROM {prefix}schema_version",
+        table_prefix,
+    ))?;
+
+    Ok(version.unwrap_or_default())
+}
+
+fn apply_pending_migrations(
+    conn: &mut PooledConnection<MySqlConnectionManager>,
+    table_prefix: &str,
+) -> Result<Vec<&'static Migration>, Error> {
+    let pending = migrations::pending(migrations::MYSQL, schema_version(conn, table_prefix)?);
+
+    for migration in pending {
+        let needed = match migration.condition(table_prefix) {
+            Some(condition) => conn.query_first::<u32, _>(condition)?.unwrap_or_default() > 0,
+            None => true,
         };
 
+        if needed {
+            for statement in migration.statements(table_prefix) {
+                conn.query_drop(statement)?;
+            }
+        }
+
         conn.exec_drop(
-            "INSERT INTO `keys` (`key`, valid_until) VALUES (:key, :valid_until)",
-            params! { key, valid_until },
+            with_table_prefix(
+                "INSERT INTO {prefix}schema_version (version, description, applied_at) VALUES (:version, :description, :applied_at)",
+                table_prefix,
+            ),
+            params! { "version" => migration.version, "description" => migration.description, "applied_at" => CurrentClock::now().as_secs() },
         )?;
 
-        Ok(1)
+        tracing::info!(
+            version = migration.version,
+            description = migration.description,
+            "applied a database migration"
+        );
     }
 
-    /// Refer to [`databases::Database::remove_key_from_keys`](crate::core::databases::Database::remove_key_from_keys).
-    fn remove_key_from_keys(&self, key: &Key) -> Result<usize, Error> {
-        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
-
-        conn.exec_drop("DELETE FROM `keys` WHERE key = :key", params! { "key" => key.to_string() })?;
+    Ok(pending.iter().collect())
+}
 
-        Ok(1)
+fn peer_key_from_row((key, valid_until, created_at, label): KeyRow) -> auth::PeerKey {
+    auth::PeerKey {
+        key: key.parse::<Key>().unwrap(),
+        valid_until: valid_until.map(|valid_until| DurationSinceUnixEpoch::from_secs(valid_until.unsigned_abs())),
+        created_at: created_at.map(|created_at| DurationSinceUnixEpoch::from_secs(created_at.unsigned_abs())),
+        label,
     }
 }
@@ -2,23 +2,95 @@
 use std::panic::Location;
 use std::str::FromStr;
 
-use r2d2::Pool;
-use r2d2_sqlite::rusqlite::params;
-use r2d2_sqlite::rusqlite::types::Null;
+use async_trait::async_trait;
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::rusqlite::{params, Row};
 use r2d2_sqlite::SqliteConnectionManager;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_configuration::DatabasePool;
 use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::pagination::Pagination;
 use torrust_tracker_primitives::{DurationSinceUnixEpoch, PersistentTorrents};
 
 use super::driver::Driver;
-use super::{Database, Error};
+use super::migrations::{self, Migration};
+use super::{pool_builder, with_table_prefix, Database, Error, PoolStats};
+use crate::core::api_tokens::RotatedToken;
+use crate::core::audit;
 use crate::core::auth::{self, Key};
+use crate::core::torrent_history::Bucket;
+use crate::CurrentClock;
 
 const DRIVER: Driver = Driver::Sqlite3;
 
+const CREATE_SCHEMA_VERSION_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS {prefix}schema_version (
+        version INTEGER PRIMARY KEY,
+        description TEXT NOT NULL,
+        applied_at INTEGER NOT NULL
+    );";
+
 pub struct Sqlite {
     pool: Pool<SqliteConnectionManager>,
+    table_prefix: String,
 }
 
+impl Sqlite {
+    /// It instantiates a new `SQLite3` database driver for a database encrypted
+    /// with `SQLCipher`. The key is set on each connection of the pool. A new
+    /// database is encrypted with the key.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if unable to create the connection pool, or if the
+    /// key does not decrypt the database.
+    #[cfg(feature = "sqlcipher")]
+    pub fn encrypted(db_path: &str, pool: &DatabasePool, table_prefix: &str, encryption_key: &str) -> Result<Sqlite, Error> {
+        let encryption_key = encryption_key.to_string();
+
+        let cm = SqliteConnectionManager::file(db_path).with_init(move |conn| conn.pragma_update(None, "key", &encryption_key));
+
+        let pool = pool_builder(pool).build(cm).map_err(|e| (e, DRIVER))?;
+
+        // A wrong key is only detected when the database is read.
+        pool.get()
+            .map_err(|e| (e, DRIVER))?
+            .query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))?;
+
+        Ok(Sqlite {
+            pool,
+            table_prefix: table_prefix.to_string(),
+        })
+    }
+
+    /// It returns the `sql` with the table prefix of the database.
+    fn sql(&self, sql: &str) -> String {
+        with_table_prefix(sql, &self.table_prefix)
+    }
+
+    /// It runs the `query` with a connection of the pool. The driver is
+    /// blocking, so the query runs on the blocking threads of the runtime
+    /// instead of the worker threads serving the requests, with
+    /// [`tokio::task::spawn_blocking`].
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the query fails, or if the task running it
+    /// panics or is cancelled.
+    async fn run<T, F>(&self, query: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(PooledConnection<SqliteConnectionManager>) -> Result<T, Error> + Send + 'static,
+    {
+        let pool = self.pool.clone();
+
+        tokio::task::spawn_blocking(move || query(pool.get().map_err(|e| (e, DRIVER))?))
+            .await
+            .map_err(|e| (e, DRIVER))?
+    }
+}
+
+#[async_trait]
 impl Database for Sqlite {
     /// It instantiates a new `SQLite3` database driver.
     ///
@@ -27,261 +99,598 @@ impl Database for Sqlite {
     /// # Errors
     ///
     /// Will return `r2d2::Error` if `db_path` is not able to create `SqLite` database.
-    fn new(db_path: &str) -> Result<Sqlite, Error> {
+    fn new(db_path: &str, pool: &DatabasePool, table_prefix: &str) -> Result<Sqlite, Error> {
         let cm = SqliteConnectionManager::file(db_path);
-        Pool::new(cm).map_or_else(|err| Err((err, Driver::Sqlite3).into()), |pool| Ok(Sqlite { pool }))
+        pool_builder(pool).build(cm).map_or_else(
+            |err| Err((err, Driver::Sqlite3).into()),
+            |pool| {
+                Ok(Sqlite {
+                    pool,
+                    table_prefix: table_prefix.to_string(),
+                })
+            },
+        )
     }
 
-    /// Refer to [`databases::Database::create_database_tables`](crate::core::databases::Database::create_database_tables).
-    fn create_database_tables(&self) -> Result<(), Error> {
-        let create_whitelist_table = "
-        CREATE TABLE IF NOT EXISTS whitelist (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            info_hash TEXT NOT NULL UNIQUE
-        );"
-        .to_string();
-
-        let create_torrents_table = "
-        CREATE TABLE IF NOT EXISTS torrents (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            info_hash TEXT NOT NULL UNIQUE,
-            completed INTEGER DEFAULT 0 NOT NULL
-        );"
-        .to_string();
-
-        let create_keys_table = "
-        CREATE TABLE IF NOT EXISTS keys (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            key TEXT NOT NULL UNIQUE,
-            valid_until INTEGER
-         );"
-        .to_string();
+    /// Refer to [`databases::Database::pool_stats`](crate::core::databases::Database::pool_stats).
+    fn pool_stats(&self) -> PoolStats {
+        PoolStats::from(&self.pool)
+    }
 
+    /// Refer to [`databases::Database::schema_version`](crate::core::databases::Database::schema_version).
+    fn schema_version(&self) -> Result<u32, Error> {
         let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
 
-        conn.execute(&create_whitelist_table, [])?;
-        conn.execute(&create_keys_table, [])?;
-        conn.execute(&create_torrents_table, [])?;
+        conn.execute(&self.sql(CREATE_SCHEMA_VERSION_TABLE), [])?;
 
-        Ok(())
+        Ok(conn.query_row(
+            &self.sql("SELECT COALESCE(MAX(version), 0) FROM {prefix}schema_version"),
+            [],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Refer to [`databases::Database::migrate`](crate::core::databases::Database::migrate).
+    fn migrate(&self) -> Result<Vec<&'static Migration>, Error> {
+        let version = self.schema_version()?;
+
+        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+
+        let pending = migrations::pending(migrations::SQLITE, version);
+
+        for migration in pending {
+            let tx = conn.transaction()?;
+
+            let needed = match migration.condition(&self.table_prefix) {
+                Some(condition) => tx.query_row(&condition, [], |row| row.get::<_, u32>(0))? > 0,
+                None => true,
+            };
+
+            if needed {
+                for statement in migration.statements(&self.table_prefix) {
+                    tx.execute(&statement, [])?;
+                }
+            }
+
+            tx.execute(
+                &self.sql("INSERT INTO {prefix}schema_version (version, description, applied_at) VALUES (?1, ?2, ?3)"),
+                params![migration.version, migration.description, CurrentClock::now().as_secs()],
+            )?;
+
+            tx.commit()?;
+
+            tracing::info!(
+                version = migration.version,
+                description = migration.description,
+                "applied a database migration"
+            );
+        }
+
+        Ok(pending.iter().collect())
     }
 
     /// Refer to [`databases::Database::drop_database_tables`](crate::core::databases::Database::drop_database_tables).
     fn drop_database_tables(&self) -> Result<(), Error> {
-        let drop_whitelist_table = "
-        DROP TABLE whitelist;"
-            .to_string();
+        let drop_whitelist_table = self.sql("DROP TABLE {prefix}whitelist;");
+
+        let drop_torrents_table = self.sql("DROP TABLE {prefix}torrents;");
+
+        let drop_torrent_history_table = self.sql("DROP TABLE {prefix}torrent_history;");
 
-        let drop_torrents_table = "
-        DROP TABLE torrents;"
-            .to_string();
+        let drop_audit_log_table = self.sql("DROP TABLE {prefix}audit_log;");
 
-        let drop_keys_table = "
-        DROP TABLE keys;"
-            .to_string();
+        let drop_api_tokens_table = self.sql("DROP TABLE {prefix}api_tokens;");
+
+        let drop_keys_table = self.sql("DROP TABLE {prefix}keys;");
+
+        let drop_schema_version_table = self.sql("DROP TABLE {prefix}schema_version;");
 
         let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
 
         conn.execute(&drop_whitelist_table, [])
             .and_then(|_| conn.execute(&drop_torrents_table, []))
-            .and_then(|_| conn.execute(&drop_keys_table, []))?;
+            .and_then(|_| conn.execute(&drop_torrent_history_table, []))
+            .and_then(|_| conn.execute(&drop_audit_log_table, []))
+            .and_then(|_| conn.execute(&drop_api_tokens_table, []))
+            .and_then(|_| conn.execute(&drop_keys_table, []))
+            .and_then(|_| conn.execute(&drop_schema_version_table, []))?;
 
         Ok(())
     }
 
-    /// Refer to [`databases::Database::load_persistent_torrents`](crate::core::databases::Database::load_persistent_torrents).
-    fn load_persistent_torrents(&self) -> Result<PersistentTorrents, Error> {
-        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    /// Refer to [`databases::Database::ping`](crate::core::databases::Database::ping).
+    async fn ping(&self) -> Result<(), Error> {
+        self.run(move |conn| {
+            conn.query_row("SELECT 1", [], |_| Ok(()))?;
 
-        let mut stmt = conn.prepare("SELECT info_hash, completed FROM torrents")?;
-
-        let torrent_iter = stmt.query_map([], |row| {
-            let info_hash_string: String = row.get(0)?;
-            let info_hash = InfoHash::from_str(&info_hash_string).unwrap();
-            let completed: u32 = row.get(1)?;
-            Ok((info_hash, completed))
-        })?;
+            Ok(())
+        })
+        .await
+    }
 
-        Ok(torrent_iter.filter_map(std::result::Result::ok).collect())
+    /// Refer to [`databases::Database::load_persistent_torrents`](crate::core::databases::Database::load_persistent_torrents).
+    async fn load_persistent_torrents(&self) -> Result<PersistentTorrents, Error> {
+        let query = self.sql("SELECT info_hash, completed FROM {prefix}torrents");
+
+        self.run(move |conn| {
+            let mut stmt = conn.prepare(&query)?;
+
+            let torrent_iter = stmt.query_map([], |row| {
+                let info_hash_string: String = row.get(0)?;
+                let info_hash = InfoHash::from_str(&info_hash_string).unwrap();
+                let completed: u32 = row.get(1)?;
+                Ok((info_hash, completed))
+            })?;
+
+            Ok(torrent_iter.filter_map(std::result::Result::ok).collect())
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::load_keys`](crate::core::databases::Database::load_keys).
-    fn load_keys(&self) -> Result<Vec<auth::PeerKey>, Error> {
-        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    async fn load_keys(&self) -> Result<Vec<auth::PeerKey>, Error> {
+        let query = self.sql("SELECT key, valid_until, created_at, label FROM {prefix}keys");
 
-        let mut stmt = conn.prepare("SELECT key, valid_until FROM keys")?;
-
-        let keys_iter = stmt.query_map([], |row| {
-            let key: String = row.get(0)?;
-            let opt_valid_until: Option<i64> = row.get(1)?;
-
-            match opt_valid_until {
-                Some(valid_until) => Ok(auth::PeerKey {
-                    key: key.parse::<Key>().unwrap(),
-                    valid_until: Some(DurationSinceUnixEpoch::from_secs(valid_until.unsigned_abs())),
-                }),
-                None => Ok(auth::PeerKey {
-                    key: key.parse::<Key>().unwrap(),
-                    valid_until: None,
-                }),
-            }
-        })?;
+        self.run(move |conn| {
+            let mut stmt = conn.prepare(&query)?;
+
+            let keys_iter = stmt.query_map([], peer_key_from_row)?;
 
-        let keys: Vec<auth::PeerKey> = keys_iter.filter_map(std::result::Result::ok).collect();
+            let keys: Vec<auth::PeerKey> = keys_iter.filter_map(std::result::Result::ok).collect();
 
-        Ok(keys)
+            Ok(keys)
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::load_whitelist`](crate::core::databases::Database::load_whitelist).
-    fn load_whitelist(&self) -> Result<Vec<InfoHash>, Error> {
-        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    async fn load_whitelist(&self) -> Result<Vec<InfoHash>, Error> {
+        let query = self.sql("SELECT info_hash FROM {prefix}whitelist");
 
-        let mut stmt = conn.prepare("SELECT info_hash FROM whitelist")?;
+        self.run(move |conn| {
+            let mut stmt = conn.prepare(&query)?;
 
-        let info_hash_iter = stmt.query_map([], |row| {
-            let info_hash: String = row.get(0)?;
+            let info_hash_iter = stmt.query_map([], |row| {
+                let info_hash: String = row.get(0)?;
 
-            Ok(InfoHash::from_str(&info_hash).unwrap())
-        })?;
+                Ok(InfoHash::from_str(&info_hash).unwrap())
+            })?;
 
-        let info_hashes: Vec<InfoHash> = info_hash_iter.filter_map(std::result::Result::ok).collect();
+            let info_hashes: Vec<InfoHash> = info_hash_iter.filter_map(std::result::Result::ok).collect();
 
-        Ok(info_hashes)
+            Ok(info_hashes)
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::save_persistent_torrent`](crate::core::databases::Database::save_persistent_torrent).
-    fn save_persistent_torrent(&self, info_hash: &InfoHash, completed: u32) -> Result<(), Error> {
-        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    async fn save_persistent_torrent(&self, info_hash: &InfoHash, completed: u32) -> Result<(), Error> {
+        let info_hash = *info_hash;
+
+        let query = self.sql("INSERT INTO {prefix}torrents (info_hash, completed) VALUES (?1, ?2) ON CONFLICT(info_hash) DO UPDATE SET completed = ?2");
+
+        self.run(move |conn| {
+            let insert = conn.execute(&query, [info_hash.to_string(), completed.to_string()])?;
+
+            if insert == 0 {
+                Err(Error::InsertFailed {
+                    location: Location::caller(),
+                    driver: DRIVER,
+                })
+            } else {
+                Ok(())
+            }
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::save_persistent_torrents`](crate::core::databases::Database::save_persistent_torrents).
+    async fn save_persistent_torrents(&self, torrents: &PersistentTorrents) -> Result<(), Error> {
+        let torrents = torrents.clone();
+
+        let query = self.sql("INSERT INTO {prefix}torrents (info_hash, completed) VALUES (?1, ?2) ON CONFLICT(info_hash) DO UPDATE SET completed = ?2");
+
+        self.run(move |mut conn| {
+            let tx = conn.transaction()?;
+
+            {
+                let mut insert = tx.prepare_cached(&query)?;
+
+                for (info_hash, completed) in torrents {
+                    insert.execute(params![info_hash.to_string(), completed])?;
+                }
+            }
+
+            tx.commit()?;
 
-        let insert = conn.execute(
-            "INSERT INTO torrents (info_hash, completed) VALUES (?1, ?2) ON CONFLICT(info_hash) DO UPDATE SET completed = ?2",
-            [info_hash.to_string(), completed.to_string()],
-        )?;
-
-        if insert == 0 {
-            Err(Error::InsertFailed {
-                location: Location::caller(),
-                driver: DRIVER,
-            })
-        } else {
             Ok(())
-        }
+        })
+        .await
     }
 
-    /// Refer to [`databases::Database::get_info_hash_from_whitelist`](crate::core::databases::Database::get_info_hash_from_whitelist).
-    fn get_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<Option<InfoHash>, Error> {
-        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    /// Refer to [`databases::Database::remove_persistent_torrent`](crate::core::databases::Database::remove_persistent_torrent).
+    async fn remove_persistent_torrent(&self, info_hash: &InfoHash) -> Result<(), Error> {
+        let info_hash = *info_hash;
+
+        let query = self.sql("DELETE FROM {prefix}torrents WHERE info_hash = ?");
+
+        self.run(move |conn| {
+            conn.execute(&query, [info_hash.to_string()])?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::load_torrent_history`](crate::core::databases::Database::load_torrent_history).
+    async fn load_torrent_history(&self, info_hash: &InfoHash, from: u64, to: u64) -> Result<Vec<Bucket>, Error> {
+        let info_hash = *info_hash;
 
-        let mut stmt = conn.prepare("SELECT info_hash FROM whitelist WHERE info_hash = ?")?;
+        let query = self.sql("SELECT hour, completed, peak_seeders, peak_leechers FROM {prefix}torrent_history WHERE info_hash = ?1 AND hour >= ?2 AND hour <= ?3 ORDER BY hour");
 
-        let mut rows = stmt.query([info_hash.to_hex_string()])?;
+        self.run(move |conn| {
+            let mut stmt = conn.prepare(&query)?;
 
-        let query = rows.next()?;
+            let bucket_iter = stmt.query_map(params![info_hash.to_string(), from, to], |row| {
+                Ok(Bucket {
+                    start: row.get(0)?,
+                    completed: row.get(1)?,
+                    peak_seeders: row.get(2)?,
+                    peak_leechers: row.get(3)?,
+                })
+            })?;
 
-        Ok(query.map(|f| InfoHash::from_str(&f.get_unwrap::<_, String>(0)).unwrap()))
+            Ok(bucket_iter.filter_map(std::result::Result::ok).collect())
+        })
+        .await
     }
 
-    /// Refer to [`databases::Database::add_info_hash_to_whitelist`](crate::core::databases::Database::add_info_hash_to_whitelist).
-    fn add_info_hash_to_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error> {
-        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    /// Refer to [`databases::Database::save_torrent_history`](crate::core::databases::Database::save_torrent_history).
+    async fn save_torrent_history(&self, info_hash: &InfoHash, bucket: &Bucket) -> Result<(), Error> {
+        let info_hash = *info_hash;
+        let bucket = *bucket;
+
+        let query = self.sql("INSERT INTO {prefix}torrent_history (info_hash, hour, completed, peak_seeders, peak_leechers) VALUES (?1, ?2, ?3, ?4, ?5) ON CONFLICT(info_hash, hour) DO UPDATE SET completed = completed + ?3, peak_seeders = MAX(peak_seeders, ?4), peak_leechers = MAX(peak_leechers, ?5)");
+
+        self.run(move |conn| {
+            let insert = conn.execute(
+                &query,
+                params![
+                    info_hash.to_string(),
+                    bucket.start,
+                    bucket.completed,
+                    bucket.peak_seeders,
+                    bucket.peak_leechers
+                ],
+            )?;
+
+            if insert == 0 {
+                Err(Error::InsertFailed {
+                    location: Location::caller(),
+                    driver: DRIVER,
+                })
+            } else {
+                Ok(())
+            }
+        })
+        .await
+    }
 
-        let insert = conn.execute("INSERT INTO whitelist (info_hash) VALUES (?)", [info_hash.to_string()])?;
+    /// Refer to [`databases::Database::get_info_hash_from_whitelist`](crate::core::databases::Database::get_info_hash_from_whitelist).
+    async fn get_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<Option<InfoHash>, Error> {
+        let query = self.sql("SELECT info_hash FROM {prefix}whitelist WHERE info_hash = ?");
 
-        if insert == 0 {
-            Err(Error::InsertFailed {
-                location: Location::caller(),
-                driver: DRIVER,
-            })
-        } else {
-            Ok(insert)
-        }
+        self.run(move |conn| {
+            let mut stmt = conn.prepare(&query)?;
+
+            let mut rows = stmt.query([info_hash.to_hex_string()])?;
+
+            let query = rows.next()?;
+
+            Ok(query.map(|f| InfoHash::from_str(&f.get_unwrap::<_, String>(0)).unwrap()))
+        })
+        .await
     }
 
-    /// Refer to [`databases::Database::remove_info_hash_from_whitelist`](crate::core::databases::Database::remove_info_hash_from_whitelist).
-    fn remove_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error> {
-        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    /// Refer to [`databases::Database::add_info_hash_to_whitelist`](crate::core::databases::Database::add_info_hash_to_whitelist).
+    async fn add_info_hash_to_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error> {
+        let query = self.sql("INSERT INTO {prefix}whitelist (info_hash) VALUES (?)");
+
+        self.run(move |conn| {
+            let insert = conn.execute(&query, [info_hash.to_string()])?;
+
+            if insert == 0 {
+                Err(Error::InsertFailed {
+                    location: Location::caller(),
+                    driver: DRIVER,
+                })
+            } else {
+                Ok(insert)
+            }
+        })
+        .await
+    }
 
-        let deleted = conn.execute("DELETE FROM whitelist WHERE info_hash = ?", [info_hash.to_string()])?;
-
-        if deleted == 1 {
-            // should only remove a single record.
-            Ok(deleted)
-        } else {
-            Err(Error::DeleteFailed {
-                location: Location::caller(),
-                error_code: deleted,
-                driver: DRIVER,
-            })
-        }
+    /// Refer to [`databases::Database::remove_info_hash_from_whitelist`](crate::core::databases::Database::remove_info_hash_from_whitelist).
+    async fn remove_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error> {
+        let query = self.sql("DELETE FROM {prefix}whitelist WHERE info_hash = ?");
+
+        self.run(move |conn| {
+            let deleted = conn.execute(&query, [info_hash.to_string()])?;
+
+            if deleted == 1 {
+                // should only remove a single record.
+                Ok(deleted)
+            } else {
+                Err(Error::DeleteFailed {
+                    location: Location::caller(),
+                    error_code: deleted,
+                    driver: DRIVER,
+                })
+            }
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::get_key_from_keys`](crate::core::databases::Database::get_key_from_keys).
-    fn get_key_from_keys(&self, key: &Key) -> Result<Option<auth::PeerKey>, Error> {
-        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    async fn get_key_from_keys(&self, key: &Key) -> Result<Option<auth::PeerKey>, Error> {
+        let key = key.clone();
 
-        let mut stmt = conn.prepare("SELECT key, valid_until FROM keys WHERE key = ?")?;
+        let query = self.sql("SELECT key, valid_until, created_at, label FROM {prefix}keys WHERE key = ?");
 
-        let mut rows = stmt.query([key.to_string()])?;
+        self.run(move |conn| {
+            let mut stmt = conn.prepare(&query)?;
 
-        let key = rows.next()?;
+            let mut rows = stmt.query([key.to_string()])?;
 
-        Ok(key.map(|f| {
-            let valid_until: Option<i64> = f.get(1).unwrap();
-            let key: String = f.get(0).unwrap();
+            let key = rows.next()?;
 
-            match valid_until {
-                Some(valid_until) => auth::PeerKey {
-                    key: key.parse::<Key>().unwrap(),
-                    valid_until: Some(DurationSinceUnixEpoch::from_secs(valid_until.unsigned_abs())),
-                },
-                None => auth::PeerKey {
-                    key: key.parse::<Key>().unwrap(),
-                    valid_until: None,
-                },
-            }
-        }))
+            Ok(key.map(|row| peer_key_from_row(row).unwrap()))
+        })
+        .await
     }
 
     /// Refer to [`databases::Database::add_key_to_keys`](crate::core::databases::Database::add_key_to_keys).
-    fn add_key_to_keys(&self, auth_key: &auth::PeerKey) -> Result<usize, Error> {
-        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    async fn add_key_to_keys(&self, auth_key: &auth::PeerKey) -> Result<usize, Error> {
+        let auth_key = auth_key.clone();
+
+        let query = self.sql("INSERT INTO {prefix}keys (key, valid_until, created_at, label) VALUES (?1, ?2, ?3, ?4)");
+
+        self.run(move |conn| {
+            let insert = conn.execute(
+                &query,
+                params![
+                    auth_key.key.to_string(),
+                    auth_key.valid_until.map(|valid_until| valid_until.as_secs()),
+                    auth_key.created_at.map(|created_at| created_at.as_secs()),
+                    auth_key.label
+                ],
+            )?;
+
+            if insert == 0 {
+                Err(Error::InsertFailed {
+                    location: Location::caller(),
+                    driver: DRIVER,
+                })
+            } else {
+                Ok(insert)
+            }
+        })
+        .await
+    }
 
-        let insert = match auth_key.valid_until {
-            Some(valid_until) => conn.execute(
-                "INSERT INTO keys (key, valid_until) VALUES (?1, ?2)",
-                [auth_key.key.to_string(), valid_until.as_secs().to_string()],
-            )?,
-            None => conn.execute(
-                "INSERT INTO keys (key, valid_until) VALUES (?1, ?2)",
-                params![auth_key.key.to_string(), Null],
-            )?,
-        };
-
-        if insert == 0 {
-            Err(Error::InsertFailed {
-                location: Location::caller(),
-                driver: DRIVER,
-            })
-        } else {
-            Ok(insert)
-        }
+    /// Refer to [`databases::Database::remove_expired_keys`](crate::core::databases::Database::remove_expired_keys).
+    async fn remove_expired_keys(&self, now: DurationSinceUnixEpoch) -> Result<usize, Error> {
+        let query = self.sql("DELETE FROM {prefix}keys WHERE valid_until < ?");
+
+        self.run(move |conn| Ok(conn.execute(&query, [now.as_secs()])?)).await
     }
 
     /// Refer to [`databases::Database::remove_key_from_keys`](crate::core::databases::Database::remove_key_from_keys).
-    fn remove_key_from_keys(&self, key: &Key) -> Result<usize, Error> {
-        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+    async fn remove_key_from_keys(&self, key: &Key) -> Result<usize, Error> {
+        let key = key.clone();
+
+        let query = self.sql("DELETE FROM {prefix}keys WHERE key = ?");
+
+        self.run(move |conn| {
+            let deleted = conn.execute(&query, [key.to_string()])?;
+
+            if deleted == 1 {
+                // should only remove a single record.
+                Ok(deleted)
+            } else {
+                Err(Error::DeleteFailed {
+                    location: Location::caller(),
+                    error_code: deleted,
+                    driver: DRIVER,
+                })
+            }
+        })
+        .await
+    }
 
-        let deleted = conn.execute("DELETE FROM keys WHERE key = ?", [key.to_string()])?;
-
-        if deleted == 1 {
-            // should only remove a single record.
-            Ok(deleted)
-        } else {
-            Err(Error::DeleteFailed {
-                location: Location::caller(),
-                error_code: deleted,
-                driver: DRIVER,
-            })
-        }
+    /// Refer to [`databases::Database::add_audit_entry`](crate::core::databases::Database::add_audit_entry).
+    async fn add_audit_entry(&self, entry: &audit::Entry) -> Result<(), Error> {
+        let entry = entry.clone();
+
+        let query = self.sql("INSERT INTO {prefix}audit_log (timestamp, actor, client_ip, method, path, status, payload) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)");
+
+        self.run(move |conn| {
+            let insert = conn.execute(
+                &query,
+                params![
+                    entry.timestamp,
+                    entry.actor,
+                    entry.client_ip.map(|client_ip| client_ip.to_string()),
+                    entry.method,
+                    entry.path,
+                    entry.status,
+                    entry.payload
+                ],
+            )?;
+
+            if insert == 0 {
+                Err(Error::InsertFailed {
+                    location: Location::caller(),
+                    driver: DRIVER,
+                })
+            } else {
+                Ok(())
+            }
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::load_audit_entries`](crate::core::databases::Database::load_audit_entries).
+    async fn load_audit_entries(&self, filter: &audit::Filter, pagination: &Pagination) -> Result<Vec<audit::Entry>, Error> {
+        let filter = filter.clone();
+        let pagination = *pagination;
+
+        let query = self.sql("SELECT timestamp, actor, client_ip, method, path, status, payload FROM {prefix}audit_log WHERE (?1 IS NULL OR actor = ?1) AND (?2 IS NULL OR method = ?2) AND (?3 IS NULL OR substr(path, 1, length(?3)) = ?3) AND (?4 IS NULL OR timestamp >= ?4) AND (?5 IS NULL OR timestamp <= ?5) ORDER BY id DESC LIMIT ?6 OFFSET ?7");
+
+        self.run(move |conn| {
+            let mut stmt = conn.prepare(&query)?;
+
+            let entry_iter = stmt.query_map(
+                params![
+                    filter.actor,
+                    filter.method,
+                    filter.path_prefix,
+                    filter.from,
+                    filter.to,
+                    pagination.limit,
+                    pagination.offset
+                ],
+                |row| {
+                    let client_ip: Option<String> = row.get(2)?;
+
+                    Ok(audit::Entry {
+                        timestamp: row.get(0)?,
+                        actor: row.get(1)?,
+                        client_ip: client_ip.and_then(|client_ip| client_ip.parse().ok()),
+                        method: row.get(3)?,
+                        path: row.get(4)?,
+                        status: row.get(5)?,
+                        payload: row.get(6)?,
+                    })
+                },
+            )?;
+
+            Ok(entry_iter.filter_map(std::result::Result::ok).collect())
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::load_rotated_api_tokens`](crate::core::databases::Database::load_rotated_api_tokens).
+    async fn load_rotated_api_tokens(&self) -> Result<Vec<RotatedToken>, Error> {
+        let query = self.sql("SELECT label, token, previous_token, previous_token_valid_until FROM {prefix}api_tokens");
+
+        self.run(move |conn| {
+            let mut stmt = conn.prepare(&query)?;
+
+            let token_iter = stmt.query_map([], |row| {
+                Ok(RotatedToken {
+                    label: row.get(0)?,
+                    token: row.get(1)?,
+                    previous_token: row.get(2)?,
+                    previous_token_valid_until: row.get(3)?,
+                })
+            })?;
+
+            Ok(token_iter.filter_map(std::result::Result::ok).collect())
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::save_rotated_api_token`](crate::core::databases::Database::save_rotated_api_token).
+    async fn save_rotated_api_token(&self, rotated_token: &RotatedToken) -> Result<(), Error> {
+        let rotated_token = rotated_token.clone();
+
+        let query = self.sql("INSERT INTO {prefix}api_tokens (label, token, previous_token, previous_token_valid_until) VALUES (?1, ?2, ?3, ?4) ON CONFLICT(label) DO UPDATE SET token = ?2, previous_token = ?3, previous_token_valid_until = ?4");
+
+        self.run(move |conn| {
+            let insert = conn.execute(
+                &query,
+                params![
+                    rotated_token.label,
+                    rotated_token.token,
+                    rotated_token.previous_token,
+                    rotated_token.previous_token_valid_until
+                ],
+            )?;
+
+            if insert == 0 {
+                Err(Error::InsertFailed {
+                    location: Location::caller(),
+                    driver: DRIVER,
+                })
+            } else {
+                Ok(())
+            }
+        })
+        .await
+    }
+
+    /// Refer to [`databases::Database::compact`](crate::core::databases::Database::compact).
+    async fn compact(&self) -> Result<(), Error> {
+        self.run(move |conn| {
+            conn.execute("VACUUM", [])?;
+
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// It reads a key from the `key, valid_until, created_at, label` columns.
+fn peer_key_from_row(row: &Row<'_>) -> Result<auth::PeerKey, r2d2_sqlite::rusqlite::Error> {
+    let key: String = row.get(0)?;
+    let valid_until: Option<i64> = row.get(1)?;
+    let created_at: Option<i64> = row.get(2)?;
+
+    Ok(auth::PeerKey {
+        key: key.parse::<Key>().unwrap(),
+        valid_until: valid_until.map(|valid_until| DurationSinceUnixEpoch::from_secs(valid_until.unsigned_abs())),
+        created_at: created_at.map(|created_at| DurationSinceUnixEpoch::from_secs(created_at.unsigned_abs())),
+        label: row.get(3)?,
+    })
+}
+
+#[cfg(all(test, feature = "sqlcipher"))]
+mod tests {
+    use torrust_tracker_configuration::DatabasePool;
+
+    use super::Sqlite;
+    use crate::core::databases::error::Error;
+    use crate::core::databases::Database;
+
+    #[test]
+    fn it_should_only_open_the_encrypted_database_with_its_key() {
+        let path = std::env::temp_dir().join(format!("encrypted_{}.db", rand::random::<u64>()));
+        let path = path.to_str().unwrap();
+
+        Sqlite::encrypted(path, &DatabasePool::default(), "", "MyEncryptionKey")
+            .unwrap()
+            .migrate()
+            .unwrap();
+
+        assert!(Sqlite::encrypted(path, &DatabasePool::default(), "", "AnotherKey").is_err());
+        assert!(Sqlite::new(path, &DatabasePool::default(), "")
+            .and_then(|database| database.schema_version())
+            .is_err());
+        assert!(Sqlite::encrypted(path, &DatabasePool::default(), "", "MyEncryptionKey")
+            .and_then(|database| database.schema_version())
+            .is_ok_and(|version| version > 0));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_should_return_an_error_when_a_query_panics() {
+        let database = Sqlite::new(":memory:", &DatabasePool::default(), "").unwrap();
+
+        let result = database.run(|_| -> Result<(), Error> { panic!("the query panicked") }).await;
+
+        assert!(matches!(result, Err(Error::QueryAborted { .. })));
     }
 }
@@ -0,0 +1,96 @@
+//! Announce intervals adjusted to the size of the swarms and to the load.
+//!
+//! The configured announce interval is scaled by two factors:
+//!
+//! - The square root of the ratio between the number of peers in the swarm
+//!   and the reference swarm size. Peers in small swarms announce more often,
+//!   so they discover each other sooner, while huge swarms, which generate
+//!   most of the requests, announce less often.
+//! - The ratio between the requests per second and the reference rate, only
+//!   while the tracker receives more requests than the reference. The
+//!   intervals are never lowered because of the load.
+//!
+//! The result is clamped to the configured bounds.
+//!
+//! Refer to the [`DynamicInterval`] configuration for more information.
+use torrust_tracker_configuration::{AnnouncePolicy, DynamicInterval};
+
+/// It adjusts the announce `policy` for a swarm with `swarm_size` peers,
+/// while the tracker receives `requests_per_second` requests.
+///
+/// The minimum announce interval is lowered when needed, so it's never
+/// greater than the interval.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss, clippy::cast_sign_loss)]
+pub fn adjust(policy: AnnouncePolicy, config: &DynamicInterval, swarm_size: u32, requests_per_second: u64) -> AnnouncePolicy {
+    if !config.is_enabled() {
+        return policy;
+    }
+
+    let swarm_factor = config
+        .reference_swarm_size
+        .map_or(1.0, |reference| (f64::from(swarm_size.max(1)) / f64::from(reference)).sqrt());
+
+    let load_factor = config
+        .reference_requests_per_second
+        .map_or(1.0, |reference| (requests_per_second as f64 / f64::from(reference)).max(1.0));
+
+    let interval = (f64::from(policy.interval) * swarm_factor * load_factor)
+        .round()
+        .clamp(f64::from(config.min_interval), f64::from(config.max_interval)) as u32;
+
+    AnnouncePolicy {
+        interval,
+        interval_min: policy.interval_min.min(interval),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use torrust_tracker_configuration::{AnnouncePolicy, DynamicInterval};
+
+    use super::adjust;
+
+    fn config(reference_swarm_size: Option<u32>, reference_requests_per_second: Option<u32>) -> DynamicInterval {
+        DynamicInterval {
+            reference_swarm_size,
+            reference_requests_per_second,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_should_not_change_the_announce_policy_when_it_is_disabled() {
+        let policy = AnnouncePolicy::new(120, 120);
+
+        assert_eq!(adjust(policy, &DynamicInterval::default(), 10_000, 100_000), policy);
+    }
+
+    #[test]
+    fn it_should_scale_the_interval_with_the_square_root_of_the_swarm_size() {
+        let config = config(Some(100), None);
+        let policy = AnnouncePolicy::new(120, 60);
+
+        assert_eq!(adjust(policy, &config, 100, 0), policy);
+        assert_eq!(adjust(policy, &config, 400, 0), AnnouncePolicy::new(240, 60));
+        assert_eq!(adjust(policy, &config, 25, 0), AnnouncePolicy::new(60, 60));
+    }
+
+    #[test]
+    fn it_should_keep_the_interval_within_the_bounds() {
+        let config = config(Some(100), None);
+        let policy = AnnouncePolicy::new(120, 120);
+
+        assert_eq!(adjust(policy, &config, 1, 0), AnnouncePolicy::new(60, 60));
+        assert_eq!(adjust(policy, &config, 10_000_000, 0), AnnouncePolicy::new(3600, 120));
+    }
+
+    #[test]
+    fn it_should_only_raise_the_interval_while_the_load_is_above_the_reference() {
+        let config = config(None, Some(1000));
+        let policy = AnnouncePolicy::new(120, 120);
+
+        assert_eq!(adjust(policy, &config, 1, 500), policy);
+        assert_eq!(adjust(policy, &config, 1, 3000), AnnouncePolicy::new(360, 120));
+    }
+}
@@ -5,13 +5,21 @@
 //! `PeerKeyNotValid` | Authentication | The supplied key is not valid. It may not be registered or expired.
 //! `PeerNotAuthenticated` | Authentication | The peer did not provide the authentication key.
 //! `TorrentNotWhitelisted` | Authorization | The action cannot be perform on a not-whitelisted torrent (it only applies for trackers running in `listed` or `private_listed` modes).
+//! `TorrentBlacklisted` | Authorization | The action cannot be perform on a torrent blacklisted after an abuse report.
+//! `PeerIpBanned` | Authorization | The peer IP address has been banned after an abuse report.
+//! `ClientNotWhitelisted` | Authorization | The client of the peer is not in the client whitelist (it only applies for trackers running in `private` mode).
+//! `AnnounceBudgetExceeded` | Rate limiting | The tracker is saturated and the peer IP address has used its share of the announce budget.
+//! `TrackerOverloaded` | Overload | The tracker is overloaded and it does not handle low-priority requests.
 //!
+//! The [`BootstrapError`] is returned when the `Tracker` can't be built from
+//! its configuration.
 use std::panic::Location;
 
+use camino::Utf8PathBuf;
 use torrust_tracker_located_error::LocatedError;
 use torrust_tracker_primitives::info_hash::InfoHash;
 
-use super::auth::ParseKeyError;
+use super::auth::{Key, ParseKeyError};
 use super::databases;
 
 /// Authentication or authorization error returned by the core `Tracker`
@@ -33,6 +41,38 @@ pub enum Error {
         info_hash: InfoHash,
         location: &'static Location<'static>,
     },
+
+    #[error("The torrent: {info_hash}, is blacklisted, {location}")]
+    TorrentBlacklisted {
+        info_hash: InfoHash,
+        location: &'static Location<'static>,
+    },
+
+    #[error("The peer IP address: {ip}, is banned, {location}")]
+    PeerIpBanned {
+        ip: std::net::IpAddr,
+        location: &'static Location<'static>,
+    },
+
+    #[error("The client of the peer: {peer_id:?}, is not whitelisted, {location}")]
+    ClientNotWhitelisted {
+        peer_id: aquatic_udp_protocol::PeerId,
+        location: &'static Location<'static>,
+    },
+
+    // Rate limiting errors
+    #[error("The announce budget is exceeded for the peer IP address: {ip}, {location}")]
+    AnnounceBudgetExceeded {
+        ip: std::net::IpAddr,
+        location: &'static Location<'static>,
+    },
+
+    // Overload errors
+    #[error("The tracker is overloaded, retry in {retry_in} minutes, {location}")]
+    TrackerOverloaded {
+        retry_in: u32,
+        location: &'static Location<'static>,
+    },
 }
 
 /// Errors related to peers keys.
@@ -48,8 +88,34 @@ pub enum PeerKeyError {
         source: LocatedError<'static, ParseKeyError>,
     },
 
+    #[error("Unknown key: {key}")]
+    UnknownKey { key: Key },
+
     #[error("Can't persist key: {source}")]
     DatabaseError {
         source: LocatedError<'static, databases::error::Error>,
     },
 }
+
+/// Error returned when the core `Tracker` can't be built from its
+/// configuration.
+#[allow(clippy::module_name_repetitions)]
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum BootstrapError {
+    #[error("{source}")]
+    Database {
+        #[from]
+        source: databases::error::Error,
+    },
+
+    #[error("Unable to load the ASN database {path}: {source}")]
+    AsnDatabase {
+        path: Utf8PathBuf,
+        source: LocatedError<'static, dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("Invalid Redis URL for the swarm store: {source}")]
+    SwarmStore {
+        source: LocatedError<'static, dyn std::error::Error + Send + Sync>,
+    },
+}
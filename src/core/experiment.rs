@@ -0,0 +1,247 @@
+//! Announce experiments.
+//!
+//! An [`Experiment`] serves a percentage of the clients with alternative
+//! announce parameters: a different announce interval or a different
+//! selection of the peers returned. The rest of the clients are the control
+//! group. The announces of both groups are counted separately, so operators
+//! can compare them before applying the change to all the clients.
+//!
+//! The clients are assigned to a group by the hash of their IP address and
+//! the name of the experiment. Each client stays in the same group for all
+//! the torrents, while a new experiment shuffles the groups.
+//!
+//! Refer to the [`Experiment`](torrust_tracker_configuration::Experiment)
+//! configuration for more information.
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use aquatic_udp_protocol::AnnounceEvent;
+use rand::seq::SliceRandom;
+use torrust_tracker_configuration::{AnnouncePolicy, ExperimentPeerSelection};
+use torrust_tracker_primitives::peer;
+
+/// The group of a client in the experiment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Group {
+    /// The clients served with the regular announce parameters.
+    Control,
+    /// The clients served with the parameters of the experiment.
+    Experiment,
+}
+
+/// The announces handled for a group of the experiment.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GroupStats {
+    /// Number of `announce` requests.
+    pub announces: u64,
+    /// Number of `announce` requests with the `completed` event.
+    pub completed: u64,
+    /// Number of peers returned in all the responses.
+    pub peers_returned: u64,
+}
+
+/// The announces handled for both groups of the experiment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stats {
+    /// The name of the experiment.
+    pub name: String,
+    pub control: GroupStats,
+    pub experiment: GroupStats,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    announces: AtomicU64,
+    completed: AtomicU64,
+    peers_returned: AtomicU64,
+}
+
+impl Counters {
+    fn record(&self, event: AnnounceEvent, peers_returned: usize) {
+        self.announces.fetch_add(1, Ordering::Relaxed);
+        if event == AnnounceEvent::Completed {
+            self.completed.fetch_add(1, Ordering::Relaxed);
+        }
+        self.peers_returned
+            .fetch_add(u64::try_from(peers_returned).unwrap_or(u64::MAX), Ordering::Relaxed);
+    }
+
+    fn stats(&self) -> GroupStats {
+        GroupStats {
+            announces: self.announces.load(Ordering::Relaxed),
+            completed: self.completed.load(Ordering::Relaxed),
+            peers_returned: self.peers_returned.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// An experiment with alternative announce parameters.
+#[derive(Debug)]
+pub struct Experiment {
+    config: torrust_tracker_configuration::Experiment,
+    control: Counters,
+    experiment: Counters,
+}
+
+impl Experiment {
+    #[must_use]
+    pub fn new(config: torrust_tracker_configuration::Experiment) -> Self {
+        Self {
+            config,
+            control: Counters::default(),
+            experiment: Counters::default(),
+        }
+    }
+
+    /// It returns the group of the client with the IP address.
+    #[must_use]
+    pub fn group_of(&self, ip: &IpAddr) -> Group {
+        let mut hasher = DefaultHasher::new();
+        self.config.name.hash(&mut hasher);
+        ip.hash(&mut hasher);
+
+        if hasher.finish() % 100 < u64::from(self.config.percentage) {
+            Group::Experiment
+        } else {
+            Group::Control
+        }
+    }
+
+    /// It returns the announce policy for the experiment group: the
+    /// `policy` with the intervals of the experiment, if they are set. The
+    /// minimum interval is never greater than the interval.
+    #[must_use]
+    pub fn announce_policy(&self, policy: AnnouncePolicy) -> AnnouncePolicy {
+        let interval = self.config.announce_interval.unwrap_or(policy.interval);
+        let interval_min = self.config.announce_interval_min.unwrap_or(policy.interval_min);
+
+        AnnouncePolicy {
+            interval,
+            interval_min: interval_min.min(interval),
+        }
+    }
+
+    /// It returns `true` if the peers returned to the experiment group are
+    /// selected from the whole swarm.
+    #[must_use]
+    pub fn selects_from_whole_swarm(&self) -> bool {
+        self.config.peer_selection.is_some()
+    }
+
+    /// It selects up to `limit` peers for the experiment group among the
+    /// `peers` of the swarm.
+    #[must_use]
+    pub fn select_peers(&self, mut peers: Vec<Arc<peer::Peer>>, limit: usize) -> Vec<Arc<peer::Peer>> {
+        match self.config.peer_selection {
+            Some(ExperimentPeerSelection::Random) => {
+                let (selected, _) = peers.partial_shuffle(&mut rand::thread_rng(), limit);
+                selected.to_vec()
+            }
+            None => {
+                peers.truncate(limit);
+                peers
+            }
+        }
+    }
+
+    /// It records an announce of a client in the `group`.
+    pub fn record(&self, group: Group, event: AnnounceEvent, peers_returned: usize) {
+        match group {
+            Group::Control => self.control.record(event, peers_returned),
+            Group::Experiment => self.experiment.record(event, peers_returned),
+        }
+    }
+
+    /// It returns the announces handled for each group.
+    #[must_use]
+    pub fn stats(&self) -> Stats {
+        Stats {
+            name: self.config.name.clone(),
+            control: self.control.stats(),
+            experiment: self.experiment.stats(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use aquatic_udp_protocol::AnnounceEvent;
+    use torrust_tracker_configuration::AnnouncePolicy;
+
+    use super::{Experiment, Group, GroupStats};
+
+    fn experiment(percentage: u8) -> Experiment {
+        Experiment::new(torrust_tracker_configuration::Experiment {
+            percentage,
+            announce_interval: Some(1800),
+            ..Default::default()
+        })
+    }
+
+    fn ips() -> impl Iterator<Item = IpAddr> {
+        (0..=255).map(|octet| IpAddr::V4(Ipv4Addr::new(126, 0, 0, octet)))
+    }
+
+    #[test]
+    fn it_should_assign_the_configured_percentage_of_the_clients_to_the_experiment_group() {
+        assert!(ips().all(|ip| experiment(0).group_of(&ip) == Group::Control));
+        assert!(ips().all(|ip| experiment(100).group_of(&ip) == Group::Experiment));
+
+        let in_experiment = ips().filter(|ip| experiment(50).group_of(ip) == Group::Experiment).count();
+
+        assert!((64..192).contains(&in_experiment));
+    }
+
+    #[test]
+    fn it_should_replace_the_announce_intervals_keeping_the_minimum_not_greater_than_the_interval() {
+        let experiment = Experiment::new(torrust_tracker_configuration::Experiment {
+            percentage: 10,
+            announce_interval: Some(60),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            experiment.announce_policy(AnnouncePolicy {
+                interval: 120,
+                interval_min: 120
+            }),
+            AnnouncePolicy {
+                interval: 60,
+                interval_min: 60
+            }
+        );
+    }
+
+    #[test]
+    fn it_should_count_the_announces_of_each_group() {
+        let experiment = experiment(10);
+
+        experiment.record(Group::Experiment, AnnounceEvent::Started, 3);
+        experiment.record(Group::Experiment, AnnounceEvent::Completed, 2);
+        experiment.record(Group::Control, AnnounceEvent::Started, 1);
+
+        let stats = experiment.stats();
+
+        assert_eq!(
+            stats.experiment,
+            GroupStats {
+                announces: 2,
+                completed: 1,
+                peers_returned: 5
+            }
+        );
+        assert_eq!(
+            stats.control,
+            GroupStats {
+                announces: 1,
+                completed: 0,
+                peers_returned: 1
+            }
+        );
+    }
+}
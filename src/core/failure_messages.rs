@@ -0,0 +1,103 @@
+//! Failure messages of the HTTP tracker error responses.
+//!
+//! Operators can replace the messages of the tracker with their own
+//! templates, by rejection reason, in the
+//! [`FailureMessages`](torrust_tracker_configuration::FailureMessages)
+//! configuration. For example, to translate them or to include a link to
+//! their support pages.
+//!
+//! The templates can contain these placeholders:
+//!
+//! - `{reason}`: the message of the tracker.
+//! - `{info_hash}`: the infohash of the announced torrent.
+//! - `{key}`: the authentication key of the request.
+//! - `{retry_in}`: the minutes the client should wait before retrying.
+//!
+//! The placeholders whose value is unknown are replaced with an empty string.
+use torrust_tracker_configuration::FailureMessages;
+use torrust_tracker_primitives::info_hash::InfoHash;
+
+use super::auth::Key;
+use super::statistics::RejectReason;
+
+/// The values of the placeholders of a failure message.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Context<'a> {
+    pub info_hash: Option<&'a InfoHash>,
+    pub key: Option<&'a Key>,
+    pub retry_in: Option<u32>,
+}
+
+/// It returns the template configured for the rejection reason, if any.
+#[must_use]
+pub fn template(config: &FailureMessages, reason: RejectReason) -> Option<&str> {
+    let template = match reason {
+        RejectReason::InvalidKey => &config.invalid_key,
+        RejectReason::NotWhitelisted => &config.not_whitelisted,
+        RejectReason::Blacklisted => &config.blacklisted,
+        RejectReason::BannedIp => &config.banned_ip,
+        RejectReason::ClientNotWhitelisted => &config.client_not_whitelisted,
+        RejectReason::RateLimited => &config.rate_limited,
+        RejectReason::Overloaded => &config.overloaded,
+        _ => &None,
+    };
+
+    template.as_deref()
+}
+
+/// It replaces the placeholders of the template. The `reason` is the message
+/// of the tracker.
+#[must_use]
+pub fn render(template: &str, reason: &str, context: &Context<'_>) -> String {
+    template
+        .replace("{reason}", reason)
+        .replace("{info_hash}", &context.info_hash.map(ToString::to_string).unwrap_or_default())
+        .replace("{key}", &context.key.map(ToString::to_string).unwrap_or_default())
+        .replace(
+            "{retry_in}",
+            &context.retry_in.map(|retry_in| retry_in.to_string()).unwrap_or_default(),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use torrust_tracker_configuration::FailureMessages;
+    use torrust_tracker_primitives::info_hash::InfoHash;
+
+    use super::{render, template, Context};
+    use crate::core::statistics::RejectReason;
+
+    #[test]
+    fn it_should_replace_the_placeholders_of_the_template() {
+        let info_hash = "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap();
+
+        let message = render(
+            "{reason}: {info_hash}, retry in {retry_in} minutes{key}",
+            "torrent not whitelisted",
+            &Context {
+                info_hash: Some(&info_hash),
+                key: None,
+                retry_in: Some(60),
+            },
+        );
+
+        assert_eq!(
+            message,
+            "torrent not whitelisted: 3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0, retry in 60 minutes"
+        );
+    }
+
+    #[test]
+    fn it_should_only_return_the_template_of_the_rejection_reason() {
+        let config = FailureMessages {
+            blacklisted: Some("{reason}. See https://tracker.example.com/rules".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            template(&config, RejectReason::Blacklisted),
+            Some("{reason}. See https://tracker.example.com/rules")
+        );
+        assert_eq!(template(&config, RejectReason::NotWhitelisted), None);
+    }
+}
@@ -0,0 +1,235 @@
+//! Load monitor for the overload control.
+//!
+//! The tracker services count the requests they receive and the requests in
+//! process. A background job samples those counters periodically, together
+//! with the lag of the event loop, and decides whether the tracker is
+//! overloaded.
+//!
+//! The monitor has hysteresis: the tracker becomes overloaded when any of the
+//! metrics exceeds its threshold, and it stops being overloaded when all of
+//! them drop below the recovery threshold (a percentage of the thresholds).
+//!
+//! Refer to the [`Overload`] configuration for more information.
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use torrust_tracker_configuration::Overload;
+
+/// Load of the tracker in the last sample.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Load {
+    /// Whether the tracker is overloaded.
+    pub overloaded: bool,
+    /// Requests received per second.
+    pub requests_per_second: u64,
+    /// Requests in process when the sample was taken.
+    pub inflight_requests: u64,
+    /// How late the sampling task was woken up.
+    pub event_loop_lag: Duration,
+    /// Number of times the tracker has become overloaded.
+    pub overload_episodes: u64,
+}
+
+/// Counters of the requests and state of the overload control.
+#[derive(Debug)]
+pub struct LoadMonitor {
+    config: Overload,
+    /// Requests received since the last sample.
+    requests: AtomicU64,
+    /// Requests in process.
+    inflight_requests: AtomicU64,
+    overloaded: AtomicBool,
+    last_sample: Mutex<Load>,
+}
+
+/// A request in process. It's no longer counted when it's dropped.
+#[derive(Debug)]
+pub struct InflightRequest<'a> {
+    inflight_requests: Option<&'a AtomicU64>,
+}
+
+impl Drop for InflightRequest<'_> {
+    fn drop(&mut self) {
+        if let Some(inflight_requests) = self.inflight_requests {
+            inflight_requests.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl InflightRequest<'_> {
+    /// A request that is not counted, when the overload control is disabled.
+    #[must_use]
+    pub fn untracked() -> Self {
+        Self { inflight_requests: None }
+    }
+}
+
+impl LoadMonitor {
+    /// It builds the monitor from the configuration. It returns `None` if the
+    /// overload control is disabled.
+    #[must_use]
+    pub fn new(config: &Overload) -> Option<Self> {
+        if !config.is_enabled() {
+            return None;
+        }
+
+        Some(Self {
+            config: config.clone(),
+            requests: AtomicU64::new(0),
+            inflight_requests: AtomicU64::new(0),
+            overloaded: AtomicBool::new(false),
+            last_sample: Mutex::new(Load::default()),
+        })
+    }
+
+    /// It counts a new request. The request is in process until the returned
+    /// value is dropped.
+    pub fn track_request(&self) -> InflightRequest<'_> {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.inflight_requests.fetch_add(1, Ordering::Relaxed);
+
+        InflightRequest {
+            inflight_requests: Some(&self.inflight_requests),
+        }
+    }
+
+    #[must_use]
+    pub fn is_overloaded(&self) -> bool {
+        self.overloaded.load(Ordering::Relaxed)
+    }
+
+    /// It takes a sample of the load, `elapsed` time after the previous one,
+    /// and updates the overload state.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the last sample is poisoned.
+    pub fn sample(&self, elapsed: Duration, event_loop_lag: Duration) -> Load {
+        let requests = self.requests.swap(0, Ordering::Relaxed);
+        let elapsed_millis = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX).max(1);
+
+        let mut last_sample = self.last_sample.lock().expect("it should get a lock on the last load sample");
+
+        let mut load = Load {
+            overloaded: last_sample.overloaded,
+            requests_per_second: requests.saturating_mul(1000) / elapsed_millis,
+            inflight_requests: self.inflight_requests.load(Ordering::Relaxed),
+            event_loop_lag,
+            overload_episodes: last_sample.overload_episodes,
+        };
+
+        if !load.overloaded && self.exceeds(&load, 100) {
+            load.overloaded = true;
+            load.overload_episodes += 1;
+
+            tracing::warn!(?load, "the tracker is overloaded");
+        } else if load.overloaded && !self.exceeds(&load, u64::from(self.config.recovery_threshold)) {
+            load.overloaded = false;
+
+            tracing::info!(?load, "the tracker is no longer overloaded");
+        }
+
+        self.overloaded.store(load.overloaded, Ordering::Relaxed);
+        *last_sample = load;
+
+        load
+    }
+
+    /// It returns the last sample of the load.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the last sample is poisoned.
+    #[must_use]
+    pub fn load(&self) -> Load {
+        *self.last_sample.lock().expect("it should get a lock on the last load sample")
+    }
+
+    /// Returns `true` if any of the metrics exceeds the percentage of its
+    /// threshold.
+    fn exceeds(&self, load: &Load, percentage: u64) -> bool {
+        let above = |value: u64, threshold: Option<u64>| {
+            threshold.is_some_and(|threshold| value.saturating_mul(100) > threshold.saturating_mul(percentage))
+        };
+
+        above(load.requests_per_second, self.config.max_requests_per_second.map(u64::from))
+            || above(load.inflight_requests, self.config.max_inflight_requests.map(u64::from))
+            || above(
+                u64::try_from(load.event_loop_lag.as_millis()).unwrap_or(u64::MAX),
+                self.config.max_event_loop_lag,
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use torrust_tracker_configuration::Overload;
+
+    use super::LoadMonitor;
+
+    fn monitor() -> LoadMonitor {
+        LoadMonitor::new(&Overload {
+            max_requests_per_second: Some(10),
+            max_event_loop_lag: Some(100),
+            recovery_threshold: 50,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn receive(monitor: &LoadMonitor, requests: usize) {
+        for _ in 0..requests {
+            drop(monitor.track_request());
+        }
+    }
+
+    #[test]
+    fn it_should_be_disabled_when_no_threshold_is_set() {
+        assert!(LoadMonitor::new(&Overload::default()).is_none());
+    }
+
+    #[test]
+    fn it_should_measure_the_requests_per_second_and_the_requests_in_process() {
+        let monitor = monitor();
+
+        let _inflight_request = monitor.track_request();
+        receive(&monitor, 9);
+
+        let load = monitor.sample(Duration::from_secs(2), Duration::ZERO);
+
+        assert_eq!(load.requests_per_second, 5);
+        assert_eq!(load.inflight_requests, 1);
+    }
+
+    #[test]
+    fn it_should_be_overloaded_when_any_metric_exceeds_its_threshold() {
+        let monitor = monitor();
+
+        monitor.sample(Duration::from_secs(1), Duration::from_millis(101));
+
+        assert!(monitor.is_overloaded());
+        assert_eq!(monitor.load().overload_episodes, 1);
+    }
+
+    #[test]
+    fn it_should_stay_overloaded_until_the_load_drops_below_the_recovery_threshold() {
+        let monitor = monitor();
+
+        receive(&monitor, 11);
+        monitor.sample(Duration::from_secs(1), Duration::ZERO);
+
+        receive(&monitor, 8);
+        monitor.sample(Duration::from_secs(1), Duration::ZERO);
+
+        assert!(monitor.is_overloaded());
+
+        receive(&monitor, 5);
+        monitor.sample(Duration::from_secs(1), Duration::ZERO);
+
+        assert!(!monitor.is_overloaded());
+        assert_eq!(monitor.load().overload_episodes, 1);
+    }
+}
@@ -37,7 +37,8 @@
 //! - **Core tracker**: it handles the information about torrents and peers.
 //! - **Authentication**: it handles authentication keys which are used by HTTP trackers.
 //! - **Authorization**: it handles the permission to perform requests.
-//! - **Whitelist**: when the tracker runs in `listed` or `private_listed` mode all operations are restricted to whitelisted torrents.
+//! - **Whitelist**: when the tracker runs in `listed` or `private_listed` mode all operations are restricted to whitelisted torrents,
+//!   or torrents whose infohash starts with one of the [whitelist prefixes](torrust_tracker_configuration::Whitelist).
 //! - **Statistics**: it keeps and serves the tracker statistics.
 //!
 //! Refer to [torrust-tracker-configuration](https://docs.rs/torrust-tracker-configuration) crate docs to get more information about the tracker settings.
@@ -59,6 +60,7 @@
 //! use std::net::IpAddr;
 //! use std::net::Ipv4Addr;
 //! use std::str::FromStr;
+//! use std::time::Duration;
 //!
 //! use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
 //! use torrust_tracker_primitives::DurationSinceUnixEpoch;
@@ -75,6 +77,8 @@
 //!     downloaded: NumberOfBytes::new(0),
 //!     left: NumberOfBytes::new(0),
 //!     event: AnnounceEvent::Completed,
+//!     timeout_extension: Duration::ZERO,
+//!     alt_peer_addr: None,
 //! };
 //!
 //! let peer_ip = IpAddr::V4(Ipv4Addr::from_str("126.0.0.1").unwrap());
@@ -97,6 +101,8 @@
 //! The returned struct is:
 //!
 //! ```rust,no_run
+//! use std::net::IpAddr;
+//!
 //! use torrust_tracker_primitives::peer;
 //! use torrust_tracker_configuration::AnnouncePolicy;
 //!
@@ -104,6 +110,9 @@
 //!     pub peers: Vec<peer::Peer>,
 //!     pub swarm_stats: SwarmMetadata,
 //!     pub policy: AnnouncePolicy, // the tracker announce policy.
+//!     pub tracker_id: Option<String>, // the tracker id issued to the peer, if any.
+//!     pub external_ip: Option<IpAddr>, // the client IP, if the tracker returns it (BEP 24).
+//!     pub warning_message: Option<String>, // a non-fatal message for the client.
 //! }
 //!
 //! pub struct SwarmMetadata {
@@ -121,6 +130,9 @@
 //! }
 //! ```
 //!
+//! The v1 and v2 infohashes of hybrid torrents can be linked, so the returned
+//! peers include the peers of both swarms. See the [`torrent_aliases`] module.
+//!
 //! Refer to `BitTorrent` BEPs and other sites for more information about the `announce` request:
 //!
 //! - [BEP 3. The `BitTorrent` Protocol Specification](https://www.bittorrent.org/beps/bep_0003.html)
@@ -175,6 +187,12 @@
 //!     InfoHash::from_str("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF").unwrap()
 //! );
 //! ```
+//!
+//! The HTTP tracker caches the bencoded swarm metadata of each torrent until
+//! its counters change. With the `scrape_cache_ttl` option, the swarm metadata
+//! is cached for both trackers during the TTL instead. See the
+//! [`scrape_cache`] module.
+//!
 //! Refer to `BitTorrent` BEPs and other sites for more information about the `scrape` request:
 //!
 //! - [BEP 48. Tracker Protocol Extension: Scrape](https://www.bittorrent.org/beps/bep_0048.html)
@@ -247,6 +265,7 @@
 //!
 //! ```rust,no_run
 //! use std::net::SocketAddr;
+//! use std::time::Duration;
 
 //! use aquatic_udp_protocol::PeerId;
 //! use torrust_tracker_primitives::DurationSinceUnixEpoch;
@@ -261,6 +280,7 @@
 //!     pub downloaded: NumberOfBytes,       // Number of bytes the peer has downloaded so far   
 //!     pub left: NumberOfBytes,             // The number of bytes this peer still has to download
 //!     pub event: AnnounceEvent,            // The event the peer has announced: `started`, `completed`, `stopped`
+//!     pub timeout_extension: Duration,     // Extra time the peer is kept after `max_peer_timeout`
 //! }
 //! ```
 //!
@@ -308,6 +328,10 @@
 //!
 //! Refer to [`peer`] module for more information about peers.
 //!
+//! Several tracker instances behind a load balancer can share their swarms in
+//! a `Redis` server, so the `announce` and `scrape` responses include the
+//! peers of all the instances. See the [`swarm_store`] module.
+//!
 //! # Configuration
 //!
 //! You can control the behavior of this module with the module settings:
@@ -404,6 +428,21 @@
 //!     pub udp6_connections_handled: u64,
 //!     pub udp6_announces_handled: u64,
 //!     pub udp6_scrapes_handled: u64,
+//!
+//!     // Reverse proxies (HTTP tracker)
+//!     pub tcp_forwarded_announces_handled: u64,
+//!     pub tcp_forwarded_ip_proxy_changes: u64,
+//!     pub tcp_announces_with_tracker_id: u64,
+//!     pub tcp_announces_with_invalid_tracker_id: u64,
+//!     pub tcp_announces_without_tracker_id: u64,
+//!     pub api_requests_rate_limited: u64,
+//!     pub api_requests_locked_out: u64,
+//!     pub api_failed_auth_attempts: u64,
+//!     pub api_lockouts: u64,
+//!     pub proxies: HashMap<IpAddr, ProxyMetrics>,
+//!
+//!     // Rejected requests (HTTP and UDP trackers)
+//!     pub rejections: HashMap<Rejection, u64>,
 //! }
 //! ```
 //!
@@ -412,10 +451,15 @@
 //! - `connections_handled`: number of connections handled by the tracker
 //! - `announces_handled`: number of `announce` requests handled by the tracker
 //! - `scrapes_handled`: number of `scrape` handled requests by the tracker
+//! - `rejections`: number of rejected `announce` and `scrape` requests, by protocol, request type and reason
 //!
 //! > **NOTICE**: as the HTTP tracker does not have an specific `connection` request like the UDP tracker, `connections_handled` are
 //! > increased on every `announce` and `scrape` requests.
 //!
+//! When the tracker is behind a reverse proxy, it also counts the `announce` requests received through each proxy
+//! (`forwarded_announces_handled`) and the ones whose client IP was previously received through a different proxy
+//! (`forwarded_ip_proxy_changes`).
+//!
 //! The tracker exposes an event sender API that allows the tracker users to send events. When a higher application service handles a
 //! `connection` , `announce` or `scrape` requests, it notifies the `Tracker` by sending statistics events.
 //!
@@ -432,47 +476,77 @@
 //! Right now the `Tracker` is responsible for storing and load data into and
 //! from the database, when persistence is enabled.
 //!
-//! There are three types of persistent object:
+//! There are four types of persistent object:
 //!
 //! - Authentication keys (only expiring keys)
 //! - Torrent whitelist
 //! - Torrent metrics
+//! - Torrent history (see the [`torrent_history`] module)
 //!
 //! Refer to [`databases`] module for more information about persistence.
+pub mod abuse;
+pub mod activity;
+pub mod alerts;
+pub mod announce_budget;
+pub mod api_tokens;
+pub mod audit;
 pub mod auth;
+pub mod bandwidth;
+pub mod client_keys;
+pub mod client_whitelist;
 pub mod databases;
+pub mod dynamic_interval;
 pub mod error;
+pub mod experiment;
+pub mod failure_messages;
+pub mod load_monitor;
+pub mod peer_diversity;
+pub mod scrape_cache;
 pub mod services;
 pub mod statistics;
+pub mod stats_summary;
+pub mod swarm_snapshot;
+pub mod swarm_store;
+pub mod top_torrents;
 pub mod torrent;
+pub mod torrent_aliases;
+pub mod torrent_history;
+pub mod tracker_ids;
+pub mod warnings;
 
 pub mod peer_tests;
 
-use std::cmp::max;
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::panic::Location;
 use std::sync::Arc;
 use std::time::Duration;
 
+use aquatic_udp_protocol::{AnnounceEvent, PeerId};
 use auth::PeerKey;
 use databases::driver::Driver;
 use derive_more::Constructor;
-use error::PeerKeyError;
+use error::{BootstrapError, PeerKeyError};
+use rand::Rng;
 use tokio::sync::mpsc::error::SendError;
 use torrust_tracker_clock::clock::Time;
-use torrust_tracker_configuration::v2_0_0::database;
-use torrust_tracker_configuration::{AnnouncePolicy, Core, TORRENT_PEERS_LIMIT};
-use torrust_tracker_located_error::Located;
+use torrust_tracker_clock::conv::convert_from_timestamp_to_datetime_utc;
+use torrust_tracker_configuration::v2_0_0::maintenance::MaintenanceWindow;
+use torrust_tracker_configuration::{
+    AnnouncePolicy, ConnectionIdScheme, Core, Network, PeerIpReplacement, SwarmStoreBackend, TORRENT_PEERS_LIMIT,
+};
+use torrust_tracker_located_error::{DynError, Located};
 use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::pagination::Pagination;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
-use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
+use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch, PersistentTorrents};
 use torrust_tracker_torrent_repository::entry::EntrySync;
 use torrust_tracker_torrent_repository::repository::Repository;
 
 use self::auth::Key;
 use self::error::Error;
+use self::statistics::RejectReason;
 use self::torrent::Torrents;
 use crate::core::databases::Database;
 use crate::CurrentClock;
@@ -500,14 +574,81 @@ pub struct Tracker {
     /// The list of allowed torrents. Only for listed trackers.
     whitelist: tokio::sync::RwLock<std::collections::HashSet<InfoHash>>,
 
+    /// Mitigations applied after receiving abuse reports.
+    mitigations: abuse::Mitigations,
+
+    /// Live feed of the announce, scrape and completed events.
+    activity: activity::Feed,
+
+    /// Database to find the autonomous system of the peers. Only used when
+    /// the peer diversity constraints are enabled.
+    asn_database: Option<peer_diversity::AsnDatabase>,
+
+    /// Global budget of announce requests. Only used when it's enabled in
+    /// the configuration.
+    announce_budget: Option<announce_budget::Budget>,
+
+    /// Throughput estimates from the bytes reported in the announce requests.
+    bandwidth: bandwidth::Estimator,
+
+    /// The last peer announced by each client with the `key` param of the
+    /// HTTP announce requests.
+    client_keys: client_keys::ClientKeys,
+
+    /// The peer ID prefixes of the clients allowed to announce. Only for
+    /// private trackers.
+    client_whitelist: client_whitelist::Whitelist,
+
+    /// Load of the tracker for the overload control. Only used when it's
+    /// enabled in the configuration.
+    load_monitor: Option<load_monitor::LoadMonitor>,
+
+    /// The experiment with alternative announce parameters. Only used when
+    /// it's set in the configuration.
+    experiment: Option<experiment::Experiment>,
+
     /// The in-memory torrents repository.
     torrents: Arc<Torrents>,
 
+    /// The number of completed downloads of the torrents changed since the
+    /// last write to the database. Only used when the writes are batched.
+    pending_completed_stats: std::sync::Mutex<PersistentTorrents>,
+
+    /// Cached `scrape` data of the torrents.
+    scrape_cache: scrape_cache::ScrapeCache,
+
+    /// The swarms shared with other tracker instances. Only used when the
+    /// `redis` backend is enabled in the configuration.
+    swarm_store: Option<swarm_store::RedisSwarms>,
+
+    /// Aliases between the infohashes of hybrid torrents. Only used when
+    /// they are enabled in the configuration.
+    torrent_aliases: Option<torrent_aliases::Aliases>,
+
+    /// Generation and validation of the tracker ids of the HTTP announce
+    /// responses.
+    tracker_ids: Box<dyn tracker_ids::Scheme>,
+
+    /// Warning messages for the HTTP announce responses.
+    warnings: warnings::Warnings,
+
+    /// The `WebRTC` peers announced by the WebSocket trackers. They are
+    /// stored in the torrent repository like the rest of the peers, but
+    /// they are not returned to the UDP and HTTP clients, which can't
+    /// connect to them.
+    web_peers: dashmap::DashSet<(InfoHash, PeerId)>,
+
     /// Service to send stats events.
     stats_event_sender: Option<Box<dyn statistics::EventSender>>,
 
     /// The in-memory stats repo.
     stats_repository: statistics::Repo,
+
+    /// The last summary of the statistics computed.
+    stats_summary: stats_summary::Cache,
+
+    /// The counters of the current hour of the torrent history.
+    history: torrent_history::History,
 }
 
 /// Structure that holds the data returned by the `announce` request.
@@ -519,6 +660,15 @@ pub struct AnnounceData {
     /// Swarm statistics
     pub stats: SwarmMetadata,
     pub policy: AnnouncePolicy,
+    /// The tracker id issued to the peer, if any. See the
+    /// [`tracker_ids`](crate::core::tracker_ids) module.
+    pub tracker_id: Option<String>,
+    /// The IP address of the client, as observed by the tracker, if the
+    /// tracker returns it. See [BEP 24. Tracker Returns External IP](https://www.bittorrent.org/beps/bep_0024.html).
+    pub external_ip: Option<IpAddr>,
+    /// A non-fatal message for the client. See the [`warnings`](crate::core::warnings)
+    /// module.
+    pub warning_message: Option<String>,
 }
 
 /// How many peers the peer announcing wants in the announce response.
@@ -613,6 +763,9 @@ pub struct AddKeyRequest {
 
     /// How long the key will be valid in seconds. Use `None` for permanent keys.
     pub opt_seconds_valid: Option<u64>,
+
+    /// Optional label to attribute the key, for example, to a user.
+    pub opt_label: Option<String>,
 }
 
 impl Tracker {
@@ -621,25 +774,94 @@ impl Tracker {
     /// # Errors
     ///
     /// Will return a `databases::error::Error` if unable to connect to database. The `Tracker` is responsible for the persistence.
+    /// It also fails if the database schema can't be migrated or, when the
+    /// automatic migrations are disabled, it has pending migrations. It also
+    /// fails if the ASN database for the peer diversity constraints is
+    /// configured but it can't be loaded, or if the URL of the `Redis` swarm
+    /// store is not valid.
     pub fn new(
         config: &Core,
         stats_event_sender: Option<Box<dyn statistics::EventSender>>,
         stats_repository: statistics::Repo,
-    ) -> Result<Tracker, databases::error::Error> {
-        let driver = match config.database.driver {
-            database::Driver::Sqlite3 => Driver::Sqlite3,
-            database::Driver::MySQL => Driver::MySQL,
+    ) -> Result<Tracker, BootstrapError> {
+        let driver = Driver::from(&config.database.driver);
+        let encryption_key = databases::driver::encryption_key(&config.database)?;
+
+        let database = if config.database.auto_migrate {
+            databases::driver::build(
+                &driver,
+                &config.database.path,
+                &config.database.pool,
+                config.database.table_prefix(),
+                encryption_key.as_deref(),
+            )?
+        } else {
+            let database = databases::driver::connect(
+                &driver,
+                &config.database.path,
+                &config.database.pool,
+                config.database.table_prefix(),
+                encryption_key.as_deref(),
+            )?;
+            databases::migrations::check(database.as_ref(), &driver)?;
+            database
         };
 
-        let database = Arc::new(databases::driver::build(&driver, &config.database.path)?);
+        let database = Arc::new(database);
+
+        let asn_database = config
+            .peer_diversity
+            .asn_database_path
+            .as_ref()
+            .map(|path| {
+                peer_diversity::AsnDatabase::load(path).map_err(|err| BootstrapError::AsnDatabase {
+                    path: path.clone(),
+                    source: (Arc::new(err) as DynError).into(),
+                })
+            })
+            .transpose()?;
+
+        let swarm_store = (config.swarm_store.backend == SwarmStoreBackend::Redis)
+            .then(|| {
+                swarm_store::RedisSwarms::new(
+                    &config.swarm_store,
+                    Duration::from_secs(u64::from(config.tracker_policy.max_peer_timeout)),
+                )
+                .map_err(|err| BootstrapError::SwarmStore {
+                    source: (Arc::new(err) as DynError).into(),
+                })
+            })
+            .transpose()?;
 
         Ok(Tracker {
             config: config.clone(),
             keys: tokio::sync::RwLock::new(std::collections::HashMap::new()),
             whitelist: tokio::sync::RwLock::new(std::collections::HashSet::new()),
+            mitigations: abuse::Mitigations::default(),
+            activity: activity::Feed::default(),
+            asn_database,
+            announce_budget: announce_budget::Budget::new(&config.announce_budget, CurrentClock::now()),
+            bandwidth: bandwidth::Estimator::new(
+                Duration::from_secs(u64::from(config.announce_policy.interval)),
+                CurrentClock::now(),
+            ),
+            client_keys: client_keys::ClientKeys::default(),
+            client_whitelist: client_whitelist::Whitelist::new(&config.client_whitelist),
+            load_monitor: load_monitor::LoadMonitor::new(&config.overload),
+            experiment: config.experiment.clone().map(experiment::Experiment::new),
             torrents: Arc::default(),
+            pending_completed_stats: std::sync::Mutex::default(),
+            scrape_cache: scrape_cache::ScrapeCache::new(Duration::from_secs(config.scrape_cache_ttl)),
+            swarm_store,
+            torrent_aliases: torrent_aliases::Aliases::new(&config.torrent_aliases),
+            tracker_ids: tracker_ids::from_config(config),
+            warnings: warnings::Warnings::default(),
+            web_peers: dashmap::DashSet::new(),
             stats_event_sender,
             stats_repository,
+            stats_summary: stats_summary::Cache::default(),
+            top_torrents: top_torrents::Cache::default(),
+            history: torrent_history::History::default(),
             database,
         })
     }
@@ -664,25 +886,289 @@ impl Tracker {
         self.is_private()
     }
 
-    /// Returns `true` is the tracker is in whitelisted mode.
+    /// Returns `true` if the tracker is behind a reverse proxy.
     pub fn is_behind_reverse_proxy(&self) -> bool {
-        self.config.net.on_reverse_proxy
+        self.config.net.is_behind_reverse_proxy()
+    }
+
+    /// Returns the network configuration, used to resolve the client IP of
+    /// the HTTP requests.
+    pub fn get_network_config(&self) -> &Network {
+        &self.config.net
     }
 
     pub fn get_announce_policy(&self) -> AnnouncePolicy {
         self.config.announce_policy
     }
 
+    /// Returns the announce policy for a torrent. It's the tracker announce
+    /// policy, adjusted to the size of the swarm and to the load if dynamic
+    /// intervals are enabled, unless the intervals have been raised for the
+    /// torrent after an abuse report.
+    ///
+    /// During maintenance windows the intervals are raised to the configured
+    /// maintenance announce interval, and while the tracker is overloaded to
+    /// the configured overload announce interval.
+    pub fn get_announce_policy_for(&self, info_hash: &InfoHash) -> AnnouncePolicy {
+        self.get_announce_policy_in_group(info_hash, experiment::Group::Control)
+    }
+
+    /// Returns the announce policy for a torrent like [`Tracker::get_announce_policy_for`],
+    /// but with the intervals of the experiment for its experiment group.
+    fn get_announce_policy_in_group(&self, info_hash: &InfoHash, group: experiment::Group) -> AnnouncePolicy {
+        let default_policy = match (&self.experiment, group) {
+            (Some(experiment), experiment::Group::Experiment) => {
+                experiment.announce_policy(self.get_dynamic_announce_policy(info_hash))
+            }
+            _ => self.get_dynamic_announce_policy(info_hash),
+        };
+
+        let policy = self.mitigations.announce_policy_for(info_hash, default_policy);
+
+        let raised_interval = if self.is_under_maintenance() {
+            Some(self.config.maintenance.announce_interval)
+        } else if self.is_overloaded() {
+            Some(self.config.overload.announce_interval)
+        } else {
+            None
+        };
+
+        match raised_interval {
+            Some(interval) => AnnouncePolicy {
+                interval: policy.interval.max(interval),
+                interval_min: policy.interval_min.max(interval),
+            },
+            None => policy,
+        }
+    }
+
+    fn get_dynamic_announce_policy(&self, info_hash: &InfoHash) -> AnnouncePolicy {
+        if !self.config.dynamic_interval.is_enabled() {
+            return self.get_announce_policy();
+        }
+
+        let swarm_size = self
+            .torrents
+            .get_swarm_metadata(info_hash)
+            .map_or(0, |swarm_metadata| swarm_metadata.complete + swarm_metadata.incomplete);
+
+        dynamic_interval::adjust(
+            self.get_announce_policy(),
+            &self.config.dynamic_interval,
+            swarm_size,
+            self.get_load().requests_per_second,
+        )
+    }
+
+    /// Returns the maintenance window the tracker is currently in, if any.
+    ///
+    /// # Context: Maintenance
+    pub fn get_active_maintenance_window(&self) -> Option<MaintenanceWindow> {
+        let now = convert_from_timestamp_to_datetime_utc(CurrentClock::now());
+
+        self.config.maintenance.active_window(&now).cloned()
+    }
+
+    /// Returns `true` if the tracker is in a scheduled maintenance window.
+    ///
+    /// # Context: Maintenance
+    pub fn is_under_maintenance(&self) -> bool {
+        self.get_active_maintenance_window().is_some()
+    }
+
+    /// Returns `true` if nonessential jobs should be paused because the
+    /// tracker is in a scheduled maintenance window.
+    ///
+    /// # Context: Maintenance
+    pub fn should_pause_jobs(&self) -> bool {
+        self.config.maintenance.pause_jobs && self.is_under_maintenance()
+    }
+
+    /// Returns the minutes clients should wait before retrying a failed
+    /// request. It's only set during maintenance windows and while the
+    /// tracker is overloaded.
+    ///
+    /// # Context: Maintenance
+    pub fn get_retry_in(&self) -> Option<u32> {
+        if self.is_under_maintenance() {
+            Some(self.config.maintenance.retry_in)
+        } else if self.is_overloaded() {
+            Some(self.config.overload.retry_in)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the minutes clients should wait before retrying an announce
+    /// rejected for the given reason ([BEP 31](https://www.bittorrent.org/beps/bep_0031.html)).
+    /// The maintenance and overload retry intervals take precedence.
+    ///
+    /// # Context: Maintenance
+    #[must_use]
+    pub fn get_retry_in_after(&self, reason: RejectReason) -> Option<u32> {
+        let retry_in = match reason {
+            RejectReason::InvalidKey => self.config.failure_retry.invalid_key,
+            RejectReason::NotWhitelisted | RejectReason::Blacklisted => self.config.failure_retry.unregistered_torrent,
+            _ => 0,
+        };
+
+        self.get_retry_in().or((retry_in > 0).then_some(retry_in))
+    }
+
+    /// It returns the failure message of a request rejected for the given
+    /// reason: the template configured for the reason, if any, or the
+    /// `message` of the tracker otherwise. Refer to the
+    /// [`failure_messages`](crate::core::failure_messages) module for more
+    /// information.
+    #[must_use]
+    pub fn get_failure_message(&self, reason: RejectReason, message: String, context: &failure_messages::Context<'_>) -> String {
+        match failure_messages::template(&self.config.failure_messages, reason) {
+            Some(template) => failure_messages::render(template, &message, context),
+            None => message,
+        }
+    }
+
+    /// It counts a request received by any of the tracker services. The
+    /// request is in process until the returned value is dropped.
+    ///
+    /// # Context: Overload
+    pub fn track_request(&self) -> load_monitor::InflightRequest<'_> {
+        match &self.load_monitor {
+            Some(load_monitor) => load_monitor.track_request(),
+            None => load_monitor::InflightRequest::untracked(),
+        }
+    }
+
+    /// Returns `true` if the overload control is enabled and the tracker is
+    /// overloaded.
+    ///
+    /// # Context: Overload
+    pub fn is_overloaded(&self) -> bool {
+        self.load_monitor
+            .as_ref()
+            .is_some_and(load_monitor::LoadMonitor::is_overloaded)
+    }
+
+    /// It takes a sample of the load, `elapsed` time after the previous one.
+    /// It does nothing if the overload control is disabled.
+    ///
+    /// # Context: Overload
+    pub fn sample_load(&self, elapsed: Duration, event_loop_lag: Duration) {
+        if let Some(load_monitor) = &self.load_monitor {
+            load_monitor.sample(elapsed, event_loop_lag);
+        }
+    }
+
+    /// Returns the last sample of the load. It's empty if the overload
+    /// control is disabled.
+    ///
+    /// # Context: Overload
+    pub fn get_load(&self) -> load_monitor::Load {
+        self.load_monitor
+            .as_ref()
+            .map(load_monitor::LoadMonitor::load)
+            .unwrap_or_default()
+    }
+
+    /// It checks whether a low-priority request (like a `scrape` request) can
+    /// be handled.
+    ///
+    /// # Context: Overload
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the tracker is overloaded.
+    pub fn check_overload(&self) -> Result<(), Error> {
+        if self.is_overloaded() {
+            return Err(Error::TrackerOverloaded {
+                retry_in: self.config.overload.retry_in,
+                location: Location::caller(),
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn get_maybe_external_ip(&self) -> Option<IpAddr> {
         self.config.net.external_ip
     }
 
+    /// Returns the identifier of this tracker instance, if it's configured.
+    pub fn get_instance_id(&self) -> Option<String> {
+        self.config.instance_id.clone()
+    }
+
+    /// It replaces the scheme used to generate and validate the tracker ids
+    /// of the HTTP announce responses. Refer to the [`tracker_ids`] module
+    /// for more information.
+    pub fn set_tracker_id_scheme(&mut self, scheme: Box<dyn tracker_ids::Scheme>) {
+        self.tracker_ids = scheme;
+    }
+
+    /// It checks the tracker id sent back by the client in an announce.
+    ///
+    /// It returns `None` if no tracker id is issued to the peer, or if the
+    /// client did not send it in a `started` announce, since it can't have
+    /// one in the first announce of a session.
+    pub fn check_tracker_id(
+        &self,
+        info_hash: &InfoHash,
+        peer_id: &PeerId,
+        event: AnnounceEvent,
+        tracker_id: Option<&str>,
+    ) -> Option<tracker_ids::Check> {
+        self.tracker_ids.issue(info_hash, peer_id)?;
+
+        match tracker_id {
+            Some(tracker_id) if self.tracker_ids.validate(tracker_id, info_hash, peer_id) => Some(tracker_ids::Check::Valid),
+            Some(_) => Some(tracker_ids::Check::Invalid),
+            None if event == AnnounceEvent::Started => None,
+            None => Some(tracker_ids::Check::Missing),
+        }
+    }
+
+    /// It returns the warning message for the announce response of the
+    /// `peer`, if any. Refer to the [`warnings`](crate::core::warnings)
+    /// module for more information.
+    pub fn get_warning_message(&self, info_hash: &InfoHash, peer: &peer::Peer, key: Option<&Key>) -> Option<String> {
+        self.warnings.get(info_hash, peer, key)
+    }
+
+    /// It sets the warning message for the announce responses of the clients
+    /// using the authentication key.
+    pub fn set_key_warning_message(&self, key: &Key, message: String) {
+        self.warnings.set_for_key(key, message);
+    }
+
+    /// It removes the warning message of the authentication key. It returns
+    /// `true` if the key had one.
+    pub fn remove_key_warning_message(&self, key: &Key) -> bool {
+        self.warnings.remove_for_key(key)
+    }
+
+    /// It sets the warning message for the announce responses of the torrent.
+    pub fn set_torrent_warning_message(&self, info_hash: &InfoHash, message: String) {
+        self.warnings.set_for_torrent(info_hash, message);
+    }
+
+    /// It removes the warning message of the torrent. It returns `true` if
+    /// the torrent had one.
+    pub fn remove_torrent_warning_message(&self, info_hash: &InfoHash) -> bool {
+        self.warnings.remove_for_torrent(info_hash)
+    }
+
+    /// It registers a hook to decide the warning message of the announce
+    /// responses.
+    pub fn add_warning_policy(&self, policy: Arc<dyn warnings::WarningPolicy>) {
+        self.warnings.add_policy(policy);
+    }
+
     /// It handles an announce request.
     ///
     /// # Context: Tracker
     ///
     /// BEP 03: [The `BitTorrent` Protocol Specification](https://www.bittorrent.org/beps/bep_0003.html).
-    pub fn announce(
+    pub async fn announce(
         &self,
         info_hash: &InfoHash,
         peer: &mut peer::Peer,
@@ -705,17 +1191,123 @@ impl Tracker {
         // responsibility into another authentication service.
 
         tracing::debug!("Before: {peer:?}");
-        peer.change_ip(&assign_ip_address_to_peer(remote_client_ip, self.config.net.external_ip));
+        peer.change_ip(&assign_ip_address_to_peer(
+            remote_client_ip,
+            self.config.net.external_ip,
+            &self.config.net.peer_ip_replacement,
+        ));
         tracing::debug!("After: {peer:?}");
 
-        let stats = self.upsert_peer_and_get_stats(info_hash, peer);
+        self.bandwidth.record(info_hash, peer, CurrentClock::now());
+
+        let group = self.get_experiment_group(remote_client_ip);
+
+        let policy = apply_announce_interval_jitter(
+            self.get_announce_policy_in_group(info_hash, group),
+            self.config.announce_interval_jitter,
+        );
+
+        // The peer is kept until it's expected to announce again
+        peer.timeout_extension = peer_timeout_extension(
+            policy.interval,
+            self.config.peer_timeout_intervals,
+            self.config.tracker_policy.max_peer_timeout,
+        );
+
+        let selects_from_whole_swarm = matches!(
+            &self.experiment,
+            Some(experiment) if group == experiment::Group::Experiment && experiment.selects_from_whole_swarm()
+        );
+
+        let limit = if selects_from_whole_swarm {
+            usize::MAX
+        } else {
+            peers_wanted.limit()
+        };
+
+        let (stats, shared_peers) = self.upsert_peer_and_get_swarm(info_hash, peer, limit).await;
+
+        if let Some(aliases) = &self.torrent_aliases {
+            aliases.record(info_hash, peer, CurrentClock::now());
+        }
+
+        self.publish_activity(activity::Kind::Announce, info_hash, Some(peer.event), &stats);
 
-        let peers = self.get_peers_for(info_hash, peer, peers_wanted.limit());
+        let peers = self.get_peers_for(info_hash, peer, limit, shared_peers).await;
+
+        let peers = match &self.experiment {
+            Some(experiment) if selects_from_whole_swarm => experiment.select_peers(peers, peers_wanted.limit()),
+            _ => peers,
+        };
+
+        if let Some(experiment) = &self.experiment {
+            experiment.record(group, peer.event, peers.len());
+        }
 
         AnnounceData {
             peers,
             stats,
-            policy: self.get_announce_policy(),
+            policy,
+            tracker_id: self.tracker_ids.issue(info_hash, &peer.peer_id),
+            external_ip: self.config.net.return_client_ip.then_some(*remote_client_ip),
+            warning_message: None,
+        }
+    }
+
+    /// It handles an announce request from a `WebRTC` peer of a WebSocket
+    /// tracker.
+    ///
+    /// The peer is added to the swarm like the peers of the other trackers,
+    /// so it's included in the swarm statistics, but it's not returned to the
+    /// UDP and HTTP clients. The WebSocket trackers relay the connection
+    /// offers among their own peers, so no peers are returned.
+    ///
+    /// # Context: Tracker
+    pub async fn announce_web_peer(
+        &self,
+        info_hash: &InfoHash,
+        peer: &mut peer::Peer,
+        remote_client_ip: &IpAddr,
+    ) -> AnnounceData {
+        if peer.event == AnnounceEvent::Stopped {
+            self.web_peers.remove(&(*info_hash, peer.peer_id));
+        } else {
+            self.web_peers.insert((*info_hash, peer.peer_id));
+        }
+
+        self.announce(info_hash, peer, remote_client_ip, &PeersWanted::only(0)).await
+    }
+
+    /// It removes the peer previously announced by the same client, if the
+    /// client announces with a new peer ID. Clients are recognized by the
+    /// `key` param of the HTTP announce requests, so they don't leave a ghost
+    /// peer behind when their IP address changes.
+    ///
+    /// It must be called before the `announce` of the `peer`.
+    ///
+    /// Refer to the [`client_keys`] module for more information.
+    ///
+    /// # Context: Tracker
+    pub async fn replace_previous_peer_of_client(&self, info_hash: &InfoHash, peer: &peer::Peer, client_key: &str) {
+        if peer.event == AnnounceEvent::Stopped {
+            self.client_keys.remove(info_hash, client_key);
+            return;
+        }
+
+        let previous_peer_id = self
+            .client_keys
+            .record(info_hash, client_key, peer.peer_id, CurrentClock::now());
+
+        if let Some(previous_peer_id) = previous_peer_id.filter(|previous_peer_id| *previous_peer_id != peer.peer_id) {
+            tracing::debug!(%info_hash, ?previous_peer_id, peer_id = ?peer.peer_id, "client announced with a new peer ID");
+
+            let previous_peer = peer::Peer {
+                peer_id: previous_peer_id,
+                event: AnnounceEvent::Stopped,
+                ..*peer
+            };
+
+            self.upsert_peer_and_get_stats(info_hash, &previous_peer).await;
         }
     }
 
@@ -729,7 +1321,11 @@ impl Tracker {
 
         for info_hash in info_hashes {
             let swarm_metadata = match self.authorize(info_hash).await {
-                Ok(()) => self.get_swarm_metadata(info_hash),
+                Ok(()) => {
+                    let swarm_metadata = self.get_swarm_metadata(info_hash).await;
+                    self.publish_activity(activity::Kind::Scrape, info_hash, None, &swarm_metadata);
+                    swarm_metadata
+                }
                 Err(_) => SwarmMetadata::zeroed(),
             };
             scrape_data.add_file(info_hash, swarm_metadata);
@@ -739,10 +1335,134 @@ impl Tracker {
     }
 
     /// It returns the data for a `scrape` response.
-    fn get_swarm_metadata(&self, info_hash: &InfoHash) -> SwarmMetadata {
-        match self.torrents.get(info_hash) {
-            Some(torrent_entry) => torrent_entry.get_swarm_metadata(),
-            None => SwarmMetadata::default(),
+    ///
+    /// The data is taken from the [`scrape_cache`](crate::core::scrape_cache)
+    /// when its entries expire after a TTL.
+    async fn get_swarm_metadata(&self, info_hash: &InfoHash) -> SwarmMetadata {
+        if !self.scrape_cache.expires() {
+            return self.get_current_swarm_metadata(info_hash).await.unwrap_or_default();
+        }
+
+        let now = CurrentClock::now();
+
+        if let Some(entry) = self.scrape_cache.get(info_hash, now) {
+            return entry.swarm_metadata;
+        }
+
+        let Some(swarm_metadata) = self.get_current_swarm_metadata(info_hash).await else {
+            return SwarmMetadata::default();
+        };
+
+        self.scrape_cache.insert(info_hash, swarm_metadata, now);
+
+        swarm_metadata
+    }
+
+    /// It handles a `scrape` request like [`Tracker::scrape`], but it returns
+    /// the data of each torrent serialized with the `serialize` function.
+    ///
+    /// The serialized data is taken from the [`scrape_cache`](crate::core::scrape_cache) when possible.
+    ///
+    /// > **NOTICE**: the cache is shared by all the callers, so they must use
+    /// > the same serialization. It's only used by the HTTP tracker.
+    ///
+    /// # Context: Tracker
+    pub async fn scrape_serialized<F>(&self, info_hashes: &[InfoHash], serialize: F) -> Vec<(InfoHash, Arc<[u8]>)>
+    where
+        F: Fn(&SwarmMetadata) -> Vec<u8>,
+    {
+        let mut files = Vec::with_capacity(info_hashes.len());
+
+        for info_hash in info_hashes {
+            let file = match self.authorize(info_hash).await {
+                Ok(()) => {
+                    if self.activity.has_subscribers() {
+                        let swarm_metadata = self.torrents.get_swarm_metadata(info_hash).unwrap_or_default();
+                        self.publish_activity(activity::Kind::Scrape, info_hash, None, &swarm_metadata);
+                    }
+                    self.get_serialized_swarm_metadata(info_hash, &serialize).await
+                }
+                Err(_) => serialize(&SwarmMetadata::zeroed()).into(),
+            };
+            files.push((*info_hash, file));
+        }
+
+        files
+    }
+
+    async fn get_serialized_swarm_metadata<F>(&self, info_hash: &InfoHash, serialize: &F) -> Arc<[u8]>
+    where
+        F: Fn(&SwarmMetadata) -> Vec<u8>,
+    {
+        // The cache is invalidated when the counters of this instance change,
+        // but the shared swarms are also changed by the other instances.
+        if self.swarm_store.is_some() && !self.scrape_cache.expires() {
+            return serialize(&self.get_current_swarm_metadata(info_hash).await.unwrap_or_default()).into();
+        }
+
+        let now = CurrentClock::now();
+
+        let swarm_metadata = match self.scrape_cache.get(info_hash, now) {
+            Some(scrape_cache::Entry { file: Some(file), .. }) => return file,
+            Some(entry) => entry.swarm_metadata,
+            None => match self.get_current_swarm_metadata(info_hash).await {
+                Some(swarm_metadata) => swarm_metadata,
+                None => return serialize(&SwarmMetadata::default()).into(),
+            },
+        };
+
+        let file: Arc<[u8]> = serialize(&swarm_metadata).into();
+
+        self.scrape_cache.insert_file(info_hash, swarm_metadata, file.clone(), now);
+
+        // An announce could have changed the counters while serializing them.
+        if !self.scrape_cache.expires() && self.torrents.get_swarm_metadata(info_hash) != Some(swarm_metadata) {
+            self.scrape_cache.invalidate(info_hash);
+        }
+
+        file
+    }
+
+    /// It returns the swarm metadata of the torrent, from the shared swarms
+    /// when they are enabled and available.
+    async fn get_current_swarm_metadata(&self, info_hash: &InfoHash) -> Option<SwarmMetadata> {
+        if let Some(swarm_store) = &self.swarm_store {
+            match swarm_store.get_swarm(info_hash, None, CurrentClock::now(), 0).await {
+                Ok((_, swarm_metadata)) => return Some(swarm_metadata),
+                Err(err) => tracing::warn!(%info_hash, %err, "unable to get the swarm from the swarm store"),
+            }
+        }
+
+        self.torrents.get_swarm_metadata(info_hash)
+    }
+
+    /// It checks the database is available.
+    ///
+    /// # Context: Tracker
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to connect to the database.
+    pub async fn ping_database(&self) -> Result<(), databases::error::Error> {
+        self.database.ping().await
+    }
+
+    /// It returns the state of the connection pool of the database.
+    ///
+    /// # Context: Tracker
+    pub fn get_database_pool_stats(&self) -> databases::PoolStats {
+        self.database.pool_stats()
+    }
+
+    /// It returns the latency and errors of the database queries, by
+    /// operation.
+    ///
+    /// # Context: Tracker
+    #[must_use]
+    pub fn get_database_query_stats(&self) -> databases::metrics::QueryStats {
+        databases::metrics::QueryStats {
+            driver: Driver::from(&self.config.database.driver),
+            operations: self.database.query_stats(),
         }
     }
 
@@ -754,8 +1474,8 @@ impl Tracker {
     /// # Errors
     ///
     /// Will return a `database::Error` if unable to load the list of `persistent_torrents` from the database.
-    pub fn load_torrents_from_database(&self) -> Result<(), databases::error::Error> {
-        let persistent_torrents = self.database.load_persistent_torrents()?;
+    pub async fn load_torrents_from_database(&self) -> Result<(), databases::error::Error> {
+        let persistent_torrents = self.database.load_persistent_torrents().await?;
 
         self.torrents.import_persistent(&persistent_torrents);
 
@@ -767,10 +1487,90 @@ impl Tracker {
     /// Get torrent peers for a given torrent and client.
     ///
     /// It filters out the client making the request.
-    fn get_peers_for(&self, info_hash: &InfoHash, peer: &peer::Peer, limit: usize) -> Vec<Arc<peer::Peer>> {
+    ///
+    /// The peer diversity constraints are applied if they are enabled.
+    ///
+    /// The peers of the linked swarm are included for hybrid torrents.
+    ///
+    /// The peers are taken from the shared swarms when they are enabled and
+    /// available. The `shared_peers` already read from the shared swarm of
+    /// the torrent, if any, are used instead of reading it again.
+    async fn get_peers_for(
+        &self,
+        info_hash: &InfoHash,
+        peer: &peer::Peer,
+        limit: usize,
+        shared_peers: Option<Vec<peer::Peer>>,
+    ) -> Vec<Arc<peer::Peer>> {
+        let mut peers = match shared_peers {
+            Some(shared_peers) => self.select_shared_peers(shared_peers, peer, limit),
+            None => self.get_swarm_peers_for(info_hash, peer, limit).await,
+        };
+
+        if !self.web_peers.is_empty() {
+            peers.retain(|peer| !self.web_peers.contains(&(*info_hash, peer.peer_id)));
+        }
+
+        match self.torrent_aliases.as_ref().and_then(|aliases| aliases.get(info_hash)) {
+            None => peers,
+            Some(alias) => torrent_aliases::merge_peers(peers, self.get_swarm_peers_for(&alias, peer, limit).await, limit),
+        }
+    }
+
+    async fn get_swarm_peers_for(&self, info_hash: &InfoHash, peer: &peer::Peer, limit: usize) -> Vec<Arc<peer::Peer>> {
+        if let Some(swarm_store) = &self.swarm_store {
+            match swarm_store
+                .get_swarm(
+                    info_hash,
+                    Some(&peer.peer_id),
+                    CurrentClock::now(),
+                    self.shared_peers_limit(limit),
+                )
+                .await
+            {
+                Ok((peers, _)) => return self.select_shared_peers(peers, peer, limit),
+                Err(err) => tracing::warn!(%info_hash, %err, "unable to get the swarm from the swarm store"),
+            }
+        }
+
         match self.torrents.get(info_hash) {
             None => vec![],
-            Some(entry) => entry.get_peers_for_client(&peer.peer_addr, Some(max(limit, TORRENT_PEERS_LIMIT))),
+            Some(entry) if self.config.peer_diversity.is_enabled() => peer_diversity::select_peers(
+                entry.get_peers_for_client(&peer.peer_addr, Some(peer_diversity::max_candidates(limit))),
+                limit,
+                &self.config.peer_diversity,
+                self.asn_database.as_ref(),
+            ),
+            Some(entry) => entry.get_peers_for_client(&peer.peer_addr, Some(limit)),
+        }
+    }
+
+    /// Number of peers read from a shared swarm to select up to `limit` peers.
+    fn shared_peers_limit(&self, limit: usize) -> usize {
+        if self.config.peer_diversity.is_enabled() {
+            peer_diversity::max_candidates(limit)
+        } else {
+            limit
+        }
+    }
+
+    /// It selects up to `limit` peers for the client from the peers read
+    /// from a shared swarm.
+    fn select_shared_peers(&self, peers: Vec<peer::Peer>, peer: &peer::Peer, limit: usize) -> Vec<Arc<peer::Peer>> {
+        let peers = peers
+            .into_iter()
+            .filter(|stored_peer| stored_peer.peer_addr != peer.peer_addr)
+            .map(Arc::new);
+
+        if self.config.peer_diversity.is_enabled() {
+            peer_diversity::select_peers(
+                peers.collect(),
+                limit,
+                &self.config.peer_diversity,
+                self.asn_database.as_ref(),
+            )
+        } else {
+            peers.take(limit).collect()
         }
     }
 
@@ -784,41 +1584,436 @@ impl Tracker {
         }
     }
 
-    /// It updates the torrent entry in memory, it also stores in the database
-    /// the torrent info data which is persistent, and finally return the data
-    /// needed for a `announce` request response.
+    /// It removes the torrent and all its peers. Its persisted stats are
+    /// also removed from the database when `remove_persisted_stats` is `true`,
+    /// even if the torrent is not in memory.
+    ///
+    /// It returns `false` if the tracker does not know the torrent. Notice
+    /// the peers can announce the torrent again afterwards, unless it's not
+    /// allowed by the blacklist or the whitelist.
     ///
     /// # Context: Tracker
-    pub fn upsert_peer_and_get_stats(&self, info_hash: &InfoHash, peer: &peer::Peer) -> SwarmMetadata {
-        let swarm_metadata_before = match self.torrents.get_swarm_metadata(info_hash) {
-            Some(swarm_metadata) => swarm_metadata,
-            None => SwarmMetadata::zeroed(),
-        };
-
-        self.torrents.upsert_peer(info_hash, peer);
-
-        let swarm_metadata_after = match self.torrents.get_swarm_metadata(info_hash) {
-            Some(swarm_metadata) => swarm_metadata,
-            None => SwarmMetadata::zeroed(),
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to remove the persisted
+    /// stats.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock of the buffered stats is poisoned.
+    pub async fn remove_torrent(
+        &self,
+        info_hash: &InfoHash,
+        remove_persisted_stats: bool,
+    ) -> Result<bool, databases::error::Error> {
+        let removed = self.torrents.remove(info_hash).is_some();
+
+        self.scrape_cache.invalidate(info_hash);
+
+        if remove_persisted_stats {
+            self.pending_completed_stats
+                .lock()
+                .expect("it should lock the pending completed stats")
+                .remove(info_hash);
+            self.database.remove_persistent_torrent(info_hash).await?;
+        }
+
+        Ok(removed)
+    }
+
+    /// It removes all the peers of the torrent, keeping the torrent and its
+    /// stats. The peers are added again when they re-announce.
+    ///
+    /// It returns the number of peers removed, or `None` if the tracker does
+    /// not know the torrent.
+    ///
+    /// # Context: Tracker
+    pub fn remove_torrent_peers(&self, info_hash: &InfoHash) -> Option<usize> {
+        let entry = self.torrents.get(info_hash)?;
+
+        let peers = entry.get_peers_len();
+
+        // No peer has been updated after the end of time
+        entry.remove_inactive_peers(DurationSinceUnixEpoch::MAX);
+
+        self.scrape_cache.invalidate(info_hash);
+
+        Some(peers)
+    }
+
+    /// It updates the torrent entry in memory, it also stores in the database
+    /// the torrent info data which is persistent, and finally return the data
+    /// needed for a `announce` request response.
+    ///
+    /// # Context: Tracker
+    pub async fn upsert_peer_and_get_stats(&self, info_hash: &InfoHash, peer: &peer::Peer) -> SwarmMetadata {
+        let (swarm_metadata, _) = self.upsert_peer_and_get_swarm(info_hash, peer, 0).await;
+
+        swarm_metadata
+    }
+
+    /// Like [`Tracker::upsert_peer_and_get_stats`], but it also returns up to
+    /// `limit` peers of the shared swarm, when the shared swarms are enabled
+    /// and available, so the `announce` requests only make one request to
+    /// the swarm store.
+    async fn upsert_peer_and_get_swarm(
+        &self,
+        info_hash: &InfoHash,
+        peer: &peer::Peer,
+        limit: usize,
+    ) -> (SwarmMetadata, Option<Vec<peer::Peer>>) {
+        let swarm_metadata_before = match self.torrents.get_swarm_metadata(info_hash) {
+            Some(swarm_metadata) => swarm_metadata,
+            None => SwarmMetadata::zeroed(),
+        };
+
+        self.torrents.upsert_peer(info_hash, peer);
+
+        let swarm_metadata_after = match self.torrents.get_swarm_metadata(info_hash) {
+            Some(swarm_metadata) => swarm_metadata,
+            None => SwarmMetadata::zeroed(),
         };
 
         if swarm_metadata_before != swarm_metadata_after {
-            self.persist_stats(info_hash, &swarm_metadata_after);
+            if !self.scrape_cache.expires() {
+                self.scrape_cache.invalidate(info_hash);
+            }
+            self.persist_stats(info_hash, &swarm_metadata_after).await;
+        }
+
+        if swarm_metadata_after.downloaded > swarm_metadata_before.downloaded {
+            self.publish_activity(activity::Kind::Completed, info_hash, None, &swarm_metadata_after);
+        }
+
+        self.record_history(info_hash, &swarm_metadata_before, &swarm_metadata_after)
+            .await;
+
+        if let Some(swarm_store) = &self.swarm_store {
+            match swarm_store
+                .upsert_peer(info_hash, peer, CurrentClock::now(), self.shared_peers_limit(limit))
+                .await
+            {
+                Ok((peers, swarm_metadata)) => return (swarm_metadata, Some(peers)),
+                Err(err) => tracing::warn!(%info_hash, %err, "unable to update the swarm in the swarm store"),
+            }
+        }
+
+        (swarm_metadata_after, None)
+    }
+
+    /// It updates the counters of the current hour of the torrent history
+    /// (if the history is enabled), saving the ones of the previous hour.
+    ///
+    /// # Context: Tracker
+    async fn record_history(&self, info_hash: &InfoHash, before: &SwarmMetadata, after: &SwarmMetadata) {
+        if !self.config.persistent_torrent_history {
+            return;
+        }
+
+        if let Some(closed) = self.history.record(info_hash, before, after, CurrentClock::now()) {
+            drop(self.database.save_torrent_history(info_hash, &closed).await);
+        }
+    }
+
+    /// It returns the history of the torrent between `from` and `to`, in
+    /// seconds since the Unix epoch, with the `granularity`. The periods
+    /// without activity are not included. It's empty if the history is not
+    /// enabled.
+    ///
+    /// Refer to the [`torrent_history`] module for more information.
+    ///
+    /// # Context: Tracker
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to load the history from the
+    /// database.
+    pub async fn get_torrent_history(
+        &self,
+        info_hash: &InfoHash,
+        from: DurationSinceUnixEpoch,
+        to: DurationSinceUnixEpoch,
+        granularity: torrent_history::Granularity,
+    ) -> Result<Vec<torrent_history::Bucket>, databases::error::Error> {
+        if !self.config.persistent_torrent_history {
+            return Ok(vec![]);
+        }
+
+        // The whole periods containing `from` and `to`
+        let from = granularity.period_start(from);
+        let to = (granularity.period_start(to) + granularity.duration().as_secs()).saturating_sub(1);
+
+        let mut hourly_buckets = self.database.load_torrent_history(info_hash, from, to).await?;
+
+        if let Some(current) = self.history.current(info_hash, CurrentClock::now()) {
+            if current.start >= from && current.start <= to {
+                hourly_buckets.push(current);
+            }
+        }
+
+        Ok(torrent_history::aggregate(hourly_buckets, granularity))
+    }
+
+    /// It saves the counters of the torrent history of the previous hours,
+    /// for the torrents not updated since then.
+    ///
+    /// # Context: Tracker
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to save the counters of any
+    /// torrent. The rest of the torrents are saved anyway.
+    pub async fn save_closed_torrent_history(&self) -> Result<(), databases::error::Error> {
+        self.save_history(self.history.take_closed(CurrentClock::now())).await
+    }
+
+    async fn save_history(&self, buckets: Vec<(InfoHash, torrent_history::Bucket)>) -> Result<(), databases::error::Error> {
+        let mut result = Ok(());
+
+        for (info_hash, bucket) in buckets {
+            if let Err(err) = self.database.save_torrent_history(&info_hash, &bucket).await {
+                result = Err(err);
+            }
         }
 
-        swarm_metadata_after
+        result
+    }
+
+    /// It returns a receiver of the announce, scrape and completed events.
+    ///
+    /// Refer to the [`activity`] module for more information.
+    ///
+    /// # Context: Tracker
+    #[must_use]
+    pub fn subscribe_to_activity(&self) -> tokio::sync::broadcast::Receiver<activity::Event> {
+        self.activity.subscribe()
+    }
+
+    fn publish_activity(
+        &self,
+        kind: activity::Kind,
+        info_hash: &InfoHash,
+        announce_event: Option<AnnounceEvent>,
+        swarm_metadata: &SwarmMetadata,
+    ) {
+        if self.activity.has_subscribers() {
+            self.activity.publish(activity::Event::new(
+                kind,
+                info_hash,
+                announce_event,
+                swarm_metadata,
+                CurrentClock::now(),
+            ));
+        }
     }
 
     /// It stores the torrents stats into the database (if persistency is enabled).
     ///
+    /// When the writes are batched, the stats are buffered until the next
+    /// [`Tracker::flush_completed_stats`].
+    ///
     /// # Context: Tracker
-    fn persist_stats(&self, info_hash: &InfoHash, swarm_metadata: &SwarmMetadata) {
+    async fn persist_stats(&self, info_hash: &InfoHash, swarm_metadata: &SwarmMetadata) {
         if self.config.tracker_policy.persistent_torrent_completed_stat {
             let completed = swarm_metadata.downloaded;
             let info_hash = *info_hash;
 
-            drop(self.database.save_persistent_torrent(&info_hash, completed));
+            if self.config.database.completed_stat_flush_interval > 0 {
+                self.pending_completed_stats
+                    .lock()
+                    .expect("it should lock the pending completed stats")
+                    .insert(info_hash, completed);
+                return;
+            }
+
+            drop(self.database.save_persistent_torrent(&info_hash, completed).await);
+        }
+    }
+
+    /// It writes the buffered stats of the torrents into the database, in a
+    /// single transaction. It returns the number of torrents written.
+    ///
+    /// # Context: Tracker
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to save the stats. They are
+    /// kept in the buffer and written on the next flush then.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock of the buffer is poisoned.
+    pub async fn flush_completed_stats(&self) -> Result<usize, databases::error::Error> {
+        let pending = std::mem::take(
+            &mut *self
+                .pending_completed_stats
+                .lock()
+                .expect("it should lock the pending completed stats"),
+        );
+
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        if let Err(err) = self.database.save_persistent_torrents(&pending).await {
+            let mut pending_completed_stats = self
+                .pending_completed_stats
+                .lock()
+                .expect("it should lock the pending completed stats");
+
+            // The stats changed in the meantime are newer
+            for (info_hash, completed) in pending {
+                pending_completed_stats.entry(info_hash).or_insert(completed);
+            }
+
+            return Err(err);
+        }
+
+        Ok(pending.len())
+    }
+
+    /// It stores the stats of all the torrents, and the counters of the
+    /// current hour of the torrent history, into the database (if persistency
+    /// is enabled). It's called when the tracker is shut down, because the
+    /// errors saving the stats on each announce are ignored.
+    ///
+    /// # Context: Tracker
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to save the stats of any
+    /// torrent. The rest of the torrents are saved anyway.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock of the buffered stats is poisoned.
+    pub async fn persist_all_stats(&self) -> Result<(), databases::error::Error> {
+        let mut result = self.save_history(self.history.take_all()).await;
+
+        if !self.config.tracker_policy.persistent_torrent_completed_stat {
+            return result;
+        }
+
+        // The buffered stats are written below with the rest
+        self.pending_completed_stats
+            .lock()
+            .expect("it should lock the pending completed stats")
+            .clear();
+
+        for (info_hash, entry) in self.torrents.get_paginated(None) {
+            if let Err(err) = self
+                .database
+                .save_persistent_torrent(&info_hash, entry.get_swarm_metadata().downloaded)
+                .await
+            {
+                result = Err(err);
+            }
         }
+
+        result
+    }
+
+    /// It saves a snapshot of the swarms to the file of the
+    /// `[core.swarm_snapshot]` configuration (if the snapshots are enabled).
+    ///
+    /// Refer to the [`swarm_snapshot`] module for more information.
+    ///
+    /// # Context: Tracker
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can't be written.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the task writing the file panics.
+    pub async fn save_swarm_snapshot(&self) -> Result<(), std::io::Error> {
+        let Some(path) = self.config.swarm_snapshot.path.clone() else {
+            return Ok(());
+        };
+
+        let snapshot = swarm_snapshot::Snapshot::take(&self.torrents, CurrentClock::now(), |info_hash, peer| {
+            !self.web_peers.contains(&(*info_hash, peer.peer_id))
+        });
+
+        tokio::task::spawn_blocking(move || snapshot.save(std::path::Path::new(&path)))
+            .await
+            .expect("it should save the swarm snapshot")
+    }
+
+    /// It restores the swarms from the snapshot file of the
+    /// `[core.swarm_snapshot]` configuration (if the snapshots are enabled
+    /// and the file exists). The expired peers are not restored.
+    ///
+    /// # Context: Tracker
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can't be read or it's not a snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the task reading the file panics.
+    pub async fn restore_swarm_snapshot(&self) -> Result<swarm_snapshot::Restored, std::io::Error> {
+        let Some(path) = self.config.swarm_snapshot.path.clone() else {
+            return Ok(swarm_snapshot::Restored::default());
+        };
+
+        let snapshot = tokio::task::spawn_blocking(move || swarm_snapshot::Snapshot::load(std::path::Path::new(&path)))
+            .await
+            .expect("it should load the swarm snapshot")?;
+
+        let Some(snapshot) = snapshot else {
+            return Ok(swarm_snapshot::Restored::default());
+        };
+
+        let cutoff = CurrentClock::now_sub(&Duration::from_secs(u64::from(self.config.tracker_policy.max_peer_timeout)))
+            .unwrap_or_default();
+
+        Ok(snapshot.restore(&self.torrents, cutoff))
+    }
+
+    /// It returns the estimated throughput of all the swarms, from the bytes
+    /// reported in the announce requests.
+    ///
+    /// # Context: Tracker
+    pub fn get_bandwidth_estimate(&self) -> bandwidth::Estimate {
+        self.bandwidth.global_estimate(CurrentClock::now())
+    }
+
+    /// It returns the estimated throughput of one swarm, from the bytes
+    /// reported in the announce requests.
+    ///
+    /// # Context: Tracker
+    pub fn get_swarm_bandwidth_estimate(&self, info_hash: &InfoHash) -> bandwidth::Estimate {
+        self.bandwidth.swarm_estimate(info_hash, CurrentClock::now())
+    }
+
+    /// It returns the hits and misses of the `scrape` cache.
+    ///
+    /// # Context: Tracker
+    pub fn get_scrape_cache_stats(&self) -> scrape_cache::Stats {
+        self.scrape_cache.stats()
+    }
+
+    /// It returns the group of the experiment the client with the IP address
+    /// belongs to. All the clients are in the control group if there is no
+    /// experiment.
+    ///
+    /// # Context: Experiment
+    pub fn get_experiment_group(&self, ip: &IpAddr) -> experiment::Group {
+        self.experiment
+            .as_ref()
+            .map_or(experiment::Group::Control, |experiment| experiment.group_of(ip))
+    }
+
+    /// It returns the announces handled for each group of the experiment, if
+    /// there is one.
+    ///
+    /// # Context: Experiment
+    pub fn get_experiment_stats(&self) -> Option<experiment::Stats> {
+        self.experiment.as_ref().map(experiment::Experiment::stats)
     }
 
     /// It calculates and returns the general `Tracker`
@@ -835,15 +2030,23 @@ impl Tracker {
     /// Remove inactive peers and (optionally) peerless torrents.
     ///
     /// # Context: Tracker
-    pub fn cleanup_torrents(&self) {
+    pub async fn cleanup_torrents(&self) {
         let current_cutoff = CurrentClock::now_sub(&Duration::from_secs(u64::from(self.config.tracker_policy.max_peer_timeout)))
             .unwrap_or_default();
 
         self.torrents.remove_inactive_peers(current_cutoff);
+        self.bandwidth.remove_inactive_peers(current_cutoff);
+        self.client_keys.remove_inactive(current_cutoff);
 
         if self.config.tracker_policy.remove_peerless_torrents {
             self.torrents.remove_peerless_torrents(&self.config.tracker_policy);
         }
+
+        self.scrape_cache.clear();
+
+        if let Some(aliases) = &self.torrent_aliases {
+            aliases.cleanup(CurrentClock::now(), |info_hash| self.torrents.get(info_hash).is_some());
+        }
     }
 
     /// It authenticates the peer `key` against the `Tracker` authentication
@@ -862,6 +2065,14 @@ impl Tracker {
         }
     }
 
+    /// It returns `true` if the authentication `key` is in the key store,
+    /// whether it has expired or not.
+    ///
+    /// # Context: Authentication
+    pub async fn has_auth_key(&self, key: &Key) -> bool {
+        self.keys.read().await.contains_key(key)
+    }
+
     /// Adds new peer keys to the tracker.
     ///
     /// Keys can be pre-generated or randomly created. They can also be permanent or expire.
@@ -876,65 +2087,43 @@ impl Tracker {
     pub async fn add_peer_key(&self, add_key_req: AddKeyRequest) -> Result<auth::PeerKey, PeerKeyError> {
         // code-review: all methods related to keys should be moved to a new independent "keys" service.
 
-        match add_key_req.opt_key {
-            // Upload pre-generated key
-            Some(pre_existing_key) => {
-                if let Some(seconds_valid) = add_key_req.opt_seconds_valid {
-                    // Expiring key
-                    let Some(valid_until) = CurrentClock::now_add(&Duration::from_secs(seconds_valid)) else {
-                        return Err(PeerKeyError::DurationOverflow { seconds_valid });
-                    };
-
-                    let key = pre_existing_key.parse::<Key>();
+        let valid_until = match add_key_req.opt_seconds_valid {
+            // Expiring key
+            Some(seconds_valid) => match CurrentClock::now_add(&Duration::from_secs(seconds_valid)) {
+                Some(valid_until) => Some(valid_until),
+                None => return Err(PeerKeyError::DurationOverflow { seconds_valid }),
+            },
+            // Permanent key
+            None => None,
+        };
 
-                    match key {
-                        Ok(key) => match self.add_auth_key(key, Some(valid_until)).await {
-                            Ok(auth_key) => Ok(auth_key),
-                            Err(err) => Err(PeerKeyError::DatabaseError {
-                                source: Located(err).into(),
-                            }),
-                        },
-                        Err(err) => Err(PeerKeyError::InvalidKey {
-                            key: pre_existing_key,
-                            source: Located(err).into(),
-                        }),
-                    }
-                } else {
-                    // Permanent key
-                    let key = pre_existing_key.parse::<Key>();
-
-                    match key {
-                        Ok(key) => match self.add_permanent_auth_key(key).await {
-                            Ok(auth_key) => Ok(auth_key),
-                            Err(err) => Err(PeerKeyError::DatabaseError {
-                                source: Located(err).into(),
-                            }),
-                        },
-                        Err(err) => Err(PeerKeyError::InvalidKey {
-                            key: pre_existing_key,
-                            source: Located(err).into(),
-                        }),
-                    }
-                }
-            }
-            // Generate a new random key
-            None => match add_key_req.opt_seconds_valid {
-                // Expiring key
-                Some(seconds_valid) => match self.generate_auth_key(Some(Duration::from_secs(seconds_valid))).await {
-                    Ok(auth_key) => Ok(auth_key),
-                    Err(err) => Err(PeerKeyError::DatabaseError {
-                        source: Located(err).into(),
-                    }),
-                },
-                // Permanent key
-                None => match self.generate_permanent_auth_key().await {
-                    Ok(auth_key) => Ok(auth_key),
-                    Err(err) => Err(PeerKeyError::DatabaseError {
+        let key = match add_key_req.opt_key {
+            // Upload pre-generated key
+            Some(pre_existing_key) => match pre_existing_key.parse::<Key>() {
+                Ok(key) => key,
+                Err(err) => {
+                    return Err(PeerKeyError::InvalidKey {
+                        key: pre_existing_key,
                         source: Located(err).into(),
-                    }),
-                },
+                    })
+                }
             },
-        }
+            // Generate a new random key
+            None => auth::generate_permanent_key().key(),
+        };
+
+        let auth_key = PeerKey {
+            key,
+            valid_until,
+            created_at: Some(CurrentClock::now()),
+            label: add_key_req.opt_label,
+        };
+
+        self.insert_auth_key(auth_key)
+            .await
+            .map_err(|err| PeerKeyError::DatabaseError {
+                source: Located(err).into(),
+            })
     }
 
     /// It generates a new permanent authentication key.
@@ -965,11 +2154,7 @@ impl Tracker {
     /// * `lifetime` - The duration in seconds for the new key. The key will be
     ///   no longer valid after `lifetime` seconds.
     pub async fn generate_auth_key(&self, lifetime: Option<Duration>) -> Result<auth::PeerKey, databases::error::Error> {
-        let auth_key = auth::generate_key(lifetime);
-
-        self.database.add_key_to_keys(&auth_key)?;
-        self.keys.write().await.insert(auth_key.key.clone(), auth_key.clone());
-        Ok(auth_key)
+        self.insert_auth_key(auth::generate_key(lifetime)).await
     }
 
     /// It adds a pre-generated permanent authentication key.
@@ -1011,12 +2196,20 @@ impl Tracker {
         key: Key,
         valid_until: Option<DurationSinceUnixEpoch>,
     ) -> Result<auth::PeerKey, databases::error::Error> {
-        let auth_key = PeerKey { key, valid_until };
+        self.insert_auth_key(PeerKey {
+            key,
+            valid_until,
+            created_at: Some(CurrentClock::now()),
+            label: None,
+        })
+        .await
+    }
 
+    async fn insert_auth_key(&self, auth_key: PeerKey) -> Result<auth::PeerKey, databases::error::Error> {
         // code-review: should we return a friendly error instead of the DB
         // constrain error when the key already exist? For now, it's returning
         // the specif error for each DB driver when a UNIQUE constrain fails.
-        self.database.add_key_to_keys(&auth_key)?;
+        self.database.add_key_to_keys(&auth_key).await?;
         self.keys.write().await.insert(auth_key.key.clone(), auth_key.clone());
         Ok(auth_key)
     }
@@ -1029,11 +2222,142 @@ impl Tracker {
     ///
     /// Will return a `database::Error` if unable to remove the `key` to the database.
     pub async fn remove_auth_key(&self, key: &Key) -> Result<(), databases::error::Error> {
-        self.database.remove_key_from_keys(key)?;
+        self.database.remove_key_from_keys(key).await?;
         self.keys.write().await.remove(key);
         Ok(())
     }
 
+    /// It returns the authentication keys, ordered by key.
+    ///
+    /// # Context: Authentication
+    pub async fn get_auth_keys(&self, pagination: Option<&Pagination>) -> Vec<PeerKey> {
+        let mut peer_keys = self.keys.read().await.values().cloned().collect::<Vec<_>>();
+
+        peer_keys.sort_by(|a, b| a.key.value().cmp(b.key.value()));
+
+        match pagination {
+            Some(pagination) => peer_keys
+                .into_iter()
+                .skip(pagination.offset as usize)
+                .take(pagination.limit as usize)
+                .collect(),
+            None => peer_keys,
+        }
+    }
+
+    /// It renews an authentication key. The key will be valid for
+    /// `seconds_valid` seconds from now, even if it was permanent or it had
+    /// already expired.
+    ///
+    /// # Context: Authentication
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if:
+    ///
+    /// - The key duration overflows the duration type maximum value.
+    /// - The key does not exist.
+    /// - The renewed key could not been persisted due to database issues.
+    pub async fn renew_auth_key(&self, key: &Key, seconds_valid: u64) -> Result<auth::PeerKey, PeerKeyError> {
+        let Some(valid_until) = CurrentClock::now_add(&Duration::from_secs(seconds_valid)) else {
+            return Err(PeerKeyError::DurationOverflow { seconds_valid });
+        };
+
+        let mut keys = self.keys.write().await;
+
+        let Some(previous_key) = keys.get(key) else {
+            return Err(PeerKeyError::UnknownKey { key: key.clone() });
+        };
+
+        // It keeps the creation time and the label
+        let auth_key = PeerKey {
+            valid_until: Some(valid_until),
+            ..previous_key.clone()
+        };
+
+        let result = match self.database.remove_key_from_keys(key).await {
+            Ok(_) => self.database.add_key_to_keys(&auth_key).await,
+            Err(err) => Err(err),
+        };
+
+        result.map_err(|err| PeerKeyError::DatabaseError {
+            source: Located(err).into(),
+        })?;
+
+        keys.insert(auth_key.key.clone(), auth_key.clone());
+
+        Ok(auth_key)
+    }
+
+    /// It removes the expired authentication keys from memory and from the
+    /// database. It returns the number of keys removed from memory.
+    ///
+    /// The expired keys are removed from the database with a single query,
+    /// including the ones not loaded in memory.
+    ///
+    /// Refer to the [`key_cleanup`](crate::bootstrap::jobs::key_cleanup) job.
+    ///
+    /// # Context: Authentication
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to remove the keys from the
+    /// database. They are kept in memory then.
+    pub async fn remove_expired_auth_keys(&self) -> Result<usize, databases::error::Error> {
+        let now = CurrentClock::now();
+
+        self.database.remove_expired_keys(now).await?;
+
+        let mut keys = self.keys.write().await;
+
+        let before = keys.len();
+
+        keys.retain(|_, peer_key| !peer_key.valid_until.is_some_and(|valid_until| valid_until < now));
+
+        Ok(before - keys.len())
+    }
+
+    /// It applies the changes pulled from a remote key source: it adds (or
+    /// replaces) the updated keys and removes the revoked ones.
+    ///
+    /// Refer to the [`key_sync`](crate::bootstrap::jobs::key_sync) job for
+    /// more information.
+    ///
+    /// # Context: Authentication
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to persist the changes in the
+    /// database.
+    pub async fn sync_auth_keys(&self, updated: &[PeerKey], removed: &[Key]) -> Result<(), databases::error::Error> {
+        let mut created_at = HashMap::new();
+
+        for key in removed.iter().chain(updated.iter().map(|peer_key| &peer_key.key)) {
+            let previous_key = self.keys.read().await.get(key).cloned();
+
+            if let Some(previous_key) = previous_key {
+                created_at.insert(key.clone(), previous_key.created_at);
+                self.remove_auth_key(key).await?;
+            }
+        }
+
+        for peer_key in updated {
+            // The updated keys keep their creation time
+            self.insert_auth_key(PeerKey {
+                created_at: created_at
+                    .get(&peer_key.key)
+                    .copied()
+                    .flatten()
+                    .or(peer_key.created_at)
+                    .or(Some(CurrentClock::now())),
+                ..peer_key.clone()
+            })
+            .await?;
+        }
+
+        Ok(())
+    }
+
     /// It verifies an authentication key.
     ///
     /// # Context: Authentication
@@ -1071,7 +2395,7 @@ impl Tracker {
     ///
     /// Will return a `database::Error` if unable to `load_keys` from the database.
     pub async fn load_keys_from_database(&self) -> Result<(), databases::error::Error> {
-        let keys_from_database = self.database.load_keys()?;
+        let keys_from_database = self.database.load_keys().await?;
         let mut keys = self.keys.write().await;
 
         keys.clear();
@@ -1091,13 +2415,21 @@ impl Tracker {
     /// # Errors
     ///
     /// Will return an error if the tracker is running in `listed` mode
-    /// and the infohash is not whitelisted.
+    /// and the infohash is not whitelisted, or if the infohash has been
+    /// blacklisted after an abuse report.
     pub async fn authorize(&self, info_hash: &InfoHash) -> Result<(), Error> {
+        if self.mitigations.is_info_hash_blacklisted(info_hash) {
+            return Err(Error::TorrentBlacklisted {
+                info_hash: *info_hash,
+                location: Location::caller(),
+            });
+        }
+
         if !self.is_listed() {
             return Ok(());
         }
 
-        if self.is_info_hash_whitelisted(info_hash).await {
+        if self.is_info_hash_whitelisted(info_hash).await || self.is_info_hash_in_whitelisted_namespace(info_hash) {
             return Ok(());
         }
 
@@ -1107,6 +2439,119 @@ impl Tracker {
         })
     }
 
+    /// It checks if the peer IP address is allowed to use the tracker.
+    ///
+    /// # Context: Authorization
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the IP address has been banned after an abuse
+    /// report.
+    pub fn authorize_peer_ip(&self, ip: &IpAddr) -> Result<(), Error> {
+        if self.mitigations.is_ip_banned(ip) {
+            return Err(Error::PeerIpBanned {
+                ip: *ip,
+                location: Location::caller(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// It takes a token from the global announce budget for an announce
+    /// request from the IP address.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the tracker is saturated and the IP address
+    /// has used its fair share of the budget.
+    pub fn consume_announce_budget(&self, ip: &IpAddr) -> Result<(), Error> {
+        let Some(budget) = &self.announce_budget else {
+            return Ok(());
+        };
+
+        if !budget.try_acquire(ip, CurrentClock::now()) {
+            return Err(Error::AnnounceBudgetExceeded {
+                ip: *ip,
+                location: Location::caller(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// It checks if the client of the peer is allowed to use the tracker. The
+    /// client whitelist is only enforced in `private` mode.
+    ///
+    /// Refer to the [`client_whitelist`] module for more information.
+    ///
+    /// # Context: Authorization
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the tracker is running in `private` mode and
+    /// the peer ID does not start with any of the whitelisted prefixes.
+    pub fn authorize_client(&self, peer_id: &PeerId) -> Result<(), Error> {
+        if !self.is_private() || self.client_whitelist.is_allowed(peer_id) {
+            return Ok(());
+        }
+
+        Err(Error::ClientNotWhitelisted {
+            peer_id: *peer_id,
+            location: Location::caller(),
+        })
+    }
+
+    /// It adds a peer ID prefix to the client whitelist. It returns `false`
+    /// if the prefix is not valid.
+    ///
+    /// # Context: Client whitelist
+    pub fn add_client_to_whitelist(&self, peer_id_prefix: &str) -> bool {
+        self.client_whitelist.add(peer_id_prefix)
+    }
+
+    /// It removes a peer ID prefix from the client whitelist. It returns
+    /// `true` if the prefix was whitelisted.
+    ///
+    /// # Context: Client whitelist
+    pub fn remove_client_from_whitelist(&self, peer_id_prefix: &str) -> bool {
+        self.client_whitelist.remove(peer_id_prefix)
+    }
+
+    /// It returns the peer ID prefixes in the client whitelist.
+    ///
+    /// # Context: Client whitelist
+    pub fn get_client_whitelist(&self) -> Vec<String> {
+        self.client_whitelist.get_all()
+    }
+
+    /// It applies the configured mitigations for an abuse report and returns
+    /// the applied ones.
+    ///
+    /// # Context: Abuse
+    pub fn report_abuse(&self, report: &abuse::Report) -> Vec<abuse::Mitigation> {
+        let mitigations = self.mitigations.apply(&self.config.abuse, report);
+
+        tracing::info!(?report, ?mitigations, "abuse report received");
+
+        mitigations
+    }
+
+    /// Returns the construction used to generate the UDP connection IDs.
+    pub fn get_udp_connection_id_scheme(&self) -> ConnectionIdScheme {
+        self.config.udp_connection_id_scheme
+    }
+
+    /// Returns the proxy for the outbound HTTP requests, if configured.
+    pub fn get_outbound_proxy(&self) -> Option<String> {
+        self.config.net.outbound_proxy.clone()
+    }
+
+    /// Returns the URLs to notify when an abuse report is received.
+    pub fn get_abuse_webhooks(&self) -> Vec<String> {
+        self.config.abuse.webhooks.clone()
+    }
+
     /// It adds a torrent to the whitelist.
     /// Adding torrents is not relevant to public trackers.
     ///
@@ -1116,20 +2561,20 @@ impl Tracker {
     ///
     /// Will return a `database::Error` if unable to add the `info_hash` into the whitelist database.
     pub async fn add_torrent_to_whitelist(&self, info_hash: &InfoHash) -> Result<(), databases::error::Error> {
-        self.add_torrent_to_database_whitelist(info_hash)?;
+        self.add_torrent_to_database_whitelist(info_hash).await?;
         self.add_torrent_to_memory_whitelist(info_hash).await;
         Ok(())
     }
 
     /// It adds a torrent to the whitelist if it has not been whitelisted previously
-    fn add_torrent_to_database_whitelist(&self, info_hash: &InfoHash) -> Result<(), databases::error::Error> {
-        let is_whitelisted = self.database.is_info_hash_whitelisted(*info_hash)?;
+    async fn add_torrent_to_database_whitelist(&self, info_hash: &InfoHash) -> Result<(), databases::error::Error> {
+        let is_whitelisted = self.database.is_info_hash_whitelisted(*info_hash).await?;
 
         if is_whitelisted {
             return Ok(());
         }
 
-        self.database.add_info_hash_to_whitelist(*info_hash)?;
+        self.database.add_info_hash_to_whitelist(*info_hash).await?;
 
         Ok(())
     }
@@ -1147,7 +2592,7 @@ impl Tracker {
     ///
     /// Will return a `database::Error` if unable to remove the `info_hash` from the whitelist database.
     pub async fn remove_torrent_from_whitelist(&self, info_hash: &InfoHash) -> Result<(), databases::error::Error> {
-        self.remove_torrent_from_database_whitelist(info_hash)?;
+        self.remove_torrent_from_database_whitelist(info_hash).await?;
         self.remove_torrent_from_memory_whitelist(info_hash).await;
         Ok(())
     }
@@ -1159,14 +2604,14 @@ impl Tracker {
     /// # Errors
     ///
     /// Will return a `database::Error` if unable to remove the `info_hash` from the whitelist database.
-    pub fn remove_torrent_from_database_whitelist(&self, info_hash: &InfoHash) -> Result<(), databases::error::Error> {
-        let is_whitelisted = self.database.is_info_hash_whitelisted(*info_hash)?;
+    pub async fn remove_torrent_from_database_whitelist(&self, info_hash: &InfoHash) -> Result<(), databases::error::Error> {
+        let is_whitelisted = self.database.is_info_hash_whitelisted(*info_hash).await?;
 
         if !is_whitelisted {
             return Ok(());
         }
 
-        self.database.remove_info_hash_from_whitelist(*info_hash)?;
+        self.database.remove_info_hash_from_whitelist(*info_hash).await?;
 
         Ok(())
     }
@@ -1185,6 +2630,41 @@ impl Tracker {
         self.whitelist.read().await.contains(info_hash)
     }
 
+    /// It returns the whitelisted torrents, ordered by infohash.
+    ///
+    /// # Context: Whitelist
+    pub async fn get_whitelisted_torrents(&self, pagination: Option<&Pagination>) -> Vec<InfoHash> {
+        let mut info_hashes = self.whitelist.read().await.iter().copied().collect::<Vec<_>>();
+
+        info_hashes.sort();
+
+        match pagination {
+            Some(pagination) => info_hashes
+                .into_iter()
+                .skip(pagination.offset as usize)
+                .take(pagination.limit as usize)
+                .collect(),
+            None => info_hashes,
+        }
+    }
+
+    /// It checks if the infohash starts with one of the whitelist prefixes.
+    ///
+    /// # Context: Whitelist
+    pub fn is_info_hash_in_whitelisted_namespace(&self, info_hash: &InfoHash) -> bool {
+        if self.config.whitelist.prefixes.is_empty() {
+            return false;
+        }
+
+        let info_hash = info_hash.to_hex_string();
+
+        self.config
+            .whitelist
+            .prefixes
+            .iter()
+            .any(|prefix| info_hash.starts_with(&prefix.to_ascii_lowercase()))
+    }
+
     /// It loads the whitelist from the database.
     ///
     /// # Context: Whitelist
@@ -1193,7 +2673,7 @@ impl Tracker {
     ///
     /// Will return a `database::Error` if unable to load the list whitelisted `info_hash`s from the database.
     pub async fn load_whitelist_from_database(&self) -> Result<(), databases::error::Error> {
-        let whitelisted_torrents_from_database = self.database.load_whitelist()?;
+        let whitelisted_torrents_from_database = self.database.load_whitelist().await?;
         let mut whitelist = self.whitelist.write().await;
 
         whitelist.clear();
@@ -1205,6 +2685,77 @@ impl Tracker {
         Ok(())
     }
 
+    /// It adds an entry to the audit log of the administrative actions.
+    ///
+    /// Refer to the [`audit`] module for more information.
+    ///
+    /// # Context: Audit Log
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to save the entry to the
+    /// database.
+    pub async fn record_audit_entry(&self, entry: &audit::Entry) -> Result<(), databases::error::Error> {
+        self.database.add_audit_entry(entry).await
+    }
+
+    /// It returns the page of the audit log entries matching the `filter`,
+    /// the most recent first.
+    ///
+    /// # Context: Audit Log
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to load the entries from the
+    /// database.
+    pub async fn get_audit_entries(
+        &self,
+        filter: &audit::Filter,
+        pagination: &Pagination,
+    ) -> Result<Vec<audit::Entry>, databases::error::Error> {
+        self.database.load_audit_entries(filter, pagination).await
+    }
+
+    /// It loads the rotated access tokens of the API from the database.
+    ///
+    /// Refer to the [`api_tokens`] module for more information.
+    ///
+    /// # Context: API Access Tokens
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to load the tokens from the
+    /// database.
+    pub async fn load_rotated_api_tokens(&self) -> Result<Vec<api_tokens::RotatedToken>, databases::error::Error> {
+        self.database.load_rotated_api_tokens().await
+    }
+
+    /// It saves a rotated access token of the API to the database.
+    ///
+    /// # Context: API Access Tokens
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to save the token to the
+    /// database.
+    pub async fn save_rotated_api_token(&self, rotated_token: &api_tokens::RotatedToken) -> Result<(), databases::error::Error> {
+        self.database.save_rotated_api_token(rotated_token).await
+    }
+
+    /// It compacts the database, to reclaim the space of the deleted rows.
+    ///
+    /// Refer to the [`maintenance`](crate::core::services::maintenance)
+    /// services.
+    ///
+    /// # Context: Database Maintenance
+    ///
+    /// # Errors
+    ///
+    /// Will return a `database::Error` if unable to compact the database.
+    pub async fn compact_database(&self) -> Result<(), databases::error::Error> {
+        self.database.compact().await
+    }
+
     /// It return the `Tracker` [`statistics::Metrics`].
     ///
     /// # Context: Statistics
@@ -1222,6 +2773,70 @@ impl Tracker {
         }
     }
 
+    /// It returns the [`stats_summary::Summary`] of the statistics. The
+    /// summary is cached for [`stats_summary::TTL`].
+    ///
+    /// # Context: Statistics
+    pub async fn get_stats_summary(&self) -> stats_summary::Summary {
+        let now = CurrentClock::now();
+
+        if let Some(summary) = self.stats_summary.get(now) {
+            return summary;
+        }
+
+        let summary = stats_summary::Summary {
+            torrents_metrics: self.get_torrents_metrics(),
+            last_hour: self
+                .stats_repository
+                .get_recent_requests(Duration::from_secs(60 * 60), now)
+                .await,
+            last_day: self
+                .stats_repository
+                .get_recent_requests(Duration::from_secs(24 * 60 * 60), now)
+                .await,
+            computed_at: now,
+        };
+
+        self.stats_summary.insert(summary);
+
+        summary
+    }
+
+    /// It returns the first `limit` torrents of the ranking, at most
+    /// [`top_torrents::MAX_LIMIT`]. The rankings are cached for
+    /// [`top_torrents::TTL`].
+    ///
+    /// # Context: Torrents
+    pub fn get_top_torrents(&self, ranking: top_torrents::Ranking, limit: usize) -> Vec<(InfoHash, SwarmMetadata)> {
+        let now = CurrentClock::now();
+
+        let snapshot = self.top_torrents.get(now).unwrap_or_else(|| {
+            let torrents = self
+                .torrents
+                .get_paginated(None)
+                .into_iter()
+                .map(|(info_hash, torrent_entry)| (info_hash, torrent_entry.get_swarm_metadata()))
+                .collect();
+
+            let snapshot = Arc::new(top_torrents::Snapshot::new(torrents, now));
+
+            self.top_torrents.insert(snapshot.clone());
+
+            snapshot
+        });
+
+        snapshot.top(ranking, limit).to_vec()
+    }
+
+    /// It checks the listener of the statistic events is still running. It
+    /// returns `None` if the statistics are disabled.
+    ///
+    /// # Context: Statistics
+    #[must_use]
+    pub fn is_stats_listener_alive(&self) -> Option<bool> {
+        self.stats_event_sender.as_ref().map(|sender| sender.is_listening())
+    }
+
     /// It drops the database tables.
     ///
     /// # Errors
@@ -1234,13 +2849,47 @@ impl Tracker {
     }
 }
 
-#[must_use]
-fn assign_ip_address_to_peer(remote_client_ip: &IpAddr, tracker_external_ip: Option<IpAddr>) -> IpAddr {
-    if let Some(host_ip) = tracker_external_ip.filter(|_| remote_client_ip.is_loopback()) {
-        host_ip
-    } else {
-        *remote_client_ip
-    }
+/// It returns the IP address assigned to the peer: the tracker external IP
+/// if the replacement policy applies to the client IP, or the client IP
+/// otherwise.
+#[must_use]
+fn assign_ip_address_to_peer(
+    remote_client_ip: &IpAddr,
+    tracker_external_ip: Option<IpAddr>,
+    replacement_policy: &PeerIpReplacement,
+) -> IpAddr {
+    if let Some(host_ip) = tracker_external_ip.filter(|_| replacement_policy.applies_to(remote_client_ip)) {
+        host_ip
+    } else {
+        *remote_client_ip
+    }
+}
+
+/// It randomizes the announce interval within a `jitter` percentage band.
+/// The interval is never lower than the minimum announce interval.
+#[must_use]
+fn apply_announce_interval_jitter(policy: AnnouncePolicy, jitter: u8) -> AnnouncePolicy {
+    if jitter == 0 {
+        return policy;
+    }
+
+    let max_variation = i64::from(policy.interval) * i64::from(jitter) / 100;
+    let variation = rand::thread_rng().gen_range(-max_variation..=max_variation);
+
+    let interval = u32::try_from(i64::from(policy.interval) + variation).unwrap_or(policy.interval);
+
+    AnnouncePolicy {
+        interval: interval.max(policy.interval_min),
+        interval_min: policy.interval_min,
+    }
+}
+
+/// Extra time a peer is kept after the `max_peer_timeout`, so it's not
+/// removed before `intervals` times the announce `interval` handed to it.
+fn peer_timeout_extension(interval: u32, intervals: u32, max_peer_timeout: u32) -> Duration {
+    let timeout = u64::from(interval) * u64::from(intervals);
+
+    Duration::from_secs(timeout.saturating_sub(u64::from(max_peer_timeout)))
 }
 
 #[cfg(test)]
@@ -1251,6 +2900,7 @@ mod tests {
         use std::net::{IpAddr, Ipv4Addr, SocketAddr};
         use std::str::FromStr;
         use std::sync::Arc;
+        use std::time::Duration;
 
         use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
         use torrust_tracker_configuration::TORRENT_PEERS_LIMIT;
@@ -1260,10 +2910,10 @@ mod tests {
 
         use crate::core::peer::Peer;
         use crate::core::services::tracker_factory;
-        use crate::core::{TorrentsMetrics, Tracker};
+        use crate::core::{top_torrents, TorrentsMetrics, Tracker};
         use crate::shared::bit_torrent::info_hash::fixture::gen_seeded_infohash;
 
-        fn public_tracker() -> Tracker {
+        pub fn public_tracker() -> Tracker {
             tracker_factory(&configuration::ephemeral_public())
         }
 
@@ -1281,6 +2931,12 @@ mod tests {
             tracker_factory(&configuration)
         }
 
+        pub fn tracker_persisting_torrent_history() -> Tracker {
+            let mut configuration = configuration::ephemeral();
+            configuration.core.persistent_torrent_history = true;
+            tracker_factory(&configuration)
+        }
+
         fn sample_info_hash() -> InfoHash {
             "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()
         }
@@ -1305,6 +2961,8 @@ mod tests {
                 downloaded: NumberOfBytes::new(0),
                 left: NumberOfBytes::new(0),
                 event: AnnounceEvent::Completed,
+                timeout_extension: Duration::ZERO,
+                alt_peer_addr: None,
             }
         }
 
@@ -1318,6 +2976,8 @@ mod tests {
                 downloaded: NumberOfBytes::new(0),
                 left: NumberOfBytes::new(0),
                 event: AnnounceEvent::Completed,
+                timeout_extension: Duration::ZERO,
+                alt_peer_addr: None,
             }
         }
 
@@ -1349,6 +3009,8 @@ mod tests {
                 downloaded: NumberOfBytes::new(0),
                 left: NumberOfBytes::new(0), // No bytes left to download
                 event: AnnounceEvent::Completed,
+                timeout_extension: Duration::ZERO,
+                alt_peer_addr: None,
             }
         }
 
@@ -1362,6 +3024,8 @@ mod tests {
                 downloaded: NumberOfBytes::new(0),
                 left: NumberOfBytes::new(1000), // Still bytes to download
                 event: AnnounceEvent::Started,
+                timeout_extension: Duration::ZERO,
+                alt_peer_addr: None,
             }
         }
 
@@ -1389,7 +3053,7 @@ mod tests {
             let info_hash = sample_info_hash();
             let peer = sample_peer();
 
-            tracker.upsert_peer_and_get_stats(&info_hash, &peer);
+            tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
 
             let peers = tracker.get_torrent_peers(&info_hash);
 
@@ -1429,9 +3093,11 @@ mod tests {
                     downloaded: NumberOfBytes::new(0),
                     left: NumberOfBytes::new(0), // No bytes left to download
                     event: AnnounceEvent::Completed,
+                    timeout_extension: Duration::ZERO,
+                    alt_peer_addr: None,
                 };
 
-                tracker.upsert_peer_and_get_stats(&info_hash, &peer);
+                tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
             }
 
             let peers = tracker.get_torrent_peers(&info_hash);
@@ -1446,9 +3112,9 @@ mod tests {
             let info_hash = sample_info_hash();
             let peer = sample_peer();
 
-            tracker.upsert_peer_and_get_stats(&info_hash, &peer);
+            tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
 
-            let peers = tracker.get_peers_for(&info_hash, &peer, TORRENT_PEERS_LIMIT);
+            let peers = tracker.get_peers_for(&info_hash, &peer, TORRENT_PEERS_LIMIT, None).await;
 
             assert_eq!(peers, vec![]);
         }
@@ -1461,7 +3127,7 @@ mod tests {
 
             let excluded_peer = sample_peer();
 
-            tracker.upsert_peer_and_get_stats(&info_hash, &excluded_peer);
+            tracker.upsert_peer_and_get_stats(&info_hash, &excluded_peer).await;
 
             // Add 74 peers
             for idx in 2..=75 {
@@ -1473,12 +3139,16 @@ mod tests {
                     downloaded: NumberOfBytes::new(0),
                     left: NumberOfBytes::new(0), // No bytes left to download
                     event: AnnounceEvent::Completed,
+                    timeout_extension: Duration::ZERO,
+                    alt_peer_addr: None,
                 };
 
-                tracker.upsert_peer_and_get_stats(&info_hash, &peer);
+                tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
             }
 
-            let peers = tracker.get_peers_for(&info_hash, &excluded_peer, TORRENT_PEERS_LIMIT);
+            let peers = tracker
+                .get_peers_for(&info_hash, &excluded_peer, TORRENT_PEERS_LIMIT, None)
+                .await;
 
             assert_eq!(peers.len(), 74);
         }
@@ -1487,7 +3157,7 @@ mod tests {
         async fn it_should_return_the_torrent_metrics() {
             let tracker = public_tracker();
 
-            tracker.upsert_peer_and_get_stats(&sample_info_hash(), &leecher());
+            tracker.upsert_peer_and_get_stats(&sample_info_hash(), &leecher()).await;
 
             let torrent_metrics = tracker.get_torrents_metrics();
 
@@ -1502,13 +3172,47 @@ mod tests {
             );
         }
 
+        #[tokio::test]
+        async fn it_should_cache_the_statistics_summary() {
+            let tracker = public_tracker();
+
+            tracker.upsert_peer_and_get_stats(&sample_info_hash(), &leecher()).await;
+
+            assert_eq!(tracker.get_stats_summary().await.torrents_metrics.torrents, 1);
+
+            tracker.upsert_peer_and_get_stats(&gen_seeded_infohash(&1), &leecher()).await;
+
+            // The clock is stopped in the tests, so the summary doesn't expire
+            assert_eq!(tracker.get_stats_summary().await.torrents_metrics.torrents, 1);
+        }
+
+        #[tokio::test]
+        async fn it_should_return_the_torrents_with_more_peers_first() {
+            let tracker = public_tracker();
+
+            tracker.upsert_peer_and_get_stats(&sample_info_hash(), &sample_peer_1()).await;
+            tracker
+                .upsert_peer_and_get_stats(&gen_seeded_infohash(&1), &sample_peer_1())
+                .await;
+            tracker
+                .upsert_peer_and_get_stats(&gen_seeded_infohash(&1), &sample_peer_2())
+                .await;
+
+            let top_torrents = tracker.get_top_torrents(top_torrents::Ranking::Peers, 1);
+
+            assert_eq!(
+                top_torrents.iter().map(|(info_hash, _)| *info_hash).collect::<Vec<_>>(),
+                vec![gen_seeded_infohash(&1)]
+            );
+        }
+
         #[tokio::test]
         async fn it_should_get_many_the_torrent_metrics() {
             let tracker = public_tracker();
 
             let start_time = std::time::Instant::now();
             for i in 0..1_000_000 {
-                tracker.upsert_peer_and_get_stats(&gen_seeded_infohash(&i), &leecher());
+                tracker.upsert_peer_and_get_stats(&gen_seeded_infohash(&i), &leecher()).await;
             }
             let result_a = start_time.elapsed();
 
@@ -1528,14 +3232,33 @@ mod tests {
             );
         }
 
+        #[tokio::test]
+        async fn it_should_check_the_database_is_available() {
+            let tracker = public_tracker();
+
+            assert!(tracker.ping_database().await.is_ok());
+        }
+
+        #[tokio::test]
+        async fn it_should_check_the_statistics_event_listener_is_alive() {
+            let tracker = public_tracker();
+
+            assert_eq!(tracker.is_stats_listener_alive(), Some(true));
+        }
+
         mod for_all_config_modes {
 
             mod handling_an_announce_request {
 
                 use std::sync::Arc;
 
+                use torrust_tracker_test_helpers::configuration;
+
+                use crate::core::activity::Kind;
+                use crate::core::services::tracker_factory;
                 use crate::core::tests::the_tracker::{
-                    peer_ip, public_tracker, sample_info_hash, sample_peer, sample_peer_1, sample_peer_2,
+                    completed_peer, peer_ip, public_tracker, sample_info_hash, sample_peer, sample_peer_1, sample_peer_2,
+                    started_peer,
                 };
                 use crate::core::PeersWanted;
 
@@ -1543,29 +3266,76 @@ mod tests {
 
                     use std::net::{IpAddr, Ipv4Addr};
 
+                    use torrust_tracker_configuration::PeerIpReplacement;
+
                     use crate::core::assign_ip_address_to_peer;
 
                     #[test]
                     fn using_the_source_ip_instead_of_the_ip_in_the_announce_request() {
                         let remote_ip = IpAddr::V4(Ipv4Addr::new(126, 0, 0, 2));
 
-                        let peer_ip = assign_ip_address_to_peer(&remote_ip, None);
+                        let peer_ip = assign_ip_address_to_peer(&remote_ip, None, &PeerIpReplacement::default());
 
                         assert_eq!(peer_ip, remote_ip);
                     }
 
+                    mod and_when_the_replacement_policy_is_customized {
+
+                        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+                        use std::str::FromStr;
+
+                        use torrust_tracker_configuration::cidr::Cidr;
+                        use torrust_tracker_configuration::PeerIpReplacement;
+
+                        use crate::core::assign_ip_address_to_peer;
+
+                        #[test]
+                        fn it_should_use_the_external_ip_for_peers_in_the_configured_private_ranges() {
+                            let remote_ip = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10));
+
+                            let tracker_external_ip = IpAddr::V4(Ipv4Addr::from_str("126.0.0.1").unwrap());
+
+                            let policy = PeerIpReplacement {
+                                ranges: vec![Cidr::from_str("192.168.0.0/16").unwrap()],
+                                ..Default::default()
+                            };
+
+                            let peer_ip = assign_ip_address_to_peer(&remote_ip, Some(tracker_external_ip), &policy);
+
+                            assert_eq!(peer_ip, tracker_external_ip);
+                        }
+
+                        #[test]
+                        fn it_should_keep_the_ipv6_loopback_ip_when_ipv6_replacement_is_disabled() {
+                            let remote_ip = IpAddr::V6(Ipv6Addr::LOCALHOST);
+
+                            let tracker_external_ip = IpAddr::V4(Ipv4Addr::from_str("126.0.0.1").unwrap());
+
+                            let policy = PeerIpReplacement {
+                                ipv6: false,
+                                ..Default::default()
+                            };
+
+                            let peer_ip = assign_ip_address_to_peer(&remote_ip, Some(tracker_external_ip), &policy);
+
+                            assert_eq!(peer_ip, remote_ip);
+                        }
+                    }
+
                     mod and_when_the_client_ip_is_a_ipv4_loopback_ip {
 
                         use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
                         use std::str::FromStr;
 
+                        use torrust_tracker_configuration::PeerIpReplacement;
+
                         use crate::core::assign_ip_address_to_peer;
 
                         #[test]
                         fn it_should_use_the_loopback_ip_if_the_tracker_does_not_have_the_external_ip_configuration() {
                             let remote_ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
 
-                            let peer_ip = assign_ip_address_to_peer(&remote_ip, None);
+                            let peer_ip = assign_ip_address_to_peer(&remote_ip, None, &PeerIpReplacement::default());
 
                             assert_eq!(peer_ip, remote_ip);
                         }
@@ -1576,7 +3346,8 @@ mod tests {
 
                             let tracker_external_ip = IpAddr::V4(Ipv4Addr::from_str("126.0.0.1").unwrap());
 
-                            let peer_ip = assign_ip_address_to_peer(&remote_ip, Some(tracker_external_ip));
+                            let peer_ip =
+                                assign_ip_address_to_peer(&remote_ip, Some(tracker_external_ip), &PeerIpReplacement::default());
 
                             assert_eq!(peer_ip, tracker_external_ip);
                         }
@@ -1589,7 +3360,8 @@ mod tests {
                             let tracker_external_ip =
                                 IpAddr::V6(Ipv6Addr::from_str("2345:0425:2CA1:0000:0000:0567:5673:23b5").unwrap());
 
-                            let peer_ip = assign_ip_address_to_peer(&remote_ip, Some(tracker_external_ip));
+                            let peer_ip =
+                                assign_ip_address_to_peer(&remote_ip, Some(tracker_external_ip), &PeerIpReplacement::default());
 
                             assert_eq!(peer_ip, tracker_external_ip);
                         }
@@ -1600,13 +3372,15 @@ mod tests {
                         use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
                         use std::str::FromStr;
 
+                        use torrust_tracker_configuration::PeerIpReplacement;
+
                         use crate::core::assign_ip_address_to_peer;
 
                         #[test]
                         fn it_should_use_the_loopback_ip_if_the_tracker_does_not_have_the_external_ip_configuration() {
                             let remote_ip = IpAddr::V6(Ipv6Addr::LOCALHOST);
 
-                            let peer_ip = assign_ip_address_to_peer(&remote_ip, None);
+                            let peer_ip = assign_ip_address_to_peer(&remote_ip, None, &PeerIpReplacement::default());
 
                             assert_eq!(peer_ip, remote_ip);
                         }
@@ -1618,7 +3392,8 @@ mod tests {
                             let tracker_external_ip =
                                 IpAddr::V6(Ipv6Addr::from_str("2345:0425:2CA1:0000:0000:0567:5673:23b5").unwrap());
 
-                            let peer_ip = assign_ip_address_to_peer(&remote_ip, Some(tracker_external_ip));
+                            let peer_ip =
+                                assign_ip_address_to_peer(&remote_ip, Some(tracker_external_ip), &PeerIpReplacement::default());
 
                             assert_eq!(peer_ip, tracker_external_ip);
                         }
@@ -1630,7 +3405,8 @@ mod tests {
 
                             let tracker_external_ip = IpAddr::V4(Ipv4Addr::from_str("126.0.0.1").unwrap());
 
-                            let peer_ip = assign_ip_address_to_peer(&remote_ip, Some(tracker_external_ip));
+                            let peer_ip =
+                                assign_ip_address_to_peer(&remote_ip, Some(tracker_external_ip), &PeerIpReplacement::default());
 
                             assert_eq!(peer_ip, tracker_external_ip);
                         }
@@ -1643,7 +3419,9 @@ mod tests {
 
                     let mut peer = sample_peer();
 
-                    let announce_data = tracker.announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All);
+                    let announce_data = tracker
+                        .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All)
+                        .await;
 
                     assert_eq!(announce_data.peers, vec![]);
                 }
@@ -1653,19 +3431,117 @@ mod tests {
                     let tracker = public_tracker();
 
                     let mut previously_announced_peer = sample_peer_1();
-                    tracker.announce(
-                        &sample_info_hash(),
-                        &mut previously_announced_peer,
-                        &peer_ip(),
-                        &PeersWanted::All,
-                    );
+                    tracker
+                        .announce(
+                            &sample_info_hash(),
+                            &mut previously_announced_peer,
+                            &peer_ip(),
+                            &PeersWanted::All,
+                        )
+                        .await;
 
                     let mut peer = sample_peer_2();
-                    let announce_data = tracker.announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All);
+                    let announce_data = tracker
+                        .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All)
+                        .await;
 
                     assert_eq!(announce_data.peers, vec![Arc::new(previously_announced_peer)]);
                 }
 
+                #[tokio::test]
+                async fn it_should_replace_the_previous_peer_of_a_client_announcing_with_the_same_key() {
+                    let tracker = public_tracker();
+
+                    let mut previous_peer = sample_peer_1();
+                    tracker
+                        .replace_previous_peer_of_client(&sample_info_hash(), &previous_peer, "1A2B3C4D")
+                        .await;
+                    tracker
+                        .announce(&sample_info_hash(), &mut previous_peer, &peer_ip(), &PeersWanted::All)
+                        .await;
+
+                    // The same client, with a new IP address and peer ID
+                    let mut peer = sample_peer_2();
+                    tracker
+                        .replace_previous_peer_of_client(&sample_info_hash(), &peer, "1A2B3C4D")
+                        .await;
+                    let announce_data = tracker
+                        .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All)
+                        .await;
+
+                    assert_eq!(announce_data.peers, vec![]);
+                    assert_eq!(announce_data.stats.complete + announce_data.stats.incomplete, 1);
+                }
+
+                #[tokio::test]
+                async fn it_should_not_return_the_web_peers_to_the_other_clients() {
+                    let tracker = public_tracker();
+
+                    let mut web_peer = sample_peer_1();
+                    tracker
+                        .announce_web_peer(&sample_info_hash(), &mut web_peer, &peer_ip())
+                        .await;
+
+                    let mut peer = sample_peer_2();
+                    let announce_data = tracker
+                        .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All)
+                        .await;
+
+                    assert_eq!(announce_data.peers, vec![]);
+                    assert_eq!(announce_data.stats.complete + announce_data.stats.incomplete, 2);
+                }
+
+                #[tokio::test]
+                async fn it_should_not_return_the_client_ip_by_default() {
+                    let tracker = public_tracker();
+
+                    let mut peer = sample_peer();
+
+                    let announce_data = tracker
+                        .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All)
+                        .await;
+
+                    assert_eq!(announce_data.external_ip, None);
+                }
+
+                #[tokio::test]
+                async fn it_should_return_the_client_ip_when_it_is_enabled() {
+                    let mut configuration = configuration::ephemeral_public();
+                    configuration.core.net.return_client_ip = true;
+                    let tracker = tracker_factory(&configuration);
+
+                    let mut peer = sample_peer();
+
+                    let announce_data = tracker
+                        .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All)
+                        .await;
+
+                    assert_eq!(announce_data.external_ip, Some(peer_ip()));
+                }
+
+                #[tokio::test]
+                async fn it_should_publish_the_announce_and_completed_events_to_the_activity_subscribers() {
+                    let tracker = public_tracker();
+
+                    let mut activity = tracker.subscribe_to_activity();
+
+                    let mut started_peer = started_peer();
+                    tracker
+                        .announce(&sample_info_hash(), &mut started_peer, &peer_ip(), &PeersWanted::All)
+                        .await;
+
+                    let mut completed_peer = completed_peer();
+                    tracker
+                        .announce(&sample_info_hash(), &mut completed_peer, &peer_ip(), &PeersWanted::All)
+                        .await;
+
+                    let kinds: Vec<Kind> = std::iter::from_fn(|| activity.try_recv().ok())
+                        .map(|event| event.kind)
+                        .collect();
+
+                    assert_eq!(kinds, vec![Kind::Announce, Kind::Completed, Kind::Announce]);
+                }
+
                 mod it_should_update_the_swarm_stats_for_the_torrent {
 
                     use crate::core::tests::the_tracker::{
@@ -1679,7 +3555,9 @@ mod tests {
 
                         let mut peer = seeder();
 
-                        let announce_data = tracker.announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All);
+                        let announce_data = tracker
+                            .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All)
+                            .await;
 
                         assert_eq!(announce_data.stats.complete, 1);
                     }
@@ -1690,7 +3568,9 @@ mod tests {
 
                         let mut peer = leecher();
 
-                        let announce_data = tracker.announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All);
+                        let announce_data = tracker
+                            .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All)
+                            .await;
 
                         assert_eq!(announce_data.stats.incomplete, 1);
                     }
@@ -1701,25 +3581,109 @@ mod tests {
 
                         // We have to announce with "started" event because peer does not count if peer was not previously known
                         let mut started_peer = started_peer();
-                        tracker.announce(&sample_info_hash(), &mut started_peer, &peer_ip(), &PeersWanted::All);
+                        tracker
+                            .announce(&sample_info_hash(), &mut started_peer, &peer_ip(), &PeersWanted::All)
+                            .await;
 
                         let mut completed_peer = completed_peer();
-                        let announce_data =
-                            tracker.announce(&sample_info_hash(), &mut completed_peer, &peer_ip(), &PeersWanted::All);
+                        let announce_data = tracker
+                            .announce(&sample_info_hash(), &mut completed_peer, &peer_ip(), &PeersWanted::All)
+                            .await;
 
                         assert_eq!(announce_data.stats.downloaded, 1);
                     }
                 }
+
+                mod for_a_hybrid_torrent {
+
+                    use std::sync::Arc;
+
+                    use torrust_tracker_primitives::info_hash::InfoHash;
+                    use torrust_tracker_test_helpers::configuration;
+
+                    use crate::core::services::tracker_factory;
+                    use crate::core::tests::the_tracker::{complete_peer, peer_ip, sample_peer_1, sample_peer_2};
+                    use crate::core::PeersWanted;
+
+                    fn v1_info_hash() -> InfoHash {
+                        "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()
+                    }
+
+                    fn v2_info_hash() -> InfoHash {
+                        "99c82bb73505a3c0b453f9fa0e881d6e5a32a0c1".parse::<InfoHash>().unwrap()
+                    }
+
+                    #[tokio::test]
+                    async fn it_should_return_the_peers_of_the_linked_swarm_when_the_aliases_are_enabled() {
+                        let mut configuration = configuration::ephemeral_public();
+                        configuration.core.torrent_aliases.confirmations = Some(1);
+                        let tracker = tracker_factory(&configuration);
+
+                        // A hybrid client announces both infohashes
+                        let mut hybrid_peer = sample_peer_1();
+                        tracker
+                            .announce(&v1_info_hash(), &mut hybrid_peer, &peer_ip(), &PeersWanted::All)
+                            .await;
+                        tracker
+                            .announce(&v2_info_hash(), &mut hybrid_peer, &peer_ip(), &PeersWanted::All)
+                            .await;
+
+                        // A v2-only client
+                        let mut v2_peer = sample_peer_2();
+                        tracker
+                            .announce(&v2_info_hash(), &mut v2_peer, &peer_ip(), &PeersWanted::All)
+                            .await;
+
+                        // A v1-only client
+                        let mut v1_peer = complete_peer();
+                        let announce_data = tracker
+                            .announce(&v1_info_hash(), &mut v1_peer, &peer_ip(), &PeersWanted::All)
+                            .await;
+
+                        assert_eq!(announce_data.peers, vec![Arc::new(hybrid_peer), Arc::new(v2_peer)]);
+                    }
+
+                    #[tokio::test]
+                    async fn it_should_not_link_the_swarms_by_default() {
+                        let tracker = tracker_factory(&configuration::ephemeral_public());
+
+                        let mut hybrid_peer = sample_peer_1();
+                        tracker
+                            .announce(&v1_info_hash(), &mut hybrid_peer, &peer_ip(), &PeersWanted::All)
+                            .await;
+                        tracker
+                            .announce(&v2_info_hash(), &mut hybrid_peer, &peer_ip(), &PeersWanted::All)
+                            .await;
+
+                        let mut v2_peer = sample_peer_2();
+                        tracker
+                            .announce(&v2_info_hash(), &mut v2_peer, &peer_ip(), &PeersWanted::All)
+                            .await;
+
+                        let mut v1_peer = complete_peer();
+                        let announce_data = tracker
+                            .announce(&v1_info_hash(), &mut v1_peer, &peer_ip(), &PeersWanted::All)
+                            .await;
+
+                        assert_eq!(announce_data.peers, vec![Arc::new(hybrid_peer)]);
+                    }
+                }
             }
 
             mod handling_a_scrape_request {
 
                 use std::net::{IpAddr, Ipv4Addr};
+                use std::time::Duration;
 
+                use aquatic_udp_protocol::PeerId;
+                use torrust_tracker_clock::clock::stopped::Stopped as _;
+                use torrust_tracker_clock::clock::{self};
                 use torrust_tracker_primitives::info_hash::InfoHash;
+                use torrust_tracker_test_helpers::configuration;
 
+                use crate::core::services::tracker_factory;
                 use crate::core::tests::the_tracker::{complete_peer, incomplete_peer, public_tracker};
-                use crate::core::{PeersWanted, ScrapeData, SwarmMetadata};
+                use crate::core::{scrape_cache, PeersWanted, ScrapeData, SwarmMetadata};
 
                 #[tokio::test]
                 async fn it_should_return_a_zeroed_swarm_metadata_for_the_requested_file_if_the_tracker_does_not_have_that_torrent(
@@ -1745,21 +3709,25 @@ mod tests {
 
                     // Announce a "complete" peer for the torrent
                     let mut complete_peer = complete_peer();
-                    tracker.announce(
-                        &info_hash,
-                        &mut complete_peer,
-                        &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 10)),
-                        &PeersWanted::All,
-                    );
+                    tracker
+                        .announce(
+                            &info_hash,
+                            &mut complete_peer,
+                            &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 10)),
+                            &PeersWanted::All,
+                        )
+                        .await;
 
                     // Announce an "incomplete" peer for the torrent
                     let mut incomplete_peer = incomplete_peer();
-                    tracker.announce(
-                        &info_hash,
-                        &mut incomplete_peer,
-                        &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 11)),
-                        &PeersWanted::All,
-                    );
+                    tracker
+                        .announce(
+                            &info_hash,
+                            &mut incomplete_peer,
+                            &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 11)),
+                            &PeersWanted::All,
+                        )
+                        .await;
 
                     // Scrape
                     let scrape_data = tracker.scrape(&vec![info_hash]).await;
@@ -1795,12 +3763,122 @@ mod tests {
 
                     assert_eq!(scrape_data, expected_scrape_data);
                 }
+
+                fn serialize(swarm_metadata: &SwarmMetadata) -> Vec<u8> {
+                    format!(
+                        "{}/{}/{}",
+                        swarm_metadata.complete, swarm_metadata.downloaded, swarm_metadata.incomplete
+                    )
+                    .into_bytes()
+                }
+
+                #[tokio::test]
+                async fn it_should_cache_the_serialized_swarm_metadata_until_the_swarm_counters_change() {
+                    let tracker = public_tracker();
+
+                    let info_hash = "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap();
+
+                    let mut peer = incomplete_peer();
+                    tracker
+                        .announce(
+                            &info_hash,
+                            &mut peer,
+                            &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 11)),
+                            &PeersWanted::All,
+                        )
+                        .await;
+
+                    let files = tracker.scrape_serialized(&[info_hash], serialize).await;
+
+                    assert_eq!(&*files[0].1, b"0/0/1");
+                    assert_eq!(tracker.scrape_cache.len(), 1);
+
+                    // Announce a second "incomplete" peer for the torrent
+                    let mut peer = incomplete_peer();
+                    peer.peer_id = PeerId(*b"-qB00000000000000001");
+                    tracker
+                        .announce(
+                            &info_hash,
+                            &mut peer,
+                            &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 12)),
+                            &PeersWanted::All,
+                        )
+                        .await;
+
+                    assert!(tracker.scrape_cache.is_empty());
+
+                    let files = tracker.scrape_serialized(&[info_hash], serialize).await;
+
+                    assert_eq!(&*files[0].1, b"0/0/2");
+                }
+
+                #[tokio::test]
+                async fn it_should_not_cache_the_serialized_swarm_metadata_of_unknown_torrents() {
+                    let tracker = public_tracker();
+
+                    let info_hash = "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap();
+
+                    let files = tracker.scrape_serialized(&[info_hash], serialize).await;
+
+                    assert_eq!(&*files[0].1, b"0/0/0");
+                    assert!(tracker.scrape_cache.is_empty());
+                }
+
+                #[tokio::test]
+                async fn it_should_return_the_cached_swarm_metadata_until_it_expires_when_the_cache_has_a_ttl() {
+                    let mut configuration = configuration::ephemeral_public();
+                    configuration.core.scrape_cache_ttl = 30;
+                    let tracker = tracker_factory(&configuration);
+
+                    let info_hash = "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap();
+
+                    let mut peer = incomplete_peer();
+                    tracker
+                        .announce(
+                            &info_hash,
+                            &mut peer,
+                            &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 11)),
+                            &PeersWanted::All,
+                        )
+                        .await;
+
+                    let scrape_data = tracker.scrape(&vec![info_hash]).await;
+
+                    assert_eq!(scrape_data.files[&info_hash].incomplete, 1);
+
+                    // Announce a second "incomplete" peer for the torrent
+                    let mut peer = incomplete_peer();
+                    peer.peer_id = PeerId(*b"-qB00000000000000001");
+                    tracker
+                        .announce(
+                            &info_hash,
+                            &mut peer,
+                            &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 12)),
+                            &PeersWanted::All,
+                        )
+                        .await;
+
+                    let scrape_data = tracker.scrape(&vec![info_hash]).await;
+
+                    assert_eq!(scrape_data.files[&info_hash].incomplete, 1);
+
+                    clock::Stopped::local_add(&Duration::from_secs(30)).unwrap();
+
+                    let scrape_data = tracker.scrape(&vec![info_hash]).await;
+
+                    assert_eq!(scrape_data.files[&info_hash].incomplete, 2);
+                    assert_eq!(tracker.get_scrape_cache_stats(), scrape_cache::Stats { hits: 1, misses: 2 });
+                }
             }
         }
 
         mod configured_as_whitelisted {
 
             mod handling_authorization {
+                use torrust_tracker_primitives::info_hash::InfoHash;
+                use torrust_tracker_test_helpers::configuration;
+
+                use crate::core::services::tracker_factory;
                 use crate::core::tests::the_tracker::{sample_info_hash, whitelisted_tracker};
 
                 #[tokio::test]
@@ -1825,9 +3903,24 @@ mod tests {
                     let result = tracker.authorize(&info_hash).await;
                     assert!(result.is_err());
                 }
+
+                #[tokio::test]
+                async fn it_should_authorize_the_announce_and_scrape_actions_on_torrents_in_a_whitelisted_namespace() {
+                    let mut config = configuration::ephemeral_listed();
+                    config.core.whitelist.prefixes = vec!["3B2455".to_string(), "c0ffee".to_string()];
+                    let tracker = tracker_factory(&config);
+
+                    assert!(tracker.authorize(&sample_info_hash()).await.is_ok());
+
+                    let other_info_hash = "99c82bb73505a3c0b453f9fa0e881d6e5a32a0c1".parse::<InfoHash>().unwrap();
+                    assert!(tracker.authorize(&other_info_hash).await.is_err());
+                }
             }
 
             mod handling_the_torrent_whitelist {
+                use torrust_tracker_primitives::info_hash::InfoHash;
+                use torrust_tracker_primitives::pagination::Pagination;
+
                 use crate::core::tests::the_tracker::{sample_info_hash, whitelisted_tracker};
 
                 #[tokio::test]
@@ -1854,6 +3947,23 @@ mod tests {
                     assert!(!tracker.is_info_hash_whitelisted(&info_hash).await);
                 }
 
+                #[tokio::test]
+                async fn it_should_list_the_whitelisted_torrents_ordered_by_info_hash() {
+                    let tracker = whitelisted_tracker();
+
+                    let info_hash_1 = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".parse::<InfoHash>().unwrap();
+                    let info_hash_2 = "0b3aea4adc213ce32295be85d3883a63bca25446".parse::<InfoHash>().unwrap();
+
+                    tracker.add_torrent_to_whitelist(&info_hash_1).await.unwrap();
+                    tracker.add_torrent_to_whitelist(&info_hash_2).await.unwrap();
+
+                    assert_eq!(tracker.get_whitelisted_torrents(None).await, vec![info_hash_2, info_hash_1]);
+                    assert_eq!(
+                        tracker.get_whitelisted_torrents(Some(&Pagination::new(1, 10))).await,
+                        vec![info_hash_1]
+                    );
+                }
+
                 mod persistence {
                     use crate::core::tests::the_tracker::{sample_info_hash, whitelisted_tracker};
 
@@ -1907,11 +4017,11 @@ mod tests {
                     let info_hash = "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap();
 
                     let mut peer = incomplete_peer();
-                    tracker.announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::All);
+                    tracker.announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::All).await;
 
                     // Announce twice to force non zeroed swarm metadata
                     let mut peer = complete_peer();
-                    tracker.announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::All);
+                    tracker.announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::All).await;
 
                     let scrape_data = tracker.scrape(&vec![info_hash]).await;
 
@@ -1930,8 +4040,14 @@ mod tests {
                 use std::str::FromStr;
                 use std::time::Duration;
 
+                use torrust_tracker_clock::clock::Time;
+                use torrust_tracker_primitives::pagination::Pagination;
+
                 use crate::core::auth::{self};
+                use crate::core::error::PeerKeyError;
                 use crate::core::tests::the_tracker::private_tracker;
+                use crate::core::AddKeyRequest;
+                use crate::CurrentClock;
 
                 #[tokio::test]
                 async fn it_should_fail_authenticating_a_peer_when_it_uses_an_unregistered_key() {
@@ -1948,21 +4064,162 @@ mod tests {
                 async fn it_should_fail_verifying_an_unregistered_authentication_key() {
                     let tracker = private_tracker();
 
-                    let unregistered_key = auth::Key::from_str("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap();
+                    let unregistered_key = auth::Key::from_str("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap();
+
+                    assert!(tracker.verify_auth_key(&unregistered_key).await.is_err());
+                }
+
+                #[tokio::test]
+                async fn it_should_tell_whether_an_authentication_key_is_in_the_key_store() {
+                    let tracker = private_tracker();
+
+                    let key = tracker.generate_permanent_auth_key().await.unwrap().key();
+                    let unregistered_key = auth::Key::from_str("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap();
+
+                    assert!(tracker.has_auth_key(&key).await);
+                    assert!(!tracker.has_auth_key(&unregistered_key).await);
+                }
+
+                #[tokio::test]
+                async fn it_should_remove_an_authentication_key() {
+                    let tracker = private_tracker();
+
+                    let expiring_key = tracker.generate_auth_key(Some(Duration::from_secs(100))).await.unwrap();
+
+                    let result = tracker.remove_auth_key(&expiring_key.key()).await;
+
+                    assert!(result.is_ok());
+                    assert!(tracker.verify_auth_key(&expiring_key.key()).await.is_err());
+                }
+
+                #[tokio::test]
+                async fn it_should_apply_the_key_changes_pulled_from_a_remote_source() {
+                    let tracker = private_tracker();
+
+                    let revoked_key = tracker.generate_permanent_auth_key().await.unwrap();
+                    let updated_key = tracker.generate_permanent_auth_key().await.unwrap();
+                    let new_key = auth::PeerKey {
+                        key: auth::Key::from_str("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap(),
+                        valid_until: None,
+                        created_at: None,
+                        label: None,
+                    };
+                    let expired_key = auth::PeerKey {
+                        key: updated_key.key(),
+                        valid_until: Some(Duration::ZERO),
+                        created_at: None,
+                        label: None,
+                    };
+
+                    let result = tracker
+                        .sync_auth_keys(&[new_key.clone(), expired_key], &[revoked_key.key()])
+                        .await;
+
+                    assert!(result.is_ok());
+                    assert!(tracker.verify_auth_key(&new_key.key()).await.is_ok());
+                    assert!(tracker.verify_auth_key(&updated_key.key()).await.is_err());
+                    assert!(tracker.verify_auth_key(&revoked_key.key()).await.is_err());
+                }
+
+                #[tokio::test]
+                async fn it_should_list_the_authentication_keys_ordered_by_key() {
+                    let tracker = private_tracker();
+
+                    let key_1 = tracker
+                        .add_permanent_auth_key(auth::Key::from_str("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap())
+                        .await
+                        .unwrap();
+                    let key_2 = tracker
+                        .add_permanent_auth_key(auth::Key::from_str("AZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap())
+                        .await
+                        .unwrap();
+
+                    assert_eq!(tracker.get_auth_keys(None).await, vec![key_2, key_1.clone()]);
+                    assert_eq!(tracker.get_auth_keys(Some(&Pagination::new(1, 10))).await, vec![key_1]);
+                }
+
+                #[tokio::test]
+                async fn it_should_store_the_creation_time_and_the_label_of_the_keys() {
+                    let tracker = private_tracker();
+
+                    let peer_key = tracker
+                        .add_peer_key(AddKeyRequest {
+                            opt_key: None,
+                            opt_seconds_valid: Some(100),
+                            opt_label: Some("user-1234".to_string()),
+                        })
+                        .await
+                        .unwrap();
+
+                    assert_eq!(peer_key.created_at, Some(CurrentClock::now()));
+                    assert_eq!(peer_key.label, Some("user-1234".to_string()));
+
+                    // It keeps them when the key is renewed
+                    let renewed_key = tracker.renew_auth_key(&peer_key.key(), 200).await.unwrap();
+
+                    assert_eq!(renewed_key.created_at, peer_key.created_at);
+                    assert_eq!(renewed_key.label, peer_key.label);
+
+                    tracker.keys.write().await.clear();
+                    tracker.load_keys_from_database().await.unwrap();
+
+                    assert_eq!(tracker.get_auth_keys(None).await, vec![renewed_key]);
+                }
+
+                #[tokio::test]
+                async fn it_should_renew_an_expired_authentication_key() {
+                    let tracker = private_tracker();
+
+                    let expired_key = tracker
+                        .add_auth_key(
+                            auth::Key::from_str("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap(),
+                            Some(Duration::ZERO),
+                        )
+                        .await
+                        .unwrap();
+
+                    let renewed_key = tracker.renew_auth_key(&expired_key.key(), 100).await.unwrap();
+
+                    assert_eq!(
+                        renewed_key.valid_until,
+                        Some(CurrentClock::now_add(&Duration::from_secs(100)).unwrap())
+                    );
+                    assert!(tracker.verify_auth_key(&expired_key.key()).await.is_ok());
+                }
+
+                #[tokio::test]
+                async fn it_should_fail_renewing_an_unknown_authentication_key() {
+                    let tracker = private_tracker();
+
+                    let unknown_key = auth::Key::from_str("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap();
 
-                    assert!(tracker.verify_auth_key(&unregistered_key).await.is_err());
+                    assert!(matches!(
+                        tracker.renew_auth_key(&unknown_key, 100).await,
+                        Err(PeerKeyError::UnknownKey { .. })
+                    ));
                 }
 
                 #[tokio::test]
-                async fn it_should_remove_an_authentication_key() {
+                async fn it_should_remove_the_expired_authentication_keys() {
                     let tracker = private_tracker();
 
+                    let permanent_key = tracker.generate_permanent_auth_key().await.unwrap();
                     let expiring_key = tracker.generate_auth_key(Some(Duration::from_secs(100))).await.unwrap();
+                    let expired_key = tracker
+                        .add_auth_key(
+                            auth::Key::from_str("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap(),
+                            Some(Duration::ZERO),
+                        )
+                        .await
+                        .unwrap();
 
-                    let result = tracker.remove_auth_key(&expiring_key.key()).await;
+                    assert_eq!(tracker.remove_expired_auth_keys().await.unwrap(), 1);
 
-                    assert!(result.is_ok());
-                    assert!(tracker.verify_auth_key(&expiring_key.key()).await.is_err());
+                    let keys = tracker.get_auth_keys(None).await;
+
+                    assert!(keys.contains(&permanent_key));
+                    assert!(keys.contains(&expiring_key));
+                    assert!(!keys.contains(&expired_key));
                 }
 
                 #[tokio::test]
@@ -2053,6 +4310,7 @@ mod tests {
                                 .add_peer_key(AddKeyRequest {
                                     opt_key: Some(Key::new("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap().to_string()),
                                     opt_seconds_valid: Some(100),
+                                    opt_label: None,
                                 })
                                 .await
                                 .unwrap();
@@ -2071,6 +4329,7 @@ mod tests {
                                 .add_peer_key(AddKeyRequest {
                                     opt_key: Some(Key::new("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap().to_string()),
                                     opt_seconds_valid: Some(100),
+                                    opt_label: None,
                                 })
                                 .await
                                 .unwrap();
@@ -2092,6 +4351,7 @@ mod tests {
                                 .add_peer_key(AddKeyRequest {
                                     opt_key: Some(Key::new("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap().to_string()),
                                     opt_seconds_valid: Some(0),
+                                    opt_label: None,
                                 })
                                 .await
                                 .unwrap();
@@ -2140,6 +4400,7 @@ mod tests {
                                 .add_peer_key(AddKeyRequest {
                                     opt_key: Some(Key::new("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap().to_string()),
                                     opt_seconds_valid: None,
+                                    opt_label: None,
                                 })
                                 .await
                                 .unwrap();
@@ -2155,6 +4416,7 @@ mod tests {
                                 .add_peer_key(AddKeyRequest {
                                     opt_key: Some(Key::new("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap().to_string()),
                                     opt_seconds_valid: None,
+                                    opt_label: None,
                                 })
                                 .await
                                 .unwrap();
@@ -2167,6 +4429,38 @@ mod tests {
                 }
             }
 
+            mod handling_the_client_whitelist {
+                use aquatic_udp_protocol::PeerId;
+
+                use crate::core::tests::the_tracker::{private_tracker, public_tracker};
+
+                #[test]
+                fn it_should_only_authorize_the_whitelisted_clients() {
+                    let tracker = private_tracker();
+
+                    assert!(tracker.add_client_to_whitelist("-qB45"));
+
+                    assert!(tracker.authorize_client(&PeerId(*b"-qB4520-000000000001")).is_ok());
+                    assert!(tracker.authorize_client(&PeerId(*b"-TR4000-000000000001")).is_err());
+                }
+
+                #[test]
+                fn it_should_authorize_all_the_clients_when_the_client_whitelist_is_empty() {
+                    let tracker = private_tracker();
+
+                    assert!(tracker.authorize_client(&PeerId(*b"-TR4000-000000000001")).is_ok());
+                }
+
+                #[test]
+                fn it_should_not_enforce_the_client_whitelist_in_public_mode() {
+                    let tracker = public_tracker();
+
+                    tracker.add_client_to_whitelist("-qB45");
+
+                    assert!(tracker.authorize_client(&PeerId(*b"-TR4000-000000000001")).is_ok());
+                }
+            }
+
             mod handling_an_announce_request {}
 
             mod handling_an_scrape_request {}
@@ -2179,13 +4473,390 @@ mod tests {
             mod handling_an_scrape_request {}
         }
 
-        mod handling_torrent_persistence {
+        mod applying_the_announce_interval_jitter {
+
+            use torrust_tracker_configuration::AnnouncePolicy;
+
+            use crate::core::apply_announce_interval_jitter;
+
+            #[test]
+            fn it_should_not_change_the_announce_policy_when_the_jitter_is_disabled() {
+                let policy = AnnouncePolicy::new(120, 60);
+
+                assert_eq!(apply_announce_interval_jitter(policy, 0), policy);
+            }
+
+            #[test]
+            fn it_should_keep_the_interval_within_the_jitter_band() {
+                let policy = AnnouncePolicy::new(1000, 60);
+
+                for _ in 0..100 {
+                    let jittered = apply_announce_interval_jitter(policy, 10);
+
+                    assert!((900..=1100).contains(&jittered.interval));
+                    assert_eq!(jittered.interval_min, 60);
+                }
+            }
+
+            #[test]
+            fn it_should_never_return_an_interval_lower_than_the_minimum_interval() {
+                let policy = AnnouncePolicy::new(120, 120);
+
+                for _ in 0..100 {
+                    assert!(apply_announce_interval_jitter(policy, 50).interval >= 120);
+                }
+            }
+        }
+
+        mod computing_the_peer_timeout_extension {
+
+            use std::time::Duration;
+
+            use crate::core::peer_timeout_extension;
+
+            #[test]
+            fn it_should_not_extend_the_timeout_when_it_is_disabled() {
+                assert_eq!(peer_timeout_extension(3600, 0, 900), Duration::ZERO);
+            }
+
+            #[test]
+            fn it_should_not_extend_the_timeout_when_the_intervals_fit_in_the_max_peer_timeout() {
+                assert_eq!(peer_timeout_extension(120, 3, 900), Duration::ZERO);
+            }
+
+            #[test]
+            fn it_should_extend_the_timeout_to_cover_the_announce_intervals() {
+                assert_eq!(peer_timeout_extension(1800, 2, 900), Duration::from_secs(2700));
+            }
+        }
+
+        mod with_dynamic_intervals {
+
+            use torrust_tracker_configuration::AnnouncePolicy;
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::core::services::tracker_factory;
+            use crate::core::tests::the_tracker::sample_info_hash;
+
+            #[test]
+            fn it_should_lower_the_announce_intervals_of_the_swarms_smaller_than_the_reference() {
+                let mut configuration = configuration::ephemeral_public();
+                configuration.core.announce_policy = AnnouncePolicy::new(120, 120);
+                configuration.core.dynamic_interval.reference_swarm_size = Some(4);
+                let tracker = tracker_factory(&configuration);
+
+                assert_eq!(
+                    tracker.get_announce_policy_for(&sample_info_hash()),
+                    AnnouncePolicy::new(60, 60)
+                );
+            }
+        }
+
+        mod with_an_experiment {
+
+            use torrust_tracker_configuration::{AnnouncePolicy, Experiment};
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::core::experiment::{Group, GroupStats};
+            use crate::core::services::tracker_factory;
+            use crate::core::tests::the_tracker::{peer_ip, sample_info_hash, sample_peer};
+            use crate::core::PeersWanted;
+
+            #[tokio::test]
+            async fn it_should_serve_the_experiment_group_with_the_announce_intervals_of_the_experiment() {
+                let mut configuration = configuration::ephemeral_public();
+                configuration.core.announce_policy = AnnouncePolicy::new(120, 120);
+                configuration.core.experiment = Some(Experiment {
+                    percentage: 100,
+                    announce_interval: Some(1800),
+                    announce_interval_min: Some(900),
+                    ..Default::default()
+                });
+                let tracker = tracker_factory(&configuration);
+
+                let mut peer = sample_peer();
+
+                let announce_data = tracker
+                    .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All)
+                    .await;
+
+                assert_eq!(tracker.get_experiment_group(&peer_ip()), Group::Experiment);
+                assert_eq!(announce_data.policy, AnnouncePolicy::new(1800, 900));
+                assert_eq!(
+                    tracker.get_announce_policy_for(&sample_info_hash()),
+                    AnnouncePolicy::new(120, 120)
+                );
+                assert_eq!(
+                    tracker.get_experiment_stats().unwrap().experiment,
+                    GroupStats {
+                        announces: 1,
+                        completed: 1,
+                        peers_returned: 0
+                    }
+                );
+            }
+        }
+
+        mod during_a_maintenance_window {
+
+            use std::str::FromStr;
+
+            use chrono::{DateTime, Utc};
+            use torrust_tracker_configuration::v2_0_0::maintenance::MaintenanceWindow;
+            use torrust_tracker_configuration::AnnouncePolicy;
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::core::services::tracker_factory;
+            use crate::core::statistics::RejectReason;
+            use crate::core::tests::the_tracker::{public_tracker, sample_info_hash};
+            use crate::core::Tracker;
+
+            /// The clock is stopped at the Unix Epoch in tests.
+            fn tracker_under_maintenance() -> Tracker {
+                let mut configuration = configuration::ephemeral_public();
+                configuration.core.announce_policy = AnnouncePolicy::new(120, 120);
+                configuration.core.maintenance.announce_interval = 1800;
+                configuration.core.maintenance.retry_in = 10;
+                configuration.core.maintenance.windows = vec![MaintenanceWindow {
+                    start: DateTime::<Utc>::from_str("1970-01-01T00:00:00Z").unwrap(),
+                    end: DateTime::<Utc>::from_str("1970-01-02T00:00:00Z").unwrap(),
+                }];
+                tracker_factory(&configuration)
+            }
+
+            #[test]
+            fn it_should_not_be_under_maintenance_when_there_are_no_maintenance_windows() {
+                let tracker = public_tracker();
+
+                assert!(!tracker.is_under_maintenance());
+                assert!(!tracker.should_pause_jobs());
+                assert_eq!(tracker.get_retry_in(), None);
+            }
+
+            #[test]
+            fn it_should_raise_the_announce_intervals() {
+                let tracker = tracker_under_maintenance();
+
+                assert_eq!(
+                    tracker.get_announce_policy_for(&sample_info_hash()),
+                    AnnouncePolicy::new(1800, 1800)
+                );
+            }
+
+            #[test]
+            fn it_should_pause_the_nonessential_jobs() {
+                let tracker = tracker_under_maintenance();
+
+                assert!(tracker.should_pause_jobs());
+            }
+
+            #[test]
+            fn it_should_advertise_when_clients_should_retry_failed_requests() {
+                let tracker = tracker_under_maintenance();
+
+                assert_eq!(tracker.get_retry_in(), Some(10));
+            }
+
+            #[test]
+            fn it_should_ask_clients_to_retry_rejected_announces_after_the_maintenance_retry_interval() {
+                let tracker = tracker_under_maintenance();
+
+                assert_eq!(tracker.get_retry_in_after(RejectReason::InvalidKey), Some(10));
+            }
+        }
+
+        mod handling_rejected_announces {
+
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::core::failure_messages;
+            use crate::core::services::tracker_factory;
+            use crate::core::statistics::RejectReason;
+            use crate::core::tests::the_tracker::public_tracker;
+
+            #[test]
+            fn it_should_ask_clients_to_retry_later_when_the_key_is_invalid_or_the_torrent_is_not_registered() {
+                let tracker = public_tracker();
+
+                assert_eq!(tracker.get_retry_in_after(RejectReason::InvalidKey), Some(60));
+                assert_eq!(tracker.get_retry_in_after(RejectReason::NotWhitelisted), Some(60));
+                assert_eq!(tracker.get_retry_in_after(RejectReason::Blacklisted), Some(60));
+            }
+
+            #[test]
+            fn it_should_not_ask_clients_to_retry_later_for_the_other_rejection_reasons() {
+                let tracker = public_tracker();
+
+                assert_eq!(tracker.get_retry_in_after(RejectReason::ParseError), None);
+            }
+
+            #[test]
+            fn it_should_not_ask_clients_to_retry_later_when_the_retry_interval_is_zero() {
+                let mut configuration = configuration::ephemeral_public();
+                configuration.core.failure_retry.invalid_key = 0;
+                let tracker = tracker_factory(&configuration);
+
+                assert_eq!(tracker.get_retry_in_after(RejectReason::InvalidKey), None);
+            }
+
+            #[test]
+            fn it_should_use_the_failure_message_template_configured_for_the_rejection_reason() {
+                let mut configuration = configuration::ephemeral_public();
+                configuration.core.failure_messages.invalid_key = Some("{reason}, retry in {retry_in} minutes".to_string());
+                let tracker = tracker_factory(&configuration);
+
+                let context = failure_messages::Context {
+                    retry_in: Some(60),
+                    ..Default::default()
+                };
+
+                assert_eq!(
+                    tracker.get_failure_message(RejectReason::InvalidKey, "invalid key".to_string(), &context),
+                    "invalid key, retry in 60 minutes"
+                );
+                assert_eq!(
+                    tracker.get_failure_message(RejectReason::BannedIp, "banned ip".to_string(), &context),
+                    "banned ip"
+                );
+            }
+        }
+
+        mod while_overloaded {
+
+            use std::time::Duration;
+
+            use torrust_tracker_configuration::AnnouncePolicy;
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::core::error::Error;
+            use crate::core::services::tracker_factory;
+            use crate::core::tests::the_tracker::{public_tracker, sample_info_hash};
+            use crate::core::Tracker;
+
+            fn overloaded_tracker() -> Tracker {
+                let mut configuration = configuration::ephemeral_public();
+                configuration.core.announce_policy = AnnouncePolicy::new(120, 120);
+                configuration.core.overload.max_event_loop_lag = Some(100);
+                configuration.core.overload.announce_interval = 900;
+                configuration.core.overload.retry_in = 2;
+                let tracker = tracker_factory(&configuration);
+                tracker.sample_load(Duration::from_secs(1), Duration::from_millis(200));
+                tracker
+            }
+
+            #[test]
+            fn it_should_not_be_overloaded_when_the_overload_control_is_disabled() {
+                let tracker = public_tracker();
+
+                tracker.sample_load(Duration::from_secs(1), Duration::from_secs(60));
+
+                assert!(!tracker.is_overloaded());
+                assert!(tracker.check_overload().is_ok());
+            }
+
+            #[test]
+            fn it_should_raise_the_announce_intervals() {
+                let tracker = overloaded_tracker();
+
+                assert_eq!(
+                    tracker.get_announce_policy_for(&sample_info_hash()),
+                    AnnouncePolicy::new(900, 900)
+                );
+            }
+
+            #[test]
+            fn it_should_reject_the_low_priority_requests() {
+                let tracker = overloaded_tracker();
+
+                assert!(matches!(
+                    tracker.check_overload(),
+                    Err(Error::TrackerOverloaded { retry_in: 2, .. })
+                ));
+                assert_eq!(tracker.get_retry_in(), Some(2));
+            }
+        }
+
+        mod handling_tracker_ids {
 
             use aquatic_udp_protocol::AnnounceEvent;
+            use torrust_tracker_configuration::TrackerIdScheme;
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::core::services::tracker_factory;
+            use crate::core::tests::the_tracker::{peer_ip, public_tracker, sample_info_hash, sample_peer};
+            use crate::core::tracker_ids::Check;
+            use crate::core::{PeersWanted, Tracker};
+
+            fn tracker_issuing_tracker_ids_per_peer() -> Tracker {
+                let mut configuration = configuration::ephemeral_public();
+                configuration.core.tracker_id_scheme = TrackerIdScheme::PerPeer;
+                tracker_factory(&configuration)
+            }
+
+            #[test]
+            fn it_should_not_check_the_tracker_id_when_it_does_not_issue_tracker_ids() {
+                let tracker = public_tracker();
+
+                let peer = sample_peer();
+
+                assert_eq!(
+                    tracker.check_tracker_id(&sample_info_hash(), &peer.peer_id, AnnounceEvent::Completed, None),
+                    None
+                );
+            }
+
+            #[tokio::test]
+            async fn it_should_accept_the_tracker_id_issued_in_a_previous_announce() {
+                let tracker = tracker_issuing_tracker_ids_per_peer();
+
+                let mut peer = sample_peer();
+
+                let announce_data = tracker
+                    .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::All)
+                    .await;
+                let tracker_id = announce_data.tracker_id.expect("it should issue a tracker id");
+
+                assert_eq!(
+                    tracker.check_tracker_id(&sample_info_hash(), &peer.peer_id, AnnounceEvent::None, Some(&tracker_id)),
+                    Some(Check::Valid)
+                );
+                assert_eq!(
+                    tracker.check_tracker_id(&sample_info_hash(), &peer.peer_id, AnnounceEvent::None, Some("unknown")),
+                    Some(Check::Invalid)
+                );
+            }
+
+            #[test]
+            fn it_should_detect_the_clients_ignoring_the_tracker_id_after_the_first_announce() {
+                let tracker = tracker_issuing_tracker_ids_per_peer();
+
+                let peer = sample_peer();
+
+                assert_eq!(
+                    tracker.check_tracker_id(&sample_info_hash(), &peer.peer_id, AnnounceEvent::Started, None),
+                    None
+                );
+                assert_eq!(
+                    tracker.check_tracker_id(&sample_info_hash(), &peer.peer_id, AnnounceEvent::None, None),
+                    Some(Check::Missing)
+                );
+            }
+        }
+
+        mod handling_torrent_persistence {
+
+            use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes};
+            use torrust_tracker_clock::clock::Time;
+            use torrust_tracker_test_helpers::configuration;
             use torrust_tracker_torrent_repository::entry::EntrySync;
             use torrust_tracker_torrent_repository::repository::Repository;
 
-            use crate::core::tests::the_tracker::{sample_info_hash, sample_peer, tracker_persisting_torrents_in_database};
+            use crate::core::services::tracker_factory;
+            use crate::core::tests::the_tracker::{
+                sample_info_hash, sample_peer, tracker_persisting_torrent_history, tracker_persisting_torrents_in_database,
+            };
+            use crate::core::torrent_history::{Bucket, Granularity};
+            use crate::CurrentClock;
 
             #[tokio::test]
             async fn it_should_persist_the_number_of_completed_peers_for_all_torrents_into_the_database() {
@@ -2196,17 +4867,17 @@ mod tests {
                 let mut peer = sample_peer();
 
                 peer.event = AnnounceEvent::Started;
-                let swarm_stats = tracker.upsert_peer_and_get_stats(&info_hash, &peer);
+                let swarm_stats = tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
                 assert_eq!(swarm_stats.downloaded, 0);
 
                 peer.event = AnnounceEvent::Completed;
-                let swarm_stats = tracker.upsert_peer_and_get_stats(&info_hash, &peer);
+                let swarm_stats = tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
                 assert_eq!(swarm_stats.downloaded, 1);
 
                 // Remove the newly updated torrent from memory
                 tracker.torrents.remove(&info_hash);
 
-                tracker.load_torrents_from_database().unwrap();
+                tracker.load_torrents_from_database().await.unwrap();
 
                 let torrent_entry = tracker.torrents.get(&info_hash).expect("it should be able to get entry");
 
@@ -2216,6 +4887,310 @@ mod tests {
                 // It does not persist the peers
                 assert!(torrent_entry.peers_is_empty());
             }
+
+            #[tokio::test]
+            async fn it_should_batch_the_writes_of_the_number_of_completed_peers_when_they_are_buffered() {
+                let mut configuration = configuration::ephemeral();
+                configuration.core.tracker_policy.persistent_torrent_completed_stat = true;
+                configuration.core.database.completed_stat_flush_interval = 5;
+                let tracker = tracker_factory(&configuration);
+
+                let info_hash = sample_info_hash();
+
+                let mut peer = sample_peer();
+
+                peer.event = AnnounceEvent::Started;
+                tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
+
+                peer.event = AnnounceEvent::Completed;
+                tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
+
+                // It's not written until the buffer is flushed
+                assert!(tracker.database.load_persistent_torrents().await.unwrap().is_empty());
+
+                assert_eq!(tracker.flush_completed_stats().await.unwrap(), 1);
+                assert_eq!(tracker.flush_completed_stats().await.unwrap(), 0);
+
+                assert_eq!(
+                    tracker.database.load_persistent_torrents().await.unwrap().get(&info_hash),
+                    Some(&1)
+                );
+            }
+
+            #[tokio::test]
+            async fn it_should_persist_the_stats_of_all_torrents_on_demand() {
+                let tracker = tracker_persisting_torrents_in_database();
+
+                let info_hash = sample_info_hash();
+
+                let mut peer = sample_peer();
+
+                peer.event = AnnounceEvent::Completed;
+                tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
+
+                tracker.persist_all_stats().await.unwrap();
+
+                tracker.torrents.remove(&info_hash);
+
+                tracker.load_torrents_from_database().await.unwrap();
+
+                let torrent_entry = tracker.torrents.get(&info_hash).expect("it should be able to get entry");
+
+                assert_eq!(torrent_entry.get_swarm_metadata().downloaded, 1);
+            }
+
+            #[tokio::test]
+            async fn it_should_remove_a_torrent_and_its_persisted_stats() {
+                let tracker = tracker_persisting_torrents_in_database();
+
+                let info_hash = sample_info_hash();
+
+                let mut peer = sample_peer();
+
+                peer.event = AnnounceEvent::Completed;
+                tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
+
+                assert!(tracker.remove_torrent(&info_hash, true).await.unwrap());
+                assert!(tracker.torrents.get(&info_hash).is_none());
+
+                tracker.load_torrents_from_database().await.unwrap();
+
+                assert!(tracker.torrents.get(&info_hash).is_none());
+                assert!(!tracker.remove_torrent(&info_hash, true).await.unwrap());
+            }
+
+            #[tokio::test]
+            async fn it_should_remove_all_the_peers_of_a_torrent_keeping_its_stats() {
+                let tracker = tracker_persisting_torrents_in_database();
+
+                let info_hash = sample_info_hash();
+
+                let mut peer = sample_peer();
+
+                peer.event = AnnounceEvent::Completed;
+                tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
+
+                assert_eq!(tracker.remove_torrent_peers(&info_hash), Some(1));
+
+                let torrent_entry = tracker.torrents.get(&info_hash).expect("it should be able to get entry");
+
+                assert!(torrent_entry.peers_is_empty());
+                assert_eq!(torrent_entry.get_swarm_metadata().downloaded, 1);
+            }
+
+            #[tokio::test]
+            async fn it_should_keep_the_hourly_history_of_the_torrent() {
+                let tracker = tracker_persisting_torrent_history();
+
+                let info_hash = sample_info_hash();
+
+                let mut peer = sample_peer();
+
+                peer.event = AnnounceEvent::Started;
+                peer.left = NumberOfBytes::new(1000);
+                tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
+
+                peer.event = AnnounceEvent::Completed;
+                peer.left = NumberOfBytes::new(0);
+                tracker.upsert_peer_and_get_stats(&info_hash, &peer).await;
+
+                let now = CurrentClock::now();
+
+                let expected_history = vec![Bucket {
+                    start: Granularity::Hour.period_start(now),
+                    completed: 1,
+                    peak_seeders: 1,
+                    peak_leechers: 1,
+                }];
+
+                // The counters of the current hour are kept in memory
+                assert_eq!(
+                    tracker
+                        .get_torrent_history(&info_hash, now, now, Granularity::Hour)
+                        .await
+                        .unwrap(),
+                    expected_history
+                );
+
+                tracker.persist_all_stats().await.unwrap();
+
+                assert_eq!(
+                    tracker
+                        .get_torrent_history(&info_hash, now, now, Granularity::Hour)
+                        .await
+                        .unwrap(),
+                    expected_history
+                );
+            }
+        }
+
+        mod handling_database_migrations {
+
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::core::databases::driver::{self, Driver};
+            use crate::core::databases::error::Error;
+            use crate::core::databases::migrations;
+            use crate::core::error::BootstrapError;
+            use crate::core::services::statistics;
+            use crate::core::tests::the_tracker::public_tracker;
+            use crate::core::{auth, Tracker};
+
+            #[test]
+            fn it_should_apply_all_the_migrations_when_the_tracker_is_built() {
+                let tracker = public_tracker();
+
+                assert_eq!(
+                    tracker.database.schema_version().unwrap(),
+                    migrations::latest_version(migrations::SQLITE)
+                );
+                assert!(tracker.database.migrate().unwrap().is_empty());
+            }
+
+            #[test]
+            fn it_should_refuse_a_database_with_pending_migrations_when_the_automatic_migrations_are_disabled() {
+                let mut configuration = configuration::ephemeral();
+                configuration.core.database.auto_migrate = false;
+
+                let (stats_event_sender, stats_repository) = statistics::setup::factory(false);
+
+                let result = Tracker::new(&configuration.core, stats_event_sender, stats_repository);
+
+                assert!(matches!(
+                    result,
+                    Err(BootstrapError::Database {
+                        source: Error::PendingMigrations { version: 0, .. }
+                    })
+                ));
+
+                let database = &configuration.core.database;
+
+                driver::connect(
+                    &Driver::Sqlite3,
+                    &database.path,
+                    &database.pool,
+                    database.table_prefix(),
+                    None,
+                )
+                .unwrap()
+                .migrate()
+                .unwrap();
+
+                let (stats_event_sender, stats_repository) = statistics::setup::factory(false);
+
+                assert!(Tracker::new(&configuration.core, stats_event_sender, stats_repository).is_ok());
+            }
+
+            #[tokio::test]
+            async fn it_should_allow_permanent_keys_in_the_databases_created_before_they_were_introduced() {
+                let configuration = configuration::ephemeral();
+                let database = &configuration.core.database;
+
+                // The `keys` table before the permanent keys
+                r2d2_sqlite::rusqlite::Connection::open(&database.path)
+                    .unwrap()
+                    .execute(
+                        "CREATE TABLE keys (id INTEGER PRIMARY KEY AUTOINCREMENT, key TEXT NOT NULL UNIQUE, \
+                         valid_until INTEGER NOT NULL)",
+                        [],
+                    )
+                    .unwrap();
+
+                let database = driver::connect(
+                    &Driver::Sqlite3,
+                    &database.path,
+                    &database.pool,
+                    database.table_prefix(),
+                    None,
+                )
+                .unwrap();
+
+                database.migrate().unwrap();
+
+                let permanent_key = auth::generate_permanent_key();
+
+                database.add_key_to_keys(&permanent_key).await.unwrap();
+
+                let stored_key = database.get_key_from_keys(&permanent_key.key).await.unwrap().unwrap();
+
+                assert_eq!(stored_key.valid_until, None);
+            }
+        }
+
+        mod handling_the_peer_diversity {
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::core::error::BootstrapError;
+            use crate::core::services::statistics;
+            use crate::core::Tracker;
+
+            #[test]
+            fn it_should_fail_building_the_tracker_when_the_asn_database_cannot_be_loaded() {
+                let mut configuration = configuration::ephemeral();
+                configuration.core.peer_diversity.asn_database_path = Some("./missing-asn-database.tsv".into());
+
+                let (stats_event_sender, stats_repository) = statistics::setup::factory(false);
+
+                let result = Tracker::new(&configuration.core, stats_event_sender, stats_repository);
+
+                assert!(matches!(result, Err(BootstrapError::AsnDatabase { .. })));
+            }
+        }
+
+        mod handling_the_audit_log {
+            use std::net::{IpAddr, Ipv4Addr};
+
+            use torrust_tracker_primitives::pagination::Pagination;
+
+            use crate::core::audit::{Entry, Filter};
+            use crate::core::tests::the_tracker::public_tracker;
+
+            fn sample_entry(actor: &str, method: &str, path: &str) -> Entry {
+                Entry {
+                    timestamp: 1_700_000_000,
+                    actor: actor.to_string(),
+                    client_ip: Some(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1))),
+                    method: method.to_string(),
+                    path: path.to_string(),
+                    status: 200,
+                    payload: None,
+                }
+            }
+
+            #[tokio::test]
+            async fn it_should_return_the_audit_entries_matching_the_filter_the_most_recent_first() {
+                let tracker = public_tracker();
+
+                let whitelist = sample_entry("admin", "POST", "/api/v1/whitelist/3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0");
+                let key = sample_entry("issuer", "POST", "/api/v1/key/60");
+                let delete = sample_entry(
+                    "admin",
+                    "DELETE",
+                    "/api/v1/whitelist/3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0",
+                );
+
+                tracker.record_audit_entry(&whitelist).await.unwrap();
+                tracker.record_audit_entry(&key).await.unwrap();
+                tracker.record_audit_entry(&delete).await.unwrap();
+
+                let pagination = Pagination::default();
+
+                assert_eq!(
+                    tracker.get_audit_entries(&Filter::default(), &pagination).await.unwrap(),
+                    vec![delete.clone(), key, whitelist.clone()]
+                );
+
+                let filter = Filter {
+                    actor: Some("admin".to_string()),
+                    path_prefix: Some("/api/v1/whitelist".to_string()),
+                    ..Filter::default()
+                };
+
+                assert_eq!(
+                    tracker.get_audit_entries(&filter, &pagination).await.unwrap(),
+                    vec![delete, whitelist]
+                );
+            }
         }
     }
 }
@@ -0,0 +1,285 @@
+//! Diversity constraints for the peer lists returned in announce responses.
+//!
+//! When the tracker selects the peers for an announce response it can limit
+//! the number of peers from the same subnet and from the same autonomous
+//! system (ASN). That prevents responses dominated by a single network, like
+//! a seed farm hosted in one provider.
+//!
+//! Refer to the [`PeerDiversity`] configuration for more information about
+//! the constraints and the ASN database format.
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::{fs, io};
+
+use camino::Utf8Path;
+use torrust_tracker_configuration::cidr::Cidr;
+use torrust_tracker_configuration::PeerDiversity;
+use torrust_tracker_primitives::peer;
+
+/// Autonomous system number.
+pub type Asn = u32;
+
+/// In-memory database to find the autonomous system of an IP address.
+#[derive(Debug, Default)]
+pub struct AsnDatabase {
+    /// IP ranges indexed by their first address. The value contains the last
+    /// address in the range and the ASN.
+    ranges: BTreeMap<u128, (u128, Asn)>,
+}
+
+impl AsnDatabase {
+    /// It loads the database from a file.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can't be read or if any of the lines
+    /// is not valid.
+    pub fn load(path: &Utf8Path) -> Result<Self, io::Error> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// It returns the ASN of the IP address, if it's in the database.
+    #[must_use]
+    pub fn lookup(&self, ip: &IpAddr) -> Option<Asn> {
+        let ip = to_u128(ip);
+
+        match self.ranges.range(..=ip).next_back() {
+            Some((_, (last, asn))) if ip <= *last => Some(*asn),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for AsnDatabase {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_line = |line: &str| io::Error::new(io::ErrorKind::InvalidData, format!("invalid ASN database line: {line}"));
+
+        let mut ranges = BTreeMap::new();
+
+        for line in s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            let mut fields = line.split_whitespace();
+
+            let (Some(range), Some(asn), None) = (fields.next(), fields.next(), fields.next()) else {
+                return Err(invalid_line(line));
+            };
+
+            let range = Cidr::from_str(range).map_err(|_| invalid_line(line))?;
+            let asn = asn.trim_start_matches("AS").parse::<Asn>().map_err(|_| invalid_line(line))?;
+
+            let (first, last) = bounds(&range);
+
+            ranges.insert(first, (last, asn));
+        }
+
+        Ok(Self { ranges })
+    }
+}
+
+/// It maps IP addresses to IPv6 addresses (as integers), so IPv4 and IPv6
+/// ranges can be stored in the same index.
+fn to_u128(ip: &IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(ipv4) => u128::from(ipv4.to_ipv6_mapped()),
+        IpAddr::V6(ipv6) => ipv6
+            .to_ipv4_mapped()
+            .map_or(u128::from(*ipv6), |ipv4| u128::from(ipv4.to_ipv6_mapped())),
+    }
+}
+
+/// It returns the first and last addresses in the range.
+fn bounds(range: &Cidr) -> (u128, u128) {
+    let host_bits = match range.network() {
+        IpAddr::V4(_) => 32 - u32::from(range.prefix_len()),
+        IpAddr::V6(_) => 128 - u32::from(range.prefix_len()),
+    };
+
+    let first = to_u128(&range.network());
+    let last = first | u128::MAX.checked_shr(128 - host_bits).unwrap_or(0);
+
+    (first, last)
+}
+
+/// The subnet a peer belongs to: the IP address with the host bits cleared.
+fn subnet(ip: &IpAddr, config: &PeerDiversity) -> IpAddr {
+    let prefix_len = match ip {
+        IpAddr::V4(_) => config.ipv4_subnet_prefix_len,
+        IpAddr::V6(_) => config.ipv6_subnet_prefix_len,
+    };
+
+    Cidr::new(*ip, prefix_len).map_or(*ip, |cidr| cidr.network())
+}
+
+/// Number of candidates examined for each peer in the response. Only the
+/// first candidates of large swarms are examined, so the announces for them
+/// don't go through all the peers.
+const CANDIDATES_PER_PEER: usize = 4;
+
+/// Maximum number of candidates examined to select up to `limit` peers.
+#[must_use]
+pub fn max_candidates(limit: usize) -> usize {
+    limit.saturating_mul(CANDIDATES_PER_PEER)
+}
+
+/// It selects up to `limit` peers from the candidates, skipping the peers
+/// that would break the diversity constraints. Only the first
+/// [`max_candidates`] candidates are examined.
+///
+/// The candidates order is preserved.
+#[must_use]
+pub fn select_peers(
+    candidates: Vec<Arc<peer::Peer>>,
+    limit: usize,
+    config: &PeerDiversity,
+    asn_database: Option<&AsnDatabase>,
+) -> Vec<Arc<peer::Peer>> {
+    let max_per_subnet = config
+        .max_peers_per_subnet
+        .map(|max| usize::try_from(max).unwrap_or(usize::MAX));
+    let max_per_asn = config.max_peers_per_asn.map(|max| usize::try_from(max).unwrap_or(usize::MAX));
+
+    let mut peers_per_subnet: HashMap<IpAddr, usize> = HashMap::new();
+    let mut peers_per_asn: HashMap<Asn, usize> = HashMap::new();
+
+    let mut selected = vec![];
+
+    for candidate in candidates.into_iter().take(max_candidates(limit)) {
+        if selected.len() >= limit {
+            break;
+        }
+
+        let ip = candidate.peer_addr.ip();
+
+        let subnet = subnet(&ip, config);
+        if max_per_subnet.is_some_and(|max| peers_per_subnet.get(&subnet).copied().unwrap_or_default() >= max) {
+            continue;
+        }
+
+        let asn = asn_database.and_then(|database| database.lookup(&ip));
+        if let (Some(max), Some(asn)) = (max_per_asn, asn) {
+            if peers_per_asn.get(&asn).copied().unwrap_or_default() >= max {
+                continue;
+            }
+        }
+
+        *peers_per_subnet.entry(subnet).or_default() += 1;
+        if let Some(asn) = asn {
+            *peers_per_asn.entry(asn).or_default() += 1;
+        }
+
+        selected.push(candidate);
+    }
+
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, SocketAddr};
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use torrust_tracker_configuration::PeerDiversity;
+    use torrust_tracker_primitives::peer;
+    use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+
+    use super::{select_peers, AsnDatabase};
+
+    fn ip(ip: &str) -> IpAddr {
+        IpAddr::from_str(ip).unwrap()
+    }
+
+    fn peers(ips: &[&str]) -> Vec<Arc<peer::Peer>> {
+        ips.iter()
+            .enumerate()
+            .map(|(i, ip)| {
+                Arc::new(
+                    PeerBuilder::default()
+                        .with_peer_addr(&SocketAddr::new(
+                            IpAddr::from_str(ip).unwrap(),
+                            6881 + u16::try_from(i).unwrap(),
+                        ))
+                        .build(),
+                )
+            })
+            .collect()
+    }
+
+    fn selected_ips(peers: &[Arc<peer::Peer>]) -> Vec<String> {
+        peers.iter().map(|peer| peer.peer_addr.ip().to_string()).collect()
+    }
+
+    #[test]
+    fn it_should_find_the_asn_of_an_ip_address() {
+        let database = AsnDatabase::from_str("# comment\n1.0.0.0/24 13335\n2001:db8::/32 AS64496\n").unwrap();
+
+        assert_eq!(database.lookup(&ip("1.0.0.1")), Some(13335));
+        assert_eq!(database.lookup(&ip("::ffff:1.0.0.1")), Some(13335));
+        assert_eq!(database.lookup(&ip("2001:db8:1::1")), Some(64496));
+        assert_eq!(database.lookup(&ip("1.0.1.1")), None);
+    }
+
+    #[test]
+    fn it_should_fail_loading_an_invalid_asn_database() {
+        assert!(AsnDatabase::from_str("1.0.0.0/24").is_err());
+        assert!(AsnDatabase::from_str("1.0.0.0/24 ASX").is_err());
+    }
+
+    #[test]
+    fn it_should_not_filter_the_peers_when_there_are_no_constraints() {
+        let candidates = peers(&["10.0.0.1", "10.0.0.2", "10.0.0.3"]);
+
+        let selected = select_peers(candidates, 2, &PeerDiversity::default(), None);
+
+        assert_eq!(selected_ips(&selected), vec!["10.0.0.1", "10.0.0.2"]);
+    }
+
+    #[test]
+    fn it_should_limit_the_number_of_peers_from_the_same_subnet() {
+        let candidates = peers(&["10.0.0.1", "10.0.0.2", "10.0.0.3", "10.0.1.1"]);
+        let config = PeerDiversity {
+            max_peers_per_subnet: Some(2),
+            ..Default::default()
+        };
+
+        let selected = select_peers(candidates, 10, &config, None);
+
+        assert_eq!(selected_ips(&selected), vec!["10.0.0.1", "10.0.0.2", "10.0.1.1"]);
+    }
+
+    #[test]
+    fn it_should_limit_the_number_of_peers_from_the_same_autonomous_system() {
+        let candidates = peers(&["1.0.0.1", "1.0.1.1", "1.0.2.1", "2.0.0.1"]);
+        let database = AsnDatabase::from_str("1.0.0.0/16 13335\n").unwrap();
+        let config = PeerDiversity {
+            max_peers_per_asn: Some(1),
+            ..Default::default()
+        };
+
+        let selected = select_peers(candidates, 10, &config, Some(&database));
+
+        assert_eq!(selected_ips(&selected), vec!["1.0.0.1", "2.0.0.1"]);
+    }
+
+    #[test]
+    fn it_should_only_examine_a_bounded_number_of_candidates() {
+        let mut ips = vec!["10.0.0.1"; 8];
+        ips.push("10.0.1.1");
+        let candidates = peers(&ips);
+        let config = PeerDiversity {
+            max_peers_per_subnet: Some(1),
+            ..Default::default()
+        };
+
+        let selected = select_peers(candidates, 2, &config, None);
+
+        assert_eq!(selected_ips(&selected), vec!["10.0.0.1"]);
+    }
+}
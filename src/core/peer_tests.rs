@@ -1,6 +1,7 @@
 #![cfg(test)]
 
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
 
 use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
 use torrust_tracker_clock::clock::stopped::Stopped as _;
@@ -21,6 +22,8 @@ fn it_should_be_serializable() {
         downloaded: NumberOfBytes::new(0),
         left: NumberOfBytes::new(0),
         event: AnnounceEvent::Started,
+        timeout_extension: Duration::ZERO,
+        alt_peer_addr: None,
     };
 
     let raw_json = serde_json::to_string(&torrent_peer).unwrap();
@@ -0,0 +1,229 @@
+//! Cache of the `scrape` data.
+//!
+//! Index sites poll the tracker every few seconds to scrape the same popular
+//! torrents. The cache keeps the swarm metadata of each torrent (and its
+//! serialized form for the HTTP tracker), so those requests neither read the
+//! torrent repository nor serialize the data again.
+//!
+//! There are two modes, depending on the `scrape_cache_ttl` option:
+//!
+//! - Without TTL (`0`, the default), only the serialized data of the HTTP
+//!   tracker is cached. An entry is invalidated when the swarm counters
+//!   (`complete`, `downloaded` and `incomplete`) change.
+//! - With TTL, the data of the UDP and HTTP trackers is cached and the entries
+//!   expire after the TTL, even if the counters change in the meantime. The
+//!   counters of popular torrents change with almost every announce, so it's
+//!   the only way to cache them.
+//!
+//! In both cases, the whole cache is cleared when the inactive peers are
+//! removed.
+//!
+//! Only torrents in the repository are cached, so the cache can't grow with
+//! arbitrary infohashes.
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+/// The cached data of a torrent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub swarm_metadata: SwarmMetadata,
+    /// The serialized data, once it has been requested by the HTTP tracker.
+    pub file: Option<Arc<[u8]>>,
+    /// When the entry expires, if the cache has a TTL.
+    expires_at: Option<DurationSinceUnixEpoch>,
+}
+
+/// Hits and misses of the cache lookups.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// The `scrape` data of each torrent.
+#[derive(Debug, Default)]
+pub struct ScrapeCache {
+    /// How long the entries are valid. Until the swarm counters change if
+    /// `None`.
+    ttl: Option<Duration>,
+    entries: DashMap<InfoHash, Entry>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ScrapeCache {
+    /// It builds the cache. The entries don't expire if the `ttl` is zero.
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl: (!ttl.is_zero()).then_some(ttl),
+            ..Default::default()
+        }
+    }
+
+    /// Returns `true` if the entries expire after a TTL, instead of being
+    /// invalidated when the swarm counters change.
+    #[must_use]
+    pub fn expires(&self) -> bool {
+        self.ttl.is_some()
+    }
+
+    /// It returns the cached data of the torrent, if any. Expired entries
+    /// are removed.
+    #[must_use]
+    pub fn get(&self, info_hash: &InfoHash, now: DurationSinceUnixEpoch) -> Option<Entry> {
+        self.entries.remove_if(info_hash, |_, entry| {
+            entry.expires_at.is_some_and(|expires_at| expires_at <= now)
+        });
+
+        let entry = self.entries.get(info_hash).map(|entry| entry.value().clone());
+
+        match entry {
+            Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+            None => self.misses.fetch_add(1, Ordering::Relaxed),
+        };
+
+        entry
+    }
+
+    /// It caches the swarm metadata of the torrent.
+    pub fn insert(&self, info_hash: &InfoHash, swarm_metadata: SwarmMetadata, now: DurationSinceUnixEpoch) {
+        self.entries.insert(
+            *info_hash,
+            Entry {
+                swarm_metadata,
+                file: None,
+                expires_at: self.ttl.map(|ttl| now + ttl),
+            },
+        );
+    }
+
+    /// It caches the serialized data of the torrent. The expiration of an
+    /// existing entry is kept.
+    pub fn insert_file(&self, info_hash: &InfoHash, swarm_metadata: SwarmMetadata, file: Arc<[u8]>, now: DurationSinceUnixEpoch) {
+        match self.entries.entry(*info_hash) {
+            dashmap::Entry::Occupied(mut entry) => entry.get_mut().file = Some(file),
+            dashmap::Entry::Vacant(entry) => {
+                entry.insert(Entry {
+                    swarm_metadata,
+                    file: Some(file),
+                    expires_at: self.ttl.map(|ttl| now + ttl),
+                });
+            }
+        }
+    }
+
+    /// It removes the cached data of the torrent. It must be called when the
+    /// swarm counters change, unless the entries expire.
+    pub fn invalidate(&self, info_hash: &InfoHash) {
+        self.entries.remove(info_hash);
+    }
+
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+
+    /// It returns the hits and misses of the lookups.
+    #[must_use]
+    pub fn stats(&self) -> Stats {
+        Stats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Number of cached torrents.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use torrust_tracker_primitives::info_hash::InfoHash;
+    use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
+
+    use super::{ScrapeCache, Stats};
+
+    fn sample_info_hash() -> InfoHash {
+        "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()
+    }
+
+    #[test]
+    fn it_should_return_the_cached_data_until_it_is_invalidated() {
+        let cache = ScrapeCache::default();
+        let info_hash = sample_info_hash();
+
+        cache.insert_file(
+            &info_hash,
+            SwarmMetadata::default(),
+            Arc::from(b"data".as_slice()),
+            Duration::ZERO,
+        );
+
+        assert_eq!(
+            cache.get(&info_hash, Duration::from_secs(100)).unwrap().file.as_deref(),
+            Some(b"data".as_slice())
+        );
+
+        cache.invalidate(&info_hash);
+
+        assert!(cache.get(&info_hash, Duration::from_secs(100)).is_none());
+    }
+
+    #[test]
+    fn it_should_return_the_cached_data_until_it_expires() {
+        let cache = ScrapeCache::new(Duration::from_secs(30));
+        let info_hash = sample_info_hash();
+
+        cache.insert(&info_hash, SwarmMetadata::default(), Duration::ZERO);
+
+        assert!(cache.get(&info_hash, Duration::from_secs(29)).is_some());
+        assert!(cache.get(&info_hash, Duration::from_secs(30)).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn it_should_keep_the_expiration_of_the_entry_when_the_serialized_data_is_cached() {
+        let cache = ScrapeCache::new(Duration::from_secs(30));
+        let info_hash = sample_info_hash();
+
+        cache.insert(&info_hash, SwarmMetadata::default(), Duration::ZERO);
+        cache.insert_file(
+            &info_hash,
+            SwarmMetadata::default(),
+            Arc::from(b"data".as_slice()),
+            Duration::from_secs(20),
+        );
+
+        assert!(cache.get(&info_hash, Duration::from_secs(30)).is_none());
+    }
+
+    #[test]
+    fn it_should_count_the_hits_and_misses() {
+        let cache = ScrapeCache::new(Duration::from_secs(30));
+        let info_hash = sample_info_hash();
+
+        let _ = cache.get(&info_hash, Duration::ZERO);
+        cache.insert(&info_hash, SwarmMetadata::default(), Duration::ZERO);
+        let _ = cache.get(&info_hash, Duration::ZERO);
+        let _ = cache.get(&info_hash, Duration::ZERO);
+
+        assert_eq!(cache.stats(), Stats { hits: 2, misses: 1 });
+    }
+}
@@ -0,0 +1,74 @@
+//! Abuse report services.
+//!
+//! There is only one service: [`report_abuse`]. It applies the configured
+//! mitigations for the report and notifies the configured webhooks.
+//!
+//! Refer to the [`abuse`](crate::core::abuse) module for more information
+//! about the mitigations.
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::{build_http_client, send_request};
+use crate::core::abuse::{Mitigation, Report};
+use crate::core::Tracker;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The payload sent to the webhooks.
+#[derive(Serialize, Debug)]
+pub struct Notification<'a> {
+    pub report: &'a Report,
+    pub mitigations: &'a [Mitigation],
+}
+
+/// It applies the configured mitigations for the report and returns the
+/// applied ones.
+///
+/// Webhooks are notified in the background. Notification errors are logged
+/// but they do not affect the result.
+///
+/// # Panics
+///
+/// Will panic if the notification for the webhooks can't be serialized to
+/// JSON.
+pub fn report_abuse(tracker: &Tracker, report: &Report) -> Vec<Mitigation> {
+    let mitigations = tracker.report_abuse(report);
+
+    let webhooks = tracker.get_abuse_webhooks();
+
+    if !webhooks.is_empty() {
+        let body = serde_json::to_string(&Notification {
+            report,
+            mitigations: &mitigations,
+        })
+        .expect("abuse notifications should be serializable to JSON");
+
+        tokio::spawn(notify_webhooks(webhooks, tracker.get_outbound_proxy(), body));
+    }
+
+    mitigations
+}
+
+async fn notify_webhooks(webhooks: Vec<String>, outbound_proxy: Option<String>, body: String) {
+    let client = match build_http_client(outbound_proxy.as_deref(), WEBHOOK_TIMEOUT) {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(%err, "unable to build the HTTP client for the abuse webhooks");
+            return;
+        }
+    };
+
+    for webhook in webhooks {
+        let request = client
+            .post(&webhook)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+
+        let result = send_request(request).await.and_then(reqwest::Response::error_for_status);
+
+        if let Err(err) = result {
+            tracing::warn!(%webhook, %err, "failed to notify abuse webhook");
+        }
+    }
+}
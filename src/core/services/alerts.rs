@@ -0,0 +1,205 @@
+//! Alert notification services.
+//!
+//! The [`Notifier`] sends the [`alerts`](crate::core::alerts) to the channels
+//! configured in the [`Alerts`](torrust_tracker_configuration::Alerts)
+//! configuration section:
+//!
+//! - Email, through an SMTP server.
+//! - Slack webhooks. Any service accepting Slack messages can be used, for
+//!   example, Mattermost or the Matrix hookshot bridge.
+//! - Matrix rooms, with the client-server API.
+//!
+//! Notification errors are logged. They are not retried.
+use std::time::Duration;
+
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde_json::json;
+use thiserror::Error;
+use torrust_tracker_configuration::{Alerts, AlertsSmtp, AlertsSmtpSecurity, AlertsWebhook, AlertsWebhookKind};
+use uuid::Uuid;
+
+use super::{build_http_client, send_request};
+use crate::core::alerts::Alert;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Errors that can occur building the notifier or sending the alerts.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid email address {address}: {source}")]
+    InvalidAddress {
+        address: String,
+        source: lettre::address::AddressError,
+    },
+
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+
+    #[error("unable to build the alert email: {0}")]
+    Email(#[from] lettre::error::Error),
+
+    #[error("webhook error: {0}")]
+    Webhook(#[from] reqwest::Error),
+}
+
+/// It sends the alerts to all the configured channels.
+pub struct Notifier {
+    email: Option<Email>,
+    webhooks: Vec<AlertsWebhook>,
+    outbound_proxy: Option<String>,
+}
+
+impl Notifier {
+    /// # Errors
+    ///
+    /// Will return an error if the email addresses are not valid or the SMTP
+    /// server is not valid.
+    pub fn new(config: &Alerts, outbound_proxy: Option<&str>) -> Result<Self, Error> {
+        Ok(Self {
+            email: config.smtp.as_ref().map(Email::new).transpose()?,
+            webhooks: config.webhooks.clone(),
+            outbound_proxy: outbound_proxy.map(ToString::to_string),
+        })
+    }
+
+    /// It sends the alert to all the channels. Errors are logged.
+    pub async fn notify(&self, alert: &Alert) {
+        tracing::warn!(subject = %alert.subject(), message = %alert.message(), "alert");
+
+        if let Some(email) = &self.email {
+            if let Err(err) = email.send(alert).await {
+                tracing::error!(%err, "failed to send the alert email");
+            }
+        }
+
+        if self.webhooks.is_empty() {
+            return;
+        }
+
+        let http_client = match build_http_client(self.outbound_proxy.as_deref(), WEBHOOK_TIMEOUT) {
+            Ok(http_client) => http_client,
+            Err(err) => {
+                tracing::error!(%err, "unable to build the HTTP client for the alert webhooks");
+                return;
+            }
+        };
+
+        for webhook in &self.webhooks {
+            if let Err(err) = post(&http_client, webhook, alert).await {
+                tracing::error!(%err, kind = ?webhook.kind, "failed to notify the alert webhook");
+            }
+        }
+    }
+}
+
+async fn post(http_client: &reqwest::Client, webhook: &AlertsWebhook, alert: &Alert) -> Result<(), Error> {
+    let request = match webhook.kind {
+        AlertsWebhookKind::Slack => http_client.post(&webhook.url).json(&json!({
+            "text": format!("*{}*\n{}", alert.subject(), alert.message())
+        })),
+        // Matrix requires a unique transaction ID for each message.
+        AlertsWebhookKind::Matrix => http_client
+            .put(format!("{}/{}", webhook.url.trim_end_matches('/'), Uuid::new_v4()))
+            .bearer_auth(webhook.token.as_deref().unwrap_or_default())
+            .json(&json!({
+                "msgtype": "m.text",
+                "body": format!("{}\n{}", alert.subject(), alert.message())
+            })),
+    };
+
+    send_request(request).await?.error_for_status()?;
+
+    Ok(())
+}
+
+/// Alert emails.
+struct Email {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl Email {
+    fn new(config: &AlertsSmtp) -> Result<Self, Error> {
+        let builder = match config.security {
+            AlertsSmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.server)?,
+            AlertsSmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.server)?,
+            AlertsSmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.server),
+        }
+        .port(config.port);
+
+        let builder = match (&config.username, &config.password) {
+            (Some(username), Some(password)) => builder.credentials(Credentials::new(username.clone(), password.clone())),
+            _ => builder,
+        };
+
+        Ok(Self {
+            transport: builder.build(),
+            from: mailbox(&config.from)?,
+            to: config.to.iter().map(String::as_str).map(mailbox).collect::<Result<_, _>>()?,
+        })
+    }
+
+    async fn send(&self, alert: &Alert) -> Result<(), Error> {
+        let message = self
+            .to
+            .iter()
+            .fold(Message::builder().from(self.from.clone()), |builder, to| {
+                builder.to(to.clone())
+            })
+            .subject(alert.subject())
+            .body(alert.message())?;
+
+        self.transport.send(message).await?;
+
+        Ok(())
+    }
+}
+
+fn mailbox(address: &str) -> Result<Mailbox, Error> {
+    address.parse().map_err(|source| Error::InvalidAddress {
+        address: address.to_string(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use torrust_tracker_configuration::{Alerts, AlertsSmtp, AlertsSmtpSecurity};
+
+    use super::{Error, Notifier};
+
+    fn smtp(to: &str) -> AlertsSmtp {
+        AlertsSmtp {
+            server: "localhost".to_string(),
+            port: 25,
+            security: AlertsSmtpSecurity::None,
+            username: None,
+            password: None,
+            from: "Tracker <tracker@example.com>".to_string(),
+            to: vec![to.to_string()],
+        }
+    }
+
+    #[test]
+    fn it_should_build_a_notifier_with_valid_email_addresses() {
+        let config = Alerts {
+            smtp: Some(smtp("admin@example.com")),
+            ..Default::default()
+        };
+
+        assert!(Notifier::new(&config, None).is_ok());
+    }
+
+    #[test]
+    fn it_should_fail_building_a_notifier_with_invalid_email_addresses() {
+        let config = Alerts {
+            smtp: Some(smtp("not an email address")),
+            ..Default::default()
+        };
+
+        assert!(matches!(Notifier::new(&config, None), Err(Error::InvalidAddress { .. })));
+    }
+}
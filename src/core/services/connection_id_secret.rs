@@ -0,0 +1,349 @@
+//! UDP connection ID secret services.
+//!
+//! The UDP tracker connection IDs are derived from a secret. Refer to the
+//! [`connection_cookie`](crate::servers::udp::connection_cookie) module for
+//! more information.
+//!
+//! By default, the secret is generated randomly when the tracker starts, so
+//! the connection IDs handed out before a restart are no longer valid. The
+//! secret can be persisted:
+//!
+//! - In the configuration (`udp_connection_id_secret`).
+//! - In a secret file (`udp_connection_id_secret_file`). It's created the
+//!   first time the tracker starts, and updated every time the secret is
+//!   rotated.
+//!
+//! The secret is rotated on demand (with the tracker API) or on intervals.
+//! After a rotation, the previous secret is still accepted during the
+//! configured grace period. Refer to the
+//! [`ConnectionIdSecretRotation`](torrust_tracker_configuration::ConnectionIdSecretRotation)
+//! configuration section.
+//!
+//! The secret file contains the current secret and, during the grace period,
+//! the previous one:
+//!
+//! ```json
+//! {
+//!     "secret": "f7c9d7d1a3e6b5d4c2b1a0f9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d9",
+//!     "created_at": 1726138800,
+//!     "previous_secret": "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+//!     "previous_secret_valid_until": 1726139100
+//! }
+//! ```
+//!
+//! Timestamps are Unix timestamps in seconds.
+//!
+//! > **NOTICE**: without a secret file, the rotated secrets are only kept in
+//! > memory. The tracker starts with the configured (or a random) secret again
+//! > after a restart.
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_configuration::Core;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+use crate::core::Tracker;
+use crate::shared::crypto::ephemeral_instance_keys::{Seed, RANDOM_SEED};
+use crate::shared::crypto::keys::seeds::{self, Seeds};
+use crate::CurrentClock;
+
+/// Rotations are serialized, so the secret file always contains the last
+/// rotated secret.
+static ROTATION: Mutex<()> = Mutex::new(());
+
+/// Errors that can occur loading, saving or rotating the secret.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("unable to read the UDP connection ID secret file {path}: {source}")]
+    Read { path: PathBuf, source: std::io::Error },
+
+    #[error("unable to write the UDP connection ID secret file {path}: {source}")]
+    Write { path: PathBuf, source: std::io::Error },
+
+    #[error("invalid UDP connection ID secret file {path}: {message}")]
+    InvalidFile { path: PathBuf, message: String },
+}
+
+/// The outcome of a rotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rotation {
+    /// When the new secret was generated.
+    pub rotated_at: DurationSinceUnixEpoch,
+    /// Until when the previous secret is still accepted.
+    pub previous_secret_valid_until: DurationSinceUnixEpoch,
+}
+
+/// The contents of the secret file.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct SecretFile {
+    secret: String,
+    created_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    previous_secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    previous_secret_valid_until: Option<u64>,
+}
+
+impl SecretFile {
+    fn from_seeds(seeds: &Seeds) -> Self {
+        Self {
+            secret: encode(&seeds.current),
+            created_at: seeds.current_since.as_secs(),
+            previous_secret: seeds.previous.map(|(seed, _)| encode(&seed)),
+            previous_secret_valid_until: seeds.previous.map(|(_, valid_until)| valid_until.as_secs()),
+        }
+    }
+
+    fn into_seeds(self) -> Option<Seeds> {
+        let previous = match (self.previous_secret, self.previous_secret_valid_until) {
+            (Some(secret), Some(valid_until)) => Some((decode(&secret)?, Duration::from_secs(valid_until))),
+            _ => None,
+        };
+
+        Some(Seeds {
+            current: decode(&self.secret)?,
+            current_since: Duration::from_secs(self.created_at),
+            previous,
+        })
+    }
+}
+
+/// It sets the instance secret on startup, from the secret file or the
+/// configuration. The random secret generated on startup is used if none of
+/// them are configured.
+///
+/// # Errors
+///
+/// Will return an error if the secret file can't be read, created or it's not
+/// valid.
+pub fn initialize(config: &Core) -> Result<(), Error> {
+    let now = CurrentClock::now();
+
+    let seeds = match &config.udp_connection_id_secret_file {
+        Some(path) => load_or_create(Path::new(path), config, now)?,
+        None => Seeds {
+            current: config.udp_connection_id_secret_bytes().unwrap_or(*RANDOM_SEED),
+            current_since: now,
+            previous: None,
+        },
+    };
+
+    if seeds::configure_instance_seeds(seeds).is_err() {
+        tracing::debug!("the instance seed was already configured");
+    }
+
+    Ok(())
+}
+
+/// It replaces the secret used to generate the UDP connection IDs with a new
+/// random one. The previous secret is still accepted during the configured
+/// grace period.
+///
+/// # Errors
+///
+/// Will return an error if the new secret can't be saved to the secret file.
+/// The new secret is used anyway.
+///
+/// # Panics
+///
+/// Will panic if the rotation lock is poisoned.
+pub fn rotate(tracker: &Tracker) -> Result<Rotation, Error> {
+    let _lock = ROTATION
+        .lock()
+        .expect("it should get a lock on the UDP connection ID secret rotation");
+
+    let config = &tracker.config;
+    let now = CurrentClock::now();
+    let previous_secret_valid_until = now + Duration::from_secs(config.udp_connection_id_secret_rotation.grace_period);
+
+    let seeds = seeds::rotate_instance_seed(random_seed(), now, previous_secret_valid_until);
+
+    tracing::info!("UDP connection ID secret rotated");
+
+    if let Some(path) = &config.udp_connection_id_secret_file {
+        save(Path::new(path), &seeds)?;
+    }
+
+    Ok(Rotation {
+        rotated_at: now,
+        previous_secret_valid_until,
+    })
+}
+
+/// It returns when the secret is due to be rotated, if the scheduled rotation
+/// is enabled.
+#[must_use]
+pub fn next_scheduled_rotation(config: &Core) -> Option<DurationSinceUnixEpoch> {
+    let interval = Duration::from_secs(config.udp_connection_id_secret_rotation.interval?);
+
+    let current_since = seeds::instance_seeds().map_or_else(CurrentClock::now, |seeds| seeds.current_since);
+
+    Some(current_since + interval)
+}
+
+fn load_or_create(path: &Path, config: &Core, now: DurationSinceUnixEpoch) -> Result<Seeds, Error> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => {
+            let invalid = |message: String| Error::InvalidFile {
+                path: path.to_path_buf(),
+                message,
+            };
+
+            let file: SecretFile = serde_json::from_str(&contents).map_err(|err| invalid(err.to_string()))?;
+
+            file.into_seeds()
+                .ok_or_else(|| invalid("the secrets must be 64 hexadecimal characters".to_string()))
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            let seeds = Seeds {
+                current: config.udp_connection_id_secret_bytes().unwrap_or_else(random_seed),
+                current_since: now,
+                previous: None,
+            };
+
+            save(path, &seeds)?;
+
+            tracing::info!(path = %path.display(), "UDP connection ID secret file created");
+
+            Ok(seeds)
+        }
+        Err(source) => Err(Error::Read {
+            path: path.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+/// It writes the secret file. The contents are written to a temporary file
+/// first, and then renamed, so the file is never left half-written.
+fn save(path: &Path, seeds: &Seeds) -> Result<(), Error> {
+    let write_error = |source| Error::Write {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let contents = serde_json::to_string_pretty(&SecretFile::from_seeds(seeds)).expect("it should serialize the secret file");
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(write_error)?;
+    }
+
+    let tmp_path = path.with_extension("tmp");
+
+    write_private_file(&tmp_path, contents.as_bytes()).map_err(write_error)?;
+
+    std::fs::rename(&tmp_path, path).map_err(write_error)
+}
+
+#[cfg(unix)]
+fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(contents)
+}
+
+#[cfg(not(unix))]
+fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+fn random_seed() -> Seed {
+    rand::thread_rng().gen()
+}
+
+fn encode(seed: &Seed) -> String {
+    seed.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode(secret: &str) -> Option<Seed> {
+    if secret.len() != 64 || !secret.is_ascii() {
+        return None;
+    }
+
+    let mut seed = Seed::default();
+
+    for (byte, chunk) in seed.iter_mut().zip(secret.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+    }
+
+    Some(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use torrust_tracker_configuration::Core;
+
+    use super::{load_or_create, save, Error};
+    use crate::shared::crypto::keys::seeds::Seeds;
+
+    fn secret_file_path() -> PathBuf {
+        std::env::temp_dir().join(format!("udp_connection_id_secret_{}.json", rand::random::<u64>()))
+    }
+
+    #[test]
+    fn it_should_create_the_secret_file_with_the_configured_secret_if_it_does_not_exist() {
+        let path = secret_file_path();
+        let config = Core {
+            udp_connection_id_secret: Some("01".repeat(32)),
+            ..Default::default()
+        };
+
+        let seeds = load_or_create(&path, &config, Duration::from_secs(100)).unwrap();
+
+        assert_eq!(seeds.current, [1u8; 32]);
+        assert_eq!(
+            load_or_create(&path, &Core::default(), Duration::from_secs(200)).unwrap(),
+            seeds
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn it_should_load_the_previous_secret_from_the_secret_file() {
+        let path = secret_file_path();
+        let seeds = Seeds {
+            current: [1u8; 32],
+            current_since: Duration::from_secs(100),
+            previous: Some(([2u8; 32], Duration::from_secs(400))),
+        };
+
+        save(&path, &seeds).unwrap();
+
+        assert_eq!(
+            load_or_create(&path, &Core::default(), Duration::from_secs(200)).unwrap(),
+            seeds
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn it_should_fail_loading_an_invalid_secret_file() {
+        let path = secret_file_path();
+
+        std::fs::write(&path, r#"{ "secret": "not hex", "created_at": 0 }"#).unwrap();
+
+        assert!(matches!(
+            load_or_create(&path, &Core::default(), Duration::ZERO),
+            Err(Error::InvalidFile { .. })
+        ));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
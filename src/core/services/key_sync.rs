@@ -0,0 +1,207 @@
+//! Key synchronization services.
+//!
+//! The tracker can pull the authentication keys from a remote endpoint, for
+//! example, the user database of a torrent index. The endpoint is configured
+//! in the [`KeySync`] configuration section.
+//!
+//! The tracker sends a `GET` request to the endpoint. Except for the first
+//! request, it includes the `since` query param with the cursor returned in
+//! the previous response:
+//!
+//! ```text
+//! GET https://index.example.com/api/v1/tracker/keys?since=1f3a
+//! Authorization: Bearer MySyncToken
+//! ```
+//!
+//! The endpoint returns the keys added or updated, and the keys removed,
+//! since that cursor. When the request does not include a cursor, it must
+//! return all the keys.
+//!
+//! ```json
+//! {
+//!     "cursor": "1f3b",
+//!     "keys": [
+//!         { "key": "YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ", "valid_until": 1726138800, "label": "user-1234" },
+//!         { "key": "xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6", "valid_until": null }
+//!     ],
+//!     "removed_keys": ["C2ktyP7B1xwbV5ju2xEYrPqEEHmtRy7A"]
+//! }
+//! ```
+//!
+//! `valid_until` is a Unix timestamp in seconds, or `null` for permanent
+//! keys. The `label` is optional, to attribute the keys to the users. The
+//! cursor is opaque to the tracker: any string that lets the endpoint find the
+//! changes, like a sequence number or a timestamp.
+//!
+//! > **NOTICE**: the cursor is only kept in memory. The first
+//! > synchronization after restarting the tracker is a full synchronization.
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Deserialize;
+use thiserror::Error;
+use torrust_tracker_configuration::KeySync;
+
+use super::{build_http_client, send_request};
+use crate::core::auth::{Key, PeerKey};
+use crate::core::{databases, Tracker};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The changes returned by the remote endpoint.
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct KeyUpdates {
+    /// The position of these changes in the remote source. It's sent in the
+    /// next request.
+    pub cursor: String,
+    /// Keys added or updated.
+    #[serde(default)]
+    pub keys: Vec<RemoteKey>,
+    /// Keys removed.
+    #[serde(default)]
+    pub removed_keys: Vec<String>,
+}
+
+/// An authentication key in the remote source.
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct RemoteKey {
+    pub key: String,
+    /// Unix timestamp in seconds. Permanent keys do not have it.
+    pub valid_until: Option<u64>,
+    /// Optional label to attribute the key, for example, to a user.
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Errors that can occur synchronizing the keys.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("unable to pull the key updates from {url}: {source}")]
+    Request { url: String, source: reqwest::Error },
+
+    #[error("invalid key in the key updates: {key}")]
+    InvalidKey { key: String },
+
+    #[error("unable to store the key updates: {source}")]
+    Database { source: databases::error::Error },
+}
+
+/// It pulls the key changes since the `cursor` from the remote endpoint and
+/// applies them to the tracker.
+///
+/// It returns the cursor for the next synchronization. Nothing is applied if
+/// any of the keys in the response is not valid.
+///
+/// # Errors
+///
+/// Will return an error if the request fails, the response is not valid or
+/// the changes can't be stored in the database.
+pub async fn synchronize(tracker: &Tracker, config: &KeySync, url: &str, cursor: Option<&str>) -> Result<String, Error> {
+    let request_error = |source| Error::Request {
+        url: url.to_string(),
+        source,
+    };
+
+    let client = build_http_client(tracker.get_outbound_proxy().as_deref(), REQUEST_TIMEOUT).map_err(request_error)?;
+
+    let mut request = client.get(url);
+
+    if let Some(cursor) = cursor {
+        request = request.query(&[("since", cursor)]);
+    }
+
+    if let Some(token) = &config.token {
+        request = request.bearer_auth(token);
+    }
+
+    let updates: KeyUpdates = send_request(request)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(request_error)?
+        .json()
+        .await
+        .map_err(request_error)?;
+
+    let (updated, removed) = parse(&updates)?;
+
+    tracker
+        .sync_auth_keys(&updated, &removed)
+        .await
+        .map_err(|source| Error::Database { source })?;
+
+    tracing::info!(
+        updated = updated.len(),
+        removed = removed.len(),
+        cursor = updates.cursor,
+        "authentication keys synchronized"
+    );
+
+    Ok(updates.cursor)
+}
+
+/// It converts the key updates into the tracker keys.
+fn parse(updates: &KeyUpdates) -> Result<(Vec<PeerKey>, Vec<Key>), Error> {
+    let parse_key = |key: &str| Key::from_str(key).map_err(|_| Error::InvalidKey { key: key.to_string() });
+
+    let updated = updates
+        .keys
+        .iter()
+        .map(|remote_key| {
+            Ok(PeerKey {
+                key: parse_key(&remote_key.key)?,
+                valid_until: remote_key.valid_until.map(Duration::from_secs),
+                created_at: None,
+                label: remote_key.label.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let removed = updates
+        .removed_keys
+        .iter()
+        .map(|key| parse_key(key))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok((updated, removed))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    use super::{parse, Error, KeyUpdates};
+    use crate::core::auth::{Key, PeerKey};
+
+    #[test]
+    fn it_should_parse_the_key_updates_returned_by_the_remote_endpoint() {
+        let updates: KeyUpdates = serde_json::from_str(
+            r#"{
+                "cursor": "1f3b",
+                "keys": [{ "key": "YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ", "valid_until": 1726138800, "label": "user-1234" }],
+                "removed_keys": ["C2ktyP7B1xwbV5ju2xEYrPqEEHmtRy7A"]
+            }"#,
+        )
+        .unwrap();
+
+        let (updated, removed) = parse(&updates).unwrap();
+
+        assert_eq!(
+            updated,
+            vec![PeerKey {
+                key: Key::from_str("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap(),
+                valid_until: Some(Duration::from_secs(1_726_138_800)),
+                created_at: None,
+                label: Some("user-1234".to_string()),
+            }]
+        );
+        assert_eq!(removed, vec![Key::from_str("C2ktyP7B1xwbV5ju2xEYrPqEEHmtRy7A").unwrap()]);
+    }
+
+    #[test]
+    fn it_should_reject_key_updates_with_invalid_keys() {
+        let updates: KeyUpdates = serde_json::from_str(r#"{ "cursor": "1f3b", "removed_keys": ["invalid"] }"#).unwrap();
+
+        assert!(matches!(parse(&updates), Err(Error::InvalidKey { .. })));
+    }
+}
@@ -0,0 +1,100 @@
+//! Maintenance services.
+//!
+//! The periodic jobs of the tracker can also be run on demand, from the
+//! [`maintenance`](crate::servers::apis::v1::context::maintenance) API
+//! endpoints, instead of waiting for their next run:
+//!
+//! - [`cleanup_peers`]: the removal of the inactive peers and the peerless
+//!   torrents, run every `inactive_peer_cleanup_interval`.
+//! - [`compact_database`]: the removal of the expired authentication keys,
+//!   followed by the compaction of the database.
+use crate::core::{databases, Tracker};
+
+/// What the cleanup of the peers removed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CleanupReport {
+    /// Number of inactive peers removed.
+    pub removed_peers: u64,
+    /// Number of peerless torrents removed.
+    pub removed_torrents: u64,
+}
+
+/// What the database maintenance removed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// Number of expired authentication keys removed.
+    pub removed_expired_keys: usize,
+}
+
+/// It removes the inactive peers and, if the tracker policy says so, the
+/// peerless torrents. Refer to [`Tracker::cleanup_torrents`].
+///
+/// The report is computed from the totals before and after the cleanup, so
+/// the peers added or removed by concurrent announces are counted too.
+pub async fn cleanup_peers(tracker: &Tracker) -> CleanupReport {
+    let before = tracker.get_torrents_metrics();
+
+    tracker.cleanup_torrents().await;
+
+    let after = tracker.get_torrents_metrics();
+
+    CleanupReport {
+        removed_peers: (before.complete + before.incomplete).saturating_sub(after.complete + after.incomplete),
+        removed_torrents: before.torrents.saturating_sub(after.torrents),
+    }
+}
+
+/// It removes the expired authentication keys and compacts the database.
+///
+/// # Errors
+///
+/// Will return a `database::Error` if unable to remove the keys from the
+/// database or to compact it.
+pub async fn compact_database(tracker: &Tracker) -> Result<CompactionReport, databases::error::Error> {
+    let removed_expired_keys = tracker.remove_expired_auth_keys().await?;
+
+    tracker.compact_database().await?;
+
+    Ok(CompactionReport { removed_expired_keys })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use torrust_tracker_primitives::info_hash::InfoHash;
+    use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+    use torrust_tracker_test_helpers::configuration;
+
+    use super::{cleanup_peers, compact_database, CleanupReport, CompactionReport};
+    use crate::core::services::tracker_factory;
+
+    #[tokio::test]
+    async fn it_should_report_the_removed_peers_and_torrents() {
+        let tracker = tracker_factory(&configuration::ephemeral_public());
+
+        let info_hash = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".parse::<InfoHash>().unwrap();
+
+        let _ = tracker
+            .upsert_peer_and_get_stats(&info_hash, &PeerBuilder::default().last_updated_on(Duration::ZERO).build())
+            .await;
+
+        assert_eq!(
+            cleanup_peers(&tracker).await,
+            CleanupReport {
+                removed_peers: 1,
+                removed_torrents: 1,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_compact_the_database() {
+        let tracker = tracker_factory(&configuration::ephemeral_public());
+
+        assert_eq!(
+            compact_database(&tracker).await.unwrap(),
+            CompactionReport { removed_expired_keys: 0 }
+        );
+    }
+}
@@ -1,13 +1,23 @@
 //! Tracker domain services. Core and statistics services.
 //!
-//! There are two types of service:
+//! There are three types of service:
 //!
 //! - [Core tracker services](crate::core::services::torrent): related to the tracker main functionalities like getting info about torrents.
 //! - [Services for statistics](crate::core::services::statistics): related to tracker metrics. Aggregate data about the tracker server.
+//! - [Abuse services](crate::core::services::abuse): related to abuse reports and their mitigations.
+//! - [Key synchronization services](crate::core::services::key_sync): related to the authentication keys pulled from a remote source.
+//! - [Connection ID secret services](crate::core::services::connection_id_secret): related to the persistence and rotation of the UDP connection ID secret.
+//! - [Maintenance services](crate::core::services::maintenance): related to the cleanup of the peers and the database maintenance run on demand.
+pub mod abuse;
+pub mod alerts;
+pub mod connection_id_secret;
+pub mod key_sync;
+pub mod maintenance;
 pub mod statistics;
 pub mod torrent;
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use torrust_tracker_configuration::Configuration;
 
@@ -31,3 +41,76 @@ pub fn tracker_factory(config: &Configuration) -> Tracker {
         }
     }
 }
+
+/// Number of times an outbound request is sent when the connection fails.
+const CONNECTION_ATTEMPTS: u32 = 3;
+
+/// Time to wait before the first retry. It's doubled after each retry.
+const CONNECTION_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// It builds the HTTP client for the requests sent by the tracker. Requests
+/// go through the configured outbound proxy, or through the proxy in the
+/// standard env vars when there is no proxy configured.
+///
+/// The hostnames are resolved by the client each time a connection is opened,
+/// so the changes in the DNS records are followed.
+pub(crate) fn build_http_client(outbound_proxy: Option<&str>, timeout: Duration) -> reqwest::Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder().timeout(timeout);
+
+    if let Some(proxy) = outbound_proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+
+    builder.build()
+}
+
+/// It sends an outbound request, retrying with an exponential backoff when
+/// the connection fails, because the DNS records or the upstream service may
+/// not be ready yet (for example, in a container orchestrator).
+pub(crate) async fn send_request(request: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+    let mut delay = CONNECTION_RETRY_DELAY;
+    let mut attempt = 1;
+
+    loop {
+        // Requests with a streamed body can't be sent again
+        let retry = match request.try_clone() {
+            Some(retry) if attempt < CONNECTION_ATTEMPTS => retry,
+            _ => return request.send().await,
+        };
+
+        match retry.send().await {
+            Err(err) if err.is_connect() => {
+                tracing::debug!(%err, attempt, "failed to connect to send an outbound request, retrying");
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{build_http_client, send_request};
+
+    #[tokio::test]
+    async fn it_should_give_up_sending_an_outbound_request_after_all_the_connection_attempts() {
+        // Nothing is listening on the port once the listener is dropped
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let client = build_http_client(None, Duration::from_secs(5)).unwrap();
+
+        let err = send_request(client.get(format!("http://127.0.0.1:{port}/")))
+            .await
+            .unwrap_err();
+
+        assert!(err.is_connect());
+    }
+}
@@ -42,8 +42,12 @@ use std::sync::Arc;
 
 use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
 
+use crate::core::bandwidth::Estimate;
+use crate::core::databases::metrics::QueryStats;
+use crate::core::databases::PoolStats;
+use crate::core::load_monitor::Load;
 use crate::core::statistics::Metrics;
-use crate::core::Tracker;
+use crate::core::{experiment, scrape_cache, Tracker};
 
 /// All the metrics collected by the tracker.
 #[derive(Debug, PartialEq)]
@@ -53,6 +57,36 @@ pub struct TrackerMetrics {
     /// General metrics for all torrents (number of seeders, leechers, etcetera)
     pub torrents_metrics: TorrentsMetrics,
 
+    /// Domain level metrics.
+    ///
+    /// Throughput of all the swarms, estimated from the bytes reported in the announce requests.
+    pub bandwidth_estimate: Estimate,
+
+    /// Application level metrics.
+    ///
+    /// Load of the tracker in the last sample of the overload control.
+    pub load: Load,
+
+    /// Application level metrics.
+    ///
+    /// Hits and misses of the `scrape` cache.
+    pub scrape_cache: scrape_cache::Stats,
+
+    /// Application level metrics.
+    ///
+    /// State of the connection pool of the database.
+    pub database_pool: PoolStats,
+
+    /// Application level metrics.
+    ///
+    /// Latency and errors of the database queries, by operation.
+    pub database_queries: QueryStats,
+
+    /// Application level metrics.
+    ///
+    /// Announces handled for each group of the experiment, if there is one.
+    pub experiment: Option<experiment::Stats>,
+
     /// Application level metrics. Usage statistics/metrics.
     ///
     /// Metrics about how the tracker is been used (number of udp announce requests, number of http scrape requests, etcetera)
@@ -62,10 +96,22 @@ pub struct TrackerMetrics {
 /// It returns all the [`TrackerMetrics`]
 pub async fn get_metrics(tracker: Arc<Tracker>) -> TrackerMetrics {
     let torrents_metrics = tracker.get_torrents_metrics();
+    let bandwidth_estimate = tracker.get_bandwidth_estimate();
+    let load = tracker.get_load();
+    let scrape_cache = tracker.get_scrape_cache_stats();
+    let database_pool = tracker.get_database_pool_stats();
+    let database_queries = tracker.get_database_query_stats();
+    let experiment = tracker.get_experiment_stats();
     let stats = tracker.get_stats().await;
 
     TrackerMetrics {
         torrents_metrics,
+        bandwidth_estimate,
+        load,
+        scrape_cache,
+        database_pool,
+        database_queries,
+        experiment,
         protocol_metrics: Metrics {
             tcp4_connections_handled: stats.tcp4_connections_handled,
             tcp4_announces_handled: stats.tcp4_announces_handled,
@@ -79,6 +125,18 @@ pub async fn get_metrics(tracker: Arc<Tracker>) -> TrackerMetrics {
             udp6_connections_handled: stats.udp6_connections_handled,
             udp6_announces_handled: stats.udp6_announces_handled,
             udp6_scrapes_handled: stats.udp6_scrapes_handled,
+            tcp_forwarded_announces_handled: stats.tcp_forwarded_announces_handled,
+            tcp_forwarded_ip_proxy_changes: stats.tcp_forwarded_ip_proxy_changes,
+            tcp_announces_with_tracker_id: stats.tcp_announces_with_tracker_id,
+            tcp_announces_with_invalid_tracker_id: stats.tcp_announces_with_invalid_tracker_id,
+            tcp_announces_without_tracker_id: stats.tcp_announces_without_tracker_id,
+            proxies: stats.proxies.clone(),
+            rejections: stats.rejections.clone(),
+            udp_sockets: stats.udp_sockets.clone(),
+            api_requests_rate_limited: stats.api_requests_rate_limited,
+            api_requests_locked_out: stats.api_requests_locked_out,
+            api_failed_auth_attempts: stats.api_failed_auth_attempts,
+            api_lockouts: stats.api_lockouts,
         },
     }
 }
@@ -92,6 +150,9 @@ mod tests {
     use torrust_tracker_test_helpers::configuration;
 
     use crate::core;
+    use crate::core::bandwidth::Estimate;
+    use crate::core::load_monitor::Load;
+    use crate::core::scrape_cache;
     use crate::core::services::statistics::{get_metrics, TrackerMetrics};
     use crate::core::services::tracker_factory;
 
@@ -109,6 +170,12 @@ mod tests {
             tracker_metrics,
             TrackerMetrics {
                 torrents_metrics: TorrentsMetrics::default(),
+                bandwidth_estimate: Estimate::default(),
+                load: Load::default(),
+                scrape_cache: scrape_cache::Stats::default(),
+                database_pool: tracker.get_database_pool_stats(),
+                database_queries: tracker.get_database_query_stats(),
+                experiment: None,
                 protocol_metrics: core::statistics::Metrics::default(),
             }
         );
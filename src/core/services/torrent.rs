@@ -4,14 +4,23 @@
 //!
 //! - [`get_torrent_info`]: it returns all the data about one torrent.
 //! - [`get_torrents`]: it returns data about some torrent in bulk excluding the peer list.
+//! - [`search_torrents`]: it returns data about the torrents matching an infohash prefix or announced recently.
+//! - [`get_top_torrents`]: it returns data about the torrents with more peers or completed downloads.
+use std::cmp::Reverse;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 use std::sync::Arc;
 
+use thiserror::Error;
 use torrust_tracker_primitives::info_hash::InfoHash;
 use torrust_tracker_primitives::pagination::Pagination;
-use torrust_tracker_primitives::peer;
+use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
 use torrust_tracker_torrent_repository::entry::EntrySync;
 use torrust_tracker_torrent_repository::repository::Repository;
 
+use crate::core::bandwidth::Estimate;
+use crate::core::top_torrents::Ranking;
 use crate::core::Tracker;
 
 /// It contains all the information the tracker has about a torrent
@@ -25,10 +34,29 @@ pub struct Info {
     pub completed: u64,
     /// The total number of leechers for this torrent. Peers that actively downloading this torrent
     pub leechers: u64,
+    /// The swarm throughput, estimated from the bytes reported in the announce requests
+    pub bandwidth_estimate: Estimate,
     /// The swarm: the list of peers that are actively trying to download or serving this torrent
     pub peers: Option<Vec<peer::Peer>>,
 }
 
+impl Info {
+    /// It replaces the IP addresses of the peers with the unspecified address
+    /// of the same family (`0.0.0.0` or `::`). The ports are kept.
+    #[must_use]
+    pub fn with_redacted_peer_ips(mut self) -> Self {
+        for peer in self.peers.iter_mut().flatten() {
+            let unspecified = match peer.peer_addr.ip() {
+                IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+            };
+            peer.peer_addr.set_ip(unspecified);
+            peer.alt_peer_addr = None;
+        }
+        self
+    }
+}
+
 /// It contains only part of the information the tracker has about a torrent
 ///
 /// It contains the same data as [Info] but without the list of peers in the swarm.
@@ -61,6 +89,7 @@ pub async fn get_torrent_info(tracker: Arc<Tracker>, info_hash: &InfoHash) -> Op
         seeders: u64::from(stats.complete),
         completed: u64::from(stats.downloaded),
         leechers: u64::from(stats.incomplete),
+        bandwidth_estimate: tracker.get_swarm_bandwidth_estimate(info_hash),
         peers,
     })
 }
@@ -83,6 +112,85 @@ pub async fn get_torrents_page(tracker: Arc<Tracker>, pagination: Option<&Pagina
     basic_infos
 }
 
+/// The order of the torrents in a [`get_sorted_torrents_page`] page. All the
+/// orders are descending. Torrents with the same value are ordered by
+/// infohash, so the pages are stable.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Sort {
+    /// By number of peers: seeders and leechers.
+    Peers,
+    /// By number of completed downloads.
+    Completed,
+    /// By time of the last announce of any of the peers.
+    Recent,
+}
+
+/// Error returned when the torrents order can't be parsed.
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid sort: {value}, expected one of: peers, completed, recent")]
+pub struct ParseSortError {
+    pub value: String,
+}
+
+impl FromStr for Sort {
+    type Err = ParseSortError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "peers" => Ok(Sort::Peers),
+            "completed" => Ok(Sort::Completed),
+            "recent" => Ok(Sort::Recent),
+            _ => Err(ParseSortError {
+                value: value.to_string(),
+            }),
+        }
+    }
+}
+
+/// It returns a page of torrents in the given order, in a [`BasicInfo`] struct, excluding the peer list.
+pub async fn get_sorted_torrents_page(tracker: Arc<Tracker>, pagination: &Pagination, sort: Sort) -> Vec<BasicInfo> {
+    let mut torrents: Vec<(BasicInfo, DurationSinceUnixEpoch)> = vec![];
+
+    for (info_hash, torrent_entry) in tracker.torrents.get_paginated(None) {
+        let stats = torrent_entry.get_swarm_metadata();
+
+        let last_announce = if sort == Sort::Recent {
+            torrent_entry
+                .get_peers(None)
+                .iter()
+                .map(|peer| peer.updated)
+                .max()
+                .unwrap_or_default()
+        } else {
+            DurationSinceUnixEpoch::ZERO
+        };
+
+        torrents.push((
+            BasicInfo {
+                info_hash,
+                seeders: u64::from(stats.complete),
+                completed: u64::from(stats.downloaded),
+                leechers: u64::from(stats.incomplete),
+            },
+            last_announce,
+        ));
+    }
+
+    // The sort is stable and the torrents are ordered by infohash
+    match sort {
+        Sort::Peers => torrents.sort_by_key(|(basic_info, _)| Reverse(basic_info.seeders + basic_info.leechers)),
+        Sort::Completed => torrents.sort_by_key(|(basic_info, _)| Reverse(basic_info.completed)),
+        Sort::Recent => torrents.sort_by_key(|(_, last_announce)| Reverse(*last_announce)),
+    }
+
+    torrents
+        .into_iter()
+        .skip(pagination.offset as usize)
+        .take(pagination.limit as usize)
+        .map(|(basic_info, _)| basic_info)
+        .collect()
+}
+
 /// It returns all the information the tracker has about multiple torrents in a [`BasicInfo`] struct, excluding the peer list.
 pub async fn get_torrents(tracker: Arc<Tracker>, info_hashes: &[InfoHash]) -> Vec<BasicInfo> {
     let mut basic_infos: Vec<BasicInfo> = vec![];
@@ -101,9 +209,108 @@ pub async fn get_torrents(tracker: Arc<Tracker>, info_hashes: &[InfoHash]) -> Ve
     basic_infos
 }
 
+/// It returns the first `limit` torrents of the ranking in a [`BasicInfo`]
+/// struct, excluding the peer list. Refer to the [`top_torrents`](crate::core::top_torrents)
+/// module.
+pub async fn get_top_torrents(tracker: Arc<Tracker>, ranking: Ranking, limit: usize) -> Vec<BasicInfo> {
+    tracker
+        .get_top_torrents(ranking, limit)
+        .into_iter()
+        .map(|(info_hash, stats)| BasicInfo {
+            info_hash,
+            seeders: u64::from(stats.complete),
+            completed: u64::from(stats.downloaded),
+            leechers: u64::from(stats.incomplete),
+        })
+        .collect()
+}
+
+/// The first characters of the hex representation of an infohash: from 1 to
+/// 40 hexadecimal characters.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct InfoHashPrefix(String);
+
+/// Error returned when the infohash prefix can't be parsed.
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid infohash prefix: {value}, expected 1 to 40 hexadecimal characters")]
+pub struct ParseInfoHashPrefixError {
+    pub value: String,
+}
+
+impl FromStr for InfoHashPrefix {
+    type Err = ParseInfoHashPrefixError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.is_empty() || value.len() > 40 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParseInfoHashPrefixError {
+                value: value.to_string(),
+            });
+        }
+
+        Ok(Self(value.to_ascii_lowercase()))
+    }
+}
+
+impl InfoHashPrefix {
+    /// The range of the infohashes starting with the prefix. The torrent
+    /// repository is ordered by infohash, so the torrents in the range are
+    /// found without scanning the whole repository.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the prefix is not a valid hex string, which the parser
+    /// prevents.
+    #[must_use]
+    pub fn range(&self) -> RangeInclusive<InfoHash> {
+        let first = InfoHash::from_str(&format!("{:0<40}", self.0)).expect("it should be a valid infohash");
+        let last = InfoHash::from_str(&format!("{:f<40}", self.0)).expect("it should be a valid infohash");
+
+        first..=last
+    }
+}
+
+/// The criteria of a [`search_torrents`] search. All of them are optional.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct SearchCriteria {
+    /// Only the torrents whose infohash starts with this prefix.
+    pub hash_prefix: Option<InfoHashPrefix>,
+    /// Only the torrents with a peer announced at this time or later.
+    pub active_since: Option<DurationSinceUnixEpoch>,
+}
+
+/// It returns a page of the torrents matching the search criteria, ordered by
+/// infohash, in a [`BasicInfo`] struct, excluding the peer list.
+pub async fn search_torrents(tracker: Arc<Tracker>, criteria: &SearchCriteria, pagination: &Pagination) -> Vec<BasicInfo> {
+    let torrents = match &criteria.hash_prefix {
+        Some(hash_prefix) => tracker.torrents.get_range(hash_prefix.range()),
+        None => tracker.torrents.get_paginated(None),
+    };
+
+    torrents
+        .into_iter()
+        .filter(|(_, torrent_entry)| match criteria.active_since {
+            Some(active_since) => torrent_entry.get_peers(None).iter().any(|peer| peer.updated >= active_since),
+            None => true,
+        })
+        .skip(pagination.offset as usize)
+        .take(pagination.limit as usize)
+        .map(|(info_hash, torrent_entry)| {
+            let stats = torrent_entry.get_swarm_metadata();
+
+            BasicInfo {
+                info_hash,
+                seeders: u64::from(stats.complete),
+                completed: u64::from(stats.downloaded),
+                leechers: u64::from(stats.incomplete),
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::time::Duration;
 
     use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
     use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
@@ -117,11 +324,14 @@ mod tests {
             downloaded: NumberOfBytes::new(0),
             left: NumberOfBytes::new(0),
             event: AnnounceEvent::Started,
+            timeout_extension: Duration::ZERO,
+            alt_peer_addr: None,
         }
     }
 
     mod getting_a_torrent_info {
 
+        use std::net::{IpAddr, Ipv4Addr, SocketAddr};
         use std::str::FromStr;
         use std::sync::Arc;
 
@@ -129,6 +339,7 @@ mod tests {
         use torrust_tracker_primitives::info_hash::InfoHash;
         use torrust_tracker_test_helpers::configuration;
 
+        use crate::core::bandwidth::Estimate;
         use crate::core::services::torrent::tests::sample_peer;
         use crate::core::services::torrent::{get_torrent_info, Info};
         use crate::core::services::tracker_factory;
@@ -156,7 +367,7 @@ mod tests {
 
             let hash = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_owned();
             let info_hash = InfoHash::from_str(&hash).unwrap();
-            tracker.upsert_peer_and_get_stats(&info_hash, &sample_peer());
+            tracker.upsert_peer_and_get_stats(&info_hash, &sample_peer()).await;
 
             let torrent_info = get_torrent_info(tracker.clone(), &info_hash).await.unwrap();
 
@@ -167,10 +378,29 @@ mod tests {
                     seeders: 1,
                     completed: 0,
                     leechers: 0,
+                    bandwidth_estimate: Estimate::default(),
                     peers: Some(vec![sample_peer()]),
                 }
             );
         }
+
+        #[tokio::test]
+        async fn should_allow_redacting_the_ips_of_the_peers() {
+            let tracker = Arc::new(tracker_factory(&tracker_configuration()));
+
+            let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+            tracker.upsert_peer_and_get_stats(&info_hash, &sample_peer()).await;
+
+            let torrent_info = get_torrent_info(tracker.clone(), &info_hash)
+                .await
+                .unwrap()
+                .with_redacted_peer_ips();
+
+            let peers = torrent_info.peers.unwrap();
+
+            assert_eq!(peers[0].peer_addr, SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8080));
+            assert_eq!(peers[0].peer_id, sample_peer().peer_id);
+        }
     }
 
     mod searching_for_torrents {
@@ -206,7 +436,7 @@ mod tests {
             let hash = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_owned();
             let info_hash = InfoHash::from_str(&hash).unwrap();
 
-            tracker.upsert_peer_and_get_stats(&info_hash, &sample_peer());
+            tracker.upsert_peer_and_get_stats(&info_hash, &sample_peer()).await;
 
             let torrents = get_torrents_page(tracker.clone(), Some(&Pagination::default())).await;
 
@@ -230,8 +460,8 @@ mod tests {
             let hash2 = "03840548643af2a7b63a9f5cbca348bc7150ca3a".to_owned();
             let info_hash2 = InfoHash::from_str(&hash2).unwrap();
 
-            tracker.upsert_peer_and_get_stats(&info_hash1, &sample_peer());
-            tracker.upsert_peer_and_get_stats(&info_hash2, &sample_peer());
+            tracker.upsert_peer_and_get_stats(&info_hash1, &sample_peer()).await;
+            tracker.upsert_peer_and_get_stats(&info_hash2, &sample_peer()).await;
 
             let offset = 0;
             let limit = 1;
@@ -250,8 +480,8 @@ mod tests {
             let hash2 = "03840548643af2a7b63a9f5cbca348bc7150ca3a".to_owned();
             let info_hash2 = InfoHash::from_str(&hash2).unwrap();
 
-            tracker.upsert_peer_and_get_stats(&info_hash1, &sample_peer());
-            tracker.upsert_peer_and_get_stats(&info_hash2, &sample_peer());
+            tracker.upsert_peer_and_get_stats(&info_hash1, &sample_peer()).await;
+            tracker.upsert_peer_and_get_stats(&info_hash2, &sample_peer()).await;
 
             let offset = 1;
             let limit = 4000;
@@ -276,11 +506,11 @@ mod tests {
 
             let hash1 = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_owned();
             let info_hash1 = InfoHash::from_str(&hash1).unwrap();
-            tracker.upsert_peer_and_get_stats(&info_hash1, &sample_peer());
+            tracker.upsert_peer_and_get_stats(&info_hash1, &sample_peer()).await;
 
             let hash2 = "03840548643af2a7b63a9f5cbca348bc7150ca3a".to_owned();
             let info_hash2 = InfoHash::from_str(&hash2).unwrap();
-            tracker.upsert_peer_and_get_stats(&info_hash2, &sample_peer());
+            tracker.upsert_peer_and_get_stats(&info_hash2, &sample_peer()).await;
 
             let torrents = get_torrents_page(tracker.clone(), Some(&Pagination::default())).await;
 
@@ -303,4 +533,147 @@ mod tests {
             );
         }
     }
+
+    mod sorting_torrents {
+
+        use std::str::FromStr;
+        use std::sync::Arc;
+
+        use aquatic_udp_protocol::PeerId;
+        use torrust_tracker_primitives::info_hash::InfoHash;
+        use torrust_tracker_primitives::DurationSinceUnixEpoch;
+        use torrust_tracker_test_helpers::configuration;
+
+        use crate::core::services::torrent::tests::sample_peer;
+        use crate::core::services::torrent::{get_sorted_torrents_page, Pagination, Sort};
+        use crate::core::services::tracker_factory;
+
+        #[tokio::test]
+        async fn should_return_the_torrents_with_more_peers_first() {
+            let tracker = Arc::new(tracker_factory(&configuration::ephemeral()));
+
+            let info_hash1 = InfoHash::from_str("03840548643af2a7b63a9f5cbca348bc7150ca3a").unwrap();
+            tracker.upsert_peer_and_get_stats(&info_hash1, &sample_peer()).await;
+
+            let info_hash2 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+            tracker.upsert_peer_and_get_stats(&info_hash2, &sample_peer()).await;
+            let mut peer = sample_peer();
+            peer.peer_id = PeerId(*b"-qB00000000000000001");
+            tracker.upsert_peer_and_get_stats(&info_hash2, &peer).await;
+
+            let torrents = get_sorted_torrents_page(tracker.clone(), &Pagination::default(), Sort::Peers).await;
+
+            assert_eq!(
+                torrents.iter().map(|torrent| torrent.info_hash).collect::<Vec<_>>(),
+                vec![info_hash2, info_hash1]
+            );
+        }
+
+        #[tokio::test]
+        async fn should_return_the_torrents_announced_more_recently_first() {
+            let tracker = Arc::new(tracker_factory(&configuration::ephemeral()));
+
+            let info_hash1 = InfoHash::from_str("03840548643af2a7b63a9f5cbca348bc7150ca3a").unwrap();
+            tracker.upsert_peer_and_get_stats(&info_hash1, &sample_peer()).await;
+
+            let info_hash2 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+            let mut peer = sample_peer();
+            peer.updated = sample_peer().updated + DurationSinceUnixEpoch::from_secs(60);
+            tracker.upsert_peer_and_get_stats(&info_hash2, &peer).await;
+
+            let torrents = get_sorted_torrents_page(tracker.clone(), &Pagination::new(1, 1), Sort::Recent).await;
+
+            assert_eq!(
+                torrents.iter().map(|torrent| torrent.info_hash).collect::<Vec<_>>(),
+                vec![info_hash1]
+            );
+        }
+
+        #[test]
+        fn should_only_parse_the_supported_orders() {
+            assert_eq!(Sort::from_str("completed"), Ok(Sort::Completed));
+            assert!(Sort::from_str("seeders").is_err());
+        }
+    }
+
+    mod filtering_torrents {
+
+        use std::str::FromStr;
+        use std::sync::Arc;
+
+        use torrust_tracker_primitives::info_hash::InfoHash;
+        use torrust_tracker_primitives::DurationSinceUnixEpoch;
+        use torrust_tracker_test_helpers::configuration;
+
+        use crate::core::services::torrent::tests::sample_peer;
+        use crate::core::services::torrent::{search_torrents, InfoHashPrefix, Pagination, SearchCriteria};
+        use crate::core::services::tracker_factory;
+
+        #[tokio::test]
+        async fn should_return_the_torrents_whose_info_hash_starts_with_the_prefix() {
+            let tracker = Arc::new(tracker_factory(&configuration::ephemeral()));
+
+            let info_hash1 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+            tracker.upsert_peer_and_get_stats(&info_hash1, &sample_peer()).await;
+
+            let info_hash2 = InfoHash::from_str("9e1f0ed9b52f5d4c1f1d7b0bdb8b7d5b8f4c7f1a").unwrap();
+            tracker.upsert_peer_and_get_stats(&info_hash2, &sample_peer()).await;
+
+            let info_hash3 = InfoHash::from_str("03840548643af2a7b63a9f5cbca348bc7150ca3a").unwrap();
+            tracker.upsert_peer_and_get_stats(&info_hash3, &sample_peer()).await;
+
+            let criteria = SearchCriteria {
+                hash_prefix: Some(InfoHashPrefix::from_str("9E").unwrap()),
+                ..Default::default()
+            };
+
+            let torrents = search_torrents(tracker.clone(), &criteria, &Pagination::default()).await;
+
+            assert_eq!(
+                torrents.iter().map(|torrent| torrent.info_hash).collect::<Vec<_>>(),
+                vec![info_hash1, info_hash2]
+            );
+        }
+
+        #[tokio::test]
+        async fn should_return_the_torrents_announced_since_the_given_time() {
+            let tracker = Arc::new(tracker_factory(&configuration::ephemeral()));
+
+            let info_hash1 = InfoHash::from_str("03840548643af2a7b63a9f5cbca348bc7150ca3a").unwrap();
+            tracker.upsert_peer_and_get_stats(&info_hash1, &sample_peer()).await;
+
+            let info_hash2 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+            let mut peer = sample_peer();
+            peer.updated = sample_peer().updated + DurationSinceUnixEpoch::from_secs(60);
+            tracker.upsert_peer_and_get_stats(&info_hash2, &peer).await;
+
+            let criteria = SearchCriteria {
+                active_since: Some(peer.updated),
+                ..Default::default()
+            };
+
+            let torrents = search_torrents(tracker.clone(), &criteria, &Pagination::default()).await;
+
+            assert_eq!(
+                torrents.iter().map(|torrent| torrent.info_hash).collect::<Vec<_>>(),
+                vec![info_hash2]
+            );
+        }
+
+        #[test]
+        fn should_only_parse_the_prefixes_of_up_to_40_hexadecimal_characters() {
+            assert!(InfoHashPrefix::from_str("ab12").is_ok());
+            assert!(InfoHashPrefix::from_str("").is_err());
+            assert!(InfoHashPrefix::from_str("xyz").is_err());
+            assert!(InfoHashPrefix::from_str(&"a".repeat(41)).is_err());
+        }
+
+        #[test]
+        fn should_convert_the_prefix_into_a_range_of_info_hashes() {
+            let range = InfoHashPrefix::from_str("ab1").unwrap().range();
+
+            assert_eq!(range.start().to_string(), "ab10000000000000000000000000000000000000");
+            assert_eq!(range.end().to_string(), "ab1fffffffffffffffffffffffffffffffffffff");
+        }
+    }
 }
@@ -17,7 +17,39 @@
 //! The [`statistics::Keeper`](crate::core::statistics::Keeper) listens to new events and uses the [`statistics::Repo`](crate::core::statistics::Repo) to upgrade and store metrics.
 //!
 //! See the [`statistics::Event`](crate::core::statistics::Event) enum to check which events are available.
+//!
+//! When the HTTP tracker runs behind a reverse proxy, the tracker also counts
+//! the announce requests received from each proxy, and how many of them come
+//! from a client IP (the right most `X-Forwarded-For` IP) previously seen
+//! through a different proxy. Operators can use them to check that the
+//! proxies preserve the client addresses: a proxy overwriting the header or
+//! appending its own address makes the same clients appear behind different
+//! proxies, or all the clients appear with a few IP addresses.
+//!
+//! The HTTP `announce` requests are also counted by the result of checking
+//! their [tracker id](crate::core::tracker_ids), to correlate the announces
+//! of the same session and to detect the clients ignoring the tracker id.
+//!
+//! The tracker also counts the rejected `announce` and `scrape` requests, by
+//! protocol and by [`RejectReason`], so operators can tell
+//! why the requests are failing.
+//!
+//! Besides the global UDP counters, each UDP tracker socket keeps its own
+//! [`UdpSocketMetrics`], so operators can tell which binding is receiving the
+//! traffic (or the abuse).
+//!
+//! The API requests rejected by its rate limit, the attempts with an invalid
+//! token and the clients locked out after too many of them are counted too,
+//! to detect brute-force attacks against the API.
+//!
+//! Finally, the `announce` and `scrape` requests handled are also counted in
+//! one-minute buckets for the last day, so the [`RequestCounts`] of the last
+//! hour or day are computed without keeping every request.
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::future::BoxFuture;
 use futures::FutureExt;
@@ -25,9 +57,22 @@ use futures::FutureExt;
 use mockall::{automock, predicate::str};
 use tokio::sync::mpsc::error::SendError;
 use tokio::sync::{mpsc, RwLock, RwLockReadGuard};
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+use super::error::Error;
+use super::tracker_ids::Check;
+use crate::CurrentClock;
 
 const CHANNEL_BUFFER_SIZE: usize = 65_535;
 
+/// Number of one-minute buckets of the recent requests: one day.
+const RECENT_REQUESTS_MINUTES: u64 = 24 * 60;
+
+/// Maximum number of client IPs for which the last proxy is remembered. The
+/// list is emptied when it's full.
+const MAX_TRACKED_FORWARDED_IPS: usize = 100_000;
+
 /// An statistics event. It is used to collect tracker metrics.
 ///
 /// - `Tcp` prefix means the event was triggered by the HTTP tracker
@@ -50,6 +95,246 @@ pub enum Event {
     Udp6Connect,
     Udp6Announce,
     Udp6Scrape,
+    /// An HTTP `announce` request received through a reverse proxy.
+    TcpForwardedAnnounce {
+        /// The IP of the proxy connected to the tracker.
+        proxy_ip: IpAddr,
+        /// The client IP forwarded by the proxy.
+        forwarded_ip: IpAddr,
+    },
+    /// The result of checking the tracker id of an HTTP `announce` request.
+    TcpTrackerIdChecked(Check),
+    /// An `announce` or `scrape` request rejected with an error response.
+    Rejected(Rejection),
+    /// A response sent by a UDP tracker socket.
+    UdpResponse {
+        /// The address of the socket the request was received on.
+        server_socket_addr: SocketAddr,
+        /// The type of response.
+        kind: UdpResponseKind,
+        /// The time spent handling the request.
+        latency: Duration,
+    },
+    /// A packet dropped by a UDP tracker socket without sending a response.
+    UdpPacketDropped {
+        /// The address of the socket the packet was received on.
+        server_socket_addr: SocketAddr,
+    },
+    /// A `scrape` packet dropped by a UDP tracker socket to shed load.
+    UdpPacketShed {
+        /// The address of the socket the packet was received on.
+        server_socket_addr: SocketAddr,
+    },
+    /// An API request rejected because the client exceeded the rate limit.
+    ApiRequestRateLimited,
+    /// An API request rejected because the client is locked out.
+    ApiRequestLockedOut,
+    /// An API request with an invalid access token or JWT.
+    ApiAuthFailed,
+    /// An API client locked out after too many attempts with an invalid
+    /// token.
+    ApiClientLockedOut,
+}
+
+impl Event {
+    #[must_use]
+    pub fn rejected(protocol: Protocol, request: RequestKind, reason: RejectReason) -> Self {
+        Self::Rejected(Rejection {
+            protocol,
+            request,
+            reason,
+        })
+    }
+}
+
+/// A rejected request: the protocol, the type of request and why it was
+/// rejected.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Rejection {
+    pub protocol: Protocol,
+    pub request: RequestKind,
+    pub reason: RejectReason,
+}
+
+/// The protocol a request was received with.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Protocol {
+    Http,
+    Udp,
+}
+
+/// The type of a rejected request.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum RequestKind {
+    Announce,
+    Scrape,
+}
+
+/// Number of `announce` and `scrape` requests handled, by protocol.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct RequestCounts {
+    pub http_announces: u64,
+    pub http_scrapes: u64,
+    pub udp_announces: u64,
+    pub udp_scrapes: u64,
+}
+
+impl RequestCounts {
+    fn increase(&mut self, protocol: Protocol, request: RequestKind) {
+        match (protocol, request) {
+            (Protocol::Http, RequestKind::Announce) => self.http_announces += 1,
+            (Protocol::Http, RequestKind::Scrape) => self.http_scrapes += 1,
+            (Protocol::Udp, RequestKind::Announce) => self.udp_announces += 1,
+            (Protocol::Udp, RequestKind::Scrape) => self.udp_scrapes += 1,
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.http_announces += other.http_announces;
+        self.http_scrapes += other.http_scrapes;
+        self.udp_announces += other.udp_announces;
+        self.udp_scrapes += other.udp_scrapes;
+    }
+}
+
+/// The requests handled in the last day, in one-minute buckets. Each bucket
+/// is keyed by the number of minutes since the Unix epoch.
+#[derive(Debug, Default)]
+struct RecentRequests {
+    buckets: VecDeque<(u64, RequestCounts)>,
+}
+
+impl RecentRequests {
+    fn increase(&mut self, protocol: Protocol, request: RequestKind, now: DurationSinceUnixEpoch) {
+        let minute = now.as_secs() / 60;
+
+        while self
+            .buckets
+            .front()
+            .is_some_and(|(bucket_minute, _)| bucket_minute + RECENT_REQUESTS_MINUTES <= minute)
+        {
+            drop(self.buckets.pop_front());
+        }
+
+        if !self.buckets.back().is_some_and(|(bucket_minute, _)| *bucket_minute >= minute) {
+            self.buckets.push_back((minute, RequestCounts::default()));
+        }
+
+        if let Some((_, counts)) = self.buckets.back_mut() {
+            counts.increase(protocol, request);
+        }
+    }
+
+    fn since(&self, window: Duration, now: DurationSinceUnixEpoch) -> RequestCounts {
+        let minute = now.as_secs() / 60;
+        let minutes = window.as_secs() / 60;
+
+        let mut total = RequestCounts::default();
+
+        for (_, counts) in self
+            .buckets
+            .iter()
+            .filter(|(bucket_minute, _)| bucket_minute + minutes > minute)
+        {
+            total.add(counts);
+        }
+
+        total
+    }
+}
+
+/// The type of a response sent by a UDP tracker socket.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UdpResponseKind {
+    Connect,
+    Announce,
+    Scrape,
+    Error,
+}
+
+/// Why a request was rejected.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum RejectReason {
+    /// The request contains an invalid info-hash.
+    BadInfoHash,
+    /// The torrent is not in the whitelist.
+    NotWhitelisted,
+    /// The torrent is in the blacklist.
+    Blacklisted,
+    /// The authentication key is missing, unknown or expired.
+    InvalidKey,
+    /// The peer IP address is banned.
+    BannedIp,
+    /// The client of the peer is not in the client whitelist.
+    ClientNotWhitelisted,
+    /// The peer exceeded the rate limit.
+    RateLimited,
+    /// The request could not be parsed.
+    ParseError,
+    /// The UDP connection ID could not be verified.
+    InvalidConnectionId,
+    /// The peer IP could not be resolved from the HTTP request.
+    UnresolvedPeerIp,
+    /// The announced peer port is lower than the minimum port of the tracker.
+    InvalidPeerPort,
+    /// The tracker is overloaded and it does not handle low-priority requests.
+    Overloaded,
+    /// Any other error.
+    Other,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Protocol::Http => write!(f, "http"),
+            Protocol::Udp => write!(f, "udp"),
+        }
+    }
+}
+
+impl fmt::Display for RequestKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestKind::Announce => write!(f, "announce"),
+            RequestKind::Scrape => write!(f, "scrape"),
+        }
+    }
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            RejectReason::BadInfoHash => "bad_info_hash",
+            RejectReason::NotWhitelisted => "not_whitelisted",
+            RejectReason::Blacklisted => "blacklisted",
+            RejectReason::InvalidKey => "invalid_key",
+            RejectReason::BannedIp => "banned_ip",
+            RejectReason::ClientNotWhitelisted => "client_not_whitelisted",
+            RejectReason::RateLimited => "rate_limited",
+            RejectReason::ParseError => "parse_error",
+            RejectReason::InvalidConnectionId => "invalid_connection_id",
+            RejectReason::UnresolvedPeerIp => "unresolved_peer_ip",
+            RejectReason::InvalidPeerPort => "invalid_peer_port",
+            RejectReason::Overloaded => "overloaded",
+            RejectReason::Other => "other",
+        };
+
+        write!(f, "{reason}")
+    }
+}
+
+impl From<&Error> for RejectReason {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::PeerKeyNotValid { .. } | Error::PeerNotAuthenticated { .. } => RejectReason::InvalidKey,
+            Error::TorrentNotWhitelisted { .. } => RejectReason::NotWhitelisted,
+            Error::TorrentBlacklisted { .. } => RejectReason::Blacklisted,
+            Error::PeerIpBanned { .. } => RejectReason::BannedIp,
+            Error::ClientNotWhitelisted { .. } => RejectReason::ClientNotWhitelisted,
+            Error::AnnounceBudgetExceeded { .. } => RejectReason::RateLimited,
+            Error::TrackerOverloaded { .. } => RejectReason::Overloaded,
+        }
+    }
 }
 
 /// Metrics collected by the tracker.
@@ -88,6 +373,87 @@ pub struct Metrics {
     pub udp6_announces_handled: u64,
     /// Total number of UDP (UDP tracker) `scrape` requests from IPv6 peers.
     pub udp6_scrapes_handled: u64,
+    /// Total number of TCP (HTTP tracker) `announce` requests received
+    /// through a reverse proxy.
+    pub tcp_forwarded_announces_handled: u64,
+    /// Total number of TCP (HTTP tracker) `announce` requests whose forwarded
+    /// client IP was previously received through a different proxy.
+    pub tcp_forwarded_ip_proxy_changes: u64,
+    /// Total number of TCP (HTTP tracker) `announce` requests with the
+    /// tracker id issued to the peer: announces of a known session.
+    pub tcp_announces_with_tracker_id: u64,
+    /// Total number of TCP (HTTP tracker) `announce` requests with a tracker
+    /// id not issued to the peer.
+    pub tcp_announces_with_invalid_tracker_id: u64,
+    /// Total number of TCP (HTTP tracker) `announce` requests, other than
+    /// `started`, without the tracker id issued to the peer: clients
+    /// ignoring the tracker id.
+    pub tcp_announces_without_tracker_id: u64,
+    /// Metrics for each reverse proxy, by proxy IP.
+    pub proxies: HashMap<IpAddr, ProxyMetrics>,
+    /// Total number of rejected `announce` and `scrape` requests, by
+    /// protocol, request type and reason.
+    pub rejections: HashMap<Rejection, u64>,
+    /// Metrics for each UDP tracker socket, by socket address.
+    pub udp_sockets: HashMap<SocketAddr, UdpSocketMetrics>,
+    /// Total number of API requests rejected because the client exceeded the
+    /// rate limit.
+    pub api_requests_rate_limited: u64,
+    /// Total number of API requests rejected because the client was locked
+    /// out.
+    pub api_requests_locked_out: u64,
+    /// Total number of API requests with an invalid access token or JWT.
+    pub api_failed_auth_attempts: u64,
+    /// Total number of times an API client was locked out after too many
+    /// attempts with an invalid token.
+    pub api_lockouts: u64,
+}
+
+/// Metrics for the announce requests received through a reverse proxy.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ProxyMetrics {
+    /// Total number of `announce` requests received through the proxy.
+    pub forwarded_announces_handled: u64,
+    /// Total number of `announce` requests received through the proxy whose
+    /// forwarded client IP was previously received through a different
+    /// proxy.
+    pub forwarded_ip_proxy_changes: u64,
+}
+
+/// Metrics for the requests received by a UDP tracker socket.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct UdpSocketMetrics {
+    /// Total number of `connect` requests handled.
+    pub connections_handled: u64,
+    /// Total number of `announce` requests handled.
+    pub announces_handled: u64,
+    /// Total number of requests answered with an error response.
+    pub errors_handled: u64,
+    /// Total number of `scrape` requests handled.
+    pub scrapes_handled: u64,
+    /// Total number of packets dropped without a response.
+    pub packets_dropped: u64,
+    /// Total number of `scrape` packets dropped, without a response, to shed
+    /// load. They are not counted in `packets_dropped`.
+    pub packets_shed: u64,
+    /// Total time spent handling the requests answered.
+    pub total_response_time: Duration,
+}
+
+impl UdpSocketMetrics {
+    /// Average time spent handling the requests answered.
+    #[must_use]
+    pub fn avg_response_time(&self) -> Duration {
+        let responses = self.connections_handled + self.announces_handled + self.scrapes_handled + self.errors_handled;
+
+        if responses == 0 {
+            return Duration::ZERO;
+        }
+
+        let nanos = self.total_response_time.as_nanos() / u128::from(responses);
+
+        Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+    }
 }
 
 /// The service responsible for keeping tracker metrics (listening to statistics events and handle them).
@@ -142,20 +508,32 @@ async fn event_handler(event: Event, stats_repository: &Repo) {
         Event::Tcp4Announce => {
             stats_repository.increase_tcp4_announces().await;
             stats_repository.increase_tcp4_connections().await;
+            stats_repository
+                .increase_recent_requests(Protocol::Http, RequestKind::Announce, CurrentClock::now())
+                .await;
         }
         Event::Tcp4Scrape => {
             stats_repository.increase_tcp4_scrapes().await;
             stats_repository.increase_tcp4_connections().await;
+            stats_repository
+                .increase_recent_requests(Protocol::Http, RequestKind::Scrape, CurrentClock::now())
+                .await;
         }
 
         // TCP6
         Event::Tcp6Announce => {
             stats_repository.increase_tcp6_announces().await;
             stats_repository.increase_tcp6_connections().await;
+            stats_repository
+                .increase_recent_requests(Protocol::Http, RequestKind::Announce, CurrentClock::now())
+                .await;
         }
         Event::Tcp6Scrape => {
             stats_repository.increase_tcp6_scrapes().await;
             stats_repository.increase_tcp6_connections().await;
+            stats_repository
+                .increase_recent_requests(Protocol::Http, RequestKind::Scrape, CurrentClock::now())
+                .await;
         }
 
         // UDP4
@@ -164,9 +542,15 @@ async fn event_handler(event: Event, stats_repository: &Repo) {
         }
         Event::Udp4Announce => {
             stats_repository.increase_udp4_announces().await;
+            stats_repository
+                .increase_recent_requests(Protocol::Udp, RequestKind::Announce, CurrentClock::now())
+                .await;
         }
         Event::Udp4Scrape => {
             stats_repository.increase_udp4_scrapes().await;
+            stats_repository
+                .increase_recent_requests(Protocol::Udp, RequestKind::Scrape, CurrentClock::now())
+                .await;
         }
 
         // UDP6
@@ -175,9 +559,59 @@ async fn event_handler(event: Event, stats_repository: &Repo) {
         }
         Event::Udp6Announce => {
             stats_repository.increase_udp6_announces().await;
+            stats_repository
+                .increase_recent_requests(Protocol::Udp, RequestKind::Announce, CurrentClock::now())
+                .await;
         }
         Event::Udp6Scrape => {
             stats_repository.increase_udp6_scrapes().await;
+            stats_repository
+                .increase_recent_requests(Protocol::Udp, RequestKind::Scrape, CurrentClock::now())
+                .await;
+        }
+
+        // Reverse proxies
+        Event::TcpForwardedAnnounce { proxy_ip, forwarded_ip } => {
+            stats_repository.record_forwarded_announce(proxy_ip, forwarded_ip).await;
+        }
+
+        // Tracker ids
+        Event::TcpTrackerIdChecked(check) => {
+            stats_repository.record_tracker_id_check(check).await;
+        }
+
+        // Rejected requests
+        Event::Rejected(rejection) => {
+            stats_repository.increase_rejections(rejection).await;
+        }
+
+        // UDP sockets
+        Event::UdpResponse {
+            server_socket_addr,
+            kind,
+            latency,
+        } => {
+            stats_repository.record_udp_response(server_socket_addr, kind, latency).await;
+        }
+        Event::UdpPacketDropped { server_socket_addr } => {
+            stats_repository.increase_udp_packets_dropped(server_socket_addr).await;
+        }
+        Event::UdpPacketShed { server_socket_addr } => {
+            stats_repository.increase_udp_packets_shed(server_socket_addr).await;
+        }
+
+        // API
+        Event::ApiRequestRateLimited => {
+            stats_repository.increase_api_requests_rate_limited().await;
+        }
+        Event::ApiRequestLockedOut => {
+            stats_repository.increase_api_requests_locked_out().await;
+        }
+        Event::ApiAuthFailed => {
+            stats_repository.increase_api_failed_auth_attempts().await;
+        }
+        Event::ApiClientLockedOut => {
+            stats_repository.increase_api_lockouts().await;
         }
     }
 
@@ -188,6 +622,9 @@ async fn event_handler(event: Event, stats_repository: &Repo) {
 #[cfg_attr(test, automock)]
 pub trait EventSender: Sync + Send {
     fn send_event(&self, event: Event) -> BoxFuture<'_, Option<Result<(), SendError<Event>>>>;
+
+    /// It returns `false` if the listener receiving the events has stopped.
+    fn is_listening(&self) -> bool;
 }
 
 /// An [`statistics::EventSender`](crate::core::statistics::EventSender) implementation.
@@ -202,12 +639,20 @@ impl EventSender for Sender {
     fn send_event(&self, event: Event) -> BoxFuture<'_, Option<Result<(), SendError<Event>>>> {
         async move { Some(self.sender.send(event).await) }.boxed()
     }
+
+    fn is_listening(&self) -> bool {
+        !self.sender.is_closed()
+    }
 }
 
 /// A repository for the tracker metrics.
 #[derive(Clone)]
 pub struct Repo {
     pub stats: Arc<RwLock<Metrics>>,
+    /// The last proxy each forwarded client IP was received through.
+    last_proxies: Arc<RwLock<HashMap<IpAddr, IpAddr>>>,
+    /// The `announce` and `scrape` requests handled in the last day.
+    recent_requests: Arc<RwLock<RecentRequests>>,
 }
 
 impl Default for Repo {
@@ -221,6 +666,8 @@ impl Repo {
     pub fn new() -> Self {
         Self {
             stats: Arc::new(RwLock::new(Metrics::default())),
+            last_proxies: Arc::default(),
+            recent_requests: Arc::default(),
         }
     }
 
@@ -299,6 +746,110 @@ impl Repo {
         stats_lock.udp6_scrapes_handled += 1;
         drop(stats_lock);
     }
+
+    /// It counts an announce request received through a reverse proxy.
+    pub async fn record_forwarded_announce(&self, proxy_ip: IpAddr, forwarded_ip: IpAddr) {
+        let mut last_proxies_lock = self.last_proxies.write().await;
+        if last_proxies_lock.len() >= MAX_TRACKED_FORWARDED_IPS && !last_proxies_lock.contains_key(&forwarded_ip) {
+            last_proxies_lock.clear();
+        }
+        let previous_proxy_ip = last_proxies_lock.insert(forwarded_ip, proxy_ip);
+        drop(last_proxies_lock);
+
+        let proxy_changed = previous_proxy_ip.is_some_and(|previous_proxy_ip| previous_proxy_ip != proxy_ip);
+
+        let mut stats_lock = self.stats.write().await;
+        stats_lock.tcp_forwarded_announces_handled += 1;
+        let proxy_metrics = stats_lock.proxies.entry(proxy_ip).or_default();
+        proxy_metrics.forwarded_announces_handled += 1;
+        if proxy_changed {
+            proxy_metrics.forwarded_ip_proxy_changes += 1;
+            stats_lock.tcp_forwarded_ip_proxy_changes += 1;
+        }
+        drop(stats_lock);
+    }
+
+    /// It counts the announces by the result of checking their tracker id.
+    pub async fn record_tracker_id_check(&self, check: Check) {
+        let mut stats_lock = self.stats.write().await;
+        match check {
+            Check::Valid => stats_lock.tcp_announces_with_tracker_id += 1,
+            Check::Invalid => stats_lock.tcp_announces_with_invalid_tracker_id += 1,
+            Check::Missing => stats_lock.tcp_announces_without_tracker_id += 1,
+        }
+        drop(stats_lock);
+    }
+
+    pub async fn increase_rejections(&self, rejection: Rejection) {
+        let mut stats_lock = self.stats.write().await;
+        *stats_lock.rejections.entry(rejection).or_default() += 1;
+        drop(stats_lock);
+    }
+
+    /// It counts a response sent by a UDP tracker socket.
+    pub async fn record_udp_response(&self, server_socket_addr: SocketAddr, kind: UdpResponseKind, latency: Duration) {
+        let mut stats_lock = self.stats.write().await;
+        let socket_metrics = stats_lock.udp_sockets.entry(server_socket_addr).or_default();
+        match kind {
+            UdpResponseKind::Connect => socket_metrics.connections_handled += 1,
+            UdpResponseKind::Announce => socket_metrics.announces_handled += 1,
+            UdpResponseKind::Scrape => socket_metrics.scrapes_handled += 1,
+            UdpResponseKind::Error => socket_metrics.errors_handled += 1,
+        }
+        socket_metrics.total_response_time += latency;
+        drop(stats_lock);
+    }
+
+    pub async fn increase_udp_packets_dropped(&self, server_socket_addr: SocketAddr) {
+        let mut stats_lock = self.stats.write().await;
+        stats_lock.udp_sockets.entry(server_socket_addr).or_default().packets_dropped += 1;
+        drop(stats_lock);
+    }
+
+    pub async fn increase_udp_packets_shed(&self, server_socket_addr: SocketAddr) {
+        let mut stats_lock = self.stats.write().await;
+        stats_lock.udp_sockets.entry(server_socket_addr).or_default().packets_shed += 1;
+        drop(stats_lock);
+    }
+
+    pub async fn increase_api_requests_rate_limited(&self) {
+        let mut stats_lock = self.stats.write().await;
+        stats_lock.api_requests_rate_limited += 1;
+        drop(stats_lock);
+    }
+
+    pub async fn increase_api_requests_locked_out(&self) {
+        let mut stats_lock = self.stats.write().await;
+        stats_lock.api_requests_locked_out += 1;
+        drop(stats_lock);
+    }
+
+    pub async fn increase_api_failed_auth_attempts(&self) {
+        let mut stats_lock = self.stats.write().await;
+        stats_lock.api_failed_auth_attempts += 1;
+        drop(stats_lock);
+    }
+
+    pub async fn increase_api_lockouts(&self) {
+        let mut stats_lock = self.stats.write().await;
+        stats_lock.api_lockouts += 1;
+        drop(stats_lock);
+    }
+
+    /// It counts an `announce` or `scrape` request handled at `now` in the
+    /// recent requests.
+    pub async fn increase_recent_requests(&self, protocol: Protocol, request: RequestKind, now: DurationSinceUnixEpoch) {
+        let mut recent_requests_lock = self.recent_requests.write().await;
+        recent_requests_lock.increase(protocol, request, now);
+        drop(recent_requests_lock);
+    }
+
+    /// It returns the number of `announce` and `scrape` requests handled in
+    /// the `window` (up to one day) before `now`, with a resolution of one
+    /// minute.
+    pub async fn get_recent_requests(&self, window: Duration, now: DurationSinceUnixEpoch) -> RequestCounts {
+        self.recent_requests.read().await.since(window, now)
+    }
 }
 
 #[cfg(test)]
@@ -329,7 +880,13 @@ mod tests {
     }
 
     mod event_handler {
-        use crate::core::statistics::{event_handler, Event, Repo};
+        use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+        use std::time::Duration;
+
+        use crate::core::statistics::{
+            event_handler, Event, Protocol, RejectReason, Rejection, Repo, RequestKind, UdpResponseKind,
+        };
+        use crate::core::tracker_ids::Check;
 
         #[tokio::test]
         async fn should_increase_the_tcp4_announces_counter_when_it_receives_a_tcp4_announce_event() {
@@ -484,5 +1041,214 @@ mod tests {
 
             assert_eq!(stats.udp6_scrapes_handled, 1);
         }
+
+        #[tokio::test]
+        async fn should_increase_the_proxy_counters_when_it_receives_a_tcp_forwarded_announce_event() {
+            let stats_repository = Repo::new();
+            let proxy_ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+            event_handler(
+                Event::TcpForwardedAnnounce {
+                    proxy_ip,
+                    forwarded_ip: IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)),
+                },
+                &stats_repository,
+            )
+            .await;
+
+            let stats = stats_repository.get_stats().await;
+
+            assert_eq!(stats.tcp_forwarded_announces_handled, 1);
+            assert_eq!(stats.proxies[&proxy_ip].forwarded_announces_handled, 1);
+            assert_eq!(stats.tcp_forwarded_ip_proxy_changes, 0);
+        }
+
+        #[tokio::test]
+        async fn should_count_the_announces_from_the_same_forwarded_ip_through_a_different_proxy() {
+            let stats_repository = Repo::new();
+            let forwarded_ip = IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1));
+            let first_proxy_ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+            let second_proxy_ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+            for proxy_ip in [first_proxy_ip, first_proxy_ip, second_proxy_ip] {
+                event_handler(Event::TcpForwardedAnnounce { proxy_ip, forwarded_ip }, &stats_repository).await;
+            }
+
+            let stats = stats_repository.get_stats().await;
+
+            assert_eq!(stats.tcp_forwarded_ip_proxy_changes, 1);
+            assert_eq!(stats.proxies[&first_proxy_ip].forwarded_ip_proxy_changes, 0);
+            assert_eq!(stats.proxies[&second_proxy_ip].forwarded_ip_proxy_changes, 1);
+        }
+
+        #[tokio::test]
+        async fn should_count_the_announces_by_the_result_of_checking_their_tracker_id() {
+            let stats_repository = Repo::new();
+
+            for check in [Check::Valid, Check::Valid, Check::Invalid, Check::Missing] {
+                event_handler(Event::TcpTrackerIdChecked(check), &stats_repository).await;
+            }
+
+            let stats = stats_repository.get_stats().await;
+
+            assert_eq!(stats.tcp_announces_with_tracker_id, 2);
+            assert_eq!(stats.tcp_announces_with_invalid_tracker_id, 1);
+            assert_eq!(stats.tcp_announces_without_tracker_id, 1);
+        }
+
+        #[tokio::test]
+        async fn should_count_the_rejected_requests_by_protocol_request_type_and_reason() {
+            let stats_repository = Repo::new();
+            let rejection = Rejection {
+                protocol: Protocol::Udp,
+                request: RequestKind::Announce,
+                reason: RejectReason::NotWhitelisted,
+            };
+
+            event_handler(Event::Rejected(rejection), &stats_repository).await;
+            event_handler(Event::Rejected(rejection), &stats_repository).await;
+            event_handler(
+                Event::rejected(Protocol::Http, RequestKind::Announce, RejectReason::NotWhitelisted),
+                &stats_repository,
+            )
+            .await;
+
+            let stats = stats_repository.get_stats().await;
+
+            assert_eq!(stats.rejections[&rejection], 2);
+            assert_eq!(stats.rejections.len(), 2);
+        }
+
+        #[tokio::test]
+        async fn should_count_the_responses_and_dropped_packets_of_each_udp_socket() {
+            let stats_repository = Repo::new();
+            let server_socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 6969);
+            let other_server_socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 6868);
+
+            for (kind, latency) in [(UdpResponseKind::Connect, 10), (UdpResponseKind::Error, 30)] {
+                event_handler(
+                    Event::UdpResponse {
+                        server_socket_addr,
+                        kind,
+                        latency: Duration::from_millis(latency),
+                    },
+                    &stats_repository,
+                )
+                .await;
+            }
+            event_handler(
+                Event::UdpPacketDropped {
+                    server_socket_addr: other_server_socket_addr,
+                },
+                &stats_repository,
+            )
+            .await;
+            event_handler(
+                Event::UdpPacketShed {
+                    server_socket_addr: other_server_socket_addr,
+                },
+                &stats_repository,
+            )
+            .await;
+
+            let stats = stats_repository.get_stats().await;
+
+            let socket_metrics = stats.udp_sockets[&server_socket_addr];
+            assert_eq!(socket_metrics.connections_handled, 1);
+            assert_eq!(socket_metrics.errors_handled, 1);
+            assert_eq!(socket_metrics.avg_response_time(), Duration::from_millis(20));
+            assert_eq!(stats.udp_sockets[&other_server_socket_addr].packets_dropped, 1);
+            assert_eq!(stats.udp_sockets[&other_server_socket_addr].packets_shed, 1);
+        }
+
+        #[tokio::test]
+        async fn should_count_the_rate_limited_api_requests_and_the_failed_auth_attempts() {
+            let stats_repository = Repo::new();
+
+            for event in [
+                Event::ApiRequestRateLimited,
+                Event::ApiAuthFailed,
+                Event::ApiAuthFailed,
+                Event::ApiClientLockedOut,
+                Event::ApiRequestLockedOut,
+            ] {
+                event_handler(event, &stats_repository).await;
+            }
+
+            let stats = stats_repository.get_stats().await;
+
+            assert_eq!(stats.api_requests_rate_limited, 1);
+            assert_eq!(stats.api_failed_auth_attempts, 2);
+            assert_eq!(stats.api_lockouts, 1);
+            assert_eq!(stats.api_requests_locked_out, 1);
+        }
+    }
+
+    mod recent_requests {
+        use std::time::Duration;
+
+        use crate::core::statistics::{Protocol, Repo, RequestCounts, RequestKind};
+
+        const ONE_HOUR: Duration = Duration::from_secs(60 * 60);
+        const ONE_DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+        #[tokio::test]
+        async fn should_count_the_requests_handled_in_the_window_by_protocol() {
+            let stats_repository = Repo::new();
+            let now = Duration::from_secs(1_700_000_000);
+
+            stats_repository
+                .increase_recent_requests(Protocol::Http, RequestKind::Announce, now - Duration::from_secs(2 * 60 * 60))
+                .await;
+            stats_repository
+                .increase_recent_requests(Protocol::Udp, RequestKind::Scrape, now - Duration::from_secs(60))
+                .await;
+            stats_repository
+                .increase_recent_requests(Protocol::Udp, RequestKind::Announce, now)
+                .await;
+
+            assert_eq!(
+                stats_repository.get_recent_requests(ONE_HOUR, now).await,
+                RequestCounts {
+                    http_announces: 0,
+                    http_scrapes: 0,
+                    udp_announces: 1,
+                    udp_scrapes: 1,
+                }
+            );
+            assert_eq!(
+                stats_repository.get_recent_requests(ONE_DAY, now).await,
+                RequestCounts {
+                    http_announces: 1,
+                    http_scrapes: 0,
+                    udp_announces: 1,
+                    udp_scrapes: 1,
+                }
+            );
+        }
+
+        #[tokio::test]
+        async fn should_forget_the_requests_older_than_one_day() {
+            let stats_repository = Repo::new();
+            let now = Duration::from_secs(1_700_000_000);
+
+            stats_repository
+                .increase_recent_requests(Protocol::Http, RequestKind::Scrape, now)
+                .await;
+
+            let one_day_later = now + ONE_DAY;
+
+            stats_repository
+                .increase_recent_requests(Protocol::Http, RequestKind::Announce, one_day_later)
+                .await;
+
+            assert_eq!(
+                stats_repository.get_recent_requests(ONE_DAY, one_day_later).await,
+                RequestCounts {
+                    http_announces: 1,
+                    ..RequestCounts::default()
+                }
+            );
+        }
     }
 }
@@ -0,0 +1,91 @@
+//! Summary of the tracker statistics.
+//!
+//! The summary contains the totals for all the torrents (torrents, seeders,
+//! leechers and completed downloads) and the number of `announce` and
+//! `scrape` requests handled in the last hour and in the last day, by
+//! protocol. It's meant for dashboards, which would otherwise have to page
+//! through all the torrents to compute the totals.
+//!
+//! The request counts are kept incrementally by the
+//! [`statistics`](crate::core::statistics) repository. The torrent totals
+//! require a pass over the torrent repository, so the whole summary is cached
+//! for [`TTL`]: the dashboards polling it every few seconds don't add load to
+//! the tracker.
+use std::sync::Mutex;
+use std::time::Duration;
+
+use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+use super::statistics::RequestCounts;
+
+/// How long a summary is served from the cache.
+pub const TTL: Duration = Duration::from_secs(10);
+
+/// The summary of the tracker statistics.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    /// Totals for all the torrents.
+    pub torrents_metrics: TorrentsMetrics,
+    /// Requests handled in the last hour.
+    pub last_hour: RequestCounts,
+    /// Requests handled in the last day.
+    pub last_day: RequestCounts,
+    /// When the summary was computed.
+    pub computed_at: DurationSinceUnixEpoch,
+}
+
+/// The last summary computed.
+#[derive(Debug, Default)]
+pub struct Cache {
+    summary: Mutex<Option<Summary>>,
+}
+
+impl Cache {
+    /// It returns the cached summary, if it was computed less than [`TTL`]
+    /// before `now`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the cached summary is poisoned.
+    #[must_use]
+    pub fn get(&self, now: DurationSinceUnixEpoch) -> Option<Summary> {
+        let summary = self.summary.lock().expect("it should get a lock on the cached summary");
+
+        summary.filter(|summary| summary.computed_at + TTL > now)
+    }
+
+    /// It replaces the cached summary.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the cached summary is poisoned.
+    pub fn insert(&self, summary: Summary) {
+        *self.summary.lock().expect("it should get a lock on the cached summary") = Some(summary);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Cache, Summary, TTL};
+
+    #[test]
+    fn it_should_return_the_cached_summary_until_it_expires() {
+        let cache = Cache::default();
+        let now = Duration::from_secs(1_700_000_000);
+
+        assert_eq!(cache.get(now), None);
+
+        let summary = Summary {
+            computed_at: now,
+            ..Summary::default()
+        };
+
+        cache.insert(summary);
+
+        assert_eq!(cache.get(now + TTL - Duration::from_secs(1)), Some(summary));
+        assert_eq!(cache.get(now + TTL), None);
+    }
+}
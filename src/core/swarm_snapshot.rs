@@ -0,0 +1,248 @@
+//! Snapshots of the swarms, so a restart does not wipe them.
+//!
+//! The peers are only kept in memory. Without the snapshots, the clients get
+//! empty peer lists after a restart until the rest of the peers announce
+//! again, which can take a whole announce interval.
+//!
+//! With the `[core.swarm_snapshot]` configuration, the torrents (with their
+//! number of completed downloads) and their peers are saved to a JSON file
+//! every `interval` seconds and when the tracker stops. The snapshot is
+//! restored when the tracker starts, before the trackers accept requests:
+//!
+//! - The peers keep the time of their last announce, so the peers that
+//!   expired since the snapshot was saved are not restored, and the rest
+//!   expire as if the tracker had not been restarted.
+//! - The `WebRTC` peers of the WebSocket trackers are not saved, because
+//!   their connections are lost on restart.
+//!
+//! The file is written to a temporary file first, flushed to disk and then
+//! renamed, so a crash while saving does not corrupt the previous snapshot.
+//!
+//! The snapshots are only saved to a file, not to the database: the peers
+//! change on every announce, and writing all of them to the database every
+//! `interval` seconds would load the database shared with the rest of the
+//! tracker data. The number of completed downloads is already persisted in
+//! the database with the `persistent_torrent_completed_stat` option.
+use std::io::{self, Write};
+use std::path::Path;
+
+use aquatic_udp_protocol::PeerId;
+use serde::{Deserialize, Serialize};
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch, PersistentTorrents};
+use torrust_tracker_torrent_repository::entry::EntrySync;
+use torrust_tracker_torrent_repository::repository::Repository;
+
+use super::swarm_store::StoredPeer;
+use super::torrent::Torrents;
+
+/// The torrents and the peers of the tracker at some point.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Snapshot {
+    /// When the snapshot was taken, in seconds since the Unix epoch.
+    created_at: u64,
+    torrents: Vec<Torrent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Torrent {
+    info_hash: InfoHash,
+    downloaded: u32,
+    peers: Vec<Peer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Peer {
+    peer_id: [u8; 20],
+    #[serde(flatten)]
+    peer: StoredPeer,
+}
+
+/// The number of torrents and peers restored from a snapshot.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Restored {
+    pub torrents: usize,
+    pub peers: usize,
+}
+
+impl Snapshot {
+    /// It takes a snapshot of the torrents with peers or completed downloads.
+    /// The peers rejected by `keep` are left out.
+    pub fn take<F>(torrents: &Torrents, now: DurationSinceUnixEpoch, keep: F) -> Self
+    where
+        F: Fn(&InfoHash, &peer::Peer) -> bool,
+    {
+        let torrents = torrents
+            .get_paginated(None)
+            .into_iter()
+            .filter_map(|(info_hash, entry)| {
+                let peers: Vec<Peer> = entry
+                    .get_peers(None)
+                    .iter()
+                    .filter(|peer| keep(&info_hash, peer))
+                    .map(|peer| Peer {
+                        peer_id: peer.peer_id.0,
+                        peer: StoredPeer::from(peer.as_ref()),
+                    })
+                    .collect();
+
+                let downloaded = entry.get_swarm_metadata().downloaded;
+
+                (!peers.is_empty() || downloaded > 0).then_some(Torrent {
+                    info_hash,
+                    downloaded,
+                    peers,
+                })
+            })
+            .collect();
+
+        Self {
+            created_at: now.as_secs(),
+            torrents,
+        }
+    }
+
+    /// It adds the torrents and the peers of the snapshot to the repository.
+    /// The peers not updated since the `cutoff` (plus their timeout
+    /// extension) are left out.
+    ///
+    /// The torrents already in the repository keep their number of completed
+    /// downloads.
+    pub fn restore(self, torrents: &Torrents, cutoff: DurationSinceUnixEpoch) -> Restored {
+        let mut restored = Restored::default();
+
+        let persistent_torrents: PersistentTorrents = self
+            .torrents
+            .iter()
+            .map(|torrent| (torrent.info_hash, torrent.downloaded))
+            .collect();
+
+        torrents.import_persistent(&persistent_torrents);
+
+        for torrent in self.torrents {
+            restored.torrents += 1;
+
+            for peer in torrent.peers {
+                let peer = peer.peer.into_peer(PeerId(peer.peer_id));
+
+                if peer.updated + peer.timeout_extension > cutoff {
+                    torrents.upsert_peer(&torrent.info_hash, &peer);
+                    restored.peers += 1;
+                }
+            }
+        }
+
+        restored
+    }
+
+    /// It saves the snapshot to the file.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can't be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let temp_path = path.with_extension("tmp");
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let mut file = std::fs::File::create(&temp_path)?;
+        file.write_all(&serde_json::to_vec(self)?)?;
+        // The contents must be on disk before the rename, or a crash could
+        // leave an empty snapshot in place of the previous one.
+        file.sync_all()?;
+
+        std::fs::rename(temp_path, path)
+    }
+
+    /// It loads the snapshot from the file. It returns `None` if the file
+    /// does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can't be read or it's not a snapshot.
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        match std::fs::read(path) {
+            Ok(contents) => Ok(Some(serde_json::from_slice(&contents)?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aquatic_udp_protocol::PeerId;
+    use torrust_tracker_primitives::info_hash::InfoHash;
+    use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+    use torrust_tracker_torrent_repository::repository::Repository;
+
+    use super::{Restored, Snapshot};
+    use crate::core::torrent::Torrents;
+
+    fn sample_info_hash() -> InfoHash {
+        "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()
+    }
+
+    #[test]
+    fn it_should_restore_the_peers_that_have_not_expired() {
+        let torrents = Torrents::default();
+
+        let active = PeerBuilder::seeder()
+            .with_peer_id(&PeerId(*b"-qB00000000000000001"))
+            .last_updated_on(Duration::from_secs(1000))
+            .build();
+        let expired = PeerBuilder::leecher()
+            .with_peer_id(&PeerId(*b"-qB00000000000000002"))
+            .last_updated_on(Duration::from_secs(100))
+            .build();
+
+        torrents.upsert_peer(&sample_info_hash(), &active);
+        torrents.upsert_peer(&sample_info_hash(), &expired);
+
+        let snapshot = Snapshot::take(&torrents, Duration::from_secs(1000), |_, _| true);
+
+        let restored_torrents = Torrents::default();
+
+        let restored = snapshot.restore(&restored_torrents, Duration::from_secs(500));
+
+        assert_eq!(restored, Restored { torrents: 1, peers: 1 });
+        assert_eq!(
+            restored_torrents.get(&sample_info_hash()).unwrap().get_peers(None),
+            vec![std::sync::Arc::new(active)]
+        );
+    }
+
+    #[test]
+    fn it_should_leave_out_the_peers_rejected_when_it_takes_the_snapshot() {
+        let torrents = Torrents::default();
+
+        torrents.upsert_peer(&sample_info_hash(), &PeerBuilder::default().build());
+
+        let snapshot = Snapshot::take(&torrents, Duration::ZERO, |_, _| false);
+
+        assert_eq!(snapshot.restore(&Torrents::default(), Duration::ZERO), Restored::default());
+    }
+
+    #[test]
+    fn it_should_save_and_load_the_snapshot() {
+        let path = std::env::temp_dir().join(format!("swarm_snapshot_{}.json", rand::random::<u64>()));
+
+        assert!(Snapshot::load(&path).unwrap().is_none());
+
+        let torrents = Torrents::default();
+
+        torrents.upsert_peer(&sample_info_hash(), &PeerBuilder::default().build());
+
+        let snapshot = Snapshot::take(&torrents, Duration::from_secs(1000), |_, _| true);
+
+        snapshot.save(&path).unwrap();
+
+        assert_eq!(Snapshot::load(&path).unwrap(), Some(snapshot));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,593 @@
+//! Swarms shared by several tracker instances.
+//!
+//! A tracker instance only knows the peers that announced to it. When several
+//! instances behind a load balancer serve the same torrents, the peers of each
+//! swarm are spread among them, and each instance returns an incomplete list
+//! of peers and incomplete swarm statistics.
+//!
+//! With the `redis` backend of the `[core.swarm_store]` configuration, the
+//! peers are also stored in a `Redis` server shared by all the instances, and
+//! the `announce` and `scrape` responses are built from the shared swarms:
+//!
+//! - Each swarm is a hash, with one field per peer ID and the number of
+//!   seeders and leechers, kept up to date on each change. The hash expires
+//!   when no peer announces the torrent for the `max_peer_timeout` (plus the
+//!   [`timeout_extension`](torrust_tracker_primitives::peer::Peer::timeout_extension)
+//!   of the last peer).
+//! - The peers of each swarm are also in a sorted set, by the time they
+//!   expire. A few inactive peers of the swarm are removed on each request.
+//! - The number of completed downloads of each torrent is a field of another
+//!   hash, which does not expire.
+//!
+//! The swarms are read and updated by a Lua script run by the `Redis` server,
+//! so each announce is applied atomically, in a single round trip, and only
+//! the peers needed for the response (picked at random) are sent back to the
+//! tracker. The work done by the server for each request doesn't depend on
+//! the size of the swarm, so a large swarm doesn't stall the rest of the
+//! instances sharing the server. It requires `Redis` 6.2 or later.
+//!
+//! Each instance still keeps its swarms in memory: they are used by the API,
+//! the statistics and the persistence. The responses fall back to them when
+//! the `Redis` server is unavailable.
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
+use redis::aio::ConnectionManager;
+use redis::{Client, RedisResult, Script, ScriptInvocation};
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+use torrust_tracker_configuration::SwarmStore;
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
+use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
+
+/// Maximum number of inactive peers removed from a swarm on each request, so
+/// the work done by the `Redis` server for each request is bounded.
+const EXPIRED_PEERS_PER_REQUEST: usize = 100;
+
+/// Maximum number of peers returned on each request.
+const MAX_PEERS_PER_REQUEST: usize = 100_000;
+
+/// Fields of the swarm hashes with the number of seeders and leechers. They
+/// are not peer IDs, which are 20 bytes long.
+const COMPLETE_FIELD: &str = ":complete";
+const INCOMPLETE_FIELD: &str = ":incomplete";
+
+/// Script run by the `Redis` server to update a peer of a swarm (when the
+/// operation is `upsert` or `remove`) and then read the swarm. It removes up
+/// to [`EXPIRED_PEERS_PER_REQUEST`] inactive peers, and returns the number of
+/// seeders, leechers and completed downloads, and up to `limit` random peers,
+/// leaving out the peer in `field`.
+///
+/// The work done doesn't depend on the size of the swarm: the number of
+/// seeders and leechers is kept up to date in the swarm hash, the peers are
+/// picked with `HRANDFIELD`, and the inactive peers are found in a sorted set
+/// of the peers by the time they expire. The stored peers are not decoded:
+/// each one starts with its kind (`S`eeder or `L`eecher) and `C` if it has
+/// completed the download (`-` otherwise).
+///
+/// `KEYS`: the swarm, the expiration times of its peers and the completed
+/// downloads.
+/// `ARGV`: the infohash, the cutoff of the active peers in milliseconds since
+/// the Unix epoch, the limit, the field, the constants above, the operation,
+/// and for the `upsert` operation, the stored peer, the TTL of the swarm, `1`
+/// if the peer has completed the download and when the peer expires, in
+/// milliseconds since the Unix epoch.
+const SWARM_SCRIPT: &str = r"
+local swarm_key, expiration_key, downloaded_key = KEYS[1], KEYS[2], KEYS[3]
+local info_hash, cutoff, limit, field = ARGV[1], ARGV[2], tonumber(ARGV[3]), ARGV[4]
+local expired_peers_per_request, complete_field, incomplete_field = tonumber(ARGV[5]), ARGV[6], ARGV[7]
+local operation, entry, ttl, completed, expires_at = ARGV[8], ARGV[9], ARGV[10], ARGV[11], ARGV[12]
+
+local function count(entry, increment)
+    if string.sub(entry, 1, 1) == 'S' then
+        redis.call('HINCRBY', swarm_key, complete_field, increment)
+    else
+        redis.call('HINCRBY', swarm_key, incomplete_field, increment)
+    end
+end
+
+local function remove(peer_field)
+    local stored = redis.call('HGET', swarm_key, peer_field)
+    if stored then
+        count(stored, -1)
+        redis.call('HDEL', swarm_key, peer_field)
+    end
+    redis.call('ZREM', expiration_key, peer_field)
+end
+
+local expired = redis.call('ZRANGE', expiration_key, '-inf', cutoff, 'BYSCORE', 'LIMIT', 0, expired_peers_per_request)
+for _, expired_field in ipairs(expired) do
+    remove(expired_field)
+end
+
+if operation == 'remove' then
+    remove(field)
+elseif operation == 'upsert' then
+    local previous = redis.call('HGET', swarm_key, field)
+    if previous then
+        count(previous, -1)
+        if completed == '1' and string.sub(previous, 2, 2) ~= 'C' then
+            redis.call('HINCRBY', downloaded_key, info_hash, 1)
+        end
+    end
+    redis.call('HSET', swarm_key, field, entry)
+    count(entry, 1)
+    redis.call('ZADD', expiration_key, expires_at, field)
+    redis.call('EXPIRE', swarm_key, ttl)
+    redis.call('EXPIRE', expiration_key, ttl)
+end
+
+local peers = {}
+if limit > 0 then
+    -- Room for the counters and the peer left out
+    local entries = redis.call('HRANDFIELD', swarm_key, limit + 3, 'WITHVALUES')
+    for i = 1, #entries, 2 do
+        if #peers < limit and #entries[i] == 20 and entries[i] ~= field then
+            table.insert(peers, {entries[i], entries[i + 1]})
+        end
+    end
+end
+
+local function counter(counter_field)
+    return math.max(tonumber(redis.call('HGET', swarm_key, counter_field) or '0'), 0)
+end
+
+return {
+    counter(complete_field),
+    counter(incomplete_field),
+    tonumber(redis.call('HGET', downloaded_key, info_hash) or '0'),
+    peers
+}
+";
+
+/// The swarms stored in a `Redis` server.
+pub struct RedisSwarms {
+    client: Client,
+    connection: OnceCell<ConnectionManager>,
+    key_prefix: String,
+    max_peer_timeout: Duration,
+    script: Script,
+}
+
+impl RedisSwarms {
+    /// It does not connect to the server until the first request.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the URL of the `Redis` server is not valid.
+    pub fn new(config: &SwarmStore, max_peer_timeout: Duration) -> RedisResult<Self> {
+        Ok(Self {
+            client: Client::open(config.redis_url.as_str())?,
+            connection: OnceCell::new(),
+            key_prefix: config.redis_key_prefix.clone(),
+            max_peer_timeout,
+            script: Script::new(SWARM_SCRIPT),
+        })
+    }
+
+    /// It adds, updates or removes (when it's `stopped`) the peer in the
+    /// swarm, and returns up to `limit` other active peers of the swarm and
+    /// the statistics of the swarm after the change.
+    ///
+    /// The number of completed downloads is increased when a known peer
+    /// announces it has completed the download, like in the swarms in memory.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the request to the `Redis` server fails.
+    pub async fn upsert_peer(
+        &self,
+        info_hash: &InfoHash,
+        peer: &peer::Peer,
+        now: DurationSinceUnixEpoch,
+        limit: usize,
+    ) -> RedisResult<(Vec<peer::Peer>, SwarmMetadata)> {
+        let mut invocation = self.invocation(info_hash, &peer.peer_id.0, now, limit);
+
+        if peer.event == AnnounceEvent::Stopped {
+            invocation.arg("remove");
+        } else {
+            let ttl = self.max_peer_timeout + peer.timeout_extension;
+
+            invocation
+                .arg("upsert")
+                .arg(encode_entry(peer))
+                .arg(ttl.as_secs())
+                .arg(u8::from(peer.event == AnnounceEvent::Completed))
+                .arg(as_millis(peer.updated + peer.timeout_extension));
+        }
+
+        self.run(&invocation, now).await
+    }
+
+    /// It returns up to `limit` active peers of the swarm, leaving out the
+    /// `excluded` peer, and the statistics of the swarm. The inactive peers
+    /// are removed from the swarm.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the request to the `Redis` server fails.
+    pub async fn get_swarm(
+        &self,
+        info_hash: &InfoHash,
+        excluded: Option<&PeerId>,
+        now: DurationSinceUnixEpoch,
+        limit: usize,
+    ) -> RedisResult<(Vec<peer::Peer>, SwarmMetadata)> {
+        let mut invocation = self.invocation(info_hash, excluded.map_or(&[][..], |peer_id| &peer_id.0), now, limit);
+
+        invocation.arg("get");
+
+        self.run(&invocation, now).await
+    }
+
+    fn invocation(&self, info_hash: &InfoHash, field: &[u8], now: DurationSinceUnixEpoch, limit: usize) -> ScriptInvocation<'_> {
+        let mut invocation = self.script.prepare_invoke();
+
+        invocation
+            .key(self.swarm_key(info_hash))
+            .key(self.expiration_key(info_hash))
+            .key(self.downloaded_key())
+            .arg(info_hash.to_string())
+            .arg(as_millis(self.cutoff(now)))
+            // The script adds a few peers to the limit
+            .arg(limit.min(MAX_PEERS_PER_REQUEST))
+            .arg(field)
+            .arg(EXPIRED_PEERS_PER_REQUEST)
+            .arg(COMPLETE_FIELD)
+            .arg(INCOMPLETE_FIELD);
+
+        invocation
+    }
+
+    async fn run(
+        &self,
+        invocation: &ScriptInvocation<'_>,
+        now: DurationSinceUnixEpoch,
+    ) -> RedisResult<(Vec<peer::Peer>, SwarmMetadata)> {
+        let mut connection = self.connection().await?;
+
+        let (complete, incomplete, downloaded, stored_peers): (u32, u32, u32, Vec<(Vec<u8>, String)>) =
+            invocation.invoke_async(&mut connection).await?;
+
+        let swarm_metadata = SwarmMetadata {
+            downloaded,
+            complete,
+            incomplete,
+        };
+
+        // The inactive peers not removed yet are left out
+        let cutoff = self.cutoff(now);
+        let peers = decode_peers(stored_peers)
+            .into_iter()
+            .filter(|peer| peer.updated + peer.timeout_extension > cutoff)
+            .collect();
+
+        Ok((peers, swarm_metadata))
+    }
+
+    /// The peers not updated since the cutoff (plus their timeout extension)
+    /// are inactive.
+    fn cutoff(&self, now: DurationSinceUnixEpoch) -> DurationSinceUnixEpoch {
+        now.saturating_sub(self.max_peer_timeout)
+    }
+
+    async fn connection(&self) -> RedisResult<ConnectionManager> {
+        self.connection
+            .get_or_try_init(|| self.client.get_connection_manager())
+            .await
+            .cloned()
+    }
+
+    fn swarm_key(&self, info_hash: &InfoHash) -> String {
+        format!("{}:swarm:{info_hash}", self.key_prefix)
+    }
+
+    fn expiration_key(&self, info_hash: &InfoHash) -> String {
+        format!("{}:expiration:{info_hash}", self.key_prefix)
+    }
+
+    fn downloaded_key(&self) -> String {
+        format!("{}:downloaded", self.key_prefix)
+    }
+}
+
+fn as_millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// It encodes a peer for the swarm hash: its kind (`S`eeder or `L`eecher),
+/// `C` if it has completed the download (`-` otherwise), and the
+/// [`StoredPeer`].
+fn encode_entry(peer: &peer::Peer) -> String {
+    let kind = if peer.is_seeder() { 'S' } else { 'L' };
+    let completed = if peer.event == AnnounceEvent::Completed { 'C' } else { '-' };
+
+    format!("{kind}{completed}{}", StoredPeer::from(peer).encode())
+}
+
+/// It decodes the peers returned by the [`SWARM_SCRIPT`]: the peer IDs and
+/// the entries of the swarm hash. The ones that can't be decoded are left
+/// out.
+fn decode_peers(stored_peers: Vec<(Vec<u8>, String)>) -> Vec<peer::Peer> {
+    stored_peers
+        .into_iter()
+        .filter_map(|(field, value)| {
+            <[u8; 20]>::try_from(field.as_slice())
+                .ok()
+                .zip(value.get(2..).and_then(StoredPeer::decode))
+                .map(|(peer_id, stored_peer)| stored_peer.into_peer(PeerId(peer_id)))
+        })
+        .collect()
+}
+
+/// A peer of a swarm stored outside the tracker: in `Redis`, where the peer
+/// ID is the field of the hash, or in a [`swarm_snapshot`](crate::core::swarm_snapshot).
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct StoredPeer {
+    addr: SocketAddr,
+    alt_addr: Option<SocketAddr>,
+    /// Milliseconds since the Unix epoch.
+    updated: u64,
+    uploaded: i64,
+    downloaded: i64,
+    left: i64,
+    event: StoredEvent,
+    /// Seconds.
+    timeout_extension: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum StoredEvent {
+    Started,
+    Stopped,
+    Completed,
+    None,
+}
+
+impl StoredPeer {
+    fn encode(&self) -> String {
+        serde_json::to_string(self).expect("it should serialize the peer")
+    }
+
+    fn decode(value: &str) -> Option<Self> {
+        serde_json::from_str(value).ok()
+    }
+
+    pub(crate) fn into_peer(self, peer_id: PeerId) -> peer::Peer {
+        peer::Peer {
+            peer_id,
+            peer_addr: self.addr,
+            updated: Duration::from_millis(self.updated),
+            uploaded: NumberOfBytes::new(self.uploaded),
+            downloaded: NumberOfBytes::new(self.downloaded),
+            left: NumberOfBytes::new(self.left),
+            event: match self.event {
+                StoredEvent::Started => AnnounceEvent::Started,
+                StoredEvent::Stopped => AnnounceEvent::Stopped,
+                StoredEvent::Completed => AnnounceEvent::Completed,
+                StoredEvent::None => AnnounceEvent::None,
+            },
+            timeout_extension: Duration::from_secs(self.timeout_extension),
+            alt_peer_addr: self.alt_addr,
+        }
+    }
+}
+
+impl From<&peer::Peer> for StoredPeer {
+    fn from(peer: &peer::Peer) -> Self {
+        Self {
+            addr: peer.peer_addr,
+            alt_addr: peer.alt_peer_addr,
+            updated: u64::try_from(peer.updated.as_millis()).unwrap_or(u64::MAX),
+            uploaded: peer.uploaded.0.get(),
+            downloaded: peer.downloaded.0.get(),
+            left: peer.left.0.get(),
+            event: match peer.event {
+                AnnounceEvent::Started => StoredEvent::Started,
+                AnnounceEvent::Stopped => StoredEvent::Stopped,
+                AnnounceEvent::Completed => StoredEvent::Completed,
+                AnnounceEvent::None => StoredEvent::None,
+            },
+            timeout_extension: peer.timeout_extension.as_secs(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
+    use torrust_tracker_configuration::{SwarmStore, SwarmStoreBackend};
+    use torrust_tracker_primitives::info_hash::InfoHash;
+    use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+    use torrust_tracker_primitives::peer::Peer;
+    use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
+    use uuid::Uuid;
+
+    use super::{decode_peers, encode_entry, RedisSwarms, StoredPeer};
+
+    /// The tests against a real `Redis` server only run when its URL is in
+    /// this env var, for example: `redis://localhost:6379`.
+    const REDIS_URL_ENV_VAR: &str = "TORRUST_TRACKER_TEST_REDIS_URL";
+
+    const MAX_PEER_TIMEOUT: Duration = Duration::from_secs(900);
+
+    const NOW: Duration = Duration::from_secs(1_700_000_000);
+
+    /// The swarms in the `Redis` server of the tests, with a random key
+    /// prefix, so the tests don't share their swarms.
+    fn redis_swarms() -> Option<RedisSwarms> {
+        let redis_url = std::env::var(REDIS_URL_ENV_VAR).ok()?;
+
+        let config = SwarmStore {
+            backend: SwarmStoreBackend::Redis,
+            redis_key_prefix: format!("torrust-tracker-test-{}", Uuid::new_v4()),
+            redis_url,
+        };
+
+        Some(RedisSwarms::new(&config, MAX_PEER_TIMEOUT).unwrap())
+    }
+
+    fn sample_peer(id: u8, left: i64, event: AnnounceEvent) -> Peer {
+        let mut peer_id = *b"-qB00000000000000000";
+        peer_id[19] = id;
+
+        Peer {
+            left: NumberOfBytes::new(left),
+            event,
+            ..PeerBuilder::default()
+                .with_peer_id(&PeerId(peer_id))
+                .last_updated_on(NOW)
+                .build()
+        }
+    }
+
+    #[test]
+    fn it_should_store_the_peers_without_losing_data() {
+        let peer = Peer {
+            event: AnnounceEvent::Completed,
+            ..PeerBuilder::default()
+                .with_peer_id(&aquatic_udp_protocol::PeerId(*b"-qB00000000000000001"))
+                .last_updated_on(Duration::from_secs(1_700_000_000))
+                .build()
+        };
+
+        let stored_peer = StoredPeer::decode(&StoredPeer::from(&peer).encode()).unwrap();
+
+        assert_eq!(stored_peer.into_peer(peer.peer_id), peer);
+    }
+
+    #[test]
+    fn it_should_tell_the_kind_of_the_stored_peers_before_the_peer() {
+        let seeder = sample_peer(1, 0, AnnounceEvent::Completed);
+        let leecher = sample_peer(2, 100, AnnounceEvent::Started);
+
+        assert!(encode_entry(&seeder).starts_with("SC{"));
+        assert!(encode_entry(&leecher).starts_with("L-{"));
+    }
+
+    #[test]
+    fn it_should_leave_out_the_peers_that_cant_be_decoded() {
+        let peer = sample_peer(1, 0, AnnounceEvent::Started);
+
+        let stored_peers = vec![
+            (peer.peer_id.0.to_vec(), encode_entry(&peer)),
+            (b"-qB00000000000000003".to_vec(), "S-not a peer".to_string()),
+            (b"-qB00000000000000004".to_vec(), StoredPeer::from(&peer).encode()),
+            (b"not a peer ID".to_vec(), encode_entry(&peer)),
+        ];
+
+        assert_eq!(decode_peers(stored_peers), vec![peer]);
+    }
+
+    #[tokio::test]
+    async fn it_should_share_the_peers_of_the_swarms_in_a_redis_server() {
+        let Some(swarms) = redis_swarms() else {
+            return;
+        };
+
+        let info_hash = InfoHash::from_bytes(&[1; 20]);
+        let leecher = sample_peer(1, 100, AnnounceEvent::Started);
+        let seeder = sample_peer(2, 0, AnnounceEvent::Started);
+
+        let (peers, swarm_metadata) = swarms.upsert_peer(&info_hash, &leecher, NOW, 10).await.unwrap();
+
+        assert_eq!(peers, vec![]);
+        assert_eq!(
+            swarm_metadata,
+            SwarmMetadata {
+                downloaded: 0,
+                complete: 0,
+                incomplete: 1
+            }
+        );
+
+        let (peers, swarm_metadata) = swarms.upsert_peer(&info_hash, &seeder, NOW, 10).await.unwrap();
+
+        assert_eq!(peers, vec![leecher.clone()]);
+        assert_eq!(
+            swarm_metadata,
+            SwarmMetadata {
+                downloaded: 0,
+                complete: 1,
+                incomplete: 1
+            }
+        );
+
+        let (peers, _) = swarms.get_swarm(&info_hash, None, NOW, 1).await.unwrap();
+
+        assert_eq!(peers.len(), 1);
+
+        let (_, swarm_metadata) = swarms
+            .upsert_peer(&info_hash, &sample_peer(2, 0, AnnounceEvent::Stopped), NOW, 10)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            swarm_metadata,
+            SwarmMetadata {
+                downloaded: 0,
+                complete: 0,
+                incomplete: 1
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_count_the_completed_downloads_of_the_known_peers_once_in_a_redis_server() {
+        let Some(swarms) = redis_swarms() else {
+            return;
+        };
+
+        let info_hash = InfoHash::from_bytes(&[2; 20]);
+
+        // Unknown peer
+        swarms
+            .upsert_peer(&info_hash, &sample_peer(1, 0, AnnounceEvent::Completed), NOW, 10)
+            .await
+            .unwrap();
+
+        swarms
+            .upsert_peer(&info_hash, &sample_peer(2, 100, AnnounceEvent::Started), NOW, 10)
+            .await
+            .unwrap();
+
+        let completed = sample_peer(2, 0, AnnounceEvent::Completed);
+
+        swarms.upsert_peer(&info_hash, &completed, NOW, 10).await.unwrap();
+        let (_, swarm_metadata) = swarms.upsert_peer(&info_hash, &completed, NOW, 10).await.unwrap();
+
+        assert_eq!(swarm_metadata.downloaded, 1);
+        assert_eq!(swarm_metadata.complete, 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_remove_the_inactive_peers_from_the_swarms_in_a_redis_server() {
+        let Some(swarms) = redis_swarms() else {
+            return;
+        };
+
+        let info_hash = InfoHash::from_bytes(&[3; 20]);
+
+        swarms
+            .upsert_peer(&info_hash, &sample_peer(1, 0, AnnounceEvent::Started), NOW, 10)
+            .await
+            .unwrap();
+
+        let later = NOW + MAX_PEER_TIMEOUT + Duration::from_secs(1);
+
+        assert_eq!(
+            swarms.get_swarm(&info_hash, None, later, 10).await.unwrap(),
+            (vec![], SwarmMetadata::zeroed())
+        );
+        assert_eq!(
+            swarms.get_swarm(&info_hash, None, NOW, 10).await.unwrap(),
+            (vec![], SwarmMetadata::zeroed())
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Busiest torrents.
+//!
+//! Dashboards show the swarms with more peers or more completed downloads.
+//! Ranking the torrents requires a pass over the whole torrent repository, so
+//! the rankings are computed together in a [`Snapshot`], which is cached for
+//! [`TTL`]: the dashboards polling it don't scan the repository on every
+//! request. Only the first [`MAX_LIMIT`] torrents of each ranking are kept.
+use std::cmp::Ordering;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use thiserror::Error;
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+/// How long a snapshot is served from the cache.
+pub const TTL: Duration = Duration::from_secs(30);
+
+/// Maximum number of torrents kept in each ranking.
+pub const MAX_LIMIT: usize = 1000;
+
+/// The order of a ranking. Both are descending. Torrents with the same value
+/// are ordered by infohash.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Ranking {
+    /// By number of peers: seeders and leechers.
+    Peers,
+    /// By number of completed downloads.
+    Completed,
+}
+
+/// Error returned when the ranking can't be parsed.
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid ranking: {value}, expected one of: peers, completed")]
+pub struct ParseRankingError {
+    pub value: String,
+}
+
+impl FromStr for Ranking {
+    type Err = ParseRankingError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "peers" => Ok(Ranking::Peers),
+            "completed" => Ok(Ranking::Completed),
+            _ => Err(ParseRankingError {
+                value: value.to_string(),
+            }),
+        }
+    }
+}
+
+impl Ranking {
+    fn value(self, swarm_metadata: &SwarmMetadata) -> u64 {
+        match self {
+            Ranking::Peers => u64::from(swarm_metadata.complete) + u64::from(swarm_metadata.incomplete),
+            Ranking::Completed => u64::from(swarm_metadata.downloaded),
+        }
+    }
+
+    fn compare(self, a: &(InfoHash, SwarmMetadata), b: &(InfoHash, SwarmMetadata)) -> Ordering {
+        self.value(&b.1).cmp(&self.value(&a.1)).then_with(|| a.0.cmp(&b.0))
+    }
+}
+
+/// The rankings of the torrents at a given time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    /// The torrents with more peers first.
+    pub by_peers: Vec<(InfoHash, SwarmMetadata)>,
+    /// The torrents with more completed downloads first.
+    pub by_completed: Vec<(InfoHash, SwarmMetadata)>,
+    /// When the snapshot was computed.
+    pub computed_at: DurationSinceUnixEpoch,
+}
+
+impl Snapshot {
+    /// It ranks the torrents, keeping the first [`MAX_LIMIT`] of each ranking.
+    #[must_use]
+    pub fn new(torrents: Vec<(InfoHash, SwarmMetadata)>, computed_at: DurationSinceUnixEpoch) -> Self {
+        Self {
+            by_peers: top(torrents.clone(), Ranking::Peers),
+            by_completed: top(torrents, Ranking::Completed),
+            computed_at,
+        }
+    }
+
+    /// It returns the first `limit` torrents of the ranking.
+    #[must_use]
+    pub fn top(&self, ranking: Ranking, limit: usize) -> &[(InfoHash, SwarmMetadata)] {
+        let torrents = match ranking {
+            Ranking::Peers => &self.by_peers,
+            Ranking::Completed => &self.by_completed,
+        };
+
+        &torrents[..limit.min(torrents.len())]
+    }
+}
+
+/// It returns the first [`MAX_LIMIT`] torrents in the ranking order. Only
+/// those are sorted, the rest are just left out.
+fn top(mut torrents: Vec<(InfoHash, SwarmMetadata)>, ranking: Ranking) -> Vec<(InfoHash, SwarmMetadata)> {
+    if torrents.len() > MAX_LIMIT {
+        torrents.select_nth_unstable_by(MAX_LIMIT, |a, b| ranking.compare(a, b));
+        torrents.truncate(MAX_LIMIT);
+    }
+
+    torrents.sort_unstable_by(|a, b| ranking.compare(a, b));
+
+    torrents
+}
+
+/// The last snapshot computed.
+#[derive(Debug, Default)]
+pub struct Cache {
+    snapshot: Mutex<Option<Arc<Snapshot>>>,
+}
+
+impl Cache {
+    /// It returns the cached snapshot, if it was computed less than [`TTL`]
+    /// before `now`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the cached snapshot is poisoned.
+    #[must_use]
+    pub fn get(&self, now: DurationSinceUnixEpoch) -> Option<Arc<Snapshot>> {
+        let snapshot = self.snapshot.lock().expect("it should get a lock on the cached snapshot");
+
+        snapshot.as_ref().filter(|snapshot| snapshot.computed_at + TTL > now).cloned()
+    }
+
+    /// It replaces the cached snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the cached snapshot is poisoned.
+    pub fn insert(&self, snapshot: Arc<Snapshot>) {
+        *self.snapshot.lock().expect("it should get a lock on the cached snapshot") = Some(snapshot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use torrust_tracker_primitives::info_hash::InfoHash;
+    use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
+
+    use super::{Cache, Ranking, Snapshot, MAX_LIMIT, TTL};
+
+    fn info_hash(n: u8) -> InfoHash {
+        InfoHash::from_bytes(&[n; 20])
+    }
+
+    #[test]
+    fn it_should_rank_the_torrents_by_peers_and_by_completed_downloads() {
+        let snapshot = Snapshot::new(
+            vec![
+                (info_hash(1), SwarmMetadata::new(10, 1, 0)),
+                (info_hash(2), SwarmMetadata::new(0, 2, 3)),
+                (info_hash(3), SwarmMetadata::new(10, 0, 1)),
+            ],
+            Duration::ZERO,
+        );
+
+        let info_hashes = |ranking| {
+            snapshot
+                .top(ranking, 10)
+                .iter()
+                .map(|(info_hash, _)| *info_hash)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(info_hashes(Ranking::Peers), vec![info_hash(2), info_hash(1), info_hash(3)]);
+        assert_eq!(
+            info_hashes(Ranking::Completed),
+            vec![info_hash(1), info_hash(3), info_hash(2)]
+        );
+        assert_eq!(snapshot.top(Ranking::Peers, 1).len(), 1);
+    }
+
+    #[test]
+    fn it_should_only_keep_the_first_torrents_of_each_ranking() {
+        let torrents = (0..=MAX_LIMIT)
+            .map(|i| {
+                let mut bytes = [0; 20];
+                bytes[..8].copy_from_slice(&u64::try_from(i).unwrap().to_be_bytes());
+                (
+                    InfoHash::from_bytes(&bytes),
+                    SwarmMetadata::new(u32::try_from(i).unwrap(), 0, 0),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let snapshot = Snapshot::new(torrents, Duration::ZERO);
+
+        assert_eq!(snapshot.by_completed.len(), MAX_LIMIT);
+        assert_eq!(
+            snapshot
+                .by_completed
+                .first()
+                .map(|(_, swarm_metadata)| swarm_metadata.downloaded),
+            Some(u32::try_from(MAX_LIMIT).unwrap())
+        );
+    }
+
+    #[test]
+    fn it_should_return_the_cached_snapshot_until_it_expires() {
+        let cache = Cache::default();
+        let now = Duration::from_secs(1_700_000_000);
+
+        assert_eq!(cache.get(now), None);
+
+        let snapshot = Arc::new(Snapshot {
+            computed_at: now,
+            ..Snapshot::default()
+        });
+
+        cache.insert(snapshot.clone());
+
+        assert_eq!(cache.get(now + TTL - Duration::from_secs(1)), Some(snapshot));
+        assert_eq!(cache.get(now + TTL), None);
+    }
+}
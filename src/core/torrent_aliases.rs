@@ -0,0 +1,276 @@
+//! Aliases between the v1 and v2 infohashes of hybrid torrents.
+//!
+//! Hybrid torrents ([BEP 52](https://www.bittorrent.org/beps/bep_0052.html))
+//! have two infohashes, and clients announce both of them: the v1 infohash and
+//! the v2 infohash truncated to 20 bytes. Each infohash is a different torrent
+//! for the tracker, so the swarm is split in half: peers announcing only one
+//! of the infohashes never get the peers announcing only the other one.
+//!
+//! The tracker can't tell from the infohashes alone that they belong to the
+//! same torrent. A peer announcing one infohash and, right after, another one
+//! with the same peer ID and port is a hint. The two infohashes are linked
+//! when the configured number of peers, from different IP addresses, confirm
+//! the same pair. Each infohash can only be linked to another one.
+//!
+//! Once they are linked, the announce responses for any of them include the
+//! peers of both swarms. The swarm counters are not merged.
+//!
+//! Refer to the [`TorrentAliases`] configuration for more information.
+//!
+//! > **NOTICE**: aliases are only kept in memory. They are lost when the
+//! > tracker is restarted.
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use aquatic_udp_protocol::{AnnounceEvent, PeerId};
+use dashmap::DashMap;
+use torrust_tracker_configuration::TorrentAliases;
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
+
+/// Registry of the linked infohashes.
+#[derive(Debug)]
+pub struct Aliases {
+    confirmations: usize,
+    announce_gap: Duration,
+    /// Last infohash announced by each peer, and when.
+    last_announces: DashMap<(PeerId, SocketAddr), (InfoHash, DurationSinceUnixEpoch)>,
+    /// IP addresses of the peers that announced each pair of infohashes.
+    candidates: DashMap<(InfoHash, InfoHash), HashSet<IpAddr>>,
+    aliases: DashMap<InfoHash, InfoHash>,
+}
+
+impl Aliases {
+    /// It builds the registry from the configuration. It returns `None` if
+    /// the aliases are disabled.
+    #[must_use]
+    pub fn new(config: &TorrentAliases) -> Option<Self> {
+        let confirmations = config.confirmations?;
+
+        Some(Self {
+            confirmations: confirmations as usize,
+            announce_gap: Duration::from_secs(config.announce_gap),
+            last_announces: DashMap::new(),
+            candidates: DashMap::new(),
+            aliases: DashMap::new(),
+        })
+    }
+
+    /// It records the announce of the peer. It returns the linked infohash if
+    /// the announce confirms the alias.
+    pub fn record(&self, info_hash: &InfoHash, peer: &peer::Peer, now: DurationSinceUnixEpoch) -> Option<InfoHash> {
+        if peer.event == AnnounceEvent::Stopped {
+            return None;
+        }
+
+        let (previous, announced_at) = self
+            .last_announces
+            .insert((peer.peer_id, peer.peer_addr), (*info_hash, now))?;
+
+        if previous == *info_hash || now.saturating_sub(announced_at) > self.announce_gap {
+            return None;
+        }
+
+        if self.aliases.contains_key(info_hash) || self.aliases.contains_key(&previous) {
+            return None;
+        }
+
+        let pair = if previous < *info_hash {
+            (previous, *info_hash)
+        } else {
+            (*info_hash, previous)
+        };
+
+        let confirmed = {
+            let mut ips = self.candidates.entry(pair).or_default();
+            ips.insert(peer.peer_addr.ip());
+            ips.len() >= self.confirmations
+        };
+
+        if !confirmed {
+            return None;
+        }
+
+        self.candidates.remove(&pair);
+        self.aliases.insert(pair.0, pair.1);
+        self.aliases.insert(pair.1, pair.0);
+
+        tracing::info!(info_hash = %pair.0, alias = %pair.1, "linked the infohashes of a hybrid torrent");
+
+        Some(previous)
+    }
+
+    /// It returns the infohash linked to the torrent, if any.
+    #[must_use]
+    pub fn get(&self, info_hash: &InfoHash) -> Option<InfoHash> {
+        self.aliases.get(info_hash).map(|alias| *alias.value())
+    }
+
+    /// Number of linked pairs of infohashes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.aliases.len() / 2
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// It forgets the announces older than the announce gap, and the aliases
+    /// and candidates of the torrents that no longer exist.
+    pub fn cleanup(&self, now: DurationSinceUnixEpoch, exists: impl Fn(&InfoHash) -> bool) {
+        self.last_announces
+            .retain(|_, (_, announced_at)| now.saturating_sub(*announced_at) <= self.announce_gap);
+
+        self.candidates.retain(|(one, other), _| exists(one) && exists(other));
+
+        self.aliases.retain(|info_hash, alias| exists(info_hash) || exists(alias));
+    }
+}
+
+/// It merges the peers of a swarm with the peers of the linked swarm. Peers
+/// are taken from both lists alternately, so both swarms are represented in
+/// the result. Peers in both swarms are only returned once.
+#[must_use]
+pub fn merge_peers(peers: Vec<Arc<peer::Peer>>, alias_peers: Vec<Arc<peer::Peer>>, limit: usize) -> Vec<Arc<peer::Peer>> {
+    let mut addresses = HashSet::new();
+    let mut merged = Vec::with_capacity(limit.min(peers.len() + alias_peers.len()));
+
+    let mut peers = peers.into_iter();
+    let mut alias_peers = alias_peers.into_iter();
+
+    loop {
+        let (peer, alias_peer) = (peers.next(), alias_peers.next());
+
+        if peer.is_none() && alias_peer.is_none() {
+            return merged;
+        }
+
+        for peer in [peer, alias_peer].into_iter().flatten() {
+            if merged.len() == limit {
+                return merged;
+            }
+
+            if addresses.insert(peer.peer_addr) {
+                merged.push(peer);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use aquatic_udp_protocol::PeerId;
+    use torrust_tracker_configuration::TorrentAliases;
+    use torrust_tracker_primitives::info_hash::InfoHash;
+    use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+    use torrust_tracker_primitives::peer::Peer;
+
+    use super::{merge_peers, Aliases};
+
+    fn v1() -> InfoHash {
+        "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()
+    }
+
+    fn v2() -> InfoHash {
+        "99c82bb73505a3c0b453f9fa0e881d6e5a32a0c1".parse::<InfoHash>().unwrap()
+    }
+
+    fn aliases(confirmations: u32) -> Aliases {
+        Aliases::new(&TorrentAliases {
+            confirmations: Some(confirmations),
+            announce_gap: 60,
+        })
+        .unwrap()
+    }
+
+    fn peer(ip: u8) -> Peer {
+        PeerBuilder::default()
+            .with_peer_id(&PeerId([ip; 20]))
+            .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, ip)), 8080))
+            .build()
+    }
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn it_should_be_disabled_when_the_confirmations_are_not_set() {
+        assert!(Aliases::new(&TorrentAliases::default()).is_none());
+    }
+
+    #[test]
+    fn it_should_link_the_infohashes_announced_by_the_same_peer_one_right_after_the_other() {
+        let aliases = aliases(1);
+
+        assert_eq!(aliases.record(&v1(), &peer(1), secs(0)), None);
+        assert_eq!(aliases.record(&v2(), &peer(1), secs(1)), Some(v1()));
+
+        assert_eq!(aliases.get(&v1()), Some(v2()));
+        assert_eq!(aliases.get(&v2()), Some(v1()));
+    }
+
+    #[test]
+    fn it_should_not_link_the_infohashes_announced_too_far_apart() {
+        let aliases = aliases(1);
+
+        aliases.record(&v1(), &peer(1), secs(0));
+        aliases.record(&v2(), &peer(1), secs(61));
+
+        assert!(aliases.is_empty());
+    }
+
+    #[test]
+    fn it_should_wait_for_the_confirmations_from_different_ip_addresses() {
+        let aliases = aliases(2);
+
+        aliases.record(&v1(), &peer(1), secs(0));
+        aliases.record(&v2(), &peer(1), secs(1));
+        aliases.record(&v1(), &peer(1), secs(2));
+
+        assert!(aliases.is_empty());
+
+        aliases.record(&v2(), &peer(2), secs(3));
+        aliases.record(&v1(), &peer(2), secs(4));
+
+        assert_eq!(aliases.get(&v1()), Some(v2()));
+    }
+
+    #[test]
+    fn it_should_forget_the_aliases_of_the_removed_torrents() {
+        let aliases = aliases(1);
+
+        aliases.record(&v1(), &peer(1), secs(0));
+        aliases.record(&v2(), &peer(1), secs(1));
+
+        aliases.cleanup(secs(2), |_| false);
+
+        assert!(aliases.is_empty());
+    }
+
+    #[test]
+    fn it_should_merge_the_peers_of_both_swarms_without_duplicates() {
+        let (one, two, three) = (Arc::new(peer(1)), Arc::new(peer(2)), Arc::new(peer(3)));
+
+        let merged = merge_peers(vec![one.clone(), two.clone()], vec![two.clone(), three.clone()], 10);
+
+        assert_eq!(merged, vec![one, two, three]);
+    }
+
+    #[test]
+    fn it_should_take_the_peers_of_both_swarms_alternately_up_to_the_limit() {
+        let (one, two, three) = (Arc::new(peer(1)), Arc::new(peer(2)), Arc::new(peer(3)));
+
+        let merged = merge_peers(vec![one.clone(), two], vec![three.clone()], 2);
+
+        assert_eq!(merged, vec![one, three]);
+    }
+}
@@ -0,0 +1,313 @@
+//! History of the torrents.
+//!
+//! With the `persistent_torrent_history` option, the tracker keeps hourly
+//! counters for each torrent:
+//!
+//! - The number of peers that completed downloading the torrent in the hour.
+//! - The peak number of seeders and leechers in the hour.
+//!
+//! The bucket of the current hour is kept in memory. It's saved to the
+//! database once the hour is over, when the torrent is updated again or by the
+//! history job (which runs on the `inactive_peer_cleanup_interval`), and when
+//! the tracker is shut down. The daily counters are aggregated from the hourly
+//! ones.
+//!
+//! The buckets are saved adding the completed downloads to the ones already
+//! in the database, so the bucket of an hour can be saved more than once (for
+//! example, if the tracker is restarted in the middle of the hour).
+use std::str::FromStr;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use thiserror::Error;
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+/// The counters of a torrent for an hour or a day.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Bucket {
+    /// Start of the period, in seconds since the Unix epoch.
+    pub start: u64,
+    /// Number of peers that completed downloading the torrent in the period.
+    pub completed: u32,
+    /// Peak number of seeders in the period.
+    pub peak_seeders: u32,
+    /// Peak number of leechers in the period.
+    pub peak_leechers: u32,
+}
+
+impl Bucket {
+    #[must_use]
+    pub fn new(start: u64) -> Self {
+        Self {
+            start,
+            completed: 0,
+            peak_seeders: 0,
+            peak_leechers: 0,
+        }
+    }
+
+    /// It adds the counters of another period to this one.
+    pub fn merge(&mut self, other: &Self) {
+        self.completed += other.completed;
+        self.peak_seeders = self.peak_seeders.max(other.peak_seeders);
+        self.peak_leechers = self.peak_leechers.max(other.peak_leechers);
+    }
+}
+
+/// The length of the periods of the history.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Granularity {
+    #[default]
+    Hour,
+    Day,
+}
+
+/// Error returned when the granularity of the history can't be parsed.
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid granularity: {value}, expected one of: hour, day")]
+pub struct ParseGranularityError {
+    pub value: String,
+}
+
+impl FromStr for Granularity {
+    type Err = ParseGranularityError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "hour" => Ok(Granularity::Hour),
+            "day" => Ok(Granularity::Day),
+            _ => Err(ParseGranularityError {
+                value: value.to_string(),
+            }),
+        }
+    }
+}
+
+impl Granularity {
+    #[must_use]
+    pub fn duration(self) -> Duration {
+        match self {
+            Granularity::Hour => Duration::from_secs(60 * 60),
+            Granularity::Day => Duration::from_secs(24 * 60 * 60),
+        }
+    }
+
+    /// It returns the start of the period containing the `time`, in seconds
+    /// since the Unix epoch.
+    #[must_use]
+    pub fn period_start(self, time: DurationSinceUnixEpoch) -> u64 {
+        let seconds = self.duration().as_secs();
+
+        time.as_secs() / seconds * seconds
+    }
+}
+
+/// The buckets of the current hour, not saved yet.
+#[derive(Debug, Default)]
+pub struct History {
+    current: DashMap<InfoHash, Bucket>,
+}
+
+impl History {
+    /// It records an update of the torrent. It returns the bucket of a
+    /// previous hour, if the torrent had one. It has to be saved.
+    pub fn record(
+        &self,
+        info_hash: &InfoHash,
+        before: &SwarmMetadata,
+        after: &SwarmMetadata,
+        now: DurationSinceUnixEpoch,
+    ) -> Option<Bucket> {
+        let start = Granularity::Hour.period_start(now);
+
+        let mut bucket = self.current.entry(*info_hash).or_insert_with(|| Bucket::new(start));
+
+        let closed = if bucket.start < start {
+            Some(std::mem::replace(&mut *bucket, Bucket::new(start)))
+        } else {
+            None
+        };
+
+        bucket.completed += after.downloaded.saturating_sub(before.downloaded);
+        bucket.peak_seeders = bucket.peak_seeders.max(after.complete);
+        bucket.peak_leechers = bucket.peak_leechers.max(after.incomplete);
+
+        closed
+    }
+
+    /// It returns the bucket of the current hour of the torrent, if it has
+    /// been updated in the hour.
+    #[must_use]
+    pub fn current(&self, info_hash: &InfoHash, now: DurationSinceUnixEpoch) -> Option<Bucket> {
+        let start = Granularity::Hour.period_start(now);
+
+        self.current
+            .get(info_hash)
+            .map(|bucket| *bucket)
+            .filter(|bucket| bucket.start == start)
+    }
+
+    /// It removes and returns the buckets of the previous hours.
+    pub fn take_closed(&self, now: DurationSinceUnixEpoch) -> Vec<(InfoHash, Bucket)> {
+        let start = Granularity::Hour.period_start(now);
+
+        let mut closed = vec![];
+
+        self.current.retain(|info_hash, bucket| {
+            if bucket.start < start {
+                closed.push((*info_hash, *bucket));
+                return false;
+            }
+            true
+        });
+
+        closed
+    }
+
+    /// It removes and returns all the buckets, when the tracker is shut down.
+    pub fn take_all(&self) -> Vec<(InfoHash, Bucket)> {
+        let mut all = vec![];
+
+        self.current.retain(|info_hash, bucket| {
+            all.push((*info_hash, *bucket));
+            false
+        });
+
+        all
+    }
+}
+
+/// It aggregates the hourly buckets into buckets with the `granularity`. The
+/// buckets are returned in chronological order.
+#[must_use]
+pub fn aggregate(mut hourly_buckets: Vec<Bucket>, granularity: Granularity) -> Vec<Bucket> {
+    let mut buckets: Vec<Bucket> = vec![];
+
+    hourly_buckets.sort_by_key(|bucket| bucket.start);
+
+    for hourly_bucket in hourly_buckets {
+        let start = granularity.period_start(Duration::from_secs(hourly_bucket.start));
+
+        match buckets.last_mut() {
+            Some(bucket) if bucket.start == start => bucket.merge(&hourly_bucket),
+            _ => {
+                let mut bucket = Bucket::new(start);
+                bucket.merge(&hourly_bucket);
+                buckets.push(bucket);
+            }
+        }
+    }
+
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use torrust_tracker_primitives::info_hash::InfoHash;
+    use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
+
+    use super::{aggregate, Bucket, Granularity, History};
+
+    const MIDNIGHT: u64 = 1_699_920_000;
+
+    fn info_hash() -> InfoHash {
+        "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()
+    }
+
+    fn swarm(seeders: u32, completed: u32, leechers: u32) -> SwarmMetadata {
+        SwarmMetadata {
+            complete: seeders,
+            downloaded: completed,
+            incomplete: leechers,
+        }
+    }
+
+    #[test]
+    fn it_should_count_the_completed_downloads_and_the_peaks_of_the_current_hour() {
+        let history = History::default();
+        let now = Duration::from_secs(MIDNIGHT + 60);
+
+        assert_eq!(history.record(&info_hash(), &swarm(0, 0, 0), &swarm(0, 0, 2), now), None);
+        assert_eq!(history.record(&info_hash(), &swarm(0, 0, 2), &swarm(1, 1, 1), now), None);
+
+        assert_eq!(
+            history.current(&info_hash(), now),
+            Some(Bucket {
+                start: MIDNIGHT,
+                completed: 1,
+                peak_seeders: 1,
+                peak_leechers: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn it_should_return_the_bucket_of_the_previous_hour_when_the_hour_is_over() {
+        let history = History::default();
+
+        assert_eq!(
+            history.record(&info_hash(), &swarm(0, 0, 0), &swarm(1, 1, 0), Duration::from_secs(MIDNIGHT)),
+            None
+        );
+
+        let one_hour_later = Duration::from_secs(MIDNIGHT + 60 * 60);
+
+        assert_eq!(
+            history.record(&info_hash(), &swarm(1, 1, 0), &swarm(1, 1, 1), one_hour_later),
+            Some(Bucket {
+                start: MIDNIGHT,
+                completed: 1,
+                peak_seeders: 1,
+                peak_leechers: 0,
+            })
+        );
+        assert!(history.take_closed(one_hour_later).is_empty());
+        assert_eq!(history.take_all().len(), 1);
+    }
+
+    #[test]
+    fn it_should_aggregate_the_hourly_buckets_by_day() {
+        let hourly_buckets = vec![
+            Bucket {
+                start: MIDNIGHT + 24 * 60 * 60,
+                completed: 4,
+                peak_seeders: 1,
+                peak_leechers: 1,
+            },
+            Bucket {
+                start: MIDNIGHT,
+                completed: 1,
+                peak_seeders: 5,
+                peak_leechers: 1,
+            },
+            Bucket {
+                start: MIDNIGHT + 60 * 60,
+                completed: 2,
+                peak_seeders: 3,
+                peak_leechers: 7,
+            },
+        ];
+
+        assert_eq!(
+            aggregate(hourly_buckets, Granularity::Day),
+            vec![
+                Bucket {
+                    start: MIDNIGHT,
+                    completed: 3,
+                    peak_seeders: 5,
+                    peak_leechers: 7,
+                },
+                Bucket {
+                    start: MIDNIGHT + 24 * 60 * 60,
+                    completed: 4,
+                    peak_seeders: 1,
+                    peak_leechers: 1,
+                },
+            ]
+        );
+    }
+}
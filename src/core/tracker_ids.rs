@@ -0,0 +1,152 @@
+//! Tracker ids of the HTTP announce responses.
+//!
+//! From the [`BitTorrent` specification wiki](https://wiki.theory.org/BitTorrentSpecification#Tracker_Response):
+//! the tracker can include a `tracker id` in the announce responses, and the
+//! clients must send it back in the `trackerid` param of their next
+//! announces to the same tracker.
+//!
+//! The tracker ids are generated and validated by a [`Scheme`]. The tracker
+//! uses the one selected in the
+//! [`TrackerIdScheme`](torrust_tracker_configuration::TrackerIdScheme)
+//! configuration option, but any other implementation can be set with
+//! [`Tracker::set_tracker_id_scheme`](crate::core::Tracker::set_tracker_id_scheme).
+//!
+//! The tracker checks the tracker id of the announces (except the `started`
+//! ones) to correlate the announces of a session, and to detect the clients
+//! ignoring it. See the [`Check`] results.
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use aquatic_udp_protocol::PeerId;
+use torrust_tracker_configuration::{Core, TrackerIdScheme};
+use torrust_tracker_primitives::info_hash::InfoHash;
+
+use crate::shared::crypto::ephemeral_instance_keys::{Seed, RANDOM_SEED};
+
+/// It generates the tracker ids issued to the peers and validates the ones
+/// sent back by the clients.
+pub trait Scheme: Send + Sync {
+    /// It returns the tracker id issued to the peer of the torrent, if any.
+    fn issue(&self, info_hash: &InfoHash, peer_id: &PeerId) -> Option<String>;
+
+    /// It returns `true` if the tracker id sent by the client is the one
+    /// issued to the peer of the torrent.
+    fn validate(&self, tracker_id: &str, info_hash: &InfoHash, peer_id: &PeerId) -> bool {
+        self.issue(info_hash, peer_id).is_some_and(|issued| issued == tracker_id)
+    }
+}
+
+/// It returns the scheme selected in the configuration.
+#[must_use]
+pub fn from_config(config: &Core) -> Box<dyn Scheme> {
+    match config.tracker_id_scheme {
+        TrackerIdScheme::InstanceId => Box::new(InstanceId::new(config.instance_id.clone())),
+        TrackerIdScheme::PerPeer => Box::new(PerPeer::new(config.instance_id.clone(), *RANDOM_SEED)),
+    }
+}
+
+/// The instance id is the tracker id of all the peers.
+#[derive(Debug, Clone)]
+pub struct InstanceId {
+    instance_id: Option<String>,
+}
+
+impl InstanceId {
+    #[must_use]
+    pub fn new(instance_id: Option<String>) -> Self {
+        Self { instance_id }
+    }
+}
+
+impl Scheme for InstanceId {
+    fn issue(&self, _info_hash: &InfoHash, _peer_id: &PeerId) -> Option<String> {
+        self.instance_id.clone()
+    }
+}
+
+/// Each peer of each torrent gets its own tracker id: the hash of the
+/// infohash, the peer ID and the secret, prefixed with the instance id.
+#[derive(Debug, Clone)]
+pub struct PerPeer {
+    instance_id: Option<String>,
+    secret: Seed,
+}
+
+impl PerPeer {
+    #[must_use]
+    pub fn new(instance_id: Option<String>, secret: Seed) -> Self {
+        Self { instance_id, secret }
+    }
+}
+
+impl Scheme for PerPeer {
+    fn issue(&self, info_hash: &InfoHash, peer_id: &PeerId) -> Option<String> {
+        let mut hasher = DefaultHasher::new();
+        self.secret.hash(&mut hasher);
+        info_hash.hash(&mut hasher);
+        peer_id.0.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        Some(match &self.instance_id {
+            Some(instance_id) => format!("{instance_id}-{hash:016x}"),
+            None => format!("{hash:016x}"),
+        })
+    }
+}
+
+/// The result of checking the tracker id of an announce.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Check {
+    /// The client sent back the tracker id issued to the peer: the announce
+    /// belongs to a known session.
+    Valid,
+    /// The client sent a tracker id which was not issued to the peer. For
+    /// example, after the secret changed when the tracker was restarted.
+    Invalid,
+    /// The client did not send the tracker id back, ignoring the
+    /// specification.
+    Missing,
+}
+
+#[cfg(test)]
+mod tests {
+    use aquatic_udp_protocol::PeerId;
+    use torrust_tracker_primitives::info_hash::InfoHash;
+
+    use super::{InstanceId, PerPeer, Scheme};
+
+    fn sample_info_hash() -> InfoHash {
+        "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()
+    }
+
+    #[test]
+    fn it_should_issue_the_instance_id_to_all_the_peers() {
+        let scheme = InstanceId::new(Some("tracker-1".to_string()));
+
+        assert_eq!(
+            scheme.issue(&sample_info_hash(), &PeerId(*b"-qB00000000000000001")),
+            Some("tracker-1".to_string())
+        );
+        assert!(scheme.validate("tracker-1", &sample_info_hash(), &PeerId(*b"-qB00000000000000002")));
+    }
+
+    #[test]
+    fn it_should_issue_a_different_tracker_id_to_each_peer() {
+        let scheme = PerPeer::new(Some("tracker-1".to_string()), [0; 32]);
+
+        let tracker_id = scheme.issue(&sample_info_hash(), &PeerId(*b"-qB00000000000000001")).unwrap();
+
+        assert!(tracker_id.starts_with("tracker-1-"));
+        assert!(scheme.validate(&tracker_id, &sample_info_hash(), &PeerId(*b"-qB00000000000000001")));
+        assert!(!scheme.validate(&tracker_id, &sample_info_hash(), &PeerId(*b"-qB00000000000000002")));
+    }
+
+    #[test]
+    fn it_should_not_validate_the_tracker_ids_issued_with_another_secret() {
+        let tracker_id = PerPeer::new(None, [0; 32])
+            .issue(&sample_info_hash(), &PeerId(*b"-qB00000000000000001"))
+            .unwrap();
+
+        assert!(!PerPeer::new(None, [1; 32]).validate(&tracker_id, &sample_info_hash(), &PeerId(*b"-qB00000000000000001")));
+    }
+}
@@ -0,0 +1,171 @@
+//! Warning messages for the HTTP `announce` responses.
+//!
+//! A warning message is a non-fatal message added to the `warning message`
+//! field of the response: the announce is processed as usual, and clients
+//! show the message to the user. For example: "your client is outdated".
+//!
+//! There are two sources of warning messages:
+//!
+//! - Messages set for an authentication key or a torrent, for example with
+//!   the [`warning`](crate::servers::apis::v1::context::warning) API context.
+//! - [`WarningPolicy`] hooks registered in the tracker, which decide the
+//!   message for each announce.
+//!
+//! The message set for the key takes precedence over the message set for the
+//! torrent, and both over the policies, which are checked in the order they
+//! were registered.
+//!
+//! > **NOTICE**: warning messages are only kept in memory. They are lost when
+//! > the tracker is restarted.
+use std::sync::{Arc, RwLock};
+
+use dashmap::DashMap;
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::peer;
+
+use super::auth::Key;
+
+/// A hook to decide the warning message of an announce response.
+pub trait WarningPolicy: Send + Sync {
+    /// It returns the warning message for the announce of the `peer`, if any.
+    /// The `key` is the authentication key of the request, if it contains
+    /// one.
+    fn warning_message(&self, info_hash: &InfoHash, peer: &peer::Peer, key: Option<&Key>) -> Option<String>;
+}
+
+/// The warning messages set for keys and torrents, and the registered
+/// policies.
+#[derive(Default)]
+pub struct Warnings {
+    by_key: DashMap<Key, String>,
+    by_torrent: DashMap<InfoHash, String>,
+    policies: RwLock<Vec<Arc<dyn WarningPolicy>>>,
+}
+
+impl Warnings {
+    pub fn set_for_key(&self, key: &Key, message: String) {
+        self.by_key.insert(key.clone(), message);
+    }
+
+    /// It returns `true` if the key had a warning message.
+    pub fn remove_for_key(&self, key: &Key) -> bool {
+        self.by_key.remove(key).is_some()
+    }
+
+    pub fn set_for_torrent(&self, info_hash: &InfoHash, message: String) {
+        self.by_torrent.insert(*info_hash, message);
+    }
+
+    /// It returns `true` if the torrent had a warning message.
+    pub fn remove_for_torrent(&self, info_hash: &InfoHash) -> bool {
+        self.by_torrent.remove(info_hash).is_some()
+    }
+
+    /// # Panics
+    ///
+    /// Will panic if the lock on the policies is poisoned.
+    pub fn add_policy(&self, policy: Arc<dyn WarningPolicy>) {
+        self.policies
+            .write()
+            .expect("it should get a lock on the warning policies")
+            .push(policy);
+    }
+
+    /// It returns the warning message for the announce of the `peer`, if any.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the policies is poisoned.
+    #[must_use]
+    pub fn get(&self, info_hash: &InfoHash, peer: &peer::Peer, key: Option<&Key>) -> Option<String> {
+        if let Some(message) = key.and_then(|key| self.by_key.get(key)) {
+            return Some(message.value().clone());
+        }
+
+        if let Some(message) = self.by_torrent.get(info_hash) {
+            return Some(message.value().clone());
+        }
+
+        self.policies
+            .read()
+            .expect("it should get a lock on the warning policies")
+            .iter()
+            .find_map(|policy| policy.warning_message(info_hash, peer, key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use torrust_tracker_primitives::info_hash::InfoHash;
+    use torrust_tracker_primitives::peer;
+    use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+
+    use super::{WarningPolicy, Warnings};
+    use crate::core::auth::Key;
+
+    struct OutdatedClients;
+
+    impl WarningPolicy for OutdatedClients {
+        fn warning_message(&self, _info_hash: &InfoHash, peer: &peer::Peer, _key: Option<&Key>) -> Option<String> {
+            peer.peer_id
+                .0
+                .starts_with(b"-qB")
+                .then(|| "your client is outdated".to_string())
+        }
+    }
+
+    fn sample_info_hash() -> InfoHash {
+        "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()
+    }
+
+    fn sample_key() -> Key {
+        Key::from_str("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap()
+    }
+
+    #[test]
+    fn it_should_not_return_any_warning_message_by_default() {
+        let warnings = Warnings::default();
+
+        assert_eq!(
+            warnings.get(&sample_info_hash(), &PeerBuilder::default().build(), Some(&sample_key())),
+            None
+        );
+    }
+
+    #[test]
+    fn the_warning_message_of_the_key_should_take_precedence_over_the_one_of_the_torrent() {
+        let warnings = Warnings::default();
+        let peer = PeerBuilder::default().build();
+
+        warnings.set_for_torrent(&sample_info_hash(), "torrent".to_string());
+        warnings.set_for_key(&sample_key(), "ratio low".to_string());
+
+        assert_eq!(
+            warnings.get(&sample_info_hash(), &peer, Some(&sample_key())),
+            Some("ratio low".to_string())
+        );
+        assert_eq!(warnings.get(&sample_info_hash(), &peer, None), Some("torrent".to_string()));
+
+        assert!(warnings.remove_for_key(&sample_key()));
+
+        assert_eq!(
+            warnings.get(&sample_info_hash(), &peer, Some(&sample_key())),
+            Some("torrent".to_string())
+        );
+    }
+
+    #[test]
+    fn it_should_ask_the_policies_when_there_is_no_warning_message_set() {
+        let warnings = Warnings::default();
+
+        warnings.add_policy(Arc::new(OutdatedClients));
+
+        assert_eq!(
+            warnings.get(&sample_info_hash(), &PeerBuilder::default().build(), None),
+            Some("your client is outdated".to_string())
+        );
+    }
+}
@@ -32,6 +32,7 @@
 //!     - [Install from sources](#install-from-sources)
 //!     - [Run with docker](#run-with-docker)
 //! - [Configuration](#configuration)
+//!     - [Socket activation](#socket-activation)
 //! - [Usage](#usage)
 //!     - [API](#api)
 //!     - [HTTP Tracker](#http-tracker)
@@ -152,6 +153,23 @@
 //! cargo run
 //! ```
 //!
+//! The pending migrations of the database schema are applied when the tracker
+//! starts. They can also be applied without starting the tracker, for example
+//! when the automatic migrations are disabled with the `auto_migrate` option:
+//!
+//! ```text
+//! cargo run -- migrate
+//! ```
+//!
+//! The whitelist, the keys and the persistent stats can be exported from the
+//! database and imported into another one, for example, to move from `SQLite3`
+//! to `MySQL`. Refer to the [`dump`](crate::core::databases::dump) module:
+//!
+//! ```text
+//! cargo run -- db export --format json --output dump.json
+//! cargo run -- db import --format json --input dump.json
+//! ```
+//!
 //! ## Run with docker
 //!
 //! You can run the tracker with a pre-built docker image. Please refer to the
@@ -224,6 +242,42 @@
 //! > IMPORTANT: Every time you change the configuration you need to restart the
 //! > service.
 //!
+//! ## Socket activation
+//!
+//! The tracker can use listening sockets passed by systemd
+//! ([socket activation](https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html))
+//! for the UDP trackers, the HTTP trackers, the API and the Health Check API.
+//! The sockets are matched to the services by their `bind_address`. That
+//! allows binding privileged ports (like `80` or `443`) without running the
+//! tracker as root, and restarting the tracker without dropping the sockets.
+//!
+//! For example, with this `torrust-tracker.socket` unit:
+//!
+//! ```text
+//! [Socket]
+//! ListenDatagram=0.0.0.0:6969
+//! ListenStream=0.0.0.0:80
+//!
+//! [Install]
+//! WantedBy=sockets.target
+//! ```
+//!
+//! and this configuration:
+//!
+//! ```toml
+//! [[udp_trackers]]
+//! bind_address = "0.0.0.0:6969"
+//!
+//! [[http_trackers]]
+//! bind_address = "0.0.0.0:80"
+//! ```
+//!
+//! the tracker started by the `torrust-tracker.service` unit (with a
+//! non-privileged `User=`) uses the sockets bound by systemd. Services without
+//! a matching socket bind their own socket. Refer to the
+//! [socket activation module](crate::servers::socket_activation) for more
+//! information.
+//!
 //! # Usage
 //!
 //! Running the tracker with the default configuration and enabling the UDP and
@@ -265,6 +319,8 @@
 //!     "seeders": 0,
 //!     "completed": 0,
 //!     "leechers": 0,
+//!     "uploaded_bytes_per_second": 0,
+//!     "downloaded_bytes_per_second": 0,
 //!     "tcp4_connections_handled": 0,
 //!     "tcp4_announces_handled": 0,
 //!     "tcp4_scrapes_handled": 0,
@@ -276,7 +332,18 @@
 //!     "udp4_scrapes_handled": 0,
 //!     "udp6_connections_handled": 0,
 //!     "udp6_announces_handled": 0,
-//!     "udp6_scrapes_handled": 0
+//!     "udp6_scrapes_handled": 0,
+//!     "tcp_forwarded_announces_handled": 0,
+//!     "tcp_forwarded_ip_proxy_changes": 0,
+//!     "tcp_announces_with_tracker_id": 0,
+//!     "tcp_announces_with_invalid_tracker_id": 0,
+//!     "tcp_announces_without_tracker_id": 0,
+//!     "api_requests_rate_limited": 0,
+//!     "api_requests_locked_out": 0,
+//!     "api_failed_auth_attempts": 0,
+//!     "api_lockouts": 0,
+//!     "scrape_cache_hits": 0,
+//!     "scrape_cache_misses": 0
 //! }
 //! ```
 //!
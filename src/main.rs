@@ -1,19 +1,150 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use torrust_tracker::core::databases::dump::{self, Dump};
+use torrust_tracker::servers::signals::global_shutdown_signal;
 use torrust_tracker::{app, bootstrap};
 
-#[tokio::main]
-async fn main() {
-    let (config, tracker) = bootstrap::app::setup();
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Apply the pending migrations of the database schema and exit.
+    Migrate,
+    /// Export or import the whitelist, the keys and the persistent stats of the database.
+    Db {
+        #[command(subcommand)]
+        command: DbCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DbCommand {
+    /// Export the contents of the database.
+    Export {
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+        /// The file to write the dump to. Defaults to the standard output.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Import a dump into the database.
+    Import {
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+        /// The file to read the dump from. Defaults to the standard input.
+        #[arg(long)]
+        input: Option<PathBuf>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Format {
+    Json,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let config = bootstrap::app::load_configuration();
+
+    if let Some(Command::Migrate) = args.command {
+        bootstrap::app::initialize_logging(&config);
+
+        let applied = bootstrap::app::migrate_database(&config).expect("it should migrate the database");
+
+        tracing::info!("Applied {} database migrations.", applied.len());
+
+        return;
+    }
+
+    if let Some(Command::Db { command }) = args.command {
+        let runtime = bootstrap::runtime::build(&config.runtime).expect("it should build the async runtime");
+
+        match command {
+            DbCommand::Export {
+                format: Format::Json,
+                output,
+            } => {
+                let dump = runtime
+                    .block_on(bootstrap::app::export_database(&config))
+                    .expect("it should export the database");
+
+                // The logs go to the standard output, so they are only enabled
+                // when the dump is written to a file.
+                let writer: Box<dyn Write> = match output {
+                    Some(path) => {
+                        bootstrap::app::initialize_logging(&config);
+                        Box::new(File::create(path).expect("it should create the output file"))
+                    }
+                    None => Box::new(io::stdout()),
+                };
+
+                let mut writer = BufWriter::new(writer);
 
-    let jobs = app::start(&config, tracker).await;
+                serde_json::to_writer_pretty(&mut writer, &dump).expect("it should write the dump");
+                writer.flush().expect("it should write the dump");
 
-    // handle the signals
-    tokio::select! {
-        _ = tokio::signal::ctrl_c() => {
-            tracing::info!("Torrust shutting down ...");
+                tracing::info!(
+                    "Exported {} whitelisted torrents, {} keys and {} torrent stats.",
+                    dump.whitelist.len(),
+                    dump.keys.len(),
+                    dump.torrents.len()
+                );
+            }
+            DbCommand::Import {
+                format: Format::Json,
+                input,
+            } => {
+                bootstrap::app::initialize_logging(&config);
 
-            // Await for all jobs to shutdown
-            futures::future::join_all(jobs).await;
-            tracing::info!("Torrust successfully shutdown.");
+                let dump: Dump = match input {
+                    Some(path) => {
+                        serde_json::from_reader(BufReader::new(File::open(path).expect("it should open the input file")))
+                    }
+                    None => serde_json::from_reader(io::stdin().lock()),
+                }
+                .expect("it should read the dump");
+
+                assert_eq!(dump.version, dump::VERSION, "unsupported version of the dump format");
+
+                let imported = runtime
+                    .block_on(bootstrap::app::import_database(&config, dump))
+                    .expect("it should import the dump");
+
+                tracing::info!(
+                    "Imported {} whitelisted torrents, {} keys and {} torrent stats.",
+                    imported.whitelist,
+                    imported.keys,
+                    imported.torrents
+                );
+            }
         }
+
+        return;
     }
+
+    let runtime = bootstrap::runtime::build(&config.runtime).expect("it should build the async runtime");
+
+    runtime.block_on(async {
+        let (config, tracker) = bootstrap::app::setup_with_configuration(config);
+
+        let jobs = app::start(&config, tracker.clone()).await;
+
+        // handle the signals
+        global_shutdown_signal().await;
+
+        tracing::info!("Torrust shutting down ...");
+
+        app::stop(jobs, &tracker).await;
+
+        tracing::info!("Torrust successfully shutdown.");
+    });
 }
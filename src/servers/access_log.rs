@@ -0,0 +1,377 @@
+//! Access log of the HTTP servers.
+//!
+//! When it's enabled in the configuration of an HTTP tracker or the tracker
+//! API, the server writes a line to the log file for each request, so the
+//! tracker traffic can be processed by the usual log analysis tools.
+//!
+//! There are two formats. The Apache Combined Log Format, followed by the
+//! latency in milliseconds and the infohashes of the request (`-` if there
+//! are none):
+//!
+//! ```text
+//! 126.0.0.1 - - [10/Oct/2024:13:55:36 +0000] "GET /announce HTTP/1.1" 200 104 "-" "qBittorrent/4.5.2" 1 3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0
+//! ```
+//!
+//! And a JSON object per line:
+//!
+//! ```json
+//! {"time":"2024-10-10T13:55:36Z","client_ip":"126.0.0.1","method":"GET","path":"/announce","version":"HTTP/1.1","status":200,"bytes":104,"referer":null,"user_agent":"qBittorrent/4.5.2","latency_ms":1,"info_hashes":["3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0"]}
+//! ```
+//!
+//! Only the path of the URL is logged. The query is left out because it can
+//! contain secrets, like the API access token.
+//!
+//! The file is rotated when it reaches the configured size. Refer to the
+//! [`AccessLog`](torrust_tracker_configuration::AccessLog) configuration for
+//! more information.
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::middleware::Next;
+use axum::response::Response;
+use camino::{Utf8Path, Utf8PathBuf};
+use hyper::header::{CONTENT_LENGTH, REFERER, USER_AGENT};
+use hyper::Request;
+use serde_json::json;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_clock::conv::convert_from_timestamp_to_datetime_utc;
+use torrust_tracker_configuration::{AccessLog as AccessLogConfig, AccessLogFormat};
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+use crate::servers::http::percent_encoding::percent_decode_info_hash;
+use crate::servers::http::v1::query::Query;
+use crate::CurrentClock;
+
+const BYTES_PER_MIB: u64 = 1024 * 1024;
+
+/// A request handled by the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub time: DurationSinceUnixEpoch,
+    pub client_ip: Option<IpAddr>,
+    pub method: String,
+    pub path: String,
+    pub version: String,
+    pub status: u16,
+    /// The length of the response body, if it's known.
+    pub bytes: Option<u64>,
+    pub referer: Option<String>,
+    pub user_agent: Option<String>,
+    pub latency: Duration,
+    /// The infohashes in the `info_hash` params of the query.
+    pub info_hashes: Vec<InfoHash>,
+}
+
+impl Entry {
+    /// It returns the entry as a line in the Apache Combined Log Format,
+    /// followed by the latency and the infohashes.
+    #[must_use]
+    pub fn to_combined(&self) -> String {
+        let time = convert_from_timestamp_to_datetime_utc(self.time).format("%d/%b/%Y:%H:%M:%S %z");
+        let client_ip = self.client_ip.map_or_else(|| "-".to_string(), |ip| ip.to_string());
+        let bytes = self.bytes.map_or_else(|| "-".to_string(), |bytes| bytes.to_string());
+        let info_hashes = if self.info_hashes.is_empty() {
+            "-".to_string()
+        } else {
+            self.info_hashes
+                .iter()
+                .map(InfoHash::to_hex_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        format!(
+            "{client_ip} - - [{time}] \"{} {} {}\" {} {bytes} \"{}\" \"{}\" {} {info_hashes}",
+            self.method,
+            escape(&self.path),
+            self.version,
+            self.status,
+            self.referer.as_deref().map_or_else(|| "-".to_string(), escape),
+            self.user_agent.as_deref().map_or_else(|| "-".to_string(), escape),
+            self.latency.as_millis(),
+        )
+    }
+
+    /// It returns the entry as a JSON object in a single line.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        json!({
+            "time": convert_from_timestamp_to_datetime_utc(self.time).to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "client_ip": self.client_ip,
+            "method": self.method,
+            "path": self.path,
+            "version": self.version,
+            "status": self.status,
+            "bytes": self.bytes,
+            "referer": self.referer,
+            "user_agent": self.user_agent,
+            "latency_ms": u64::try_from(self.latency.as_millis()).unwrap_or(u64::MAX),
+            "info_hashes": self.info_hashes.iter().map(InfoHash::to_hex_string).collect::<Vec<_>>(),
+        })
+        .to_string()
+    }
+}
+
+/// It escapes the quotes and backslashes of the quoted fields of the
+/// combined format.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The access log file of a server.
+#[derive(Debug)]
+pub struct AccessLog {
+    format: AccessLogFormat,
+    file: Mutex<RotatingFile>,
+}
+
+impl AccessLog {
+    /// It opens the log file, creating it and its directory if they don't
+    /// exist. New entries are appended to the file.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can't be opened.
+    pub fn open(config: &AccessLogConfig) -> io::Result<Self> {
+        Ok(Self {
+            format: config.format,
+            file: Mutex::new(RotatingFile::open(
+                &config.path,
+                config.max_size_mib.saturating_mul(BYTES_PER_MIB),
+                config.max_files,
+            )?),
+        })
+    }
+
+    /// It writes the entry to the log file. Errors are logged but they don't
+    /// affect the request.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the file is poisoned.
+    pub fn write(&self, entry: &Entry) {
+        let line = match self.format {
+            AccessLogFormat::Combined => entry.to_combined(),
+            AccessLogFormat::Json => entry.to_json(),
+        };
+
+        if let Err(err) = self
+            .file
+            .lock()
+            .expect("it should get a lock on the access log file")
+            .write_line(&line)
+        {
+            tracing::error!(%err, "failed to write to the access log");
+        }
+    }
+}
+
+/// A log file rotated when it reaches the maximum size.
+#[derive(Debug)]
+struct RotatingFile {
+    path: Utf8PathBuf,
+    file: File,
+    size: u64,
+    /// Maximum size in bytes. It's never rotated if it's `0`.
+    max_size: u64,
+    max_files: u32,
+}
+
+impl RotatingFile {
+    fn open(path: &Utf8Path, max_size: u64, max_files: u32) -> io::Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path: path.to_owned(),
+            file,
+            size,
+            max_size,
+            max_files,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+
+        if self.max_size > 0 && self.size > 0 && self.size + len > self.max_size {
+            self.rotate()?;
+        }
+
+        self.file.write_all(format!("{line}\n").as_bytes())?;
+        self.size += len;
+
+        Ok(())
+    }
+
+    /// It shifts the rotated files, `path.1` to `path.2` and so on, removing
+    /// the oldest one, and moves the current file to `path.1`.
+    fn rotate(&mut self) -> io::Result<()> {
+        let rotated = |n: u32| format!("{}.{n}", self.path);
+
+        if self.max_files == 0 {
+            self.file = File::create(&self.path)?;
+            self.size = 0;
+            return Ok(());
+        }
+
+        ignore_not_found(fs::remove_file(rotated(self.max_files)))?;
+
+        for n in (1..self.max_files).rev() {
+            ignore_not_found(fs::rename(rotated(n), rotated(n + 1)))?;
+        }
+
+        fs::rename(&self.path, rotated(1))?;
+
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+
+        Ok(())
+    }
+}
+
+fn ignore_not_found(result: io::Result<()>) -> io::Result<()> {
+    match result {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// It runs the request and writes its entry to the access log. The
+/// `client_ip` is resolved by each server.
+pub async fn log_request(access_log: &AccessLog, client_ip: Option<IpAddr>, request: Request<Body>, next: Next) -> Response {
+    let time = CurrentClock::now();
+    let started = std::time::Instant::now();
+
+    let header = |name| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string)
+    };
+
+    let mut entry = Entry {
+        time,
+        client_ip,
+        method: request.method().to_string(),
+        path: request.uri().path().to_string(),
+        version: format!("{:?}", request.version()),
+        status: 0,
+        bytes: None,
+        referer: header(REFERER),
+        user_agent: header(USER_AGENT),
+        latency: Duration::ZERO,
+        info_hashes: info_hashes_in(request.uri().query()),
+    };
+
+    let response = next.run(request).await;
+
+    entry.status = response.status().as_u16();
+    entry.bytes = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok());
+    entry.latency = started.elapsed();
+
+    access_log.write(&entry);
+
+    response
+}
+
+/// It returns the valid infohashes in the `info_hash` params of the query.
+fn info_hashes_in(raw_query: Option<&str>) -> Vec<InfoHash> {
+    raw_query
+        .and_then(|raw_query| raw_query.parse::<Query>().ok())
+        .and_then(|query| query.get_param_vec("info_hash"))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|raw_info_hash| percent_decode_info_hash(raw_info_hash).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Duration;
+
+    use torrust_tracker_clock::conv::convert_from_iso_8601_to_timestamp;
+    use torrust_tracker_primitives::info_hash::InfoHash;
+
+    use super::{info_hashes_in, Entry, RotatingFile};
+
+    fn sample_entry() -> Entry {
+        Entry {
+            time: convert_from_iso_8601_to_timestamp("2024-10-10T13:55:36Z"),
+            client_ip: Some(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1))),
+            method: "GET".to_string(),
+            path: "/announce".to_string(),
+            version: "HTTP/1.1".to_string(),
+            status: 200,
+            bytes: Some(104),
+            referer: None,
+            user_agent: Some("qBittorrent/4.5.2".to_string()),
+            latency: Duration::from_millis(1),
+            info_hashes: vec!["3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()],
+        }
+    }
+
+    #[test]
+    fn it_should_format_an_entry_in_the_combined_log_format() {
+        assert_eq!(
+            sample_entry().to_combined(),
+            "126.0.0.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /announce HTTP/1.1\" 200 104 \"-\" \"qBittorrent/4.5.2\" 1 3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0"
+        );
+    }
+
+    #[test]
+    fn it_should_format_an_entry_as_json() {
+        let json: serde_json::Value = serde_json::from_str(&sample_entry().to_json()).unwrap();
+
+        assert_eq!(json["time"], "2024-10-10T13:55:36Z");
+        assert_eq!(json["client_ip"], "126.0.0.1");
+        assert_eq!(json["status"], 200);
+        assert_eq!(json["latency_ms"], 1);
+        assert_eq!(json["info_hashes"][0], "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0");
+    }
+
+    #[test]
+    fn it_should_get_the_infohashes_from_the_query() {
+        let info_hashes = info_hashes_in(Some(
+            "info_hash=%3B%24U%04%CF%5F%11%BB%DB%E1%20%1C%EAjk%F4Z%EE%1B%C0&port=17548",
+        ));
+
+        assert_eq!(
+            info_hashes,
+            vec!["3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()]
+        );
+    }
+
+    #[test]
+    fn it_should_rotate_the_file_when_it_reaches_the_maximum_size() {
+        let dir = std::env::temp_dir().join(format!("access_log_{}", rand::random::<u64>()));
+        let path = camino::Utf8PathBuf::from_path_buf(dir.join("access.log")).unwrap();
+
+        let mut file = RotatingFile::open(&path, 10, 1).unwrap();
+
+        file.write_line("first").unwrap();
+        file.write_line("second").unwrap();
+        file.write_line("third").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "third\n");
+        assert_eq!(std::fs::read_to_string(format!("{path}.1")).unwrap(), "second\n");
+        assert!(!std::path::Path::new(&format!("{path}.2")).exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
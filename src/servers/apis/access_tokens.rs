@@ -0,0 +1,202 @@
+//! Access tokens of the API.
+//!
+//! The store has the access tokens of the configuration and the ones that
+//! replaced them at runtime. A label can be authenticated with:
+//!
+//! - Its current token: the last rotated one or, if it has never been
+//!   rotated, the one in the configuration.
+//! - Its previous token, until the end of the overlap period of the last
+//!   rotation.
+//!
+//! The rotated tokens are saved to the database by the
+//! [`token`](crate::servers::apis::v1::context::token) API endpoint. Refer to
+//! the [`api_tokens`](crate::core::api_tokens) module for more information.
+//!
+//! Other tracker instances sharing the database don't know about a rotation
+//! until they reload the rotated tokens. They are reloaded when a request
+//! has an unknown token, at most once every [`RELOAD_INTERVAL`], so a flood
+//! of invalid tokens does not flood the database.
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use torrust_tracker_configuration::AccessTokens;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+use crate::core::api_tokens::RotatedToken;
+
+/// Length of the generated tokens.
+pub const TOKEN_LENGTH: usize = 32;
+
+/// Minimum time between two reloads of the rotated tokens.
+pub const RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The access tokens of the API, by label.
+#[derive(Debug)]
+pub struct AccessTokenStore {
+    configured: AccessTokens,
+    rotated: RwLock<HashMap<String, RotatedToken>>,
+    last_reload: Mutex<Option<DurationSinceUnixEpoch>>,
+}
+
+impl AccessTokenStore {
+    #[must_use]
+    pub fn new(configured: &AccessTokens) -> Self {
+        Self {
+            configured: configured.clone(),
+            rotated: RwLock::default(),
+            last_reload: Mutex::default(),
+        }
+    }
+
+    /// It replaces the rotated tokens with the ones loaded from the database.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the rotated tokens is poisoned.
+    pub fn load(&self, rotated_tokens: Vec<RotatedToken>) {
+        let mut rotated = self.rotated.write().expect("it should get a lock on the rotated tokens");
+
+        *rotated = rotated_tokens
+            .into_iter()
+            .map(|rotated_token| (rotated_token.label.clone(), rotated_token))
+            .collect();
+    }
+
+    /// It returns the label of the token, if it's the current or the previous
+    /// token of a label in the configuration.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the rotated tokens is poisoned.
+    #[must_use]
+    pub fn authenticate(&self, token: &str, now: DurationSinceUnixEpoch) -> Option<String> {
+        let rotated = self.rotated.read().expect("it should get a lock on the rotated tokens");
+
+        self.configured
+            .iter()
+            .find(|(label, configured_token)| match rotated.get(*label) {
+                Some(rotated_token) => {
+                    rotated_token.token == token
+                        || (rotated_token.previous_token.as_deref() == Some(token)
+                            && now.as_secs() < rotated_token.previous_token_valid_until)
+                }
+                None => *configured_token == token,
+            })
+            .map(|(label, _)| label.clone())
+    }
+
+    /// It returns a new token for the label, replacing its current token. The
+    /// current token is still accepted for the `overlap`. The new token is
+    /// not used until it's [`applied`](Self::apply). It returns `None` if the
+    /// label is not in the configuration.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the rotated tokens is poisoned.
+    #[must_use]
+    pub fn rotate(&self, label: &str, overlap: Duration, now: DurationSinceUnixEpoch) -> Option<RotatedToken> {
+        let configured_token = self.configured.get(label)?;
+
+        let rotated = self.rotated.read().expect("it should get a lock on the rotated tokens");
+
+        let current_token = rotated
+            .get(label)
+            .map_or(configured_token, |rotated_token| &rotated_token.token);
+
+        Some(RotatedToken {
+            label: label.to_string(),
+            token: generate_token(),
+            previous_token: Some(current_token.clone()),
+            previous_token_valid_until: (now + overlap).as_secs(),
+        })
+    }
+
+    /// It replaces the current token of the label with the rotated one.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the rotated tokens is poisoned.
+    pub fn apply(&self, rotated_token: RotatedToken) {
+        self.rotated
+            .write()
+            .expect("it should get a lock on the rotated tokens")
+            .insert(rotated_token.label.clone(), rotated_token);
+    }
+
+    /// It returns `true`, and records the reload, if the rotated tokens have
+    /// not been reloaded in the last [`RELOAD_INTERVAL`].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the last reload time is poisoned.
+    pub fn start_reload(&self, now: DurationSinceUnixEpoch) -> bool {
+        let mut last_reload = self.last_reload.lock().expect("it should get a lock on the last reload time");
+
+        if last_reload.is_some_and(|last_reload| last_reload + RELOAD_INTERVAL > now) {
+            return false;
+        }
+
+        *last_reload = Some(now);
+
+        true
+    }
+}
+
+fn generate_token() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use torrust_tracker_configuration::AccessTokens;
+
+    use super::{AccessTokenStore, RELOAD_INTERVAL};
+
+    const NOW: Duration = Duration::from_secs(1_700_000_000);
+
+    fn store() -> AccessTokenStore {
+        AccessTokenStore::new(&AccessTokens::from([("admin".to_string(), "MyAccessToken".to_string())]))
+    }
+
+    #[test]
+    fn it_should_accept_the_previous_token_during_the_overlap_after_a_rotation() {
+        let store = store();
+
+        let rotated_token = store.rotate("admin", Duration::from_secs(60), NOW).unwrap();
+        let new_token = rotated_token.token.clone();
+
+        assert_ne!(new_token, "MyAccessToken");
+
+        store.apply(rotated_token);
+
+        assert_eq!(store.authenticate(&new_token, NOW), Some("admin".to_string()));
+        assert_eq!(
+            store.authenticate("MyAccessToken", NOW + Duration::from_secs(59)),
+            Some("admin".to_string())
+        );
+        assert_eq!(store.authenticate("MyAccessToken", NOW + Duration::from_secs(60)), None);
+    }
+
+    #[test]
+    fn it_should_not_rotate_the_tokens_of_the_labels_not_in_the_configuration() {
+        assert_eq!(store().rotate("dashboard", Duration::ZERO, NOW), None);
+    }
+
+    #[test]
+    fn it_should_not_reload_the_rotated_tokens_more_than_once_per_interval() {
+        let store = store();
+
+        assert!(store.start_reload(NOW));
+        assert!(!store.start_reload(NOW + RELOAD_INTERVAL - Duration::from_secs(1)));
+        assert!(store.start_reload(NOW + RELOAD_INTERVAL));
+    }
+}
@@ -68,6 +68,8 @@
 //!   "seeders": 0,
 //!   "completed": 0,
 //!   "leechers": 0,
+//!   "uploaded_bytes_per_second": 0,
+//!   "downloaded_bytes_per_second": 0,
 //!   "tcp4_connections_handled": 0,
 //!   "tcp4_announces_handled": 0,
 //!   "tcp4_scrapes_handled": 0,
@@ -79,7 +81,18 @@
 //!   "udp4_scrapes_handled": 0,
 //!   "udp6_connections_handled": 0,
 //!   "udp6_announces_handled": 0,
-//!   "udp6_scrapes_handled": 0
+//!   "udp6_scrapes_handled": 0,
+//!   "tcp_forwarded_announces_handled": 0,
+//!   "tcp_forwarded_ip_proxy_changes": 0,
+//!   "tcp_announces_with_tracker_id": 0,
+//!   "tcp_announces_with_invalid_tracker_id": 0,
+//!   "tcp_announces_without_tracker_id": 0,
+//!   "api_requests_rate_limited": 0,
+//!   "api_requests_locked_out": 0,
+//!   "api_failed_auth_attempts": 0,
+//!   "api_lockouts": 0,
+//!   "scrape_cache_hits": 0,
+//!   "scrape_cache_misses": 0
 //! }
 //! ```
 //!
@@ -96,8 +109,18 @@
 //! admin = "MyAccessToken"
 //! ```
 //!
-//! The token label is used to identify the token. All tokens have full access
-//! to the API.
+//! The token label is used to identify the token. The endpoints each token can
+//! be used for depend on its role.
+//!
+//! The clients can be rate limited, and locked out after too many attempts
+//! with an invalid token, to prevent brute-force attacks against the tokens:
+//!
+//! ```toml
+//! [http_api.rate_limit]
+//! requests_per_minute_per_ip = 120
+//! max_failed_auth_attempts = 10
+//! lockout_duration = 900
+//! ```
 //!
 //! Refer to [`torrust-tracker-configuration`](torrust_tracker_configuration)
 //! for more information about the API configuration and to the
@@ -133,6 +156,20 @@
 //! > options in the configuration file with the paths to the certificate
 //! > (`localhost.crt`) and key (`localhost.key`) files.
 //!
+//! You can also require clients to authenticate with a certificate (mutual
+//! TLS). Set the `ssl_client_ca_path` option with the path to a PEM file
+//! containing the CA certificates used to sign the client certificates:
+//!
+//! ```toml
+//! [http_api.tsl_config]
+//! ssl_cert_path = "./storage/tracker/lib/tls/localhost.crt"
+//! ssl_key_path = "./storage/tracker/lib/tls/localhost.key"
+//! ssl_client_ca_path = "./storage/tracker/lib/tls/clients-ca.crt"
+//! ```
+//!
+//! Connections from clients without a valid certificate are rejected during
+//! the TLS handshake, before the access token is sent.
+//!
 //! # Versioning
 //!
 //! The API is versioned and each version has its own module.
@@ -141,11 +178,26 @@
 //! but a version [`v2`](https://github.com/torrust/torrust-tracker/issues/144)
 //! is planned.
 //!
+//! The version is the first path segment after the `/api` prefix, for
+//! example: `/api/v1/stats`. Breaking changes to the requests or the
+//! responses are only made in new versions, so the clients keep working
+//! until they migrate.
+//!
+//! The `v1` endpoints are also served without the version in the path, for
+//! the clients of the old unversioned API. For example, `/api/stats`. Those
+//! paths are deprecated: their responses include a `Deprecation` header and
+//! a `Link` header to the versioned path. Refer to the
+//! [`deprecation`](crate::servers::apis::v1::middlewares::deprecation)
+//! middleware.
+//!
 //! # Endpoints
 //!
 //! Refer to the [v1] module for the list of available
 //! API endpoints.
 //!
+//! The [`OpenAPI` specification](openapi) of all the endpoints is served at
+//! `/api/openapi.json`.
+//!
 //! # Documentation
 //!
 //! If you want to contribute to this documentation you can [open a new pull request](https://github.com/torrust/torrust-tracker/pulls).
@@ -153,11 +205,17 @@
 //! > **NOTICE**: we are using [curl](https://curl.se/) in the API examples.
 //! > And you have to use quotes around the URL in order to avoid unexpected
 //! > errors. For example: `curl "http://127.0.0.1:1212/api/v1/stats?token=MyAccessToken"`.
+pub mod access_tokens;
+pub mod openapi;
+pub mod rate_limiter;
 pub mod routes;
 pub mod server;
 pub mod v1;
 
-use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize};
 
 pub const API_LOG_TARGET: &str = "API";
 
@@ -173,6 +231,20 @@ pub const API_LOG_TARGET: &str = "API";
 #[derive(Deserialize)]
 pub struct InfoHashParam(pub String);
 
+/// Serde deserialization decorator to map empty Strings to None,
+pub(crate) fn empty_string_as_none<'de, D, T>(de: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let opt = Option::<String>::deserialize(de)?;
+    match opt.as_deref() {
+        None | Some("") => Ok(None),
+        Some(s) => FromStr::from_str(s).map_err(de::Error::custom).map(Some),
+    }
+}
+
 /// The version of the HTTP Api.
 #[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
 pub enum Version {
@@ -0,0 +1,157 @@
+//! `OpenAPI` specification of the API.
+//!
+//! The [`OpenAPI` 3](https://spec.openapis.org/oas/v3.0.3) document describes
+//! all the API endpoints, the authentication and the error responses. It's
+//! served at `/api/openapi.json` without authentication, so downstream
+//! projects can generate their clients from it:
+//!
+//! ```text
+//! curl "http://127.0.0.1:1212/api/openapi.json"
+//! ```
+//!
+//! The document is maintained by hand in the `openapi.json` file next to this
+//! module. It must be updated when an endpoint is added or changed: the tests
+//! fail when a route of the API contexts is not in the document.
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+
+/// The `OpenAPI` document of the API.
+pub const SPECIFICATION: &str = include_str!("openapi.json");
+
+/// It handles the request to get the `OpenAPI` document of the API.
+pub async fn openapi_handler() -> Response {
+    (StatusCode::OK, [(header::CONTENT_TYPE, "application/json")], SPECIFICATION).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use serde_json::Value;
+
+    use super::SPECIFICATION;
+
+    /// Paths of the routes that don't require authentication.
+    const PUBLIC_PATHS: [&str; 2] = ["/api/health_check", "/api/openapi.json"];
+
+    #[test]
+    fn it_should_be_a_valid_json_openapi_3_document() {
+        let specification: Value = serde_json::from_str(SPECIFICATION).unwrap();
+
+        assert!(specification["openapi"].as_str().unwrap().starts_with("3."));
+        assert!(specification["paths"].is_object());
+    }
+
+    #[test]
+    fn it_should_only_reference_the_defined_components() {
+        let specification: Value = serde_json::from_str(SPECIFICATION).unwrap();
+
+        let mut references = vec![];
+        collect_references(&specification, &mut references);
+
+        for reference in references {
+            let pointer = reference.trim_start_matches('#');
+            assert!(specification.pointer(pointer).is_some(), "undefined component: {reference}");
+        }
+    }
+
+    #[test]
+    fn it_should_document_all_the_routes_of_the_api_contexts() {
+        let specification: Value = serde_json::from_str(SPECIFICATION).unwrap();
+
+        let paths = registered_paths();
+
+        assert!(paths.contains(&"/api/v1/torrent/{info_hash}".to_string()));
+
+        for path in paths {
+            assert!(specification["paths"][&path].is_object(), "undocumented route: {path}");
+        }
+    }
+
+    #[test]
+    fn it_should_document_the_response_to_the_unauthenticated_requests() {
+        let specification: Value = serde_json::from_str(SPECIFICATION).unwrap();
+
+        for (path, operations) in specification["paths"].as_object().unwrap() {
+            for (method, operation) in operations.as_object().unwrap() {
+                if PUBLIC_PATHS.contains(&path.as_str()) {
+                    assert_eq!(
+                        operation["security"],
+                        Value::Array(vec![]),
+                        "{method} {path} requires authentication"
+                    );
+                } else {
+                    assert!(
+                        operation.get("security").is_none(),
+                        "{method} {path} does not require authentication"
+                    );
+                    assert_eq!(
+                        operation["responses"]["500"]["$ref"], "#/components/responses/UnhandledRejection",
+                        "{method} {path} does not document the unauthenticated requests"
+                    );
+                }
+            }
+        }
+    }
+
+    /// It returns the paths of the routes added by the API contexts, read from
+    /// their `routes.rs` files, in the `OpenAPI` format. For example:
+    /// `/api/v1/torrent/{info_hash}`.
+    fn registered_paths() -> Vec<String> {
+        let contexts = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/servers/apis/v1/context");
+
+        let mut paths = vec![];
+
+        for context in fs::read_dir(contexts).unwrap() {
+            let Ok(routes) = fs::read_to_string(context.unwrap().path().join("routes.rs")) else {
+                continue;
+            };
+
+            // Some contexts add their routes under their own prefix
+            let mut prefix = "/api/v1".to_string();
+
+            for line in routes.lines().map(str::trim) {
+                if let Some(context_prefix) = line
+                    .strip_prefix("let prefix = format!(\"{prefix}")
+                    .and_then(|rest| rest.strip_suffix("\");"))
+                {
+                    prefix = format!("{prefix}{context_prefix}");
+                } else if line.starts_with(".route(&prefix,") {
+                    paths.push(prefix.clone());
+                } else if let Some(rest) = line.split("format!(\"{prefix}").nth(1) {
+                    paths.push(format!("{prefix}{}", rest.split('"').next().unwrap()));
+                }
+            }
+        }
+
+        paths
+            .iter()
+            .map(|path| {
+                path.split('/')
+                    .map(|segment| match segment.strip_prefix(':') {
+                        Some(param) => format!("{{{param}}}"),
+                        None => segment.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .collect()
+    }
+
+    fn collect_references(value: &Value, references: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    if let ("$ref", Value::String(reference)) = (key.as_str(), value) {
+                        references.push(reference.clone());
+                    } else {
+                        collect_references(value, references);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter().for_each(|value| collect_references(value, references)),
+            _ => {}
+        }
+    }
+}
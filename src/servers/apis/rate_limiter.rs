@@ -0,0 +1,233 @@
+//! Per-IP rate limiter and brute-force lockout for the API.
+//!
+//! Each client IP address has a token bucket refilled at the configured
+//! requests per minute. Every authenticated request takes one token from the
+//! bucket of its IP address. Requests received with an empty bucket exceed the
+//! limit.
+//!
+//! The client IP addresses are also locked out, for the configured lockout
+//! duration, after too many consecutive attempts with an invalid token. The
+//! token check would otherwise be an unthrottled oracle to brute force the
+//! access tokens. A request with a valid token resets the failed attempts of
+//! its IP address.
+//!
+//! Refer to the [`HttpApiRateLimit`] configuration for more information.
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use torrust_tracker_configuration::HttpApiRateLimit;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+use crate::servers::token_buckets::TokenBuckets;
+
+/// Maximum number of IP addresses with failed attempts tracked. When it's
+/// reached, the ones whose failed attempts expired are forgotten.
+const MAX_TRACKED_IPS: usize = 100_000;
+
+/// A rate limiter and brute-force lockout keyed by client IP address.
+#[derive(Debug)]
+pub struct RateLimiter {
+    per_ip: Option<TokenBuckets<IpAddr>>,
+    lockout: Option<Lockout>,
+}
+
+/// The consecutive failed attempts of an IP address.
+#[derive(Debug, Clone, Copy)]
+struct FailedAttempts {
+    count: u32,
+    last_failure: DurationSinceUnixEpoch,
+    locked_until: Option<DurationSinceUnixEpoch>,
+}
+
+#[derive(Debug)]
+struct Lockout {
+    max_failed_attempts: u32,
+    duration: Duration,
+    failed_attempts: Mutex<HashMap<IpAddr, FailedAttempts>>,
+}
+
+impl Lockout {
+    fn is_expired(&self, failed_attempts: &FailedAttempts, now: DurationSinceUnixEpoch) -> bool {
+        match failed_attempts.locked_until {
+            Some(locked_until) => locked_until <= now,
+            None => failed_attempts.last_failure + self.duration <= now,
+        }
+    }
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new(config: &HttpApiRateLimit) -> Self {
+        Self {
+            per_ip: config.requests_per_minute_per_ip.map(TokenBuckets::per_minute),
+            lockout: config.max_failed_auth_attempts.map(|max_failed_attempts| Lockout {
+                max_failed_attempts,
+                duration: Duration::from_secs(config.lockout_duration),
+                failed_attempts: Mutex::default(),
+            }),
+        }
+    }
+
+    /// It takes a token for a request from the IP address. It returns `false`
+    /// if the request exceeds the limit.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the buckets is poisoned.
+    pub fn try_acquire(&self, ip: &IpAddr, now: DurationSinceUnixEpoch) -> bool {
+        match &self.per_ip {
+            Some(per_ip) => per_ip.try_acquire(ip, now),
+            None => true,
+        }
+    }
+
+    /// It returns how long the IP address stays locked out, if it's locked
+    /// out.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the failed attempts is poisoned.
+    #[must_use]
+    pub fn locked_out_for(&self, ip: &IpAddr, now: DurationSinceUnixEpoch) -> Option<Duration> {
+        let lockout = self.lockout.as_ref()?;
+
+        let failed_attempts = lockout
+            .failed_attempts
+            .lock()
+            .expect("it should get a lock on the failed attempts");
+
+        failed_attempts
+            .get(ip)
+            .and_then(|failed_attempts| failed_attempts.locked_until)
+            .filter(|locked_until| *locked_until > now)
+            .map(|locked_until| locked_until - now)
+    }
+
+    /// It records an attempt with an invalid token from the IP address. It
+    /// returns `true` if the IP address has been locked out because of it.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the failed attempts is poisoned.
+    pub fn record_failed_attempt(&self, ip: &IpAddr, now: DurationSinceUnixEpoch) -> bool {
+        let Some(lockout) = &self.lockout else {
+            return false;
+        };
+
+        let mut failed_attempts = lockout
+            .failed_attempts
+            .lock()
+            .expect("it should get a lock on the failed attempts");
+
+        if failed_attempts.len() >= MAX_TRACKED_IPS && !failed_attempts.contains_key(ip) {
+            failed_attempts.retain(|_, failed_attempts| !lockout.is_expired(failed_attempts, now));
+        }
+
+        let entry = failed_attempts.entry(*ip).or_insert(FailedAttempts {
+            count: 0,
+            last_failure: now,
+            locked_until: None,
+        });
+
+        if lockout.is_expired(entry, now) {
+            entry.count = 0;
+            entry.locked_until = None;
+        }
+
+        entry.count += 1;
+        entry.last_failure = now;
+
+        if entry.locked_until.is_none() && entry.count >= lockout.max_failed_attempts {
+            entry.locked_until = Some(now + lockout.duration);
+            return true;
+        }
+
+        false
+    }
+
+    /// It forgets the failed attempts of the IP address, after a request with
+    /// a valid token.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the failed attempts is poisoned.
+    pub fn record_successful_attempt(&self, ip: &IpAddr) {
+        if let Some(lockout) = &self.lockout {
+            lockout
+                .failed_attempts
+                .lock()
+                .expect("it should get a lock on the failed attempts")
+                .remove(ip);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Duration;
+
+    use torrust_tracker_configuration::HttpApiRateLimit;
+
+    use super::RateLimiter;
+
+    fn ip(last_octet: u8) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(126, 0, 0, last_octet))
+    }
+
+    fn rate_limiter(requests_per_minute_per_ip: Option<u32>, max_failed_auth_attempts: Option<u32>) -> RateLimiter {
+        RateLimiter::new(&HttpApiRateLimit {
+            requests_per_minute_per_ip,
+            max_failed_auth_attempts,
+            lockout_duration: 60,
+        })
+    }
+
+    #[test]
+    fn it_should_reject_the_requests_from_an_ip_exceeding_the_limit() {
+        let rate_limiter = rate_limiter(Some(1), None);
+
+        assert!(rate_limiter.try_acquire(&ip(1), Duration::ZERO));
+        assert!(!rate_limiter.try_acquire(&ip(1), Duration::ZERO));
+
+        assert!(rate_limiter.try_acquire(&ip(2), Duration::ZERO));
+    }
+
+    #[test]
+    fn it_should_lock_out_an_ip_after_too_many_failed_attempts() {
+        let rate_limiter = rate_limiter(None, Some(2));
+
+        assert!(!rate_limiter.record_failed_attempt(&ip(1), Duration::ZERO));
+        assert_eq!(rate_limiter.locked_out_for(&ip(1), Duration::ZERO), None);
+
+        assert!(rate_limiter.record_failed_attempt(&ip(1), Duration::from_secs(10)));
+        assert_eq!(
+            rate_limiter.locked_out_for(&ip(1), Duration::from_secs(20)),
+            Some(Duration::from_secs(50))
+        );
+        assert_eq!(rate_limiter.locked_out_for(&ip(2), Duration::from_secs(20)), None);
+
+        assert_eq!(rate_limiter.locked_out_for(&ip(1), Duration::from_secs(70)), None);
+    }
+
+    #[test]
+    fn it_should_forget_the_failed_attempts_after_a_successful_one() {
+        let rate_limiter = rate_limiter(None, Some(2));
+
+        assert!(!rate_limiter.record_failed_attempt(&ip(1), Duration::ZERO));
+        rate_limiter.record_successful_attempt(&ip(1));
+
+        assert!(!rate_limiter.record_failed_attempt(&ip(1), Duration::ZERO));
+    }
+
+    #[test]
+    fn it_should_forget_the_failed_attempts_older_than_the_lockout_duration() {
+        let rate_limiter = rate_limiter(None, Some(2));
+
+        assert!(!rate_limiter.record_failed_attempt(&ip(1), Duration::ZERO));
+
+        assert!(!rate_limiter.record_failed_attempt(&ip(1), Duration::from_secs(60)));
+    }
+}
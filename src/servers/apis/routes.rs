@@ -1,20 +1,26 @@
 //! API routes.
 //!
 //! It loads all the API routes for all API versions and adds the authentication
-//! middleware to them.
+//! and the audit log middlewares to them.
 //!
 //! All the API routes have the `/api` prefix and the version number as the
-//! first path segment. For example: `/api/v1/torrents`.
+//! first path segment. For example: `/api/v1/torrents`. The `v1` routes are
+//! also served without the version, for the clients of the old unversioned
+//! API, but they are deprecated. For example: `/api/torrents`.
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
+use axum::body::Body;
 use axum::error_handling::HandleErrorLayer;
+use axum::extract::{self, ConnectInfo};
 use axum::http::HeaderName;
+use axum::middleware::Next;
 use axum::response::Response;
 use axum::routing::get;
 use axum::{middleware, BoxError, Router};
 use hyper::{Request, StatusCode};
-use torrust_tracker_configuration::{AccessTokens, DEFAULT_TIMEOUT};
+use torrust_tracker_configuration::{AccessTokenRoles, Configuration, HttpApiJwt, HttpApiRateLimit, DEFAULT_TIMEOUT};
 use tower::timeout::TimeoutLayer;
 use tower::ServiceBuilder;
 use tower_http::compression::CompressionLayer;
@@ -23,27 +29,80 @@ use tower_http::request_id::{MakeRequestUuid, SetRequestIdLayer};
 use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 use tracing::{instrument, Level, Span};
 
+use super::access_tokens::AccessTokenStore;
+use super::openapi::openapi_handler;
+use super::rate_limiter::RateLimiter;
 use super::v1;
 use super::v1::context::health_check::handlers::health_check_handler;
 use super::v1::middlewares::auth::State;
+use super::v1::middlewares::jwt;
 use crate::core::Tracker;
+use crate::servers::access_log::{self, AccessLog};
 use crate::servers::apis::API_LOG_TARGET;
 
-/// Add all API routes to the router.
+/// Add all API routes to the router. The endpoints each access token can be
+/// used for depend on its role in `access_token_roles`. The `access_tokens`
+/// can be rotated, and the previous token is accepted for the
+/// `token_rotation_overlap` after a rotation. The caller loads the tokens
+/// rotated before the tracker started into the store. The requests can also
+/// be authenticated with JSON Web Tokens, if `jwt` is set. The clients are
+/// rate limited, and locked out after too many invalid tokens, if
+/// `rate_limit` is set. All the requests are written to the `access_log`, if
+/// any. The IPs of the peers are redacted in the responses when
+/// `redact_peer_ips` is `true`. The `running_config` is the configuration of
+/// the whole tracker, served with the secrets masked.
 #[allow(clippy::needless_pass_by_value)]
-#[instrument(skip(tracker, access_tokens))]
-pub fn router(tracker: Arc<Tracker>, access_tokens: Arc<AccessTokens>) -> Router {
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(tracker, access_tokens, access_token_roles, jwt, access_log, running_config))]
+pub fn router(
+    tracker: Arc<Tracker>,
+    access_tokens: Arc<AccessTokenStore>,
+    access_token_roles: Arc<AccessTokenRoles>,
+    token_rotation_overlap: Duration,
+    jwt: Option<Arc<HttpApiJwt>>,
+    rate_limit: Option<HttpApiRateLimit>,
+    access_log: Option<Arc<AccessLog>>,
+    redact_peer_ips: bool,
+    running_config: Arc<Configuration>,
+) -> Router {
     let router = Router::new();
 
     let api_url_prefix = "/api";
 
-    let router = v1::routes::add(api_url_prefix, router, tracker.clone());
+    let router = v1::routes::add(
+        api_url_prefix,
+        router,
+        tracker.clone(),
+        access_tokens.clone(),
+        running_config.clone(),
+        redact_peer_ips,
+        token_rotation_overlap,
+    );
 
-    let state = State { access_tokens };
+    // Deprecated routes without the API version
+    let router = v1::routes::add_unversioned(
+        api_url_prefix,
+        router,
+        tracker.clone(),
+        access_tokens.clone(),
+        running_config,
+        redact_peer_ips,
+        token_rotation_overlap,
+    );
 
-    router
+    let state = State {
+        tracker: tracker.clone(),
+        access_tokens,
+        access_token_roles,
+        jwt: jwt.map(|config| Arc::new(jwt::Verifier::new(config, tracker.get_outbound_proxy()))),
+        rate_limiter: rate_limit.map(|rate_limit| Arc::new(RateLimiter::new(&rate_limit))),
+    };
+
+    let router = router
+        .layer(middleware::from_fn_with_state(tracker.clone(), v1::middlewares::audit::audit))
         .layer(middleware::from_fn_with_state(state, v1::middlewares::auth::auth))
         .route(&format!("{api_url_prefix}/health_check"), get(health_check_handler))
+        .route(&format!("{api_url_prefix}/openapi.json"), get(openapi_handler))
         .layer(CompressionLayer::new())
         .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
         .layer(PropagateHeaderLayer::new(HeaderName::from_static("x-request-id")))
@@ -84,5 +143,21 @@ pub fn router(tracker: Arc<Tracker>, access_tokens: Arc<AccessTokens>) -> Router
                 // errors returned by `TimeoutLayer`
                 .layer(HandleErrorLayer::new(|_: BoxError| async { StatusCode::REQUEST_TIMEOUT }))
                 .layer(TimeoutLayer::new(DEFAULT_TIMEOUT)),
-        )
+        );
+
+    match access_log {
+        Some(access_log) => router.layer(middleware::from_fn_with_state(access_log, log_access)),
+        None => router,
+    }
+}
+
+/// Middleware to write the requests to the access log, with the IP from the
+/// connection info.
+async fn log_access(extract::State(access_log): extract::State<Arc<AccessLog>>, request: Request<Body>, next: Next) -> Response {
+    let client_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|connect_info| connect_info.0.ip());
+
+    access_log::log_request(&access_log, client_ip, request, next).await
 }
@@ -25,6 +25,7 @@
 /// shutdown the server, etc.
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum_server::tls_rustls::RustlsConfig;
 use axum_server::Handle;
@@ -33,17 +34,20 @@ use derive_more::Constructor;
 use futures::future::BoxFuture;
 use thiserror::Error;
 use tokio::sync::oneshot::{Receiver, Sender};
-use torrust_tracker_configuration::AccessTokens;
+use torrust_tracker_configuration::{AccessTokenRoles, AccessTokens, Configuration, HttpApiJwt, HttpApiRateLimit};
 use tracing::{instrument, Level};
 
+use super::access_tokens::AccessTokenStore;
 use super::routes::router;
 use crate::bootstrap::jobs::Started;
 use crate::core::Tracker;
+use crate::servers::access_log::AccessLog;
 use crate::servers::apis::API_LOG_TARGET;
 use crate::servers::custom_axum_server::{self, TimeoutAcceptor};
 use crate::servers::logging::STARTED_ON;
 use crate::servers::registar::{ServiceHealthCheckJob, ServiceRegistration, ServiceRegistrationForm};
 use crate::servers::signals::{graceful_shutdown, Halted};
+use crate::servers::socket_activation;
 
 /// Errors that can occur when starting or stopping the API server.
 #[derive(Debug, Error)]
@@ -212,6 +216,25 @@ pub fn check_fn(binding: &SocketAddr) -> ServiceHealthCheckJob {
 pub struct Launcher {
     bind_to: SocketAddr,
     tls: Option<RustlsConfig>,
+    /// Access log of the requests. Disabled if `None`.
+    access_log: Option<Arc<AccessLog>>,
+    /// How long the server waits for the requests in process when it's shut
+    /// down.
+    shutdown_timeout: Duration,
+    /// Whether the IPs of the peers are redacted in the responses.
+    redact_peer_ips: bool,
+    /// Roles of the access tokens, by token label.
+    access_token_roles: Arc<AccessTokenRoles>,
+    /// How long the previous token is accepted after an access token is
+    /// rotated.
+    token_rotation_overlap: Duration,
+    /// Authentication with JSON Web Tokens. Disabled if `None`.
+    jwt: Option<Arc<HttpApiJwt>>,
+    /// Rate limit and brute-force lockout of the clients. Unlimited if
+    /// `None`.
+    rate_limit: Option<HttpApiRateLimit>,
+    /// Configuration of the running tracker, served with the secrets masked.
+    running_config: Arc<Configuration>,
 }
 
 impl std::fmt::Display for Launcher {
@@ -243,8 +266,21 @@ impl Launcher {
         tx_start: Sender<Started>,
         rx_halt: Receiver<Halted>,
     ) -> BoxFuture<'static, ()> {
-        let router = router(tracker, access_tokens);
-        let socket = std::net::TcpListener::bind(self.bind_to).expect("Could not bind tcp_listener to address.");
+        let access_tokens = Arc::new(AccessTokenStore::new(&access_tokens));
+
+        let router = router(
+            tracker.clone(),
+            access_tokens.clone(),
+            self.access_token_roles.clone(),
+            self.token_rotation_overlap,
+            self.jwt.clone(),
+            self.rate_limit,
+            self.access_log.clone(),
+            self.redact_peer_ips,
+            self.running_config.clone(),
+        );
+        let socket = socket_activation::take_tcp_listener(self.bind_to)
+            .unwrap_or_else(|| std::net::TcpListener::bind(self.bind_to).expect("Could not bind tcp_listener to address."));
         let address = socket.local_addr().expect("Could not get local_addr from tcp_listener.");
 
         let handle = Handle::new();
@@ -253,6 +289,7 @@ impl Launcher {
             handle.clone(),
             rx_halt,
             format!("Shutting down tracker API server on socket address: {address}"),
+            self.shutdown_timeout,
         ));
 
         let tls = self.tls.clone();
@@ -260,7 +297,14 @@ impl Launcher {
 
         tracing::info!(target: API_LOG_TARGET, "Starting on {protocol}://{}", address);
 
-        let running = Box::pin(async {
+        let running = Box::pin(async move {
+            // The tokens rotated before the restart replace the configured
+            // ones before the first request is served
+            match tracker.load_rotated_api_tokens().await {
+                Ok(rotated_tokens) => access_tokens.load(rotated_tokens),
+                Err(err) => tracing::error!(target: API_LOG_TARGET, %err, "failed to load the rotated API access tokens"),
+            }
+
             match tls {
                 Some(tls) => custom_axum_server::from_tcp_rustls_with_timeouts(socket, tls)
                     .handle(handle)
@@ -292,6 +336,7 @@ impl Launcher {
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
+    use std::time::Duration;
 
     use torrust_tracker_test_helpers::configuration::ephemeral_public;
 
@@ -307,7 +352,7 @@ mod tests {
 
         let tracker = initialize_with_configuration(&cfg);
 
-        let bind_to = config.bind_address;
+        let bind_to = config.bind_address.socket_addr().expect("it should bind to an IP address");
 
         let tls = make_rust_tls(&config.tsl_config)
             .await
@@ -315,7 +360,18 @@ mod tests {
 
         let access_tokens = Arc::new(config.access_tokens.clone());
 
-        let stopped = ApiServer::new(Launcher::new(bind_to, tls));
+        let stopped = ApiServer::new(Launcher::new(
+            bind_to,
+            tls,
+            None,
+            Duration::from_secs(config.shutdown_timeout),
+            config.redact_peer_ips,
+            Arc::new(config.access_token_roles.clone()),
+            Duration::from_secs(config.access_token_rotation_overlap),
+            config.jwt.clone().map(Arc::new),
+            config.rate_limit,
+            cfg.clone(),
+        ));
 
         let register = &Registar::default();
 
@@ -0,0 +1,23 @@
+//! API forms for the [`abuse`](crate::servers::apis::v1::context::abuse) API context.
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DefaultOnNull};
+
+/// This type contains the info needed to report an abuse.
+///
+/// At least one of `info_hash` or `ip` must be provided.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AbuseReportForm {
+    /// The reported torrent infohash (40-char hex string).
+    #[serde_as(deserialize_as = "DefaultOnNull")]
+    #[serde(default)]
+    pub info_hash: Option<String>,
+
+    /// The reported peer IP address.
+    #[serde_as(deserialize_as = "DefaultOnNull")]
+    #[serde(default)]
+    pub ip: Option<String>,
+
+    /// Free-form description of the abuse.
+    pub reason: String,
+}
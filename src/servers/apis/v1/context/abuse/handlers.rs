@@ -0,0 +1,57 @@
+//! API handlers for the [`abuse`](crate::servers::apis::v1::context::abuse) API context.
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::{self, State};
+use axum::response::Response;
+use torrust_tracker_primitives::info_hash::InfoHash;
+
+use super::forms::AbuseReportForm;
+use super::resources::AbuseReportOutcome;
+use super::responses::{
+    abuse_report_outcome_response, invalid_info_hash_response, invalid_ip_response, missing_abuse_target_response,
+};
+use crate::core::abuse::Report;
+use crate::core::services::abuse::report_abuse;
+use crate::core::Tracker;
+
+/// It handles the request to report an abuse.
+///
+/// It returns:
+///
+/// - `200` with a json [`AbuseReportOutcome`] resource containing the applied
+///   mitigations.
+/// - `400` with an error if the report does not contain an infohash or an IP
+///   address, or if any of them is invalid.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::abuse#report-an-abuse)
+/// for more information about this endpoint.
+pub async fn report_abuse_handler(
+    State(tracker): State<Arc<Tracker>>,
+    extract::Json(report_form): extract::Json<AbuseReportForm>,
+) -> Response {
+    let info_hash = match report_form.info_hash.as_deref().map(InfoHash::from_str).transpose() {
+        Ok(info_hash) => info_hash,
+        Err(_) => return invalid_info_hash_response(&report_form.info_hash.unwrap_or_default()),
+    };
+
+    let ip = match report_form.ip.as_deref().map(IpAddr::from_str).transpose() {
+        Ok(ip) => ip,
+        Err(_) => return invalid_ip_response(&report_form.ip.unwrap_or_default()),
+    };
+
+    if info_hash.is_none() && ip.is_none() {
+        return missing_abuse_target_response();
+    }
+
+    let report = Report {
+        info_hash,
+        ip,
+        reason: report_form.reason,
+    };
+
+    let mitigations = report_abuse(&tracker, &report);
+
+    abuse_report_outcome_response(&AbuseReportOutcome::from(mitigations))
+}
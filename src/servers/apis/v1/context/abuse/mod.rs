@@ -0,0 +1,74 @@
+//! Abuse API context.
+//!
+//! This API context is responsible for handling abuse reports.
+//!
+//! An abuse report identifies a torrent, a peer IP address or both. When the
+//! tracker receives a report it applies the mitigations enabled in the
+//! `[core.abuse]` configuration section (ban the IP address, blacklist the
+//! torrent or raise its announce interval) and notifies the configured
+//! webhooks. Refer to the [`abuse`](crate::core::abuse) module for more
+//! information about the mitigations.
+//!
+//! > **NOTICE**: mitigations are only kept in memory. They are lost when the
+//! > tracker is restarted.
+//!
+//! # Endpoints
+//!
+//! - [Report an abuse](#report-an-abuse)
+//!
+//! # Report an abuse
+//!
+//! `POST /abuse`
+//!
+//! It applies the configured mitigations for the report.
+//!
+//! **POST params (json)**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `info_hash` | 40-char string | The Info Hash v1 of the reported torrent | No | `5452869be36f9f3350ccee6b4544e7e76caaadab`
+//! `ip` | string | The reported peer IP address | No | `203.0.113.7`
+//! `reason` | string | Description of the abuse | Yes | `Copyright infringement`
+//!
+//! At least one of `info_hash` or `ip` is required.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X POST http://localhost:1212/api/v1/abuse?token=MyAccessToken \
+//!      -H "Content-Type: application/json" \
+//!      -d '{
+//!            "info_hash": "5452869be36f9f3350ccee6b4544e7e76caaadab",
+//!            "ip": "203.0.113.7",
+//!            "reason": "Copyright infringement"
+//!          }'
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "mitigations": [
+//!         {
+//!             "action": "ban_ip",
+//!             "ip": "203.0.113.7"
+//!         },
+//!         {
+//!             "action": "blacklist_info_hash",
+//!             "info_hash": "5452869be36f9f3350ccee6b4544e7e76caaadab"
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! The list of mitigations is empty if none of them is enabled.
+//!
+//! **Resource**
+//!
+//! Refer to the API [`AbuseReportOutcome`](crate::servers::apis::v1::context::abuse::resources::AbuseReportOutcome)
+//! resource for more information about the response attributes.
+pub mod forms;
+pub mod handlers;
+pub mod resources;
+pub mod responses;
+pub mod routes;
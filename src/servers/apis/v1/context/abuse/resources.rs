@@ -0,0 +1,47 @@
+//! API resources for the [`abuse`](crate::servers::apis::v1::context::abuse) API context.
+use serde::Serialize;
+
+use crate::core::abuse::Mitigation;
+
+/// The result of processing an abuse report: the list of applied mitigations.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AbuseReportOutcome {
+    pub mitigations: Vec<Mitigation>,
+}
+
+impl From<Vec<Mitigation>> for AbuseReportOutcome {
+    fn from(mitigations: Vec<Mitigation>) -> Self {
+        Self { mitigations }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::str::FromStr;
+
+    use torrust_tracker_primitives::info_hash::InfoHash;
+
+    use super::AbuseReportOutcome;
+    use crate::core::abuse::Mitigation;
+
+    #[test]
+    fn abuse_report_outcome_should_be_converted_to_json() {
+        let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+        let outcome = AbuseReportOutcome::from(vec![
+            Mitigation::BanIp {
+                ip: IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)),
+            },
+            Mitigation::RaiseInterval {
+                info_hash,
+                interval: 3600,
+            },
+        ]);
+
+        assert_eq!(
+            serde_json::to_string(&outcome).unwrap(),
+            r#"{"mitigations":[{"action":"ban_ip","ip":"126.0.0.1"},{"action":"raise_interval","info_hash":"9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d","interval":3600}]}"# // DevSkim: ignore DS173237
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! API responses for the [`abuse`](crate::servers::apis::v1::context::abuse) API context.
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+
+use super::resources::AbuseReportOutcome;
+use crate::servers::apis::v1::responses::bad_request_response;
+
+/// `200` response that contains the [`AbuseReportOutcome`] resource as json.
+///
+/// # Panics
+///
+/// Will panic if it can't convert the `AbuseReportOutcome` resource to json
+#[must_use]
+pub fn abuse_report_outcome_response(outcome: &AbuseReportOutcome) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
+        serde_json::to_string(outcome).unwrap(),
+    )
+        .into_response()
+}
+
+// Error responses
+
+#[must_use]
+pub fn invalid_info_hash_response(info_hash: &str) -> Response {
+    bad_request_response(&format!(
+        "Invalid abuse report: invalid infohash: string \"{info_hash}\", expected a 40 character long string"
+    ))
+}
+
+#[must_use]
+pub fn invalid_ip_response(ip: &str) -> Response {
+    bad_request_response(&format!("Invalid abuse report: invalid IP address: \"{ip}\""))
+}
+
+#[must_use]
+pub fn missing_abuse_target_response() -> Response {
+    bad_request_response("Invalid abuse report: the report must contain an infohash or an IP address")
+}
@@ -0,0 +1,17 @@
+//! API routes for the [`abuse`](crate::servers::apis::v1::context::abuse) API context.
+//!
+//! - `POST /abuse`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::abuse).
+use std::sync::Arc;
+
+use axum::routing::post;
+use axum::Router;
+
+use super::handlers::report_abuse_handler;
+use crate::core::Tracker;
+
+/// It adds the routes to the router for the [`abuse`](crate::servers::apis::v1::context::abuse) API context.
+pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>) -> Router {
+    router.route(&format!("{prefix}/abuse"), post(report_abuse_handler).with_state(tracker))
+}
@@ -0,0 +1,78 @@
+//! API handlers for the [`audit`](crate::servers::apis::v1::context::audit) API context.
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::Response;
+use axum_extra::extract::Query;
+use serde::Deserialize;
+use torrust_tracker_primitives::pagination::Pagination;
+
+use super::responses::{audit_log_response, failed_to_load_audit_log_response};
+use crate::core::audit::Filter;
+use crate::core::Tracker;
+use crate::servers::apis::empty_string_as_none;
+use crate::servers::apis::v1::serializers::Negotiated;
+
+/// A container for the URL query parameters of the request to get the audit
+/// log. All the filters are optional.
+#[derive(Deserialize, Debug)]
+pub struct QueryParams {
+    /// Only the entries of this actor.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub actor: Option<String>,
+    /// Only the entries with this HTTP method.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub method: Option<String>,
+    /// Only the entries whose path starts with this prefix.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub path: Option<String>,
+    /// Only the entries recorded at this time or later, in seconds since the
+    /// Unix epoch.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub from: Option<u64>,
+    /// Only the entries recorded at this time or earlier, in seconds since
+    /// the Unix epoch.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub to: Option<u64>,
+    /// The number of entries to skip.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub offset: Option<u32>,
+    /// The maximum number of entries to return.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub limit: Option<u32>,
+}
+
+/// It handles the request to get the audit log.
+///
+/// It returns:
+///
+/// - `200` response with an array of [`AuditEntry`](crate::servers::apis::v1::context::audit::resources::AuditEntry)
+///   resources, the most recent first, in the negotiated format.
+/// - `500` with serialized error in debug format if the audit log couldn't be
+///   loaded from the database.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::audit#get-the-audit-log)
+/// for more information about this endpoint.
+pub async fn get_audit_log_handler(
+    State(tracker): State<Arc<Tracker>>,
+    Negotiated(format): Negotiated,
+    params: Query<QueryParams>,
+) -> Response {
+    let params = params.0;
+
+    let filter = Filter {
+        actor: params.actor,
+        method: params.method.map(|method| method.to_ascii_uppercase()),
+        path_prefix: params.path,
+        from: params.from,
+        to: params.to,
+    };
+
+    match tracker
+        .get_audit_entries(&filter, &Pagination::new_with_options(params.offset, params.limit))
+        .await
+    {
+        Ok(entries) => audit_log_response(&entries, format),
+        Err(e) => failed_to_load_audit_log_response(e),
+    }
+}
@@ -0,0 +1,67 @@
+//! Audit log API context.
+//!
+//! This API context is responsible for handling the audit log of the
+//! administrative actions.
+//!
+//! Every request to the API changing the tracker state (the `POST`, `PUT`,
+//! `PATCH` and `DELETE` requests) is recorded in the audit log, by the
+//! [`audit`](crate::servers::apis::v1::middlewares::audit) middleware. Refer to
+//! the [`audit`](crate::core::audit) module for more information.
+//!
+//! Only the access tokens with the `admin` role can read the audit log.
+//!
+//! # Endpoints
+//!
+//! - [Get the audit log](#get-the-audit-log)
+//!
+//! # Get the audit log
+//!
+//! `GET /audit`
+//!
+//! It returns the entries of the audit log, the most recent first.
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Default | Example
+//! ---|---|---|---|---|---
+//! `actor` | string | Only the entries of this access token label, or `jwt:<subject>` | No | | `admin`
+//! `method` | string | Only the entries with this HTTP method | No | | `DELETE`
+//! `path` | string | Only the entries whose path starts with this prefix | No | | `/api/v1/whitelist`
+//! `from` | positive integer | Only the entries recorded at this time or later, in seconds since the Unix epoch | No | | `1699920000`
+//! `to` | positive integer | Only the entries recorded at this time or earlier, in seconds since the Unix epoch | No | | `1700006400`
+//! `offset` | positive integer | The number of entries to skip | No | `0` | `20`
+//! `limit` | positive integer | The maximum number of entries to return | No | `4000` | `20`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/audit?token=MyAccessToken&actor=admin&limit=1"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!     {
+//!         "timestamp": 1700003600,
+//!         "actor": "admin",
+//!         "client_ip": "203.0.113.7",
+//!         "method": "POST",
+//!         "path": "/api/v1/whitelist/9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d",
+//!         "status": 200,
+//!         "payload": null
+//!     }
+//! ]
+//! ```
+//!
+//! The access tokens are removed from the recorded paths. The `payload` is
+//! the beginning of the request body, `null` if the request has no body.
+//!
+//! **Resource**
+//!
+//! Refer to the API [`AuditEntry`](crate::servers::apis::v1::context::audit::resources::AuditEntry)
+//! resource for more information about the response attributes.
+pub mod handlers;
+pub mod resources;
+pub mod responses;
+pub mod routes;
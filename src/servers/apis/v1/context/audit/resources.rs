@@ -0,0 +1,64 @@
+//! API resources for the [`audit`](crate::servers::apis::v1::context::audit) API context.
+use serde::{Deserialize, Serialize};
+
+use crate::core::audit::Entry;
+use crate::servers::apis::v1::serializers::CsvRecord;
+
+/// `AuditEntry` API resource. A request changing the tracker state.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// Time of the request, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The label of the access token, or `jwt:` and the subject of the JSON
+    /// Web Token.
+    pub actor: String,
+    /// The IP address of the client, if it's known.
+    pub client_ip: Option<String>,
+    /// The HTTP method.
+    pub method: String,
+    /// The path and the query of the request, without the access token.
+    pub path: String,
+    /// The HTTP status code of the response.
+    pub status: u16,
+    /// The beginning of the request body, if it has one.
+    pub payload: Option<String>,
+}
+
+impl AuditEntry {
+    #[must_use]
+    pub fn new_vec(entries: &[Entry]) -> Vec<Self> {
+        entries.iter().map(AuditEntry::from).collect()
+    }
+}
+
+impl From<&Entry> for AuditEntry {
+    fn from(entry: &Entry) -> Self {
+        Self {
+            timestamp: entry.timestamp,
+            actor: entry.actor.clone(),
+            client_ip: entry.client_ip.map(|client_ip| client_ip.to_string()),
+            method: entry.method.clone(),
+            path: entry.path.clone(),
+            status: entry.status,
+            payload: entry.payload.clone(),
+        }
+    }
+}
+
+impl CsvRecord for AuditEntry {
+    fn csv_header() -> Vec<&'static str> {
+        vec!["timestamp", "actor", "client_ip", "method", "path", "status", "payload"]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_string(),
+            self.actor.clone(),
+            self.client_ip.clone().unwrap_or_default(),
+            self.method.clone(),
+            self.path.clone(),
+            self.status.to_string(),
+            self.payload.clone().unwrap_or_default(),
+        ]
+    }
+}
@@ -0,0 +1,22 @@
+//! API responses for the [`audit`](crate::servers::apis::v1::context::audit) API context.
+use std::error::Error;
+
+use axum::response::Response;
+
+use super::resources::AuditEntry;
+use crate::core::audit::Entry;
+use crate::servers::apis::v1::responses::unhandled_rejection_response;
+use crate::servers::apis::v1::serializers::{resource_list_response, Format};
+
+/// `200` response that contains an array of [`AuditEntry`] resources in the
+/// negotiated format (json, bencode or csv).
+#[must_use]
+pub fn audit_log_response(entries: &[Entry], format: Format) -> Response {
+    resource_list_response(format, &AuditEntry::new_vec(entries))
+}
+
+/// `500` error response when the audit log cannot be loaded.
+#[must_use]
+pub fn failed_to_load_audit_log_response<E: Error>(e: E) -> Response {
+    unhandled_rejection_response(format!("failed to load audit log: {e}"))
+}
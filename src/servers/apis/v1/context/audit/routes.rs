@@ -0,0 +1,17 @@
+//! API routes for the [`audit`](crate::servers::apis::v1::context::audit) API context.
+//!
+//! - `GET /audit`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::audit).
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Router;
+
+use super::handlers::get_audit_log_handler;
+use crate::core::Tracker;
+
+/// It adds the routes to the router for the [`audit`](crate::servers::apis::v1::context::audit) API context.
+pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>) -> Router {
+    router.route(&format!("{prefix}/audit"), get(get_audit_log_handler).with_state(tracker))
+}
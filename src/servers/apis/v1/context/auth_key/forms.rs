@@ -19,4 +19,15 @@ pub struct AddKeyForm {
     #[serde_as(deserialize_as = "DefaultOnNull")]
     #[serde(rename = "seconds_valid")]
     pub opt_seconds_valid: Option<u64>,
+
+    /// Optional label to attribute the key, for example, to a user.
+    #[serde(default, rename = "label")]
+    pub opt_label: Option<String>,
+}
+
+/// This type contains the info needed to renew a tracker key.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RenewKeyForm {
+    /// How long the key will be valid in seconds, from now.
+    pub seconds_valid: u64,
 }
@@ -5,15 +5,20 @@ use std::time::Duration;
 
 use axum::extract::{self, Path, State};
 use axum::response::Response;
+use axum_extra::extract::Query;
 use serde::Deserialize;
+use torrust_tracker_primitives::pagination::Pagination;
 
-use super::forms::AddKeyForm;
+use super::forms::{AddKeyForm, RenewKeyForm};
 use super::responses::{
-    auth_key_response, failed_to_delete_key_response, failed_to_generate_key_response, failed_to_reload_keys_response,
-    invalid_auth_key_duration_response, invalid_auth_key_response,
+    auth_key_list_response, auth_key_response, failed_to_delete_key_response, failed_to_generate_key_response,
+    failed_to_reload_keys_response, failed_to_renew_key_response, invalid_auth_key_duration_response, invalid_auth_key_response,
+    unknown_auth_key_response,
 };
 use crate::core::auth::Key;
+use crate::core::error::PeerKeyError;
 use crate::core::{AddKeyRequest, Tracker};
+use crate::servers::apis::empty_string_as_none;
 use crate::servers::apis::v1::context::auth_key::resources::AuthKey;
 use crate::servers::apis::v1::responses::{invalid_auth_key_param_response, ok_response};
 
@@ -36,8 +41,9 @@ pub async fn add_auth_key_handler(
 ) -> Response {
     match tracker
         .add_peer_key(AddKeyRequest {
-            opt_key: add_key_form.opt_key.clone(),
+            opt_key: add_key_form.opt_key,
             opt_seconds_valid: add_key_form.opt_seconds_valid,
+            opt_label: add_key_form.opt_label,
         })
         .await
     {
@@ -47,6 +53,7 @@ pub async fn add_auth_key_handler(
                 invalid_auth_key_duration_response(seconds_valid)
             }
             crate::core::error::PeerKeyError::InvalidKey { key, source } => invalid_auth_key_response(&key, source),
+            crate::core::error::PeerKeyError::UnknownKey { key } => unknown_auth_key_response(key.value()),
             crate::core::error::PeerKeyError::DatabaseError { source } => failed_to_generate_key_response(source),
         },
     }
@@ -115,6 +122,70 @@ pub async fn delete_auth_key_handler(
     }
 }
 
+/// It handles the request to renew an authentication key.
+///
+/// It returns these types of responses:
+///
+/// - `200` with a json [`AuthKey`]
+///    resource. If the key was renewed successfully.
+/// - `400` with an error if the key is invalid or unknown, or if the duration
+///    overflows.
+/// - `500` with serialized error in debug format. If the key couldn't be
+///    renewed.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::auth_key#renew-an-authentication-key)
+/// for more information about this endpoint.
+pub async fn renew_auth_key_handler(
+    State(tracker): State<Arc<Tracker>>,
+    Path(seconds_valid_or_key): Path<KeyParam>,
+    extract::Json(renew_key_form): extract::Json<RenewKeyForm>,
+) -> Response {
+    match Key::from_str(&seconds_valid_or_key.0) {
+        Err(_) => invalid_auth_key_param_response(&seconds_valid_or_key.0),
+        Ok(key) => match tracker.renew_auth_key(&key, renew_key_form.seconds_valid).await {
+            Ok(auth_key) => auth_key_response(&AuthKey::from(auth_key)),
+            Err(err) => match err {
+                PeerKeyError::DurationOverflow { seconds_valid } => invalid_auth_key_duration_response(seconds_valid),
+                PeerKeyError::InvalidKey { key, source } => invalid_auth_key_response(&key, source),
+                PeerKeyError::UnknownKey { key } => unknown_auth_key_response(key.value()),
+                PeerKeyError::DatabaseError { source } => failed_to_renew_key_response(source),
+            },
+        },
+    }
+}
+
+/// A container for the pagination URL query parameters: `offset` and
+/// `limit`.
+#[derive(Deserialize, Debug)]
+pub struct QueryParams {
+    /// The offset of the first page to return. Starts at 0.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub offset: Option<u32>,
+    /// The maximum number of items to return per page.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub limit: Option<u32>,
+}
+
+/// It handles the request to list the authentication keys.
+///
+/// It returns a `200` response with a json array of [`AuthKey`] resources,
+/// ordered by key.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::auth_key#list-the-authentication-keys)
+/// for more information about this endpoint.
+pub async fn get_auth_keys_handler(State(tracker): State<Arc<Tracker>>, pagination: Query<QueryParams>) -> Response {
+    let pagination = Pagination::new_with_options(pagination.0.offset, pagination.0.limit);
+
+    let auth_keys = tracker
+        .get_auth_keys(Some(&pagination))
+        .await
+        .into_iter()
+        .map(AuthKey::from)
+        .collect::<Vec<_>>();
+
+    auth_key_list_response(&auth_keys)
+}
+
 /// It handles the request to reload the authentication keys from the database
 /// into memory.
 ///
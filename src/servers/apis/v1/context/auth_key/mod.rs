@@ -17,6 +17,8 @@
 //! # Endpoints
 //!
 //! - [Generate a new authentication key](#generate-a-new-authentication-key)
+//! - [List the authentication keys](#list-the-authentication-keys)
+//! - [Renew an authentication key](#renew-an-authentication-key)
 //! - [Delete an authentication key](#delete-an-authentication-key)
 //! - [Reload authentication keys](#reload-authentication-keys)
 //!
@@ -32,8 +34,9 @@
 //! ---|---|---|---|---
 //! `key` | 32-char string (0-9, a-z, A-Z) or `null` | The optional pre-generated key. | Yes | `Xc1L4PbQJSFGlrgSRZl8wxSFAuMa21z7` or `null`
 //! `seconds_valid` | positive integer or `null` | The number of seconds the key will be valid. | Yes | `3600` or `null`
+//! `label` | string or `null` | The optional label to attribute the key, for example, to a user. | No | `user-1234`
 //!
-//! > **NOTICE**: the `key` and `seconds_valid` fields are optional. If `key` is not provided the tracker
+//! > **NOTICE**: the `key`, `seconds_valid` and `label` fields are optional. If `key` is not provided the tracker
 //! > will generated a random one. If `seconds_valid` field is not provided the key will be permanent. You can use the `null` value.
 //!
 //! **Example request**
@@ -43,7 +46,8 @@
 //!      -H "Content-Type: application/json" \
 //!      -d '{
 //!            "key": "xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6",
-//!            "seconds_valid": 7200
+//!            "seconds_valid": 7200,
+//!            "label": "user-1234"
 //!          }'
 //! ```
 //!
@@ -53,7 +57,9 @@
 //! {
 //!     "key": "xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6",
 //!     "valid_until": 1680009900,
-//!     "expiry_time": "2023-03-28 13:25:00.058085050 UTC"
+//!     "expiry_time": "2023-03-28 13:25:00.058085050 UTC",
+//!     "created_at": "2023-03-28 11:25:00.058085050 UTC",
+//!     "label": "user-1234"
 //! }
 //! ```
 //!
@@ -67,6 +73,80 @@
 //! Refer to the API [`AuthKey`](crate::servers::apis::v1::context::auth_key::resources::AuthKey)
 //! resource for more information about the response attributes.
 //!
+//! # List the authentication keys
+//!
+//! `GET /keys`
+//!
+//! It returns the authentication keys, ordered by key. Expired keys are
+//! listed until the cleanup job removes them (every
+//! `core.expired_keys_cleanup_interval` seconds).
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `offset` | positive integer | The page number, starting at 0 | No | `1`
+//! `limit` | positive integer | Page size. The number of results per page | No | `10`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/keys?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!     {
+//!         "key": "xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6",
+//!         "valid_until": 1680009900,
+//!         "expiry_time": "2023-03-28 13:25:00.058085050 UTC"
+//!     }
+//! ]
+//! ```
+//!
+//! # Renew an authentication key
+//!
+//! `PUT /key/:key`
+//!
+//! It sets a new expiration time for an existing authentication key. The key
+//! will be valid for `seconds_valid` seconds from now, even if it was
+//! permanent or it had already expired.
+//!
+//! **Path parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `key` | 32-char string (0-9, a-z, A-Z) | The `key` to renew. | Yes | `xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6`
+//!
+//! **PUT parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `seconds_valid` | positive integer | The number of seconds the key will be valid. | Yes | `3600`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X PUT http://localhost:1212/api/v1/key/xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6?token=MyAccessToken \
+//!      -H "Content-Type: application/json" \
+//!      -d '{ "seconds_valid": 7200 }'
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "key": "xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6",
+//!     "valid_until": 1680009900,
+//!     "expiry_time": "2023-03-28 13:25:00.058085050 UTC"
+//! }
+//! ```
+//!
+//! It you try to renew a non-existent key, the response will be an error with
+//! a `400` status code.
+//!
 //! # Delete an authentication key
 //!
 //! `DELETE /key/:key`
@@ -1,7 +1,7 @@
 //! API resources for the [`auth_key`](crate::servers::apis::v1::context::auth_key) API context.
 
 use serde::{Deserialize, Serialize};
-use torrust_tracker_clock::conv::convert_from_iso_8601_to_timestamp;
+use torrust_tracker_clock::conv::{convert_from_iso_8601_to_timestamp, convert_from_timestamp_to_datetime_utc};
 
 use crate::core::auth::{self, Key};
 
@@ -15,6 +15,13 @@ pub struct AuthKey {
     pub valid_until: Option<u64>, // todo: remove when the torrust-index-backend starts using the `expiry_time` attribute.
     /// The ISO 8601 timestamp when the key will expire.
     pub expiry_time: Option<String>,
+    /// The ISO 8601 timestamp when the key was created. Keys created by
+    /// older versions of the tracker don't have it.
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// The optional label to attribute the key.
+    #[serde(default)]
+    pub label: Option<String>,
 }
 
 impl From<AuthKey> for auth::PeerKey {
@@ -24,6 +31,10 @@ impl From<AuthKey> for auth::PeerKey {
             valid_until: auth_key_resource
                 .expiry_time
                 .map(|expiry_time| convert_from_iso_8601_to_timestamp(&expiry_time)),
+            created_at: auth_key_resource
+                .created_at
+                .map(|created_at| convert_from_iso_8601_to_timestamp(&created_at)),
+            label: auth_key_resource.label,
         }
     }
 }
@@ -31,16 +42,24 @@ impl From<AuthKey> for auth::PeerKey {
 #[allow(deprecated)]
 impl From<auth::PeerKey> for AuthKey {
     fn from(auth_key: auth::PeerKey) -> Self {
+        let created_at = auth_key
+            .created_at
+            .map(|created_at| convert_from_timestamp_to_datetime_utc(created_at).to_string());
+
         match (auth_key.valid_until, auth_key.expiry_time()) {
             (Some(valid_until), Some(expiry_time)) => AuthKey {
                 key: auth_key.key.to_string(),
                 valid_until: Some(valid_until.as_secs()),
                 expiry_time: Some(expiry_time.to_string()),
+                created_at,
+                label: auth_key.label,
             },
             _ => AuthKey {
                 key: auth_key.key.to_string(),
                 valid_until: None,
                 expiry_time: None,
+                created_at,
+                label: auth_key.label,
             },
         }
     }
@@ -83,13 +102,17 @@ mod tests {
             key: "IaWDneuFNZi8IB4MPA3qW1CD0M30EZSM".to_string(), // cspell:disable-line
             valid_until: Some(one_hour_after_unix_epoch().timestamp),
             expiry_time: Some(one_hour_after_unix_epoch().iso_8601_v1),
+            created_at: Some("1970-01-01T00:00:00.000Z".to_string()),
+            label: Some("user-1234".to_string()),
         };
 
         assert_eq!(
             auth::PeerKey::from(auth_key_resource),
             auth::PeerKey {
                 key: "IaWDneuFNZi8IB4MPA3qW1CD0M30EZSM".parse::<Key>().unwrap(), // cspell:disable-line
-                valid_until: Some(CurrentClock::now_add(&Duration::new(one_hour_after_unix_epoch().timestamp, 0)).unwrap())
+                valid_until: Some(CurrentClock::now_add(&Duration::new(one_hour_after_unix_epoch().timestamp, 0)).unwrap()),
+                created_at: Some(CurrentClock::now()),
+                label: Some("user-1234".to_string()),
             }
         );
     }
@@ -102,6 +125,8 @@ mod tests {
         let auth_key = auth::PeerKey {
             key: "IaWDneuFNZi8IB4MPA3qW1CD0M30EZSM".parse::<Key>().unwrap(), // cspell:disable-line
             valid_until: Some(CurrentClock::now_add(&Duration::new(one_hour_after_unix_epoch().timestamp, 0)).unwrap()),
+            created_at: Some(CurrentClock::now()),
+            label: None,
         };
 
         assert_eq!(
@@ -110,6 +135,8 @@ mod tests {
                 key: "IaWDneuFNZi8IB4MPA3qW1CD0M30EZSM".to_string(), // cspell:disable-line
                 valid_until: Some(one_hour_after_unix_epoch().timestamp),
                 expiry_time: Some(one_hour_after_unix_epoch().iso_8601_v2),
+                created_at: Some("1970-01-01 00:00:00 UTC".to_string()),
+                label: None,
             }
         );
     }
@@ -122,9 +149,11 @@ mod tests {
                 key: "IaWDneuFNZi8IB4MPA3qW1CD0M30EZSM".to_string(), // cspell:disable-line
                 valid_until: Some(one_hour_after_unix_epoch().timestamp),
                 expiry_time: Some(one_hour_after_unix_epoch().iso_8601_v1),
+                created_at: None,
+                label: Some("user-1234".to_string()),
             })
             .unwrap(),
-            "{\"key\":\"IaWDneuFNZi8IB4MPA3qW1CD0M30EZSM\",\"valid_until\":60,\"expiry_time\":\"1970-01-01T00:01:00.000Z\"}" // cspell:disable-line
+            "{\"key\":\"IaWDneuFNZi8IB4MPA3qW1CD0M30EZSM\",\"valid_until\":60,\"expiry_time\":\"1970-01-01T00:01:00.000Z\",\"created_at\":null,\"label\":\"user-1234\"}" // cspell:disable-line
         );
     }
 }
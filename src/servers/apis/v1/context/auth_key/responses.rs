@@ -22,6 +22,21 @@ pub fn auth_key_response(auth_key: &AuthKey) -> Response {
         .into_response()
 }
 
+/// `200` response that contains an array of `AuthKey` resources as json.
+///
+/// # Panics
+///
+/// Will panic if it can't convert the `AuthKey` resources to json
+#[must_use]
+pub fn auth_key_list_response(auth_keys: &[AuthKey]) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
+        serde_json::to_string(auth_keys).unwrap(),
+    )
+        .into_response()
+}
+
 // Error responses
 
 /// `500` error response when a new authentication key cannot be generated.
@@ -42,6 +57,12 @@ pub fn failed_to_delete_key_response<E: Error>(e: E) -> Response {
     unhandled_rejection_response(format!("failed to delete key: {e}"))
 }
 
+/// `500` error response when an authentication key cannot be renewed.
+#[must_use]
+pub fn failed_to_renew_key_response<E: Error>(e: E) -> Response {
+    unhandled_rejection_response(format!("failed to renew key: {e}"))
+}
+
 /// `500` error response when the authentication keys cannot be reloaded from
 /// the database into memory.
 #[must_use]
@@ -54,6 +75,11 @@ pub fn invalid_auth_key_response<E: Error>(auth_key: &str, e: E) -> Response {
     bad_request_response(&format!("Invalid URL: invalid auth key: string \"{auth_key}\", {e}"))
 }
 
+#[must_use]
+pub fn unknown_auth_key_response(auth_key: &str) -> Response {
+    bad_request_response(&format!("Invalid URL: unknown auth key: \"{auth_key}\""))
+}
+
 #[must_use]
 pub fn invalid_auth_key_duration_response(duration: u64) -> Response {
     bad_request_response(&format!("Invalid URL: invalid auth key duration: \"{duration}\""))
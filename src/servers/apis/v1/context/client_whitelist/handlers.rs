@@ -0,0 +1,55 @@
+//! API handlers for the [`client_whitelist`](crate::servers::apis::v1::context::client_whitelist) API context.
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::response::Response;
+
+use super::responses::{client_whitelist_response, invalid_peer_id_prefix_param_response};
+use crate::core::Tracker;
+use crate::servers::apis::v1::responses::ok_response;
+
+/// It handles the request to get the peer ID prefixes of the allowed clients.
+///
+/// It returns a `200` response with the prefixes in json.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::client_whitelist#get-the-client-whitelist)
+/// for more information about this endpoint.
+pub async fn get_client_whitelist_handler(State(tracker): State<Arc<Tracker>>) -> Response {
+    client_whitelist_response(&tracker.get_client_whitelist())
+}
+
+/// It handles the request to add a client to the whitelist.
+///
+/// It returns:
+///
+/// - `200` response with a [`ActionStatus::Ok`](crate::servers::apis::v1::responses::ActionStatus::Ok) in json.
+/// - `400` with an error if the peer ID prefix is not valid.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::client_whitelist#add-a-client-to-the-whitelist)
+/// for more information about this endpoint.
+pub async fn add_client_to_whitelist_handler(
+    State(tracker): State<Arc<Tracker>>,
+    Path(peer_id_prefix): Path<String>,
+) -> Response {
+    if !tracker.add_client_to_whitelist(&peer_id_prefix) {
+        return invalid_peer_id_prefix_param_response(&peer_id_prefix);
+    }
+
+    ok_response()
+}
+
+/// It handles the request to remove a client from the whitelist.
+///
+/// It returns a `200` response with a [`ActionStatus::Ok`](crate::servers::apis::v1::responses::ActionStatus::Ok)
+/// in json, even if the client was not whitelisted.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::client_whitelist#remove-a-client-from-the-whitelist)
+/// for more information about this endpoint.
+pub async fn remove_client_from_whitelist_handler(
+    State(tracker): State<Arc<Tracker>>,
+    Path(peer_id_prefix): Path<String>,
+) -> Response {
+    tracker.remove_client_from_whitelist(&peer_id_prefix);
+
+    ok_response()
+}
@@ -0,0 +1,94 @@
+//! Client whitelist API context.
+//!
+//! This API context is responsible for handling the BitTorrent clients
+//! allowed to announce to a private tracker. Clients are identified by the
+//! prefix of their peer ID, for example, `-qB45` for qBittorrent 4.5.x or
+//! `-TR40` for Transmission 4.0.x. Refer to the
+//! [`client_whitelist`](crate::core::client_whitelist) module for more
+//! information.
+//!
+//! The whitelist is only enforced when the tracker runs in `private` mode. An
+//! empty whitelist allows all the clients.
+//!
+//! > **NOTICE**: changes made with the API are only kept in memory. They are
+//! > lost when the tracker is restarted. Use the `core.client_whitelist`
+//! > section of the configuration to set the initial prefixes.
+//!
+//! # Endpoints
+//!
+//! - [Get the client whitelist](#get-the-client-whitelist)
+//! - [Add a client to the whitelist](#add-a-client-to-the-whitelist)
+//! - [Remove a client from the whitelist](#remove-a-client-from-the-whitelist)
+//!
+//! # Get the client whitelist
+//!
+//! `GET /client-whitelist`
+//!
+//! It returns the peer ID prefixes of the allowed clients in alphabetical
+//! order.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/client-whitelist?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!     "-TR40",
+//!     "-qB45"
+//! ]
+//! ```
+//!
+//! # Add a client to the whitelist
+//!
+//! `POST /client-whitelist/:peer_id_prefix`
+//!
+//! **Path parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `peer_id_prefix` | 1 to 20-char ASCII string | The prefix of the peer IDs of the client | Yes | `-qB45`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X POST "http://127.0.0.1:1212/api/v1/client-whitelist/-qB45?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "status": "ok"
+//! }
+//! ```
+//!
+//! **Example error response** `400`
+//!
+//! ```text
+//! Invalid peer ID prefix param "-qB45ñ": it must have between 1 and 20 ASCII characters
+//! ```
+//!
+//! # Remove a client from the whitelist
+//!
+//! `DELETE /client-whitelist/:peer_id_prefix`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X DELETE "http://127.0.0.1:1212/api/v1/client-whitelist/-qB45?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "status": "ok"
+//! }
+//! ```
+pub mod handlers;
+pub mod responses;
+pub mod routes;
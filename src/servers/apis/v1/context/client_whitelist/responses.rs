@@ -0,0 +1,31 @@
+//! API responses for the [`client_whitelist`](crate::servers::apis::v1::context::client_whitelist)
+//! API context.
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+
+use crate::servers::apis::v1::responses::bad_request_response;
+
+/// `200` response that contains the peer ID prefixes of the allowed clients
+/// as json.
+///
+/// # Panics
+///
+/// Will panic if it can't convert the prefixes to json
+#[must_use]
+pub fn client_whitelist_response(peer_id_prefixes: &[String]) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
+        serde_json::to_string(peer_id_prefixes).unwrap(),
+    )
+        .into_response()
+}
+
+// Error responses
+
+#[must_use]
+pub fn invalid_peer_id_prefix_param_response(peer_id_prefix: &str) -> Response {
+    bad_request_response(&format!(
+        "Invalid peer ID prefix param \"{peer_id_prefix}\": it must have between 1 and 20 ASCII characters"
+    ))
+}
@@ -0,0 +1,30 @@
+//! API routes for the [`client_whitelist`](crate::servers::apis::v1::context::client_whitelist) API context.
+//!
+//! - `GET /client-whitelist`
+//! - `POST /client-whitelist/:peer_id_prefix`
+//! - `DELETE /client-whitelist/:peer_id_prefix`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::client_whitelist).
+use std::sync::Arc;
+
+use axum::routing::{delete, get, post};
+use axum::Router;
+
+use super::handlers::{add_client_to_whitelist_handler, get_client_whitelist_handler, remove_client_from_whitelist_handler};
+use crate::core::Tracker;
+
+/// It adds the routes to the router for the [`client_whitelist`](crate::servers::apis::v1::context::client_whitelist) API context.
+pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>) -> Router {
+    let prefix = format!("{prefix}/client-whitelist");
+
+    router
+        .route(&prefix, get(get_client_whitelist_handler).with_state(tracker.clone()))
+        .route(
+            &format!("{prefix}/:peer_id_prefix"),
+            post(add_client_to_whitelist_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/:peer_id_prefix"),
+            delete(remove_client_from_whitelist_handler).with_state(tracker),
+        )
+}
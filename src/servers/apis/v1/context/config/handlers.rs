@@ -0,0 +1,19 @@
+//! API handlers for the [`config`](crate::servers::apis::v1::context::config) API context.
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::Response;
+use torrust_tracker_configuration::Configuration;
+
+use super::responses::config_response;
+
+/// It handles the request to get the configuration of the running tracker.
+///
+/// It returns a `200` response with the configuration in json, with the
+/// secrets masked.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::config#get-the-running-configuration)
+/// for more information about this endpoint.
+pub async fn get_config_handler(State(running_config): State<Arc<Configuration>>) -> Response {
+    config_response(running_config.as_ref().clone().mask_secrets())
+}
@@ -0,0 +1,11 @@
+//! API responses for the [`config`](crate::servers::apis::v1::context::config)
+//! API context.
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use torrust_tracker_configuration::Configuration;
+
+/// `200` response that contains the configuration in json.
+#[must_use]
+pub fn config_response(configuration: Configuration) -> Response {
+    Json(configuration).into_response()
+}
@@ -0,0 +1,20 @@
+//! API routes for the [`config`](crate::servers::apis::v1::context::config) API context.
+//!
+//! - `GET /config`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::config).
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Router;
+use torrust_tracker_configuration::Configuration;
+
+use super::handlers::get_config_handler;
+
+/// It adds the routes to the router for the [`config`](crate::servers::apis::v1::context::config) API context.
+pub fn add(prefix: &str, router: Router, running_config: Arc<Configuration>) -> Router {
+    router.route(
+        &format!("{prefix}/config"),
+        get(get_config_handler).with_state(running_config),
+    )
+}
@@ -0,0 +1,27 @@
+//! API handlers for the [`connection_id_secret`](crate::servers::apis::v1::context::connection_id_secret) API context.
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::Response;
+
+use super::resources::SecretRotation;
+use super::responses::{failed_to_persist_connection_id_secret_response, secret_rotation_response};
+use crate::core::services::connection_id_secret::rotate;
+use crate::core::Tracker;
+
+/// It handles the request to rotate the UDP connection ID secret.
+///
+/// It returns:
+///
+/// - `200` with a json [`SecretRotation`] resource.
+/// - `500` with serialized error in debug format if the new secret couldn't
+///   be saved to the secret file. The new secret is used anyway.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::connection_id_secret#rotate-the-secret)
+/// for more information about this endpoint.
+pub async fn rotate_connection_id_secret_handler(State(tracker): State<Arc<Tracker>>) -> Response {
+    match rotate(&tracker) {
+        Ok(rotation) => secret_rotation_response(&SecretRotation::from(rotation)),
+        Err(e) => failed_to_persist_connection_id_secret_response(e),
+    }
+}
@@ -0,0 +1,48 @@
+//! UDP connection ID secret API context.
+//!
+//! This API context is responsible for rotating the secret used to generate
+//! the UDP tracker connection IDs.
+//!
+//! After a rotation, the connection IDs generated with the previous secret are
+//! still accepted during the grace period configured in the
+//! `[core.udp_connection_id_secret_rotation]` section. When the secret is
+//! persisted in a secret file (`udp_connection_id_secret_file`), the new
+//! secret is saved to it. Refer to the
+//! [`connection_id_secret`](crate::core::services::connection_id_secret)
+//! services for more information.
+//!
+//! # Endpoints
+//!
+//! - [Rotate the secret](#rotate-the-secret)
+//!
+//! # Rotate the secret
+//!
+//! `POST /connection-id-secret/rotate`
+//!
+//! It replaces the secret with a new random one.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X POST http://localhost:1212/api/v1/connection-id-secret/rotate?token=MyAccessToken
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "rotated_at": 1726138800,
+//!     "previous_secret_valid_until": 1726139100
+//! }
+//! ```
+//!
+//! Timestamps are Unix timestamps in seconds.
+//!
+//! **Resource**
+//!
+//! Refer to the API [`SecretRotation`](crate::servers::apis::v1::context::connection_id_secret::resources::SecretRotation)
+//! resource for more information about the response attributes.
+pub mod handlers;
+pub mod resources;
+pub mod responses;
+pub mod routes;
@@ -0,0 +1,23 @@
+//! API resources for the [`connection_id_secret`](crate::servers::apis::v1::context::connection_id_secret) API context.
+use serde::Serialize;
+
+use crate::core::services::connection_id_secret::Rotation;
+
+/// The result of a secret rotation.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct SecretRotation {
+    /// When the secret was rotated, as a Unix timestamp in seconds.
+    pub rotated_at: u64,
+    /// Until when the previous secret is still accepted, as a Unix timestamp
+    /// in seconds.
+    pub previous_secret_valid_until: u64,
+}
+
+impl From<Rotation> for SecretRotation {
+    fn from(rotation: Rotation) -> Self {
+        Self {
+            rotated_at: rotation.rotated_at.as_secs(),
+            previous_secret_valid_until: rotation.previous_secret_valid_until.as_secs(),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! API responses for the [`connection_id_secret`](crate::servers::apis::v1::context::connection_id_secret)
+//! API context.
+use std::error::Error;
+
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+
+use super::resources::SecretRotation;
+use crate::servers::apis::v1::responses::unhandled_rejection_response;
+
+/// `200` response that contains the [`SecretRotation`] resource as json.
+///
+/// # Panics
+///
+/// Will panic if it can't convert the `SecretRotation` resource to json
+#[must_use]
+pub fn secret_rotation_response(rotation: &SecretRotation) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
+        serde_json::to_string(rotation).unwrap(),
+    )
+        .into_response()
+}
+
+/// `500` error response when the rotated secret cannot be saved to the secret
+/// file.
+#[must_use]
+pub fn failed_to_persist_connection_id_secret_response<E: Error>(e: E) -> Response {
+    unhandled_rejection_response(format!("failed to persist the rotated connection ID secret: {e}"))
+}
@@ -0,0 +1,20 @@
+//! API routes for the [`connection_id_secret`](crate::servers::apis::v1::context::connection_id_secret) API context.
+//!
+//! - `POST /connection-id-secret/rotate`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::connection_id_secret).
+use std::sync::Arc;
+
+use axum::routing::post;
+use axum::Router;
+
+use super::handlers::rotate_connection_id_secret_handler;
+use crate::core::Tracker;
+
+/// It adds the routes to the router for the [`connection_id_secret`](crate::servers::apis::v1::context::connection_id_secret) API context.
+pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>) -> Router {
+    router.route(
+        &format!("{prefix}/connection-id-secret/rotate"),
+        post(rotate_connection_id_secret_handler).with_state(tracker),
+    )
+}
@@ -0,0 +1,47 @@
+//! API handlers for the [`events`](crate::servers::apis::v1::context::events) API context.
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::Response;
+use axum_extra::extract::Query;
+use serde::Deserialize;
+use torrust_tracker_primitives::info_hash::InfoHash;
+
+use super::responses::events_response;
+use crate::core::Tracker;
+use crate::servers::apis::v1::responses::invalid_info_hash_param_response;
+
+/// A container for the URL query parameters of the events stream.
+#[derive(Deserialize, Debug)]
+pub struct QueryParams {
+    /// The infohashes of the torrents whose events are streamed. The events
+    /// of all the torrents are streamed if it's empty.
+    #[serde(default)]
+    pub info_hash: Vec<String>,
+}
+
+/// It handles the request to stream the activity of the tracker.
+///
+/// It returns:
+///
+/// - `200` response with a stream of Server-Sent Events.
+/// - `400` with an error if any of the infohashes is not valid.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::events#stream-the-tracker-activity)
+/// for more information about this endpoint.
+pub async fn events_handler(State(tracker): State<Arc<Tracker>>, params: Query<QueryParams>) -> Response {
+    let mut info_hashes = HashSet::new();
+
+    for info_hash in &params.0.info_hash {
+        match InfoHash::from_str(info_hash) {
+            Ok(info_hash) => {
+                let _: bool = info_hashes.insert(info_hash);
+            }
+            Err(_) => return invalid_info_hash_param_response(info_hash),
+        }
+    }
+
+    events_response(tracker.subscribe_to_activity(), info_hashes)
+}
@@ -0,0 +1,62 @@
+//! Events API context.
+//!
+//! This API context streams the activity of the tracker as
+//! [Server-Sent Events](https://html.spec.whatwg.org/multipage/server-sent-events.html):
+//! the announce requests, the torrents scraped and the peers completing the
+//! download of a torrent. It's useful for live dashboards and debugging,
+//! without scraping the logs.
+//!
+//! Only the events published after the client connects are streamed. The
+//! events are buffered in a bounded channel, so the clients reading them too
+//! slowly skip the oldest ones. Refer to the [`activity`](crate::core::activity)
+//! module for more information.
+//!
+//! # Endpoints
+//!
+//! - [Stream the tracker activity](#stream-the-tracker-activity)
+//!
+//! # Stream the tracker activity
+//!
+//! `GET /events`
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `info_hash` | 40-char string | Only stream the events of this torrent. It can be repeated | No | `9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -N "http://127.0.0.1:1212/api/v1/events?token=MyAccessToken&info_hash=9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! The name of each event is its kind: `announce`, `scrape` or `completed`.
+//! The data is the [`Event`](crate::core::activity::Event) in json.
+//!
+//! ```text
+//! event: announce
+//! data: {"kind":"announce","info_hash":"9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d","announce_event":"Started","seeders":0,"leechers":1,"completed":0,"timestamp":1700000000}
+//!
+//! event: completed
+//! data: {"kind":"completed","info_hash":"9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d","seeders":1,"leechers":0,"completed":1,"timestamp":1700000060}
+//! ```
+//!
+//! When the client falls behind, a `lagged` event with the number of skipped
+//! events is sent instead of them:
+//!
+//! ```text
+//! event: lagged
+//! data: 42
+//! ```
+//!
+//! **Not allowed response** `400`
+//!
+//! ```text
+//! Invalid URL: invalid infohash param: string "INVALID INFOHASH", expected a 40 character long string
+//! ```
+pub mod handlers;
+pub mod responses;
+pub mod routes;
@@ -0,0 +1,42 @@
+//! API responses for the [`events`](crate::servers::apis::v1::context::events)
+//! API context.
+use std::collections::HashSet;
+
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use torrust_tracker_primitives::info_hash::InfoHash;
+
+use crate::core::activity::{self, Kind};
+
+/// `200` response with the stream of Server-Sent Events of the tracker
+/// activity. Only the events of the `info_hashes` are sent, unless it's
+/// empty.
+#[must_use]
+pub fn events_response(receiver: Receiver<activity::Event>, info_hashes: HashSet<InfoHash>) -> Response {
+    let stream = futures::stream::unfold((receiver, info_hashes), |(mut receiver, info_hashes)| async move {
+        loop {
+            let event = match receiver.recv().await {
+                Ok(event) if info_hashes.is_empty() || info_hashes.contains(&event.info_hash) => {
+                    Event::default().event(event_name(event.kind)).json_data(&event)
+                }
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => Ok(Event::default().event("lagged").data(skipped.to_string())),
+                Err(RecvError::Closed) => return None,
+            };
+
+            return Some((event, (receiver, info_hashes)));
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default()).into_response()
+}
+
+fn event_name(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Announce => "announce",
+        Kind::Scrape => "scrape",
+        Kind::Completed => "completed",
+    }
+}
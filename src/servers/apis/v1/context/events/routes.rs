@@ -0,0 +1,17 @@
+//! API routes for the [`events`](crate::servers::apis::v1::context::events) API context.
+//!
+//! - `GET /events`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::events).
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Router;
+
+use super::handlers::events_handler;
+use crate::core::Tracker;
+
+/// It adds the routes to the router for the [`events`](crate::servers::apis::v1::context::events) API context.
+pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>) -> Router {
+    router.route(&format!("{prefix}/events"), get(events_handler).with_state(tracker))
+}
@@ -0,0 +1,40 @@
+//! API handlers for the [`maintenance`](crate::servers::apis::v1::context::maintenance) API context.
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::Response;
+
+use super::responses::{cleanup_report_response, compaction_report_response, failed_to_compact_database_response};
+use crate::core::services::maintenance::{cleanup_peers, compact_database};
+use crate::core::Tracker;
+
+/// It handles the request to remove the inactive peers and the peerless
+/// torrents now.
+///
+/// It returns `200` with a json [`CleanupReport`](crate::servers::apis::v1::context::maintenance::resources::CleanupReport)
+/// resource.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::maintenance#clean-up-the-peers)
+/// for more information about this endpoint.
+pub async fn cleanup_peers_handler(State(tracker): State<Arc<Tracker>>) -> Response {
+    cleanup_report_response(cleanup_peers(&tracker).await)
+}
+
+/// It handles the request to remove the expired authentication keys and
+/// compact the database.
+///
+/// It returns:
+///
+/// - `200` with a json [`CompactionReport`](crate::servers::apis::v1::context::maintenance::resources::CompactionReport)
+///   resource.
+/// - `500` with serialized error in debug format if the keys couldn't be
+///   removed or the database couldn't be compacted.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::maintenance#compact-the-database)
+/// for more information about this endpoint.
+pub async fn compact_database_handler(State(tracker): State<Arc<Tracker>>) -> Response {
+    match compact_database(&tracker).await {
+        Ok(report) => compaction_report_response(report),
+        Err(e) => failed_to_compact_database_response(e),
+    }
+}
@@ -0,0 +1,70 @@
+//! Maintenance API context.
+//!
+//! This API context is responsible for running the maintenance jobs of the
+//! tracker on demand, instead of waiting for their next periodic run. Refer
+//! to the [`maintenance`](crate::core::services::maintenance) services for
+//! more information.
+//!
+//! # Endpoints
+//!
+//! - [Clean up the peers](#clean-up-the-peers)
+//! - [Compact the database](#compact-the-database)
+//!
+//! # Clean up the peers
+//!
+//! `POST /maintenance/cleanup-peers`
+//!
+//! It removes the peers that have not announced for `max_peer_timeout` and,
+//! if `remove_peerless_torrents` is enabled, the torrents without peers. It's
+//! the same sweep run every `inactive_peer_cleanup_interval`.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X POST "http://127.0.0.1:1212/api/v1/maintenance/cleanup-peers?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "removed_peers": 12,
+//!     "removed_torrents": 3
+//! }
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`CleanupReport`](crate::servers::apis::v1::context::maintenance::resources::CleanupReport)
+//! resource for more information about the response attributes.
+//!
+//! # Compact the database
+//!
+//! `POST /maintenance/compact-db`
+//!
+//! It removes the expired authentication keys and compacts the database:
+//! `VACUUM` for `SQLite` and `OPTIMIZE TABLE` for `MySQL`. The database can
+//! be locked while it's compacted.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X POST "http://127.0.0.1:1212/api/v1/maintenance/compact-db?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "removed_expired_keys": 2
+//! }
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`CompactionReport`](crate::servers::apis::v1::context::maintenance::resources::CompactionReport)
+//! resource for more information about the response attributes.
+pub mod handlers;
+pub mod resources;
+pub mod responses;
+pub mod routes;
@@ -0,0 +1,37 @@
+//! API resources for the [`maintenance`](crate::servers::apis::v1::context::maintenance) API context.
+use serde::{Deserialize, Serialize};
+
+use crate::core::services::maintenance;
+
+/// `CleanupReport` API resource. What the cleanup of the peers removed.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct CleanupReport {
+    /// Number of inactive peers removed.
+    pub removed_peers: u64,
+    /// Number of peerless torrents removed.
+    pub removed_torrents: u64,
+}
+
+impl From<maintenance::CleanupReport> for CleanupReport {
+    fn from(report: maintenance::CleanupReport) -> Self {
+        Self {
+            removed_peers: report.removed_peers,
+            removed_torrents: report.removed_torrents,
+        }
+    }
+}
+
+/// `CompactionReport` API resource. What the database maintenance removed.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// Number of expired authentication keys removed.
+    pub removed_expired_keys: usize,
+}
+
+impl From<maintenance::CompactionReport> for CompactionReport {
+    fn from(report: maintenance::CompactionReport) -> Self {
+        Self {
+            removed_expired_keys: report.removed_expired_keys,
+        }
+    }
+}
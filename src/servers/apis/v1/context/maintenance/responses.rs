@@ -0,0 +1,26 @@
+//! API responses for the [`maintenance`](crate::servers::apis::v1::context::maintenance) API context.
+use std::error::Error;
+
+use axum::response::{IntoResponse, Json, Response};
+
+use super::resources::{CleanupReport, CompactionReport};
+use crate::core::services::maintenance;
+use crate::servers::apis::v1::responses::unhandled_rejection_response;
+
+/// `200` response that contains the [`CleanupReport`] resource as json.
+#[must_use]
+pub fn cleanup_report_response(report: maintenance::CleanupReport) -> Response {
+    Json(CleanupReport::from(report)).into_response()
+}
+
+/// `200` response that contains the [`CompactionReport`] resource as json.
+#[must_use]
+pub fn compaction_report_response(report: maintenance::CompactionReport) -> Response {
+    Json(CompactionReport::from(report)).into_response()
+}
+
+/// `500` error response when the database maintenance fails.
+#[must_use]
+pub fn failed_to_compact_database_response<E: Error>(e: E) -> Response {
+    unhandled_rejection_response(format!("failed to compact database: {e}"))
+}
@@ -0,0 +1,26 @@
+//! API routes for the [`maintenance`](crate::servers::apis::v1::context::maintenance) API context.
+//!
+//! - `POST /maintenance/cleanup-peers`
+//! - `POST /maintenance/compact-db`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::maintenance).
+use std::sync::Arc;
+
+use axum::routing::post;
+use axum::Router;
+
+use super::handlers::{cleanup_peers_handler, compact_database_handler};
+use crate::core::Tracker;
+
+/// It adds the routes to the router for the [`maintenance`](crate::servers::apis::v1::context::maintenance) API context.
+pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>) -> Router {
+    router
+        .route(
+            &format!("{prefix}/maintenance/cleanup-peers"),
+            post(cleanup_peers_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/maintenance/compact-db"),
+            post(compact_database_handler).with_state(tracker),
+        )
+}
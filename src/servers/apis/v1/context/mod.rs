@@ -2,8 +2,17 @@
 //!
 //! Each context is a module that contains the API endpoints related to a
 //! specific resource group.
+pub mod abuse;
+pub mod audit;
 pub mod auth_key;
+pub mod client_whitelist;
+pub mod config;
+pub mod connection_id_secret;
+pub mod events;
 pub mod health_check;
+pub mod maintenance;
 pub mod stats;
+pub mod token;
 pub mod torrent;
+pub mod warning;
 pub mod whitelist;
@@ -3,19 +3,114 @@
 use std::sync::Arc;
 
 use axum::extract::State;
-use axum::response::Json;
+use axum::response::Response;
 
-use super::resources::Stats;
-use super::responses::stats_response;
+use super::responses::{
+    database_pool_stats_response, database_query_stats_response, experiment_stats_response, load_stats_response,
+    proxy_stats_response, rejection_stats_response, stats_response, stats_summary_response, udp_socket_stats_response,
+};
 use crate::core::services::statistics::get_metrics;
 use crate::core::Tracker;
+use crate::servers::apis::v1::serializers::Negotiated;
 
 /// It handles the request to get the tracker statistics.
 ///
-/// It returns a `200` response with a json [`Stats`]
+/// It returns a `200` response with a [`Stats`](crate::servers::apis::v1::context::stats::resources::Stats)
+/// resource serialized as json, bencode or csv depending on the `Accept` header.
 ///
 /// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats#get-tracker-statistics)
 /// for more information about this endpoint.
-pub async fn get_stats_handler(State(tracker): State<Arc<Tracker>>) -> Json<Stats> {
-    stats_response(get_metrics(tracker.clone()).await)
+pub async fn get_stats_handler(State(tracker): State<Arc<Tracker>>, Negotiated(format): Negotiated) -> Response {
+    stats_response(get_metrics(tracker.clone()).await, format)
+}
+
+/// It handles the request to get the statistics for each reverse proxy.
+///
+/// It returns a `200` response with a list of [`ProxyStats`](crate::servers::apis::v1::context::stats::resources::ProxyStats)
+/// resources serialized as json, bencode or csv depending on the `Accept` header.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats#get-reverse-proxy-statistics)
+/// for more information about this endpoint.
+pub async fn get_proxy_stats_handler(State(tracker): State<Arc<Tracker>>, Negotiated(format): Negotiated) -> Response {
+    proxy_stats_response(&get_metrics(tracker.clone()).await, format)
+}
+
+/// It handles the request to get the number of rejected requests by reason.
+///
+/// It returns a `200` response with a list of [`RejectionStats`](crate::servers::apis::v1::context::stats::resources::RejectionStats)
+/// resources serialized as json, bencode or csv depending on the `Accept` header.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats#get-rejected-request-statistics)
+/// for more information about this endpoint.
+pub async fn get_rejection_stats_handler(State(tracker): State<Arc<Tracker>>, Negotiated(format): Negotiated) -> Response {
+    rejection_stats_response(&get_metrics(tracker.clone()).await, format)
+}
+
+/// It handles the request to get the statistics for each UDP tracker socket.
+///
+/// It returns a `200` response with a list of [`UdpSocketStats`](crate::servers::apis::v1::context::stats::resources::UdpSocketStats)
+/// resources serialized as json, bencode or csv depending on the `Accept` header.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats#get-udp-socket-statistics)
+/// for more information about this endpoint.
+pub async fn get_udp_socket_stats_handler(State(tracker): State<Arc<Tracker>>, Negotiated(format): Negotiated) -> Response {
+    udp_socket_stats_response(&get_metrics(tracker.clone()).await, format)
+}
+
+/// It handles the request to get the load of the tracker.
+///
+/// It returns a `200` response with a [`LoadStats`](crate::servers::apis::v1::context::stats::resources::LoadStats)
+/// resource serialized as json, bencode or csv depending on the `Accept` header.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats#get-load-statistics)
+/// for more information about this endpoint.
+pub async fn get_load_stats_handler(State(tracker): State<Arc<Tracker>>, Negotiated(format): Negotiated) -> Response {
+    load_stats_response(&get_metrics(tracker.clone()).await, format)
+}
+
+/// It handles the request to get the state of the connection pool of the
+/// database.
+///
+/// It returns a `200` response with a [`DatabasePoolStats`](crate::servers::apis::v1::context::stats::resources::DatabasePoolStats)
+/// resource serialized as json, bencode or csv depending on the `Accept` header.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats#get-database-pool-statistics)
+/// for more information about this endpoint.
+pub async fn get_database_pool_stats_handler(State(tracker): State<Arc<Tracker>>, Negotiated(format): Negotiated) -> Response {
+    database_pool_stats_response(&get_metrics(tracker.clone()).await, format)
+}
+
+/// It handles the request to get the latency and errors of the database
+/// queries.
+///
+/// It returns a `200` response with a list of [`DatabaseQueryStats`](crate::servers::apis::v1::context::stats::resources::DatabaseQueryStats)
+/// resources serialized as json, bencode or csv depending on the `Accept` header.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats#get-database-query-statistics)
+/// for more information about this endpoint.
+pub async fn get_database_query_stats_handler(State(tracker): State<Arc<Tracker>>, Negotiated(format): Negotiated) -> Response {
+    database_query_stats_response(&get_metrics(tracker.clone()).await, format)
+}
+
+/// It handles the request to get the summary of the tracker statistics.
+///
+/// It returns a `200` response with a [`StatsSummary`](crate::servers::apis::v1::context::stats::resources::StatsSummary)
+/// resource serialized as json, bencode or csv depending on the `Accept` header.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats#get-statistics-summary)
+/// for more information about this endpoint.
+pub async fn get_stats_summary_handler(State(tracker): State<Arc<Tracker>>, Negotiated(format): Negotiated) -> Response {
+    stats_summary_response(&tracker.get_stats_summary().await, format)
+}
+
+/// It handles the request to get the announces handled for each group of the
+/// experiment.
+///
+/// It returns a `200` response with a list of [`ExperimentStats`](crate::servers::apis::v1::context::stats::resources::ExperimentStats)
+/// resources serialized as json, bencode or csv depending on the `Accept` header.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats#get-experiment-statistics)
+/// for more information about this endpoint.
+pub async fn get_experiment_stats_handler(State(tracker): State<Arc<Tracker>>, Negotiated(format): Negotiated) -> Response {
+    experiment_stats_response(&get_metrics(tracker.clone()).await, format)
 }
@@ -1,11 +1,20 @@
 //! Tracker statistics API context.
 //!
 //! The tracker collects statistics about the number of torrents, seeders,
-//! leechers, completed downloads, and the number of requests handled.
+//! leechers, completed downloads, the estimated throughput of the swarms, and
+//! the number of requests handled.
 //!
 //! # Endpoints
 //!
 //! - [Get tracker statistics](#get-tracker-statistics)
+//! - [Get reverse proxy statistics](#get-reverse-proxy-statistics)
+//! - [Get rejected request statistics](#get-rejected-request-statistics)
+//! - [Get UDP socket statistics](#get-udp-socket-statistics)
+//! - [Get load statistics](#get-load-statistics)
+//! - [Get database pool statistics](#get-database-pool-statistics)
+//! - [Get database query statistics](#get-database-query-statistics)
+//! - [Get statistics summary](#get-statistics-summary)
+//! - [Get experiment statistics](#get-experiment-statistics)
 //!
 //! # Get tracker statistics
 //!
@@ -27,6 +36,8 @@
 //!     "seeders": 0,
 //!     "completed": 0,
 //!     "leechers": 0,
+//!     "uploaded_bytes_per_second": 0,
+//!     "downloaded_bytes_per_second": 0,
 //!     "tcp4_connections_handled": 0,
 //!     "tcp4_announces_handled": 0,
 //!     "tcp4_scrapes_handled": 0,
@@ -38,7 +49,18 @@
 //!     "udp4_scrapes_handled": 0,
 //!     "udp6_connections_handled": 0,
 //!     "udp6_announces_handled": 0,
-//!     "udp6_scrapes_handled": 0
+//!     "udp6_scrapes_handled": 0,
+//!     "tcp_forwarded_announces_handled": 0,
+//!     "tcp_forwarded_ip_proxy_changes": 0,
+//!     "tcp_announces_with_tracker_id": 0,
+//!     "tcp_announces_with_invalid_tracker_id": 0,
+//!     "tcp_announces_without_tracker_id": 0,
+//!     "api_requests_rate_limited": 0,
+//!     "api_requests_locked_out": 0,
+//!     "api_failed_auth_attempts": 0,
+//!     "api_lockouts": 0,
+//!     "scrape_cache_hits": 0,
+//!     "scrape_cache_misses": 0
 //!   }
 //! ```
 //!
@@ -46,6 +68,311 @@
 //!
 //! Refer to the API [`Stats`](crate::servers::apis::v1::context::stats::resources::Stats)
 //! resource for more information about the response attributes.
+//!
+//! # Get reverse proxy statistics
+//!
+//! `GET /stats/proxies`
+//!
+//! Returns the `announce` requests received through each reverse proxy, when
+//! the HTTP tracker runs behind a reverse proxy. Operators can use them to
+//! check that the proxies preserve the client addresses. A high number of
+//! `forwarded_ip_proxy_changes` means the same client IPs are received
+//! through different proxies, which usually happens when some proxy does not
+//! forward the original `X-Forwarded-For` header.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/stats/proxies?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!   {
+//!     "ip": "10.0.0.1",
+//!     "forwarded_announces_handled": 1520,
+//!     "forwarded_ip_proxy_changes": 3
+//!   }
+//! ]
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`ProxyStats`](crate::servers::apis::v1::context::stats::resources::ProxyStats)
+//! resource for more information about the response attributes.
+//!
+//! # Get rejected request statistics
+//!
+//! `GET /stats/rejections`
+//!
+//! Returns the number of rejected `announce` and `scrape` requests, by
+//! protocol (`http` or `udp`), request type and reason. The reasons are:
+//!
+//! - `bad_info_hash`: the request contains an invalid info-hash.
+//! - `not_whitelisted`: the torrent is not in the whitelist.
+//! - `blacklisted`: the torrent is in the blacklist.
+//! - `invalid_key`: the authentication key is missing, unknown or expired.
+//! - `banned_ip`: the peer IP address is banned.
+//! - `client_not_whitelisted`: the client of the peer is not in the client whitelist.
+//! - `rate_limited`: the peer exceeded the rate limit.
+//! - `parse_error`: the request could not be parsed.
+//! - `invalid_connection_id`: the UDP connection ID could not be verified.
+//! - `unresolved_peer_ip`: the peer IP could not be resolved from the HTTP
+//!   request, for example, the `X-Forwarded-For` header is missing.
+//! - `invalid_peer_port`: the announced peer port is lower than the minimum
+//!   port of the tracker.
+//! - `overloaded`: the tracker is overloaded and it does not handle
+//!   low-priority requests.
+//! - `other`: any other error.
+//!
+//! Only the combinations with at least one rejected request are returned.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/stats/rejections?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!   {
+//!     "protocol": "http",
+//!     "request": "announce",
+//!     "reason": "not_whitelisted",
+//!     "count": 412
+//!   },
+//!   {
+//!     "protocol": "udp",
+//!     "request": "announce",
+//!     "reason": "invalid_connection_id",
+//!     "count": 58
+//!   }
+//! ]
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`RejectionStats`](crate::servers::apis::v1::context::stats::resources::RejectionStats)
+//! resource for more information about the response attributes.
+//!
+//! # Get UDP socket statistics
+//!
+//! `GET /stats/udp`
+//!
+//! Returns the requests handled by each UDP tracker socket, so operators can
+//! tell which binding is receiving the traffic. Only the sockets that have
+//! received at least one packet are returned.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/stats/udp?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!   {
+//!     "socket": "0.0.0.0:6969",
+//!     "connections_handled": 3410,
+//!     "announces_handled": 3012,
+//!     "scrapes_handled": 120,
+//!     "errors_handled": 41,
+//!     "packets_dropped": 7,
+//!     "packets_shed": 0,
+//!     "avg_response_time_us": 85
+//!   }
+//! ]
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`UdpSocketStats`](crate::servers::apis::v1::context::stats::resources::UdpSocketStats)
+//! resource for more information about the response attributes.
+//!
+//! # Get load statistics
+//!
+//! `GET /stats/load`
+//!
+//! Returns the last sample of the load of the tracker, taken by the overload
+//! control, and whether the tracker is overloaded. While it's overloaded, the
+//! announce intervals are raised and the `scrape` requests are rejected. All
+//! the values are zero if the overload control is disabled.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/stats/load?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!   "overloaded": false,
+//!   "requests_per_second": 1830,
+//!   "inflight_requests": 12,
+//!   "event_loop_lag_ms": 1,
+//!   "overload_episodes": 0
+//! }
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`LoadStats`](crate::servers::apis::v1::context::stats::resources::LoadStats)
+//! resource for more information about the response attributes.
+//!
+//! # Get database pool statistics
+//!
+//! `GET /stats/database-pool`
+//!
+//! Returns the state of the connection pool of the database. A pool with all
+//! its connections in use makes the queries wait for a connection, up to the
+//! `acquire_timeout` of the `[core.database.pool]` configuration.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/stats/database-pool?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!   "connections": 10,
+//!   "idle_connections": 9,
+//!   "active_connections": 1,
+//!   "max_size": 10
+//! }
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`DatabasePoolStats`](crate::servers::apis::v1::context::stats::resources::DatabasePoolStats)
+//! resource for more information about the response attributes.
+//!
+//! # Get database query statistics
+//!
+//! `GET /stats/database-queries`
+//!
+//! Returns the number of queries, the number of failed ones and their latency
+//! for each operation of the database called since the tracker started. The
+//! latency includes the wait for a connection of the pool.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/stats/database-queries?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!   {
+//!     "driver": "sqlite3",
+//!     "operation": "load_whitelist",
+//!     "queries": 3,
+//!     "errors": 0,
+//!     "avg_time_us": 152,
+//!     "max_time_us": 311
+//!   }
+//! ]
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`DatabaseQueryStats`](crate::servers::apis::v1::context::stats::resources::DatabaseQueryStats)
+//! resource for more information about the response attributes.
+//!
+//! # Get statistics summary
+//!
+//! `GET /stats/summary`
+//!
+//! Returns the totals for all the torrents and the number of `announce` and
+//! `scrape` requests handled in the last hour and in the last day, by
+//! protocol. The request counts have a resolution of one minute.
+//!
+//! The summary is cached for a few seconds (see the
+//! [`stats_summary`](crate::core::stats_summary) module), so it can be polled
+//! frequently without paging through all the torrents.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/stats/summary?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!   "torrents": 2,
+//!   "seeders": 1,
+//!   "leechers": 3,
+//!   "completed": 4,
+//!   "last_hour_http_announces": 120,
+//!   "last_hour_http_scrapes": 10,
+//!   "last_hour_udp_announces": 860,
+//!   "last_hour_udp_scrapes": 45,
+//!   "last_day_http_announces": 2880,
+//!   "last_day_http_scrapes": 240,
+//!   "last_day_udp_announces": 20640,
+//!   "last_day_udp_scrapes": 1080,
+//!   "computed_at": 1700000000
+//! }
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`StatsSummary`](crate::servers::apis::v1::context::stats::resources::StatsSummary)
+//! resource for more information about the response attributes.
+//!
+//! # Get experiment statistics
+//!
+//! `GET /stats/experiment`
+//!
+//! Returns the announces handled for the control and the experiment groups of
+//! the [announce experiment](crate::core::experiment), or an empty list if
+//! there is no experiment in the `[core.experiment]` configuration.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/stats/experiment?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!   {
+//!     "experiment": "longer-intervals",
+//!     "group": "control",
+//!     "announces": 9000,
+//!     "completed": 120,
+//!     "peers_returned": 412000
+//!   },
+//!   {
+//!     "experiment": "longer-intervals",
+//!     "group": "experiment",
+//!     "announces": 510,
+//!     "completed": 14,
+//!     "peers_returned": 23100
+//!   }
+//! ]
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`ExperimentStats`](crate::servers::apis::v1::context::stats::resources::ExperimentStats)
+//! resource for more information about the response attributes.
 pub mod handlers;
 pub mod resources;
 pub mod responses;
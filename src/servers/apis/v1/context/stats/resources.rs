@@ -1,8 +1,16 @@
 //! API resources for the [`stats`](crate::servers::apis::v1::context::stats)
 //! API context.
+use std::net::{IpAddr, SocketAddr};
+
 use serde::{Deserialize, Serialize};
 
+use crate::core::databases::driver::Driver;
+use crate::core::databases::metrics::OperationStats;
+use crate::core::experiment::GroupStats;
 use crate::core::services::statistics::TrackerMetrics;
+use crate::core::statistics::{ProxyMetrics, Rejection, UdpSocketMetrics};
+use crate::core::stats_summary::Summary;
+use crate::servers::apis::v1::serializers::CsvRecord;
 
 /// It contains all the statistics generated by the tracker.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -16,6 +24,10 @@ pub struct Stats {
     pub completed: u64,
     /// Total number of leechers for all torrents.
     pub leechers: u64,
+    /// Estimated bytes uploaded per second by the peers of all torrents.
+    pub uploaded_bytes_per_second: u64,
+    /// Estimated bytes downloaded per second by the peers of all torrents.
+    pub downloaded_bytes_per_second: u64,
 
     // Protocol metrics
     /// Total number of TCP (HTTP tracker) connections from IPv4 peers.
@@ -44,6 +56,38 @@ pub struct Stats {
     pub udp6_announces_handled: u64,
     /// Total number of UDP (UDP tracker) `scrape` requests from IPv6 peers.
     pub udp6_scrapes_handled: u64,
+    /// Total number of TCP (HTTP tracker) `announce` requests received
+    /// through a reverse proxy.
+    pub tcp_forwarded_announces_handled: u64,
+    /// Total number of TCP (HTTP tracker) `announce` requests whose forwarded
+    /// client IP was previously received through a different proxy.
+    pub tcp_forwarded_ip_proxy_changes: u64,
+    /// Total number of TCP (HTTP tracker) `announce` requests with the
+    /// tracker id issued to the peer: announces of a known session.
+    pub tcp_announces_with_tracker_id: u64,
+    /// Total number of TCP (HTTP tracker) `announce` requests with a tracker
+    /// id not issued to the peer.
+    pub tcp_announces_with_invalid_tracker_id: u64,
+    /// Total number of TCP (HTTP tracker) `announce` requests, other than
+    /// `started`, without the tracker id issued to the peer.
+    pub tcp_announces_without_tracker_id: u64,
+    /// Total number of API requests rejected because the client exceeded the
+    /// rate limit.
+    pub api_requests_rate_limited: u64,
+    /// Total number of API requests rejected because the client was locked
+    /// out.
+    pub api_requests_locked_out: u64,
+    /// Total number of API requests with an invalid access token or JWT.
+    pub api_failed_auth_attempts: u64,
+    /// Total number of times an API client was locked out after too many
+    /// attempts with an invalid token.
+    pub api_lockouts: u64,
+
+    // Scrape cache metrics
+    /// Total number of torrents in `scrape` requests found in the cache.
+    pub scrape_cache_hits: u64,
+    /// Total number of torrents in `scrape` requests not found in the cache.
+    pub scrape_cache_misses: u64,
 }
 
 impl From<TrackerMetrics> for Stats {
@@ -53,6 +97,8 @@ impl From<TrackerMetrics> for Stats {
             seeders: metrics.torrents_metrics.complete,
             completed: metrics.torrents_metrics.downloaded,
             leechers: metrics.torrents_metrics.incomplete,
+            uploaded_bytes_per_second: metrics.bandwidth_estimate.uploaded_bytes_per_second,
+            downloaded_bytes_per_second: metrics.bandwidth_estimate.downloaded_bytes_per_second,
             tcp4_connections_handled: metrics.protocol_metrics.tcp4_connections_handled,
             tcp4_announces_handled: metrics.protocol_metrics.tcp4_announces_handled,
             tcp4_scrapes_handled: metrics.protocol_metrics.tcp4_scrapes_handled,
@@ -65,17 +111,592 @@ impl From<TrackerMetrics> for Stats {
             udp6_connections_handled: metrics.protocol_metrics.udp6_connections_handled,
             udp6_announces_handled: metrics.protocol_metrics.udp6_announces_handled,
             udp6_scrapes_handled: metrics.protocol_metrics.udp6_scrapes_handled,
+            tcp_forwarded_announces_handled: metrics.protocol_metrics.tcp_forwarded_announces_handled,
+            tcp_forwarded_ip_proxy_changes: metrics.protocol_metrics.tcp_forwarded_ip_proxy_changes,
+            tcp_announces_with_tracker_id: metrics.protocol_metrics.tcp_announces_with_tracker_id,
+            tcp_announces_with_invalid_tracker_id: metrics.protocol_metrics.tcp_announces_with_invalid_tracker_id,
+            tcp_announces_without_tracker_id: metrics.protocol_metrics.tcp_announces_without_tracker_id,
+            api_requests_rate_limited: metrics.protocol_metrics.api_requests_rate_limited,
+            api_requests_locked_out: metrics.protocol_metrics.api_requests_locked_out,
+            api_failed_auth_attempts: metrics.protocol_metrics.api_failed_auth_attempts,
+            api_lockouts: metrics.protocol_metrics.api_lockouts,
+            scrape_cache_hits: metrics.scrape_cache.hits,
+            scrape_cache_misses: metrics.scrape_cache.misses,
+        }
+    }
+}
+
+impl CsvRecord for Stats {
+    fn csv_header() -> Vec<&'static str> {
+        vec![
+            "torrents",
+            "seeders",
+            "completed",
+            "leechers",
+            "uploaded_bytes_per_second",
+            "downloaded_bytes_per_second",
+            "tcp4_connections_handled",
+            "tcp4_announces_handled",
+            "tcp4_scrapes_handled",
+            "tcp6_connections_handled",
+            "tcp6_announces_handled",
+            "tcp6_scrapes_handled",
+            "udp4_connections_handled",
+            "udp4_announces_handled",
+            "udp4_scrapes_handled",
+            "udp6_connections_handled",
+            "udp6_announces_handled",
+            "udp6_scrapes_handled",
+            "tcp_forwarded_announces_handled",
+            "tcp_forwarded_ip_proxy_changes",
+            "tcp_announces_with_tracker_id",
+            "tcp_announces_with_invalid_tracker_id",
+            "tcp_announces_without_tracker_id",
+            "api_requests_rate_limited",
+            "api_requests_locked_out",
+            "api_failed_auth_attempts",
+            "api_lockouts",
+            "scrape_cache_hits",
+            "scrape_cache_misses",
+        ]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        [
+            self.torrents,
+            self.seeders,
+            self.completed,
+            self.leechers,
+            self.uploaded_bytes_per_second,
+            self.downloaded_bytes_per_second,
+            self.tcp4_connections_handled,
+            self.tcp4_announces_handled,
+            self.tcp4_scrapes_handled,
+            self.tcp6_connections_handled,
+            self.tcp6_announces_handled,
+            self.tcp6_scrapes_handled,
+            self.udp4_connections_handled,
+            self.udp4_announces_handled,
+            self.udp4_scrapes_handled,
+            self.udp6_connections_handled,
+            self.udp6_announces_handled,
+            self.udp6_scrapes_handled,
+            self.tcp_forwarded_announces_handled,
+            self.tcp_forwarded_ip_proxy_changes,
+            self.tcp_announces_with_tracker_id,
+            self.tcp_announces_with_invalid_tracker_id,
+            self.tcp_announces_without_tracker_id,
+            self.api_requests_rate_limited,
+            self.api_requests_locked_out,
+            self.api_failed_auth_attempts,
+            self.api_lockouts,
+            self.scrape_cache_hits,
+            self.scrape_cache_misses,
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+    }
+}
+
+/// Statistics for the `announce` requests received through a reverse proxy.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ProxyStats {
+    /// The IP address of the proxy.
+    pub ip: String,
+    /// Total number of `announce` requests received through the proxy.
+    pub forwarded_announces_handled: u64,
+    /// Total number of `announce` requests received through the proxy whose
+    /// forwarded client IP was previously received through a different
+    /// proxy.
+    pub forwarded_ip_proxy_changes: u64,
+}
+
+impl ProxyStats {
+    /// It returns the statistics for all the proxies, sorted by IP address.
+    #[must_use]
+    pub fn from_tracker_metrics(metrics: &TrackerMetrics) -> Vec<Self> {
+        let mut proxies: Vec<_> = metrics.protocol_metrics.proxies.iter().collect();
+        proxies.sort_by_key(|(ip, _)| **ip);
+
+        proxies
+            .into_iter()
+            .map(|(ip, proxy_metrics)| Self::new(ip, proxy_metrics))
+            .collect()
+    }
+
+    fn new(ip: &IpAddr, proxy_metrics: &ProxyMetrics) -> Self {
+        Self {
+            ip: ip.to_string(),
+            forwarded_announces_handled: proxy_metrics.forwarded_announces_handled,
+            forwarded_ip_proxy_changes: proxy_metrics.forwarded_ip_proxy_changes,
+        }
+    }
+}
+
+impl CsvRecord for ProxyStats {
+    fn csv_header() -> Vec<&'static str> {
+        vec!["ip", "forwarded_announces_handled", "forwarded_ip_proxy_changes"]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.ip.clone(),
+            self.forwarded_announces_handled.to_string(),
+            self.forwarded_ip_proxy_changes.to_string(),
+        ]
+    }
+}
+
+/// Number of rejected requests for a protocol, request type and reason.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct RejectionStats {
+    /// The protocol the requests were received with: `http` or `udp`.
+    pub protocol: String,
+    /// The type of request: `announce` or `scrape`.
+    pub request: String,
+    /// Why the requests were rejected, for example, `not_whitelisted`.
+    pub reason: String,
+    /// Total number of rejected requests.
+    pub count: u64,
+}
+
+impl RejectionStats {
+    /// It returns the number of rejected requests for each protocol, request
+    /// type and reason, sorted by protocol, request type and reason.
+    #[must_use]
+    pub fn from_tracker_metrics(metrics: &TrackerMetrics) -> Vec<Self> {
+        let mut rejections: Vec<_> = metrics.protocol_metrics.rejections.iter().collect();
+        rejections.sort_by_key(|(rejection, _)| **rejection);
+
+        rejections
+            .into_iter()
+            .map(|(rejection, count)| Self::new(rejection, *count))
+            .collect()
+    }
+
+    fn new(rejection: &Rejection, count: u64) -> Self {
+        Self {
+            protocol: rejection.protocol.to_string(),
+            request: rejection.request.to_string(),
+            reason: rejection.reason.to_string(),
+            count,
+        }
+    }
+}
+
+impl CsvRecord for RejectionStats {
+    fn csv_header() -> Vec<&'static str> {
+        vec!["protocol", "request", "reason", "count"]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.protocol.clone(),
+            self.request.clone(),
+            self.reason.clone(),
+            self.count.to_string(),
+        ]
+    }
+}
+
+/// Statistics for the requests received by a UDP tracker socket.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct UdpSocketStats {
+    /// The address of the socket.
+    pub socket: String,
+    /// Total number of `connect` requests handled.
+    pub connections_handled: u64,
+    /// Total number of `announce` requests handled.
+    pub announces_handled: u64,
+    /// Total number of `scrape` requests handled.
+    pub scrapes_handled: u64,
+    /// Total number of requests answered with an error response.
+    pub errors_handled: u64,
+    /// Total number of packets dropped without a response.
+    pub packets_dropped: u64,
+    /// Total number of `scrape` packets dropped, without a response, to shed
+    /// load.
+    pub packets_shed: u64,
+    /// Average time, in microseconds, spent handling the requests answered.
+    pub avg_response_time_us: u64,
+}
+
+impl UdpSocketStats {
+    /// It returns the statistics for all the UDP tracker sockets, sorted by
+    /// socket address.
+    #[must_use]
+    pub fn from_tracker_metrics(metrics: &TrackerMetrics) -> Vec<Self> {
+        let mut sockets: Vec<_> = metrics.protocol_metrics.udp_sockets.iter().collect();
+        sockets.sort_by_key(|(socket, _)| **socket);
+
+        sockets
+            .into_iter()
+            .map(|(socket, socket_metrics)| Self::new(socket, socket_metrics))
+            .collect()
+    }
+
+    fn new(socket: &SocketAddr, socket_metrics: &UdpSocketMetrics) -> Self {
+        Self {
+            socket: socket.to_string(),
+            connections_handled: socket_metrics.connections_handled,
+            announces_handled: socket_metrics.announces_handled,
+            scrapes_handled: socket_metrics.scrapes_handled,
+            errors_handled: socket_metrics.errors_handled,
+            packets_dropped: socket_metrics.packets_dropped,
+            packets_shed: socket_metrics.packets_shed,
+            avg_response_time_us: u64::try_from(socket_metrics.avg_response_time().as_micros()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+impl CsvRecord for UdpSocketStats {
+    fn csv_header() -> Vec<&'static str> {
+        vec![
+            "socket",
+            "connections_handled",
+            "announces_handled",
+            "scrapes_handled",
+            "errors_handled",
+            "packets_dropped",
+            "packets_shed",
+            "avg_response_time_us",
+        ]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.socket.clone(),
+            self.connections_handled.to_string(),
+            self.announces_handled.to_string(),
+            self.scrapes_handled.to_string(),
+            self.errors_handled.to_string(),
+            self.packets_dropped.to_string(),
+            self.packets_shed.to_string(),
+            self.avg_response_time_us.to_string(),
+        ]
+    }
+}
+
+/// Load of the tracker, sampled by the overload control.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct LoadStats {
+    /// Whether the tracker is overloaded.
+    pub overloaded: bool,
+    /// Requests received per second.
+    pub requests_per_second: u64,
+    /// Requests in process.
+    pub inflight_requests: u64,
+    /// Lag of the event loop, in milliseconds.
+    pub event_loop_lag_ms: u64,
+    /// Number of times the tracker has become overloaded.
+    pub overload_episodes: u64,
+}
+
+impl From<&TrackerMetrics> for LoadStats {
+    fn from(metrics: &TrackerMetrics) -> Self {
+        Self {
+            overloaded: metrics.load.overloaded,
+            requests_per_second: metrics.load.requests_per_second,
+            inflight_requests: metrics.load.inflight_requests,
+            event_loop_lag_ms: u64::try_from(metrics.load.event_loop_lag.as_millis()).unwrap_or(u64::MAX),
+            overload_episodes: metrics.load.overload_episodes,
+        }
+    }
+}
+
+impl CsvRecord for LoadStats {
+    fn csv_header() -> Vec<&'static str> {
+        vec![
+            "overloaded",
+            "requests_per_second",
+            "inflight_requests",
+            "event_loop_lag_ms",
+            "overload_episodes",
+        ]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.overloaded.to_string(),
+            self.requests_per_second.to_string(),
+            self.inflight_requests.to_string(),
+            self.event_loop_lag_ms.to_string(),
+            self.overload_episodes.to_string(),
+        ]
+    }
+}
+
+/// State of the connection pool of the database.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DatabasePoolStats {
+    /// Number of open connections, in use or idle.
+    pub connections: u32,
+    /// Number of open connections not in use.
+    pub idle_connections: u32,
+    /// Number of connections in use.
+    pub active_connections: u32,
+    /// Maximum number of connections of the pool.
+    pub max_size: u32,
+}
+
+impl From<&TrackerMetrics> for DatabasePoolStats {
+    fn from(metrics: &TrackerMetrics) -> Self {
+        Self {
+            connections: metrics.database_pool.connections,
+            idle_connections: metrics.database_pool.idle_connections,
+            active_connections: metrics
+                .database_pool
+                .connections
+                .saturating_sub(metrics.database_pool.idle_connections),
+            max_size: metrics.database_pool.max_size,
         }
     }
 }
 
+impl CsvRecord for DatabasePoolStats {
+    fn csv_header() -> Vec<&'static str> {
+        vec!["connections", "idle_connections", "active_connections", "max_size"]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.connections.to_string(),
+            self.idle_connections.to_string(),
+            self.active_connections.to_string(),
+            self.max_size.to_string(),
+        ]
+    }
+}
+
+/// Latency and errors of the database queries of an operation.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DatabaseQueryStats {
+    /// The database driver.
+    pub driver: String,
+    /// The operation, for example, `load_whitelist`.
+    pub operation: String,
+    /// Total number of queries.
+    pub queries: u64,
+    /// Total number of queries that failed.
+    pub errors: u64,
+    /// Average time of the queries, in microseconds.
+    pub avg_time_us: u64,
+    /// Time of the slowest query, in microseconds.
+    pub max_time_us: u64,
+}
+
+impl DatabaseQueryStats {
+    /// It returns the statistics for all the operations called at least once,
+    /// sorted by operation.
+    #[must_use]
+    pub fn from_tracker_metrics(metrics: &TrackerMetrics) -> Vec<Self> {
+        metrics
+            .database_queries
+            .operations
+            .iter()
+            .map(|(operation, operation_stats)| Self::new(&metrics.database_queries.driver, operation, operation_stats))
+            .collect()
+    }
+
+    fn new(driver: &Driver, operation: &str, operation_stats: &OperationStats) -> Self {
+        Self {
+            driver: driver.to_string().to_lowercase(),
+            operation: operation.to_string(),
+            queries: operation_stats.queries,
+            errors: operation_stats.errors,
+            avg_time_us: u64::try_from(operation_stats.average_time().as_micros()).unwrap_or(u64::MAX),
+            max_time_us: u64::try_from(operation_stats.max_time.as_micros()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+impl CsvRecord for DatabaseQueryStats {
+    fn csv_header() -> Vec<&'static str> {
+        vec!["driver", "operation", "queries", "errors", "avg_time_us", "max_time_us"]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.driver.clone(),
+            self.operation.clone(),
+            self.queries.to_string(),
+            self.errors.to_string(),
+            self.avg_time_us.to_string(),
+            self.max_time_us.to_string(),
+        ]
+    }
+}
+
+/// Summary of the tracker statistics.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct StatsSummary {
+    /// Total number of torrents.
+    pub torrents: u64,
+    /// Total number of seeders for all torrents.
+    pub seeders: u64,
+    /// Total number of leechers for all torrents.
+    pub leechers: u64,
+    /// Total number of peers that have ever completed downloading for all torrents.
+    pub completed: u64,
+    /// HTTP `announce` requests handled in the last hour.
+    pub last_hour_http_announces: u64,
+    /// HTTP `scrape` requests handled in the last hour.
+    pub last_hour_http_scrapes: u64,
+    /// UDP `announce` requests handled in the last hour.
+    pub last_hour_udp_announces: u64,
+    /// UDP `scrape` requests handled in the last hour.
+    pub last_hour_udp_scrapes: u64,
+    /// HTTP `announce` requests handled in the last day.
+    pub last_day_http_announces: u64,
+    /// HTTP `scrape` requests handled in the last day.
+    pub last_day_http_scrapes: u64,
+    /// UDP `announce` requests handled in the last day.
+    pub last_day_udp_announces: u64,
+    /// UDP `scrape` requests handled in the last day.
+    pub last_day_udp_scrapes: u64,
+    /// When the summary was computed, in seconds since the Unix epoch.
+    pub computed_at: u64,
+}
+
+impl From<&Summary> for StatsSummary {
+    fn from(summary: &Summary) -> Self {
+        Self {
+            torrents: summary.torrents_metrics.torrents,
+            seeders: summary.torrents_metrics.complete,
+            leechers: summary.torrents_metrics.incomplete,
+            completed: summary.torrents_metrics.downloaded,
+            last_hour_http_announces: summary.last_hour.http_announces,
+            last_hour_http_scrapes: summary.last_hour.http_scrapes,
+            last_hour_udp_announces: summary.last_hour.udp_announces,
+            last_hour_udp_scrapes: summary.last_hour.udp_scrapes,
+            last_day_http_announces: summary.last_day.http_announces,
+            last_day_http_scrapes: summary.last_day.http_scrapes,
+            last_day_udp_announces: summary.last_day.udp_announces,
+            last_day_udp_scrapes: summary.last_day.udp_scrapes,
+            computed_at: summary.computed_at.as_secs(),
+        }
+    }
+}
+
+impl CsvRecord for StatsSummary {
+    fn csv_header() -> Vec<&'static str> {
+        vec![
+            "torrents",
+            "seeders",
+            "leechers",
+            "completed",
+            "last_hour_http_announces",
+            "last_hour_http_scrapes",
+            "last_hour_udp_announces",
+            "last_hour_udp_scrapes",
+            "last_day_http_announces",
+            "last_day_http_scrapes",
+            "last_day_udp_announces",
+            "last_day_udp_scrapes",
+            "computed_at",
+        ]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.torrents.to_string(),
+            self.seeders.to_string(),
+            self.leechers.to_string(),
+            self.completed.to_string(),
+            self.last_hour_http_announces.to_string(),
+            self.last_hour_http_scrapes.to_string(),
+            self.last_hour_udp_announces.to_string(),
+            self.last_hour_udp_scrapes.to_string(),
+            self.last_day_http_announces.to_string(),
+            self.last_day_http_scrapes.to_string(),
+            self.last_day_udp_announces.to_string(),
+            self.last_day_udp_scrapes.to_string(),
+            self.computed_at.to_string(),
+        ]
+    }
+}
+
+/// Announces handled for a group of the experiment.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ExperimentStats {
+    /// The name of the experiment.
+    pub experiment: String,
+    /// The group: `control` or `experiment`.
+    pub group: String,
+    /// Total number of `announce` requests.
+    pub announces: u64,
+    /// Total number of `announce` requests with the `completed` event.
+    pub completed: u64,
+    /// Total number of peers returned in the responses.
+    pub peers_returned: u64,
+}
+
+impl ExperimentStats {
+    /// It returns the statistics for the control and the experiment groups,
+    /// or none if there is no experiment.
+    #[must_use]
+    pub fn from_tracker_metrics(metrics: &TrackerMetrics) -> Vec<Self> {
+        match &metrics.experiment {
+            Some(stats) => vec![
+                Self::new(&stats.name, "control", &stats.control),
+                Self::new(&stats.name, "experiment", &stats.experiment),
+            ],
+            None => vec![],
+        }
+    }
+
+    fn new(experiment: &str, group: &str, group_stats: &GroupStats) -> Self {
+        Self {
+            experiment: experiment.to_string(),
+            group: group.to_string(),
+            announces: group_stats.announces,
+            completed: group_stats.completed,
+            peers_returned: group_stats.peers_returned,
+        }
+    }
+}
+
+impl CsvRecord for ExperimentStats {
+    fn csv_header() -> Vec<&'static str> {
+        vec!["experiment", "group", "announces", "completed", "peers_returned"]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.experiment.clone(),
+            self.group.clone(),
+            self.announces.to_string(),
+            self.completed.to_string(),
+            self.peers_returned.to_string(),
+        ]
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::time::Duration;
+
     use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
 
-    use super::Stats;
+    use super::{
+        DatabasePoolStats, DatabaseQueryStats, ExperimentStats, LoadStats, ProxyStats, RejectionStats, Stats, StatsSummary,
+        UdpSocketStats,
+    };
+    use crate::core::bandwidth::Estimate;
+    use crate::core::databases::driver::Driver;
+    use crate::core::databases::metrics::{OperationStats, QueryStats};
+    use crate::core::databases::PoolStats;
+    use crate::core::experiment::{self, GroupStats};
+    use crate::core::load_monitor::Load;
+    use crate::core::scrape_cache;
     use crate::core::services::statistics::TrackerMetrics;
-    use crate::core::statistics::Metrics;
+    use crate::core::statistics::{
+        Metrics, Protocol, ProxyMetrics, RejectReason, Rejection, RequestCounts, RequestKind, UdpSocketMetrics,
+    };
+    use crate::core::stats_summary::Summary;
 
     #[test]
     fn stats_resource_should_be_converted_from_tracker_metrics() {
@@ -87,6 +708,15 @@ mod tests {
                     incomplete: 3,
                     torrents: 4
                 },
+                bandwidth_estimate: Estimate {
+                    uploaded_bytes_per_second: 19,
+                    downloaded_bytes_per_second: 20
+                },
+                load: Load::default(),
+                scrape_cache: scrape_cache::Stats { hits: 21, misses: 22 },
+                database_pool: PoolStats::default(),
+                database_queries: QueryStats::default(),
+                experiment: None,
                 protocol_metrics: Metrics {
                     tcp4_connections_handled: 5,
                     tcp4_announces_handled: 6,
@@ -99,7 +729,19 @@ mod tests {
                     udp4_scrapes_handled: 13,
                     udp6_connections_handled: 14,
                     udp6_announces_handled: 15,
-                    udp6_scrapes_handled: 16
+                    udp6_scrapes_handled: 16,
+                    tcp_forwarded_announces_handled: 17,
+                    tcp_forwarded_ip_proxy_changes: 18,
+                    tcp_announces_with_tracker_id: 23,
+                    tcp_announces_with_invalid_tracker_id: 24,
+                    tcp_announces_without_tracker_id: 25,
+                    proxies: HashMap::default(),
+                    rejections: HashMap::default(),
+                    udp_sockets: HashMap::default(),
+                    api_requests_rate_limited: 26,
+                    api_requests_locked_out: 27,
+                    api_failed_auth_attempts: 28,
+                    api_lockouts: 29
                 }
             }),
             Stats {
@@ -107,6 +749,8 @@ mod tests {
                 seeders: 1,
                 completed: 2,
                 leechers: 3,
+                uploaded_bytes_per_second: 19,
+                downloaded_bytes_per_second: 20,
                 tcp4_connections_handled: 5,
                 tcp4_announces_handled: 6,
                 tcp4_scrapes_handled: 7,
@@ -118,8 +762,341 @@ mod tests {
                 udp4_scrapes_handled: 13,
                 udp6_connections_handled: 14,
                 udp6_announces_handled: 15,
-                udp6_scrapes_handled: 16
+                udp6_scrapes_handled: 16,
+                tcp_forwarded_announces_handled: 17,
+                tcp_forwarded_ip_proxy_changes: 18,
+                tcp_announces_with_tracker_id: 23,
+                tcp_announces_with_invalid_tracker_id: 24,
+                tcp_announces_without_tracker_id: 25,
+                api_requests_rate_limited: 26,
+                api_requests_locked_out: 27,
+                api_failed_auth_attempts: 28,
+                api_lockouts: 29,
+                scrape_cache_hits: 21,
+                scrape_cache_misses: 22
             }
         );
     }
+
+    #[test]
+    fn proxy_stats_resources_should_be_converted_from_tracker_metrics() {
+        let proxy_metrics = ProxyMetrics {
+            forwarded_announces_handled: 2,
+            forwarded_ip_proxy_changes: 1,
+        };
+
+        let tracker_metrics = TrackerMetrics {
+            torrents_metrics: TorrentsMetrics::default(),
+            bandwidth_estimate: Estimate::default(),
+            load: Load::default(),
+            scrape_cache: scrape_cache::Stats::default(),
+            database_pool: PoolStats::default(),
+            database_queries: QueryStats::default(),
+            experiment: None,
+            protocol_metrics: Metrics {
+                proxies: HashMap::from([
+                    (IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)), proxy_metrics),
+                    (IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), proxy_metrics),
+                ]),
+                ..Default::default()
+            },
+        };
+
+        assert_eq!(
+            ProxyStats::from_tracker_metrics(&tracker_metrics),
+            vec![
+                ProxyStats {
+                    ip: "10.0.0.1".to_string(),
+                    forwarded_announces_handled: 2,
+                    forwarded_ip_proxy_changes: 1
+                },
+                ProxyStats {
+                    ip: "10.0.0.2".to_string(),
+                    forwarded_announces_handled: 2,
+                    forwarded_ip_proxy_changes: 1
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn rejection_stats_resources_should_be_converted_from_tracker_metrics() {
+        let tracker_metrics = TrackerMetrics {
+            torrents_metrics: TorrentsMetrics::default(),
+            bandwidth_estimate: Estimate::default(),
+            experiment: None,
+            load: Load::default(),
+            scrape_cache: scrape_cache::Stats::default(),
+            database_pool: PoolStats::default(),
+            database_queries: QueryStats::default(),
+            protocol_metrics: Metrics {
+                rejections: HashMap::from([
+                    (
+                        Rejection {
+                            protocol: Protocol::Udp,
+                            request: RequestKind::Announce,
+                            reason: RejectReason::RateLimited,
+                        },
+                        3,
+                    ),
+                    (
+                        Rejection {
+                            protocol: Protocol::Http,
+                            request: RequestKind::Announce,
+                            reason: RejectReason::NotWhitelisted,
+                        },
+                        7,
+                    ),
+                ]),
+                ..Default::default()
+            },
+        };
+
+        assert_eq!(
+            RejectionStats::from_tracker_metrics(&tracker_metrics),
+            vec![
+                RejectionStats {
+                    protocol: "http".to_string(),
+                    request: "announce".to_string(),
+                    reason: "not_whitelisted".to_string(),
+                    count: 7
+                },
+                RejectionStats {
+                    protocol: "udp".to_string(),
+                    request: "announce".to_string(),
+                    reason: "rate_limited".to_string(),
+                    count: 3
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn udp_socket_stats_resources_should_be_converted_from_tracker_metrics() {
+        let socket_metrics = UdpSocketMetrics {
+            connections_handled: 2,
+            announces_handled: 1,
+            scrapes_handled: 0,
+            errors_handled: 1,
+            packets_dropped: 3,
+            packets_shed: 4,
+            total_response_time: Duration::from_micros(400),
+        };
+
+        let tracker_metrics = TrackerMetrics {
+            torrents_metrics: TorrentsMetrics::default(),
+            bandwidth_estimate: Estimate::default(),
+            experiment: None,
+            load: Load::default(),
+            scrape_cache: scrape_cache::Stats::default(),
+            database_pool: PoolStats::default(),
+            database_queries: QueryStats::default(),
+            protocol_metrics: Metrics {
+                udp_sockets: HashMap::from([(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 6969), socket_metrics)]),
+                ..Default::default()
+            },
+        };
+
+        assert_eq!(
+            UdpSocketStats::from_tracker_metrics(&tracker_metrics),
+            vec![UdpSocketStats {
+                socket: "0.0.0.0:6969".to_string(),
+                connections_handled: 2,
+                announces_handled: 1,
+                scrapes_handled: 0,
+                errors_handled: 1,
+                packets_dropped: 3,
+                packets_shed: 4,
+                avg_response_time_us: 100
+            }]
+        );
+    }
+
+    #[test]
+    fn load_stats_resource_should_be_converted_from_tracker_metrics() {
+        let tracker_metrics = TrackerMetrics {
+            torrents_metrics: TorrentsMetrics::default(),
+            bandwidth_estimate: Estimate::default(),
+            experiment: None,
+            load: Load {
+                overloaded: true,
+                requests_per_second: 25000,
+                inflight_requests: 120,
+                event_loop_lag: Duration::from_micros(4500),
+                overload_episodes: 2,
+            },
+            scrape_cache: scrape_cache::Stats::default(),
+            database_pool: PoolStats::default(),
+            database_queries: QueryStats::default(),
+            protocol_metrics: Metrics::default(),
+        };
+
+        assert_eq!(
+            LoadStats::from(&tracker_metrics),
+            LoadStats {
+                overloaded: true,
+                requests_per_second: 25000,
+                inflight_requests: 120,
+                event_loop_lag_ms: 4,
+                overload_episodes: 2
+            }
+        );
+    }
+
+    #[test]
+    fn database_pool_stats_resource_should_be_converted_from_tracker_metrics() {
+        let tracker_metrics = TrackerMetrics {
+            torrents_metrics: TorrentsMetrics::default(),
+            bandwidth_estimate: Estimate::default(),
+            experiment: None,
+            load: Load::default(),
+            scrape_cache: scrape_cache::Stats::default(),
+            database_pool: PoolStats {
+                connections: 8,
+                idle_connections: 3,
+                max_size: 10,
+            },
+            database_queries: QueryStats::default(),
+            protocol_metrics: Metrics::default(),
+        };
+
+        assert_eq!(
+            DatabasePoolStats::from(&tracker_metrics),
+            DatabasePoolStats {
+                connections: 8,
+                idle_connections: 3,
+                active_connections: 5,
+                max_size: 10
+            }
+        );
+    }
+
+    #[test]
+    fn database_query_stats_resources_should_be_converted_from_tracker_metrics() {
+        let tracker_metrics = TrackerMetrics {
+            torrents_metrics: TorrentsMetrics::default(),
+            bandwidth_estimate: Estimate::default(),
+            experiment: None,
+            load: Load::default(),
+            scrape_cache: scrape_cache::Stats::default(),
+            database_pool: PoolStats::default(),
+            database_queries: QueryStats {
+                driver: Driver::MySQL,
+                operations: [(
+                    "load_whitelist",
+                    OperationStats {
+                        queries: 4,
+                        errors: 1,
+                        total_time: Duration::from_millis(10),
+                        max_time: Duration::from_millis(7),
+                    },
+                )]
+                .into_iter()
+                .collect(),
+            },
+            protocol_metrics: Metrics::default(),
+        };
+
+        assert_eq!(
+            DatabaseQueryStats::from_tracker_metrics(&tracker_metrics),
+            vec![DatabaseQueryStats {
+                driver: "mysql".to_string(),
+                operation: "load_whitelist".to_string(),
+                queries: 4,
+                errors: 1,
+                avg_time_us: 2500,
+                max_time_us: 7000,
+            }]
+        );
+    }
+
+    #[test]
+    fn stats_summary_resource_should_be_converted_from_the_summary() {
+        let summary = Summary {
+            torrents_metrics: TorrentsMetrics {
+                complete: 1,
+                downloaded: 2,
+                incomplete: 3,
+                torrents: 4,
+            },
+            last_hour: RequestCounts {
+                http_announces: 5,
+                http_scrapes: 6,
+                udp_announces: 7,
+                udp_scrapes: 8,
+            },
+            last_day: RequestCounts {
+                http_announces: 9,
+                http_scrapes: 10,
+                udp_announces: 11,
+                udp_scrapes: 12,
+            },
+            computed_at: Duration::from_secs(1_700_000_000),
+        };
+
+        assert_eq!(
+            StatsSummary::from(&summary),
+            StatsSummary {
+                torrents: 4,
+                seeders: 1,
+                leechers: 3,
+                completed: 2,
+                last_hour_http_announces: 5,
+                last_hour_http_scrapes: 6,
+                last_hour_udp_announces: 7,
+                last_hour_udp_scrapes: 8,
+                last_day_http_announces: 9,
+                last_day_http_scrapes: 10,
+                last_day_udp_announces: 11,
+                last_day_udp_scrapes: 12,
+                computed_at: 1_700_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn experiment_stats_resources_should_be_converted_from_tracker_metrics() {
+        let tracker_metrics = TrackerMetrics {
+            torrents_metrics: TorrentsMetrics::default(),
+            bandwidth_estimate: Estimate::default(),
+            experiment: Some(experiment::Stats {
+                name: "longer-intervals".to_string(),
+                control: GroupStats {
+                    announces: 90,
+                    completed: 9,
+                    peers_returned: 900,
+                },
+                experiment: GroupStats {
+                    announces: 10,
+                    completed: 1,
+                    peers_returned: 100,
+                },
+            }),
+            load: Load::default(),
+            scrape_cache: scrape_cache::Stats::default(),
+            database_pool: PoolStats::default(),
+            database_queries: QueryStats::default(),
+            protocol_metrics: Metrics::default(),
+        };
+
+        assert_eq!(
+            ExperimentStats::from_tracker_metrics(&tracker_metrics),
+            vec![
+                ExperimentStats {
+                    experiment: "longer-intervals".to_string(),
+                    group: "control".to_string(),
+                    announces: 90,
+                    completed: 9,
+                    peers_returned: 900,
+                },
+                ExperimentStats {
+                    experiment: "longer-intervals".to_string(),
+                    group: "experiment".to_string(),
+                    announces: 10,
+                    completed: 1,
+                    peers_returned: 100,
+                }
+            ]
+        );
+    }
 }
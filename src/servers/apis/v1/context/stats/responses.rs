@@ -1,11 +1,74 @@
 //! API responses for the [`stats`](crate::servers::apis::v1::context::stats)
 //! API context.
-use axum::response::Json;
+use axum::response::Response;
 
-use super::resources::Stats;
+use super::resources::{
+    DatabasePoolStats, DatabaseQueryStats, ExperimentStats, LoadStats, ProxyStats, RejectionStats, Stats, StatsSummary,
+    UdpSocketStats,
+};
 use crate::core::services::statistics::TrackerMetrics;
+use crate::core::stats_summary::Summary;
+use crate::servers::apis::v1::serializers::{resource_list_response, resource_response, Format};
 
-/// `200` response that contains the [`Stats`] resource as json.
-pub fn stats_response(tracker_metrics: TrackerMetrics) -> Json<Stats> {
-    Json(Stats::from(tracker_metrics))
+/// `200` response that contains the [`Stats`] resource in the negotiated
+/// format (json, bencode or csv).
+#[must_use]
+pub fn stats_response(tracker_metrics: TrackerMetrics, format: Format) -> Response {
+    resource_response(format, &Stats::from(tracker_metrics))
+}
+
+/// `200` response that contains the list of [`ProxyStats`] resources in the
+/// negotiated format (json, bencode or csv).
+#[must_use]
+pub fn proxy_stats_response(tracker_metrics: &TrackerMetrics, format: Format) -> Response {
+    resource_list_response(format, &ProxyStats::from_tracker_metrics(tracker_metrics))
+}
+
+/// `200` response that contains the list of [`RejectionStats`] resources in
+/// the negotiated format (json, bencode or csv).
+#[must_use]
+pub fn rejection_stats_response(tracker_metrics: &TrackerMetrics, format: Format) -> Response {
+    resource_list_response(format, &RejectionStats::from_tracker_metrics(tracker_metrics))
+}
+
+/// `200` response that contains the list of [`UdpSocketStats`] resources in
+/// the negotiated format (json, bencode or csv).
+#[must_use]
+pub fn udp_socket_stats_response(tracker_metrics: &TrackerMetrics, format: Format) -> Response {
+    resource_list_response(format, &UdpSocketStats::from_tracker_metrics(tracker_metrics))
+}
+
+/// `200` response that contains the [`LoadStats`] resource in the negotiated
+/// format (json, bencode or csv).
+#[must_use]
+pub fn load_stats_response(tracker_metrics: &TrackerMetrics, format: Format) -> Response {
+    resource_response(format, &LoadStats::from(tracker_metrics))
+}
+
+/// `200` response that contains the [`DatabasePoolStats`] resource in the
+/// negotiated format (json, bencode or csv).
+#[must_use]
+pub fn database_pool_stats_response(tracker_metrics: &TrackerMetrics, format: Format) -> Response {
+    resource_response(format, &DatabasePoolStats::from(tracker_metrics))
+}
+
+/// `200` response that contains the list of [`DatabaseQueryStats`] resources
+/// in the negotiated format (json, bencode or csv).
+#[must_use]
+pub fn database_query_stats_response(tracker_metrics: &TrackerMetrics, format: Format) -> Response {
+    resource_list_response(format, &DatabaseQueryStats::from_tracker_metrics(tracker_metrics))
+}
+
+/// `200` response that contains the [`StatsSummary`] resource in the
+/// negotiated format (json, bencode or csv).
+#[must_use]
+pub fn stats_summary_response(summary: &Summary, format: Format) -> Response {
+    resource_response(format, &StatsSummary::from(summary))
+}
+
+/// `200` response that contains the list of [`ExperimentStats`] resources in
+/// the negotiated format (json, bencode or csv).
+#[must_use]
+pub fn experiment_stats_response(tracker_metrics: &TrackerMetrics, format: Format) -> Response {
+    resource_list_response(format, &ExperimentStats::from_tracker_metrics(tracker_metrics))
 }
@@ -1,6 +1,14 @@
 //! API routes for the [`stats`](crate::servers::apis::v1::context::stats) API context.
 //!
 //! - `GET /stats`
+//! - `GET /stats/proxies`
+//! - `GET /stats/rejections`
+//! - `GET /stats/udp`
+//! - `GET /stats/load`
+//! - `GET /stats/database-pool`
+//! - `GET /stats/database-queries`
+//! - `GET /stats/summary`
+//! - `GET /stats/experiment`
 //!
 //! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats).
 use std::sync::Arc;
@@ -8,10 +16,47 @@ use std::sync::Arc;
 use axum::routing::get;
 use axum::Router;
 
-use super::handlers::get_stats_handler;
+use super::handlers::{
+    get_database_pool_stats_handler, get_database_query_stats_handler, get_experiment_stats_handler, get_load_stats_handler,
+    get_proxy_stats_handler, get_rejection_stats_handler, get_stats_handler, get_stats_summary_handler,
+    get_udp_socket_stats_handler,
+};
 use crate::core::Tracker;
 
 /// It adds the routes to the router for the [`stats`](crate::servers::apis::v1::context::stats) API context.
 pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>) -> Router {
-    router.route(&format!("{prefix}/stats"), get(get_stats_handler).with_state(tracker))
+    router
+        .route(&format!("{prefix}/stats"), get(get_stats_handler).with_state(tracker.clone()))
+        .route(
+            &format!("{prefix}/stats/proxies"),
+            get(get_proxy_stats_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/stats/rejections"),
+            get(get_rejection_stats_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/stats/experiment"),
+            get(get_experiment_stats_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/stats/udp"),
+            get(get_udp_socket_stats_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/stats/load"),
+            get(get_load_stats_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/stats/database-pool"),
+            get(get_database_pool_stats_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/stats/database-queries"),
+            get(get_database_query_stats_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/stats/summary"),
+            get(get_stats_summary_handler).with_state(tracker),
+        )
 }
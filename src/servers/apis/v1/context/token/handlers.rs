@@ -0,0 +1,49 @@
+//! API handlers for the [`token`](crate::servers::apis::v1::context::token) API context.
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Extension, State};
+use axum::response::Response;
+use torrust_tracker_clock::clock::Time;
+
+use super::responses::{failed_to_rotate_token_response, rotated_token_response, token_not_rotatable_response};
+use crate::core::Tracker;
+use crate::servers::apis::access_tokens::AccessTokenStore;
+use crate::servers::apis::v1::middlewares::auth::{Credential, Principal};
+use crate::CurrentClock;
+
+/// It handles the request to rotate the access token of the request. The
+/// previous token is accepted for the `overlap`.
+///
+/// It returns:
+///
+/// - `200` with a json [`RotatedToken`](crate::servers::apis::v1::context::token::resources::RotatedToken)
+///   resource containing the new token.
+/// - `400` if the request was not authenticated with an access token of the
+///   configuration.
+/// - `500` with serialized error in debug format if the new token couldn't be
+///   saved to the database.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::token#rotate-the-access-token)
+/// for more information about this endpoint.
+pub async fn rotate_token_handler(
+    State((tracker, access_tokens, overlap)): State<(Arc<Tracker>, Arc<AccessTokenStore>, Duration)>,
+    Extension(principal): Extension<Principal>,
+) -> Response {
+    if principal.credential != Credential::AccessToken {
+        return token_not_rotatable_response();
+    }
+
+    let Some(rotated_token) = access_tokens.rotate(&principal.name, overlap, CurrentClock::now()) else {
+        return token_not_rotatable_response();
+    };
+
+    // It's saved first, so the token is not replaced if it can't be saved
+    if let Err(e) = tracker.save_rotated_api_token(&rotated_token).await {
+        return failed_to_rotate_token_response(e);
+    }
+
+    access_tokens.apply(rotated_token.clone());
+
+    rotated_token_response(rotated_token)
+}
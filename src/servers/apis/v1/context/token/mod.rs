@@ -0,0 +1,51 @@
+//! Token API context.
+//!
+//! This API context is responsible for rotating the access tokens of the API.
+//!
+//! A client can replace the access token it uses with a new random token,
+//! without editing the configuration and restarting the tracker. The new
+//! token is saved to the database, so it's kept when the tracker is
+//! restarted, and the other tracker instances using the same database
+//! accept it too. The previous token is still accepted for the
+//! `access_token_rotation_overlap` (one hour by default) of the
+//! [HTTP API configuration](torrust_tracker_configuration::HttpApi), to give
+//! time to update the other clients using it. Refer to the
+//! [`api_tokens`](crate::core::api_tokens) module for more information.
+//!
+//! All the access tokens can be rotated, whatever their role. The requests
+//! authenticated with a JSON Web Token can't rotate anything.
+//!
+//! # Endpoints
+//!
+//! - [Rotate the access token](#rotate-the-access-token)
+//!
+//! # Rotate the access token
+//!
+//! `POST /token/rotate`
+//!
+//! It replaces the access token used in the request with a new one.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X POST "http://127.0.0.1:1212/api/v1/token/rotate?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "label": "admin",
+//!     "token": "kyH0sLrkKWU6pWdc0jq8D0a1LkQ5Y2zV",
+//!     "previous_token_valid_until": 1700003600
+//! }
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`RotatedToken`](crate::servers::apis::v1::context::token::resources::RotatedToken)
+//! resource for more information about the response attributes.
+pub mod handlers;
+pub mod resources;
+pub mod responses;
+pub mod routes;
@@ -0,0 +1,47 @@
+//! API resources for the [`token`](crate::servers::apis::v1::context::token) API context.
+use serde::{Deserialize, Serialize};
+
+use crate::core::api_tokens;
+
+/// `RotatedToken` API resource. The new access token of a label.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct RotatedToken {
+    /// The label of the access token in the configuration.
+    pub label: String,
+    /// The new token.
+    pub token: String,
+    /// Time until which the previous token is accepted, in seconds since the
+    /// Unix epoch.
+    pub previous_token_valid_until: u64,
+}
+
+impl From<api_tokens::RotatedToken> for RotatedToken {
+    fn from(rotated_token: api_tokens::RotatedToken) -> Self {
+        Self {
+            label: rotated_token.label,
+            token: rotated_token.token,
+            previous_token_valid_until: rotated_token.previous_token_valid_until,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RotatedToken;
+    use crate::core::api_tokens;
+
+    #[test]
+    fn it_should_not_include_the_previous_token() {
+        let rotated_token = api_tokens::RotatedToken {
+            label: "admin".to_string(),
+            token: "kyH0sLrkKWU6pWdc0jq8D0a1LkQ5Y2zV".to_string(),
+            previous_token: Some("MyAccessToken".to_string()),
+            previous_token_valid_until: 1_700_003_600,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&RotatedToken::from(rotated_token)).unwrap(),
+            r#"{"label":"admin","token":"kyH0sLrkKWU6pWdc0jq8D0a1LkQ5Y2zV","previous_token_valid_until":1700003600}"#
+        );
+    }
+}
@@ -0,0 +1,27 @@
+//! API responses for the [`token`](crate::servers::apis::v1::context::token) API context.
+use std::error::Error;
+
+use axum::response::{IntoResponse, Json, Response};
+
+use super::resources::RotatedToken;
+use crate::core::api_tokens;
+use crate::servers::apis::v1::responses::{bad_request_response, unhandled_rejection_response};
+
+/// `200` response that contains the [`RotatedToken`] resource as json.
+#[must_use]
+pub fn rotated_token_response(rotated_token: api_tokens::RotatedToken) -> Response {
+    Json(RotatedToken::from(rotated_token)).into_response()
+}
+
+/// `400` error response when the request was not authenticated with an
+/// access token of the configuration.
+#[must_use]
+pub fn token_not_rotatable_response() -> Response {
+    bad_request_response("only the access tokens of the configuration can be rotated")
+}
+
+/// `500` error response when the rotated token cannot be saved.
+#[must_use]
+pub fn failed_to_rotate_token_response<E: Error>(e: E) -> Response {
+    unhandled_rejection_response(format!("failed to rotate token: {e}"))
+}
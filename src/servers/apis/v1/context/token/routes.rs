@@ -0,0 +1,29 @@
+//! API routes for the [`token`](crate::servers::apis::v1::context::token) API context.
+//!
+//! - `POST /token/rotate`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::token).
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::routing::post;
+use axum::Router;
+
+use super::handlers::rotate_token_handler;
+use crate::core::Tracker;
+use crate::servers::apis::access_tokens::AccessTokenStore;
+
+/// It adds the routes to the router for the [`token`](crate::servers::apis::v1::context::token) API context.
+/// The previous token is accepted for the `overlap` after a rotation.
+pub fn add(
+    prefix: &str,
+    router: Router,
+    tracker: Arc<Tracker>,
+    access_tokens: Arc<AccessTokenStore>,
+    overlap: Duration,
+) -> Router {
+    router.route(
+        &format!("{prefix}/token/rotate"),
+        post(rotate_token_handler).with_state((tracker, access_tokens, overlap)),
+    )
+}
@@ -1,22 +1,40 @@
 //! API handlers for the [`torrent`](crate::servers::apis::v1::context::torrent)
 //! API context.
-use std::fmt;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::extract::{Path, State};
 use axum::response::{IntoResponse, Response};
 use axum_extra::extract::Query;
-use serde::{de, Deserialize, Deserializer};
+use serde::Deserialize;
 use thiserror::Error;
+use torrust_tracker_clock::clock::Time;
 use torrust_tracker_primitives::info_hash::InfoHash;
 use torrust_tracker_primitives::pagination::Pagination;
 
-use super::responses::{torrent_info_response, torrent_list_response, torrent_not_known_response};
-use crate::core::services::torrent::{get_torrent_info, get_torrents, get_torrents_page};
+use super::responses::{
+    failed_to_load_torrent_history_response, failed_to_remove_torrent_response, torrent_history_response, torrent_info_response,
+    torrent_list_response, torrent_not_known_response,
+};
+use crate::core::services::torrent::{
+    get_sorted_torrents_page, get_torrent_info, get_torrents, get_torrents_page, search_torrents, InfoHashPrefix, SearchCriteria,
+    Sort,
+};
+use crate::core::torrent_history::Granularity;
 use crate::core::Tracker;
-use crate::servers::apis::v1::responses::invalid_info_hash_param_response;
-use crate::servers::apis::InfoHashParam;
+use crate::servers::apis::v1::responses::{invalid_info_hash_param_response, ok_response};
+use crate::servers::apis::v1::serializers::Negotiated;
+use crate::servers::apis::{empty_string_as_none, InfoHashParam};
+use crate::CurrentClock;
+
+/// The period of the torrent history returned when the `from` query
+/// parameter is not set: seven days.
+const DEFAULT_HISTORY_PERIOD: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The number of busiest torrents returned when the `limit` query parameter
+/// is not set.
+const DEFAULT_TOP_TORRENTS_LIMIT: usize = 100;
 
 /// It handles the request to get the torrent data.
 ///
@@ -25,21 +43,145 @@ use crate::servers::apis::InfoHashParam;
 /// - `200` response with a json [`Torrent`](crate::servers::apis::v1::context::torrent::resources::torrent::Torrent).
 /// - `500` with serialized error in debug format if the torrent is not known.
 ///
+/// The IPs of the peers are redacted if `redact_peer_ips` is enabled in the
+/// API configuration.
+///
 /// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent#get-a-torrent)
 /// for more information about this endpoint.
-pub async fn get_torrent_handler(State(tracker): State<Arc<Tracker>>, Path(info_hash): Path<InfoHashParam>) -> Response {
+pub async fn get_torrent_handler(
+    State((tracker, redact_peer_ips)): State<(Arc<Tracker>, bool)>,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
     match InfoHash::from_str(&info_hash.0) {
         Err(_) => invalid_info_hash_param_response(&info_hash.0),
         Ok(info_hash) => match get_torrent_info(tracker.clone(), &info_hash).await {
+            Some(info) if redact_peer_ips => torrent_info_response(info.with_redacted_peer_ips()).into_response(),
             Some(info) => torrent_info_response(info).into_response(),
             None => torrent_not_known_response(),
         },
     }
 }
 
+/// A container for the URL query parameters of the request to remove a
+/// torrent.
+#[derive(Deserialize, Debug)]
+pub struct RemoveTorrentQueryParams {
+    /// Whether the persisted stats of the torrent are removed from the
+    /// database too.
+    #[serde(default)]
+    pub remove_persisted_stats: bool,
+}
+
+/// It handles the request to remove a torrent and all its peers.
+///
+/// It returns:
+///
+/// - `200` response with a [`ActionStatus::Ok`](crate::servers::apis::v1::responses::ActionStatus::Ok) in json.
+/// - `200` response with the `"torrent not known"` string if the torrent is
+///   not known.
+/// - `500` with serialized error in debug format if the persisted stats of
+///   the torrent couldn't be removed.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent#remove-a-torrent)
+/// for more information about this endpoint.
+pub async fn remove_torrent_handler(
+    State(tracker): State<Arc<Tracker>>,
+    Path(info_hash): Path<InfoHashParam>,
+    params: Query<RemoveTorrentQueryParams>,
+) -> Response {
+    match InfoHash::from_str(&info_hash.0) {
+        Err(_) => invalid_info_hash_param_response(&info_hash.0),
+        Ok(info_hash) => match tracker.remove_torrent(&info_hash, params.0.remove_persisted_stats).await {
+            Ok(true) => ok_response(),
+            Ok(false) => torrent_not_known_response(),
+            Err(e) => failed_to_remove_torrent_response(e),
+        },
+    }
+}
+
+/// It handles the request to remove all the peers of a torrent, so they have
+/// to re-announce it.
+///
+/// It returns:
+///
+/// - `200` response with a [`ActionStatus::Ok`](crate::servers::apis::v1::responses::ActionStatus::Ok) in json.
+/// - `200` response with the `"torrent not known"` string if the torrent is
+///   not known.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent#remove-the-peers-of-a-torrent)
+/// for more information about this endpoint.
+pub async fn remove_torrent_peers_handler(State(tracker): State<Arc<Tracker>>, Path(info_hash): Path<InfoHashParam>) -> Response {
+    match InfoHash::from_str(&info_hash.0) {
+        Err(_) => invalid_info_hash_param_response(&info_hash.0),
+        Ok(info_hash) => match tracker.remove_torrent_peers(&info_hash) {
+            Some(peers) => {
+                tracing::info!(%info_hash, peers, "removed all the peers of the torrent");
+                ok_response()
+            }
+            None => torrent_not_known_response(),
+        },
+    }
+}
+
+/// A container for the URL query parameters of the request to get the
+/// history of a torrent.
+#[derive(Deserialize, Debug)]
+pub struct HistoryQueryParams {
+    /// Start of the history, in seconds since the Unix epoch. Seven days
+    /// before `to` if it's not set.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub from: Option<u64>,
+    /// End of the history, in seconds since the Unix epoch. The current time
+    /// if it's not set.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub to: Option<u64>,
+    /// The length of the periods: `hour` (default) or `day`.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub granularity: Option<Granularity>,
+}
+
+/// It handles the request to get the history of a torrent.
+///
+/// It returns:
+///
+/// - `200` response with an array of [`HistoryItem`](crate::servers::apis::v1::context::torrent::resources::history::HistoryItem)
+///   resources serialized as json, bencode or csv depending on the `Accept`
+///   header.
+/// - `500` with serialized error in debug format if the history couldn't be
+///   loaded from the database.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent#get-the-history-of-a-torrent)
+/// for more information about this endpoint.
+pub async fn get_torrent_history_handler(
+    State(tracker): State<Arc<Tracker>>,
+    Path(info_hash): Path<InfoHashParam>,
+    Negotiated(format): Negotiated,
+    params: Query<HistoryQueryParams>,
+) -> Response {
+    match InfoHash::from_str(&info_hash.0) {
+        Err(_) => invalid_info_hash_param_response(&info_hash.0),
+        Ok(info_hash) => {
+            let to = params.0.to.map_or_else(CurrentClock::now, Duration::from_secs);
+            let from = params
+                .0
+                .from
+                .map_or_else(|| to.saturating_sub(DEFAULT_HISTORY_PERIOD), Duration::from_secs);
+
+            match tracker
+                .get_torrent_history(&info_hash, from, to, params.0.granularity.unwrap_or_default())
+                .await
+            {
+                Ok(buckets) => torrent_history_response(&buckets, format),
+                Err(e) => failed_to_load_torrent_history_response(e),
+            }
+        }
+    }
+}
+
 /// A container for the URL query parameters.
 ///
 /// Pagination: `offset` and `limit`.
+/// Order: `sort`.
 /// Array of infohashes: `info_hash`.
 ///
 /// You can either get all torrents with pagination or get a list of torrents
@@ -50,12 +192,17 @@ pub async fn get_torrent_handler(State(tracker): State<Arc<Tracker>>, Path(info_
 /// <http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken>
 ///
 ///
+/// First page of torrents, the ones with more peers first:
+///
+/// <http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken&sort=peers>
+///
+///
 /// Only two torrents:
 ///
 /// <http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken&info_hash=9c38422213e30bff212b30c360d26f9a02136422&info_hash=2b66980093bc11806fab50cb3cb41835b95a0362>
 ///
 ///
-/// NOTICE: Pagination is ignored if array of infohashes is provided.
+/// NOTICE: Pagination and order are ignored if array of infohashes is provided.
 #[derive(Deserialize, Debug)]
 pub struct QueryParams {
     /// The offset of the first page to return. Starts at 0.
@@ -64,6 +211,10 @@ pub struct QueryParams {
     /// The maximum number of items to return per page.
     #[serde(default, deserialize_with = "empty_string_as_none")]
     pub limit: Option<u32>,
+    /// The order of the torrents: `peers`, `completed` or `recent`. They are
+    /// ordered by infohash if it's not set.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub sort: Option<Sort>,
     /// A list of infohashes to retrieve.
     #[serde(default, rename = "info_hash")]
     pub info_hashes: Vec<String>,
@@ -71,25 +222,31 @@ pub struct QueryParams {
 
 /// It handles the request to get a list of torrents.
 ///
-/// It returns a `200` response with a json array with [`crate::servers::apis::v1::context::torrent::resources::torrent::ListItem`] resources.
+/// It returns a `200` response with an array of [`crate::servers::apis::v1::context::torrent::resources::torrent::ListItem`] resources.
+/// The array is serialized as json, bencode or csv depending on the `Accept`
+/// header. See [`serializers`](crate::servers::apis::v1::serializers).
 ///
 /// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent#list-torrents)
 /// for more information about this endpoint.
-pub async fn get_torrents_handler(State(tracker): State<Arc<Tracker>>, pagination: Query<QueryParams>) -> Response {
+pub async fn get_torrents_handler(
+    State(tracker): State<Arc<Tracker>>,
+    Negotiated(format): Negotiated,
+    pagination: Query<QueryParams>,
+) -> Response {
     tracing::debug!("pagination: {:?}", pagination);
 
     if pagination.0.info_hashes.is_empty() {
-        torrent_list_response(
-            &get_torrents_page(
-                tracker.clone(),
-                Some(&Pagination::new_with_options(pagination.0.offset, pagination.0.limit)),
-            )
-            .await,
-        )
-        .into_response()
+        let page = Pagination::new_with_options(pagination.0.offset, pagination.0.limit);
+
+        let torrents = match pagination.0.sort {
+            Some(sort) => get_sorted_torrents_page(tracker.clone(), &page, sort).await,
+            None => get_torrents_page(tracker.clone(), Some(&page)).await,
+        };
+
+        torrent_list_response(&torrents, format)
     } else {
         match parse_info_hashes(pagination.0.info_hashes) {
-            Ok(info_hashes) => torrent_list_response(&get_torrents(tracker.clone(), &info_hashes).await).into_response(),
+            Ok(info_hashes) => torrent_list_response(&get_torrents(tracker.clone(), &info_hashes).await, format),
             Err(err) => match err {
                 QueryParamError::InvalidInfoHash { info_hash } => invalid_info_hash_param_response(&info_hash),
             },
@@ -97,6 +254,93 @@ pub async fn get_torrents_handler(State(tracker): State<Arc<Tracker>>, paginatio
     }
 }
 
+/// A container for the URL query parameters of the torrent search.
+///
+/// Pagination: `offset` and `limit`.
+/// Criteria: `hash_prefix` and `active_since`.
+///
+/// For example, the torrents whose infohash starts with `ab12` and announced
+/// in the last hour:
+///
+/// <http://127.0.0.1:1212/api/v1/torrents/search?token=MyAccessToken&hash_prefix=ab12&active_since=3600>
+#[derive(Deserialize, Debug)]
+pub struct SearchQueryParams {
+    /// The offset of the first page to return. Starts at 0.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub offset: Option<u32>,
+    /// The maximum number of items to return per page.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub limit: Option<u32>,
+    /// The first characters of the infohash, from 1 to 40 hexadecimal
+    /// characters.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub hash_prefix: Option<InfoHashPrefix>,
+    /// Only the torrents with a peer announced in the last `active_since`
+    /// seconds.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub active_since: Option<u64>,
+}
+
+/// It handles the request to search torrents by infohash prefix or announce
+/// recency.
+///
+/// It returns a `200` response with an array of [`crate::servers::apis::v1::context::torrent::resources::torrent::ListItem`] resources,
+/// ordered by infohash. The array is serialized as json, bencode or csv
+/// depending on the `Accept` header.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent#search-torrents)
+/// for more information about this endpoint.
+pub async fn search_torrents_handler(
+    State(tracker): State<Arc<Tracker>>,
+    Negotiated(format): Negotiated,
+    params: Query<SearchQueryParams>,
+) -> Response {
+    let page = Pagination::new_with_options(params.0.offset, params.0.limit);
+
+    let criteria = SearchCriteria {
+        hash_prefix: params.0.hash_prefix,
+        active_since: params
+            .0
+            .active_since
+            .map(|seconds| CurrentClock::now_sub(&Duration::from_secs(seconds)).unwrap_or_default()),
+    };
+
+    torrent_list_response(&search_torrents(tracker, &criteria, &page).await, format)
+}
+
+/// A container for the URL query parameters of the request to get the
+/// busiest torrents.
+#[derive(Deserialize, Debug)]
+pub struct TopQueryParams {
+    /// The ranking: `peers` (default) or `completed`.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub by: Option<Ranking>,
+    /// The maximum number of torrents to return, up to
+    /// [`MAX_LIMIT`](crate::core::top_torrents::MAX_LIMIT).
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub limit: Option<usize>,
+}
+
+/// It handles the request to get the torrents with more peers or more
+/// completed downloads.
+///
+/// It returns a `200` response with an array of [`crate::servers::apis::v1::context::torrent::resources::torrent::ListItem`] resources,
+/// the busiest first. The array is serialized as json, bencode or csv
+/// depending on the `Accept` header.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent#get-the-busiest-torrents)
+/// for more information about this endpoint.
+pub async fn get_top_torrents_handler(
+    State(tracker): State<Arc<Tracker>>,
+    Negotiated(format): Negotiated,
+    params: Query<TopQueryParams>,
+) -> Response {
+    let ranking = params.0.by.unwrap_or(Ranking::Peers);
+    let limit = params.0.limit.unwrap_or(DEFAULT_TOP_TORRENTS_LIMIT).min(MAX_LIMIT);
+
+    torrent_list_response(&get_top_torrents(tracker, ranking, limit).await, format)
+}
+
 #[derive(Error, Debug)]
 pub enum QueryParamError {
     #[error("invalid infohash {info_hash}")]
@@ -119,17 +363,3 @@ fn parse_info_hashes(info_hashes_str: Vec<String>) -> Result<Vec<InfoHash>, Quer
 
     Ok(info_hashes)
 }
-
-/// Serde deserialization decorator to map empty Strings to None,
-fn empty_string_as_none<'de, D, T>(de: D) -> Result<Option<T>, D::Error>
-where
-    D: Deserializer<'de>,
-    T: FromStr,
-    T::Err: fmt::Display,
-{
-    let opt = Option::<String>::deserialize(de)?;
-    match opt.as_deref() {
-        None | Some("") => Ok(None),
-        Some(s) => FromStr::from_str(s).map_err(de::Error::custom).map(Some),
-    }
-}
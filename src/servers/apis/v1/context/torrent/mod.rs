@@ -6,7 +6,12 @@
 //! # Endpoints
 //!
 //! - [Get a torrent](#get-a-torrent)
+//! - [Remove a torrent](#remove-a-torrent)
+//! - [Remove the peers of a torrent](#remove-the-peers-of-a-torrent)
+//! - [Get the history of a torrent](#get-the-history-of-a-torrent)
 //! - [List torrents](#list-torrents)
+//! - [Search torrents](#search-torrents)
+//! - [Get the busiest torrents](#get-the-busiest-torrents)
 //!
 //! # Get a torrent
 //!
@@ -34,6 +39,8 @@
 //!     "seeders": 1,
 //!     "completed": 0,
 //!     "leechers": 0,
+//!     "uploaded_bytes_per_second": 0,
+//!     "downloaded_bytes_per_second": 0,
 //!     "peers": [
 //!       {
 //!         "peer_id": {
@@ -52,6 +59,11 @@
 //! }
 //! ```
 //!
+//! The IPs of the peers are replaced with the unspecified address (`0.0.0.0`
+//! or `::`) when the `redact_peer_ips` option of the
+//! [`HttpApi`](torrust_tracker_configuration::HttpApi) configuration is
+//! enabled. Only the ports are returned.
+//!
 //! **Not Found response** `200`
 //!
 //! This response is returned when the tracker does not have the torrent.
@@ -65,6 +77,136 @@
 //! Refer to the API [`Torrent`](crate::servers::apis::v1::context::torrent::resources::torrent::Torrent)
 //! resource for more information about the response attributes.
 //!
+//! # Remove a torrent
+//!
+//! `DELETE /torrent/:info_hash`
+//!
+//! It removes the torrent and all its peers from the tracker, without
+//! waiting for the peers to time out. For example, to take down a swarm.
+//!
+//! **Path parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `info_hash` | 40-char string | The Info Hash v1 | Yes | `5452869be36f9f3350ccee6b4544e7e76caaadab`
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `remove_persisted_stats` | boolean | Remove the persisted stats (the `completed` counter) from the database too | No | `true`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X DELETE "http://127.0.0.1:1212/api/v1/torrent/5452869be36f9f3350ccee6b4544e7e76caaadab?token=MyAccessToken&remove_persisted_stats=true"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "status": "ok"
+//! }
+//! ```
+//!
+//! The `"torrent not known"` response is returned when the tracker does not
+//! have the torrent.
+//!
+//! > **NOTICE**: the peers can announce the torrent again. Report it to the
+//! > [abuse](crate::servers::apis::v1::context::abuse) endpoint to blacklist
+//! > it, or remove it from the
+//! > [whitelist](crate::servers::apis::v1::context::whitelist), to keep it
+//! > down.
+//!
+//! # Remove the peers of a torrent
+//!
+//! `DELETE /torrent/:info_hash/peers`
+//!
+//! It removes all the peers of the torrent, keeping the torrent and its
+//! stats. The peers are added again when they re-announce.
+//!
+//! **Path parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `info_hash` | 40-char string | The Info Hash v1 | Yes | `5452869be36f9f3350ccee6b4544e7e76caaadab`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X DELETE "http://127.0.0.1:1212/api/v1/torrent/5452869be36f9f3350ccee6b4544e7e76caaadab/peers?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "status": "ok"
+//! }
+//! ```
+//!
+//! The `"torrent not known"` response is returned when the tracker does not
+//! have the torrent.
+//!
+//! # Get the history of a torrent
+//!
+//! `GET /torrent/:info_hash/history`
+//!
+//! Returns the hourly or daily counters of the torrent: the peers that
+//! completed the download in each period, and the peak number of seeders and
+//! leechers. The periods without activity are not included.
+//!
+//! The history is only kept when the `persistent_torrent_history` option of
+//! the [`Core`](torrust_tracker_configuration::Core) configuration is
+//! enabled. The response is an empty array otherwise. Refer to the
+//! [`torrent_history`](crate::core::torrent_history) module for more
+//! information.
+//!
+//! **Path parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `info_hash` | 40-char string | The Info Hash v1 | Yes | `5452869be36f9f3350ccee6b4544e7e76caaadab`
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `from` | positive integer | Start of the history, in seconds since the Unix epoch. Seven days before `to` by default | No | `1699920000`
+//! `to` | positive integer | End of the history, in seconds since the Unix epoch. The current time by default | No | `1700006400`
+//! `granularity` | string | The length of the periods: `hour` (default) or `day` | No | `day`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/torrent/5452869be36f9f3350ccee6b4544e7e76caaadab/history?token=MyAccessToken&granularity=day"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!     {
+//!         "start": 1699920000,
+//!         "completed": 12,
+//!         "peak_seeders": 40,
+//!         "peak_leechers": 7
+//!     },
+//!     {
+//!         "start": 1700006400,
+//!         "completed": 3,
+//!         "peak_seeders": 42,
+//!         "peak_leechers": 2
+//!     }
+//! ]
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`HistoryItem`](crate::servers::apis::v1::context::torrent::resources::history::HistoryItem)
+//! resource for more information about the response attributes.
+//!
 //! # List torrents
 //!
 //! `GET /torrents`
@@ -79,6 +221,12 @@
 //! ---|---|---|---|---
 //! `offset` | positive integer | The page number, starting at 0 | No | `1`
 //! `limit` | positive integer | Page size. The number of results per page | No | `10`
+//! `sort` | `peers`, `completed` or `recent` | The order of the torrents | No | `peers`
+//!
+//! The torrents are ordered by infohash by default. The `sort` orders are
+//! descending: the torrents with more peers (seeders and leechers), more
+//! completed downloads, or announced more recently, first. Torrents with the
+//! same value are ordered by infohash, so the pages are stable.
 //!
 //! **Example request**
 //!
@@ -86,6 +234,10 @@
 //! curl "http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken&offset=1&limit=1"
 //! ```
 //!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken&sort=peers&limit=10"
+//! ```
+//!
 //! **Example response** `200`
 //!
 //! ```json
@@ -100,6 +252,21 @@
 //! ]
 //! ```
 //!
+//! The list can also be requested as CSV (`Accept: text/csv`) or bencode
+//! (`Accept: application/x-bittorrent`):
+//!
+//! ```bash
+//! curl -H "Accept: text/csv" "http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken"
+//! ```
+//!
+//! ```text
+//! info_hash,seeders,completed,leechers
+//! 5452869be36f9f3350ccee6b4544e7e76caaadab,1,0,0
+//! ```
+//!
+//! Refer to [`serializers`](crate::servers::apis::v1::serializers) for more
+//! information about content negotiation.
+//!
 //! **Resource**
 //!
 //! Refer to the API [`ListItem`](crate::servers::apis::v1::context::torrent::resources::torrent::ListItem)
@@ -107,6 +274,104 @@
 //! response.
 //!
 //! > **NOTICE**: this endpoint does not include the `peers` list.
+//!
+//! # Search torrents
+//!
+//! `GET /torrents/search`
+//!
+//! Returns basic information (no peer list) for the torrents matching the
+//! search criteria, ordered by infohash. It finds a swarm without knowing its
+//! whole infohash, or lists the swarms active in a time window.
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `hash_prefix` | 1 to 40 hexadecimal characters | The first characters of the infohash | No | `ab12`
+//! `active_since` | positive integer | Only the torrents with a peer announced in the last `active_since` seconds | No | `3600`
+//! `offset` | positive integer | The page number, starting at 0 | No | `1`
+//! `limit` | positive integer | Page size. The number of results per page | No | `10`
+//!
+//! The torrents are looked up by infohash range when the `hash_prefix` is
+//! set, so the search does not scan all the torrents. The `active_since`
+//! filter checks the peers of every matching torrent.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/torrents/search?token=MyAccessToken&hash_prefix=5452&active_since=3600"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!     {
+//!       "info_hash": "5452869be36f9f3350ccee6b4544e7e76caaadab",
+//!       "seeders": 1,
+//!       "completed": 0,
+//!       "leechers": 0
+//!     }
+//! ]
+//! ```
+//!
+//! As the list of torrents, the result can also be requested as CSV or
+//! bencode.
+//!
+//! **Resource**
+//!
+//! Refer to the API [`ListItem`](crate::servers::apis::v1::context::torrent::resources::torrent::ListItem)
+//! resource for more information about the attributes for a single item in the
+//! response.
+//!
+//! # Get the busiest torrents
+//!
+//! `GET /torrents/top`
+//!
+//! Returns basic information (no peer list) for the torrents with more peers
+//! (seeders and leechers) or more completed downloads, the busiest first.
+//! Torrents with the same value are ordered by infohash.
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `by` | `peers` or `completed` | The ranking. `peers` by default | No | `completed`
+//! `limit` | positive integer | The number of torrents, `100` by default and `1000` at most | No | `10`
+//!
+//! The rankings are computed in a single pass over all the torrents and
+//! cached for 30 seconds, so the dashboards polling this endpoint don't scan
+//! the torrents on every request. A torrent can take up to 30 seconds to
+//! appear in, or leave, the rankings. Refer to the
+//! [`top_torrents`](crate::core::top_torrents) module.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/torrents/top?token=MyAccessToken&by=peers&limit=10"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!     {
+//!       "info_hash": "5452869be36f9f3350ccee6b4544e7e76caaadab",
+//!       "seeders": 12,
+//!       "completed": 40,
+//!       "leechers": 3
+//!     }
+//! ]
+//! ```
+//!
+//! As the list of torrents, the result can also be requested as CSV or
+//! bencode.
+//!
+//! **Resource**
+//!
+//! Refer to the API [`ListItem`](crate::servers::apis::v1::context::torrent::resources::torrent::ListItem)
+//! resource for more information about the attributes for a single item in the
+//! response.
 pub mod handlers;
 pub mod resources;
 pub mod responses;
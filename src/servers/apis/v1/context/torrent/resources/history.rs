@@ -0,0 +1,52 @@
+//! `HistoryItem` API resource.
+use serde::{Deserialize, Serialize};
+
+use crate::core::torrent_history::Bucket;
+use crate::servers::apis::v1::serializers::CsvRecord;
+
+/// `HistoryItem` API resource. The counters of a torrent for an hour or a
+/// day.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct HistoryItem {
+    /// Start of the period, in seconds since the Unix epoch.
+    pub start: u64,
+    /// Peers that completed downloading the torrent in the period.
+    pub completed: u32,
+    /// Peak number of seeders in the period.
+    pub peak_seeders: u32,
+    /// Peak number of leechers in the period.
+    pub peak_leechers: u32,
+}
+
+impl HistoryItem {
+    #[must_use]
+    pub fn new_vec(buckets: &[Bucket]) -> Vec<Self> {
+        buckets.iter().map(HistoryItem::from).collect()
+    }
+}
+
+impl From<&Bucket> for HistoryItem {
+    fn from(bucket: &Bucket) -> Self {
+        Self {
+            start: bucket.start,
+            completed: bucket.completed,
+            peak_seeders: bucket.peak_seeders,
+            peak_leechers: bucket.peak_leechers,
+        }
+    }
+}
+
+impl CsvRecord for HistoryItem {
+    fn csv_header() -> Vec<&'static str> {
+        vec!["start", "completed", "peak_seeders", "peak_leechers"]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.start.to_string(),
+            self.completed.to_string(),
+            self.peak_seeders.to_string(),
+            self.peak_leechers.to_string(),
+        ]
+    }
+}
@@ -7,6 +7,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::core::services::torrent::{BasicInfo, Info};
+use crate::servers::apis::v1::serializers::CsvRecord;
 
 /// `Torrent` API resource.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -22,6 +23,10 @@ pub struct Torrent {
     /// The torrent's leechers counter. Active peers that are downloading the
     /// torrent.
     pub leechers: u64,
+    /// Estimated bytes uploaded per second by the torrent's peers.
+    pub uploaded_bytes_per_second: u64,
+    /// Estimated bytes downloaded per second by the torrent's peers.
+    pub downloaded_bytes_per_second: u64,
     /// The torrent's peers. See [`Peer`](crate::servers::apis::v1::context::torrent::resources::peer::Peer).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub peers: Option<Vec<super::peer::Peer>>,
@@ -55,6 +60,21 @@ impl ListItem {
     }
 }
 
+impl CsvRecord for ListItem {
+    fn csv_header() -> Vec<&'static str> {
+        vec!["info_hash", "seeders", "completed", "leechers"]
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.info_hash.clone(),
+            self.seeders.to_string(),
+            self.completed.to_string(),
+            self.leechers.to_string(),
+        ]
+    }
+}
+
 /// Maps an array of the domain type [`BasicInfo`]
 /// to the API resource type [`ListItem`].
 #[must_use]
@@ -76,6 +96,8 @@ impl From<Info> for Torrent {
             seeders: info.seeders,
             completed: info.completed,
             leechers: info.leechers,
+            uploaded_bytes_per_second: info.bandwidth_estimate.uploaded_bytes_per_second,
+            downloaded_bytes_per_second: info.bandwidth_estimate.downloaded_bytes_per_second,
             peers,
         }
     }
@@ -96,15 +118,18 @@ impl From<BasicInfo> for ListItem {
 mod tests {
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
     use std::str::FromStr;
+    use std::time::Duration;
 
     use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
     use torrust_tracker_primitives::info_hash::InfoHash;
     use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
 
     use super::Torrent;
+    use crate::core::bandwidth::Estimate;
     use crate::core::services::torrent::{BasicInfo, Info};
     use crate::servers::apis::v1::context::torrent::resources::peer::Peer;
     use crate::servers::apis::v1::context::torrent::resources::torrent::ListItem;
+    use crate::servers::apis::v1::serializers::to_csv;
 
     fn sample_peer() -> peer::Peer {
         peer::Peer {
@@ -115,6 +140,8 @@ mod tests {
             downloaded: NumberOfBytes::new(0),
             left: NumberOfBytes::new(0),
             event: AnnounceEvent::Started,
+            timeout_extension: Duration::ZERO,
+            alt_peer_addr: None,
         }
     }
 
@@ -126,6 +153,10 @@ mod tests {
                 seeders: 1,
                 completed: 2,
                 leechers: 3,
+                bandwidth_estimate: Estimate {
+                    uploaded_bytes_per_second: 4,
+                    downloaded_bytes_per_second: 5,
+                },
                 peers: Some(vec![sample_peer()]),
             }),
             Torrent {
@@ -133,6 +164,8 @@ mod tests {
                 seeders: 1,
                 completed: 2,
                 leechers: 3,
+                uploaded_bytes_per_second: 4,
+                downloaded_bytes_per_second: 5,
                 peers: Some(vec![Peer::from(sample_peer())]),
             }
         );
@@ -155,4 +188,19 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn torrent_resource_list_items_should_be_serializable_as_csv() {
+        let list = vec![ListItem {
+            info_hash: "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_string(),
+            seeders: 1,
+            completed: 2,
+            leechers: 3,
+        }];
+
+        assert_eq!(
+            to_csv(&list),
+            "info_hash,seeders,completed,leechers\r\n9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d,1,2,3\r\n"
+        );
+    }
 }
@@ -1,16 +1,23 @@
 //! API responses for the [`torrent`](crate::servers::apis::v1::context::torrent)
 //! API context.
+use std::error::Error;
+
 use axum::response::{IntoResponse, Json, Response};
 use serde_json::json;
 
+use super::resources::history::HistoryItem;
 use super::resources::torrent::{ListItem, Torrent};
 use crate::core::services::torrent::{BasicInfo, Info};
+use crate::core::torrent_history::Bucket;
+use crate::servers::apis::v1::responses::unhandled_rejection_response;
+use crate::servers::apis::v1::serializers::{resource_list_response, Format};
 
 /// `200` response that contains an array of
 /// [`ListItem`]
-/// resources as json.
-pub fn torrent_list_response(basic_infos: &[BasicInfo]) -> Json<Vec<ListItem>> {
-    Json(ListItem::new_vec(basic_infos))
+/// resources in the negotiated format (json, bencode or csv).
+#[must_use]
+pub fn torrent_list_response(basic_infos: &[BasicInfo], format: Format) -> Response {
+    resource_list_response(format, &ListItem::new_vec(basic_infos))
 }
 
 /// `200` response that contains a
@@ -20,6 +27,25 @@ pub fn torrent_info_response(info: Info) -> Json<Torrent> {
     Json(Torrent::from(info))
 }
 
+/// `200` response that contains an array of [`HistoryItem`] resources in the
+/// negotiated format (json, bencode or csv).
+#[must_use]
+pub fn torrent_history_response(buckets: &[Bucket], format: Format) -> Response {
+    resource_list_response(format, &HistoryItem::new_vec(buckets))
+}
+
+/// `500` error response when the history of a torrent cannot be loaded.
+#[must_use]
+pub fn failed_to_load_torrent_history_response<E: Error>(e: E) -> Response {
+    unhandled_rejection_response(format!("failed to load torrent history: {e}"))
+}
+
+/// `500` error response when a torrent cannot be removed.
+#[must_use]
+pub fn failed_to_remove_torrent_response<E: Error>(e: E) -> Response {
+    unhandled_rejection_response(format!("failed to remove torrent: {e}"))
+}
+
 /// `500` error response in plain text returned when a torrent is not found.
 #[must_use]
 pub fn torrent_not_known_response() -> Response {
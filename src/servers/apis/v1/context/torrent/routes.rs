@@ -1,24 +1,55 @@
 //! API routes for the [`torrent`](crate::servers::apis::v1::context::torrent) API context.
 //!
 //! - `GET /torrent/:info_hash`
+//! - `DELETE /torrent/:info_hash`
+//! - `DELETE /torrent/:info_hash/peers`
+//! - `GET /torrent/:info_hash/history`
 //! - `GET /torrents`
+//! - `GET /torrents/search`
+//! - `GET /torrents/top`
 //!
 //! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent).
 use std::sync::Arc;
 
-use axum::routing::get;
+use axum::routing::{delete, get};
 use axum::Router;
 
-use super::handlers::{get_torrent_handler, get_torrents_handler};
+use super::handlers::{
+    get_top_torrents_handler, get_torrent_handler, get_torrent_history_handler, get_torrents_handler, remove_torrent_handler,
+    remove_torrent_peers_handler, search_torrents_handler,
+};
 use crate::core::Tracker;
 
 /// It adds the routes to the router for the [`torrent`](crate::servers::apis::v1::context::torrent) API context.
-pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>) -> Router {
+/// The IPs of the peers are redacted when `redact_peer_ips` is `true`.
+pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>, redact_peer_ips: bool) -> Router {
     // Torrents
     router
         .route(
             &format!("{prefix}/torrent/:info_hash"),
-            get(get_torrent_handler).with_state(tracker.clone()),
+            get(get_torrent_handler)
+                .with_state((tracker.clone(), redact_peer_ips))
+                .delete(remove_torrent_handler)
+                .with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/torrent/:info_hash/peers"),
+            delete(remove_torrent_peers_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/torrent/:info_hash/history"),
+            get(get_torrent_history_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/torrents"),
+            get(get_torrents_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/torrents/search"),
+            get(search_torrents_handler).with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/torrents/top"),
+            get(get_top_torrents_handler).with_state(tracker),
         )
-        .route(&format!("{prefix}/torrents"), get(get_torrents_handler).with_state(tracker))
 }
@@ -0,0 +1,10 @@
+//! API forms for the [`warning`](crate::servers::apis::v1::context::warning) API context.
+use serde::{Deserialize, Serialize};
+
+/// This type contains the warning message to set for a key or a torrent.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WarningMessageForm {
+    /// The message added to the `warning message` field of the announce
+    /// responses.
+    pub message: String,
+}
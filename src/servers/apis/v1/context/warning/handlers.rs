@@ -0,0 +1,113 @@
+//! API handlers for the [`warning`](crate::servers::apis::v1::context::warning) API context.
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::{self, Path, State};
+use axum::response::Response;
+use torrust_tracker_primitives::info_hash::InfoHash;
+
+use super::forms::WarningMessageForm;
+use super::responses::empty_warning_message_response;
+use crate::core::auth::Key;
+use crate::core::Tracker;
+use crate::servers::apis::v1::responses::{invalid_auth_key_param_response, invalid_info_hash_param_response, ok_response};
+use crate::servers::apis::InfoHashParam;
+
+/// It handles the request to set the warning message of an authentication
+/// key.
+///
+/// It returns:
+///
+/// - `200` response with a [`ActionStatus::Ok`](crate::servers::apis::v1::responses::ActionStatus::Ok) in json.
+/// - `400` with an error if the key or the message are not valid.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::warning#set-the-warning-message-of-a-key)
+/// for more information about this endpoint.
+pub async fn set_key_warning_message_handler(
+    State(tracker): State<Arc<Tracker>>,
+    Path(key): Path<String>,
+    extract::Json(warning_message_form): extract::Json<WarningMessageForm>,
+) -> Response {
+    let Ok(key) = Key::from_str(&key) else {
+        return invalid_auth_key_param_response(&key);
+    };
+
+    if warning_message_form.message.trim().is_empty() {
+        return empty_warning_message_response();
+    }
+
+    tracker.set_key_warning_message(&key, warning_message_form.message);
+
+    ok_response()
+}
+
+/// It handles the request to remove the warning message of an
+/// authentication key.
+///
+/// It returns:
+///
+/// - `200` response with a [`ActionStatus::Ok`](crate::servers::apis::v1::responses::ActionStatus::Ok) in json,
+///   even if the key didn't have a warning message.
+/// - `400` with an error if the key is not valid.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::warning#remove-the-warning-message-of-a-key)
+/// for more information about this endpoint.
+pub async fn remove_key_warning_message_handler(State(tracker): State<Arc<Tracker>>, Path(key): Path<String>) -> Response {
+    let Ok(key) = Key::from_str(&key) else {
+        return invalid_auth_key_param_response(&key);
+    };
+
+    tracker.remove_key_warning_message(&key);
+
+    ok_response()
+}
+
+/// It handles the request to set the warning message of a torrent.
+///
+/// It returns:
+///
+/// - `200` response with a [`ActionStatus::Ok`](crate::servers::apis::v1::responses::ActionStatus::Ok) in json.
+/// - `400` with an error if the infohash or the message are not valid.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::warning#set-the-warning-message-of-a-torrent)
+/// for more information about this endpoint.
+pub async fn set_torrent_warning_message_handler(
+    State(tracker): State<Arc<Tracker>>,
+    Path(info_hash): Path<InfoHashParam>,
+    extract::Json(warning_message_form): extract::Json<WarningMessageForm>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.0) else {
+        return invalid_info_hash_param_response(&info_hash.0);
+    };
+
+    if warning_message_form.message.trim().is_empty() {
+        return empty_warning_message_response();
+    }
+
+    tracker.set_torrent_warning_message(&info_hash, warning_message_form.message);
+
+    ok_response()
+}
+
+/// It handles the request to remove the warning message of a torrent.
+///
+/// It returns:
+///
+/// - `200` response with a [`ActionStatus::Ok`](crate::servers::apis::v1::responses::ActionStatus::Ok) in json,
+///   even if the torrent didn't have a warning message.
+/// - `400` with an error if the infohash is not valid.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::warning#remove-the-warning-message-of-a-torrent)
+/// for more information about this endpoint.
+pub async fn remove_torrent_warning_message_handler(
+    State(tracker): State<Arc<Tracker>>,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.0) else {
+        return invalid_info_hash_param_response(&info_hash.0);
+    };
+
+    tracker.remove_torrent_warning_message(&info_hash);
+
+    ok_response()
+}
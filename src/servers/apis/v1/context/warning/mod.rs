@@ -0,0 +1,125 @@
+//! Warning API context.
+//!
+//! This API context is responsible for handling the warning messages of the
+//! HTTP `announce` responses.
+//!
+//! A warning message is a non-fatal message for the clients, like
+//! "your client is outdated" or "ratio low". The announce is processed as
+//! usual and the message is added to the `warning message` field of the
+//! response. Messages can be set for an authentication key or a torrent. The
+//! message of the key takes precedence. Refer to the
+//! [`warnings`](crate::core::warnings) module for more information.
+//!
+//! > **NOTICE**: warning messages are only kept in memory. They are lost when
+//! > the tracker is restarted.
+//!
+//! # Endpoints
+//!
+//! - [Set the warning message of a key](#set-the-warning-message-of-a-key)
+//! - [Remove the warning message of a key](#remove-the-warning-message-of-a-key)
+//! - [Set the warning message of a torrent](#set-the-warning-message-of-a-torrent)
+//! - [Remove the warning message of a torrent](#remove-the-warning-message-of-a-torrent)
+//!
+//! # Set the warning message of a key
+//!
+//! `POST /warning/key/:key`
+//!
+//! **Path parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `key` | 32-char string (0-9, a-z, A-Z) | The authentication key | Yes | `xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6`
+//!
+//! **POST params (json)**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `message` | string | The warning message | Yes | `ratio low`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X POST http://localhost:1212/api/v1/warning/key/xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6?token=MyAccessToken \
+//!      -H "Content-Type: application/json" \
+//!      -d '{ "message": "ratio low" }'
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "status": "ok"
+//! }
+//! ```
+//!
+//! # Remove the warning message of a key
+//!
+//! `DELETE /warning/key/:key`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X DELETE "http://localhost:1212/api/v1/warning/key/xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "status": "ok"
+//! }
+//! ```
+//!
+//! # Set the warning message of a torrent
+//!
+//! `POST /warning/torrent/:info_hash`
+//!
+//! **Path parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `info_hash` | 40-char string | The Info Hash v1 | Yes | `5452869be36f9f3350ccee6b4544e7e76caaadab`
+//!
+//! **POST params (json)**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `message` | string | The warning message | Yes | `please update to the v2 torrent`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X POST http://localhost:1212/api/v1/warning/torrent/5452869be36f9f3350ccee6b4544e7e76caaadab?token=MyAccessToken \
+//!      -H "Content-Type: application/json" \
+//!      -d '{ "message": "please update to the v2 torrent" }'
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "status": "ok"
+//! }
+//! ```
+//!
+//! # Remove the warning message of a torrent
+//!
+//! `DELETE /warning/torrent/:info_hash`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X DELETE "http://localhost:1212/api/v1/warning/torrent/5452869be36f9f3350ccee6b4544e7e76caaadab?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "status": "ok"
+//! }
+//! ```
+pub mod forms;
+pub mod handlers;
+pub mod responses;
+pub mod routes;
@@ -0,0 +1,11 @@
+//! API responses for the [`warning`](crate::servers::apis::v1::context::warning) API context.
+use axum::response::Response;
+
+use crate::servers::apis::v1::responses::bad_request_response;
+
+// Error responses
+
+#[must_use]
+pub fn empty_warning_message_response() -> Response {
+    bad_request_response("Invalid warning message: the message can't be empty")
+}
@@ -0,0 +1,37 @@
+//! API routes for the [`warning`](crate::servers::apis::v1::context::warning) API context.
+//!
+//! - `POST /warning/key/:key`
+//! - `DELETE /warning/key/:key`
+//! - `POST /warning/torrent/:info_hash`
+//! - `DELETE /warning/torrent/:info_hash`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::warning).
+use std::sync::Arc;
+
+use axum::routing::post;
+use axum::Router;
+
+use super::handlers::{
+    remove_key_warning_message_handler, remove_torrent_warning_message_handler, set_key_warning_message_handler,
+    set_torrent_warning_message_handler,
+};
+use crate::core::Tracker;
+
+/// It adds the routes to the router for the [`warning`](crate::servers::apis::v1::context::warning) API context.
+pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>) -> Router {
+    router
+        .route(
+            &format!("{prefix}/warning/key/:key"),
+            post(set_key_warning_message_handler)
+                .with_state(tracker.clone())
+                .delete(remove_key_warning_message_handler)
+                .with_state(tracker.clone()),
+        )
+        .route(
+            &format!("{prefix}/warning/torrent/:info_hash"),
+            post(set_torrent_warning_message_handler)
+                .with_state(tracker.clone())
+                .delete(remove_torrent_warning_message_handler)
+                .with_state(tracker),
+        )
+}
@@ -5,14 +5,43 @@ use std::sync::Arc;
 
 use axum::extract::{Path, State};
 use axum::response::Response;
+use axum_extra::extract::Query;
+use serde::Deserialize;
 use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::pagination::Pagination;
 
 use super::responses::{
     failed_to_reload_whitelist_response, failed_to_remove_torrent_from_whitelist_response, failed_to_whitelist_torrent_response,
+    whitelist_response,
 };
 use crate::core::Tracker;
 use crate::servers::apis::v1::responses::{invalid_info_hash_param_response, ok_response};
-use crate::servers::apis::InfoHashParam;
+use crate::servers::apis::{empty_string_as_none, InfoHashParam};
+
+/// A container for the pagination URL query parameters: `offset` and
+/// `limit`.
+#[derive(Deserialize, Debug)]
+pub struct QueryParams {
+    /// The offset of the first page to return. Starts at 0.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub offset: Option<u32>,
+    /// The maximum number of items to return per page.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub limit: Option<u32>,
+}
+
+/// It handles the request to get the whitelisted torrents.
+///
+/// It returns a `200` response with the infohashes of the whitelisted
+/// torrents, ordered by infohash, in json.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::whitelist#list-the-whitelisted-torrents)
+/// for more information about this endpoint.
+pub async fn get_whitelist_handler(State(tracker): State<Arc<Tracker>>, pagination: Query<QueryParams>) -> Response {
+    let pagination = Pagination::new_with_options(pagination.0.offset, pagination.0.limit);
+
+    whitelist_response(&tracker.get_whitelisted_torrents(Some(&pagination)).await)
+}
 
 /// It handles the request to add a torrent to the whitelist.
 ///
@@ -20,10 +20,40 @@
 //!
 //! # Endpoints
 //!
+//! - [List the whitelisted torrents](#list-the-whitelisted-torrents)
 //! - [Add a torrent to the whitelist](#add-a-torrent-to-the-whitelist)
 //! - [Remove a torrent from the whitelist](#remove-a-torrent-from-the-whitelist)
 //! - [Reload the whitelist](#reload-the-whitelist)
 //!
+//! # List the whitelisted torrents
+//!
+//! `GET /whitelist`
+//!
+//! It returns the infohashes of the whitelisted torrents, ordered by infohash.
+//!
+//! **Query parameters**
+//!
+//! The endpoint supports pagination.
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `offset` | positive integer | The page number, starting at 0 | No | `1`
+//! `limit` | positive integer | Page size. The number of results per page | No | `10`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/whitelist?token=MyAccessToken&offset=0&limit=10"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!     "5452869be36f9f3350ccee6b4544e7e76caaadab"
+//! ]
+//! ```
+//!
 //! # Add a torrent to the whitelist
 //!
 //! `POST /whitelist/:info_hash`
@@ -78,14 +108,20 @@
 //!
 //! # Reload the whitelist
 //!
-//! It reloads the whitelist from the database.
+//! `POST /whitelist/reload`
+//!
+//! It reloads the whitelist from the database. For example, after adding
+//! torrents to the database out-of-band.
 //!
 //! **Example request**
 //!
 //! ```bash
-//! curl "http://127.0.0.1:1212/api/v1/whitelist/reload?token=MyAccessToken"
+//! curl -X POST "http://127.0.0.1:1212/api/v1/whitelist/reload?token=MyAccessToken"
 //! ```
 //!
+//! > **NOTICE**: the `GET /whitelist/reload` endpoint is deprecated, but it's
+//! > still supported.
+//!
 //! **Example response** `200`
 //!
 //! ```json
@@ -2,10 +2,30 @@
 //! API context.
 use std::error::Error;
 
-use axum::response::Response;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use torrust_tracker_primitives::info_hash::InfoHash;
 
 use crate::servers::apis::v1::responses::unhandled_rejection_response;
 
+/// `200` response that contains the infohashes of the whitelisted torrents
+/// as json.
+///
+/// # Panics
+///
+/// Will panic if it can't convert the infohashes to json
+#[must_use]
+pub fn whitelist_response(info_hashes: &[InfoHash]) -> Response {
+    let info_hashes = info_hashes.iter().map(InfoHash::to_hex_string).collect::<Vec<_>>();
+
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
+        serde_json::to_string(&info_hashes).unwrap(),
+    )
+        .into_response()
+}
+
 /// `500` error response when a torrent cannot be removed from the whitelist.
 #[must_use]
 pub fn failed_to_remove_torrent_from_whitelist_response<E: Error>(e: E) -> Response {
@@ -1,8 +1,10 @@
 //! API routes for the [`whitelist`](crate::servers::apis::v1::context::whitelist) API context.
 //!
+//! - `GET /whitelist`
 //! - `POST /whitelist/:info_hash`
 //! - `DELETE /whitelist/:info_hash`
-//! - `GET /whitelist/reload`
+//! - `POST /whitelist/reload`
+//! - `GET /whitelist/reload` (deprecated)
 //!
 //! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent).
 use std::sync::Arc;
@@ -10,7 +12,9 @@ use std::sync::Arc;
 use axum::routing::{delete, get, post};
 use axum::Router;
 
-use super::handlers::{add_torrent_to_whitelist_handler, reload_whitelist_handler, remove_torrent_from_whitelist_handler};
+use super::handlers::{
+    add_torrent_to_whitelist_handler, get_whitelist_handler, reload_whitelist_handler, remove_torrent_from_whitelist_handler,
+};
 use crate::core::Tracker;
 
 /// It adds the routes to the router for the [`whitelist`](crate::servers::apis::v1::context::whitelist) API context.
@@ -19,6 +23,7 @@ pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>) -> Router {
 
     router
         // Whitelisted torrents
+        .route(&prefix, get(get_whitelist_handler).with_state(tracker.clone()))
         .route(
             &format!("{prefix}/:info_hash"),
             post(add_torrent_to_whitelist_handler).with_state(tracker.clone()),
@@ -28,5 +33,10 @@ pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>) -> Router {
             delete(remove_torrent_from_whitelist_handler).with_state(tracker.clone()),
         )
         // Whitelist commands
-        .route(&format!("{prefix}/reload"), get(reload_whitelist_handler).with_state(tracker))
+        .route(
+            &format!("{prefix}/reload"),
+            post(reload_whitelist_handler)
+                .get(reload_whitelist_handler)
+                .with_state(tracker),
+        )
 }
@@ -0,0 +1,117 @@
+//! Audit log middleware for the API.
+//!
+//! It records the requests changing the tracker state (`POST`, `PUT`,
+//! `PATCH` and `DELETE`) in the [audit log](crate::core::audit), after they
+//! are handled:
+//!
+//! - The client, from the [`Principal`] added by the
+//!   [authentication middleware](crate::servers::apis::v1::middlewares::auth).
+//! - The client IP, from the connection info.
+//! - The method, and the path and the query without the `token` param.
+//! - The status of the response.
+//! - The beginning of the request body.
+//!
+//! The requests rejected by the authentication middleware are not recorded.
+//! A request is not rejected when its entry can't be saved, the error is
+//! only logged.
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::body::{self, Body};
+use axum::extract::{self, ConnectInfo};
+use axum::http::{Method, Request, Uri};
+use axum::middleware::Next;
+use axum::response::Response;
+use torrust_tracker_clock::clock::Time;
+
+use super::auth::Principal;
+use crate::core::audit::{self, Entry};
+use crate::core::Tracker;
+use crate::servers::apis::v1::responses::payload_too_large_response;
+use crate::servers::apis::API_LOG_TARGET;
+use crate::CurrentClock;
+
+/// Maximum size of the request bodies of the audited requests.
+const MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+/// Middleware to record the requests changing the tracker state in the audit
+/// log.
+pub async fn audit(extract::State(tracker): extract::State<Arc<Tracker>>, request: Request<Body>, next: Next) -> Response {
+    if !matches!(*request.method(), Method::POST | Method::PUT | Method::PATCH | Method::DELETE) {
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+
+    // The body is buffered to record its beginning, and given back to the handler
+    let Ok(bytes) = body::to_bytes(body, MAX_BODY_SIZE).await else {
+        return payload_too_large_response();
+    };
+
+    let actor = parts
+        .extensions
+        .get::<Principal>()
+        .map_or_else(|| "unknown".to_string(), |principal| principal.name.clone());
+    let client_ip = parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|connect_info| connect_info.0.ip());
+    let method = parts.method.to_string();
+    let path = path_without_token(&parts.uri);
+    let payload = audit::payload_summary(&bytes);
+
+    let response = next.run(Request::from_parts(parts, Body::from(bytes))).await;
+
+    let entry = Entry {
+        timestamp: CurrentClock::now().as_secs(),
+        actor,
+        client_ip,
+        method,
+        path,
+        status: response.status().as_u16(),
+        payload,
+    };
+
+    if let Err(err) = tracker.record_audit_entry(&entry).await {
+        tracing::warn!(target: API_LOG_TARGET, %err, ?entry, "failed to record the audit log entry");
+    }
+
+    response
+}
+
+/// It returns the path and the query of the request, without the `token`
+/// param, so the access tokens are not written to the audit log.
+fn path_without_token(uri: &Uri) -> String {
+    let query = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|param| !param.is_empty() && *param != "token" && !param.starts_with("token="))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    if query.is_empty() {
+        uri.path().to_string()
+    } else {
+        format!("{}?{query}", uri.path())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Uri;
+
+    use super::path_without_token;
+
+    #[test]
+    fn it_should_remove_the_token_from_the_recorded_path() {
+        assert_eq!(
+            path_without_token(&Uri::from_static("/api/v1/key/60?token=MyAccessToken")),
+            "/api/v1/key/60"
+        );
+        assert_eq!(
+            path_without_token(&Uri::from_static("/api/v1/keys?a=1&token=MyAccessToken&b=2")),
+            "/api/v1/keys?a=1&b=2"
+        );
+    }
+}
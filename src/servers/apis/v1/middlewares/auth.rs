@@ -18,19 +18,65 @@
 //! admin = "MyAccessToken"
 //! ```
 //!
-//! All the tokes have the same permissions, so it is not possible to have
-//! different permissions for different tokens. The label is only used to
-//! identify the token.
+//! The label identifies the token. Each token has a role which sets the
+//! endpoints it can be used for:
+//!
+//! Role         | Endpoints
+//! -------------|----------
+//! `admin`      | All the endpoints, including the `/audit` log.
+//! `stats`      | `GET /stats` and `GET /stats/*`.
+//! `key_issuer` | `/key/*`, `/keys` and `/keys/*`.
+//!
+//! All the access tokens can be rotated by their clients with the
+//! `POST /token/rotate` endpoint, whatever their role. Refer to the
+//! [`token`](crate::servers::apis::v1::context::token) API context.
+//!
+//! The tokens have the `admin` role unless another role is set for their
+//! label:
+//!
+//! ```toml
+//! [http_api.access_tokens]
+//! admin = "MyAccessToken"
+//! dashboard = "MyDashboardToken"
+//!
+//! [http_api.access_token_roles]
+//! dashboard = "stats"
+//! ```
+//!
+//! When the [JWT authentication](torrust_tracker_configuration::HttpApiJwt)
+//! is enabled, the requests can also be authenticated with a JSON Web Token
+//! sent as a bearer token in the `Authorization` header, instead of the
+//! `token` param. The role is taken from the claims of the token. Refer to
+//! the [`jwt`](crate::servers::apis::v1::middlewares::jwt) module.
+//!
+//! When the [rate limit](torrust_tracker_configuration::HttpApiRateLimit) is
+//! enabled, the requests from a client IP over the limit, and all the
+//! requests from a client IP locked out after too many attempts with an
+//! invalid token, are rejected with a `429 Too Many Requests` response
+//! before checking the token. Refer to the
+//! [`rate_limiter`](crate::servers::apis::rate_limiter) module.
+//!
+//! The label of the access token, or the subject of the JSON Web Token, is
+//! recorded in the [audit log](crate::servers::apis::v1::middlewares::audit)
+//! for the requests changing the tracker state.
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 
-use axum::extract::{self};
-use axum::http::Request;
+use axum::extract::{self, ConnectInfo};
+use axum::http::{header, HeaderMap, Method, Request};
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
 use serde::Deserialize;
-use torrust_tracker_configuration::AccessTokens;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_configuration::{AccessTokenRole, AccessTokenRoles};
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
 
-use crate::servers::apis::v1::responses::unhandled_rejection_response;
+use super::jwt;
+use crate::core::{statistics, Tracker};
+use crate::servers::apis::access_tokens::AccessTokenStore;
+use crate::servers::apis::rate_limiter::RateLimiter;
+use crate::servers::apis::v1::responses::{too_many_requests_response, unhandled_rejection_response};
+use crate::CurrentClock;
 
 /// Container for the `token` extracted from the query params.
 #[derive(Deserialize, Debug)]
@@ -38,35 +84,155 @@ pub struct QueryParams {
     pub token: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+/// The client authenticated by the middleware. It's added to the extensions
+/// of the request, for the inner middlewares and the handlers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Principal {
+    /// The label of the access token, or `jwt:` and the subject of the JSON
+    /// Web Token (only `jwt` if it has no subject).
+    pub name: String,
+    pub role: AccessTokenRole,
+    pub credential: Credential,
+}
+
+/// How the client was authenticated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Credential {
+    AccessToken,
+    JsonWebToken,
+}
+
+#[derive(Clone)]
 pub struct State {
-    pub access_tokens: Arc<AccessTokens>,
+    pub tracker: Arc<Tracker>,
+    pub access_tokens: Arc<AccessTokenStore>,
+    pub access_token_roles: Arc<AccessTokenRoles>,
+    pub jwt: Option<Arc<jwt::Verifier>>,
+    pub rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 /// Middleware for authentication using a "token" GET param.
-/// The token must be one of the tokens in the tracker [HTTP API configuration](torrust_tracker_configuration::HttpApi).
+/// The token must be one of the tokens in the tracker [HTTP API configuration](torrust_tracker_configuration::HttpApi),
+/// and its role must allow the requested endpoint. The authenticated
+/// [`Principal`] is added to the request extensions.
 pub async fn auth(
     extract::State(state): extract::State<State>,
     extract::Query(params): extract::Query<QueryParams>,
-    request: Request<axum::body::Body>,
+    mut request: Request<axum::body::Body>,
     next: Next,
 ) -> Response {
-    let Some(token) = params.token else {
-        return AuthError::Unauthorized.into_response();
+    let client_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|connect_info| connect_info.0.ip());
+
+    let rate_limiter = state.rate_limiter.as_ref().zip(client_ip);
+
+    if let Some((rate_limiter, client_ip)) = rate_limiter {
+        if let Some(locked_out_for) = rate_limiter.locked_out_for(&client_ip, CurrentClock::now()) {
+            state.tracker.send_stats_event(statistics::Event::ApiRequestLockedOut).await;
+            return too_many_requests_response(Some(locked_out_for));
+        }
+
+        if !rate_limiter.try_acquire(&client_ip, CurrentClock::now()) {
+            state.tracker.send_stats_event(statistics::Event::ApiRequestRateLimited).await;
+            return too_many_requests_response(None);
+        }
+    }
+
+    let principal = match authenticate_request(&state, params.token.as_deref(), request.headers()).await {
+        Ok(principal) => principal,
+        Err(err) => {
+            if matches!(err, AuthError::TokenNotValid) {
+                record_failed_attempt(&state.tracker, rate_limiter).await;
+            }
+
+            return err.into_response();
+        }
     };
 
-    if !authenticate(&token, &state.access_tokens) {
-        return AuthError::TokenNotValid.into_response();
+    if let Some((rate_limiter, client_ip)) = rate_limiter {
+        rate_limiter.record_successful_attempt(&client_ip);
+    }
+
+    if !authorize(principal.role, request.method(), request.uri().path()) {
+        return AuthError::TokenNotAllowed.into_response();
     }
 
+    request.extensions_mut().insert(principal);
+
     next.run(request).await
 }
 
+/// It returns the client of the request, from the bearer token of the
+/// `Authorization` header or, if there is none, from the `token` param.
+async fn authenticate_request(state: &State, token: Option<&str>, headers: &HeaderMap) -> Result<Principal, AuthError> {
+    if let Some(bearer_token) = bearer_token(headers) {
+        let Some(jwt) = &state.jwt else {
+            return Err(AuthError::TokenNotValid);
+        };
+
+        return jwt
+            .verify(bearer_token, CurrentClock::now())
+            .await
+            .map(|token| Principal {
+                name: token
+                    .subject
+                    .map_or_else(|| "jwt".to_string(), |subject| format!("jwt:{subject}")),
+                role: token.role,
+                credential: Credential::JsonWebToken,
+            })
+            .map_err(|err| {
+                tracing::debug!(%err, "invalid JSON Web Token");
+                AuthError::TokenNotValid
+            });
+    }
+
+    let Some(token) = token else {
+        return Err(AuthError::Unauthorized);
+    };
+
+    let now = CurrentClock::now();
+
+    if let Some(principal) = authenticate(token, &state.access_tokens, &state.access_token_roles, now) {
+        return Ok(principal);
+    }
+
+    // The token could have been rotated by another tracker instance
+    if state.access_tokens.start_reload(now) {
+        match state.tracker.load_rotated_api_tokens().await {
+            Ok(rotated_tokens) => state.access_tokens.load(rotated_tokens),
+            Err(err) => tracing::warn!(%err, "failed to reload the rotated API access tokens"),
+        }
+
+        if let Some(principal) = authenticate(token, &state.access_tokens, &state.access_token_roles, now) {
+            return Ok(principal);
+        }
+    }
+
+    Err(AuthError::TokenNotValid)
+}
+
+/// It counts an attempt with an invalid token and, if the rate limit is
+/// enabled, records it for the brute-force lockout of the client IP.
+async fn record_failed_attempt(tracker: &Tracker, rate_limiter: Option<(&Arc<RateLimiter>, IpAddr)>) {
+    tracker.send_stats_event(statistics::Event::ApiAuthFailed).await;
+
+    if let Some((rate_limiter, client_ip)) = rate_limiter {
+        if rate_limiter.record_failed_attempt(&client_ip, CurrentClock::now()) {
+            tracing::warn!(%client_ip, "API client locked out after too many attempts with an invalid token");
+            tracker.send_stats_event(statistics::Event::ApiClientLockedOut).await;
+        }
+    }
+}
+
 enum AuthError {
     /// Missing token for authentication.
     Unauthorized,
     /// Token was provided but it is not valid.
     TokenNotValid,
+    /// Token is valid but its role does not allow the endpoint.
+    TokenNotAllowed,
 }
 
 impl IntoResponse for AuthError {
@@ -74,12 +240,57 @@ impl IntoResponse for AuthError {
         match self {
             AuthError::Unauthorized => unauthorized_response(),
             AuthError::TokenNotValid => token_not_valid_response(),
+            AuthError::TokenNotAllowed => token_not_allowed_response(),
         }
     }
 }
 
-fn authenticate(token: &str, tokens: &AccessTokens) -> bool {
-    tokens.values().any(|t| t == token)
+/// It returns the bearer token of the `Authorization` header, if any.
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// It returns the label and the role of the token, if it's one of the access
+/// tokens.
+fn authenticate(
+    token: &str,
+    tokens: &AccessTokenStore,
+    roles: &AccessTokenRoles,
+    now: DurationSinceUnixEpoch,
+) -> Option<Principal> {
+    tokens.authenticate(token, now).map(|label| Principal {
+        role: roles.get(&label).copied().unwrap_or_default(),
+        name: label,
+        credential: Credential::AccessToken,
+    })
+}
+
+/// It checks if the role allows the request. The `path` is the whole request
+/// path, for example: `/api/v1/stats`, or `/api/stats` for the deprecated
+/// unversioned paths.
+fn authorize(role: AccessTokenRole, method: &Method, path: &str) -> bool {
+    // The context is the segment after the API version: `/api/v1/<context>`
+    let mut segments = path
+        .trim_start_matches('/')
+        .split('/')
+        .skip(1)
+        .skip_while(|segment| *segment == "v1");
+    let context = segments.next().unwrap_or_default();
+
+    // Every client can rotate its own token
+    if context == "token" {
+        return true;
+    }
+
+    match role {
+        AccessTokenRole::Admin => true,
+        AccessTokenRole::Stats => method == Method::GET && context == "stats",
+        AccessTokenRole::KeyIssuer => matches!(context, "key" | "keys"),
+    }
 }
 
 /// `500` error response returned when the token is missing.
@@ -93,3 +304,86 @@ pub fn unauthorized_response() -> Response {
 pub fn token_not_valid_response() -> Response {
     unhandled_rejection_response("token not valid".to_string())
 }
+
+/// `500` error response when the role of the provided token does not allow
+/// the endpoint.
+#[must_use]
+pub fn token_not_allowed_response() -> Response {
+    unhandled_rejection_response("token not allowed".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::http::Method;
+    use torrust_tracker_configuration::{AccessTokenRole, AccessTokenRoles, AccessTokens};
+
+    use super::{authenticate, authorize, Credential, Principal};
+    use crate::servers::apis::access_tokens::AccessTokenStore;
+
+    const NOW: Duration = Duration::from_secs(1_700_000_000);
+
+    #[test]
+    fn it_should_give_the_admin_role_to_the_tokens_without_a_role() {
+        let tokens = AccessTokenStore::new(&AccessTokens::from([
+            ("admin".to_string(), "MyAccessToken".to_string()),
+            ("dashboard".to_string(), "MyDashboardToken".to_string()),
+        ]));
+        let roles = AccessTokenRoles::from([("dashboard".to_string(), AccessTokenRole::Stats)]);
+
+        assert_eq!(
+            authenticate("MyAccessToken", &tokens, &roles, NOW),
+            Some(Principal {
+                name: "admin".to_string(),
+                role: AccessTokenRole::Admin,
+                credential: Credential::AccessToken
+            })
+        );
+        assert_eq!(
+            authenticate("MyDashboardToken", &tokens, &roles, NOW),
+            Some(Principal {
+                name: "dashboard".to_string(),
+                role: AccessTokenRole::Stats,
+                credential: Credential::AccessToken
+            })
+        );
+        assert_eq!(authenticate("UnknownToken", &tokens, &roles, NOW), None);
+    }
+
+    #[test]
+    fn it_should_only_allow_reading_the_stats_to_the_stats_role() {
+        assert!(authorize(AccessTokenRole::Stats, &Method::GET, "/api/v1/stats"));
+        assert!(authorize(AccessTokenRole::Stats, &Method::GET, "/api/v1/stats/udp"));
+        assert!(!authorize(AccessTokenRole::Stats, &Method::GET, "/api/v1/torrents"));
+        assert!(authorize(AccessTokenRole::Stats, &Method::GET, "/api/stats"));
+        assert!(!authorize(AccessTokenRole::Stats, &Method::GET, "/api/torrents"));
+        assert!(!authorize(
+            AccessTokenRole::Stats,
+            &Method::DELETE,
+            "/api/v1/whitelist/9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d"
+        ));
+    }
+
+    #[test]
+    fn it_should_only_allow_managing_the_keys_to_the_key_issuer_role() {
+        assert!(authorize(AccessTokenRole::KeyIssuer, &Method::POST, "/api/v1/keys"));
+        assert!(authorize(
+            AccessTokenRole::KeyIssuer,
+            &Method::DELETE,
+            "/api/v1/key/xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6"
+        ));
+        assert!(!authorize(AccessTokenRole::KeyIssuer, &Method::GET, "/api/v1/stats"));
+        assert!(!authorize(
+            AccessTokenRole::KeyIssuer,
+            &Method::POST,
+            "/api/v1/whitelist/reload"
+        ));
+    }
+
+    #[test]
+    fn it_should_allow_rotating_the_token_to_all_the_roles() {
+        assert!(authorize(AccessTokenRole::Stats, &Method::POST, "/api/v1/token/rotate"));
+        assert!(authorize(AccessTokenRole::KeyIssuer, &Method::POST, "/api/v1/token/rotate"));
+    }
+}
@@ -0,0 +1,57 @@
+//! Deprecation headers of the unversioned API paths.
+//!
+//! The `v1` endpoints are also served without the version in the path, for
+//! the clients of the old unversioned API. For example, `/api/stats` is the
+//! same endpoint as `/api/v1/stats`. Their responses include the
+//! [`Deprecation`](https://datatracker.ietf.org/doc/draft-ietf-httpapi-deprecation-header/)
+//! header and a `Link` header to the versioned path, so the clients can
+//! migrate before the unversioned paths are removed:
+//!
+//! ```text
+//! Deprecation: true
+//! Link: </api/v1/stats>; rel="successor-version"
+//! ```
+use axum::body::Body;
+use axum::http::header::LINK;
+use axum::http::{HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+
+/// Middleware to add the deprecation headers to the responses of the
+/// unversioned paths.
+pub async fn deprecated(request: Request<Body>, next: Next) -> Response {
+    let successor = successor_path(request.uri().path());
+
+    tracing::debug!(path = %request.uri().path(), %successor, "request to a deprecated unversioned API path");
+
+    let mut response = next.run(request).await;
+
+    let headers = response.headers_mut();
+
+    headers.insert("deprecation", HeaderValue::from_static("true"));
+
+    if let Ok(link) = HeaderValue::from_str(&format!("<{successor}>; rel=\"successor-version\"")) {
+        headers.insert(LINK, link);
+    }
+
+    response
+}
+
+/// It returns the versioned path of an unversioned API path. For example,
+/// `/api/v1/stats` for `/api/stats`.
+fn successor_path(path: &str) -> String {
+    path.replacen("/api/", "/api/v1/", 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::successor_path;
+
+    #[test]
+    fn it_should_add_the_version_to_the_unversioned_paths() {
+        assert_eq!(
+            successor_path("/api/torrent/9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d"),
+            "/api/v1/torrent/9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d"
+        );
+    }
+}
@@ -0,0 +1,590 @@
+//! Validation of the JSON Web Tokens used to authenticate the API requests.
+//!
+//! The tokens are sent as bearer tokens in the `Authorization` header:
+//!
+//! ```text
+//! curl -H "Authorization: Bearer <jwt>" "http://127.0.0.1:1212/api/v1/stats"
+//! ```
+//!
+//! The tokens must be signed with the shared secret of the
+//! [JWT configuration](torrust_tracker_configuration::HttpApiJwt), using an
+//! HMAC algorithm (`HS256`, `HS384` or `HS512`), or with one of the keys of
+//! its JWKS URL, using `RS256` or `ES256`. The keys are fetched from the JWKS
+//! URL when they are first needed, and cached by the [`Verifier`]. The `exp`
+//! claim is required, and the `nbf`, `iss` and `aud` claims are checked when
+//! they are present or configured. The API role of the request is taken from
+//! the role claim, and the client is identified by the `sub` claim.
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use ring::signature::{RsaPublicKeyComponents, UnparsedPublicKey, ECDSA_P256_SHA256_FIXED, RSA_PKCS1_2048_8192_SHA256};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Sha256, Sha384, Sha512};
+use thiserror::Error;
+use tokio::sync::Mutex;
+use torrust_tracker_configuration::{AccessTokenRole, HttpApiJwt};
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+use crate::core::services::{build_http_client, send_request};
+
+/// Timeout of the requests to the JWKS URL.
+const JWKS_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Minimum time between two requests to the JWKS URL, so tokens signed with
+/// unknown keys, or an unavailable JWKS URL, don't flood it with requests.
+const JWKS_MIN_REFETCH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Errors returned when a JWT is not valid.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("malformed token")]
+    Malformed,
+
+    #[error("unsupported algorithm: {alg}")]
+    UnsupportedAlgorithm { alg: String },
+
+    #[error("unknown signing key")]
+    UnknownKey,
+
+    #[error("invalid signature")]
+    InvalidSignature,
+
+    #[error("token expired")]
+    Expired,
+
+    #[error("token not valid yet")]
+    NotValidYet,
+
+    #[error("invalid issuer")]
+    InvalidIssuer,
+
+    #[error("invalid audience")]
+    InvalidAudience,
+
+    #[error("missing or invalid role claim")]
+    InvalidRole,
+}
+
+/// The claims of a valid token used by the API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    /// The API role of the request.
+    pub role: AccessTokenRole,
+    /// The `sub` claim, if any.
+    pub subject: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    alg: String,
+    kid: Option<String>,
+}
+
+/// A JSON Web Key Set ([RFC 7517](https://www.rfc-editor.org/rfc/rfc7517#section-5)).
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Jwks {
+    pub keys: Vec<Jwk>,
+}
+
+/// A public key of a [`Jwks`]. Only the RSA and the `P-256` elliptic curve
+/// keys are used.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Jwk {
+    pub kty: String,
+    pub kid: Option<String>,
+    pub alg: Option<String>,
+    /// The modulus of an RSA key.
+    pub n: Option<String>,
+    /// The exponent of an RSA key.
+    pub e: Option<String>,
+    /// The curve of an elliptic curve key.
+    pub crv: Option<String>,
+    /// The x coordinate of an elliptic curve key.
+    pub x: Option<String>,
+    /// The y coordinate of an elliptic curve key.
+    pub y: Option<String>,
+}
+
+impl Jwk {
+    /// Returns `true` if the token with this algorithm and key ID could have
+    /// been signed with this key.
+    fn is_candidate(&self, kty: &str, alg: &str, kid: Option<&str>) -> bool {
+        self.kty == kty
+            && self.alg.as_deref().map_or(true, |key_alg| key_alg == alg)
+            && kid.map_or(true, |kid| self.kid.as_deref() == Some(kid))
+    }
+
+    fn verify_rs256(&self, message: &str, signature: &[u8]) -> bool {
+        let (Some(Ok(n)), Some(Ok(e))) = (self.n.as_deref().map(decode), self.e.as_deref().map(decode)) else {
+            return false;
+        };
+
+        RsaPublicKeyComponents { n, e }
+            .verify(&RSA_PKCS1_2048_8192_SHA256, message.as_bytes(), signature)
+            .is_ok()
+    }
+
+    fn verify_es256(&self, message: &str, signature: &[u8]) -> bool {
+        let (Some("P-256"), Some(Ok(x)), Some(Ok(y))) = (
+            self.crv.as_deref(),
+            self.x.as_deref().map(decode),
+            self.y.as_deref().map(decode),
+        ) else {
+            return false;
+        };
+
+        // Uncompressed point: 0x04 followed by the coordinates
+        let public_key = [&[0x04], x.as_slice(), y.as_slice()].concat();
+
+        UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, public_key)
+            .verify(message.as_bytes(), signature)
+            .is_ok()
+    }
+}
+
+/// It validates the tokens of the API requests, with the keys of the JWKS URL
+/// of the configuration cached.
+pub struct Verifier {
+    config: Arc<HttpApiJwt>,
+    outbound_proxy: Option<String>,
+    cache: Mutex<CachedJwks>,
+}
+
+#[derive(Default)]
+struct CachedJwks {
+    jwks: Arc<Jwks>,
+    /// When the keys were fetched for the last time.
+    fetched_at: Option<DurationSinceUnixEpoch>,
+    /// When the keys were requested for the last time, even if the request
+    /// failed.
+    requested_at: Option<DurationSinceUnixEpoch>,
+}
+
+impl Verifier {
+    /// The requests to the JWKS URL go through the `outbound_proxy`, if any.
+    #[must_use]
+    pub fn new(config: Arc<HttpApiJwt>, outbound_proxy: Option<String>) -> Self {
+        Self {
+            config,
+            outbound_proxy,
+            cache: Mutex::default(),
+        }
+    }
+
+    /// It validates the token like [`validate`], with the keys of the JWKS
+    /// URL. The keys are fetched again when they are older than the cache TTL
+    /// or the token is signed with an unknown key.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the token is not valid.
+    pub async fn verify(&self, token: &str, now: DurationSinceUnixEpoch) -> Result<Token, Error> {
+        let jwks = self.jwks(now, false).await;
+
+        match validate(token, &self.config, &jwks, now.as_secs()) {
+            // The keys could have been rotated
+            Err(Error::UnknownKey) if self.config.jwks_url.is_some() => {
+                let jwks = self.jwks(now, true).await;
+
+                validate(token, &self.config, &jwks, now.as_secs())
+            }
+            result => result,
+        }
+    }
+
+    async fn jwks(&self, now: DurationSinceUnixEpoch, refresh: bool) -> Arc<Jwks> {
+        let Some(jwks_url) = &self.config.jwks_url else {
+            return Arc::default();
+        };
+
+        let mut cache = self.cache.lock().await;
+
+        let is_stale = cache.fetched_at.map_or(true, |fetched_at| {
+            now >= fetched_at + Duration::from_secs(self.config.jwks_cache_ttl)
+        });
+
+        let can_request = cache
+            .requested_at
+            .map_or(true, |requested_at| now >= requested_at + JWKS_MIN_REFETCH_INTERVAL);
+
+        if (is_stale || refresh) && can_request {
+            cache.requested_at = Some(now);
+
+            match fetch_jwks(jwks_url, self.outbound_proxy.as_deref()).await {
+                Ok(jwks) => {
+                    cache.jwks = Arc::new(jwks);
+                    cache.fetched_at = Some(now);
+                }
+                Err(err) => tracing::warn!(%jwks_url, %err, "failed to fetch the JWKS of the API JWT authentication"),
+            }
+        }
+
+        cache.jwks.clone()
+    }
+}
+
+async fn fetch_jwks(url: &str, outbound_proxy: Option<&str>) -> reqwest::Result<Jwks> {
+    send_request(build_http_client(outbound_proxy, JWKS_REQUEST_TIMEOUT)?.get(url))
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+}
+
+/// It validates the token and returns the API role and the subject of the
+/// request. The tokens signed with an asymmetric algorithm are verified with
+/// the keys of the `jwks`.
+///
+/// `now` is the current time, in seconds since the Unix epoch.
+///
+/// # Errors
+///
+/// Will return an error if the token is malformed, it's signed with an
+/// unknown key, its signature is not valid, any of its claims is not valid,
+/// or it does not contain a valid role.
+pub fn validate(token: &str, config: &HttpApiJwt, jwks: &Jwks, now: u64) -> Result<Token, Error> {
+    let mut parts = token.split('.');
+
+    let (Some(encoded_header), Some(encoded_claims), Some(encoded_signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(Error::Malformed);
+    };
+
+    let header: Header = serde_json::from_slice(&decode(encoded_header)?).map_err(|_| Error::Malformed)?;
+    let signature = decode(encoded_signature)?;
+
+    // The signed part of the token: the header and the claims
+    let message = &token[..encoded_header.len() + 1 + encoded_claims.len()];
+
+    let valid = match (header.alg.as_str(), &config.secret) {
+        ("HS256", Some(secret)) => verify::<Hmac<Sha256>>(secret, message, &signature),
+        ("HS384", Some(secret)) => verify::<Hmac<Sha384>>(secret, message, &signature),
+        ("HS512", Some(secret)) => verify::<Hmac<Sha512>>(secret, message, &signature),
+        ("RS256", _) => verify_with_jwks(jwks, "RSA", &header, |key| key.verify_rs256(message, &signature))?,
+        ("ES256", _) => verify_with_jwks(jwks, "EC", &header, |key| key.verify_es256(message, &signature))?,
+        _ => return Err(Error::UnsupportedAlgorithm { alg: header.alg }),
+    };
+
+    if !valid {
+        return Err(Error::InvalidSignature);
+    }
+
+    let claims: Value = serde_json::from_slice(&decode(encoded_claims)?).map_err(|_| Error::Malformed)?;
+
+    match claims.get("exp").and_then(Value::as_u64) {
+        Some(exp) if exp > now => {}
+        _ => return Err(Error::Expired),
+    }
+
+    if claims.get("nbf").and_then(Value::as_u64).is_some_and(|nbf| nbf > now) {
+        return Err(Error::NotValidYet);
+    }
+
+    if let Some(issuer) = &config.issuer {
+        if claims.get("iss").and_then(Value::as_str) != Some(issuer) {
+            return Err(Error::InvalidIssuer);
+        }
+    }
+
+    if let Some(audience) = &config.audience {
+        let valid = match claims.get("aud") {
+            Some(Value::String(aud)) => aud == audience,
+            Some(Value::Array(auds)) => auds.iter().any(|aud| aud.as_str() == Some(audience)),
+            _ => false,
+        };
+
+        if !valid {
+            return Err(Error::InvalidAudience);
+        }
+    }
+
+    let role = match claims.get(&config.role_claim) {
+        Some(role) => serde_json::from_value(role.clone()).map_err(|_| Error::InvalidRole)?,
+        None => config.default_role.ok_or(Error::InvalidRole)?,
+    };
+
+    Ok(Token {
+        role,
+        subject: claims.get("sub").and_then(Value::as_str).map(ToString::to_string),
+    })
+}
+
+/// It returns `true` if any of the keys the token could have been signed
+/// with verifies the signature.
+fn verify_with_jwks(jwks: &Jwks, kty: &str, header: &Header, verify: impl Fn(&Jwk) -> bool) -> Result<bool, Error> {
+    let mut candidates = jwks
+        .keys
+        .iter()
+        .filter(|key| key.is_candidate(kty, &header.alg, header.kid.as_deref()))
+        .peekable();
+
+    if candidates.peek().is_none() {
+        return Err(Error::UnknownKey);
+    }
+
+    Ok(candidates.any(verify))
+}
+
+fn verify<M: Mac + hmac::digest::KeyInit>(secret: &str, message: &str, signature: &[u8]) -> bool {
+    let mut mac = <M as Mac>::new_from_slice(secret.as_bytes()).expect("HMAC should accept keys of any length");
+    mac.update(message.as_bytes());
+    mac.verify_slice(signature).is_ok()
+}
+
+/// It decodes a base64url string without padding
+/// ([RFC 4648, section 5](https://www.rfc-editor.org/rfc/rfc4648#section-5)).
+fn decode(input: &str) -> Result<Vec<u8>, Error> {
+    URL_SAFE_NO_PAD.decode(input).map_err(|_| Error::Malformed)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use base64::Engine;
+    use hmac::{Hmac, Mac};
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+    use serde_json::json;
+    use sha2::Sha256;
+    use torrust_tracker_configuration::{AccessTokenRole, HttpApiJwt};
+
+    use super::{validate, Error, Jwk, Jwks, Token};
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn config() -> HttpApiJwt {
+        HttpApiJwt {
+            secret: Some("MyJwtSecret".to_string()),
+            jwks_url: None,
+            jwks_cache_ttl: 3600,
+            issuer: Some("https://sso.example.com".to_string()),
+            audience: Some("torrust-tracker".to_string()),
+            role_claim: "role".to_string(),
+            default_role: None,
+        }
+    }
+
+    fn encode(input: &[u8]) -> String {
+        URL_SAFE_NO_PAD.encode(input)
+    }
+
+    fn sign(claims: &serde_json::Value, secret: &str) -> String {
+        let message = format!(
+            "{}.{}",
+            encode(json!({"alg": "HS256", "typ": "JWT"}).to_string().as_bytes()),
+            encode(claims.to_string().as_bytes())
+        );
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(message.as_bytes());
+
+        format!("{message}.{}", encode(&mac.finalize().into_bytes()))
+    }
+
+    fn valid_claims() -> serde_json::Value {
+        json!({
+            "iss": "https://sso.example.com",
+            "aud": ["torrust-tracker", "another-service"],
+            "exp": NOW + 60,
+            "sub": "dashboard",
+            "role": "stats"
+        })
+    }
+
+    #[test]
+    fn it_should_return_the_role_of_a_valid_token() {
+        assert_eq!(
+            validate(&sign(&valid_claims(), "MyJwtSecret"), &config(), &Jwks::default(), NOW),
+            Ok(Token {
+                role: AccessTokenRole::Stats,
+                subject: Some("dashboard".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn it_should_reject_the_tokens_signed_with_another_secret() {
+        assert_eq!(
+            validate(&sign(&valid_claims(), "AnotherSecret"), &config(), &Jwks::default(), NOW),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn it_should_reject_the_expired_tokens() {
+        let mut claims = valid_claims();
+        claims["exp"] = json!(NOW);
+
+        assert_eq!(
+            validate(&sign(&claims, "MyJwtSecret"), &config(), &Jwks::default(), NOW),
+            Err(Error::Expired)
+        );
+    }
+
+    #[test]
+    fn it_should_reject_the_tokens_for_another_audience() {
+        let mut claims = valid_claims();
+        claims["aud"] = json!("another-service");
+
+        assert_eq!(
+            validate(&sign(&claims, "MyJwtSecret"), &config(), &Jwks::default(), NOW),
+            Err(Error::InvalidAudience)
+        );
+    }
+
+    #[test]
+    fn it_should_use_the_default_role_for_the_tokens_without_the_role_claim() {
+        let mut claims = valid_claims();
+        claims.as_object_mut().unwrap().remove("role");
+
+        assert_eq!(
+            validate(&sign(&claims, "MyJwtSecret"), &config(), &Jwks::default(), NOW),
+            Err(Error::InvalidRole)
+        );
+
+        let config = HttpApiJwt {
+            default_role: Some(AccessTokenRole::KeyIssuer),
+            ..config()
+        };
+
+        assert_eq!(
+            validate(&sign(&claims, "MyJwtSecret"), &config, &Jwks::default(), NOW).map(|token| token.role),
+            Ok(AccessTokenRole::KeyIssuer)
+        );
+    }
+
+    #[test]
+    fn it_should_reject_the_unsigned_tokens() {
+        let token = format!(
+            "{}.{}.",
+            encode(json!({"alg": "none"}).to_string().as_bytes()),
+            encode(valid_claims().to_string().as_bytes())
+        );
+
+        assert_eq!(
+            validate(&token, &config(), &Jwks::default(), NOW),
+            Err(Error::UnsupportedAlgorithm { alg: "none".to_string() })
+        );
+    }
+
+    /// An `RS256` token with the claims of [`valid_claims`], but with a
+    /// single audience and the `admin` role, signed with the key of
+    /// [`rsa_jwk`].
+    const RS256_TOKEN: &str = concat!(
+        "eyJhbGciOiJSUzI1NiIsImtpZCI6InJzYS1rZXkiLCJ0eXAiOiJKV1QifQ",
+        ".eyJpc3MiOiJodHRwczovL3Nzby5leGFtcGxlLmNvbSIsImF1ZCI6InRvcnJ1c3QtdHJhY2tlciIsImV4cCI6MTcwMDAwMDA2MCwi",
+        "c3ViIjoiZGFzaGJvYXJkIiwicm9sZSI6ImFkbWluIn0",
+        ".gGiCrDnPRq2rSfN1dWB5e2bhRIai2amPdN9iVIoCbE2B-koCr05eu_Z7NawAHUzz7OmBF2Yo3SvR9yVAFLh9NvMR4KbNREABKzn5",
+        "2WApCwhZMcPYQoTC7T66hRQrSbNribsVpmkiO5wzhhoL3MAuFHebEloK0ABVux6FPaWVzBDZFl6rDs1Bliz3OG14844rS2FxCSJQ",
+        "7Zx7ZVrDMohNisR6tMkKYnu4QRm5-C16vUjjxL1C8ThtqJwesqYrqvLRt6U62-EmN_p-LTkcjF1e9ckHKhxthVr0AVJyX3FAsl4p",
+        "DtxV0PlVjtBPUYc6ZvrxYLPej0ts-WnnYsDxMiT1Wg",
+    );
+
+    fn rsa_jwk() -> Jwk {
+        Jwk {
+            kty: "RSA".to_string(),
+            kid: Some("rsa-key".to_string()),
+            alg: Some("RS256".to_string()),
+            n: Some(
+                concat!(
+                    "10Mo1grpfJSJvHZsi7UUgxqjaljxm_bzAiTNDVoU-F1WtqQ-ovA32URhQ9p6Yy9zqyau3MzkTA8K2nhXX1EP0wMUGT",
+                    "Oq2_vkGDdLsthzFIXPYPZ0Gp95-voxjI3zc7sr6MjKqcKWpSjHq69ozCTJRBWIE3LWeW_nkYXIsfOaFn-nnKMvIHP-",
+                    "ZkbHwXmktUW_2el6xxxkFtMKPI_s3zxt4REXFpVkYmAWnqbCH0nZRnrJb8z6-pPOXJkxw75gfVtptX5fhICfU2fB6N",
+                    "m0rIT1UNI-bZieXHU1YkpeAir4MXG_-BSEIHdcUeq4IDIXNPN4pGhbQaiDu2y02q2ssfDzYQ",
+                )
+                .to_string(),
+            ),
+            e: Some("AQAB".to_string()),
+            crv: None,
+            x: None,
+            y: None,
+        }
+    }
+
+    fn sign_es256(claims: &serde_json::Value, kid: &str) -> (String, Jwk) {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+        let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng).unwrap();
+
+        let message = format!(
+            "{}.{}",
+            encode(json!({"alg": "ES256", "kid": kid}).to_string().as_bytes()),
+            encode(claims.to_string().as_bytes())
+        );
+
+        let signature = key_pair.sign(&rng, message.as_bytes()).unwrap();
+
+        // Uncompressed point: 0x04 followed by the coordinates
+        let public_key = key_pair.public_key().as_ref();
+
+        let jwk = Jwk {
+            kty: "EC".to_string(),
+            kid: Some(kid.to_string()),
+            alg: None,
+            n: None,
+            e: None,
+            crv: Some("P-256".to_string()),
+            x: Some(encode(&public_key[1..33])),
+            y: Some(encode(&public_key[33..])),
+        };
+
+        (format!("{message}.{}", encode(signature.as_ref())), jwk)
+    }
+
+    #[test]
+    fn it_should_verify_the_rs256_tokens_with_the_keys_of_the_jwks() {
+        let jwks = Jwks { keys: vec![rsa_jwk()] };
+
+        assert_eq!(
+            validate(RS256_TOKEN, &config(), &jwks, NOW),
+            Ok(Token {
+                role: AccessTokenRole::Admin,
+                subject: Some("dashboard".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn it_should_verify_the_es256_tokens_with_the_keys_of_the_jwks() {
+        let (token, jwk) = sign_es256(&valid_claims(), "ec-key");
+
+        assert_eq!(
+            validate(
+                &token,
+                &config(),
+                &Jwks {
+                    keys: vec![rsa_jwk(), jwk]
+                },
+                NOW
+            )
+            .map(|token| token.role),
+            Ok(AccessTokenRole::Stats)
+        );
+    }
+
+    #[test]
+    fn it_should_reject_the_tokens_signed_with_a_key_that_is_not_in_the_jwks() {
+        let (token, _jwk) = sign_es256(&valid_claims(), "ec-key");
+
+        assert_eq!(
+            validate(&token, &config(), &Jwks { keys: vec![rsa_jwk()] }, NOW),
+            Err(Error::UnknownKey)
+        );
+    }
+
+    #[test]
+    fn it_should_reject_the_tokens_signed_with_another_key_with_the_same_key_id() {
+        let (token, _jwk) = sign_es256(&valid_claims(), "ec-key");
+        let (_token, another_jwk) = sign_es256(&valid_claims(), "ec-key");
+
+        assert_eq!(
+            validate(&token, &config(), &Jwks { keys: vec![another_jwk] }, NOW),
+            Err(Error::InvalidSignature)
+        );
+    }
+}
@@ -8,11 +8,22 @@
 //! `Torrents` | Torrents | [`v1`](crate::servers::apis::v1::context::torrent)
 //! `Whitelist` | Torrents whitelist | [`v1`](crate::servers::apis::v1::context::whitelist)
 //! `Authentication keys` | Authentication keys | [`v1`](crate::servers::apis::v1::context::auth_key)
+//! `Abuse` | Abuse reports and mitigations | [`v1`](crate::servers::apis::v1::context::abuse)
+//! `Connection ID secret` | UDP connection ID secret rotation | [`v1`](crate::servers::apis::v1::context::connection_id_secret)
+//! `Warning` | Warning messages of the announce responses | [`v1`](crate::servers::apis::v1::context::warning)
+//! `Client whitelist` | Clients allowed by peer ID prefix | [`v1`](crate::servers::apis::v1::context::client_whitelist)
+//! `Events` | Live stream of the tracker activity | [`v1`](crate::servers::apis::v1::context::events)
+//! `Configuration` | Running configuration, with the secrets masked | [`v1`](crate::servers::apis::v1::context::config)
+//! `Audit log` | Audit log of the administrative actions | [`v1`](crate::servers::apis::v1::context::audit)
+//! `Token` | Rotation of the API access tokens | [`v1`](crate::servers::apis::v1::context::token)
+//! `Maintenance` | Peer cleanup and database maintenance on demand | [`v1`](crate::servers::apis::v1::context::maintenance)
 //!
 //! > **NOTICE**:
 //! - The authentication keys are only used by the HTTP tracker.
 //! - The whitelist is only used when the tracker is running in `listed` or
 //!   `private_listed` mode.
+//! - The client whitelist is only used when the tracker is running in
+//!   `private` or `private_listed` mode.
 //!
 //! Refer to the [authentication middleware](crate::servers::apis::v1::middlewares::auth)
 //! for more information about the authentication process.
@@ -20,3 +31,4 @@ pub mod context;
 pub mod middlewares;
 pub mod responses;
 pub mod routes;
+pub mod serializers;
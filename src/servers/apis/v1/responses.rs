@@ -1,5 +1,7 @@
 //! Common responses for the API v1 shared by all the contexts.
-use axum::http::{header, StatusCode};
+use std::time::Duration;
+
+use axum::http::{header, HeaderValue, StatusCode};
 use axum::response::{IntoResponse, Response};
 use serde::Serialize;
 
@@ -71,6 +73,39 @@ pub fn bad_request_response(body: &str) -> Response {
         .into_response()
 }
 
+/// `413` error response for the requests with a body over the size limit.
+#[must_use]
+pub fn payload_too_large_response() -> Response {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        "request body too large".to_owned(),
+    )
+        .into_response()
+}
+
+/// `429` error response for the requests over the rate limit, or from a
+/// locked out client. The `Retry-After` header is set when the time the client
+/// has to wait is known.
+#[must_use]
+pub fn too_many_requests_response(retry_after: Option<Duration>) -> Response {
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        "too many requests, try again later".to_owned(),
+    )
+        .into_response();
+
+    if let Some(retry_after) = retry_after {
+        // Rounded up, so the client does not retry too early
+        let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+
+        response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+    }
+
+    response
+}
+
 /// This error response is to keep backward compatibility with the old API.
 /// It should be a plain text or json.
 #[must_use]
@@ -1,18 +1,89 @@
 //! Route initialization for the v1 API.
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::Router;
+use axum::{middleware, Router};
+use torrust_tracker_configuration::Configuration;
 
-use super::context::{auth_key, stats, torrent, whitelist};
+use super::context::{
+    abuse, audit, auth_key, client_whitelist, config, connection_id_secret, events, maintenance, stats, token, torrent, warning,
+    whitelist,
+};
+use super::middlewares::deprecation;
 use crate::core::Tracker;
+use crate::servers::apis::access_tokens::AccessTokenStore;
 
 /// Add the routes for the v1 API.
-pub fn add(prefix: &str, router: Router, tracker: Arc<Tracker>) -> Router {
+pub fn add(
+    prefix: &str,
+    router: Router,
+    tracker: Arc<Tracker>,
+    access_tokens: Arc<AccessTokenStore>,
+    running_config: Arc<Configuration>,
+    redact_peer_ips: bool,
+    token_rotation_overlap: Duration,
+) -> Router {
     let v1_prefix = format!("{prefix}/v1");
 
-    let router = auth_key::routes::add(&v1_prefix, router, tracker.clone());
-    let router = stats::routes::add(&v1_prefix, router, tracker.clone());
-    let router = whitelist::routes::add(&v1_prefix, router, tracker.clone());
+    add_contexts(
+        &v1_prefix,
+        router,
+        tracker,
+        access_tokens,
+        running_config,
+        redact_peer_ips,
+        token_rotation_overlap,
+    )
+}
+
+/// Add the routes for the v1 API without the version in the path, for the
+/// clients of the old unversioned API. For example: `/api/stats`.
+///
+/// They are deprecated. Refer to the [`deprecation`] middleware.
+pub fn add_unversioned(
+    prefix: &str,
+    router: Router,
+    tracker: Arc<Tracker>,
+    access_tokens: Arc<AccessTokenStore>,
+    running_config: Arc<Configuration>,
+    redact_peer_ips: bool,
+    token_rotation_overlap: Duration,
+) -> Router {
+    let unversioned = add_contexts(
+        prefix,
+        Router::new(),
+        tracker,
+        access_tokens,
+        running_config,
+        redact_peer_ips,
+        token_rotation_overlap,
+    )
+    .layer(middleware::from_fn(deprecation::deprecated));
+
+    router.merge(unversioned)
+}
+
+fn add_contexts(
+    prefix: &str,
+    router: Router,
+    tracker: Arc<Tracker>,
+    access_tokens: Arc<AccessTokenStore>,
+    running_config: Arc<Configuration>,
+    redact_peer_ips: bool,
+    token_rotation_overlap: Duration,
+) -> Router {
+    let router = abuse::routes::add(prefix, router, tracker.clone());
+    let router = audit::routes::add(prefix, router, tracker.clone());
+    let router = auth_key::routes::add(prefix, router, tracker.clone());
+    let router = client_whitelist::routes::add(prefix, router, tracker.clone());
+    let router = config::routes::add(prefix, router, running_config);
+    let router = connection_id_secret::routes::add(prefix, router, tracker.clone());
+    let router = events::routes::add(prefix, router, tracker.clone());
+    let router = maintenance::routes::add(prefix, router, tracker.clone());
+    let router = stats::routes::add(prefix, router, tracker.clone());
+    let router = token::routes::add(prefix, router, tracker.clone(), access_tokens, token_rotation_overlap);
+    let router = warning::routes::add(prefix, router, tracker.clone());
+    let router = whitelist::routes::add(prefix, router, tracker.clone());
 
-    torrent::routes::add(&v1_prefix, router, tracker)
+    torrent::routes::add(prefix, router, tracker, redact_peer_ips)
 }
@@ -0,0 +1,284 @@
+//! Response serializers for the API.
+//!
+//! API resources are serialized to JSON by default. Some endpoints also
+//! support other formats, which are negotiated using the HTTP `Accept` header:
+//!
+//! Media type                 | Format
+//! ---------------------------|---------
+//! `application/json`         | JSON (default)
+//! `application/x-bittorrent` | Bencode
+//! `text/csv`                 | CSV
+//!
+//! For example, to get the torrent list as CSV:
+//!
+//! ```text
+//! curl -H "Accept: text/csv" "http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken"
+//! ```
+//!
+//! If the `Accept` header is missing or it contains a wildcard (`*/*`) the
+//! response is serialized as JSON. If none of the accepted media types is
+//! supported the API returns a `406 Not Acceptable` response.
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use serde::Serialize;
+
+/// The serialization formats supported by the API.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Format {
+    /// `application/json`
+    #[default]
+    Json,
+    /// `application/x-bittorrent`
+    Bencode,
+    /// `text/csv`
+    Csv,
+}
+
+impl Format {
+    /// It returns the format for a given media type, ignoring any media type
+    /// parameters like `q` or `charset`.
+    #[must_use]
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        let essence = media_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+
+        match essence.as_str() {
+            "application/json" | "application/*" | "*/*" => Some(Format::Json),
+            "application/x-bittorrent" => Some(Format::Bencode),
+            "text/csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+
+    /// It returns the first supported format in the `Accept` header value.
+    /// An empty header value means the client accepts any format.
+    #[must_use]
+    pub fn from_accept_header(accept: &str) -> Option<Self> {
+        if accept.trim().is_empty() {
+            return Some(Format::default());
+        }
+
+        accept.split(',').find_map(Self::from_media_type)
+    }
+
+    #[must_use]
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::Bencode => "application/x-bittorrent",
+            Format::Csv => "text/csv; charset=utf-8",
+        }
+    }
+}
+
+/// Extractor for the [`Format`] negotiated with the client.
+pub struct Negotiated(pub Format);
+
+impl<S> FromRequestParts<S> for Negotiated
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    #[must_use]
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut Parts,
+        _state: &'life1 S,
+    ) -> BoxFuture<'async_trait, Result<Self, Self::Rejection>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        async {
+            let accept = parts
+                .headers
+                .get(header::ACCEPT)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default();
+
+            match Format::from_accept_header(accept) {
+                Some(format) => Ok(Negotiated(format)),
+                None => Err(not_acceptable_response(accept)),
+            }
+        }
+        .boxed()
+    }
+}
+
+/// A resource that can be serialized as a CSV record.
+pub trait CsvRecord {
+    /// The names of the columns.
+    fn csv_header() -> Vec<&'static str>;
+
+    /// The values of the columns, in the same order as the header.
+    fn csv_fields(&self) -> Vec<String>;
+}
+
+/// It serializes a single resource in the negotiated format.
+///
+/// CSV responses contain a header row followed by one record.
+#[must_use]
+pub fn resource_response<T>(format: Format, resource: &T) -> Response
+where
+    T: Serialize + CsvRecord,
+{
+    match format {
+        Format::Json => json_response(resource),
+        Format::Bencode => bencode_response(resource),
+        Format::Csv => csv_response(std::slice::from_ref(resource)),
+    }
+}
+
+/// It serializes a list of resources in the negotiated format.
+///
+/// CSV responses contain a header row followed by one record per resource.
+#[must_use]
+pub fn resource_list_response<T>(format: Format, resources: &[T]) -> Response
+where
+    T: Serialize + CsvRecord,
+{
+    match format {
+        Format::Json => json_response(&resources),
+        Format::Bencode => bencode_response(&resources),
+        Format::Csv => csv_response(resources),
+    }
+}
+
+/// # Panics
+///
+/// Will panic if it can't convert the resource to JSON.
+fn json_response<T: Serialize + ?Sized>(resource: &T) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, Format::Json.content_type())],
+        serde_json::to_string(resource).expect("API resources should be serializable to JSON"),
+    )
+        .into_response()
+}
+
+fn bencode_response<T: Serialize + ?Sized>(resource: &T) -> Response {
+    match serde_bencode::to_bytes(resource) {
+        Ok(bytes) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, Format::Bencode.content_type())],
+            bytes,
+        )
+            .into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            format!("Unable to serialize resource as bencode: {err}"),
+        )
+            .into_response(),
+    }
+}
+
+fn csv_response<T: CsvRecord>(resources: &[T]) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, Format::Csv.content_type())],
+        to_csv(resources),
+    )
+        .into_response()
+}
+
+/// It encodes a list of records as CSV, including the header row.
+#[must_use]
+pub fn to_csv<T: CsvRecord>(records: &[T]) -> String {
+    let mut csv = T::csv_header().join(",");
+    csv.push_str("\r\n");
+
+    for record in records {
+        let fields: Vec<String> = record.csv_fields().iter().map(|field| escape_csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+/// It quotes a CSV field if it contains a delimiter, a quote or a line break
+/// (RFC 4180).
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// `406` response returned when none of the media types accepted by the
+/// client is supported.
+#[must_use]
+pub fn not_acceptable_response(accept: &str) -> Response {
+    (
+        StatusCode::NOT_ACCEPTABLE,
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        format!("None of the accepted media types is supported: {accept}"),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_csv, CsvRecord, Format};
+
+    struct Row {
+        name: String,
+        value: u64,
+    }
+
+    impl CsvRecord for Row {
+        fn csv_header() -> Vec<&'static str> {
+            vec!["name", "value"]
+        }
+
+        fn csv_fields(&self) -> Vec<String> {
+            vec![self.name.clone(), self.value.to_string()]
+        }
+    }
+
+    #[test]
+    fn it_should_use_json_when_the_client_does_not_send_the_accept_header() {
+        assert_eq!(Format::from_accept_header(""), Some(Format::Json));
+    }
+
+    #[test]
+    fn it_should_use_json_when_the_client_accepts_any_media_type() {
+        assert_eq!(Format::from_accept_header("*/*"), Some(Format::Json));
+    }
+
+    #[test]
+    fn it_should_use_the_first_supported_media_type_in_the_accept_header() {
+        assert_eq!(
+            Format::from_accept_header("text/html, text/csv;q=0.9, application/json;q=0.8"),
+            Some(Format::Csv)
+        );
+        assert_eq!(Format::from_accept_header("application/x-bittorrent"), Some(Format::Bencode));
+    }
+
+    #[test]
+    fn it_should_not_negotiate_any_format_when_no_accepted_media_type_is_supported() {
+        assert_eq!(Format::from_accept_header("text/html, image/png"), None);
+    }
+
+    #[test]
+    fn it_should_encode_a_list_of_records_as_csv_with_a_header_row() {
+        let rows = vec![
+            Row {
+                name: "a".to_string(),
+                value: 1,
+            },
+            Row {
+                name: "b, \"c\"".to_string(),
+                value: 2,
+            },
+        ];
+
+        assert_eq!(to_csv(&rows), "name,value\r\na,1\r\n\"b, \"\"c\"\"\",2\r\n");
+    }
+}
@@ -16,16 +16,22 @@
 //! ```
 //!
 //! If you want to know more about Axum and timeouts see <https://github.com/josecelano/axum-server-timeout>.
+//!
+//! Servers can also be configured with [`ConnectionSettings`], to keep the
+//! connections open between requests (keep-alive) and to limit the number of
+//! simultaneous connections.
 use std::future::Ready;
 use std::io::ErrorKind;
 use std::net::TcpListener;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
 use axum_server::accept::Accept;
 use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
 use axum_server::Server;
+use futures::future::BoxFuture;
 use futures_util::{ready, Future};
 use http_body::{Body, Frame};
 use hyper::Response;
@@ -33,7 +39,9 @@ use hyper_util::rt::TokioTimer;
 use pin_project_lite::pin_project;
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio::time::{Instant, Sleep};
+use torrust_tracker_configuration::HttpTracker;
 use tower::Service;
 
 const HTTP1_HEADER_READ_TIMEOUT: Duration = Duration::from_secs(5);
@@ -50,6 +58,32 @@ pub fn from_tcp_rustls_with_timeouts(socket: TcpListener, tls: RustlsConfig) ->
     add_timeouts(axum_server::from_tcp_rustls(socket, tls))
 }
 
+/// It creates a server with the timeouts and the connection `settings`.
+#[must_use]
+pub fn from_tcp_with_settings(socket: TcpListener, settings: &ConnectionSettings) -> Server<ConnectionAcceptor> {
+    with_settings(from_tcp_with_timeouts(socket), settings)
+}
+
+/// It creates a TLS server with the timeouts and the connection `settings`.
+#[must_use]
+pub fn from_tcp_rustls_with_settings(
+    socket: TcpListener,
+    tls: RustlsConfig,
+    settings: &ConnectionSettings,
+) -> Server<ConnectionAcceptor<RustlsAcceptor>> {
+    with_settings(from_tcp_rustls_with_timeouts(socket, tls), settings)
+}
+
+/// It applies the connection `settings` to a server, wrapping its acceptor.
+/// The wrapped acceptor can be the TLS one, so the settings also apply to the
+/// TLS connections.
+#[must_use]
+pub fn with_settings<A>(mut server: Server<A>, settings: &ConnectionSettings) -> Server<ConnectionAcceptor<A>> {
+    server.http_builder().http1().keep_alive(settings.keep_alive());
+
+    server.map(|inner| ConnectionAcceptor::new(inner, settings))
+}
+
 fn add_timeouts<A>(mut server: Server<A>) -> Server<A> {
     server.http_builder().http1().timer(TokioTimer::new());
     server.http_builder().http2().timer(TokioTimer::new());
@@ -82,6 +116,149 @@ impl<I, S> Accept<I, S> for TimeoutAcceptor {
     }
 }
 
+/// Settings of the connections accepted by a server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectionSettings {
+    /// How long idle connections are kept open, waiting for the next
+    /// request. Connections are closed after each response if it's zero.
+    pub keep_alive_timeout: Duration,
+    /// Maximum number of simultaneous connections. Unlimited if `None`.
+    pub max_connections: Option<u32>,
+    /// How long the server waits for the requests in process when it's shut
+    /// down.
+    pub shutdown_timeout: Duration,
+}
+
+impl ConnectionSettings {
+    #[must_use]
+    pub fn new(config: &HttpTracker) -> Self {
+        Self {
+            keep_alive_timeout: Duration::from_secs(config.keep_alive_timeout),
+            max_connections: config.max_connections,
+            shutdown_timeout: Duration::from_secs(config.shutdown_timeout),
+        }
+    }
+
+    fn keep_alive(&self) -> bool {
+        !self.keep_alive_timeout.is_zero()
+    }
+
+    /// Time to wait for the request headers, and for the next request on
+    /// kept-alive connections.
+    fn idle_timeout(&self) -> Duration {
+        if self.keep_alive() {
+            self.keep_alive_timeout
+        } else {
+            HTTP1_HEADER_READ_TIMEOUT
+        }
+    }
+}
+
+/// An acceptor that closes the idle connections, like the
+/// [`TimeoutAcceptor`], and rejects the connections over the limit of the
+/// [`ConnectionSettings`].
+///
+/// It wraps another acceptor, by default the one of plain TCP connections.
+#[derive(Clone)]
+pub struct ConnectionAcceptor<A = axum_server::accept::DefaultAcceptor> {
+    inner: A,
+    idle_timeout: Duration,
+    connections: Option<Arc<Semaphore>>,
+}
+
+impl<A> ConnectionAcceptor<A> {
+    fn new(inner: A, settings: &ConnectionSettings) -> Self {
+        Self {
+            inner,
+            idle_timeout: settings.idle_timeout(),
+            connections: settings.max_connections.map(|max_connections| {
+                Arc::new(Semaphore::new(
+                    usize::try_from(max_connections).unwrap_or(Semaphore::MAX_PERMITS),
+                ))
+            }),
+        }
+    }
+}
+
+impl<A, I, S> Accept<I, S> for ConnectionAcceptor<A>
+where
+    A: Accept<I, S>,
+    A::Stream: Send + 'static,
+    A::Service: Send + 'static,
+    A::Future: Send + 'static,
+{
+    type Stream = LimitedStream<TimeoutStream<A::Stream>>;
+    type Service = TimeoutService<A::Service>;
+    type Future = BoxFuture<'static, std::io::Result<(Self::Stream, Self::Service)>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        // The permit is taken before the TLS handshake, so the handshakes in
+        // progress are also limited.
+        let permit = match &self.connections {
+            None => None,
+            Some(connections) => match connections.clone().try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => return Box::pin(std::future::ready(Err(std::io::Error::other("too many connections")))),
+            },
+        };
+
+        let accepting = self.inner.accept(stream, service);
+        let idle_timeout = self.idle_timeout;
+
+        Box::pin(async move {
+            let (stream, service) = accepting.await?;
+
+            let (tx, rx) = mpsc::unbounded_channel();
+
+            let stream = LimitedStream {
+                inner: TimeoutStream::new(stream, idle_timeout, rx),
+                _permit: permit,
+            };
+
+            Ok((stream, TimeoutService::new(service, tx)))
+        })
+    }
+}
+
+/// A connection counted in the limit of simultaneous connections. The
+/// connection is released when the stream is dropped.
+pub struct LimitedStream<IO> {
+    inner: IO,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl<IO: AsyncRead + Unpin> AsyncRead for LimitedStream<IO> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<IO: AsyncWrite + Unpin> AsyncWrite for LimitedStream<IO> {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<Result<usize, std::io::Error>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+}
+
 #[derive(Clone)]
 pub struct TimeoutService<S> {
     inner: S,
@@ -1,19 +1,22 @@
 use std::collections::VecDeque;
+use std::sync::Arc;
 
 use axum::extract::State;
+use axum::http::StatusCode;
 use axum::Json;
 use tracing::{instrument, Level};
 
-use super::resources::{CheckReport, Report};
+use super::resources::{CheckReport, ProbeReport, Report, ServiceCheck, Status};
 use super::responses;
+use crate::core::Tracker;
 use crate::servers::registar::{ServiceHealthCheckJob, ServiceRegistration, ServiceRegistry};
 
 /// Endpoint for container health check.
 ///
 /// Creates a vector [`CheckReport`] from the input set of [`CheckJob`], and then builds a report from the results.
 ///
-#[instrument(skip(register), ret(level = Level::DEBUG))]
-pub(crate) async fn health_check_handler(State(register): State<ServiceRegistry>) -> Json<Report> {
+#[instrument(skip(_tracker, register), ret(level = Level::DEBUG))]
+pub(crate) async fn health_check_handler(State((_tracker, register)): State<(Arc<Tracker>, ServiceRegistry)>) -> Json<Report> {
     #[allow(unused_assignments)]
     let mut checks: VecDeque<ServiceHealthCheckJob> = VecDeque::new();
 
@@ -50,3 +53,54 @@ pub(crate) async fn health_check_handler(State(register): State<ServiceRegistry>
         responses::ok(results)
     }
 }
+
+/// Endpoint for the liveness probe of the orchestrators.
+///
+/// The tracker is alive while it can respond. It does not check the services,
+/// so the orchestrator does not restart the tracker when one of its
+/// dependencies is not available.
+#[instrument(ret(level = Level::DEBUG))]
+pub(crate) async fn liveness_handler() -> (StatusCode, Json<ProbeReport>) {
+    responses::probe(ProbeReport::new(vec![]))
+}
+
+/// Endpoint for the readiness probe of the orchestrators.
+///
+/// It checks every registered UDP and HTTP listener is bound and responding,
+/// the database is reachable and the statistics event listener is running.
+/// It responds with the `503 Service Unavailable` status code if any of them
+/// fails, so the orchestrator stops routing traffic to the tracker.
+#[instrument(skip(tracker, register), ret(level = Level::DEBUG))]
+pub(crate) async fn readiness_handler(
+    State((tracker, register)): State<(Arc<Tracker>, ServiceRegistry)>,
+) -> (StatusCode, Json<ProbeReport>) {
+    let listener_checks: Vec<ServiceHealthCheckJob> =
+        register.lock().await.values().map(ServiceRegistration::spawn_check).collect();
+
+    let mut checks: Vec<ServiceCheck> = futures::future::join_all(listener_checks.into_iter().map(|c| async move {
+        let result = c.job.await.unwrap_or_else(|err| Err(err.to_string()));
+        ServiceCheck::new("listener", Some(c.binding), result)
+    }))
+    .await;
+
+    let database_result = tracker
+        .ping_database()
+        .await
+        .map(|()| "database reachable".to_string())
+        .map_err(|err| err.to_string());
+
+    checks.push(ServiceCheck::new("database", None, database_result));
+
+    checks.push(match tracker.is_stats_listener_alive() {
+        Some(true) => ServiceCheck::new("statistics", None, Ok("statistics event listener running".to_string())),
+        Some(false) => ServiceCheck::new("statistics", None, Err("statistics event listener stopped".to_string())),
+        None => ServiceCheck {
+            service: "statistics".to_string(),
+            binding: None,
+            status: Status::None,
+            message: "statistics disabled".to_string(),
+        },
+    });
+
+    responses::probe(ProbeReport::new(checks))
+}
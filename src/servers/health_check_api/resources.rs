@@ -62,3 +62,57 @@ impl Report {
         }
     }
 }
+
+/// The status of one of the services checked by the liveness and readiness
+/// probes.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ServiceCheck {
+    pub service: String,
+    pub binding: Option<SocketAddr>,
+    pub status: Status,
+    pub message: String,
+}
+
+impl ServiceCheck {
+    #[must_use]
+    pub fn new(service: &str, binding: Option<SocketAddr>, result: Result<String, String>) -> Self {
+        let (status, message) = match result {
+            Ok(message) => (Status::Ok, message),
+            Err(message) => (Status::Error, message),
+        };
+
+        Self {
+            service: service.to_string(),
+            binding,
+            status,
+            message,
+        }
+    }
+
+    #[must_use]
+    pub fn fail(&self) -> bool {
+        self.status == Status::Error
+    }
+}
+
+/// The report of the liveness and readiness probes.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ProbeReport {
+    pub status: Status,
+    pub checks: Vec<ServiceCheck>,
+}
+
+impl ProbeReport {
+    /// It builds the report of the checks. The status is `Error` if any of
+    /// the checks failed.
+    #[must_use]
+    pub fn new(checks: Vec<ServiceCheck>) -> Self {
+        let status = if checks.iter().any(ServiceCheck::fail) {
+            Status::Error
+        } else {
+            Status::Ok
+        };
+
+        Self { status, checks }
+    }
+}
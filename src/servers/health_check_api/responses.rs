@@ -1,6 +1,7 @@
+use axum::http::StatusCode;
 use axum::Json;
 
-use super::resources::{CheckReport, Report};
+use super::resources::{CheckReport, ProbeReport, Report, Status};
 
 pub fn ok(details: Vec<CheckReport>) -> Json<Report> {
     Json(Report::ok(details))
@@ -13,3 +14,14 @@ pub fn error(message: String, details: Vec<CheckReport>) -> Json<Report> {
 pub fn none() -> Json<Report> {
     Json(Report::none())
 }
+
+/// It returns the report of a probe, with the `503 Service Unavailable`
+/// status code if any of the checks failed.
+pub fn probe(report: ProbeReport) -> (StatusCode, Json<ProbeReport>) {
+    let status_code = match report.status {
+        Status::Error => StatusCode::SERVICE_UNAVAILABLE,
+        Status::Ok | Status::None => StatusCode::OK,
+    };
+
+    (status_code, Json(report))
+}
@@ -2,7 +2,45 @@
 //!
 //! This API is intended to be used by the container infrastructure to check if
 //! the whole application is healthy.
+//!
+//! Besides the `/health_check` endpoint, it provides the endpoints for the
+//! probes of the orchestrators:
+//!
+//! - `/health/live`: the liveness probe. It always responds with `200 OK`
+//!   while the tracker is running.
+//! - `/health/ready`: the readiness probe. It checks the UDP and HTTP
+//!   listeners, the database and the statistics event listener, and responds
+//!   with `503 Service Unavailable` if any of them fails.
+//!
+//! For example, the readiness report of a tracker with one HTTP tracker:
+//!
+//! ```json
+//! {
+//!   "status": "Ok",
+//!   "checks": [
+//!     {
+//!       "service": "listener",
+//!       "binding": "0.0.0.0:7070",
+//!       "status": "Ok",
+//!       "message": "200 OK"
+//!     },
+//!     {
+//!       "service": "database",
+//!       "binding": null,
+//!       "status": "Ok",
+//!       "message": "database reachable"
+//!     },
+//!     {
+//!       "service": "statistics",
+//!       "binding": null,
+//!       "status": "Ok",
+//!       "message": "statistics event listener running"
+//!     }
+//!   ]
+//! }
+//! ```
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
 
 use axum::http::HeaderName;
@@ -21,27 +59,32 @@ use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 use tracing::{instrument, Level, Span};
 
 use crate::bootstrap::jobs::Started;
-use crate::servers::health_check_api::handlers::health_check_handler;
+use crate::core::Tracker;
+use crate::servers::health_check_api::handlers::{health_check_handler, liveness_handler, readiness_handler};
 use crate::servers::health_check_api::HEALTH_CHECK_API_LOG_TARGET;
 use crate::servers::registar::ServiceRegistry;
-use crate::servers::signals::{graceful_shutdown, Halted};
+use crate::servers::signals::{graceful_shutdown, Halted, DEFAULT_SHUTDOWN_TIMEOUT};
+use crate::servers::socket_activation;
 
 /// Starts Health Check API server.
 ///
 /// # Panics
 ///
 /// Will panic if binding to the socket address fails.
-#[instrument(skip(bind_to, tx, rx_halt, register))]
+#[instrument(skip(bind_to, tx, rx_halt, register, tracker))]
 pub fn start(
     bind_to: SocketAddr,
     tx: Sender<Started>,
     rx_halt: Receiver<Halted>,
     register: ServiceRegistry,
+    tracker: Arc<Tracker>,
 ) -> impl Future<Output = Result<(), std::io::Error>> {
     let router = Router::new()
         .route("/", get(|| async { Json(json!({})) }))
         .route("/health_check", get(health_check_handler))
-        .with_state(register)
+        .route("/health/live", get(liveness_handler))
+        .route("/health/ready", get(readiness_handler))
+        .with_state((tracker, register))
         .layer(CompressionLayer::new())
         .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
         .layer(PropagateHeaderLayer::new(HeaderName::from_static("x-request-id")))
@@ -77,7 +120,8 @@ pub fn start(
         )
         .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid));
 
-    let socket = std::net::TcpListener::bind(bind_to).expect("Could not bind tcp_listener to address.");
+    let socket = socket_activation::take_tcp_listener(bind_to)
+        .unwrap_or_else(|| std::net::TcpListener::bind(bind_to).expect("Could not bind tcp_listener to address."));
     let address = socket.local_addr().expect("Could not get local_addr from tcp_listener.");
 
     let handle = Handle::new();
@@ -88,6 +132,7 @@ pub fn start(
         handle.clone(),
         rx_halt,
         format!("Shutting down http server on socket address: {address}"),
+        DEFAULT_SHUTDOWN_TIMEOUT,
     ));
 
     let running = axum_server::from_tcp(socket)
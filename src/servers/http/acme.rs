@@ -0,0 +1,50 @@
+//! Automatic TLS certificates for the HTTP tracker with the ACME protocol.
+//!
+//! The certificates are obtained from the certificate authority (Let's
+//! Encrypt by default) with the `TLS-ALPN-01` challenge, and renewed before
+//! they expire. The TLS connections are accepted with the current certificate,
+//! so the server does not need to be reloaded on renewal.
+//!
+//! HTTP/2 and HTTP/1.1 are negotiated with ALPN, like with the TLS
+//! certificates from files.
+//!
+//! Refer to the [`HttpTrackerAcme`] configuration for more information.
+use std::sync::Arc;
+
+use futures::StreamExt as _;
+use rustls_acme::axum::AxumAcceptor;
+use rustls_acme::caches::DirCache;
+use rustls_acme::AcmeConfig;
+use torrust_tracker_configuration::HttpTrackerAcme;
+
+use crate::servers::http::HTTP_TRACKER_LOG_TARGET;
+
+/// It builds the acceptor of the TLS connections. It spawns a task that
+/// obtains and renews the certificate, and logs the ACME events.
+#[must_use]
+pub fn make_acceptor(config: &HttpTrackerAcme) -> AxumAcceptor {
+    let mut state = AcmeConfig::new(config.domains.clone())
+        .contact(config.contact_email.iter().map(|email| format!("mailto:{email}")))
+        .cache(DirCache::new(config.cache_dir.clone()))
+        .directory(&config.directory_url)
+        .state();
+
+    // The default config only includes the ALPN protocol of the challenge
+    let mut rustls_config = (*state.default_rustls_config()).clone();
+    rustls_config
+        .alpn_protocols
+        .splice(0..0, [b"h2".to_vec(), b"http/1.1".to_vec()]);
+
+    let acceptor = state.axum_acceptor(Arc::new(rustls_config));
+
+    tokio::spawn(async move {
+        while let Some(event) = state.next().await {
+            match event {
+                Ok(event) => tracing::info!(target: HTTP_TRACKER_LOG_TARGET, "ACME event: {event:?}"),
+                Err(err) => tracing::error!(target: HTTP_TRACKER_LOG_TARGET, "ACME error: {err:?}"),
+            }
+        }
+    });
+
+    acceptor
+}
@@ -15,6 +15,8 @@
 //! - [Requests](#requests)
 //!     - [Announce](#announce)
 //!     - [Scrape](#scrape)
+//! - [Rate limiting](#rate-limiting)
+//! - [Connections](#connections)
 //! - [Versioning](#versioning)
 //! - [Links](#links)
 //!
@@ -52,7 +54,11 @@
 //! [`left`](crate::servers::http::v1::requests::announce::Announce::left) | positive integer | The number of bytes pending to download. | No | `0` | `0`
 //! [`event`](crate::servers::http::v1::requests::announce::Announce::event) | positive integer | The event that triggered the `Announce` request: `started`, `completed`, `stopped` | No | `None` | `completed`
 //! [`compact`](crate::servers::http::v1::requests::announce::Announce::compact) | `0` or `1` | Whether the tracker should return a compact peer list. | No | `None` | `0`
+//! [`no_peer_id`](crate::servers::http::v1::requests::announce::Announce::no_peer_id) | `0` or `1` | Whether the tracker should omit the peer IDs in the non-compact peer list. | No | `0` | `1`
 //! `numwant` | positive integer | **Not implemented**. The maximum number of peers you want in the reply. | No | `50` | `50`
+//! [`ipv4`](crate::servers::http::v1::requests::announce::Announce::ipv4) | string | The `IPv4` endpoint of a dual-stack peer announcing over `IPv6`. | No | `None` | `126.0.0.1:17548`
+//! [`ipv6`](crate::servers::http::v1::requests::announce::Announce::ipv6) | string | The `IPv6` endpoint of a dual-stack peer announcing over `IPv4`. | No | `None` | `[2001:db8::1]:17548`
+//! [`key`](crate::servers::http::v1::requests::announce::Announce::key) | string | An identification of the client, to recognize it when its IP address changes. | No | `None` | `1A2B3C4D`
 //!
 //! Refer to the [`Announce`](crate::servers::http::v1::requests::announce::Announce)
 //! request for more information about the parameters.
@@ -70,6 +76,11 @@
 //! > request or the right-most-ip in the `X-Forwarded-For` header if the tracker
 //! > is behind a reverse proxy.
 //!
+//! > **NOTICE**: the `ipv4` and `ipv6` parameters are defined in the
+//! > [BEP 07](https://www.bittorrent.org/beps/bep_0007.html). Only the
+//! > endpoint in the other IP family is used, so dual-stack peers are returned
+//! > in both the `peers` and `peers6` lists of the compact responses.
+//!
 //! > **NOTICE**: the maximum number of peers that the tracker can return is
 //! > `74`. Defined with a hardcoded const [`TORRENT_PEERS_LIMIT`](torrust_tracker_configuration::TORRENT_PEERS_LIMIT).
 //! > Refer to [issue 262](https://github.com/torrust/torrust-tracker/issues/262)
@@ -99,6 +110,16 @@
 //!
 //! <http://0.0.0.0:7070/announce?info_hash=%81%00%00%00%00%00%00%00%00%00%00%00%00%00%00%00%00%00%00%00&peer_addr=2.137.87.41&downloaded=0&uploaded=0&peer_id=-qB00000000000000001&port=17548&left=0&event=completed&compact=0>
 //!
+//! When the tracker is running in private mode, the authentication key
+//! (passkey) is embedded in the URL path, either after or before the
+//! `announce` segment:
+//!
+//! - `http://0.0.0.0:7070/announce/YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ?info_hash=...`
+//! - `http://0.0.0.0:7070/YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ/announce?info_hash=...`
+//!
+//! The key is validated against the tracker keys before processing the
+//! announce. The same forms are supported for the `scrape` requests.
+//!
 //! **Sample non-compact response**
 //!
 //! In [bencoded](https://en.wikipedia.org/wiki/Bencode) format:
@@ -238,7 +259,11 @@
 //! `info_hash` parameters: `info_hash=%81%00%0...00%00%00&info_hash=%82%00%0...00%00%00`
 //!
 //! > **NOTICE**: the maximum number of torrents you can scrape at the same time
-//! > is `74`. Defined with a hardcoded const [`MAX_SCRAPE_TORRENTS`](crate::shared::bit_torrent::common::MAX_SCRAPE_TORRENTS).
+//! > is `74` by default. It can be changed with the `max_scrape_torrents`
+//! > option of each HTTP tracker. Requests with more torrents are rejected.
+//!
+//! > **NOTICE**: the `scrape` requests can be disabled in private mode with
+//! > the `scrape_in_private_mode` option of each HTTP tracker.
 //!
 //! **Sample response**
 //!
@@ -287,6 +312,50 @@
 //! - [BEP 48. Tracker Protocol Extension: Scrape](https://www.bittorrent.org/beps/bep_0048.html)
 //! - [Vuze scrape docs](https://wiki.vuze.com/w/Scrape)
 //!
+//! ## Rate limiting
+//!
+//! The `announce` and `scrape` requests can be limited per client IP address
+//! and per authentication key with the `rate_limit` option of each HTTP
+//! tracker:
+//!
+//! ```toml
+//! [[http_trackers]]
+//! bind_address = "0.0.0.0:7070"
+//!
+//! [http_trackers.rate_limit]
+//! requests_per_minute_per_ip = 60
+//! requests_per_minute_per_key = 120
+//! retry_in = 5
+//! ```
+//!
+//! Requests over the limit are rejected with an error response including the
+//! minutes the client should wait before retrying
+//! ([BEP 31](https://www.bittorrent.org/beps/bep_0031.html)):
+//!
+//! ```text
+//! d14:failure reason34:too many requests, try again later8:retry ini5ee
+//! ```
+//!
+//! Refer to the [`rate_limiter`] module for more information.
+//!
+//! ## Connections
+//!
+//! Clients announce periodically, so the connections are kept open between
+//! requests for the `keep_alive_timeout` (5 seconds by default), to save the
+//! TCP and TLS handshakes. Over TLS, HTTP/2 is negotiated with the clients
+//! supporting it. The number of simultaneous connections can be limited with
+//! `max_connections`:
+//!
+//! ```toml
+//! [[http_trackers]]
+//! bind_address = "0.0.0.0:7070"
+//! keep_alive_timeout = 60
+//! max_connections = 10000
+//! ```
+//!
+//! Refer to the [`custom_axum_server`](crate::servers::custom_axum_server)
+//! module for more information.
+//!
 //! ## Versioning
 //!
 //! Right not there is only version `v1`. The HTTP tracker implements BEPS:
@@ -305,7 +374,9 @@
 //! - [Bencode to Json Online converter](https://chocobo1.github.io/bencode_online).
 use serde::{Deserialize, Serialize};
 
+pub mod acme;
 pub mod percent_encoding;
+pub mod rate_limiter;
 pub mod server;
 pub mod v1;
 
@@ -0,0 +1,123 @@
+//! Per-IP and per-key rate limiter for the HTTP tracker.
+//!
+//! Each client IP address and each authentication key has a token bucket
+//! refilled at the configured requests per minute. Every `announce` and
+//! `scrape` request takes one token from the bucket of its IP address and
+//! from the bucket of its key, if any. Requests received with an empty bucket
+//! exceed the limit. Only the keys in the key store have a bucket: the
+//! requests with unknown keys are only limited by their IP address, and
+//! rejected by the handlers.
+//!
+//! Refer to the [`HttpTrackerRateLimit`] configuration for more information.
+use std::net::IpAddr;
+
+use torrust_tracker_configuration::HttpTrackerRateLimit;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+use crate::core::auth::Key;
+use crate::servers::token_buckets::TokenBuckets;
+
+/// A token bucket rate limiter keyed by client IP address and by
+/// authentication key.
+#[derive(Debug)]
+pub struct RateLimiter {
+    per_ip: Option<TokenBuckets<IpAddr>>,
+    per_key: Option<TokenBuckets<Key>>,
+    retry_in: u32,
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new(config: &HttpTrackerRateLimit) -> Self {
+        Self {
+            per_ip: config.requests_per_minute_per_ip.map(TokenBuckets::per_minute),
+            per_key: config.requests_per_minute_per_key.map(TokenBuckets::per_minute),
+            retry_in: config.retry_in,
+        }
+    }
+
+    /// Minutes the clients are asked to wait before retrying the requests
+    /// over the limit.
+    #[must_use]
+    pub fn retry_in(&self) -> u32 {
+        self.retry_in
+    }
+
+    /// It takes a token for a request from the IP address and, if the request
+    /// contains a known key, from the key. It returns `false` if the request
+    /// exceeds any of the limits.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the buckets is poisoned.
+    pub fn try_acquire(&self, ip: &IpAddr, key: Option<&Key>, now: DurationSinceUnixEpoch) -> bool {
+        if let Some(per_ip) = &self.per_ip {
+            if !per_ip.try_acquire(ip, now) {
+                return false;
+            }
+        }
+
+        match (&self.per_key, key) {
+            (Some(per_key), Some(key)) => per_key.try_acquire(key, now),
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    use torrust_tracker_configuration::HttpTrackerRateLimit;
+
+    use super::RateLimiter;
+    use crate::core::auth::Key;
+
+    fn ip(last_octet: u8) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(126, 0, 0, last_octet))
+    }
+
+    fn sample_key() -> Key {
+        Key::from_str("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap()
+    }
+
+    fn rate_limiter(requests_per_minute_per_ip: Option<u32>, requests_per_minute_per_key: Option<u32>) -> RateLimiter {
+        RateLimiter::new(&HttpTrackerRateLimit {
+            requests_per_minute_per_ip,
+            requests_per_minute_per_key,
+            retry_in: 1,
+        })
+    }
+
+    #[test]
+    fn it_should_reject_the_requests_from_an_ip_exceeding_the_limit() {
+        let rate_limiter = rate_limiter(Some(2), None);
+
+        assert!(rate_limiter.try_acquire(&ip(1), None, Duration::ZERO));
+        assert!(rate_limiter.try_acquire(&ip(1), None, Duration::ZERO));
+        assert!(!rate_limiter.try_acquire(&ip(1), None, Duration::ZERO));
+
+        assert!(rate_limiter.try_acquire(&ip(2), None, Duration::ZERO));
+    }
+
+    #[test]
+    fn it_should_accept_the_requests_again_once_the_bucket_is_refilled() {
+        let rate_limiter = rate_limiter(Some(1), None);
+
+        assert!(rate_limiter.try_acquire(&ip(1), None, Duration::ZERO));
+        assert!(!rate_limiter.try_acquire(&ip(1), None, Duration::from_secs(59)));
+        assert!(rate_limiter.try_acquire(&ip(1), None, Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn it_should_reject_the_requests_with_a_key_exceeding_the_limit_from_any_ip() {
+        let rate_limiter = rate_limiter(None, Some(1));
+
+        assert!(rate_limiter.try_acquire(&ip(1), Some(&sample_key()), Duration::ZERO));
+        assert!(!rate_limiter.try_acquire(&ip(2), Some(&sample_key()), Duration::ZERO));
+
+        assert!(rate_limiter.try_acquire(&ip(2), None, Duration::ZERO));
+    }
+}
@@ -7,16 +7,21 @@ use axum_server::Handle;
 use derive_more::Constructor;
 use futures::future::BoxFuture;
 use tokio::sync::oneshot::{Receiver, Sender};
+use torrust_tracker_configuration::{HttpTrackerAcme, HttpTrackerRateLimit};
 use tracing::instrument;
 
 use super::v1::routes::router;
 use crate::bootstrap::jobs::Started;
 use crate::core::Tracker;
-use crate::servers::custom_axum_server::{self, TimeoutAcceptor};
-use crate::servers::http::HTTP_TRACKER_LOG_TARGET;
+use crate::servers::access_log::AccessLog;
+use crate::servers::custom_axum_server::{self, ConnectionSettings};
+use crate::servers::http::v1::handlers::announce::AnnounceOptions;
+use crate::servers::http::v1::handlers::scrape::ScrapeOptions;
+use crate::servers::http::{acme, HTTP_TRACKER_LOG_TARGET};
 use crate::servers::logging::STARTED_ON;
 use crate::servers::registar::{ServiceHealthCheckJob, ServiceRegistration, ServiceRegistrationForm};
 use crate::servers::signals::{graceful_shutdown, Halted};
+use crate::servers::socket_activation;
 
 /// Error that can occur when starting or stopping the HTTP server.
 ///
@@ -39,12 +44,28 @@ pub enum Error {
 pub struct Launcher {
     pub bind_to: SocketAddr,
     pub tls: Option<RustlsConfig>,
+    /// Automatic TLS certificates. The `tls` config is ignored when it's set.
+    pub acme: Option<HttpTrackerAcme>,
+    /// Keep-alive and limit of the connections.
+    pub connection_settings: ConnectionSettings,
+    /// Maximum number of requests handled concurrently. Unlimited if `None`.
+    pub max_concurrent_requests: Option<u32>,
+    /// Options of the `announce` requests.
+    pub announce_options: AnnounceOptions,
+    /// Limits of the `scrape` requests.
+    pub scrape_options: ScrapeOptions,
+    /// Rate limits of the `announce` and `scrape` requests. Unlimited if
+    /// `None`.
+    pub rate_limit: Option<HttpTrackerRateLimit>,
+    /// Access log of the requests. Disabled if `None`.
+    pub access_log: Option<Arc<AccessLog>>,
 }
 
 impl Launcher {
     #[instrument(skip(self, tracker, tx_start, rx_halt))]
     fn start(&self, tracker: Arc<Tracker>, tx_start: Sender<Started>, rx_halt: Receiver<Halted>) -> BoxFuture<'static, ()> {
-        let socket = std::net::TcpListener::bind(self.bind_to).expect("Could not bind tcp_listener to address.");
+        let socket = socket_activation::take_tcp_listener(self.bind_to)
+            .unwrap_or_else(|| std::net::TcpListener::bind(self.bind_to).expect("Could not bind tcp_listener to address."));
         let address = socket.local_addr().expect("Could not get local_addr from tcp_listener.");
 
         let handle = Handle::new();
@@ -53,28 +74,44 @@ impl Launcher {
             handle.clone(),
             rx_halt,
             format!("Shutting down HTTP server on socket address: {address}"),
+            self.connection_settings.shutdown_timeout,
         ));
 
         let tls = self.tls.clone();
-        let protocol = if tls.is_some() { "https" } else { "http" };
+        let acme = self.acme.as_ref().map(acme::make_acceptor);
+        let protocol = if tls.is_some() || acme.is_some() { "https" } else { "http" };
 
         tracing::info!(target: HTTP_TRACKER_LOG_TARGET, "Starting on: {protocol}://{}", address);
 
-        let app = router(tracker, address);
-
-        let running = Box::pin(async {
-            match tls {
-                Some(tls) => custom_axum_server::from_tcp_rustls_with_timeouts(socket, tls)
+        let app = router(
+            tracker,
+            address,
+            self.max_concurrent_requests,
+            self.announce_options,
+            self.scrape_options,
+            self.rate_limit,
+            self.access_log.clone(),
+        );
+
+        let connection_settings = self.connection_settings;
+
+        let running = Box::pin(async move {
+            match (acme, tls) {
+                (Some(acme), _) => custom_axum_server::with_settings(
+                    custom_axum_server::from_tcp_with_timeouts(socket).acceptor(acme),
+                    &connection_settings,
+                )
+                .handle(handle)
+                .serve(app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+                .await
+                .expect("Axum server crashed."),
+                (None, Some(tls)) => custom_axum_server::from_tcp_rustls_with_settings(socket, tls, &connection_settings)
                     .handle(handle)
-                    // The TimeoutAcceptor is commented because TSL does not work with it.
-                    // See: https://github.com/torrust/torrust-index/issues/204#issuecomment-2115529214
-                    //.acceptor(TimeoutAcceptor)
                     .serve(app.into_make_service_with_connect_info::<std::net::SocketAddr>())
                     .await
                     .expect("Axum server crashed."),
-                None => custom_axum_server::from_tcp_with_timeouts(socket)
+                (None, None) => custom_axum_server::from_tcp_with_settings(socket, &connection_settings)
                     .handle(handle)
-                    .acceptor(TimeoutAcceptor)
                     .serve(app.into_make_service_with_connect_info::<std::net::SocketAddr>())
                     .await
                     .expect("Axum server crashed."),
@@ -233,7 +270,10 @@ mod tests {
 
     use crate::bootstrap::app::initialize_with_configuration;
     use crate::bootstrap::jobs::make_rust_tls;
+    use crate::servers::custom_axum_server::ConnectionSettings;
     use crate::servers::http::server::{HttpServer, Launcher};
+    use crate::servers::http::v1::handlers::announce::AnnounceOptions;
+    use crate::servers::http::v1::handlers::scrape::ScrapeOptions;
     use crate::servers::registar::Registar;
 
     #[tokio::test]
@@ -243,7 +283,7 @@ mod tests {
         let http_trackers = cfg.http_trackers.clone().expect("missing HTTP trackers configuration");
         let config = &http_trackers[0];
 
-        let bind_to = config.bind_address;
+        let bind_to = config.bind_address.socket_addr().expect("it should bind to an IP address");
 
         let tls = make_rust_tls(&config.tsl_config)
             .await
@@ -251,7 +291,17 @@ mod tests {
 
         let register = &Registar::default();
 
-        let stopped = HttpServer::new(Launcher::new(bind_to, tls));
+        let stopped = HttpServer::new(Launcher::new(
+            bind_to,
+            tls,
+            config.acme.clone(),
+            ConnectionSettings::new(config),
+            config.max_concurrent_requests,
+            AnnounceOptions::new(config),
+            ScrapeOptions::new(config),
+            config.rate_limit,
+            None,
+        ));
         let started = stopped
             .start(tracker, register.give_form())
             .await
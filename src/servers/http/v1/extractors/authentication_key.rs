@@ -3,8 +3,9 @@
 //!
 //! It's only used when the tracker is running in private mode.
 //!
-//! Given the following URL route with a path param: `/announce/:key`,
-//! it extracts the `key` param from the URL path.
+//! Given the following URL routes with a path param: `/announce/:key` or
+//! `/:key/announce` (the form used by some private tracker frontends), it
+//! extracts the `key` param from the URL path.
 //!
 //! It's a wrapper for Axum `Path` extractor in order to return custom
 //! authentication errors.
@@ -43,9 +44,10 @@
 //! > nor [The Private Torrents](https://www.bittorrent.org/beps/bep_0027.html)
 //! > specifications specify any HTTP status code for authentication errors.
 use std::panic::Location;
+use std::sync::Arc;
 
 use axum::extract::rejection::PathRejection;
-use axum::extract::{FromRequestParts, Path};
+use axum::extract::{FromRef, FromRequestParts, Path};
 use axum::http::request::Parts;
 use axum::response::{IntoResponse, Response};
 use futures::future::BoxFuture;
@@ -53,6 +55,8 @@ use futures::FutureExt;
 use serde::Deserialize;
 
 use crate::core::auth::Key;
+use crate::core::statistics::{self, Protocol, RejectReason, RequestKind};
+use crate::core::Tracker;
 use crate::servers::http::v1::handlers::common::auth;
 use crate::servers::http::v1::responses;
 
@@ -72,6 +76,7 @@ impl KeyParam {
 impl<S> FromRequestParts<S> for Extract
 where
     S: Send + Sync,
+    Arc<Tracker>: FromRef<S>,
 {
     type Rejection = Response;
 
@@ -91,7 +96,19 @@ where
 
             match extract_key(maybe_path_with_key) {
                 Ok(key) => Ok(Extract(key)),
-                Err(error) => Err(error.into_response()),
+                Err(error) => {
+                    let request = if parts.uri.path().split('/').any(|segment| segment == "scrape") {
+                        RequestKind::Scrape
+                    } else {
+                        RequestKind::Announce
+                    };
+
+                    Arc::<Tracker>::from_ref(state)
+                        .send_stats_event(statistics::Event::rejected(Protocol::Http, request, RejectReason::InvalidKey))
+                        .await;
+
+                    Err(error.into_response())
+                }
             }
         }
         .boxed()
@@ -1,22 +1,16 @@
 //! Axum [`extractor`](axum::extract) to get the relevant information to resolve the remote
 //! client IP.
 //!
-//! It's a wrapper for two third-party Axum extractors.
-//!
-//! The first one is `RightmostXForwardedFor` from the `axum-client-ip` crate.
-//! This extractor is used to get the right-most IP address from the
-//! `X-Forwarded-For` header.
-//!
-//! The second one is `ConnectInfo` from the `axum` crate. This extractor is
-//! used to get the IP address of the client from the connection info.
-//!
-//! The `ClientIpSources` struct is a wrapper for the two extractors.
+//! It collects the values of the client IP headers (in order of precedence)
+//! and the IP address of the client from the connection info, using the
+//! `ConnectInfo` extractor from the `axum` crate.
 //!
 //! The tracker can be configured to run behind a reverse proxy. In this case,
-//! the tracker will use the `X-Forwarded-For` header to get the client IP
-//! address.
+//! the tracker will use the client IP headers (`X-Forwarded-For` by default)
+//! to get the client IP address, but only if the connection comes from a
+//! trusted proxy.
 //!
-//! See [`torrust_tracker_configuration::Configuration::core.on_reverse_proxy`].
+//! See [`torrust_tracker_configuration::Network`].
 //!
 //! The tracker can also be configured to run without a reverse proxy. In this
 //! case, the tracker will use the IP address from the connection info.
@@ -32,34 +26,32 @@
 //! This extractor returns these values:
 //!
 //! ```text
-//! `right_most_x_forwarded_for` = 126.0.0.2
-//! `connection_info_ip`         = 126.0.0.3
+//! `client_ip_headers`  = [(X-Forwarded-For, "126.0.0.1,126.0.0.2")]
+//! `connection_info_ip` = 126.0.0.3
 //! ```
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 use axum::extract::{ConnectInfo, FromRequestParts};
 use axum::http::request::Parts;
 use axum::response::Response;
-use axum_client_ip::RightmostXForwardedFor;
 use futures::future::BoxFuture;
 use futures::FutureExt;
 
+use crate::core::Tracker;
 use crate::servers::http::v1::services::peer_ip_resolver::ClientIpSources;
 
 /// Extractor for the [`ClientIpSources`]
 /// struct.
 pub struct Extract(pub ClientIpSources);
 
-impl<S> FromRequestParts<S> for Extract
-where
-    S: Send + Sync,
-{
+impl FromRequestParts<Arc<Tracker>> for Extract {
     type Rejection = Response;
 
     #[must_use]
     fn from_request_parts<'life0, 'life1, 'async_trait>(
         parts: &'life0 mut Parts,
-        state: &'life1 S,
+        state: &'life1 Arc<Tracker>,
     ) -> BoxFuture<'async_trait, Result<Self, Self::Rejection>>
     where
         'life0: 'async_trait,
@@ -67,10 +59,22 @@ where
         Self: 'async_trait,
     {
         async {
-            let right_most_x_forwarded_for = match RightmostXForwardedFor::from_request_parts(parts, state).await {
-                Ok(right_most_x_forwarded_for) => Some(right_most_x_forwarded_for.0),
-                Err(_) => None,
-            };
+            // Repeated headers are combined into a single comma-separated list
+            let client_ip_headers = state
+                .get_network_config()
+                .client_ip_headers
+                .iter()
+                .filter_map(|header| {
+                    let values = parts
+                        .headers
+                        .get_all(header.name())
+                        .iter()
+                        .filter_map(|value| value.to_str().ok())
+                        .collect::<Vec<_>>();
+
+                    (!values.is_empty()).then(|| (header.clone(), values.join(",")))
+                })
+                .collect();
 
             let connection_info_ip = match ConnectInfo::<SocketAddr>::from_request_parts(parts, state).await {
                 Ok(connection_info_socket_addr) => Some(connection_info_socket_addr.0.ip()),
@@ -78,7 +82,7 @@ where
             };
 
             Ok(Extract(ClientIpSources {
-                right_most_x_forwarded_for,
+                client_ip_headers,
                 connection_info_ip,
             }))
         }
@@ -28,13 +28,16 @@
 //! d14:failure reason235:Cannot parse query params for scrape request: invalid param value invalid for info_hash in not enough bytes for infohash: got 7 bytes, expected 20 src/shared/bit_torrent/info_hash.rs:240:27, src/servers/http/v1/requests/scrape.rs:66:46e
 //! ```
 use std::panic::Location;
+use std::sync::Arc;
 
-use axum::extract::FromRequestParts;
+use axum::extract::{FromRef, FromRequestParts};
 use axum::http::request::Parts;
 use axum::response::{IntoResponse, Response};
 use futures::future::BoxFuture;
 use futures::FutureExt;
 
+use crate::core::statistics::{self, Protocol, RejectReason, RequestKind};
+use crate::core::Tracker;
 use crate::servers::http::v1::query::Query;
 use crate::servers::http::v1::requests::scrape::{ParseScrapeQueryError, Scrape};
 use crate::servers::http::v1::responses;
@@ -46,13 +49,14 @@ pub struct ExtractRequest(pub Scrape);
 impl<S> FromRequestParts<S> for ExtractRequest
 where
     S: Send + Sync,
+    Arc<Tracker>: FromRef<S>,
 {
     type Rejection = Response;
 
     #[must_use]
     fn from_request_parts<'life0, 'life1, 'async_trait>(
         parts: &'life0 mut Parts,
-        _state: &'life1 S,
+        state: &'life1 S,
     ) -> BoxFuture<'async_trait, Result<Self, Self::Rejection>>
     where
         'life0: 'async_trait,
@@ -62,30 +66,48 @@ where
         async {
             match extract_scrape_from(parts.uri.query()) {
                 Ok(scrape_request) => Ok(ExtractRequest(scrape_request)),
-                Err(error) => Err(error.into_response()),
+                Err((reason, error)) => {
+                    let tracker = Arc::<Tracker>::from_ref(state);
+
+                    tracker
+                        .send_stats_event(statistics::Event::rejected(Protocol::Http, RequestKind::Scrape, reason))
+                        .await;
+
+                    Err(error.into_response())
+                }
             }
         }
         .boxed()
     }
 }
 
-fn extract_scrape_from(maybe_raw_query: Option<&str>) -> Result<Scrape, responses::error::Error> {
+/// It parses the request. It returns why the request is rejected along with
+/// the error response.
+fn extract_scrape_from(maybe_raw_query: Option<&str>) -> Result<Scrape, (RejectReason, responses::error::Error)> {
     if maybe_raw_query.is_none() {
-        return Err(responses::error::Error::from(ParseScrapeQueryError::MissingParams {
-            location: Location::caller(),
-        }));
+        return Err((
+            RejectReason::ParseError,
+            responses::error::Error::from(ParseScrapeQueryError::MissingParams {
+                location: Location::caller(),
+            }),
+        ));
     }
 
     let query = maybe_raw_query.unwrap().parse::<Query>();
 
     if let Err(error) = query {
-        return Err(responses::error::Error::from(error));
+        return Err((RejectReason::ParseError, responses::error::Error::from(error)));
     }
 
     let scrape_request = Scrape::try_from(query.unwrap());
 
     if let Err(error) = scrape_request {
-        return Err(responses::error::Error::from(error));
+        let reason = match error {
+            ParseScrapeQueryError::InvalidInfoHashParam { .. } => RejectReason::BadInfoHash,
+            _ => RejectReason::ParseError,
+        };
+
+        return Err((reason, responses::error::Error::from(error)));
     }
 
     Ok(scrape_request.unwrap())
@@ -98,6 +120,7 @@ mod tests {
     use torrust_tracker_primitives::info_hash::InfoHash;
 
     use super::extract_scrape_from;
+    use crate::core::statistics::RejectReason;
     use crate::servers::http::v1::requests::scrape::Scrape;
     use crate::servers::http::v1::responses::error::Error;
 
@@ -154,7 +177,9 @@ mod tests {
 
     #[test]
     fn it_should_reject_a_request_without_query_params() {
-        let response = extract_scrape_from(None).unwrap_err();
+        let (reason, response) = extract_scrape_from(None).unwrap_err();
+
+        assert_eq!(reason, RejectReason::ParseError);
 
         assert_error_response(
             &response,
@@ -165,15 +190,27 @@ mod tests {
     #[test]
     fn it_should_reject_a_request_with_a_query_that_cannot_be_parsed() {
         let invalid_query = "param1=value1=value2";
-        let response = extract_scrape_from(Some(invalid_query)).unwrap_err();
+        let (reason, response) = extract_scrape_from(Some(invalid_query)).unwrap_err();
+
+        assert_eq!(reason, RejectReason::ParseError);
 
         assert_error_response(&response, "Cannot parse query params");
     }
 
     #[test]
     fn it_should_reject_a_request_with_a_query_that_cannot_be_parsed_into_a_scrape_request() {
-        let response = extract_scrape_from(Some("param1=value1")).unwrap_err();
+        let (reason, response) = extract_scrape_from(Some("param1=value1")).unwrap_err();
+
+        assert_eq!(reason, RejectReason::ParseError);
 
         assert_error_response(&response, "Cannot parse query params for scrape request");
     }
+
+    #[test]
+    fn it_should_reject_a_request_with_an_invalid_info_hash_as_a_bad_info_hash() {
+        let (reason, response) = extract_scrape_from(Some("info_hash=invalid")).unwrap_err();
+
+        assert_eq!(reason, RejectReason::BadInfoHash);
+        assert_error_response(&response, "invalid param value invalid for info_hash");
+    }
 }
@@ -8,15 +8,19 @@
 use std::net::{IpAddr, SocketAddr};
 use std::panic::Location;
 use std::sync::Arc;
+use std::time::Duration;
 
 use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes};
 use axum::extract::State;
 use axum::response::{IntoResponse, Response};
+use axum::Extension;
 use torrust_tracker_clock::clock::Time;
+use torrust_tracker_configuration::HttpTracker;
 use torrust_tracker_primitives::peer;
 
 use crate::core::auth::Key;
-use crate::core::{AnnounceData, PeersWanted, Tracker};
+use crate::core::statistics::{Protocol, RejectReason, RequestKind};
+use crate::core::{failure_messages, statistics, AnnounceData, PeersWanted, Tracker};
 use crate::servers::http::v1::extractors::announce_request::ExtractRequest;
 use crate::servers::http::v1::extractors::authentication_key::Extract as ExtractKey;
 use crate::servers::http::v1::extractors::client_ip_sources::Extract as ExtractClientIpSources;
@@ -27,17 +31,45 @@ use crate::servers::http::v1::services::peer_ip_resolver::ClientIpSources;
 use crate::servers::http::v1::services::{self, peer_ip_resolver};
 use crate::CurrentClock;
 
+/// Options of the `announce` requests of an HTTP tracker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnnounceOptions {
+    /// Minimum port of the announced peers. Announces with a lower port are
+    /// rejected.
+    pub min_peer_port: u16,
+    /// Whether the client IP is returned in the `external ip` field of the
+    /// responses. The `core.net` option is used if `None`.
+    pub return_client_ip: Option<bool>,
+}
+
+impl AnnounceOptions {
+    #[must_use]
+    pub fn new(config: &HttpTracker) -> Self {
+        Self {
+            min_peer_port: config.min_peer_port,
+            return_client_ip: config.return_client_ip,
+        }
+    }
+}
+
+impl Default for AnnounceOptions {
+    fn default() -> Self {
+        Self::new(&HttpTracker::default())
+    }
+}
+
 /// It handles the `announce` request when the HTTP tracker does not require
 /// authentication (no PATH `key` parameter required).
 #[allow(clippy::unused_async)]
 pub async fn handle_without_key(
     State(tracker): State<Arc<Tracker>>,
+    Extension(options): Extension<AnnounceOptions>,
     ExtractRequest(announce_request): ExtractRequest,
     ExtractClientIpSources(client_ip_sources): ExtractClientIpSources,
 ) -> Response {
     tracing::debug!("http announce request: {:#?}", announce_request);
 
-    handle(&tracker, &announce_request, &client_ip_sources, None).await
+    handle(&tracker, &announce_request, &client_ip_sources, None, options).await
 }
 
 /// It handles the `announce` request when the HTTP tracker requires
@@ -45,13 +77,14 @@ pub async fn handle_without_key(
 #[allow(clippy::unused_async)]
 pub async fn handle_with_key(
     State(tracker): State<Arc<Tracker>>,
+    Extension(options): Extension<AnnounceOptions>,
     ExtractRequest(announce_request): ExtractRequest,
     ExtractClientIpSources(client_ip_sources): ExtractClientIpSources,
     ExtractKey(key): ExtractKey,
 ) -> Response {
     tracing::debug!("http announce request: {:#?}", announce_request);
 
-    handle(&tracker, &announce_request, &client_ip_sources, Some(key)).await
+    handle(&tracker, &announce_request, &client_ip_sources, Some(key), options).await
 }
 
 /// It handles the `announce` request.
@@ -63,10 +96,14 @@ async fn handle(
     announce_request: &Announce,
     client_ip_sources: &ClientIpSources,
     maybe_key: Option<Key>,
+    options: AnnounceOptions,
 ) -> Response {
-    let announce_data = match handle_announce(tracker, announce_request, client_ip_sources, maybe_key).await {
+    let announce_data = match handle_announce(tracker, announce_request, client_ip_sources, maybe_key, options).await {
         Ok(announce_data) => announce_data,
-        Err(error) => return error.into_response(),
+        Err(error) => {
+            let retry_in = tracker.get_retry_in().or(error.retry_in);
+            return error.with_retry_in(retry_in).into_response();
+        }
     };
     build_response(announce_request, announce_data)
 }
@@ -82,57 +119,176 @@ async fn handle_announce(
     announce_request: &Announce,
     client_ip_sources: &ClientIpSources,
     maybe_key: Option<Key>,
+    options: AnnounceOptions,
 ) -> Result<AnnounceData, responses::error::Error> {
     // Authentication
     if tracker.requires_authentication() {
-        match maybe_key {
-            Some(key) => match tracker.authenticate(&key).await {
+        match &maybe_key {
+            Some(key) => match tracker.authenticate(key).await {
                 Ok(()) => (),
-                Err(error) => return Err(responses::error::Error::from(error)),
+                Err(error) => {
+                    let error =
+                        responses::error::Error::from(error).with_retry_in(tracker.get_retry_in_after(RejectReason::InvalidKey));
+                    return Err(reject(tracker, RejectReason::InvalidKey, error, announce_request, maybe_key.as_ref()).await);
+                }
             },
             None => {
-                return Err(responses::error::Error::from(auth::Error::MissingAuthKey {
+                let error = responses::error::Error::from(auth::Error::MissingAuthKey {
                     location: Location::caller(),
-                }))
+                })
+                .with_retry_in(tracker.get_retry_in_after(RejectReason::InvalidKey));
+                return Err(reject(tracker, RejectReason::InvalidKey, error, announce_request, None).await);
             }
         }
     }
 
+    if announce_request.port < options.min_peer_port {
+        let error = responses::error::Error {
+            failure_reason: format!(
+                "invalid peer port: {}, the minimum port is {}",
+                announce_request.port, options.min_peer_port
+            ),
+            retry_in: None,
+        };
+        return Err(reject(
+            tracker,
+            RejectReason::InvalidPeerPort,
+            error,
+            announce_request,
+            maybe_key.as_ref(),
+        )
+        .await);
+    }
+
     // Authorization
     match tracker.authorize(&announce_request.info_hash).await {
         Ok(()) => (),
-        Err(error) => return Err(responses::error::Error::from(error)),
+        Err(error) => {
+            let reason = RejectReason::from(&error);
+            let error = responses::error::Error::from(error).with_retry_in(tracker.get_retry_in_after(reason));
+            return Err(reject(tracker, reason, error, announce_request, maybe_key.as_ref()).await);
+        }
+    }
+
+    match tracker.authorize_client(&announce_request.peer_id) {
+        Ok(()) => (),
+        Err(error) => {
+            let reason = RejectReason::from(&error);
+            return Err(reject(tracker, reason, error.into(), announce_request, maybe_key.as_ref()).await);
+        }
     }
 
-    let peer_ip = match peer_ip_resolver::invoke(tracker.is_behind_reverse_proxy(), client_ip_sources) {
+    let peer_ip = match peer_ip_resolver::invoke(tracker.get_network_config(), client_ip_sources) {
         Ok(peer_ip) => peer_ip,
-        Err(error) => return Err(responses::error::Error::from(error)),
+        Err(error) => {
+            let reason = RejectReason::from(&error);
+            return Err(reject(tracker, reason, error.into(), announce_request, maybe_key.as_ref()).await);
+        }
     };
 
+    match tracker.authorize_peer_ip(&peer_ip) {
+        Ok(()) => (),
+        Err(error) => {
+            let reason = RejectReason::from(&error);
+            return Err(reject(tracker, reason, error.into(), announce_request, maybe_key.as_ref()).await);
+        }
+    }
+
+    match tracker.consume_announce_budget(&peer_ip) {
+        Ok(()) => (),
+        Err(error) => {
+            let reason = RejectReason::from(&error);
+            let error = responses::error::Error::from(error).with_retry_in(Some(1));
+            return Err(reject(tracker, reason, error, announce_request, maybe_key.as_ref()).await);
+        }
+    }
+
     let mut peer = peer_from_request(announce_request, &peer_ip);
     let peers_wanted = match announce_request.numwant {
         Some(numwant) => PeersWanted::only(numwant),
         None => PeersWanted::All,
     };
 
-    let announce_data = services::announce::invoke(tracker.clone(), announce_request.info_hash, &mut peer, &peers_wanted).await;
+    if let Some(client_key) = &announce_request.key {
+        tracker
+            .replace_previous_peer_of_client(&announce_request.info_hash, &peer, client_key)
+            .await;
+    }
+
+    let mut announce_data =
+        services::announce::invoke(tracker.clone(), announce_request.info_hash, &mut peer, &peers_wanted).await;
+
+    announce_data.warning_message = tracker.get_warning_message(&announce_request.info_hash, &peer, maybe_key.as_ref());
+
+    if let Some(return_client_ip) = options.return_client_ip {
+        announce_data.external_ip = return_client_ip.then_some(peer_ip);
+    }
+
+    if let Some(check) = tracker.check_tracker_id(
+        &announce_request.info_hash,
+        &announce_request.peer_id,
+        peer.event,
+        announce_request.tracker_id.as_deref(),
+    ) {
+        tracker.send_stats_event(statistics::Event::TcpTrackerIdChecked(check)).await;
+    }
+
+    if let Some(proxy_ip) = client_ip_sources
+        .connection_info_ip
+        .filter(|proxy_ip| tracker.get_network_config().is_trusted_proxy(proxy_ip))
+    {
+        tracker
+            .send_stats_event(statistics::Event::TcpForwardedAnnounce {
+                proxy_ip,
+                forwarded_ip: peer_ip,
+            })
+            .await;
+    }
 
     Ok(announce_data)
 }
 
+/// It records the rejection of the announce and returns the error response,
+/// with the failure message configured for the rejection reason, if any.
+async fn reject(
+    tracker: &Tracker,
+    reason: RejectReason,
+    error: responses::error::Error,
+    announce_request: &Announce,
+    maybe_key: Option<&Key>,
+) -> responses::error::Error {
+    tracker
+        .send_stats_event(statistics::Event::rejected(Protocol::Http, RequestKind::Announce, reason))
+        .await;
+
+    let context = failure_messages::Context {
+        info_hash: Some(&announce_request.info_hash),
+        key: maybe_key,
+        retry_in: tracker.get_retry_in().or(error.retry_in),
+    };
+
+    responses::error::Error {
+        failure_reason: tracker.get_failure_message(reason, error.failure_reason, &context),
+        retry_in: error.retry_in,
+    }
+}
+
 fn build_response(announce_request: &Announce, announce_data: AnnounceData) -> Response {
     if announce_request.compact.as_ref().is_some_and(|f| *f == Compact::Accepted) {
         let response: responses::Announce<responses::Compact> = announce_data.into();
         response.into_response()
     } else {
-        let response: responses::Announce<responses::Normal> = announce_data.into();
+        let response =
+            responses::Announce::new(responses::Normal::from(announce_data).with_no_peer_id(announce_request.no_peer_id));
         response.into_response()
     }
 }
 
 /// It builds a `Peer` from the announce request.
 ///
-/// It ignores the peer address in the announce request params.
+/// It ignores the peer address in the announce request params. Only the
+/// endpoint in the other IP family of a dual-stack peer (the `ipv4` or `ipv6`
+/// params) is kept, so the peer can be returned to clients of both families.
 #[must_use]
 fn peer_from_request(announce_request: &Announce, peer_ip: &IpAddr) -> peer::Peer {
     peer::Peer {
@@ -143,6 +299,11 @@ fn peer_from_request(announce_request: &Announce, peer_ip: &IpAddr) -> peer::Pee
         downloaded: announce_request.downloaded.unwrap_or(NumberOfBytes::new(0)),
         left: announce_request.left.unwrap_or(NumberOfBytes::new(0)),
         event: map_to_torrust_event(&announce_request.event),
+        timeout_extension: Duration::ZERO,
+        alt_peer_addr: match peer_ip {
+            IpAddr::V4(_) => announce_request.ipv6.map(SocketAddr::V6),
+            IpAddr::V6(_) => announce_request.ipv4.map(SocketAddr::V4),
+        },
     }
 }
 
@@ -209,13 +370,18 @@ mod tests {
             left: None,
             event: None,
             compact: None,
+            no_peer_id: false,
             numwant: None,
+            ipv4: None,
+            ipv6: None,
+            key: None,
+            tracker_id: None,
         }
     }
 
     fn sample_client_ip_sources() -> ClientIpSources {
         ClientIpSources {
-            right_most_x_forwarded_for: None,
+            client_ip_headers: vec![],
             connection_info_ip: None,
         }
     }
@@ -234,8 +400,8 @@ mod tests {
 
         use super::{private_tracker, sample_announce_request, sample_client_ip_sources};
         use crate::core::auth;
-        use crate::servers::http::v1::handlers::announce::handle_announce;
         use crate::servers::http::v1::handlers::announce::tests::assert_error_response;
+        use crate::servers::http::v1::handlers::announce::{handle_announce, AnnounceOptions};
 
         #[tokio::test]
         async fn it_should_fail_when_the_authentication_key_is_missing() {
@@ -243,9 +409,15 @@ mod tests {
 
             let maybe_key = None;
 
-            let response = handle_announce(&tracker, &sample_announce_request(), &sample_client_ip_sources(), maybe_key)
-                .await
-                .unwrap_err();
+            let response = handle_announce(
+                &tracker,
+                &sample_announce_request(),
+                &sample_client_ip_sources(),
+                maybe_key,
+                AnnounceOptions::default(),
+            )
+            .await
+            .unwrap_err();
 
             assert_error_response(
                 &response,
@@ -261,21 +433,47 @@ mod tests {
 
             let maybe_key = Some(unregistered_key);
 
-            let response = handle_announce(&tracker, &sample_announce_request(), &sample_client_ip_sources(), maybe_key)
-                .await
-                .unwrap_err();
+            let response = handle_announce(
+                &tracker,
+                &sample_announce_request(),
+                &sample_client_ip_sources(),
+                maybe_key,
+                AnnounceOptions::default(),
+            )
+            .await
+            .unwrap_err();
 
             assert_error_response(&response, "Authentication error: Failed to read key");
         }
+
+        #[tokio::test]
+        async fn it_should_ask_the_client_to_retry_later_when_the_authentication_key_is_invalid() {
+            let tracker = Arc::new(private_tracker());
+
+            let response = handle_announce(
+                &tracker,
+                &sample_announce_request(),
+                &sample_client_ip_sources(),
+                None,
+                AnnounceOptions::default(),
+            )
+            .await
+            .unwrap_err();
+
+            assert_eq!(response.retry_in, Some(60));
+        }
     }
 
     mod with_tracker_in_listed_mode {
 
         use std::sync::Arc;
 
+        use torrust_tracker_test_helpers::configuration;
+
         use super::{sample_announce_request, sample_client_ip_sources, whitelisted_tracker};
-        use crate::servers::http::v1::handlers::announce::handle_announce;
+        use crate::core::services::tracker_factory;
         use crate::servers::http::v1::handlers::announce::tests::assert_error_response;
+        use crate::servers::http::v1::handlers::announce::{handle_announce, AnnounceOptions};
 
         #[tokio::test]
         async fn it_should_fail_when_the_announced_torrent_is_not_whitelisted() {
@@ -283,9 +481,15 @@ mod tests {
 
             let announce_request = sample_announce_request();
 
-            let response = handle_announce(&tracker, &announce_request, &sample_client_ip_sources(), None)
-                .await
-                .unwrap_err();
+            let response = handle_announce(
+                &tracker,
+                &announce_request,
+                &sample_client_ip_sources(),
+                None,
+                AnnounceOptions::default(),
+            )
+            .await
+            .unwrap_err();
 
             assert_error_response(
                 &response,
@@ -295,34 +499,66 @@ mod tests {
                 ),
             );
         }
+
+        #[tokio::test]
+        async fn it_should_use_the_failure_message_template_when_the_announced_torrent_is_not_whitelisted() {
+            let mut configuration = configuration::ephemeral_listed();
+            configuration.core.failure_messages.not_whitelisted =
+                Some("torrent {info_hash} is not tracked here, retry in {retry_in} minutes".to_string());
+            let tracker = Arc::new(tracker_factory(&configuration));
+
+            let announce_request = sample_announce_request();
+
+            let response = handle_announce(
+                &tracker,
+                &announce_request,
+                &sample_client_ip_sources(),
+                None,
+                AnnounceOptions::default(),
+            )
+            .await
+            .unwrap_err();
+
+            assert_eq!(
+                response.failure_reason,
+                format!(
+                    "torrent {} is not tracked here, retry in 60 minutes",
+                    announce_request.info_hash
+                )
+            );
+        }
     }
 
     mod with_tracker_on_reverse_proxy {
 
+        use std::net::{IpAddr, Ipv4Addr};
         use std::sync::Arc;
 
         use super::{sample_announce_request, tracker_on_reverse_proxy};
-        use crate::servers::http::v1::handlers::announce::handle_announce;
         use crate::servers::http::v1::handlers::announce::tests::assert_error_response;
+        use crate::servers::http::v1::handlers::announce::{handle_announce, AnnounceOptions};
         use crate::servers::http::v1::services::peer_ip_resolver::ClientIpSources;
 
         #[tokio::test]
-        async fn it_should_fail_when_the_right_most_x_forwarded_for_header_ip_is_not_available() {
+        async fn it_should_fail_when_the_client_ip_header_is_not_available() {
             let tracker = Arc::new(tracker_on_reverse_proxy());
 
             let client_ip_sources = ClientIpSources {
-                right_most_x_forwarded_for: None,
-                connection_info_ip: None,
+                client_ip_headers: vec![],
+                connection_info_ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
             };
 
-            let response = handle_announce(&tracker, &sample_announce_request(), &client_ip_sources, None)
-                .await
-                .unwrap_err();
-
-            assert_error_response(
-                &response,
-                "Error resolving peer IP: missing or invalid the right most X-Forwarded-For IP",
-            );
+            let response = handle_announce(
+                &tracker,
+                &sample_announce_request(),
+                &client_ip_sources,
+                None,
+                AnnounceOptions::default(),
+            )
+            .await
+            .unwrap_err();
+
+            assert_error_response(&response, "Error resolving peer IP: missing or invalid client IP header");
         }
     }
 
@@ -331,8 +567,8 @@ mod tests {
         use std::sync::Arc;
 
         use super::{sample_announce_request, tracker_not_on_reverse_proxy};
-        use crate::servers::http::v1::handlers::announce::handle_announce;
         use crate::servers::http::v1::handlers::announce::tests::assert_error_response;
+        use crate::servers::http::v1::handlers::announce::{handle_announce, AnnounceOptions};
         use crate::servers::http::v1::services::peer_ip_resolver::ClientIpSources;
 
         #[tokio::test]
@@ -340,13 +576,19 @@ mod tests {
             let tracker = Arc::new(tracker_not_on_reverse_proxy());
 
             let client_ip_sources = ClientIpSources {
-                right_most_x_forwarded_for: None,
+                client_ip_headers: vec![],
                 connection_info_ip: None,
             };
 
-            let response = handle_announce(&tracker, &sample_announce_request(), &client_ip_sources, None)
-                .await
-                .unwrap_err();
+            let response = handle_announce(
+                &tracker,
+                &sample_announce_request(),
+                &client_ip_sources,
+                None,
+                AnnounceOptions::default(),
+            )
+            .await
+            .unwrap_err();
 
             assert_error_response(
                 &response,
@@ -354,4 +596,94 @@ mod tests {
             );
         }
     }
+
+    mod with_a_minimum_peer_port {
+
+        use std::sync::Arc;
+
+        use super::{sample_announce_request, sample_client_ip_sources, tracker_not_on_reverse_proxy};
+        use crate::servers::http::v1::handlers::announce::tests::assert_error_response;
+        use crate::servers::http::v1::handlers::announce::{handle_announce, AnnounceOptions};
+
+        #[tokio::test]
+        async fn it_should_fail_when_the_announced_peer_port_is_lower_than_the_minimum() {
+            let tracker = Arc::new(tracker_not_on_reverse_proxy());
+
+            let announce_request = sample_announce_request();
+
+            let response = handle_announce(
+                &tracker,
+                &announce_request,
+                &sample_client_ip_sources(),
+                None,
+                AnnounceOptions {
+                    min_peer_port: announce_request.port + 1,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap_err();
+
+            assert_error_response(&response, "invalid peer port");
+        }
+    }
+
+    mod returning_the_client_ip {
+
+        use std::net::{IpAddr, Ipv4Addr};
+        use std::sync::Arc;
+
+        use super::{sample_announce_request, tracker_not_on_reverse_proxy};
+        use crate::servers::http::v1::handlers::announce::{handle_announce, AnnounceOptions};
+        use crate::servers::http::v1::services::peer_ip_resolver::ClientIpSources;
+
+        fn client_ip_sources(client_ip: IpAddr) -> ClientIpSources {
+            ClientIpSources {
+                client_ip_headers: vec![],
+                connection_info_ip: Some(client_ip),
+            }
+        }
+
+        #[tokio::test]
+        async fn it_should_return_the_client_ip_when_the_http_tracker_enables_it() {
+            let tracker = Arc::new(tracker_not_on_reverse_proxy());
+
+            let client_ip = IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1));
+
+            let announce_data = handle_announce(
+                &tracker,
+                &sample_announce_request(),
+                &client_ip_sources(client_ip),
+                None,
+                AnnounceOptions {
+                    return_client_ip: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(announce_data.external_ip, Some(client_ip));
+        }
+
+        #[tokio::test]
+        async fn it_should_not_return_the_client_ip_when_the_http_tracker_disables_it() {
+            let tracker = Arc::new(tracker_not_on_reverse_proxy());
+
+            let announce_data = handle_announce(
+                &tracker,
+                &sample_announce_request(),
+                &client_ip_sources(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1))),
+                None,
+                AnnounceOptions {
+                    return_client_ip: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(announce_data.external_ip, None);
+        }
+    }
 }
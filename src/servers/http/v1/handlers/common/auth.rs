@@ -27,6 +27,7 @@ impl From<Error> for responses::error::Error {
     fn from(err: Error) -> Self {
         responses::error::Error {
             failure_reason: format!("Authentication error: {err}"),
+            retry_in: None,
         }
     }
 }
@@ -35,6 +36,7 @@ impl From<auth::Error> for responses::error::Error {
     fn from(err: auth::Error) -> Self {
         responses::error::Error {
             failure_reason: format!("Authentication error: {err}"),
+            retry_in: None,
         }
     }
 }
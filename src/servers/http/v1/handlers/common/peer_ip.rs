@@ -11,6 +11,7 @@ impl From<PeerIpResolutionError> for responses::error::Error {
     fn from(err: PeerIpResolutionError) -> Self {
         responses::error::Error {
             failure_reason: format!("Error resolving peer IP: {err}"),
+            retry_in: None,
         }
     }
 }
@@ -14,6 +14,7 @@ impl From<Error> for responses::error::Error {
     fn from(err: Error) -> Self {
         responses::error::Error {
             failure_reason: format!("Tracker error: {err}"),
+            retry_in: None,
         }
     }
 }
@@ -9,9 +9,12 @@ use std::sync::Arc;
 
 use axum::extract::State;
 use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use torrust_tracker_configuration::HttpTracker;
 
 use crate::core::auth::Key;
-use crate::core::{ScrapeData, Tracker};
+use crate::core::statistics::{self, Protocol, RejectReason, RequestKind};
+use crate::core::{failure_messages, Tracker};
 use crate::servers::http::v1::extractors::authentication_key::Extract as ExtractKey;
 use crate::servers::http::v1::extractors::client_ip_sources::Extract as ExtractClientIpSources;
 use crate::servers::http::v1::extractors::scrape_request::ExtractRequest;
@@ -19,17 +22,44 @@ use crate::servers::http::v1::requests::scrape::Scrape;
 use crate::servers::http::v1::services::peer_ip_resolver::{self, ClientIpSources};
 use crate::servers::http::v1::{responses, services};
 
+/// Options of the `scrape` requests of an HTTP tracker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrapeOptions {
+    /// Maximum number of torrents in a request.
+    pub max_torrents: u32,
+    /// Whether the requests are handled when the tracker runs in private
+    /// mode.
+    pub in_private_mode: bool,
+}
+
+impl ScrapeOptions {
+    #[must_use]
+    pub fn new(config: &HttpTracker) -> Self {
+        Self {
+            max_torrents: config.max_scrape_torrents,
+            in_private_mode: config.scrape_in_private_mode,
+        }
+    }
+}
+
+impl Default for ScrapeOptions {
+    fn default() -> Self {
+        Self::new(&HttpTracker::default())
+    }
+}
+
 /// It handles the `scrape` request when the HTTP tracker is configured
 /// to run in `public` mode.
 #[allow(clippy::unused_async)]
 pub async fn handle_without_key(
     State(tracker): State<Arc<Tracker>>,
+    Extension(options): Extension<ScrapeOptions>,
     ExtractRequest(scrape_request): ExtractRequest,
     ExtractClientIpSources(client_ip_sources): ExtractClientIpSources,
 ) -> Response {
     tracing::debug!("http scrape request: {:#?}", &scrape_request);
 
-    handle(&tracker, &scrape_request, &client_ip_sources, None).await
+    handle(&tracker, &scrape_request, &client_ip_sources, None, options).await
 }
 
 /// It handles the `scrape` request when the HTTP tracker is configured
@@ -39,13 +69,14 @@ pub async fn handle_without_key(
 #[allow(clippy::unused_async)]
 pub async fn handle_with_key(
     State(tracker): State<Arc<Tracker>>,
+    Extension(options): Extension<ScrapeOptions>,
     ExtractRequest(scrape_request): ExtractRequest,
     ExtractClientIpSources(client_ip_sources): ExtractClientIpSources,
     ExtractKey(key): ExtractKey,
 ) -> Response {
     tracing::debug!("http scrape request: {:#?}", &scrape_request);
 
-    handle(&tracker, &scrape_request, &client_ip_sources, Some(key)).await
+    handle(&tracker, &scrape_request, &client_ip_sources, Some(key), options).await
 }
 
 async fn handle(
@@ -53,12 +84,12 @@ async fn handle(
     scrape_request: &Scrape,
     client_ip_sources: &ClientIpSources,
     maybe_key: Option<Key>,
+    options: ScrapeOptions,
 ) -> Response {
-    let scrape_data = match handle_scrape(tracker, scrape_request, client_ip_sources, maybe_key).await {
-        Ok(scrape_data) => scrape_data,
-        Err(error) => return error.into_response(),
-    };
-    build_response(scrape_data)
+    match handle_scrape(tracker, scrape_request, client_ip_sources, maybe_key, options).await {
+        Ok(bencoded) => bencoded.into_response(),
+        Err(error) => error.with_retry_in(tracker.get_retry_in()).into_response(),
+    }
 }
 
 /* code-review: authentication, authorization and peer IP resolution could be moved
@@ -72,11 +103,38 @@ async fn handle_scrape(
     scrape_request: &Scrape,
     client_ip_sources: &ClientIpSources,
     maybe_key: Option<Key>,
-) -> Result<ScrapeData, responses::error::Error> {
+    options: ScrapeOptions,
+) -> Result<responses::scrape::Bencoded, responses::error::Error> {
+    if tracker.is_private() && !options.in_private_mode {
+        let error = responses::error::Error {
+            failure_reason: "scrape requests are disabled in private mode".to_string(),
+            retry_in: None,
+        };
+        return Err(reject(tracker, RejectReason::Other, error, maybe_key.as_ref()).await);
+    }
+
+    if scrape_request.info_hashes.len() > usize::try_from(options.max_torrents).unwrap_or(usize::MAX) {
+        let error = responses::error::Error {
+            failure_reason: format!(
+                "too many infohashes: {}, the maximum is {}",
+                scrape_request.info_hashes.len(),
+                options.max_torrents
+            ),
+            retry_in: None,
+        };
+        return Err(reject(tracker, RejectReason::Other, error, maybe_key.as_ref()).await);
+    }
+
+    // Scrapes are not handled while the tracker is overloaded
+    if let Err(error) = tracker.check_overload() {
+        let reason = RejectReason::from(&error);
+        return Err(reject(tracker, reason, error.into(), maybe_key.as_ref()).await);
+    }
+
     // Authentication
     let return_real_scrape_data = if tracker.requires_authentication() {
-        match maybe_key {
-            Some(key) => match tracker.authenticate(&key).await {
+        match &maybe_key {
+            Some(key) => match tracker.authenticate(key).await {
                 Ok(()) => true,
                 Err(_error) => false,
             },
@@ -89,20 +147,47 @@ async fn handle_scrape(
     // Authorization for scrape requests is handled at the `Tracker` level
     // for each torrent.
 
-    let peer_ip = match peer_ip_resolver::invoke(tracker.is_behind_reverse_proxy(), client_ip_sources) {
+    let peer_ip = match peer_ip_resolver::invoke(tracker.get_network_config(), client_ip_sources) {
         Ok(peer_ip) => peer_ip,
-        Err(error) => return Err(responses::error::Error::from(error)),
+        Err(error) => {
+            let reason = RejectReason::from(&error);
+            return Err(reject(tracker, reason, error.into(), maybe_key.as_ref()).await);
+        }
     };
 
     if return_real_scrape_data {
-        Ok(services::scrape::invoke(tracker, &scrape_request.info_hashes, &peer_ip).await)
+        Ok(responses::scrape::Bencoded::from(
+            services::scrape::invoke_bencoded(tracker, &scrape_request.info_hashes, &peer_ip).await,
+        ))
     } else {
-        Ok(services::scrape::fake(tracker, &scrape_request.info_hashes, &peer_ip).await)
+        Ok(responses::scrape::Bencoded::from(
+            services::scrape::fake(tracker, &scrape_request.info_hashes, &peer_ip).await,
+        ))
     }
 }
 
-fn build_response(scrape_data: ScrapeData) -> Response {
-    responses::scrape::Bencoded::from(scrape_data).into_response()
+/// It records the rejection of the scrape and returns the error response,
+/// with the failure message configured for the rejection reason, if any.
+async fn reject(
+    tracker: &Tracker,
+    reason: RejectReason,
+    error: responses::error::Error,
+    maybe_key: Option<&Key>,
+) -> responses::error::Error {
+    tracker
+        .send_stats_event(statistics::Event::rejected(Protocol::Http, RequestKind::Scrape, reason))
+        .await;
+
+    let context = failure_messages::Context {
+        info_hash: None,
+        key: maybe_key,
+        retry_in: tracker.get_retry_in().or(error.retry_in),
+    };
+
+    responses::error::Error {
+        failure_reason: tracker.get_failure_message(reason, error.failure_reason, &context),
+        retry_in: error.retry_in,
+    }
 }
 
 #[cfg(test)]
@@ -110,6 +195,7 @@ mod tests {
     use std::net::IpAddr;
     use std::str::FromStr;
 
+    use torrust_tracker_configuration::ClientIpHeader;
     use torrust_tracker_primitives::info_hash::InfoHash;
     use torrust_tracker_test_helpers::configuration;
 
@@ -143,7 +229,7 @@ mod tests {
 
     fn sample_client_ip_sources() -> ClientIpSources {
         ClientIpSources {
-            right_most_x_forwarded_for: Some(IpAddr::from_str("203.0.113.195").unwrap()),
+            client_ip_headers: vec![(ClientIpHeader::XForwardedFor, "203.0.113.195".to_string())],
             connection_info_ip: Some(IpAddr::from_str("203.0.113.196").unwrap()),
         }
     }
@@ -159,9 +245,10 @@ mod tests {
         use std::str::FromStr;
         use std::sync::Arc;
 
-        use super::{private_tracker, sample_client_ip_sources, sample_scrape_request};
+        use super::{assert_error_response, private_tracker, sample_client_ip_sources, sample_scrape_request};
         use crate::core::{auth, ScrapeData};
-        use crate::servers::http::v1::handlers::scrape::handle_scrape;
+        use crate::servers::http::v1::handlers::scrape::{handle_scrape, ScrapeOptions};
+        use crate::servers::http::v1::responses::scrape::Bencoded;
 
         #[tokio::test]
         async fn it_should_return_zeroed_swarm_metadata_when_the_authentication_key_is_missing() {
@@ -170,13 +257,19 @@ mod tests {
             let scrape_request = sample_scrape_request();
             let maybe_key = None;
 
-            let scrape_data = handle_scrape(&tracker, &scrape_request, &sample_client_ip_sources(), maybe_key)
-                .await
-                .unwrap();
+            let response = handle_scrape(
+                &tracker,
+                &scrape_request,
+                &sample_client_ip_sources(),
+                maybe_key,
+                ScrapeOptions::default(),
+            )
+            .await
+            .unwrap();
 
-            let expected_scrape_data = ScrapeData::zeroed(&scrape_request.info_hashes);
+            let expected_response = Bencoded::from(ScrapeData::zeroed(&scrape_request.info_hashes));
 
-            assert_eq!(scrape_data, expected_scrape_data);
+            assert_eq!(response, expected_response);
         }
 
         #[tokio::test]
@@ -187,13 +280,84 @@ mod tests {
             let unregistered_key = auth::Key::from_str("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ").unwrap();
             let maybe_key = Some(unregistered_key);
 
-            let scrape_data = handle_scrape(&tracker, &scrape_request, &sample_client_ip_sources(), maybe_key)
+            let response = handle_scrape(
+                &tracker,
+                &scrape_request,
+                &sample_client_ip_sources(),
+                maybe_key,
+                ScrapeOptions::default(),
+            )
+            .await
+            .unwrap();
+
+            let expected_response = Bencoded::from(ScrapeData::zeroed(&scrape_request.info_hashes));
+
+            assert_eq!(response, expected_response);
+        }
+
+        #[tokio::test]
+        async fn it_should_fail_when_the_scrape_requests_are_disabled_in_private_mode() {
+            let tracker = Arc::new(private_tracker());
+
+            let options = ScrapeOptions {
+                in_private_mode: false,
+                ..ScrapeOptions::default()
+            };
+
+            let response = handle_scrape(&tracker, &sample_scrape_request(), &sample_client_ip_sources(), None, options)
                 .await
-                .unwrap();
+                .unwrap_err();
 
-            let expected_scrape_data = ScrapeData::zeroed(&scrape_request.info_hashes);
+            assert_error_response(&response, "scrape requests are disabled in private mode");
+        }
+    }
 
-            assert_eq!(scrape_data, expected_scrape_data);
+    mod with_a_maximum_number_of_torrents {
+        use std::sync::Arc;
+
+        use torrust_tracker_primitives::info_hash::InfoHash;
+
+        use super::{sample_client_ip_sources, tracker_not_on_reverse_proxy};
+        use crate::servers::http::v1::handlers::scrape::tests::assert_error_response;
+        use crate::servers::http::v1::handlers::scrape::{handle_scrape, ScrapeOptions};
+        use crate::servers::http::v1::requests::scrape::Scrape;
+
+        fn scrape_request_with(number_of_torrents: u8) -> Scrape {
+            Scrape {
+                info_hashes: (0..number_of_torrents).map(|i| InfoHash::from_bytes(&[i; 20])).collect(),
+            }
+        }
+
+        #[tokio::test]
+        async fn it_should_fail_when_the_request_includes_more_torrents_than_the_maximum() {
+            let tracker = Arc::new(tracker_not_on_reverse_proxy());
+
+            let options = ScrapeOptions {
+                max_torrents: 2,
+                ..ScrapeOptions::default()
+            };
+
+            let response = handle_scrape(&tracker, &scrape_request_with(3), &sample_client_ip_sources(), None, options)
+                .await
+                .unwrap_err();
+
+            assert_error_response(&response, "too many infohashes: 3, the maximum is 2");
+        }
+
+        #[tokio::test]
+        async fn it_should_scrape_all_the_torrents_in_the_request_up_to_the_maximum() {
+            let tracker = Arc::new(tracker_not_on_reverse_proxy());
+
+            let options = ScrapeOptions {
+                max_torrents: 2,
+                ..ScrapeOptions::default()
+            };
+
+            assert!(
+                handle_scrape(&tracker, &scrape_request_with(2), &sample_client_ip_sources(), None, options)
+                    .await
+                    .is_ok()
+            );
         }
     }
 
@@ -203,7 +367,8 @@ mod tests {
 
         use super::{sample_client_ip_sources, sample_scrape_request, whitelisted_tracker};
         use crate::core::ScrapeData;
-        use crate::servers::http::v1::handlers::scrape::handle_scrape;
+        use crate::servers::http::v1::handlers::scrape::{handle_scrape, ScrapeOptions};
+        use crate::servers::http::v1::responses::scrape::Bencoded;
 
         #[tokio::test]
         async fn it_should_return_zeroed_swarm_metadata_when_the_torrent_is_not_whitelisted() {
@@ -211,41 +376,51 @@ mod tests {
 
             let scrape_request = sample_scrape_request();
 
-            let scrape_data = handle_scrape(&tracker, &scrape_request, &sample_client_ip_sources(), None)
-                .await
-                .unwrap();
+            let response = handle_scrape(
+                &tracker,
+                &scrape_request,
+                &sample_client_ip_sources(),
+                None,
+                ScrapeOptions::default(),
+            )
+            .await
+            .unwrap();
 
-            let expected_scrape_data = ScrapeData::zeroed(&scrape_request.info_hashes);
+            let expected_response = Bencoded::from(ScrapeData::zeroed(&scrape_request.info_hashes));
 
-            assert_eq!(scrape_data, expected_scrape_data);
+            assert_eq!(response, expected_response);
         }
     }
 
     mod with_tracker_on_reverse_proxy {
+        use std::net::{IpAddr, Ipv4Addr};
         use std::sync::Arc;
 
         use super::{sample_scrape_request, tracker_on_reverse_proxy};
-        use crate::servers::http::v1::handlers::scrape::handle_scrape;
         use crate::servers::http::v1::handlers::scrape::tests::assert_error_response;
+        use crate::servers::http::v1::handlers::scrape::{handle_scrape, ScrapeOptions};
         use crate::servers::http::v1::services::peer_ip_resolver::ClientIpSources;
 
         #[tokio::test]
-        async fn it_should_fail_when_the_right_most_x_forwarded_for_header_ip_is_not_available() {
+        async fn it_should_fail_when_the_client_ip_header_is_not_available() {
             let tracker = Arc::new(tracker_on_reverse_proxy());
 
             let client_ip_sources = ClientIpSources {
-                right_most_x_forwarded_for: None,
-                connection_info_ip: None,
+                client_ip_headers: vec![],
+                connection_info_ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
             };
 
-            let response = handle_scrape(&tracker, &sample_scrape_request(), &client_ip_sources, None)
-                .await
-                .unwrap_err();
-
-            assert_error_response(
-                &response,
-                "Error resolving peer IP: missing or invalid the right most X-Forwarded-For IP",
-            );
+            let response = handle_scrape(
+                &tracker,
+                &sample_scrape_request(),
+                &client_ip_sources,
+                None,
+                ScrapeOptions::default(),
+            )
+            .await
+            .unwrap_err();
+
+            assert_error_response(&response, "Error resolving peer IP: missing or invalid client IP header");
         }
     }
 
@@ -253,8 +428,8 @@ mod tests {
         use std::sync::Arc;
 
         use super::{sample_scrape_request, tracker_not_on_reverse_proxy};
-        use crate::servers::http::v1::handlers::scrape::handle_scrape;
         use crate::servers::http::v1::handlers::scrape::tests::assert_error_response;
+        use crate::servers::http::v1::handlers::scrape::{handle_scrape, ScrapeOptions};
         use crate::servers::http::v1::services::peer_ip_resolver::ClientIpSources;
 
         #[tokio::test]
@@ -262,13 +437,19 @@ mod tests {
             let tracker = Arc::new(tracker_not_on_reverse_proxy());
 
             let client_ip_sources = ClientIpSources {
-                right_most_x_forwarded_for: None,
+                client_ip_headers: vec![],
                 connection_info_ip: None,
             };
 
-            let response = handle_scrape(&tracker, &sample_scrape_request(), &client_ip_sources, None)
-                .await
-                .unwrap_err();
+            let response = handle_scrape(
+                &tracker,
+                &sample_scrape_request(),
+                &client_ip_sources,
+                None,
+                ScrapeOptions::default(),
+            )
+            .await
+            .unwrap_err();
 
             assert_error_response(
                 &response,
@@ -2,6 +2,7 @@
 //!
 //! Data structures and logic for parsing the `announce` request.
 use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
 use std::panic::Location;
 use std::str::FromStr;
 
@@ -24,7 +25,12 @@ const UPLOADED: &str = "uploaded";
 const LEFT: &str = "left";
 const EVENT: &str = "event";
 const COMPACT: &str = "compact";
+const NO_PEER_ID: &str = "no_peer_id";
 const NUMWANT: &str = "numwant";
+const IPV4: &str = "ipv4";
+const IPV6: &str = "ipv6";
+const KEY: &str = "key";
+const TRACKER_ID: &str = "trackerid";
 
 /// The `Announce` request. Fields use the domain types after parsing the
 /// query params of the request.
@@ -45,7 +51,12 @@ const NUMWANT: &str = "numwant";
 ///     left: Some(NumberOfBytes::new(1)),
 ///     event: Some(Event::Started),
 ///     compact: Some(Compact::NotAccepted),
-///     numwant: Some(50)
+///     no_peer_id: false,
+///     numwant: Some(50),
+///     ipv4: None,
+///     ipv6: None,
+///     key: Some("1A2B3C4D".to_string()),
+///     tracker_id: None,
 /// };
 /// ```
 ///
@@ -85,9 +96,32 @@ pub struct Announce {
     /// Whether the response should be in compact mode or not.
     pub compact: Option<Compact>,
 
+    /// Whether the peer IDs should be omitted from the non-compact responses.
+    /// It's ignored in compact mode, where the peer IDs are never included.
+    pub no_peer_id: bool,
+
     /// Number of peers that the client would receive from the tracker. The
     /// value is permitted to be zero.
     pub numwant: Option<u32>,
+
+    /// The `IPv4` endpoint of a dual-stack peer announcing over `IPv6`. The
+    /// port defaults to the `port` param. See
+    /// [BEP 07. IPv6 Tracker Extension](https://www.bittorrent.org/beps/bep_0007.html).
+    pub ipv4: Option<SocketAddrV4>,
+
+    /// The `IPv6` endpoint of a dual-stack peer announcing over `IPv4`. The
+    /// port defaults to the `port` param.
+    pub ipv6: Option<SocketAddrV6>,
+
+    /// An identification of the client not shared with other peers. It
+    /// allows the tracker to recognize the client when its IP address
+    /// changes. It's not the authentication key of private trackers.
+    pub key: Option<String>,
+
+    /// The `tracker id` returned by the tracker in a previous announce
+    /// response, if any. See the [`tracker_ids`](crate::core::tracker_ids)
+    /// module.
+    pub tracker_id: Option<String>,
 }
 
 /// Errors that can occur when parsing the `Announce` request.
@@ -227,6 +261,7 @@ impl From<ParseQueryError> for responses::error::Error {
     fn from(err: ParseQueryError) -> Self {
         responses::error::Error {
             failure_reason: format!("Cannot parse query params: {err}"),
+            retry_in: None,
         }
     }
 }
@@ -235,6 +270,7 @@ impl From<ParseAnnounceQueryError> for responses::error::Error {
     fn from(err: ParseAnnounceQueryError) -> Self {
         responses::error::Error {
             failure_reason: format!("Cannot parse query params for announce request: {err}"),
+            retry_in: None,
         }
     }
 }
@@ -243,16 +279,25 @@ impl TryFrom<Query> for Announce {
     type Error = ParseAnnounceQueryError;
 
     fn try_from(query: Query) -> Result<Self, Self::Error> {
+        let info_hash = extract_info_hash(&query)?;
+        let peer_id = extract_peer_id(&query)?;
+        let port = extract_port(&query)?;
+
         Ok(Self {
-            info_hash: extract_info_hash(&query)?,
-            peer_id: extract_peer_id(&query)?,
-            port: extract_port(&query)?,
+            info_hash,
+            peer_id,
+            port,
             downloaded: extract_downloaded(&query)?,
             uploaded: extract_uploaded(&query)?,
             left: extract_left(&query)?,
             event: extract_event(&query)?,
             compact: extract_compact(&query)?,
+            no_peer_id: extract_no_peer_id(&query)?,
             numwant: extract_numwant(&query)?,
+            ipv4: extract_ipv4(&query, port)?,
+            ipv6: extract_ipv6(&query, port)?,
+            key: extract_key(&query),
+            tracker_id: extract_tracker_id(&query),
         })
     }
 }
@@ -359,6 +404,21 @@ fn extract_compact(query: &Query) -> Result<Option<Compact>, ParseAnnounceQueryE
     }
 }
 
+fn extract_no_peer_id(query: &Query) -> Result<bool, ParseAnnounceQueryError> {
+    match query.get_param(NO_PEER_ID) {
+        Some(raw_param) => match raw_param.as_str() {
+            "1" => Ok(true),
+            "0" => Ok(false),
+            _ => Err(ParseAnnounceQueryError::InvalidParam {
+                param_name: NO_PEER_ID.to_owned(),
+                param_value: raw_param.clone(),
+                location: Location::caller(),
+            }),
+        },
+        None => Ok(false),
+    }
+}
+
 fn extract_numwant(query: &Query) -> Result<Option<u32>, ParseAnnounceQueryError> {
     match query.get_param(NUMWANT) {
         Some(raw_param) => match u32::from_str(&raw_param) {
@@ -373,17 +433,87 @@ fn extract_numwant(query: &Query) -> Result<Option<u32>, ParseAnnounceQueryError
     }
 }
 
+/// The `ipv4` param is either an address or an address and a port, for
+/// example `126.0.0.1` or `126.0.0.1:6881`.
+fn extract_ipv4(query: &Query, port: u16) -> Result<Option<SocketAddrV4>, ParseAnnounceQueryError> {
+    match query.get_param(IPV4) {
+        Some(raw_param) => {
+            let value = percent_encoding::percent_decode_str(&raw_param).decode_utf8_lossy();
+
+            match SocketAddrV4::from_str(&value) {
+                Ok(socket_addr) => Ok(Some(socket_addr)),
+                Err(_) => match Ipv4Addr::from_str(&value) {
+                    Ok(ip) => Ok(Some(SocketAddrV4::new(ip, port))),
+                    Err(_) => Err(ParseAnnounceQueryError::InvalidParam {
+                        param_name: IPV4.to_owned(),
+                        param_value: raw_param.clone(),
+                        location: Location::caller(),
+                    }),
+                },
+            }
+        }
+        None => Ok(None),
+    }
+}
+
+/// The `ipv6` param is either an address or an address in brackets and a
+/// port, for example `2001:db8::1` or `[2001:db8::1]:6881`.
+fn extract_ipv6(query: &Query, port: u16) -> Result<Option<SocketAddrV6>, ParseAnnounceQueryError> {
+    match query.get_param(IPV6) {
+        Some(raw_param) => {
+            let value = percent_encoding::percent_decode_str(&raw_param).decode_utf8_lossy();
+
+            match SocketAddrV6::from_str(&value) {
+                Ok(socket_addr) => Ok(Some(socket_addr)),
+                Err(_) => match Ipv6Addr::from_str(&value) {
+                    Ok(ip) => Ok(Some(SocketAddrV6::new(ip, port, 0, 0))),
+                    Err(_) => Err(ParseAnnounceQueryError::InvalidParam {
+                        param_name: IPV6.to_owned(),
+                        param_value: raw_param.clone(),
+                        location: Location::caller(),
+                    }),
+                },
+            }
+        }
+        None => Ok(None),
+    }
+}
+
+fn extract_key(query: &Query) -> Option<String> {
+    extract_text_param(query, KEY)
+}
+
+fn extract_tracker_id(query: &Query) -> Option<String> {
+    extract_text_param(query, TRACKER_ID)
+}
+
+/// It returns the percent-decoded value of an optional text param. Empty
+/// values are ignored.
+fn extract_text_param(query: &Query, param_name: &str) -> Option<String> {
+    query
+        .get_param(param_name)
+        .map(|raw_param| {
+            percent_encoding::percent_decode_str(&raw_param)
+                .decode_utf8_lossy()
+                .into_owned()
+        })
+        .filter(|value| !value.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
 
     mod announce_request {
 
+        use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+
         use aquatic_udp_protocol::{NumberOfBytes, PeerId};
         use torrust_tracker_primitives::info_hash::InfoHash;
 
         use crate::servers::http::v1::query::Query;
         use crate::servers::http::v1::requests::announce::{
-            Announce, Compact, Event, COMPACT, DOWNLOADED, EVENT, INFO_HASH, LEFT, NUMWANT, PEER_ID, PORT, UPLOADED,
+            Announce, Compact, Event, COMPACT, DOWNLOADED, EVENT, INFO_HASH, IPV4, IPV6, KEY, LEFT, NO_PEER_ID, NUMWANT, PEER_ID,
+            PORT, TRACKER_ID, UPLOADED,
         };
 
         #[test]
@@ -410,7 +540,12 @@ mod tests {
                     left: None,
                     event: None,
                     compact: None,
+                    no_peer_id: false,
                     numwant: None,
+                    ipv4: None,
+                    ipv6: None,
+                    key: None,
+                    tracker_id: None,
                 }
             );
         }
@@ -426,7 +561,10 @@ mod tests {
                 (LEFT, "3"),
                 (EVENT, "started"),
                 (COMPACT, "0"),
+                (NO_PEER_ID, "1"),
                 (NUMWANT, "50"),
+                (KEY, "1A2B3C4D"),
+                (TRACKER_ID, "tracker-1"),
             ])
             .to_string();
 
@@ -445,16 +583,44 @@ mod tests {
                     left: Some(NumberOfBytes::new(3)),
                     event: Some(Event::Started),
                     compact: Some(Compact::NotAccepted),
+                    no_peer_id: true,
                     numwant: Some(50),
+                    ipv4: None,
+                    ipv6: None,
+                    key: Some("1A2B3C4D".to_string()),
+                    tracker_id: Some("tracker-1".to_string()),
                 }
             );
         }
 
+        #[test]
+        fn should_include_the_endpoints_of_a_dual_stack_peer() {
+            let raw_query = Query::from(vec![
+                (INFO_HASH, "%3B%24U%04%CF%5F%11%BB%DB%E1%20%1C%EAjk%F4Z%EE%1B%C0"),
+                (PEER_ID, "-qB00000000000000001"),
+                (PORT, "17548"),
+                (IPV4, "126.0.0.1"),
+                (IPV6, "%5B2001%3Adb8%3A%3A1%5D%3A6881"),
+            ])
+            .to_string();
+
+            let announce_request = Announce::try_from(raw_query.parse::<Query>().unwrap()).unwrap();
+
+            assert_eq!(
+                announce_request.ipv4,
+                Some(SocketAddrV4::new(Ipv4Addr::new(126, 0, 0, 1), 17548))
+            );
+            assert_eq!(
+                announce_request.ipv6,
+                Some(SocketAddrV6::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1), 6881, 0, 0))
+            );
+        }
+
         mod when_it_is_instantiated_from_the_url_query_params {
 
             use crate::servers::http::v1::query::Query;
             use crate::servers::http::v1::requests::announce::{
-                Announce, COMPACT, DOWNLOADED, EVENT, INFO_HASH, LEFT, NUMWANT, PEER_ID, PORT, UPLOADED,
+                Announce, COMPACT, DOWNLOADED, EVENT, INFO_HASH, LEFT, NO_PEER_ID, NUMWANT, PEER_ID, PORT, UPLOADED,
             };
 
             #[test]
@@ -574,6 +740,19 @@ mod tests {
                 assert!(Announce::try_from(raw_query.parse::<Query>().unwrap()).is_err());
             }
 
+            #[test]
+            fn it_should_fail_if_the_no_peer_id_param_is_invalid() {
+                let raw_query = Query::from(vec![
+                    (INFO_HASH, "%3B%24U%04%CF%5F%11%BB%DB%E1%20%1C%EAjk%F4Z%EE%1B%C0"),
+                    (PEER_ID, "-qB00000000000000001"),
+                    (PORT, "17548"),
+                    (NO_PEER_ID, "INVALID_NO_PEER_ID_VALUE"),
+                ])
+                .to_string();
+
+                assert!(Announce::try_from(raw_query.parse::<Query>().unwrap()).is_err());
+            }
+
             #[test]
             fn it_should_fail_if_the_numwant_param_is_invalid() {
                 let raw_query = Query::from(vec![
@@ -2,10 +2,11 @@
 //!
 //! Data structures and logic to build the `announce` response.
 use std::io::Write;
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
 use axum::http::StatusCode;
 use derive_more::{AsRef, Constructor, From};
+use torrust_tracker_contrib_bencode::inner::BCowConvert;
 use torrust_tracker_contrib_bencode::{ben_bytes, ben_int, ben_list, ben_map, BMutAccess, BencodeMut};
 use torrust_tracker_primitives::peer;
 
@@ -29,6 +30,7 @@ use crate::servers::http::v1::responses;
 /// - [BEP 03: The `BitTorrent` Protocol Specification](https://www.bittorrent.org/beps/bep_0003.html)
 /// - [BEP 23: Tracker Returns Compact Peer Lists](https://www.bittorrent.org/beps/bep_0023.html)
 /// - [BEP 07: IPv6 Tracker Extension](https://www.bittorrent.org/beps/bep_0007.html)
+/// - [BEP 24: Tracker Returns External IP](https://www.bittorrent.org/beps/bep_0024.html)
 
 #[derive(Debug, AsRef, PartialEq, Constructor)]
 pub struct Announce<E>
@@ -70,6 +72,20 @@ pub struct Normal {
     interval: i64,
     min_interval: i64,
     peers: Vec<NormalPeer>,
+    tracker_id: Option<String>,
+    external_ip: Option<IpAddr>,
+    warning_message: Option<String>,
+    no_peer_id: bool,
+}
+
+impl Normal {
+    /// It omits the `peer id` of the peers in the response, when the client
+    /// sets the `no_peer_id` param.
+    #[must_use]
+    pub fn with_no_peer_id(mut self, no_peer_id: bool) -> Self {
+        self.no_peer_id = no_peer_id;
+        self
+    }
 }
 
 impl From<AnnounceData> for Normal {
@@ -80,6 +96,10 @@ impl From<AnnounceData> for Normal {
             interval: data.policy.interval.into(),
             min_interval: data.policy.interval_min.into(),
             peers: data.peers.iter().map(AsRef::as_ref).copied().collect(),
+            tracker_id: data.tracker_id,
+            external_ip: data.external_ip,
+            warning_message: data.warning_message,
+            no_peer_id: false,
         }
     }
 }
@@ -90,17 +110,29 @@ impl Into<Vec<u8>> for Normal {
         let mut peers_list = ben_list!();
         let peers_list_mut = peers_list.list_mut().unwrap();
         for peer in &self.peers {
-            peers_list_mut.push(peer.into());
+            if self.no_peer_id {
+                peers_list_mut.push(ben_map! {
+                    "ip" => ben_bytes!(peer.ip.to_string()),
+                    "port" => ben_int!(i64::from(peer.port))
+                });
+            } else {
+                peers_list_mut.push(peer.into());
+            }
         }
 
-        (ben_map! {
+        let mut response = ben_map! {
             "complete" => ben_int!(self.complete),
             "incomplete" => ben_int!(self.incomplete),
             "interval" => ben_int!(self.interval),
             "min interval" => ben_int!(self.min_interval),
             "peers" => peers_list.clone()
-        })
-        .encode()
+        };
+
+        insert_tracker_id(&mut response, self.tracker_id);
+        insert_external_ip(&mut response, self.external_ip);
+        insert_warning_message(&mut response, self.warning_message);
+
+        response.encode()
     }
 }
 
@@ -112,11 +144,20 @@ pub struct Compact {
     min_interval: i64,
     peers: Vec<u8>,
     peers6: Vec<u8>,
+    tracker_id: Option<String>,
+    external_ip: Option<IpAddr>,
+    warning_message: Option<String>,
 }
 
 impl From<AnnounceData> for Compact {
     fn from(data: AnnounceData) -> Self {
-        let compact_peers: Vec<CompactPeer> = data.peers.iter().map(AsRef::as_ref).copied().collect();
+        // Dual-stack peers are returned in both lists
+        let compact_peers: Vec<CompactPeer> = data
+            .peers
+            .iter()
+            .flat_map(|peer| peer.ipv4_addr().into_iter().chain(peer.ipv6_addr()))
+            .map(CompactPeer::from)
+            .collect();
 
         let (peers, peers6): (Vec<CompactPeerData<Ipv4Addr>>, Vec<CompactPeerData<Ipv6Addr>>) =
             compact_peers.into_iter().collect();
@@ -131,6 +172,9 @@ impl From<AnnounceData> for Compact {
             min_interval: data.policy.interval_min.into(),
             peers: peers_encoded.0,
             peers6: peers_encoded_6.0,
+            tracker_id: data.tracker_id,
+            external_ip: data.external_ip,
+            warning_message: data.warning_message,
         }
     }
 }
@@ -138,15 +182,70 @@ impl From<AnnounceData> for Compact {
 #[allow(clippy::from_over_into)]
 impl Into<Vec<u8>> for Compact {
     fn into(self) -> Vec<u8> {
-        (ben_map! {
+        let mut response = ben_map! {
             "complete" => ben_int!(self.complete),
             "incomplete" => ben_int!(self.incomplete),
             "interval" => ben_int!(self.interval),
             "min interval" => ben_int!(self.min_interval),
             "peers" => ben_bytes!(self.peers),
             "peers6" => ben_bytes!(self.peers6)
-        })
-        .encode()
+        };
+
+        insert_tracker_id(&mut response, self.tracker_id);
+        insert_external_ip(&mut response, self.external_ip);
+        insert_warning_message(&mut response, self.warning_message);
+
+        response.encode()
+    }
+}
+
+/// It adds the optional `tracker id` field to the bencoded announce response.
+///
+/// # Panics
+///
+/// Will panic if the response is not a bencoded dictionary.
+fn insert_tracker_id(response: &mut BencodeMut<'_>, tracker_id: Option<String>) {
+    if let Some(tracker_id) = tracker_id {
+        response
+            .dict_mut()
+            .expect("announce response should be a bencoded dictionary")
+            .insert(BCowConvert::convert("tracker id"), ben_bytes!(tracker_id));
+    }
+}
+
+/// It adds the optional `external ip` field to the bencoded announce response.
+/// The IP address is encoded in network byte order: 4 bytes for `IPv4` and 16
+/// bytes for `IPv6`.
+///
+/// # Panics
+///
+/// Will panic if the response is not a bencoded dictionary.
+fn insert_external_ip(response: &mut BencodeMut<'_>, external_ip: Option<IpAddr>) {
+    if let Some(external_ip) = external_ip {
+        let octets = match external_ip {
+            IpAddr::V4(ip) => ip.octets().to_vec(),
+            IpAddr::V6(ip) => ip.octets().to_vec(),
+        };
+
+        response
+            .dict_mut()
+            .expect("announce response should be a bencoded dictionary")
+            .insert(BCowConvert::convert("external ip"), ben_bytes!(octets));
+    }
+}
+
+/// It adds the optional `warning message` field to the bencoded announce
+/// response.
+///
+/// # Panics
+///
+/// Will panic if the response is not a bencoded dictionary.
+fn insert_warning_message(response: &mut BencodeMut<'_>, warning_message: Option<String>) {
+    if let Some(warning_message) = warning_message {
+        response
+            .dict_mut()
+            .expect("announce response should be a bencoded dictionary")
+            .insert(BCowConvert::convert("warning message"), ben_bytes!(warning_message));
     }
 }
 
@@ -229,7 +328,13 @@ impl peer::Encoding for CompactPeer {}
 
 impl From<peer::Peer> for CompactPeer {
     fn from(peer: peer::Peer) -> Self {
-        match (peer.peer_addr.ip(), peer.peer_addr.port()) {
+        Self::from(peer.peer_addr)
+    }
+}
+
+impl From<SocketAddr> for CompactPeer {
+    fn from(peer_addr: SocketAddr) -> Self {
+        match (peer_addr.ip(), peer_addr.port()) {
             (IpAddr::V4(ip), port) => Self::V4(CompactPeerData { ip, port }),
             (IpAddr::V6(ip), port) => Self::V6(CompactPeerData { ip, port }),
         }
@@ -339,7 +444,7 @@ mod tests {
         let peers = vec![Arc::new(peer_ipv4), Arc::new(peer_ipv6)];
         let stats = SwarmMetadata::new(333, 333, 444);
 
-        AnnounceData::new(peers, stats, policy)
+        AnnounceData::new(peers, stats, policy, None, None, None)
     }
 
     #[test]
@@ -356,6 +461,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn non_compact_announce_response_should_not_include_the_peer_ids_when_the_client_does_not_want_them() {
+        let response: Announce<Normal> = Announce::new(Normal::from(setup_announce_data()).with_no_peer_id(true));
+        let bytes = response.body().expect("it should encode the response");
+
+        // cspell:disable-next-line
+        let expected_bytes = b"d8:completei333e10:incompletei444e8:intervali111e12:min intervali222e5:peersld2:ip15:105.105.105.1054:porti28784eed2:ip39:6969:6969:6969:6969:6969:6969:6969:69694:porti28784eeee";
+
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            String::from_utf8(expected_bytes.to_vec()).unwrap()
+        );
+    }
+
     #[test]
     fn compact_announce_response_can_be_bencoded() {
         let response: Announce<Compact> = setup_announce_data().into();
@@ -370,4 +489,92 @@ mod tests {
             String::from_utf8(expected_bytes.to_vec()).unwrap()
         );
     }
+
+    #[test]
+    fn compact_announce_response_should_include_dual_stack_peers_in_both_peer_lists() {
+        let dual_stack_peer = PeerBuilder::default()
+            .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0x69, 0x69, 0x69, 0x69)), 0x7070))
+            .with_alt_peer_addr(&SocketAddr::new(
+                IpAddr::V6(Ipv6Addr::new(0x6969, 0x6969, 0x6969, 0x6969, 0x6969, 0x6969, 0x6969, 0x6969)),
+                0x7070,
+            ))
+            .build();
+
+        let announce_data = AnnounceData {
+            peers: vec![Arc::new(dual_stack_peer)],
+            ..setup_announce_data()
+        };
+
+        let response: Announce<Compact> = announce_data.into();
+        let bytes = response.body().expect("it should encode the response");
+
+        let expected_bytes =
+            // cspell:disable-next-line
+            b"d8:completei333e10:incompletei444e8:intervali111e12:min intervali222e5:peers6:iiiipp6:peers618:iiiiiiiiiiiiiiiippe";
+
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            String::from_utf8(expected_bytes.to_vec()).unwrap()
+        );
+    }
+
+    #[test]
+    fn announce_responses_should_include_the_tracker_id_when_the_tracker_instance_has_one() {
+        let announce_data = AnnounceData {
+            tracker_id: Some("tracker-1".to_string()),
+            ..setup_announce_data()
+        };
+
+        let response: Announce<Compact> = announce_data.into();
+        let bytes = response.body().expect("it should encode the response");
+
+        let expected_bytes =
+            // cspell:disable-next-line
+            b"d8:completei333e10:incompletei444e8:intervali111e12:min intervali222e5:peers6:iiiipp6:peers618:iiiiiiiiiiiiiiiipp10:tracker id9:tracker-1e";
+
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            String::from_utf8(expected_bytes.to_vec()).unwrap()
+        );
+    }
+
+    #[test]
+    fn announce_responses_should_include_the_client_external_ip_when_the_tracker_returns_it() {
+        let announce_data = AnnounceData {
+            external_ip: Some(IpAddr::V4(Ipv4Addr::new(0x69, 0x69, 0x69, 0x69))),
+            ..setup_announce_data()
+        };
+
+        let response: Announce<Compact> = announce_data.into();
+        let bytes = response.body().expect("it should encode the response");
+
+        let expected_bytes =
+            // cspell:disable-next-line
+            b"d8:completei333e11:external ip4:iiii10:incompletei444e8:intervali111e12:min intervali222e5:peers6:iiiipp6:peers618:iiiiiiiiiiiiiiiippe";
+
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            String::from_utf8(expected_bytes.to_vec()).unwrap()
+        );
+    }
+
+    #[test]
+    fn announce_responses_should_include_the_warning_message_when_there_is_one() {
+        let announce_data = AnnounceData {
+            warning_message: Some("ratio low".to_string()),
+            ..setup_announce_data()
+        };
+
+        let response: Announce<Compact> = announce_data.into();
+        let bytes = response.body().expect("it should encode the response");
+
+        let expected_bytes =
+            // cspell:disable-next-line
+            b"d8:completei333e10:incompletei444e8:intervali111e12:min intervali222e5:peers6:iiiipp6:peers618:iiiiiiiiiiiiiiiipp15:warning message9:ratio lowe";
+
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            String::from_utf8(expected_bytes.to_vec()).unwrap()
+        );
+    }
 }
@@ -21,9 +21,20 @@ pub struct Error {
     /// Human readable string which explains why the request failed.
     #[serde(rename = "failure reason")]
     pub failure_reason: String,
+    /// Minutes the client should wait before retrying the request
+    /// ([BEP 31](https://www.bittorrent.org/beps/bep_0031.html)).
+    #[serde(rename = "retry in", skip_serializing_if = "Option::is_none")]
+    pub retry_in: Option<u32>,
 }
 
 impl Error {
+    /// It sets the minutes the client should wait before retrying the
+    /// request, if any.
+    #[must_use]
+    pub fn with_retry_in(self, retry_in: Option<u32>) -> Self {
+        Self { retry_in, ..self }
+    }
+
     /// Returns the bencoded representation of the `Error` struct.
     ///
     /// ```rust
@@ -31,6 +42,7 @@ impl Error {
     ///
     /// let err = Error {
     ///    failure_reason: "error message".to_owned(),
+    ///    retry_in: None,
     /// };
     ///
     /// // cspell:disable-next-line
@@ -62,8 +74,20 @@ mod tests {
     fn http_tracker_errors_can_be_bencoded() {
         let err = Error {
             failure_reason: "error message".to_owned(),
+            retry_in: None,
         };
 
         assert_eq!(err.write(), "d14:failure reason13:error messagee"); // cspell:disable-line
     }
+
+    #[test]
+    fn http_tracker_errors_should_include_when_the_client_should_retry_the_request() {
+        let err = Error {
+            failure_reason: "error message".to_owned(),
+            retry_in: Some(5),
+        };
+
+        assert_eq!(err.write(), "d14:failure reason13:error message8:retry ini5ee");
+        // cspell:disable-line
+    }
 }
@@ -1,11 +1,14 @@
 //! `Scrape` response for the HTTP tracker [`scrape`](crate::servers::http::v1::requests::scrape::Scrape) request.
 //!
 //! Data structures and logic to build the `scrape` response.
-use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use torrust_tracker_contrib_bencode::{ben_int, ben_map, BMutAccess};
+use torrust_tracker_contrib_bencode::{ben_int, ben_map};
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 
 use crate::core::ScrapeData;
 
@@ -42,43 +45,61 @@ use crate::core::ScrapeData;
 /// ```
 #[derive(Debug, PartialEq, Default)]
 pub struct Bencoded {
-    /// The scrape data to be bencoded.
-    scrape_data: ScrapeData,
+    /// The bencoded swarm metadata of each torrent, sorted by infohash as
+    /// required for bencoded dictionaries.
+    files: BTreeMap<[u8; 20], Arc<[u8]>>,
 }
 
 impl Bencoded {
     /// Returns the bencoded representation of the `Scrape` struct.
     ///
-    /// # Panics
-    ///
-    /// Will return an error if it can't access the bencode as a mutable `BDictAccess`.    
+    /// The swarm metadata of the torrents is already bencoded, so it can be
+    /// taken from the [`scrape_cache`](crate::core::scrape_cache).
     #[must_use]
     pub fn body(&self) -> Vec<u8> {
-        let mut scrape_list = ben_map!();
-
-        let scrape_list_mut = scrape_list.dict_mut().unwrap();
+        let mut body = b"d5:filesd".to_vec();
 
-        for (info_hash, value) in &self.scrape_data.files {
-            scrape_list_mut.insert(
-                Cow::from(info_hash.bytes().to_vec()),
-                ben_map! {
-                    "complete" => ben_int!(i64::from(value.complete)),
-                    "downloaded" => ben_int!(i64::from(value.downloaded)),
-                    "incomplete" => ben_int!(i64::from(value.incomplete))
-                },
-            );
+        for (info_hash, file) in &self.files {
+            body.extend_from_slice(b"20:");
+            body.extend_from_slice(info_hash);
+            body.extend_from_slice(file);
         }
 
-        (ben_map! {
-            "files" => scrape_list
-        })
-        .encode()
+        body.extend_from_slice(b"ee");
+
+        body
     }
 }
 
+/// It bencodes the swarm metadata of a torrent, the value of each entry in the
+/// `files` dictionary of the response.
+#[must_use]
+pub fn bencode_swarm_metadata(swarm_metadata: &SwarmMetadata) -> Vec<u8> {
+    (ben_map! {
+        "complete" => ben_int!(i64::from(swarm_metadata.complete)),
+        "downloaded" => ben_int!(i64::from(swarm_metadata.downloaded)),
+        "incomplete" => ben_int!(i64::from(swarm_metadata.incomplete))
+    })
+    .encode()
+}
+
 impl From<ScrapeData> for Bencoded {
     fn from(scrape_data: ScrapeData) -> Self {
-        Self { scrape_data }
+        Self {
+            files: scrape_data
+                .files
+                .iter()
+                .map(|(info_hash, swarm_metadata)| (info_hash.bytes(), bencode_swarm_metadata(swarm_metadata).into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<Vec<(InfoHash, Arc<[u8]>)>> for Bencoded {
+    fn from(files: Vec<(InfoHash, Arc<[u8]>)>) -> Self {
+        Self {
+            files: files.into_iter().map(|(info_hash, file)| (info_hash.bytes(), file)).collect(),
+        }
     }
 }
 
@@ -92,6 +113,8 @@ impl IntoResponse for Bencoded {
 mod tests {
 
     mod scrape_response {
+        use std::sync::Arc;
+
         use torrust_tracker_primitives::info_hash::InfoHash;
         use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 
@@ -118,17 +141,16 @@ mod tests {
 
             assert_eq!(
                 response,
-                Bencoded {
-                    scrape_data: sample_scrape_data()
-                }
+                Bencoded::from(vec![(
+                    InfoHash::from_bytes(&[0x69; 20]),
+                    Arc::from(b"d8:completei1e10:downloadedi2e10:incompletei3ee".as_slice())
+                )])
             );
         }
 
         #[test]
         fn should_be_bencoded() {
-            let response = Bencoded {
-                scrape_data: sample_scrape_data(),
-            };
+            let response = Bencoded::from(sample_scrape_data());
 
             let bytes = response.body();
 
@@ -140,5 +162,23 @@ mod tests {
                 String::from_utf8(expected_bytes.to_vec()).unwrap()
             );
         }
+
+        #[test]
+        fn should_sort_the_torrents_by_infohash_when_bencoded() {
+            let response = Bencoded::from(vec![
+                (InfoHash::from_bytes(&[0x6A; 20]), Arc::from(b"de".as_slice())),
+                (InfoHash::from_bytes(&[0x69; 20]), Arc::from(b"de".as_slice())),
+            ]);
+
+            let bytes = response.body();
+
+            // cspell:disable-next-line
+            let expected_bytes = b"d5:filesd20:iiiiiiiiiiiiiiiiiiiide20:jjjjjjjjjjjjjjjjjjjjdeee";
+
+            assert_eq!(
+                String::from_utf8(bytes).unwrap(),
+                String::from_utf8(expected_bytes.to_vec()).unwrap()
+            );
+        }
     }
 }
@@ -3,14 +3,19 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
+use axum::body::Body;
 use axum::error_handling::HandleErrorLayer;
+use axum::extract::{FromRequestParts, State};
 use axum::http::HeaderName;
-use axum::response::Response;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
 use axum::routing::get;
-use axum::{BoxError, Router};
+use axum::{BoxError, Extension, Router};
 use axum_client_ip::SecureClientIpSource;
 use hyper::{Request, StatusCode};
-use torrust_tracker_configuration::DEFAULT_TIMEOUT;
+use tokio::sync::Semaphore;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_configuration::{HttpTrackerRateLimit, DEFAULT_TIMEOUT};
 use tower::timeout::TimeoutLayer;
 use tower::ServiceBuilder;
 use tower_http::compression::CompressionLayer;
@@ -19,26 +24,73 @@ use tower_http::request_id::{MakeRequestUuid, SetRequestIdLayer};
 use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 use tracing::{instrument, Level, Span};
 
+use super::extractors::client_ip_sources::Extract as ExtractClientIpSources;
 use super::handlers::{announce, health_check, scrape};
-use crate::core::Tracker;
+use super::responses;
+use super::services::peer_ip_resolver;
+use crate::core::auth::Key;
+use crate::core::statistics::{self, Protocol, RejectReason, RequestKind};
+use crate::core::{failure_messages, Tracker};
+use crate::servers::access_log::{self, AccessLog};
+use crate::servers::http::rate_limiter::RateLimiter;
 use crate::servers::http::HTTP_TRACKER_LOG_TARGET;
+use crate::CurrentClock;
 
 /// It adds the routes to the router.
 ///
 /// > **NOTICE**: it's added a layer to get the client IP from the connection
 /// > info. The tracker could use the connection info to get the client IP.
+///
+/// When `max_concurrent_requests` is set, requests over the limit are rejected
+/// with an error response. The `announce` requests are handled with the
+/// `announce_options`, and the `scrape` requests are limited by the
+/// `scrape_options`. The `announce` and `scrape` requests over the
+/// `rate_limit` are rejected with an error response. All the requests are
+/// written to the `access_log`, if any.
 #[allow(clippy::needless_pass_by_value)]
-#[instrument(skip(tracker, server_socket_addr))]
-pub fn router(tracker: Arc<Tracker>, server_socket_addr: SocketAddr) -> Router {
-    Router::new()
+#[instrument(skip(
+    tracker,
+    server_socket_addr,
+    max_concurrent_requests,
+    announce_options,
+    scrape_options,
+    rate_limit,
+    access_log
+))]
+pub fn router(
+    tracker: Arc<Tracker>,
+    server_socket_addr: SocketAddr,
+    max_concurrent_requests: Option<u32>,
+    announce_options: announce::AnnounceOptions,
+    scrape_options: scrape::ScrapeOptions,
+    rate_limit: Option<HttpTrackerRateLimit>,
+    access_log: Option<Arc<AccessLog>>,
+) -> Router {
+    let router = Router::new()
         // Health check
         .route("/health_check", get(health_check::handler))
         // Announce request
         .route("/announce", get(announce::handle_without_key).with_state(tracker.clone()))
         .route("/announce/:key", get(announce::handle_with_key).with_state(tracker.clone()))
+        .route("/:key/announce", get(announce::handle_with_key).with_state(tracker.clone()))
         // Scrape request
         .route("/scrape", get(scrape::handle_without_key).with_state(tracker.clone()))
-        .route("/scrape/:key", get(scrape::handle_with_key).with_state(tracker))
+        .route("/scrape/:key", get(scrape::handle_with_key).with_state(tracker.clone()))
+        .route("/:key/scrape", get(scrape::handle_with_key).with_state(tracker.clone()))
+        .layer(Extension(announce_options))
+        .layer(Extension(scrape_options));
+
+    let router = match rate_limit {
+        Some(rate_limit) => router.layer(middleware::from_fn_with_state(
+            (tracker.clone(), Arc::new(RateLimiter::new(&rate_limit))),
+            limit_request_rate,
+        )),
+        None => router,
+    };
+
+    let router = router
+        // Count the requests for the overload control
+        .layer(middleware::from_fn_with_state(tracker.clone(), track_load))
         // Add extension to get the client IP from the connection info
         .layer(SecureClientIpSource::ConnectInfo.into_extension())
         .layer(CompressionLayer::new())
@@ -81,5 +133,148 @@ pub fn router(tracker: Arc<Tracker>, server_socket_addr: SocketAddr) -> Router {
                 // errors returned by `TimeoutLayer`
                 .layer(HandleErrorLayer::new(|_: BoxError| async { StatusCode::REQUEST_TIMEOUT }))
                 .layer(TimeoutLayer::new(DEFAULT_TIMEOUT)),
-        )
+        );
+
+    let router = match max_concurrent_requests {
+        Some(max) => {
+            let permits = Arc::new(Semaphore::new(usize::try_from(max).unwrap_or(Semaphore::MAX_PERMITS)));
+            router.layer(middleware::from_fn_with_state(permits, limit_concurrent_requests))
+        }
+        None => router,
+    };
+
+    match access_log {
+        Some(access_log) => router.layer(middleware::from_fn_with_state((tracker, access_log), log_access)),
+        None => router,
+    }
+}
+
+/// Middleware to write the requests to the access log. The client IP is
+/// resolved like the peer IP, so it's the IP of the client when the tracker
+/// runs behind a reverse proxy.
+async fn log_access(
+    State((tracker, access_log)): State<(Arc<Tracker>, Arc<AccessLog>)>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let (mut parts, body) = request.into_parts();
+
+    let client_ip = match ExtractClientIpSources::from_request_parts(&mut parts, &tracker).await {
+        Ok(ExtractClientIpSources(client_ip_sources)) => {
+            peer_ip_resolver::invoke(tracker.get_network_config(), &client_ip_sources)
+                .ok()
+                .or(client_ip_sources.connection_info_ip)
+        }
+        Err(_) => None,
+    };
+
+    access_log::log_request(&access_log, client_ip, Request::from_parts(parts, body), next).await
+}
+
+/// Middleware to reject the requests when all the permits for concurrent
+/// requests are in use.
+async fn limit_concurrent_requests(State(permits): State<Arc<Semaphore>>, request: Request<Body>, next: Next) -> Response {
+    let Ok(_permit) = permits.try_acquire_owned() else {
+        return responses::error::Error {
+            failure_reason: "too many concurrent requests, try again later".to_string(),
+            retry_in: Some(1),
+        }
+        .into_response();
+    };
+
+    next.run(request).await
+}
+
+/// Middleware to reject the `announce` and `scrape` requests exceeding the
+/// rate limits of the client IP or the authentication key.
+///
+/// Requests whose client IP can't be resolved are not limited here. They are
+/// rejected by the handlers.
+async fn limit_request_rate(
+    State((tracker, rate_limiter)): State<(Arc<Tracker>, Arc<RateLimiter>)>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some((request_kind, key)) = request_kind_and_key(request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let (mut parts, body) = request.into_parts();
+
+    let client_ip = match ExtractClientIpSources::from_request_parts(&mut parts, &tracker).await {
+        Ok(ExtractClientIpSources(client_ip_sources)) => {
+            peer_ip_resolver::invoke(tracker.get_network_config(), &client_ip_sources).ok()
+        }
+        Err(_) => None,
+    };
+
+    let Some(client_ip) = client_ip else {
+        return next.run(Request::from_parts(parts, body)).await;
+    };
+
+    // Only the known keys have their own bucket, so random keys can't fill
+    // the rate limiter. The unknown keys are rejected by the handlers.
+    let known_key = match &key {
+        Some(key) if tracker.has_auth_key(key).await => Some(key),
+        _ => None,
+    };
+
+    if !rate_limiter.try_acquire(&client_ip, known_key, CurrentClock::now()) {
+        tracker
+            .send_stats_event(statistics::Event::rejected(
+                Protocol::Http,
+                request_kind,
+                RejectReason::RateLimited,
+            ))
+            .await;
+
+        let retry_in = Some(rate_limiter.retry_in());
+
+        let context = failure_messages::Context {
+            info_hash: None,
+            key: key.as_ref(),
+            retry_in,
+        };
+
+        return responses::error::Error {
+            failure_reason: tracker.get_failure_message(
+                RejectReason::RateLimited,
+                "too many requests, try again later".to_string(),
+                &context,
+            ),
+            retry_in,
+        }
+        .into_response();
+    }
+
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// It returns the kind of the request and the authentication key in the
+/// path, if any, for the `announce` and `scrape` routes. Keys with an invalid
+/// format are ignored.
+fn request_kind_and_key(path: &str) -> Option<(RequestKind, Option<Key>)> {
+    let segments = path.split('/').filter(|segment| !segment.is_empty()).collect::<Vec<_>>();
+
+    let (kind, key) = match segments.as_slice() {
+        [kind] => (*kind, None),
+        [kind @ ("announce" | "scrape"), key] | [key, kind] => (*kind, Some(*key)),
+        _ => return None,
+    };
+
+    let request_kind = match kind {
+        "announce" => RequestKind::Announce,
+        "scrape" => RequestKind::Scrape,
+        _ => return None,
+    };
+
+    Some((request_kind, key.and_then(|key| key.parse::<Key>().ok())))
+}
+
+/// Middleware to count the requests, and the requests in process, for the
+/// overload control.
+async fn track_load(State(tracker): State<Arc<Tracker>>, request: Request<Body>, next: Next) -> Response {
+    let _request = tracker.track_request();
+
+    next.run(request).await
 }
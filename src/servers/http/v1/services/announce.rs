@@ -35,7 +35,7 @@ pub async fn invoke(
     let original_peer_ip = peer.peer_addr.ip();
 
     // The tracker could change the original peer ip
-    let announce_data = tracker.announce(&info_hash, peer, &original_peer_ip, peers_wanted);
+    let announce_data = tracker.announce(&info_hash, peer, &original_peer_ip, peers_wanted).await;
 
     match original_peer_ip {
         IpAddr::V4(_) => {
@@ -52,6 +52,7 @@ pub async fn invoke(
 #[cfg(test)]
 mod tests {
     use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+    use std::time::Duration;
 
     use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
     use torrust_tracker_primitives::info_hash::InfoHash;
@@ -91,6 +92,8 @@ mod tests {
             downloaded: NumberOfBytes::new(0),
             left: NumberOfBytes::new(0),
             event: AnnounceEvent::Started,
+            timeout_extension: Duration::ZERO,
+            alt_peer_addr: None,
         }
     }
 
@@ -125,6 +128,9 @@ mod tests {
                     incomplete: 0,
                 },
                 policy: tracker.get_announce_policy(),
+                tracker_id: None,
+                external_ip: None,
+                warning_message: None,
             };
 
             assert_eq!(announce_data, expected_announce_data);
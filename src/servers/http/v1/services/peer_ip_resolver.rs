@@ -12,25 +12,41 @@
 //!                     X-Forwarded-For: 126.0.0.1       X-Forwarded-For: 126.0.0.1,126.0.0.2
 //! ```
 //!
-//! This service returns two options for the peer IP:
+//! The sources for the peer IP are:
 //!
 //! ```text
-//! right_most_x_forwarded_for = 126.0.0.2
-//! connection_info_ip         = 126.0.0.3
+//! X-Forwarded-For    = 126.0.0.1,126.0.0.2
+//! connection_info_ip = 126.0.0.3
 //! ```
 //!
-//! Depending on the tracker configuration.
-use std::net::IpAddr;
+//! Depending on the tracker configuration:
+//!
+//! - Without reverse proxy, the peer IP is `connection_info_ip`.
+//! - On reverse proxy mode, the peer IP is taken from the first configured
+//!   client IP header present in the request, but only if the connection
+//!   comes from a trusted proxy. The IPs in the list headers are checked from
+//!   right to left, skipping the trusted proxies. With
+//!   `trusted_proxies = ["126.0.0.2", "126.0.0.3"]`, the peer IP is
+//!   `126.0.0.1`. With the deprecated `on_reverse_proxy` flag and no trusted
+//!   proxies, only the loopback addresses are trusted.
+//!
+//! Refer to the [`Network`] configuration for more information.
+use std::net::{IpAddr, SocketAddr};
 use std::panic::Location;
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use torrust_tracker_configuration::{ClientIpHeader, Network};
+
+use crate::core::statistics::RejectReason;
 
 /// This struct contains the sources from which the peer IP can be obtained.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct ClientIpSources {
-    /// The right most IP from the `X-Forwarded-For` HTTP header.
-    pub right_most_x_forwarded_for: Option<IpAddr>,
+    /// The values of the configured client IP headers present in the request,
+    /// in order of precedence.
+    pub client_ip_headers: Vec<(ClientIpHeader, String)>,
     /// The IP from the connection info.
     pub connection_info_ip: Option<IpAddr>,
 }
@@ -39,39 +55,45 @@ pub struct ClientIpSources {
 #[derive(Error, Debug)]
 pub enum PeerIpResolutionError {
     /// The peer IP cannot be obtained because the tracker is configured as a
-    /// reverse proxy but the `X-Forwarded-For` HTTP header is missing or
-    /// invalid.
-    #[error(
-        "missing or invalid the right most X-Forwarded-For IP (mandatory on reverse proxy tracker configuration) in {location}"
-    )]
-    MissingRightMostXForwardedForIp { location: &'static Location<'static> },
-    /// The peer IP cannot be obtained because the tracker is not configured as
-    /// a reverse proxy but the connection info was not provided to the Axum
-    /// framework via a route extension.
+    /// reverse proxy but none of the client IP headers is present or valid.
+    #[error("missing or invalid client IP header (mandatory on reverse proxy tracker configuration) in {location}")]
+    MissingForwardedClientIp { location: &'static Location<'static> },
+    /// The peer IP cannot be obtained because the connection info was not
+    /// provided to the Axum framework via a route extension.
     #[error("cannot get the client IP from the connection info in {location}")]
     MissingClientIp { location: &'static Location<'static> },
 }
 
+impl From<&PeerIpResolutionError> for RejectReason {
+    fn from(_error: &PeerIpResolutionError) -> Self {
+        RejectReason::UnresolvedPeerIp
+    }
+}
+
 /// Resolves the peer IP from the request.
 ///
 /// Given the sources from which the peer IP can be obtained, this function
-/// resolves the peer IP according to the tracker configuration.
+/// resolves the peer IP according to the tracker network configuration.
 ///
-/// With the tracker running on reverse proxy mode:
+/// With the tracker running behind a trusted reverse proxy:
 ///
 /// ```rust
 /// use std::net::IpAddr;
 /// use std::str::FromStr;
 ///
 /// use torrust_tracker::servers::http::v1::services::peer_ip_resolver::{invoke, ClientIpSources, PeerIpResolutionError};
+/// use torrust_tracker_configuration::{ClientIpHeader, Network};
 ///
-/// let on_reverse_proxy = true;
+/// let net = Network {
+///     trusted_proxies: vec!["10.0.0.0/8".parse().unwrap()],
+///     ..Default::default()
+/// };
 ///
 /// let ip = invoke(
-///     on_reverse_proxy,
+///     &net,
 ///     &ClientIpSources {
-///         right_most_x_forwarded_for: Some(IpAddr::from_str("203.0.113.195").unwrap()),
-///         connection_info_ip: None,
+///         client_ip_headers: vec![(ClientIpHeader::XForwardedFor, "203.0.113.195".to_string())],
+///         connection_info_ip: Some(IpAddr::from_str("10.0.0.1").unwrap()),
 ///     },
 /// )
 /// .unwrap();
@@ -86,13 +108,12 @@ pub enum PeerIpResolutionError {
 /// use std::str::FromStr;
 ///
 /// use torrust_tracker::servers::http::v1::services::peer_ip_resolver::{invoke, ClientIpSources, PeerIpResolutionError};
-///
-/// let on_reverse_proxy = false;
+/// use torrust_tracker_configuration::Network;
 ///
 /// let ip = invoke(
-///     on_reverse_proxy,
+///     &Network::default(),
 ///     &ClientIpSources {
-///         right_most_x_forwarded_for: None,
+///         client_ip_headers: vec![],
 ///         connection_info_ip: Some(IpAddr::from_str("203.0.113.195").unwrap()),
 ///     },
 /// )
@@ -105,14 +126,21 @@ pub enum PeerIpResolutionError {
 ///
 /// Will return an error if the peer IP cannot be obtained according to the configuration.
 /// For example, if the IP is extracted from an HTTP header which is missing in the request.
-pub fn invoke(on_reverse_proxy: bool, client_ip_sources: &ClientIpSources) -> Result<IpAddr, PeerIpResolutionError> {
-    if on_reverse_proxy {
-        resolve_peer_ip_on_reverse_proxy(client_ip_sources)
+pub fn invoke(net: &Network, client_ip_sources: &ClientIpSources) -> Result<IpAddr, PeerIpResolutionError> {
+    if net.is_behind_reverse_proxy() && is_from_trusted_proxy(net, client_ip_sources) {
+        resolve_peer_ip_on_reverse_proxy(net, client_ip_sources)
     } else {
         resolve_peer_ip_without_reverse_proxy(client_ip_sources)
     }
 }
 
+/// The headers are never trusted when the connection info is not available.
+fn is_from_trusted_proxy(net: &Network, client_ip_sources: &ClientIpSources) -> bool {
+    client_ip_sources
+        .connection_info_ip
+        .is_some_and(|proxy_ip| net.is_trusted_proxy(&proxy_ip))
+}
+
 fn resolve_peer_ip_without_reverse_proxy(remote_client_ip: &ClientIpSources) -> Result<IpAddr, PeerIpResolutionError> {
     if let Some(ip) = remote_client_ip.connection_info_ip {
         Ok(ip)
@@ -123,96 +151,270 @@ fn resolve_peer_ip_without_reverse_proxy(remote_client_ip: &ClientIpSources) ->
     }
 }
 
-fn resolve_peer_ip_on_reverse_proxy(remote_client_ip: &ClientIpSources) -> Result<IpAddr, PeerIpResolutionError> {
-    if let Some(ip) = remote_client_ip.right_most_x_forwarded_for {
-        Ok(ip)
-    } else {
-        Err(PeerIpResolutionError::MissingRightMostXForwardedForIp {
+fn resolve_peer_ip_on_reverse_proxy(net: &Network, remote_client_ip: &ClientIpSources) -> Result<IpAddr, PeerIpResolutionError> {
+    remote_client_ip
+        .client_ip_headers
+        .iter()
+        .find_map(|(header, value)| client_ip_from_header(net, header, value))
+        .ok_or_else(|| PeerIpResolutionError::MissingForwardedClientIp {
             location: Location::caller(),
         })
-    }
+}
+
+/// It returns the right-most IP in the header that is not a trusted proxy.
+/// If all of them are trusted, the left-most one.
+///
+/// Any invalid IP in the header makes the whole header invalid.
+fn client_ip_from_header(net: &Network, header: &ClientIpHeader, value: &str) -> Option<IpAddr> {
+    let ips = match header {
+        ClientIpHeader::Forwarded => value
+            .split(',')
+            .map(forwarded_for_param)
+            .map(|node| node.and_then(parse_node))
+            .collect::<Option<Vec<IpAddr>>>()?,
+        ClientIpHeader::XForwardedFor | ClientIpHeader::XRealIp | ClientIpHeader::Custom(_) => {
+            value.split(',').map(parse_node).collect::<Option<Vec<IpAddr>>>()?
+        }
+    };
+
+    ips.iter()
+        .rev()
+        .find(|ip| !net.is_trusted_proxy(ip))
+        .or_else(|| ips.first())
+        .copied()
+}
+
+/// It returns the value of the `for` parameter of an element in the
+/// `Forwarded` header, for example: `for=192.0.2.60;proto=http`.
+fn forwarded_for_param(element: &str) -> Option<&str> {
+    element.split(';').find_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        name.trim().eq_ignore_ascii_case("for").then_some(value.trim())
+    })
+}
+
+/// It parses an IP address, optionally quoted, in brackets or with a port:
+/// `192.0.2.60`, `"[2001:db8:cafe::17]:4711"`, `192.0.2.60:8080`.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+
+    IpAddr::from_str(node)
+        .ok()
+        .or_else(|| SocketAddr::from_str(node).ok().map(|socket_addr| socket_addr.ip()))
+        .or_else(|| {
+            node.strip_prefix('[')
+                .and_then(|node| node.strip_suffix(']'))
+                .and_then(|ip| IpAddr::from_str(ip).ok())
+        })
 }
 
 #[cfg(test)]
 mod tests {
-    use super::invoke;
+    use std::net::IpAddr;
+    use std::str::FromStr;
+
+    use torrust_tracker_configuration::{ClientIpHeader, Network};
+
+    use super::{invoke, ClientIpSources};
+
+    fn ip(ip: &str) -> IpAddr {
+        IpAddr::from_str(ip).unwrap()
+    }
+
+    fn sources(client_ip_headers: &[(ClientIpHeader, &str)], connection_info_ip: Option<&str>) -> ClientIpSources {
+        ClientIpSources {
+            client_ip_headers: client_ip_headers
+                .iter()
+                .map(|(header, value)| (header.clone(), (*value).to_string()))
+                .collect(),
+            connection_info_ip: connection_info_ip.map(ip),
+        }
+    }
 
     mod working_without_reverse_proxy {
-        use std::net::IpAddr;
-        use std::str::FromStr;
+        use torrust_tracker_configuration::{ClientIpHeader, Network};
 
-        use super::invoke;
-        use crate::servers::http::v1::services::peer_ip_resolver::{ClientIpSources, PeerIpResolutionError};
+        use super::{invoke, ip, sources};
+        use crate::servers::http::v1::services::peer_ip_resolver::PeerIpResolutionError;
 
         #[test]
         fn it_should_get_the_peer_ip_from_the_connection_info() {
-            let on_reverse_proxy = false;
-
-            let ip = invoke(
-                on_reverse_proxy,
-                &ClientIpSources {
-                    right_most_x_forwarded_for: None,
-                    connection_info_ip: Some(IpAddr::from_str("203.0.113.195").unwrap()),
-                },
+            let peer_ip = invoke(
+                &Network::default(),
+                &sources(&[(ClientIpHeader::XForwardedFor, "126.0.0.1")], Some("203.0.113.195")),
             )
             .unwrap();
 
-            assert_eq!(ip, IpAddr::from_str("203.0.113.195").unwrap());
+            assert_eq!(peer_ip, ip("203.0.113.195"));
         }
 
         #[test]
         fn it_should_return_an_error_if_it_cannot_get_the_peer_ip_from_the_connection_info() {
-            let on_reverse_proxy = false;
-
-            let error = invoke(
-                on_reverse_proxy,
-                &ClientIpSources {
-                    right_most_x_forwarded_for: None,
-                    connection_info_ip: None,
-                },
-            )
-            .unwrap_err();
+            let error = invoke(&Network::default(), &sources(&[], None)).unwrap_err();
 
             assert!(matches!(error, PeerIpResolutionError::MissingClientIp { .. }));
         }
+
+        #[test]
+        fn it_should_ignore_the_client_ip_headers() {
+            let peer_ip = invoke(
+                &Network::default(),
+                &sources(&[(ClientIpHeader::XRealIp, "126.0.0.1")], Some("126.0.0.3")),
+            );
+
+            assert_eq!(peer_ip.unwrap(), ip("126.0.0.3"));
+        }
     }
 
     mod working_on_reverse_proxy {
-        use std::net::IpAddr;
-        use std::str::FromStr;
+        use torrust_tracker_configuration::{ClientIpHeader, Network};
 
-        use crate::servers::http::v1::services::peer_ip_resolver::{invoke, ClientIpSources, PeerIpResolutionError};
+        use super::{invoke, ip, sources};
+        use crate::servers::http::v1::services::peer_ip_resolver::PeerIpResolutionError;
+
+        fn on_reverse_proxy() -> Network {
+            Network {
+                on_reverse_proxy: true,
+                ..Default::default()
+            }
+        }
 
         #[test]
         fn it_should_get_the_peer_ip_from_the_right_most_ip_in_the_x_forwarded_for_header() {
-            let on_reverse_proxy = true;
-
-            let ip = invoke(
-                on_reverse_proxy,
-                &ClientIpSources {
-                    right_most_x_forwarded_for: Some(IpAddr::from_str("203.0.113.195").unwrap()),
-                    connection_info_ip: None,
-                },
+            let peer_ip = invoke(
+                &on_reverse_proxy(),
+                &sources(
+                    &[(ClientIpHeader::XForwardedFor, "126.0.0.1, 203.0.113.195")],
+                    Some("127.0.0.1"),
+                ),
             )
             .unwrap();
 
-            assert_eq!(ip, IpAddr::from_str("203.0.113.195").unwrap());
+            assert_eq!(peer_ip, ip("203.0.113.195"));
         }
 
         #[test]
         fn it_should_return_an_error_if_it_cannot_get_the_right_most_ip_from_the_x_forwarded_for_header() {
-            let on_reverse_proxy = true;
+            let error = invoke(&on_reverse_proxy(), &sources(&[], Some("127.0.0.1"))).unwrap_err();
+
+            assert!(matches!(error, PeerIpResolutionError::MissingForwardedClientIp { .. }));
+        }
 
+        #[test]
+        fn it_should_return_an_error_if_the_header_contains_an_invalid_ip() {
             let error = invoke(
-                on_reverse_proxy,
-                &ClientIpSources {
-                    right_most_x_forwarded_for: None,
-                    connection_info_ip: None,
-                },
+                &on_reverse_proxy(),
+                &sources(&[(ClientIpHeader::XForwardedFor, "INVALID IP")], Some("127.0.0.1")),
             )
             .unwrap_err();
 
-            assert!(matches!(error, PeerIpResolutionError::MissingRightMostXForwardedForIp { .. }));
+            assert!(matches!(error, PeerIpResolutionError::MissingForwardedClientIp { .. }));
+        }
+
+        #[test]
+        fn it_should_use_the_first_valid_header_in_order_of_precedence() {
+            let peer_ip = invoke(
+                &on_reverse_proxy(),
+                &sources(
+                    &[
+                        (ClientIpHeader::Custom("CF-Connecting-IP".to_string()), "INVALID IP"),
+                        (ClientIpHeader::XRealIp, "203.0.113.195"),
+                        (ClientIpHeader::XForwardedFor, "126.0.0.1"),
+                    ],
+                    Some("127.0.0.1"),
+                ),
+            )
+            .unwrap();
+
+            assert_eq!(peer_ip, ip("203.0.113.195"));
+        }
+
+        #[test]
+        fn it_should_get_the_peer_ip_from_the_for_parameter_of_the_forwarded_header() {
+            let peer_ip = invoke(
+                &on_reverse_proxy(),
+                &sources(
+                    &[(
+                        ClientIpHeader::Forwarded,
+                        r#"for=192.0.2.60;proto=http;by=203.0.113.43, For="[2001:db8:cafe::17]:4711""#,
+                    )],
+                    Some("127.0.0.1"),
+                ),
+            )
+            .unwrap();
+
+            assert_eq!(peer_ip, ip("2001:db8:cafe::17"));
+        }
+
+        #[test]
+        fn it_should_ignore_the_headers_spoofed_by_a_client_that_is_not_a_loopback_proxy() {
+            let peer_ip = invoke(
+                &on_reverse_proxy(),
+                &sources(&[(ClientIpHeader::XForwardedFor, "126.0.0.1")], Some("203.0.113.195")),
+            )
+            .unwrap();
+
+            assert_eq!(peer_ip, ip("203.0.113.195"));
+        }
+    }
+
+    mod working_behind_trusted_proxies {
+        use std::str::FromStr;
+
+        use torrust_tracker_configuration::cidr::Cidr;
+        use torrust_tracker_configuration::{ClientIpHeader, Network};
+
+        use super::{invoke, ip, sources};
+        use crate::servers::http::v1::services::peer_ip_resolver::PeerIpResolutionError;
+
+        fn behind_trusted_proxies() -> Network {
+            Network {
+                trusted_proxies: vec![Cidr::from_str("126.0.0.2").unwrap(), Cidr::from_str("126.0.0.3").unwrap()],
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn it_should_get_the_peer_ip_from_the_headers_when_the_connection_comes_from_a_trusted_proxy() {
+            let peer_ip = invoke(
+                &behind_trusted_proxies(),
+                &sources(&[(ClientIpHeader::XForwardedFor, "126.0.0.1")], Some("126.0.0.3")),
+            )
+            .unwrap();
+
+            assert_eq!(peer_ip, ip("126.0.0.1"));
+        }
+
+        #[test]
+        fn it_should_ignore_the_headers_when_the_connection_does_not_come_from_a_trusted_proxy() {
+            let peer_ip = invoke(
+                &behind_trusted_proxies(),
+                &sources(&[(ClientIpHeader::XForwardedFor, "1.1.1.1")], Some("203.0.113.195")),
+            )
+            .unwrap();
+
+            assert_eq!(peer_ip, ip("203.0.113.195"));
+        }
+
+        #[test]
+        fn it_should_skip_the_trusted_proxies_in_the_x_forwarded_for_header() {
+            let peer_ip = invoke(
+                &behind_trusted_proxies(),
+                &sources(
+                    &[(ClientIpHeader::XForwardedFor, "1.1.1.1, 126.0.0.1, 126.0.0.2")],
+                    Some("126.0.0.3"),
+                ),
+            )
+            .unwrap();
+
+            assert_eq!(peer_ip, ip("126.0.0.1"));
+        }
+
+        #[test]
+        fn it_should_return_an_error_if_the_trusted_proxy_does_not_send_the_client_ip() {
+            let error = invoke(&behind_trusted_proxies(), &sources(&[], Some("126.0.0.3"))).unwrap_err();
+
+            assert!(matches!(error, PeerIpResolutionError::MissingForwardedClientIp { .. }));
         }
     }
 }
@@ -14,6 +14,7 @@ use std::sync::Arc;
 use torrust_tracker_primitives::info_hash::InfoHash;
 
 use crate::core::{statistics, ScrapeData, Tracker};
+use crate::servers::http::v1::responses::scrape::bencode_swarm_metadata;
 
 /// The HTTP tracker `scrape` service.
 ///
@@ -33,6 +34,23 @@ pub async fn invoke(tracker: &Arc<Tracker>, info_hashes: &Vec<InfoHash>, origina
     scrape_data
 }
 
+/// The HTTP tracker `scrape` service, returning the bencoded swarm metadata
+/// of each torrent.
+///
+/// The bencoded data is taken from the [`scrape_cache`](crate::core::scrape_cache)
+/// when possible. The service sends the same statistics events as [`invoke`].
+pub async fn invoke_bencoded(
+    tracker: &Arc<Tracker>,
+    info_hashes: &[InfoHash],
+    original_peer_ip: &IpAddr,
+) -> Vec<(InfoHash, Arc<[u8]>)> {
+    let files = tracker.scrape_serialized(info_hashes, bencode_swarm_metadata).await;
+
+    send_scrape_event(original_peer_ip, tracker).await;
+
+    files
+}
+
 /// The HTTP tracker fake `scrape` service. It returns zeroed stats.
 ///
 /// When the peer is not authenticated and the tracker is running in `private` mode,
@@ -60,6 +78,7 @@ async fn send_scrape_event(original_peer_ip: &IpAddr, tracker: &Arc<Tracker>) {
 mod tests {
 
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::time::Duration;
 
     use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
     use torrust_tracker_primitives::info_hash::InfoHash;
@@ -90,6 +109,8 @@ mod tests {
             downloaded: NumberOfBytes::new(0),
             left: NumberOfBytes::new(0),
             event: AnnounceEvent::Started,
+            timeout_extension: Duration::ZERO,
+            alt_peer_addr: None,
         }
     }
 
@@ -104,10 +125,10 @@ mod tests {
         use torrust_tracker_test_helpers::configuration;
 
         use crate::core::{statistics, PeersWanted, ScrapeData, Tracker};
-        use crate::servers::http::v1::services::scrape::invoke;
         use crate::servers::http::v1::services::scrape::tests::{
             public_tracker, sample_info_hash, sample_info_hashes, sample_peer,
         };
+        use crate::servers::http::v1::services::scrape::{invoke, invoke_bencoded};
 
         #[tokio::test]
         async fn it_should_return_the_scrape_data_for_a_torrent() {
@@ -119,7 +140,9 @@ mod tests {
             // Announce a new peer to force scrape data to contain not zeroed data
             let mut peer = sample_peer();
             let original_peer_ip = peer.ip();
-            tracker.announce(&info_hash, &mut peer, &original_peer_ip, &PeersWanted::All);
+            tracker
+                .announce(&info_hash, &mut peer, &original_peer_ip, &PeersWanted::All)
+                .await;
 
             let scrape_data = invoke(&tracker, &info_hashes, &original_peer_ip).await;
 
@@ -136,6 +159,26 @@ mod tests {
             assert_eq!(scrape_data, expected_scrape_data);
         }
 
+        #[tokio::test]
+        async fn it_should_return_the_bencoded_scrape_data_for_a_torrent() {
+            let tracker = Arc::new(public_tracker());
+
+            let info_hash = sample_info_hash();
+
+            // Announce a new peer to force scrape data to contain not zeroed data
+            let mut peer = sample_peer();
+            let original_peer_ip = peer.ip();
+            tracker
+                .announce(&info_hash, &mut peer, &original_peer_ip, &PeersWanted::All)
+                .await;
+
+            let files = invoke_bencoded(&tracker, &[info_hash], &original_peer_ip).await;
+
+            assert_eq!(files.len(), 1);
+            assert_eq!(files[0].0, info_hash);
+            assert_eq!(&*files[0].1, b"d8:completei1e10:downloadedi0e10:incompletei0ee");
+        }
+
         #[tokio::test]
         async fn it_should_send_the_tcp_4_scrape_event_when_the_peer_uses_ipv4() {
             let mut stats_event_sender_mock = statistics::MockEventSender::new();
@@ -210,7 +253,9 @@ mod tests {
             // Announce a new peer to force scrape data to contain not zeroed data
             let mut peer = sample_peer();
             let original_peer_ip = peer.ip();
-            tracker.announce(&info_hash, &mut peer, &original_peer_ip, &PeersWanted::All);
+            tracker
+                .announce(&info_hash, &mut peer, &original_peer_ip, &PeersWanted::All)
+                .await;
 
             let scrape_data = fake(&tracker, &info_hashes, &original_peer_ip).await;
 
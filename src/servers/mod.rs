@@ -1,4 +1,5 @@
 //! Servers. Services that can be started and stopped.
+pub mod access_log;
 pub mod apis;
 pub mod custom_axum_server;
 pub mod health_check_api;
@@ -6,4 +7,7 @@ pub mod http;
 pub mod logging;
 pub mod registar;
 pub mod signals;
+pub mod socket_activation;
+pub mod token_buckets;
 pub mod udp;
+pub mod websocket;
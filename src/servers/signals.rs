@@ -13,6 +13,10 @@ pub enum Halted {
     Normal,
 }
 
+/// Time the servers without a configurable shutdown timeout wait for the
+/// requests in process when they are shut down.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Resolves on `ctrl_c` or the `terminate` signal.
 ///
 /// # Panics
@@ -69,16 +73,25 @@ pub async fn shutdown_signal_with_message(rx_halt: tokio::sync::oneshot::Receive
     tracing::info!("{message}");
 }
 
+/// Resolves when the `rx_halt` or the `global_shutdown_signal()` resolves,
+/// after shutting down the server gracefully: it stops accepting new
+/// connections, and it waits up to the `timeout` for the requests in process
+/// to be handled. The connections still open after the `timeout` are closed.
 #[instrument(skip(handle, rx_halt, message))]
-pub async fn graceful_shutdown(handle: axum_server::Handle, rx_halt: tokio::sync::oneshot::Receiver<Halted>, message: String) {
+pub async fn graceful_shutdown(
+    handle: axum_server::Handle,
+    rx_halt: tokio::sync::oneshot::Receiver<Halted>,
+    message: String,
+    timeout: Duration,
+) {
     shutdown_signal_with_message(rx_halt, message).await;
 
     tracing::debug!("Sending graceful shutdown signal");
-    handle.graceful_shutdown(Some(Duration::from_secs(90)));
+    handle.graceful_shutdown(Some(timeout));
 
-    println!("!! shuting down in 90 seconds !!");
+    tracing::info!("shutting down in {} seconds at most", timeout.as_secs());
 
-    loop {
+    while handle.connection_count() > 0 {
         sleep(Duration::from_secs(1)).await;
 
         tracing::info!("remaining alive connections: {}", handle.connection_count());
@@ -0,0 +1,161 @@
+//! Support for inherited listening sockets (systemd socket activation).
+//!
+//! The tracker can take its listening sockets from the process that started
+//! it, instead of binding them itself. That allows upgrading the tracker
+//! binary without dropping the UDP sockets and the HTTP listeners: the
+//! sockets are owned by the service manager and handed to each new tracker
+//! process. Packets and connections arriving during the restart are queued
+//! in the kernel until the new process starts serving them.
+//!
+//! The sockets are passed using the
+//! [`sd_listen_fds`](https://www.freedesktop.org/software/systemd/man/latest/sd_listen_fds.html)
+//! protocol:
+//!
+//! - `LISTEN_PID`: the PID of the process the sockets are passed to.
+//! - `LISTEN_FDS`: the number of sockets passed, starting at file descriptor
+//!   `3`.
+//!
+//! The inherited sockets are matched with the servers by their bound address.
+//! For example, with this systemd socket unit:
+//!
+//! ```text
+//! [Socket]
+//! ListenDatagram=0.0.0.0:6969
+//! ListenStream=0.0.0.0:7070
+//! ```
+//!
+//! the UDP tracker bound to `0.0.0.0:6969` and the HTTP tracker bound to
+//! `0.0.0.0:7070` use the inherited sockets. Servers without a matching
+//! inherited socket bind their own socket as usual.
+//!
+//! > **NOTICE**: servers bound to port `0` (random port) never use inherited
+//! > sockets.
+use std::net::SocketAddr;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use socket2::{Socket, Type};
+
+/// The first file descriptor passed by the service manager.
+#[cfg(unix)]
+const SD_LISTEN_FDS_START: i32 = 3;
+
+static INHERITED_SOCKETS: OnceLock<Mutex<Vec<Socket>>> = OnceLock::new();
+
+/// It takes the inherited TCP listener bound to the address, if any.
+///
+/// The listener can only be taken once.
+#[must_use]
+pub fn take_tcp_listener(addr: SocketAddr) -> Option<std::net::TcpListener> {
+    take(addr, Type::STREAM).map(Into::into)
+}
+
+/// It takes the inherited UDP socket bound to the address, if any.
+///
+/// The socket can only be taken once.
+#[must_use]
+pub fn take_udp_socket(addr: SocketAddr) -> Option<std::net::UdpSocket> {
+    take(addr, Type::DGRAM).map(Into::into)
+}
+
+/// It logs a warning for each inherited socket not taken by any server. They
+/// usually are sockets for addresses missing in the configuration.
+///
+/// # Panics
+///
+/// Will panic if the lock on the inherited sockets is poisoned.
+pub fn warn_about_unused_sockets() {
+    for socket in lock_inherited_sockets().iter() {
+        let addr = socket.local_addr().ok().and_then(|local| local.as_socket());
+
+        tracing::warn!(?addr, "inherited socket not used by any service");
+    }
+}
+
+fn lock_inherited_sockets() -> MutexGuard<'static, Vec<Socket>> {
+    INHERITED_SOCKETS
+        .get_or_init(|| Mutex::new(inherited_sockets()))
+        .lock()
+        .expect("it should get a lock on the inherited sockets")
+}
+
+fn take(addr: SocketAddr, socket_type: Type) -> Option<Socket> {
+    if addr.port() == 0 {
+        return None;
+    }
+
+    let mut sockets = lock_inherited_sockets();
+
+    let position = sockets.iter().position(|socket| {
+        socket.r#type().is_ok_and(|t| t == socket_type)
+            && socket.local_addr().ok().and_then(|local| local.as_socket()) == Some(addr)
+    })?;
+
+    let socket = sockets.remove(position);
+
+    if let Err(err) = socket.set_nonblocking(true) {
+        tracing::error!(%addr, %err, "unable to use the inherited socket");
+        return None;
+    }
+
+    tracing::info!(%addr, "using inherited socket");
+
+    Some(socket)
+}
+
+/// It returns the sockets passed by the service manager.
+///
+/// The environment variables are ignored if they are not meant for this
+/// process.
+#[cfg(unix)]
+fn inherited_sockets() -> Vec<Socket> {
+    use std::os::fd::FromRawFd;
+
+    let for_this_process = std::env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+        .is_some_and(|pid| pid == std::process::id());
+
+    if !for_this_process {
+        return vec![];
+    }
+
+    let count = std::env::var("LISTEN_FDS")
+        .ok()
+        .and_then(|fds| fds.parse::<i32>().ok())
+        .unwrap_or_default();
+
+    (SD_LISTEN_FDS_START..SD_LISTEN_FDS_START.saturating_add(count))
+        .map(|fd| {
+            // SAFETY: the service manager passes the ownership of the
+            // descriptors to this process, and they are only wrapped once.
+            let socket = unsafe { Socket::from_raw_fd(fd) };
+
+            // Child processes must not inherit the sockets.
+            if let Err(err) = socket.set_cloexec(true) {
+                tracing::warn!(fd, %err, "unable to set close-on-exec on inherited socket");
+            }
+
+            socket
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+fn inherited_sockets() -> Vec<Socket> {
+    vec![]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::{take_tcp_listener, take_udp_socket};
+
+    #[test]
+    fn it_should_not_use_inherited_sockets_for_servers_bound_to_a_random_port() {
+        let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
+
+        assert!(take_tcp_listener(addr).is_none());
+        assert!(take_udp_socket(addr).is_none());
+    }
+}
@@ -0,0 +1,156 @@
+//! Token buckets for the rate limiters of the servers.
+//!
+//! Each key (for example, a client IP address) has a bucket with up to
+//! `burst` tokens, refilled at `rate` tokens per second. Every request takes
+//! one token from the bucket of its key, and the requests received with an
+//! empty bucket exceed the limit.
+//!
+//! The buckets are split in shards, each one with its own lock, so the
+//! workers handling requests from different keys rarely wait for each other.
+//! The number of keys tracked is bounded. When a shard is full, it's emptied
+//! before adding a new key, like the forwarded IPs in the
+//! [`statistics`](crate::core::statistics). The keys of the shard start again
+//! with a full bucket, but adding a key takes constant (amortized) time, so a
+//! flood of requests from new (maybe spoofed) IP addresses can't make each
+//! request scan all the buckets.
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::sync::Mutex;
+
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+/// Maximum number of keys tracked.
+const MAX_KEYS: usize = 100_000;
+
+/// Number of shards of the buckets.
+const SHARDS: usize = 64;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: DurationSinceUnixEpoch,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: DurationSinceUnixEpoch, rate: f64, burst: f64) {
+        let elapsed = now.saturating_sub(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * rate).min(burst);
+        self.last_refill = now;
+    }
+}
+
+/// The token buckets of the keys of a rate limit.
+#[derive(Debug)]
+pub(crate) struct TokenBuckets<K> {
+    /// Tokens added per second.
+    rate: f64,
+    /// Maximum number of tokens in a bucket.
+    burst: f64,
+    max_keys_per_shard: usize,
+    hasher: RandomState,
+    shards: Box<[Mutex<HashMap<K, Bucket>>]>,
+}
+
+impl<K: Eq + Hash + Clone> TokenBuckets<K> {
+    /// Buckets refilled at `rate` tokens per second, with up to `burst`
+    /// tokens.
+    pub(crate) fn new(rate: f64, burst: f64) -> Self {
+        Self::with_max_keys(rate, burst, MAX_KEYS)
+    }
+
+    /// Buckets for a limit of requests per minute. The whole minute can be
+    /// used at once.
+    pub(crate) fn per_minute(requests_per_minute: u32) -> Self {
+        Self::new(f64::from(requests_per_minute) / 60.0, f64::from(requests_per_minute))
+    }
+
+    fn with_max_keys(rate: f64, burst: f64, max_keys: usize) -> Self {
+        Self {
+            rate,
+            burst,
+            max_keys_per_shard: max_keys.div_ceil(SHARDS).max(1),
+            hasher: RandomState::new(),
+            shards: (0..SHARDS).map(|_| Mutex::default()).collect(),
+        }
+    }
+
+    /// It takes a token from the bucket of the `key`. It returns `false` if
+    /// the bucket is empty.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the buckets is poisoned.
+    pub(crate) fn try_acquire(&self, key: &K, now: DurationSinceUnixEpoch) -> bool {
+        #[allow(clippy::cast_possible_truncation)]
+        let shard = self.hasher.hash_one(key) as usize % SHARDS;
+
+        let mut buckets = self.shards[shard]
+            .lock()
+            .expect("it should get a lock on the rate limiter buckets");
+
+        if buckets.len() >= self.max_keys_per_shard && !buckets.contains_key(key) {
+            buckets.clear();
+        }
+
+        let bucket = buckets.entry(key.clone()).or_insert(Bucket {
+            tokens: self.burst,
+            last_refill: now,
+        });
+
+        bucket.refill(now, self.rate, self.burst);
+
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+
+        bucket.tokens -= 1.0;
+
+        true
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().expect("it should get a lock on the rate limiter buckets").len())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{TokenBuckets, SHARDS};
+
+    #[test]
+    fn it_should_reject_the_requests_exceeding_the_burst() {
+        let buckets = TokenBuckets::new(1.0, 2.0);
+
+        assert!(buckets.try_acquire(&1, Duration::ZERO));
+        assert!(buckets.try_acquire(&1, Duration::ZERO));
+        assert!(!buckets.try_acquire(&1, Duration::ZERO));
+        assert!(buckets.try_acquire(&2, Duration::ZERO));
+    }
+
+    #[test]
+    fn it_should_refill_the_buckets_at_the_rate() {
+        let buckets = TokenBuckets::per_minute(1);
+
+        assert!(buckets.try_acquire(&1, Duration::ZERO));
+        assert!(!buckets.try_acquire(&1, Duration::from_secs(59)));
+        assert!(buckets.try_acquire(&1, Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn it_should_not_track_more_keys_than_the_maximum() {
+        let buckets = TokenBuckets::with_max_keys(1.0, 1.0, SHARDS * 2);
+
+        for key in 0..10_000 {
+            buckets.try_acquire(&key, Duration::ZERO);
+        }
+
+        assert!(buckets.len() <= SHARDS * 2);
+    }
+}
@@ -25,8 +25,31 @@
 //! ```
 //!
 //! Time slots are two minute intervals since the Unix epoch. The secret seed is
-//! a random number that is generated when the server starts. And the client IP
-//! is used in order generate a unique connection ID for each client.
+//! a random number that is generated when the server starts (or the configured
+//! `udp_connection_id_secret`, or the one persisted in the
+//! `udp_connection_id_secret_file`). And the client IP is used in order
+//! generate a unique connection ID for each client.
+//!
+//! The secret seed can be rotated. After a rotation, the connection IDs made
+//! with the previous seed are still valid during the configured grace period.
+//! Refer to the [`connection_id_secret`](crate::core::services::connection_id_secret)
+//! services.
+//!
+//! The `hash` function depends on the configured
+//! [`ConnectionIdScheme`]:
+//!
+//! - `hash` (default): the input is hashed with the standard library hasher.
+//! - `hmac_sha256`: HMAC-SHA256 of the client address and time slot, using the
+//!   secret seed as key.
+//! - `keyed_blake3`: BLAKE3 keyed hash of the client address and time slot,
+//!   using the secret seed as key.
+//! - `siphash24`: SipHash-2-4 of the client address and time slot, using the
+//!   first 128 bits of the secret seed as key. It's the cheapest of the
+//!   keyed constructions, for low-power hardware.
+//!
+//! The keyed constructions are standard MACs, for deployments that require
+//! them. In all cases, the output is truncated to the 8 bytes of the
+//! connection ID.
 //!
 //! The BEP-15 recommends a two-minute time slot.
 //!
@@ -68,11 +91,16 @@ use std::net::SocketAddr;
 use std::panic::Location;
 
 use aquatic_udp_protocol::ConnectionId;
+use torrust_tracker_clock::clock::Time;
 use torrust_tracker_clock::time_extent::{Extent, TimeExtent};
+use torrust_tracker_configuration::ConnectionIdScheme;
 use zerocopy::network_endian::I64;
 use zerocopy::AsBytes;
 
 use super::error::Error;
+use crate::shared::crypto::ephemeral_instance_keys::Seed;
+use crate::shared::crypto::keys::seeds::{Current, Keeper};
+use crate::CurrentClock;
 
 pub type Cookie = [u8; 8];
 
@@ -96,11 +124,11 @@ pub fn into_connection_id(connection_cookie: &Cookie) -> ConnectionId {
 
 /// Generates a new connection cookie.
 #[must_use]
-pub fn make(remote_address: &SocketAddr) -> Cookie {
+pub fn make(remote_address: &SocketAddr, scheme: ConnectionIdScheme) -> Cookie {
     let time_extent = cookie_builder::get_last_time_extent();
 
     //println!("remote_address: {remote_address:?}, time_extent: {time_extent:?}, cookie: {cookie:?}");
-    cookie_builder::build(remote_address, &time_extent)
+    cookie_builder::build(remote_address, &time_extent, scheme)
 }
 
 /// Checks if the supplied `connection_cookie` is valid.
@@ -112,32 +140,62 @@ pub fn make(remote_address: &SocketAddr) -> Cookie {
 /// # Errors
 ///
 /// Will return a `ServerError::InvalidConnectionId` if the supplied `connection_cookie` fails to verify.
-pub fn check(remote_address: &SocketAddr, connection_cookie: &Cookie) -> Result<SinceUnixEpochTimeExtent, Error> {
+pub fn check(
+    remote_address: &SocketAddr,
+    connection_cookie: &Cookie,
+    scheme: ConnectionIdScheme,
+) -> Result<SinceUnixEpochTimeExtent, Error> {
+    let seed = Current::get_seed();
+    let previous_seed = Current::get_previous_seed(CurrentClock::now());
+
+    // The connection IDs made with the previous seed are still valid during
+    // the grace period after a rotation.
+    for seed in std::iter::once(seed).chain(previous_seed) {
+        if let Some(time_extent) = check_with_seed(&seed, remote_address, connection_cookie, scheme) {
+            return Ok(time_extent);
+        }
+    }
+
+    Err(Error::InvalidConnectionId {
+        location: Location::caller(),
+    })
+}
+
+fn check_with_seed(
+    seed: &Seed,
+    remote_address: &SocketAddr,
+    connection_cookie: &Cookie,
+    scheme: ConnectionIdScheme,
+) -> Option<SinceUnixEpochTimeExtent> {
     // we loop backwards testing each time_extent until we find one that matches.
     // (or the lifetime of time_extents is exhausted)
     for offset in 0..=COOKIE_LIFETIME.amount {
         let checking_time_extent = cookie_builder::get_last_time_extent().decrease(offset).unwrap();
 
-        let checking_cookie = cookie_builder::build(remote_address, &checking_time_extent);
+        let checking_cookie = cookie_builder::build_with_seed(seed, remote_address, &checking_time_extent, scheme);
         //println!("remote_address: {remote_address:?}, time_extent: {checking_time_extent:?}, cookie: {checking_cookie:?}");
 
         if *connection_cookie == checking_cookie {
-            return Ok(checking_time_extent);
+            return Some(checking_time_extent);
         }
     }
-    Err(Error::InvalidConnectionId {
-        location: Location::caller(),
-    })
+
+    None
 }
 
 mod cookie_builder {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
-    use std::net::SocketAddr;
+    use std::net::{IpAddr, SocketAddr};
 
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+    use siphasher::sip::SipHasher24;
     use torrust_tracker_clock::time_extent::{Extent, Make, TimeExtent};
+    use torrust_tracker_configuration::ConnectionIdScheme;
 
     use super::{Cookie, SinceUnixEpochTimeExtent, COOKIE_LIFETIME};
+    use crate::shared::crypto::ephemeral_instance_keys::Seed;
     use crate::shared::crypto::keys::seeds::{Current, Keeper};
     use crate::DefaultTimeExtentMaker;
 
@@ -149,16 +207,76 @@ mod cookie_builder {
             .unwrap()
     }
 
-    pub(super) fn build(remote_address: &SocketAddr, time_extent: &TimeExtent) -> Cookie {
-        let seed = Current::get_seed();
+    pub(super) fn build(remote_address: &SocketAddr, time_extent: &TimeExtent, scheme: ConnectionIdScheme) -> Cookie {
+        build_with_seed(&Current::get_seed(), remote_address, time_extent, scheme)
+    }
+
+    pub(super) fn build_with_seed(
+        seed: &Seed,
+        remote_address: &SocketAddr,
+        time_extent: &TimeExtent,
+        scheme: ConnectionIdScheme,
+    ) -> Cookie {
+        match scheme {
+            ConnectionIdScheme::Hash => {
+                let mut hasher = DefaultHasher::new();
+
+                remote_address.hash(&mut hasher);
+                time_extent.hash(&mut hasher);
+                seed.hash(&mut hasher);
+
+                hasher.finish().to_le_bytes()
+            }
+            ConnectionIdScheme::HmacSha256 => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(seed).expect("HMAC should accept keys of any length");
+
+                mac.update(&message(remote_address, time_extent));
+
+                truncate(&mac.finalize().into_bytes())
+            }
+            ConnectionIdScheme::KeyedBlake3 => {
+                truncate(blake3::keyed_hash(seed, &message(remote_address, time_extent)).as_bytes())
+            }
+            ConnectionIdScheme::SipHash24 => {
+                let mut key = [0u8; 16];
+                key.copy_from_slice(&seed[..16]);
+
+                let mut hasher = SipHasher24::new_with_key(&key);
+
+                hasher.write(&message(remote_address, time_extent));
+
+                hasher.finish().to_le_bytes()
+            }
+        }
+    }
+
+    /// The unambiguous encoding of the input authenticated by the keyed
+    /// schemes: IP version, IP, port and time extent.
+    fn message(remote_address: &SocketAddr, time_extent: &TimeExtent) -> Vec<u8> {
+        let mut message = Vec::with_capacity(51);
+
+        match remote_address.ip() {
+            IpAddr::V4(ip) => {
+                message.push(4);
+                message.extend_from_slice(&ip.octets());
+            }
+            IpAddr::V6(ip) => {
+                message.push(6);
+                message.extend_from_slice(&ip.octets());
+            }
+        }
 
-        let mut hasher = DefaultHasher::new();
+        message.extend_from_slice(&remote_address.port().to_be_bytes());
+        message.extend_from_slice(&time_extent.increment.as_nanos().to_be_bytes());
+        message.extend_from_slice(&time_extent.amount.to_be_bytes());
 
-        remote_address.hash(&mut hasher);
-        time_extent.hash(&mut hasher);
-        seed.hash(&mut hasher);
+        message
+    }
 
-        hasher.finish().to_le_bytes()
+    fn truncate(mac: &[u8]) -> Cookie {
+        let mut cookie = Cookie::default();
+        cookie.copy_from_slice(&mac[..cookie.len()]);
+        cookie
     }
 }
 
@@ -169,9 +287,10 @@ mod tests {
     use torrust_tracker_clock::clock::stopped::Stopped as _;
     use torrust_tracker_clock::clock::{self};
     use torrust_tracker_clock::time_extent::{self, Extent};
+    use torrust_tracker_configuration::ConnectionIdScheme;
 
     use super::cookie_builder::{self};
-    use crate::servers::udp::connection_cookie::{check, make, Cookie, COOKIE_LIFETIME};
+    use crate::servers::udp::connection_cookie::{check, check_with_seed, make, Cookie, COOKIE_LIFETIME};
 
     // #![feature(const_socketaddr)]
     // const REMOTE_ADDRESS_IPV4_ZERO: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
@@ -185,7 +304,10 @@ mod tests {
 
         clock::Stopped::local_set_to_unix_epoch();
 
-        let cookie = make(&SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0));
+        let cookie = make(
+            &SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+            ConnectionIdScheme::default(),
+        );
 
         assert!(cookie == ID_COOKIE_OLD_HASHER || cookie == ID_COOKIE_NEW_HASHER);
     }
@@ -195,8 +317,8 @@ mod tests {
         let remote_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
         let time_extent_zero = time_extent::ZERO;
 
-        let cookie = cookie_builder::build(&remote_address, &time_extent_zero);
-        let cookie_2 = cookie_builder::build(&remote_address, &time_extent_zero);
+        let cookie = cookie_builder::build(&remote_address, &time_extent_zero, ConnectionIdScheme::default());
+        let cookie_2 = cookie_builder::build(&remote_address, &time_extent_zero, ConnectionIdScheme::default());
 
         println!("remote_address: {remote_address:?}, time_extent: {time_extent_zero:?}, cookie: {cookie:?}");
         println!("remote_address: {remote_address:?}, time_extent: {time_extent_zero:?}, cookie: {cookie_2:?}");
@@ -213,8 +335,8 @@ mod tests {
         let remote_address_2 = SocketAddr::new(IpAddr::V4(Ipv4Addr::BROADCAST), 0);
         let time_extent_zero = time_extent::ZERO;
 
-        let cookie = cookie_builder::build(&remote_address, &time_extent_zero);
-        let cookie_2 = cookie_builder::build(&remote_address_2, &time_extent_zero);
+        let cookie = cookie_builder::build(&remote_address, &time_extent_zero, ConnectionIdScheme::default());
+        let cookie_2 = cookie_builder::build(&remote_address_2, &time_extent_zero, ConnectionIdScheme::default());
 
         println!("remote_address: {remote_address:?}, time_extent: {time_extent_zero:?}, cookie: {cookie:?}");
         println!("remote_address: {remote_address_2:?}, time_extent: {time_extent_zero:?}, cookie: {cookie_2:?}");
@@ -231,8 +353,8 @@ mod tests {
         let remote_address_2 = SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0);
         let time_extent_zero = time_extent::ZERO;
 
-        let cookie = cookie_builder::build(&remote_address, &time_extent_zero);
-        let cookie_2 = cookie_builder::build(&remote_address_2, &time_extent_zero);
+        let cookie = cookie_builder::build(&remote_address, &time_extent_zero, ConnectionIdScheme::default());
+        let cookie_2 = cookie_builder::build(&remote_address_2, &time_extent_zero, ConnectionIdScheme::default());
 
         println!("remote_address: {remote_address:?}, time_extent: {time_extent_zero:?}, cookie: {cookie:?}");
         println!("remote_address: {remote_address_2:?}, time_extent: {time_extent_zero:?}, cookie: {cookie_2:?}");
@@ -249,8 +371,8 @@ mod tests {
         let remote_address_2 = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 1);
         let time_extent_zero = time_extent::ZERO;
 
-        let cookie = cookie_builder::build(&remote_address, &time_extent_zero);
-        let cookie_2 = cookie_builder::build(&remote_address_2, &time_extent_zero);
+        let cookie = cookie_builder::build(&remote_address, &time_extent_zero, ConnectionIdScheme::default());
+        let cookie_2 = cookie_builder::build(&remote_address_2, &time_extent_zero, ConnectionIdScheme::default());
 
         println!("remote_address: {remote_address:?}, time_extent: {time_extent_zero:?}, cookie: {cookie:?}");
         println!("remote_address: {remote_address_2:?}, time_extent: {time_extent_zero:?}, cookie: {cookie_2:?}");
@@ -267,8 +389,8 @@ mod tests {
         let time_extent_zero = time_extent::ZERO;
         let time_extent_max = time_extent::MAX;
 
-        let cookie = cookie_builder::build(&remote_address, &time_extent_zero);
-        let cookie_2 = cookie_builder::build(&remote_address, &time_extent_max);
+        let cookie = cookie_builder::build(&remote_address, &time_extent_zero, ConnectionIdScheme::default());
+        let cookie_2 = cookie_builder::build(&remote_address, &time_extent_max, ConnectionIdScheme::default());
 
         println!("remote_address: {remote_address:?}, time_extent: {time_extent_zero:?}, cookie: {cookie:?}");
         println!("remote_address: {remote_address:?}, time_extent: {time_extent_max:?}, cookie: {cookie_2:?}");
@@ -283,11 +405,11 @@ mod tests {
     fn it_should_make_different_cookies_for_the_next_time_extent() {
         let remote_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
 
-        let cookie = make(&remote_address);
+        let cookie = make(&remote_address, ConnectionIdScheme::default());
 
         clock::Stopped::local_add(&COOKIE_LIFETIME.increment).unwrap();
 
-        let cookie_next = make(&remote_address);
+        let cookie_next = make(&remote_address, ConnectionIdScheme::default());
 
         assert_ne!(cookie, cookie_next);
     }
@@ -296,20 +418,20 @@ mod tests {
     fn it_should_be_valid_for_this_time_extent() {
         let remote_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
 
-        let cookie = make(&remote_address);
+        let cookie = make(&remote_address, ConnectionIdScheme::default());
 
-        check(&remote_address, &cookie).unwrap();
+        check(&remote_address, &cookie, ConnectionIdScheme::default()).unwrap();
     }
 
     #[test]
     fn it_should_be_valid_for_the_next_time_extent() {
         let remote_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
 
-        let cookie = make(&remote_address);
+        let cookie = make(&remote_address, ConnectionIdScheme::default());
 
         clock::Stopped::local_add(&COOKIE_LIFETIME.increment).unwrap();
 
-        check(&remote_address, &cookie).unwrap();
+        check(&remote_address, &cookie, ConnectionIdScheme::default()).unwrap();
     }
 
     #[test]
@@ -318,11 +440,11 @@ mod tests {
 
         clock::Stopped::local_set_to_unix_epoch();
 
-        let cookie = make(&remote_address);
+        let cookie = make(&remote_address, ConnectionIdScheme::default());
 
         clock::Stopped::local_set(&COOKIE_LIFETIME.total().unwrap().unwrap());
 
-        check(&remote_address, &cookie).unwrap();
+        check(&remote_address, &cookie, ConnectionIdScheme::default()).unwrap();
     }
 
     #[test]
@@ -330,10 +452,70 @@ mod tests {
     fn it_should_be_not_valid_after_their_last_time_extent() {
         let remote_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
 
-        let cookie = make(&remote_address);
+        let cookie = make(&remote_address, ConnectionIdScheme::default());
 
         clock::Stopped::local_set(&COOKIE_LIFETIME.total_next().unwrap().unwrap());
 
-        check(&remote_address, &cookie).unwrap();
+        check(&remote_address, &cookie, ConnectionIdScheme::default()).unwrap();
+    }
+
+    #[test]
+    fn it_should_make_different_connection_cookies_for_each_scheme() {
+        let remote_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+        let time_extent_zero = time_extent::ZERO;
+
+        let hash = cookie_builder::build(&remote_address, &time_extent_zero, ConnectionIdScheme::Hash);
+        let hmac_sha256 = cookie_builder::build(&remote_address, &time_extent_zero, ConnectionIdScheme::HmacSha256);
+        let keyed_blake3 = cookie_builder::build(&remote_address, &time_extent_zero, ConnectionIdScheme::KeyedBlake3);
+        let siphash24 = cookie_builder::build(&remote_address, &time_extent_zero, ConnectionIdScheme::SipHash24);
+
+        assert_ne!(hash, hmac_sha256);
+        assert_ne!(hash, keyed_blake3);
+        assert_ne!(hash, siphash24);
+        assert_ne!(hmac_sha256, keyed_blake3);
+        assert_ne!(hmac_sha256, siphash24);
+        assert_ne!(keyed_blake3, siphash24);
+    }
+
+    #[test]
+    fn connection_cookies_made_with_a_keyed_scheme_should_be_valid_for_this_time_extent() {
+        let remote_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+
+        for scheme in [
+            ConnectionIdScheme::HmacSha256,
+            ConnectionIdScheme::KeyedBlake3,
+            ConnectionIdScheme::SipHash24,
+        ] {
+            let cookie = make(&remote_address, scheme);
+
+            check(&remote_address, &cookie, scheme).unwrap();
+        }
+    }
+
+    #[test]
+    fn connection_cookies_made_with_a_keyed_scheme_should_depend_on_the_client_address() {
+        let remote_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+        let remote_address_2 = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 1);
+
+        for scheme in [
+            ConnectionIdScheme::HmacSha256,
+            ConnectionIdScheme::KeyedBlake3,
+            ConnectionIdScheme::SipHash24,
+        ] {
+            let cookie = make(&remote_address, scheme);
+
+            assert!(check(&remote_address_2, &cookie, scheme).is_err());
+        }
+    }
+
+    #[test]
+    fn it_should_only_be_valid_for_the_seed_it_was_made_with() {
+        let remote_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+        let time_extent = cookie_builder::get_last_time_extent();
+
+        let cookie = cookie_builder::build_with_seed(&[1u8; 32], &remote_address, &time_extent, ConnectionIdScheme::default());
+
+        assert!(check_with_seed(&[1u8; 32], &remote_address, &cookie, ConnectionIdScheme::default()).is_some());
+        assert!(check_with_seed(&[2u8; 32], &remote_address, &cookie, ConnectionIdScheme::default()).is_none());
     }
 }
@@ -4,12 +4,20 @@ use std::panic::Location;
 use thiserror::Error;
 use torrust_tracker_located_error::LocatedError;
 
+use crate::core;
+use crate::core::statistics::RejectReason;
+
 /// Error returned by the UDP server.
 #[derive(Error, Debug)]
 pub enum Error {
-    /// Error returned when the domain tracker returns an error.
+    /// Error returned when the domain tracker rejects the request.
     #[error("tracker server error: {source}")]
-    TrackerError {
+    TrackerError { source: core::error::Error },
+
+    /// Error returned when the authentication key in the `URLData` option
+    /// can't be parsed or it's not valid.
+    #[error("authentication failed: {source}")]
+    AuthenticationFailed {
         source: LocatedError<'static, dyn std::error::Error + Send + Sync>,
     },
 
@@ -30,7 +38,67 @@ pub enum Error {
         source: LocatedError<'static, dyn std::error::Error + Send + Sync>,
     },
 
-    /// Error returned when tracker requires authentication.
-    #[error("domain tracker requires authentication but is not supported in current UDP implementation. Location: {location}")]
+    /// Error returned when the client sends more packets than allowed by the
+    /// rate limit.
+    #[error("too many requests, {location}")]
+    RateLimitExceeded { location: &'static Location<'static> },
+
+    /// Error returned when tracker requires authentication and the request
+    /// does not include the key in the `URLData` option.
+    #[error("domain tracker requires authentication, the key is missing in the URL data option. Location: {location}")]
     TrackerAuthenticationRequired { location: &'static Location<'static> },
+
+    /// Error returned when the announced peer port is lower than the minimum
+    /// port of the tracker.
+    #[error("invalid peer port: {port}, {location}")]
+    InvalidPeerPort {
+        port: u16,
+        location: &'static Location<'static>,
+    },
+}
+
+impl From<&Error> for RejectReason {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::TrackerError { source } => RejectReason::from(source),
+            Error::AuthenticationFailed { .. } | Error::TrackerAuthenticationRequired { .. } => RejectReason::InvalidKey,
+            Error::InvalidConnectionId { .. } => RejectReason::InvalidConnectionId,
+            Error::BadRequest { .. } => RejectReason::ParseError,
+            Error::RateLimitExceeded { .. } => RejectReason::RateLimited,
+            Error::InvalidPeerPort { .. } => RejectReason::InvalidPeerPort,
+            Error::InternalServer { .. } => RejectReason::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::Location;
+
+    use torrust_tracker_primitives::info_hash::InfoHash;
+
+    use super::Error;
+    use crate::core;
+    use crate::core::statistics::RejectReason;
+
+    #[test]
+    fn it_should_label_the_rejections_with_the_reason_of_the_domain_tracker_error() {
+        let error = Error::TrackerError {
+            source: core::error::Error::TorrentNotWhitelisted {
+                info_hash: InfoHash::default(),
+                location: Location::caller(),
+            },
+        };
+
+        assert_eq!(RejectReason::from(&error), RejectReason::NotWhitelisted);
+    }
+
+    #[test]
+    fn it_should_label_the_rejections_with_the_reason_of_the_udp_server_error() {
+        let error = Error::InvalidConnectionId {
+            location: Location::caller(),
+        };
+
+        assert_eq!(RejectReason::from(&error), RejectReason::InvalidConnectionId);
+    }
 }
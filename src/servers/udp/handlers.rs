@@ -10,6 +10,7 @@ use aquatic_udp_protocol::{
     ErrorResponse, Ipv4AddrBytes, Ipv6AddrBytes, NumberOfDownloads, NumberOfPeers, Port, Request, Response, ResponsePeer,
     ScrapeRequest, ScrapeResponse, TorrentScrapeStatistics, TransactionId,
 };
+use torrust_tracker_configuration::{ConnectionIdScheme, UdpTracker};
 use torrust_tracker_located_error::DynError;
 use torrust_tracker_primitives::info_hash::InfoHash;
 use tracing::{instrument, Level};
@@ -18,12 +19,54 @@ use zerocopy::network_endian::I32;
 
 use super::connection_cookie::{check, from_connection_id, into_connection_id, make};
 use super::RawRequest;
+use crate::core::statistics::{Protocol, RejectReason, RequestKind};
 use crate::core::{statistics, PeersWanted, ScrapeData, Tracker};
 use crate::servers::udp::error::Error;
 use crate::servers::udp::logging::{log_bad_request, log_error_response, log_request, log_response};
 use crate::servers::udp::peer_builder;
+use crate::servers::udp::url_data::UrlData;
 use crate::shared::bit_torrent::common::MAX_SCRAPE_TORRENTS;
 
+/// Peers returned in the announce responses of a UDP tracker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeersInResponse {
+    /// Peers returned when the client does not set the `num_want` field.
+    pub default: u32,
+    /// The `num_want` field is clamped to this value.
+    pub max: u32,
+    /// Whether the client's address, as observed by the tracker, is returned
+    /// as the first peer. It's not counted in the peers wanted.
+    pub client: bool,
+}
+
+impl PeersInResponse {
+    #[must_use]
+    pub fn new(config: &UdpTracker) -> Self {
+        Self {
+            default: config.default_peers_in_response,
+            max: config.max_peers_in_response,
+            client: config.return_client_ip,
+        }
+    }
+
+    /// It returns the peers wanted for the `num_want` field of an announce
+    /// request. Zero or negative values (`-1` is the default in the
+    /// protocol) mean the client does not have a preference.
+    #[must_use]
+    pub fn peers_wanted(&self, num_want: i32) -> PeersWanted {
+        match u32::try_from(num_want) {
+            Ok(num_want) if num_want > 0 => PeersWanted::only(num_want.min(self.max)),
+            _ => PeersWanted::only(self.default),
+        }
+    }
+}
+
+impl Default for PeersInResponse {
+    fn default() -> Self {
+        Self::new(&UdpTracker::default())
+    }
+}
+
 /// It handles the incoming UDP packets.
 ///
 /// It's responsible for:
@@ -33,7 +76,14 @@ use crate::shared::bit_torrent::common::MAX_SCRAPE_TORRENTS;
 ///
 /// It will return an `Error` response if the request is invalid.
 #[instrument(skip(udp_request, tracker, local_addr), ret(level = Level::TRACE))]
-pub(crate) async fn handle_packet(udp_request: RawRequest, tracker: &Tracker, local_addr: SocketAddr) -> Response {
+pub(crate) async fn handle_packet(
+    udp_request: RawRequest,
+    tracker: &Tracker,
+    local_addr: SocketAddr,
+    peers_in_response: PeersInResponse,
+    min_peer_port: u16,
+    connection_id_scheme: ConnectionIdScheme,
+) -> Response {
     tracing::debug!("Handling Packets: {udp_request:?}");
 
     let start_time = Instant::now();
@@ -55,7 +105,17 @@ pub(crate) async fn handle_packet(udp_request: RawRequest, tracker: &Tracker, lo
                 Request::Scrape(scrape_request) => scrape_request.transaction_id,
             };
 
-            let response = match handle_request(request, udp_request.from, tracker).await {
+            let response = match handle_request(
+                request,
+                &udp_request.payload,
+                canonical_addr(udp_request.from),
+                peers_in_response,
+                min_peer_port,
+                connection_id_scheme,
+                tracker,
+            )
+            .await
+            {
                 Ok(response) => response,
                 Err(e) => handle_error(&e, transaction_id),
             };
@@ -69,12 +129,15 @@ pub(crate) async fn handle_packet(udp_request: RawRequest, tracker: &Tracker, lo
         Err(e) => {
             log_bad_request(&request_id);
 
-            let response = handle_error(
-                &Error::BadRequest {
-                    source: (Arc::new(e) as DynError).into(),
-                },
-                TransactionId(I32::new(0)),
-            );
+            let error = Error::BadRequest {
+                source: (Arc::new(e) as DynError).into(),
+            };
+
+            if let Some(request) = request_kind(&udp_request.payload) {
+                record_rejection(tracker, request, &error).await;
+            }
+
+            let response = handle_error(&error, TransactionId(I32::new(0)));
 
             log_error_response(&request_id);
 
@@ -83,18 +146,116 @@ pub(crate) async fn handle_packet(udp_request: RawRequest, tracker: &Tracker, lo
     }
 }
 
+/// It builds the error response for a packet exceeding the rate limit.
+///
+/// The packet is not parsed. The transaction ID is read from its position in
+/// the packet, which is the same for all the requests (bytes 12 to 15).
+pub(crate) async fn handle_rate_limited_packet(udp_request: &RawRequest, tracker: &Tracker) -> Response {
+    let error = Error::RateLimitExceeded {
+        location: Location::caller(),
+    };
+
+    if let Some(request) = request_kind(&udp_request.payload) {
+        record_rejection(tracker, request, &error).await;
+    }
+
+    let transaction_id = udp_request
+        .payload
+        .get(12..16)
+        .and_then(|bytes| <[u8; 4]>::try_from(bytes).ok())
+        .map_or(0, i32::from_be_bytes);
+
+    handle_error(&error, TransactionId(I32::new(transaction_id)))
+}
+
+/// It returns the type of request in the packet, if it's an `announce` or
+/// a `scrape` request. The action is read from its position in the packet,
+/// which is the same for all the requests (bytes 8 to 11).
+pub(crate) fn request_kind(payload: &[u8]) -> Option<RequestKind> {
+    let action = payload
+        .get(8..12)
+        .and_then(|bytes| <[u8; 4]>::try_from(bytes).ok())
+        .map(i32::from_be_bytes)?;
+
+    match action {
+        1 => Some(RequestKind::Announce),
+        2 => Some(RequestKind::Scrape),
+        _ => None,
+    }
+}
+
+/// It counts the rejected request in the statistics, labeled with the reason
+/// derived from the error.
+async fn record_rejection(tracker: &Tracker, request: RequestKind, error: &Error) {
+    tracker
+        .send_stats_event(statistics::Event::rejected(Protocol::Udp, request, RejectReason::from(error)))
+        .await;
+}
+
+/// It converts `IPv4`-mapped `IPv6` addresses, used by dual-stack sockets for
+/// `IPv4` clients, into `IPv4` addresses.
+fn canonical_addr(addr: SocketAddr) -> SocketAddr {
+    SocketAddr::new(addr.ip().to_canonical(), addr.port())
+}
+
 /// It dispatches the request to the correct handler.
 ///
+/// The `payload` is the raw packet. It's used to parse the announce request
+/// options, which are not parsed by the [`Request`] parser.
+///
+/// Announces with a peer port lower than `min_peer_port` are rejected. The
+/// connection IDs are made and checked with the `connection_id_scheme`.
+///
 /// # Errors
 ///
 /// If a error happens in the `handle_request` function, it will just return the  `ServerError`.
-#[instrument(skip(request, remote_addr, tracker))]
-pub async fn handle_request(request: Request, remote_addr: SocketAddr, tracker: &Tracker) -> Result<Response, Error> {
+#[instrument(skip(request, payload, remote_addr, tracker))]
+pub async fn handle_request(
+    request: Request,
+    payload: &[u8],
+    remote_addr: SocketAddr,
+    peers_in_response: PeersInResponse,
+    min_peer_port: u16,
+    connection_id_scheme: ConnectionIdScheme,
+    tracker: &Tracker,
+) -> Result<Response, Error> {
     tracing::trace!("handle request");
 
     match request {
-        Request::Connect(connect_request) => handle_connect(remote_addr, &connect_request, tracker).await,
-        Request::Announce(announce_request) => handle_announce(remote_addr, &announce_request, tracker).await,
+        Request::Connect(connect_request) => handle_connect(remote_addr, &connect_request, connection_id_scheme, tracker).await,
+        Request::Announce(announce_request) => {
+            let url_data = match UrlData::from_announce_packet(payload) {
+                Ok(url_data) => url_data,
+                Err(e) => {
+                    let error = Error::BadRequest {
+                        source: (Arc::new(e) as DynError).into(),
+                    };
+                    record_rejection(tracker, RequestKind::Announce, &error).await;
+                    return Err(error);
+                }
+            };
+
+            let port = announce_request.port.0.get();
+
+            if port < min_peer_port {
+                let error = Error::InvalidPeerPort {
+                    port,
+                    location: Location::caller(),
+                };
+                record_rejection(tracker, RequestKind::Announce, &error).await;
+                return Err(error);
+            }
+
+            handle_announce(
+                remote_addr,
+                &announce_request,
+                url_data.as_ref(),
+                peers_in_response,
+                connection_id_scheme,
+                tracker,
+            )
+            .await
+        }
         Request::Scrape(scrape_request) => handle_scrape(remote_addr, &scrape_request, tracker).await,
     }
 }
@@ -106,10 +267,15 @@ pub async fn handle_request(request: Request, remote_addr: SocketAddr, tracker:
 ///
 /// This function does not ever return an error.
 #[instrument(skip(tracker), err, ret(level = Level::TRACE))]
-pub async fn handle_connect(remote_addr: SocketAddr, request: &ConnectRequest, tracker: &Tracker) -> Result<Response, Error> {
+pub async fn handle_connect(
+    remote_addr: SocketAddr,
+    request: &ConnectRequest,
+    connection_id_scheme: ConnectionIdScheme,
+    tracker: &Tracker,
+) -> Result<Response, Error> {
     tracing::trace!("handle connect");
 
-    let connection_cookie = make(&remote_addr);
+    let connection_cookie = make(&remote_addr, connection_id_scheme);
     let connection_id = into_connection_id(&connection_cookie);
 
     let response = ConnectResponse {
@@ -133,6 +299,9 @@ pub async fn handle_connect(remote_addr: SocketAddr, request: &ConnectRequest, t
 /// It handles the `Announce` request. Refer to [`Announce`](crate::servers::udp#announce)
 /// request for more information.
 ///
+/// The authentication key of private trackers is read from the
+/// [`URLData`](crate::servers::udp::url_data) option.
+///
 /// # Errors
 ///
 /// If a error happens in the `handle_announce` function, it will just return the  `ServerError`.
@@ -140,31 +309,71 @@ pub async fn handle_connect(remote_addr: SocketAddr, request: &ConnectRequest, t
 pub async fn handle_announce(
     remote_addr: SocketAddr,
     announce_request: &AnnounceRequest,
+    url_data: Option<&UrlData>,
+    peers_in_response: PeersInResponse,
+    connection_id_scheme: ConnectionIdScheme,
     tracker: &Tracker,
 ) -> Result<Response, Error> {
     tracing::trace!("handle announce");
 
     // Authentication
     if tracker.requires_authentication() {
-        return Err(Error::TrackerAuthenticationRequired {
-            location: Location::caller(),
-        });
+        let authentication = match url_data.and_then(UrlData::key) {
+            Some(Ok(key)) => tracker.authenticate(&key).await.map_err(|e| Error::AuthenticationFailed {
+                source: (Arc::new(e) as DynError).into(),
+            }),
+            Some(Err(e)) => Err(Error::AuthenticationFailed {
+                source: (Arc::new(e) as DynError).into(),
+            }),
+            None => Err(Error::TrackerAuthenticationRequired {
+                location: Location::caller(),
+            }),
+        };
+
+        if let Err(e) = authentication {
+            record_rejection(tracker, RequestKind::Announce, &e).await;
+            return Err(e);
+        }
     }
 
-    check(&remote_addr, &from_connection_id(&announce_request.connection_id))?;
+    if let Err(e) = check(
+        &remote_addr,
+        &from_connection_id(&announce_request.connection_id),
+        connection_id_scheme,
+    ) {
+        record_rejection(tracker, RequestKind::Announce, &e).await;
+        return Err(e);
+    }
 
     let info_hash = announce_request.info_hash.into();
     let remote_client_ip = remote_addr.ip();
 
     // Authorization
-    tracker.authorize(&info_hash).await.map_err(|e| Error::TrackerError {
-        source: (Arc::new(e) as Arc<dyn std::error::Error + Send + Sync>).into(),
-    })?;
+    let authorization = match tracker.authorize(&info_hash).await {
+        Ok(()) => tracker
+            .authorize_peer_ip(&remote_client_ip)
+            .and_then(|()| tracker.authorize_client(&announce_request.peer_id))
+            .and_then(|()| tracker.consume_announce_budget(&remote_client_ip)),
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = authorization {
+        let error = Error::TrackerError { source: e };
+        record_rejection(tracker, RequestKind::Announce, &error).await;
+        return Err(error);
+    }
 
     let mut peer = peer_builder::from_request(announce_request, &remote_client_ip);
-    let peers_wanted: PeersWanted = i32::from(announce_request.peers_wanted.0).into();
+    let peers_wanted = peers_in_response.peers_wanted(i32::from(announce_request.peers_wanted.0));
+
+    // The IP the packet came from, not the one the tracker may replace it with
+    let client_addr = peers_in_response
+        .client
+        .then_some(SocketAddr::new(remote_client_ip, peer.peer_addr.port()));
 
-    let response = tracker.announce(&info_hash, &mut peer, &remote_client_ip, &peers_wanted);
+    let response = tracker
+        .announce(&info_hash, &mut peer, &remote_client_ip, &peers_wanted)
+        .await;
 
     match remote_client_ip {
         IpAddr::V4(_) => {
@@ -180,18 +389,18 @@ pub async fn handle_announce(
         let announce_response = AnnounceResponse {
             fixed: AnnounceResponseFixedData {
                 transaction_id: announce_request.transaction_id,
-                announce_interval: AnnounceInterval(I32::new(i64::from(tracker.get_announce_policy().interval) as i32)),
+                announce_interval: AnnounceInterval(I32::new(i64::from(response.policy.interval) as i32)),
                 leechers: NumberOfPeers(I32::new(i64::from(response.stats.incomplete) as i32)),
                 seeders: NumberOfPeers(I32::new(i64::from(response.stats.complete) as i32)),
             },
-            peers: response
-                .peers
-                .iter()
-                .filter_map(|peer| {
-                    if let IpAddr::V4(ip) = peer.peer_addr.ip() {
+            peers: client_addr
+                .into_iter()
+                .chain(response.peers.iter().filter_map(|peer| peer.ipv4_addr()))
+                .filter_map(|peer_addr| {
+                    if let IpAddr::V4(ip) = peer_addr.ip() {
                         Some(ResponsePeer::<Ipv4AddrBytes> {
                             ip_address: ip.into(),
-                            port: Port(peer.peer_addr.port().into()),
+                            port: Port(peer_addr.port().into()),
                         })
                     } else {
                         None
@@ -205,18 +414,18 @@ pub async fn handle_announce(
         let announce_response = AnnounceResponse {
             fixed: AnnounceResponseFixedData {
                 transaction_id: announce_request.transaction_id,
-                announce_interval: AnnounceInterval(I32::new(i64::from(tracker.get_announce_policy().interval) as i32)),
+                announce_interval: AnnounceInterval(I32::new(i64::from(response.policy.interval) as i32)),
                 leechers: NumberOfPeers(I32::new(i64::from(response.stats.incomplete) as i32)),
                 seeders: NumberOfPeers(I32::new(i64::from(response.stats.complete) as i32)),
             },
-            peers: response
-                .peers
-                .iter()
-                .filter_map(|peer| {
-                    if let IpAddr::V6(ip) = peer.peer_addr.ip() {
+            peers: client_addr
+                .into_iter()
+                .chain(response.peers.iter().filter_map(|peer| peer.ipv6_addr()))
+                .filter_map(|peer_addr| {
+                    if let IpAddr::V6(ip) = peer_addr.ip() {
                         Some(ResponsePeer::<Ipv6AddrBytes> {
                             ip_address: ip.into(),
-                            port: Port(peer.peer_addr.port().into()),
+                            port: Port(peer_addr.port().into()),
                         })
                     } else {
                         None
@@ -239,6 +448,13 @@ pub async fn handle_announce(
 pub async fn handle_scrape(remote_addr: SocketAddr, request: &ScrapeRequest, tracker: &Tracker) -> Result<Response, Error> {
     tracing::trace!("handle scrape");
 
+    // Scrapes are not handled while the tracker is overloaded
+    if let Err(e) = tracker.check_overload() {
+        let error = Error::TrackerError { source: e };
+        record_rejection(tracker, RequestKind::Scrape, &error).await;
+        return Err(error);
+    }
+
     // Convert from aquatic infohashes
     let mut info_hashes: Vec<InfoHash> = vec![];
     for info_hash in &request.info_hashes {
@@ -435,6 +651,7 @@ mod tests {
 
         use aquatic_udp_protocol::{ConnectRequest, ConnectResponse, Response, TransactionId};
         use mockall::predicate::eq;
+        use torrust_tracker_configuration::ConnectionIdScheme;
 
         use super::{sample_ipv4_socket_address, sample_ipv6_remote_addr, tracker_configuration};
         use crate::core::{self, statistics};
@@ -454,14 +671,19 @@ mod tests {
                 transaction_id: TransactionId(0i32.into()),
             };
 
-            let response = handle_connect(sample_ipv4_remote_addr(), &request, &public_tracker())
-                .await
-                .unwrap();
+            let response = handle_connect(
+                sample_ipv4_remote_addr(),
+                &request,
+                ConnectionIdScheme::default(),
+                &public_tracker(),
+            )
+            .await
+            .unwrap();
 
             assert_eq!(
                 response,
                 Response::Connect(ConnectResponse {
-                    connection_id: into_connection_id(&make(&sample_ipv4_remote_addr())),
+                    connection_id: into_connection_id(&make(&sample_ipv4_remote_addr(), ConnectionIdScheme::default())),
                     transaction_id: request.transaction_id
                 })
             );
@@ -473,14 +695,19 @@ mod tests {
                 transaction_id: TransactionId(0i32.into()),
             };
 
-            let response = handle_connect(sample_ipv4_remote_addr(), &request, &public_tracker())
-                .await
-                .unwrap();
+            let response = handle_connect(
+                sample_ipv4_remote_addr(),
+                &request,
+                ConnectionIdScheme::default(),
+                &public_tracker(),
+            )
+            .await
+            .unwrap();
 
             assert_eq!(
                 response,
                 Response::Connect(ConnectResponse {
-                    connection_id: into_connection_id(&make(&sample_ipv4_remote_addr())),
+                    connection_id: into_connection_id(&make(&sample_ipv4_remote_addr(), ConnectionIdScheme::default())),
                     transaction_id: request.transaction_id
                 })
             );
@@ -506,9 +733,14 @@ mod tests {
                 )
                 .unwrap(),
             );
-            handle_connect(client_socket_address, &sample_connect_request(), &torrent_tracker)
-                .await
-                .unwrap();
+            handle_connect(
+                client_socket_address,
+                ConnectionIdScheme::default(),
+                &sample_connect_request(),
+                &torrent_tracker,
+            )
+            .await
+            .unwrap();
         }
 
         #[tokio::test]
@@ -529,9 +761,14 @@ mod tests {
                 )
                 .unwrap(),
             );
-            handle_connect(sample_ipv6_remote_addr(), &sample_connect_request(), &torrent_tracker)
-                .await
-                .unwrap();
+            handle_connect(
+                sample_ipv6_remote_addr(),
+                ConnectionIdScheme::default(),
+                &sample_connect_request(),
+                &torrent_tracker,
+            )
+            .await
+            .unwrap();
         }
     }
 
@@ -544,6 +781,7 @@ mod tests {
             AnnounceActionPlaceholder, AnnounceEvent, AnnounceRequest, ConnectionId, NumberOfBytes, NumberOfPeers,
             PeerId as AquaticPeerId, PeerKey, Port, TransactionId,
         };
+        use torrust_tracker_configuration::ConnectionIdScheme;
 
         use crate::servers::udp::connection_cookie::{into_connection_id, make};
         use crate::servers::udp::handlers::tests::sample_ipv4_remote_addr;
@@ -559,7 +797,7 @@ mod tests {
                 let info_hash_aquatic = aquatic_udp_protocol::InfoHash([0u8; 20]);
 
                 let default_request = AnnounceRequest {
-                    connection_id: into_connection_id(&make(&sample_ipv4_remote_addr())),
+                    connection_id: into_connection_id(&make(&sample_ipv4_remote_addr(), ConnectionIdScheme::default())),
                     action_placeholder: AnnounceActionPlaceholder::default(),
                     transaction_id: TransactionId(0i32.into()),
                     info_hash: info_hash_aquatic,
@@ -619,14 +857,17 @@ mod tests {
                 PeerId as AquaticPeerId, Response, ResponsePeer,
             };
             use mockall::predicate::eq;
+            use torrust_tracker_configuration::ConnectionIdScheme;
+            use torrust_tracker_test_helpers::configuration;
 
+            use crate::core::statistics::{Protocol, RejectReason, RequestKind};
             use crate::core::{self, statistics};
             use crate::servers::udp::connection_cookie::{into_connection_id, make};
             use crate::servers::udp::handlers::tests::announce_request::AnnounceRequestBuilder;
             use crate::servers::udp::handlers::tests::{
                 public_tracker, sample_ipv4_socket_address, tracker_configuration, TorrentPeerBuilder,
             };
-            use crate::servers::udp::handlers::{handle_announce, AnnounceResponseFixedData};
+            use crate::servers::udp::handlers::{handle_announce, AnnounceResponseFixedData, PeersInResponse};
 
             #[tokio::test]
             async fn an_announced_peer_should_be_added_to_the_tracker() {
@@ -640,14 +881,23 @@ mod tests {
                 let remote_addr = SocketAddr::new(IpAddr::V4(client_ip), client_port);
 
                 let request = AnnounceRequestBuilder::default()
-                    .with_connection_id(into_connection_id(&make(&remote_addr)))
+                    .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
                     .with_info_hash(info_hash)
                     .with_peer_id(peer_id)
                     .with_ip_address(client_ip)
                     .with_port(client_port)
                     .into();
 
-                handle_announce(remote_addr, &request, &tracker).await.unwrap();
+                handle_announce(
+                    remote_addr,
+                    &request,
+                    None,
+                    PeersInResponse::default(),
+                    ConnectionIdScheme::default(),
+                    &tracker,
+                )
+                .await
+                .unwrap();
 
                 let peers = tracker.get_torrent_peers(&info_hash.0.into());
 
@@ -664,10 +914,19 @@ mod tests {
                 let remote_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)), 8080);
 
                 let request = AnnounceRequestBuilder::default()
-                    .with_connection_id(into_connection_id(&make(&remote_addr)))
+                    .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
                     .into();
 
-                let response = handle_announce(remote_addr, &request, &public_tracker()).await.unwrap();
+                let response = handle_announce(
+                    remote_addr,
+                    &request,
+                    None,
+                    PeersInResponse::default(),
+                    ConnectionIdScheme::default(),
+                    &public_tracker(),
+                )
+                .await
+                .unwrap();
 
                 let empty_peer_vector: Vec<ResponsePeer<Ipv4AddrBytes>> = vec![];
                 assert_eq!(
@@ -703,21 +962,30 @@ mod tests {
                 let remote_addr = SocketAddr::new(IpAddr::V4(remote_client_ip), remote_client_port);
 
                 let request = AnnounceRequestBuilder::default()
-                    .with_connection_id(into_connection_id(&make(&remote_addr)))
+                    .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
                     .with_info_hash(info_hash)
                     .with_peer_id(peer_id)
                     .with_ip_address(peer_address)
                     .with_port(client_port)
                     .into();
 
-                handle_announce(remote_addr, &request, &tracker).await.unwrap();
+                handle_announce(
+                    remote_addr,
+                    &request,
+                    None,
+                    PeersInResponse::default(),
+                    ConnectionIdScheme::default(),
+                    &tracker,
+                )
+                .await
+                .unwrap();
 
                 let peers = tracker.get_torrent_peers(&info_hash.0.into());
 
                 assert_eq!(peers[0].peer_addr, SocketAddr::new(IpAddr::V4(remote_client_ip), client_port));
             }
 
-            fn add_a_torrent_peer_using_ipv6(tracker: &Arc<core::Tracker>) {
+            async fn add_a_torrent_peer_using_ipv6(tracker: &Arc<core::Tracker>) {
                 let info_hash = AquaticInfoHash([0u8; 20]);
 
                 let client_ip_v4 = Ipv4Addr::new(126, 0, 0, 1);
@@ -730,23 +998,32 @@ mod tests {
                     .with_peer_address(SocketAddr::new(IpAddr::V6(client_ip_v6), client_port))
                     .into();
 
-                tracker.upsert_peer_and_get_stats(&info_hash.0.into(), &peer_using_ipv6);
+                tracker.upsert_peer_and_get_stats(&info_hash.0.into(), &peer_using_ipv6).await;
             }
 
             async fn announce_a_new_peer_using_ipv4(tracker: Arc<core::Tracker>) -> Response {
                 let remote_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)), 8080);
                 let request = AnnounceRequestBuilder::default()
-                    .with_connection_id(into_connection_id(&make(&remote_addr)))
+                    .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
                     .into();
 
-                handle_announce(remote_addr, &request, &tracker).await.unwrap()
+                handle_announce(
+                    remote_addr,
+                    &request,
+                    None,
+                    PeersInResponse::default(),
+                    ConnectionIdScheme::default(),
+                    &tracker,
+                )
+                .await
+                .unwrap()
             }
 
             #[tokio::test]
             async fn when_the_announce_request_comes_from_a_client_using_ipv4_the_response_should_not_include_peers_using_ipv6() {
                 let tracker = public_tracker();
 
-                add_a_torrent_peer_using_ipv6(&tracker);
+                add_a_torrent_peer_using_ipv6(&tracker).await;
 
                 let response = announce_a_new_peer_using_ipv4(tracker.clone()).await;
 
@@ -781,22 +1058,62 @@ mod tests {
                 handle_announce(
                     sample_ipv4_socket_address(),
                     &AnnounceRequestBuilder::default().into(),
+                    None,
+                    PeersInResponse::default(),
+                    ConnectionIdScheme::default(),
                     &tracker,
                 )
                 .await
                 .unwrap();
             }
 
+            #[tokio::test]
+            async fn should_send_the_rejected_announce_event_when_the_torrent_is_not_whitelisted() {
+                let mut stats_event_sender_mock = statistics::MockEventSender::new();
+                stats_event_sender_mock
+                    .expect_send_event()
+                    .with(eq(statistics::Event::rejected(
+                        Protocol::Udp,
+                        RequestKind::Announce,
+                        RejectReason::NotWhitelisted,
+                    )))
+                    .times(1)
+                    .returning(|_| Box::pin(future::ready(Some(Ok(())))));
+                let stats_event_sender = Box::new(stats_event_sender_mock);
+
+                let tracker = Arc::new(
+                    core::Tracker::new(
+                        &configuration::ephemeral_listed().core,
+                        Some(stats_event_sender),
+                        statistics::Repo::new(),
+                    )
+                    .unwrap(),
+                );
+
+                let result = handle_announce(
+                    sample_ipv4_socket_address(),
+                    &AnnounceRequestBuilder::default().into(),
+                    None,
+                    PeersInResponse::default(),
+                    ConnectionIdScheme::default(),
+                    &tracker,
+                )
+                .await;
+
+                assert!(result.is_err());
+            }
+
             mod from_a_loopback_ip {
                 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
                 use std::sync::Arc;
 
                 use aquatic_udp_protocol::{InfoHash as AquaticInfoHash, PeerId as AquaticPeerId};
+                use torrust_tracker_configuration::ConnectionIdScheme;
 
                 use crate::servers::udp::connection_cookie::{into_connection_id, make};
-                use crate::servers::udp::handlers::handle_announce;
                 use crate::servers::udp::handlers::tests::announce_request::AnnounceRequestBuilder;
                 use crate::servers::udp::handlers::tests::{public_tracker, TorrentPeerBuilder};
+                use crate::servers::udp::handlers::{handle_announce, PeersInResponse};
 
                 #[tokio::test]
                 async fn the_peer_ip_should_be_changed_to_the_external_ip_in_the_tracker_configuration_if_defined() {
@@ -810,14 +1127,23 @@ mod tests {
                     let remote_addr = SocketAddr::new(IpAddr::V4(client_ip), client_port);
 
                     let request = AnnounceRequestBuilder::default()
-                        .with_connection_id(into_connection_id(&make(&remote_addr)))
+                        .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
                         .with_info_hash(info_hash)
                         .with_peer_id(peer_id)
                         .with_ip_address(client_ip)
                         .with_port(client_port)
                         .into();
 
-                    handle_announce(remote_addr, &request, &tracker).await.unwrap();
+                    handle_announce(
+                        remote_addr,
+                        &request,
+                        None,
+                        PeersInResponse::default(),
+                        ConnectionIdScheme::default(),
+                        &tracker,
+                    )
+                    .await
+                    .unwrap();
 
                     let peers = tracker.get_torrent_peers(&info_hash.0.into());
 
@@ -844,6 +1170,7 @@ mod tests {
                 PeerId as AquaticPeerId, Response, ResponsePeer,
             };
             use mockall::predicate::eq;
+            use torrust_tracker_configuration::ConnectionIdScheme;
 
             use crate::core::{self, statistics};
             use crate::servers::udp::connection_cookie::{into_connection_id, make};
@@ -851,7 +1178,7 @@ mod tests {
             use crate::servers::udp::handlers::tests::{
                 public_tracker, sample_ipv6_remote_addr, tracker_configuration, TorrentPeerBuilder,
             };
-            use crate::servers::udp::handlers::{handle_announce, AnnounceResponseFixedData};
+            use crate::servers::udp::handlers::{handle_announce, AnnounceResponseFixedData, PeersInResponse};
 
             #[tokio::test]
             async fn an_announced_peer_should_be_added_to_the_tracker() {
@@ -866,14 +1193,23 @@ mod tests {
                 let remote_addr = SocketAddr::new(IpAddr::V6(client_ip_v6), client_port);
 
                 let request = AnnounceRequestBuilder::default()
-                    .with_connection_id(into_connection_id(&make(&remote_addr)))
+                    .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
                     .with_info_hash(info_hash)
                     .with_peer_id(peer_id)
                     .with_ip_address(client_ip_v4)
                     .with_port(client_port)
                     .into();
 
-                handle_announce(remote_addr, &request, &tracker).await.unwrap();
+                handle_announce(
+                    remote_addr,
+                    &request,
+                    None,
+                    PeersInResponse::default(),
+                    ConnectionIdScheme::default(),
+                    &tracker,
+                )
+                .await
+                .unwrap();
 
                 let peers = tracker.get_torrent_peers(&info_hash.0.into());
 
@@ -893,10 +1229,19 @@ mod tests {
                 let remote_addr = SocketAddr::new(IpAddr::V6(client_ip_v6), 8080);
 
                 let request = AnnounceRequestBuilder::default()
-                    .with_connection_id(into_connection_id(&make(&remote_addr)))
+                    .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
                     .into();
 
-                let response = handle_announce(remote_addr, &request, &public_tracker()).await.unwrap();
+                let response = handle_announce(
+                    remote_addr,
+                    &request,
+                    None,
+                    PeersInResponse::default(),
+                    ConnectionIdScheme::default(),
+                    &public_tracker(),
+                )
+                .await
+                .unwrap();
 
                 let empty_peer_vector: Vec<ResponsePeer<Ipv6AddrBytes>> = vec![];
                 assert_eq!(
@@ -932,14 +1277,23 @@ mod tests {
                 let remote_addr = SocketAddr::new(IpAddr::V6(remote_client_ip), remote_client_port);
 
                 let request = AnnounceRequestBuilder::default()
-                    .with_connection_id(into_connection_id(&make(&remote_addr)))
+                    .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
                     .with_info_hash(info_hash)
                     .with_peer_id(peer_id)
                     .with_ip_address(peer_address)
                     .with_port(client_port)
                     .into();
 
-                handle_announce(remote_addr, &request, &tracker).await.unwrap();
+                handle_announce(
+                    remote_addr,
+                    &request,
+                    None,
+                    PeersInResponse::default(),
+                    ConnectionIdScheme::default(),
+                    &tracker,
+                )
+                .await
+                .unwrap();
 
                 let peers = tracker.get_torrent_peers(&info_hash.0.into());
 
@@ -947,7 +1301,7 @@ mod tests {
                 assert_eq!(peers[0].peer_addr, SocketAddr::new(IpAddr::V6(remote_client_ip), client_port));
             }
 
-            fn add_a_torrent_peer_using_ipv4(tracker: &Arc<core::Tracker>) {
+            async fn add_a_torrent_peer_using_ipv4(tracker: &Arc<core::Tracker>) {
                 let info_hash = AquaticInfoHash([0u8; 20]);
 
                 let client_ip_v4 = Ipv4Addr::new(126, 0, 0, 1);
@@ -959,7 +1313,7 @@ mod tests {
                     .with_peer_address(SocketAddr::new(IpAddr::V4(client_ip_v4), client_port))
                     .into();
 
-                tracker.upsert_peer_and_get_stats(&info_hash.0.into(), &peer_using_ipv4);
+                tracker.upsert_peer_and_get_stats(&info_hash.0.into(), &peer_using_ipv4).await;
             }
 
             async fn announce_a_new_peer_using_ipv6(tracker: Arc<core::Tracker>) -> Response {
@@ -968,17 +1322,26 @@ mod tests {
                 let client_port = 8080;
                 let remote_addr = SocketAddr::new(IpAddr::V6(client_ip_v6), client_port);
                 let request = AnnounceRequestBuilder::default()
-                    .with_connection_id(into_connection_id(&make(&remote_addr)))
+                    .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
                     .into();
 
-                handle_announce(remote_addr, &request, &tracker).await.unwrap()
+                handle_announce(
+                    remote_addr,
+                    &request,
+                    None,
+                    PeersInResponse::default(),
+                    ConnectionIdScheme::default(),
+                    &tracker,
+                )
+                .await
+                .unwrap()
             }
 
             #[tokio::test]
             async fn when_the_announce_request_comes_from_a_client_using_ipv6_the_response_should_not_include_peers_using_ipv4() {
                 let tracker = public_tracker();
 
-                add_a_torrent_peer_using_ipv4(&tracker);
+                add_a_torrent_peer_using_ipv4(&tracker).await;
 
                 let response = announce_a_new_peer_using_ipv6(tracker.clone()).await;
 
@@ -1013,10 +1376,19 @@ mod tests {
                 let remote_addr = sample_ipv6_remote_addr();
 
                 let announce_request = AnnounceRequestBuilder::default()
-                    .with_connection_id(into_connection_id(&make(&remote_addr)))
+                    .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
                     .into();
 
-                handle_announce(remote_addr, &announce_request, &tracker).await.unwrap();
+                handle_announce(
+                    remote_addr,
+                    &announce_request,
+                    None,
+                    PeersInResponse::default(),
+                    ConnectionIdScheme::default(),
+                    &tracker,
+                )
+                .await
+                .unwrap();
             }
 
             mod from_a_loopback_ip {
@@ -1024,13 +1396,14 @@ mod tests {
                 use std::sync::Arc;
 
                 use aquatic_udp_protocol::{InfoHash as AquaticInfoHash, PeerId as AquaticPeerId};
+                use torrust_tracker_configuration::ConnectionIdScheme;
 
                 use crate::core;
                 use crate::core::statistics::Keeper;
                 use crate::servers::udp::connection_cookie::{into_connection_id, make};
-                use crate::servers::udp::handlers::handle_announce;
                 use crate::servers::udp::handlers::tests::announce_request::AnnounceRequestBuilder;
                 use crate::servers::udp::handlers::tests::TrackerConfigurationBuilder;
+                use crate::servers::udp::handlers::{handle_announce, PeersInResponse};
 
                 #[tokio::test]
                 async fn the_peer_ip_should_be_changed_to_the_external_ip_in_the_tracker_configuration() {
@@ -1052,14 +1425,23 @@ mod tests {
                     let remote_addr = SocketAddr::new(IpAddr::V6(client_ip_v6), client_port);
 
                     let request = AnnounceRequestBuilder::default()
-                        .with_connection_id(into_connection_id(&make(&remote_addr)))
+                        .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
                         .with_info_hash(info_hash)
                         .with_peer_id(peer_id)
                         .with_ip_address(client_ip_v4)
                         .with_port(client_port)
                         .into();
 
-                    handle_announce(remote_addr, &request, &tracker).await.unwrap();
+                    handle_announce(
+                        remote_addr,
+                        &request,
+                        None,
+                        PeersInResponse::default(),
+                        ConnectionIdScheme::default(),
+                        &tracker,
+                    )
+                    .await
+                    .unwrap();
 
                     let peers = tracker.get_torrent_peers(&info_hash.0.into());
 
@@ -1076,6 +1458,275 @@ mod tests {
                 }
             }
         }
+
+        mod using_a_dual_stack_socket {
+
+            use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+            use aquatic_udp_protocol::{InfoHash as AquaticInfoHash, Request, Response};
+            use torrust_tracker_configuration::ConnectionIdScheme;
+
+            use crate::servers::udp::connection_cookie::{into_connection_id, make};
+            use crate::servers::udp::handlers::tests::announce_request::AnnounceRequestBuilder;
+            use crate::servers::udp::handlers::tests::{public_tracker, sample_ipv6_socket_address};
+            use crate::servers::udp::handlers::{handle_packet, PeersInResponse};
+            use crate::servers::udp::RawRequest;
+
+            #[tokio::test]
+            async fn ipv4_clients_should_be_handled_as_ipv4_clients_and_get_the_ipv4_peer_list() {
+                let tracker = public_tracker();
+
+                let client_ip = Ipv4Addr::new(126, 0, 0, 1);
+                let client_port = 8080;
+                let info_hash = AquaticInfoHash([0u8; 20]);
+
+                // Dual-stack sockets receive the packets from IPv4 clients with
+                // IPv4-mapped IPv6 addresses (::ffff:126.0.0.1)
+                let remote_addr = SocketAddr::new(IpAddr::V6(client_ip.to_ipv6_mapped()), client_port);
+
+                let request = AnnounceRequestBuilder::default()
+                    .with_connection_id(into_connection_id(&make(
+                        &SocketAddr::new(IpAddr::V4(client_ip), client_port),
+                        ConnectionIdScheme::default(),
+                    )))
+                    .with_info_hash(info_hash)
+                    .into();
+
+                let mut payload = vec![];
+                Request::Announce(request).write_bytes(&mut payload).unwrap();
+
+                let response = handle_packet(
+                    RawRequest {
+                        payload,
+                        from: remote_addr,
+                    },
+                    &tracker,
+                    sample_ipv6_socket_address(),
+                    PeersInResponse::default(),
+                    0,
+                    ConnectionIdScheme::default(),
+                )
+                .await;
+
+                assert!(matches!(response, Response::AnnounceIpv4(_)));
+
+                let peers = tracker.get_torrent_peers(&info_hash.0.into());
+
+                assert_eq!(peers[0].peer_addr.ip(), IpAddr::V4(client_ip));
+            }
+        }
+
+        mod with_a_private_tracker {
+
+            use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+            use aquatic_udp_protocol::{InfoHash as AquaticInfoHash, Request, Response};
+            use torrust_tracker_configuration::ConnectionIdScheme;
+
+            use crate::servers::udp::connection_cookie::{into_connection_id, make};
+            use crate::servers::udp::handlers::tests::announce_request::AnnounceRequestBuilder;
+            use crate::servers::udp::handlers::tests::{private_tracker, sample_ipv4_socket_address};
+            use crate::servers::udp::handlers::{handle_packet, PeersInResponse};
+            use crate::servers::udp::RawRequest;
+
+            fn announce_packet(remote_addr: &SocketAddr, info_hash: AquaticInfoHash, url_data: Option<&str>) -> Vec<u8> {
+                let request = AnnounceRequestBuilder::default()
+                    .with_connection_id(into_connection_id(&make(remote_addr, ConnectionIdScheme::default())))
+                    .with_info_hash(info_hash)
+                    .into();
+
+                let mut payload = vec![];
+                Request::Announce(request).write_bytes(&mut payload).unwrap();
+
+                // BEP 41 URLData option
+                if let Some(url_data) = url_data {
+                    payload.push(0x2);
+                    payload.push(u8::try_from(url_data.len()).unwrap());
+                    payload.extend_from_slice(url_data.as_bytes());
+                }
+
+                payload
+            }
+
+            #[tokio::test]
+            async fn it_should_authenticate_the_peer_with_the_key_in_the_url_data_option() {
+                let tracker = private_tracker();
+
+                let peer_key = tracker.generate_auth_key(None).await.unwrap();
+
+                let remote_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)), 8080);
+                let info_hash = AquaticInfoHash([0u8; 20]);
+
+                let response = handle_packet(
+                    RawRequest {
+                        payload: announce_packet(&remote_addr, info_hash, Some(&format!("/announce/{}", peer_key.key))),
+                        from: remote_addr,
+                    },
+                    &tracker,
+                    sample_ipv4_socket_address(),
+                    PeersInResponse::default(),
+                    0,
+                    ConnectionIdScheme::default(),
+                )
+                .await;
+
+                assert!(matches!(response, Response::AnnounceIpv4(_)));
+                assert_eq!(tracker.get_torrent_peers(&info_hash.0.into()).len(), 1);
+            }
+
+            #[tokio::test]
+            async fn it_should_reject_the_announce_when_the_url_data_option_does_not_include_the_key() {
+                let tracker = private_tracker();
+
+                let remote_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)), 8080);
+                let info_hash = AquaticInfoHash([0u8; 20]);
+
+                let response = handle_packet(
+                    RawRequest {
+                        payload: announce_packet(&remote_addr, info_hash, Some("/announce")),
+                        from: remote_addr,
+                    },
+                    &tracker,
+                    sample_ipv4_socket_address(),
+                    PeersInResponse::default(),
+                    0,
+                    ConnectionIdScheme::default(),
+                )
+                .await;
+
+                assert!(matches!(response, Response::Error(_)));
+                assert!(tracker.get_torrent_peers(&info_hash.0.into()).is_empty());
+            }
+        }
+
+        mod with_a_minimum_peer_port {
+
+            use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+            use aquatic_udp_protocol::{InfoHash as AquaticInfoHash, Request, Response};
+            use torrust_tracker_configuration::ConnectionIdScheme;
+
+            use crate::servers::udp::connection_cookie::{into_connection_id, make};
+            use crate::servers::udp::handlers::tests::announce_request::AnnounceRequestBuilder;
+            use crate::servers::udp::handlers::tests::{public_tracker, sample_ipv4_socket_address};
+            use crate::servers::udp::handlers::{handle_packet, PeersInResponse};
+            use crate::servers::udp::RawRequest;
+
+            async fn announce(peer_port: u16, min_peer_port: u16) -> (Response, usize) {
+                let tracker = public_tracker();
+
+                let remote_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)), 8080);
+                let info_hash = AquaticInfoHash([0u8; 20]);
+
+                let request = AnnounceRequestBuilder::default()
+                    .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
+                    .with_info_hash(info_hash)
+                    .with_port(peer_port)
+                    .into();
+
+                let mut payload = vec![];
+                Request::Announce(request).write_bytes(&mut payload).unwrap();
+
+                let response = handle_packet(
+                    RawRequest {
+                        payload,
+                        from: remote_addr,
+                    },
+                    &tracker,
+                    sample_ipv4_socket_address(),
+                    PeersInResponse::default(),
+                    min_peer_port,
+                    ConnectionIdScheme::default(),
+                )
+                .await;
+
+                (response, tracker.get_torrent_peers(&info_hash.0.into()).len())
+            }
+
+            #[tokio::test]
+            async fn it_should_reject_the_announce_when_the_peer_port_is_lower_than_the_minimum() {
+                let (response, peers) = announce(80, 1024).await;
+
+                assert!(matches!(response, Response::Error(_)));
+                assert_eq!(peers, 0);
+            }
+
+            #[tokio::test]
+            async fn it_should_accept_the_announce_when_the_peer_port_is_not_lower_than_the_minimum() {
+                let (response, peers) = announce(1024, 1024).await;
+
+                assert!(matches!(response, Response::AnnounceIpv4(_)));
+                assert_eq!(peers, 1);
+            }
+        }
+
+        mod returning_the_client_ip {
+
+            use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+            use aquatic_udp_protocol::{Ipv4AddrBytes, Port, Response, ResponsePeer};
+            use torrust_tracker_configuration::ConnectionIdScheme;
+
+            use crate::servers::udp::connection_cookie::{into_connection_id, make};
+            use crate::servers::udp::handlers::tests::announce_request::AnnounceRequestBuilder;
+            use crate::servers::udp::handlers::tests::public_tracker;
+            use crate::servers::udp::handlers::{handle_announce, PeersInResponse};
+
+            async fn announce_from(client_ip: Ipv4Addr, return_client_ip: bool) -> Vec<ResponsePeer<Ipv4AddrBytes>> {
+                let remote_addr = SocketAddr::new(IpAddr::V4(client_ip), 8081);
+
+                let request = AnnounceRequestBuilder::default()
+                    .with_connection_id(into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())))
+                    .with_port(8080)
+                    .into();
+
+                let response = handle_announce(
+                    remote_addr,
+                    &request,
+                    None,
+                    PeersInResponse {
+                        client: return_client_ip,
+                        ..PeersInResponse::default()
+                    },
+                    ConnectionIdScheme::default(),
+                    &public_tracker(),
+                )
+                .await
+                .unwrap();
+
+                match response {
+                    Response::AnnounceIpv4(announce_response) => announce_response.peers,
+                    _ => panic!("it should return an IPv4 announce response"),
+                }
+            }
+
+            #[tokio::test]
+            async fn it_should_return_the_client_address_as_the_first_peer_when_enabled() {
+                let peers = announce_from(Ipv4Addr::new(126, 0, 0, 1), true).await;
+
+                assert_eq!(
+                    peers,
+                    vec![ResponsePeer::<Ipv4AddrBytes> {
+                        ip_address: Ipv4Addr::new(126, 0, 0, 1).into(),
+                        port: Port(8080u16.into()),
+                    }]
+                );
+            }
+
+            #[tokio::test]
+            async fn it_should_return_the_observed_ip_even_if_the_tracker_replaces_the_peer_ip() {
+                let peers = announce_from(Ipv4Addr::LOCALHOST, true).await;
+
+                assert_eq!(peers[0].ip_address, Ipv4Addr::LOCALHOST.into());
+            }
+
+            #[tokio::test]
+            async fn it_should_not_return_the_client_address_when_disabled() {
+                let peers = announce_from(Ipv4Addr::new(126, 0, 0, 1), false).await;
+
+                assert!(peers.is_empty());
+            }
+        }
     }
 
     mod scrape_request {
@@ -1086,6 +1737,7 @@ mod tests {
             InfoHash, NumberOfDownloads, NumberOfPeers, PeerId, Response, ScrapeRequest, ScrapeResponse, TorrentScrapeStatistics,
             TransactionId,
         };
+        use torrust_tracker_configuration::ConnectionIdScheme;
 
         use super::TorrentPeerBuilder;
         use crate::core::{self};
@@ -1109,7 +1761,7 @@ mod tests {
             let info_hashes = vec![info_hash];
 
             let request = ScrapeRequest {
-                connection_id: into_connection_id(&make(&remote_addr)),
+                connection_id: into_connection_id(&make(&remote_addr, ConnectionIdScheme::default())),
                 transaction_id: TransactionId(0i32.into()),
                 info_hashes,
             };
@@ -1136,14 +1788,14 @@ mod tests {
                 .with_number_of_bytes_left(0)
                 .into();
 
-            tracker.upsert_peer_and_get_stats(&info_hash.0.into(), &peer);
+            tracker.upsert_peer_and_get_stats(&info_hash.0.into(), &peer).await;
         }
 
         fn build_scrape_request(remote_addr: &SocketAddr, info_hash: &InfoHash) -> ScrapeRequest {
             let info_hashes = vec![*info_hash];
 
             ScrapeRequest {
-                connection_id: into_connection_id(&make(remote_addr)),
+                connection_id: into_connection_id(&make(remote_addr, ConnectionIdScheme::default())),
                 transaction_id: TransactionId::new(0i32),
                 info_hashes,
             }
@@ -1285,7 +1937,7 @@ mod tests {
             let info_hashes = vec![info_hash];
 
             ScrapeRequest {
-                connection_id: into_connection_id(&make(remote_addr)),
+                connection_id: into_connection_id(&make(remote_addr, ConnectionIdScheme::default())),
                 transaction_id: TransactionId(0i32.into()),
                 info_hashes,
             }
@@ -1365,4 +2017,58 @@ mod tests {
             }
         }
     }
+
+    mod peers_in_response {
+        use crate::core::PeersWanted;
+        use crate::servers::udp::handlers::PeersInResponse;
+
+        fn peers_in_response() -> PeersInResponse {
+            PeersInResponse {
+                default: 30,
+                max: 50,
+                client: false,
+            }
+        }
+
+        #[test]
+        fn it_should_clamp_the_number_of_peers_wanted_to_the_maximum() {
+            assert_eq!(peers_in_response().peers_wanted(10), PeersWanted::only(10));
+            assert_eq!(peers_in_response().peers_wanted(200), PeersWanted::only(50));
+        }
+
+        #[test]
+        fn it_should_use_the_default_when_the_client_does_not_have_a_preference() {
+            assert_eq!(peers_in_response().peers_wanted(-1), PeersWanted::only(30));
+            assert_eq!(peers_in_response().peers_wanted(0), PeersWanted::only(30));
+        }
+    }
+
+    mod rate_limited_packet {
+        use aquatic_udp_protocol::{ConnectRequest, Request, Response, TransactionId};
+
+        use crate::servers::udp::handlers::handle_rate_limited_packet;
+        use crate::servers::udp::handlers::tests::{public_tracker, sample_ipv4_remote_addr};
+        use crate::servers::udp::RawRequest;
+
+        #[tokio::test]
+        async fn it_should_return_an_error_response_with_the_transaction_id_of_the_request() {
+            let transaction_id = TransactionId(123i32.into());
+
+            let mut payload = vec![];
+            Request::Connect(ConnectRequest { transaction_id })
+                .write_bytes(&mut payload)
+                .unwrap();
+
+            let response = handle_rate_limited_packet(
+                &RawRequest {
+                    payload,
+                    from: sample_ipv4_remote_addr(),
+                },
+                &public_tracker(),
+            )
+            .await;
+
+            assert!(matches!(response, Response::Error(error) if error.transaction_id == transaction_id));
+        }
+    }
 }
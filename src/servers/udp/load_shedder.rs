@@ -0,0 +1,140 @@
+//! Load shedding for the UDP tracker.
+//!
+//! The shedder counts the packets in process by all the workers of a tracker.
+//! While the tracker is loaded, the `scrape` packets are dropped before
+//! parsing them, so the `connect` and `announce` requests, which keep the
+//! swarms alive, are answered in time during floods instead of degrading all
+//! the requests equally.
+//!
+//! Refer to the [`UdpLoadShedding`] configuration for more information.
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use torrust_tracker_configuration::UdpLoadShedding;
+
+use crate::core::statistics::RequestKind;
+use crate::servers::udp::handlers::request_kind;
+
+/// Counter of the packets in process by a tracker.
+#[derive(Debug)]
+pub struct LoadShedder {
+    max_queue_depth: Option<u64>,
+    shed_when_overloaded: bool,
+    queue_depth: AtomicU64,
+}
+
+/// A packet in process. It's no longer counted when it's dropped.
+#[derive(Debug)]
+pub struct QueuedPacket {
+    shedder: Arc<LoadShedder>,
+}
+
+impl Drop for QueuedPacket {
+    fn drop(&mut self) {
+        self.shedder.queue_depth.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl LoadShedder {
+    #[must_use]
+    pub fn new(config: &UdpLoadShedding) -> Self {
+        Self {
+            max_queue_depth: config.max_queue_depth.map(u64::from),
+            shed_when_overloaded: config.shed_when_overloaded,
+            queue_depth: AtomicU64::new(0),
+        }
+    }
+
+    /// It counts a new packet in process, until the returned value is
+    /// dropped.
+    #[must_use]
+    pub fn enqueue(self: &Arc<Self>) -> QueuedPacket {
+        self.queue_depth.fetch_add(1, Ordering::Relaxed);
+
+        QueuedPacket { shedder: self.clone() }
+    }
+
+    /// Returns `true` if the packet should be dropped: it's a `scrape`
+    /// request and the tracker is loaded.
+    ///
+    /// The `overloaded` flag is the state of the tracker overload control.
+    #[must_use]
+    pub fn should_shed(&self, payload: &[u8], overloaded: bool) -> bool {
+        self.is_loaded(overloaded) && request_kind(payload) == Some(RequestKind::Scrape)
+    }
+
+    fn is_loaded(&self, overloaded: bool) -> bool {
+        (self.shed_when_overloaded && overloaded)
+            || self
+                .max_queue_depth
+                .is_some_and(|max_queue_depth| self.queue_depth.load(Ordering::Relaxed) >= max_queue_depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use torrust_tracker_configuration::UdpLoadShedding;
+
+    use super::LoadShedder;
+
+    /// A packet with the action of the request (bytes 8 to 11).
+    fn packet(action: u8) -> Vec<u8> {
+        let mut payload = vec![0u8; 16];
+        payload[11] = action;
+        payload
+    }
+
+    fn scrape_packet() -> Vec<u8> {
+        packet(2)
+    }
+
+    fn announce_packet() -> Vec<u8> {
+        packet(1)
+    }
+
+    fn load_shedder(max_queue_depth: Option<u32>, shed_when_overloaded: bool) -> Arc<LoadShedder> {
+        Arc::new(LoadShedder::new(&UdpLoadShedding {
+            max_queue_depth,
+            shed_when_overloaded,
+        }))
+    }
+
+    #[test]
+    fn it_should_shed_the_scrape_packets_when_the_queue_is_full() {
+        let load_shedder = load_shedder(Some(2), false);
+
+        let _first = load_shedder.enqueue();
+
+        assert!(!load_shedder.should_shed(&scrape_packet(), false));
+
+        let _second = load_shedder.enqueue();
+
+        assert!(load_shedder.should_shed(&scrape_packet(), false));
+    }
+
+    #[test]
+    fn it_should_not_shed_the_announce_packets() {
+        let load_shedder = load_shedder(Some(1), true);
+
+        let _queued = load_shedder.enqueue();
+
+        assert!(!load_shedder.should_shed(&announce_packet(), true));
+    }
+
+    #[test]
+    fn it_should_stop_shedding_when_the_packets_in_process_are_handled() {
+        let load_shedder = load_shedder(Some(1), false);
+
+        drop(load_shedder.enqueue());
+
+        assert!(!load_shedder.should_shed(&scrape_packet(), false));
+    }
+
+    #[test]
+    fn it_should_shed_the_scrape_packets_while_the_tracker_is_overloaded_if_enabled() {
+        assert!(load_shedder(None, true).should_shed(&scrape_packet(), true));
+        assert!(!load_shedder(None, false).should_shed(&scrape_packet(), true));
+    }
+}
@@ -1,5 +1,6 @@
 //! Logging for UDP Tracker requests and responses.
 
+use std::fmt::Write;
 use std::net::SocketAddr;
 use std::time::Duration;
 
@@ -85,3 +86,71 @@ pub fn log_error_response(request_id: &RequestId) {
         target: UDP_TRACKER_LOG_TARGET,
         tracing::Level::INFO, "response", request_id = %request_id);
 }
+
+/// It logs a malformed or rejected packet, with a hex dump of the payload.
+///
+/// Only used when the `debug_capture` option of the UDP tracker is enabled.
+pub fn log_rejected_packet(payload: &[u8], from: &SocketAddr, server_socket_addr: &SocketAddr, reason: &str) {
+    tracing::info!(
+        target: UDP_TRACKER_LOG_TARGET,
+        server_socket_addr = %server_socket_addr,
+        from = %from,
+        bytes_count = payload.len(),
+        reason,
+        "rejected packet:\n{}",
+        hex_dump(payload));
+}
+
+/// It formats the bytes in lines of 16, with the offset of the line, the
+/// bytes in hex and the printable ASCII characters:
+///
+/// ```text
+/// 0000  00 00 04 17 27 10 19 80 00 00 00 00 12 34 56 78  ....'........4Vx
+/// ```
+fn hex_dump(payload: &[u8]) -> String {
+    let mut dump = String::with_capacity(payload.len().div_ceil(16) * 72);
+
+    for (line, chunk) in payload.chunks(16).enumerate() {
+        let _ = write!(dump, "{:04x} ", line * 16);
+
+        for byte in chunk {
+            let _ = write!(dump, " {byte:02x}");
+        }
+
+        let padding = (16 - chunk.len()) * 3 + 2;
+        dump.push_str(&" ".repeat(padding));
+
+        dump.extend(chunk.iter().map(|byte| {
+            if byte.is_ascii_graphic() || *byte == b' ' {
+                char::from(*byte)
+            } else {
+                '.'
+            }
+        }));
+
+        dump.push('\n');
+    }
+
+    dump
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hex_dump;
+
+    #[test]
+    fn it_should_dump_the_bytes_in_lines_of_sixteen() {
+        let payload = b"\x00\x00\x04\x17\x27\x10\x19\x80\x00\x00\x00\x00\x12\x34\x56\x78abc";
+
+        assert_eq!(
+            hex_dump(payload),
+            "0000  00 00 04 17 27 10 19 80 00 00 00 00 12 34 56 78  ....'........4Vx\n\
+             0010  61 62 63                                         abc\n"
+        );
+    }
+
+    #[test]
+    fn it_should_return_an_empty_dump_for_an_empty_payload() {
+        assert_eq!(hex_dump(&[]), "");
+    }
+}
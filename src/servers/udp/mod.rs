@@ -27,6 +27,12 @@
 //! > **NOTICE**: we are using the [`aquatic_udp_protocol`](https://crates.io/crates/aquatic_udp_protocol)
 //! > crate so requests and responses are handled by it.
 //!
+//! > **NOTICE**: packets are received and sent in batches, with `recvmmsg` and
+//! > `sendmmsg` on Linux. Refer to the [`batch`](crate::servers::udp::server::batch)
+//! > module for more information. Builds with the `io-uring` cargo feature can
+//! > use an `io_uring` backend instead, enabled with the `udp_io_backend`
+//! > option of the UDP tracker.
+//!
 //! > **NOTICE**: all values are send in network byte order ([big endian](https://en.wikipedia.org/wiki/Endianness)).
 //!
 //! ## Table of Contents
@@ -316,9 +322,10 @@
 //! > **NOTICE**: bytes after offset 98 are part of the [BEP-41. UDP Tracker Protocol Extensions](https://www.bittorrent.org/beps/bep_0041.html).
 //! > There are three options defined for byte 98: `0x0` (`EndOfOptions`), `0x1` (`NOP`) and `0x2` (`URLData`).
 //!
-//! > **NOTICE**: `num_want` is being ignored by the tracker. Refer to
-//! > [issue 262](https://github.com/torrust/torrust-tracker/issues/262) for more
-//! > information.
+//! > **NOTICE**: `num_want` is clamped to the `max_peers_in_response` option
+//! > of the UDP tracker. When it's not set (`0` or `-1`) the tracker returns
+//! > `default_peers_in_response` peers. Both options default to `74`. Refer to
+//! > [`PeersInResponse`](crate::servers::udp::handlers::PeersInResponse).
 //!
 //! **Announce request (parsed struct)**
 //!
@@ -400,6 +407,11 @@
 //! underlying UDP packet. I.e. packets from a v4 address use the v4 format,
 //! those from a v6 address use the v6 format.
 //!
+//! UDP trackers bound to an `IPv6` address (for example, `[::]:6969`) are
+//! dual-stack: they also receive packets from `IPv4` clients, as
+//! `IPv4`-mapped `IPv6` addresses (`::ffff:a.b.c.d`). Those clients are
+//! handled as `IPv4` clients and get the `IPv4` format.
+//!
 //! UDP packet bytes (`IPv4` peer list):
 //!
 //! ```text
@@ -618,8 +630,10 @@
 //!
 //! ## Extensions
 //!
-//! Extensions described in [BEP 41. UDP Tracker Protocol Extensions](https://www.bittorrent.org/beps/bep_0041.html)
-//! are not supported yet.
+//! The `URLData` option described in [BEP 41. UDP Tracker Protocol Extensions](https://www.bittorrent.org/beps/bep_0041.html)
+//! is supported in announce requests. Private trackers read the
+//! authentication key from it. Refer to the [`url_data`] module for more
+//! information.
 //!
 //! ## Links
 //!
@@ -641,9 +655,12 @@ use std::net::SocketAddr;
 pub mod connection_cookie;
 pub mod error;
 pub mod handlers;
+pub mod load_shedder;
 pub mod logging;
 pub mod peer_builder;
+pub mod rate_limiter;
 pub mod server;
+pub mod url_data;
 
 pub const UDP_TRACKER_LOG_TARGET: &str = "UDP TRACKER";
 
@@ -1,5 +1,6 @@
 //! Logic to extract the peer info from the announce request.
 use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
 
 use torrust_tracker_clock::clock::Time;
 use torrust_tracker_primitives::peer;
@@ -22,5 +23,7 @@ pub fn from_request(announce_request: &aquatic_udp_protocol::AnnounceRequest, pe
         downloaded: announce_request.bytes_downloaded,
         left: announce_request.bytes_left,
         event: announce_request.event.into(),
+        timeout_extension: Duration::ZERO,
+        alt_peer_addr: None,
     }
 }
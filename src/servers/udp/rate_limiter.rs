@@ -0,0 +1,103 @@
+//! Per-IP rate limiter for the UDP tracker.
+//!
+//! Each source IP address has a token bucket refilled at the configured
+//! packets per second. Every received packet takes one token, and packets
+//! received with an empty bucket exceed the limit. The limiter is checked
+//! before parsing the packets, so misbehaving clients flooding the tracker
+//! cost as little as possible. The number of IP addresses tracked is bounded,
+//! because the source IP addresses of UDP packets can be spoofed. Refer to
+//! the [`token_buckets`](crate::servers::token_buckets) module.
+//!
+//! Refer to the [`UdpRateLimit`] configuration for more information.
+use std::net::IpAddr;
+
+use torrust_tracker_configuration::{UdpRateLimit, UdpRateLimitAction};
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+use crate::servers::token_buckets::TokenBuckets;
+
+/// A token bucket rate limiter keyed by IP address.
+#[derive(Debug)]
+pub struct RateLimiter {
+    action: UdpRateLimitAction,
+    buckets: TokenBuckets<IpAddr>,
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new(config: &UdpRateLimit) -> Self {
+        Self {
+            action: config.action,
+            buckets: TokenBuckets::new(
+                f64::from(config.packets_per_second),
+                f64::from(config.burst.unwrap_or(config.packets_per_second)),
+            ),
+        }
+    }
+
+    /// What to do with the packets exceeding the limit.
+    #[must_use]
+    pub fn action(&self) -> UdpRateLimitAction {
+        self.action
+    }
+
+    /// It takes a token for a packet from the IP address. It returns `false`
+    /// if the packet exceeds the limit.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the buckets is poisoned.
+    pub fn try_acquire(&self, ip: &IpAddr, now: DurationSinceUnixEpoch) -> bool {
+        self.buckets.try_acquire(ip, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Duration;
+
+    use torrust_tracker_configuration::{UdpRateLimit, UdpRateLimitAction};
+
+    use super::RateLimiter;
+
+    fn ip(last_octet: u8) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(126, 0, 0, last_octet))
+    }
+
+    fn rate_limiter(packets_per_second: u32, burst: Option<u32>) -> RateLimiter {
+        RateLimiter::new(&UdpRateLimit {
+            packets_per_second,
+            burst,
+            action: UdpRateLimitAction::Drop,
+        })
+    }
+
+    #[test]
+    fn it_should_reject_the_packets_exceeding_the_burst() {
+        let rate_limiter = rate_limiter(1, Some(2));
+
+        assert!(rate_limiter.try_acquire(&ip(1), Duration::ZERO));
+        assert!(rate_limiter.try_acquire(&ip(1), Duration::ZERO));
+        assert!(!rate_limiter.try_acquire(&ip(1), Duration::ZERO));
+    }
+
+    #[test]
+    fn it_should_limit_each_ip_address_independently() {
+        let rate_limiter = rate_limiter(1, None);
+
+        assert!(rate_limiter.try_acquire(&ip(1), Duration::ZERO));
+        assert!(!rate_limiter.try_acquire(&ip(1), Duration::ZERO));
+        assert!(rate_limiter.try_acquire(&ip(2), Duration::ZERO));
+    }
+
+    #[test]
+    fn it_should_accept_packets_again_after_the_bucket_is_refilled() {
+        let rate_limiter = rate_limiter(2, None);
+
+        assert!(rate_limiter.try_acquire(&ip(1), Duration::ZERO));
+        assert!(rate_limiter.try_acquire(&ip(1), Duration::ZERO));
+        assert!(!rate_limiter.try_acquire(&ip(1), Duration::ZERO));
+        assert!(rate_limiter.try_acquire(&ip(1), Duration::from_millis(500)));
+    }
+}
@@ -0,0 +1,272 @@
+//! Batched UDP I/O.
+//!
+//! Receiving or sending one packet per system call limits the throughput of
+//! busy trackers. On Linux, the packets are received with `recvmmsg` and sent
+//! with `sendmmsg`, up to [`BATCH_SIZE`] packets per call. On other systems
+//! the batches are received and sent one packet at a time, with non-blocking
+//! calls, until the socket would block.
+//!
+//! Like [`try_recv_from`](tokio::net::UdpSocket::try_recv_from), the
+//! functions in this module never wait for the socket. They return a
+//! [`WouldBlock`](std::io::ErrorKind::WouldBlock) error when no packet can be
+//! received or sent without blocking, so they are meant to be called after
+//! the socket has become ready.
+use std::net::SocketAddr;
+
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+
+use crate::shared::bit_torrent::tracker::udp::MAX_PACKET_SIZE;
+
+/// Maximum number of packets received or sent in a single call.
+pub const BATCH_SIZE: usize = 32;
+
+/// Pre-allocated buffers for a batch of received packets.
+pub struct RecvBatch {
+    buffers: Vec<[u8; MAX_PACKET_SIZE]>,
+    /// The index of the buffer, the length and the address of each packet
+    /// received. Not every buffer holds a packet to process: the packets from
+    /// addresses that aren't IP addresses are skipped.
+    received: Vec<(usize, usize, SocketAddr)>,
+}
+
+impl Default for RecvBatch {
+    fn default() -> Self {
+        Self {
+            buffers: vec![[0; MAX_PACKET_SIZE]; BATCH_SIZE],
+            received: Vec::with_capacity(BATCH_SIZE),
+        }
+    }
+}
+
+impl RecvBatch {
+    /// It returns the packets received in the last call to [`recv`], with
+    /// the address they were received from.
+    pub fn packets(&self) -> impl Iterator<Item = (&[u8], SocketAddr)> {
+        self.received
+            .iter()
+            .map(|(index, len, from)| (&self.buffers[*index][..*len], *from))
+    }
+}
+
+/// A packet waiting to be sent.
+#[derive(Debug)]
+pub struct Packet {
+    pub payload: Vec<u8>,
+    pub target: SocketAddr,
+}
+
+/// It receives a batch of packets into the pre-allocated buffers, and returns
+/// the number of packets received.
+///
+/// # Errors
+///
+/// Will return an error if no packet can be received.
+pub fn recv(socket: &UdpSocket, batch: &mut RecvBatch) -> std::io::Result<usize> {
+    batch.received.clear();
+
+    sys::recv(socket, batch)?;
+
+    Ok(batch.received.len())
+}
+
+/// It sends as many of the packets as possible, in order, and returns the
+/// number of packets sent.
+///
+/// # Errors
+///
+/// Will return an error if the first packet can't be sent.
+pub fn send(socket: &UdpSocket, packets: &[Packet]) -> std::io::Result<usize> {
+    sys::send(socket, &packets[..packets.len().min(BATCH_SIZE)])
+}
+
+/// It sends the packets queued in the channel until all the senders are
+/// dropped. The packets queued while the previous batch was being sent are
+/// sent together in the next batch.
+pub async fn run_sender(socket: &UdpSocket, mut queue: mpsc::Receiver<Packet>) {
+    let mut packets = Vec::with_capacity(BATCH_SIZE);
+
+    while queue.recv_many(&mut packets, BATCH_SIZE).await > 0 {
+        let mut sent = 0;
+
+        while sent < packets.len() {
+            if let Err(err) = socket.writable().await {
+                tracing::warn!(%err, "failed to wait for the socket to be writable");
+                break;
+            }
+
+            match send(socket, &packets[sent..]) {
+                Ok(count) => sent += count,
+                // The readiness was cleared, wait again.
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(err) => {
+                    // Doesn't matter if it reaches or not. The packet is skipped.
+                    tracing::warn!(target = %packets[sent].target, %err, "failed to send");
+                    sent += 1;
+                }
+            }
+        }
+
+        packets.clear();
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::os::fd::AsRawFd;
+
+    use socket2::SockAddr;
+    use tokio::io::Interest;
+    use tokio::net::UdpSocket;
+
+    use super::{Packet, RecvBatch, BATCH_SIZE};
+    use crate::shared::bit_torrent::tracker::udp::MAX_PACKET_SIZE;
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn recv(socket: &UdpSocket, batch: &mut RecvBatch) -> std::io::Result<()> {
+        // SAFETY: all-zero is a valid value for these C structs.
+        let mut addresses: [libc::sockaddr_storage; BATCH_SIZE] = unsafe { std::mem::zeroed() };
+        let mut headers: [libc::mmsghdr; BATCH_SIZE] = unsafe { std::mem::zeroed() };
+
+        let mut iovecs: Vec<libc::iovec> = batch
+            .buffers
+            .iter_mut()
+            .map(|buffer| libc::iovec {
+                iov_base: buffer.as_mut_ptr().cast(),
+                iov_len: MAX_PACKET_SIZE,
+            })
+            .collect();
+
+        for ((header, address), iovec) in headers.iter_mut().zip(addresses.iter_mut()).zip(iovecs.iter_mut()) {
+            header.msg_hdr.msg_name = std::ptr::from_mut(address).cast();
+            header.msg_hdr.msg_namelen = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+            header.msg_hdr.msg_iov = std::ptr::from_mut(iovec);
+            header.msg_hdr.msg_iovlen = 1;
+        }
+
+        let received = socket.try_io(Interest::READABLE, || {
+            // SAFETY: the headers point to buffers and addresses that outlive
+            // the call, with their actual sizes.
+            let received = unsafe {
+                libc::recvmmsg(
+                    socket.as_raw_fd(),
+                    headers.as_mut_ptr(),
+                    BATCH_SIZE as libc::c_uint,
+                    libc::MSG_DONTWAIT,
+                    std::ptr::null_mut(),
+                )
+            };
+
+            usize::try_from(received).map_err(|_| std::io::Error::last_os_error())
+        })?;
+
+        for (index, (header, address)) in headers.iter().zip(addresses).take(received).enumerate() {
+            // SAFETY: the kernel has written a valid address of that length.
+            let from = unsafe { SockAddr::new(address, header.msg_hdr.msg_namelen) };
+
+            if let Some(from) = from.as_socket() {
+                batch.received.push((index, header.msg_len as usize, from));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn send(socket: &UdpSocket, packets: &[Packet]) -> std::io::Result<usize> {
+        let targets: Vec<SockAddr> = packets.iter().map(|packet| SockAddr::from(packet.target)).collect();
+
+        let mut iovecs: Vec<libc::iovec> = packets
+            .iter()
+            .map(|packet| libc::iovec {
+                iov_base: packet.payload.as_ptr().cast_mut().cast(),
+                iov_len: packet.payload.len(),
+            })
+            .collect();
+
+        // SAFETY: all-zero is a valid value for this C struct.
+        let mut headers: Vec<libc::mmsghdr> = targets.iter().map(|_| unsafe { std::mem::zeroed() }).collect();
+
+        for ((header, target), iovec) in headers.iter_mut().zip(&targets).zip(iovecs.iter_mut()) {
+            header.msg_hdr.msg_name = target.as_ptr().cast_mut().cast();
+            header.msg_hdr.msg_namelen = target.len();
+            header.msg_hdr.msg_iov = std::ptr::from_mut(iovec);
+            header.msg_hdr.msg_iovlen = 1;
+        }
+
+        socket.try_io(Interest::WRITABLE, || {
+            // SAFETY: the headers point to payloads and addresses that outlive
+            // the call, with their actual sizes. The kernel does not write to them.
+            let sent = unsafe {
+                libc::sendmmsg(
+                    socket.as_raw_fd(),
+                    headers.as_mut_ptr(),
+                    headers.len() as libc::c_uint,
+                    libc::MSG_DONTWAIT,
+                )
+            };
+
+            usize::try_from(sent).map_err(|_| std::io::Error::last_os_error())
+        })
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use tokio::net::UdpSocket;
+
+    use super::{Packet, RecvBatch};
+
+    pub fn recv(socket: &UdpSocket, batch: &mut RecvBatch) -> std::io::Result<()> {
+        for (index, buffer) in batch.buffers.iter_mut().enumerate() {
+            match socket.try_recv_from(buffer) {
+                Ok((len, from)) => batch.received.push((index, len, from)),
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock && !batch.received.is_empty() => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn send(socket: &UdpSocket, packets: &[Packet]) -> std::io::Result<usize> {
+        let mut sent = 0;
+
+        for packet in packets {
+            match socket.try_send_to(&packet.payload, packet.target) {
+                Ok(_) => sent += 1,
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock && sent > 0 => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(sent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::RecvBatch;
+
+    #[test]
+    fn it_should_pair_each_received_packet_with_its_own_buffer_when_a_packet_is_skipped() {
+        let mut batch = RecvBatch::default();
+
+        let first: SocketAddr = "127.0.0.1:6881".parse().unwrap();
+        let third: SocketAddr = "127.0.0.1:6883".parse().unwrap();
+
+        batch.buffers[0][..5].copy_from_slice(b"first");
+        batch.buffers[1][..7].copy_from_slice(b"skipped");
+        batch.buffers[2][..5].copy_from_slice(b"third");
+
+        // The second packet was received from an address that is not an IP address
+        batch.received = vec![(0, 5, first), (2, 5, third)];
+
+        assert_eq!(
+            batch.packets().collect::<Vec<_>>(),
+            vec![(&b"first"[..], first), (&b"third"[..], third)]
+        );
+    }
+}
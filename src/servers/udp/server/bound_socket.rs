@@ -2,8 +2,10 @@ use std::fmt::Debug;
 use std::net::SocketAddr;
 use std::ops::Deref;
 
+use socket2::{Domain, Protocol, Socket, Type};
 use url::Url;
 
+use crate::servers::socket_activation;
 use crate::servers::udp::UDP_TRACKER_LOG_TARGET;
 
 /// Wrapper for Tokio [`UdpSocket`][`tokio::net::UdpSocket`] that is bound to a particular socket.
@@ -12,14 +14,21 @@ pub struct BoundSocket {
 }
 
 impl BoundSocket {
+    /// It binds a new socket to the address. With `reuse_port`, more sockets
+    /// can be bound to the same address, and the kernel load-balances the
+    /// received packets across them.
+    ///
     /// # Errors
     ///
     /// Will return an error if the socket can't be bound the the provided address.
-    pub async fn new(addr: SocketAddr) -> Result<Self, Box<std::io::Error>> {
+    pub async fn new(addr: SocketAddr, reuse_port: bool) -> Result<Self, Box<std::io::Error>> {
         let bind_addr = format!("udp://{addr}");
         tracing::debug!(target: UDP_TRACKER_LOG_TARGET, bind_addr, "UdpSocket::new (binding)");
 
-        let socket = tokio::net::UdpSocket::bind(addr).await;
+        let socket = match socket_activation::take_udp_socket(addr) {
+            Some(socket) => tokio::net::UdpSocket::from_std(socket),
+            None => bind(addr, reuse_port).and_then(tokio::net::UdpSocket::from_std),
+        };
 
         let socket = match socket {
             Ok(socket) => socket,
@@ -50,6 +59,42 @@ impl BoundSocket {
     }
 }
 
+/// It binds a new UDP socket to the address.
+///
+/// Sockets bound to an `IPv6` address are dual-stack: they also receive
+/// packets from `IPv4` clients, regardless of the operating system default.
+fn bind(addr: SocketAddr, reuse_port: bool) -> std::io::Result<std::net::UdpSocket> {
+    let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))?;
+
+    if addr.is_ipv6() {
+        if let Err(err) = socket.set_only_v6(false) {
+            tracing::warn!(target: UDP_TRACKER_LOG_TARGET, %addr, %err, "unable to accept IPv4 packets on IPv6 socket");
+        }
+    }
+
+    if reuse_port {
+        set_reuse_port(&socket)?;
+    }
+
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+
+    Ok(socket.into())
+}
+
+#[cfg(unix)]
+fn set_reuse_port(socket: &Socket) -> std::io::Result<()> {
+    socket.set_reuse_port(true)
+}
+
+#[cfg(not(unix))]
+fn set_reuse_port(_socket: &Socket) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "SO_REUSEPORT is only supported on Unix systems",
+    ))
+}
+
 impl Deref for BoundSocket {
     type Target = tokio::net::UdpSocket;
 
@@ -5,20 +5,32 @@ use std::time::Duration;
 use derive_more::Constructor;
 use futures_util::StreamExt;
 use tokio::select;
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc, oneshot, watch, Semaphore};
+use tokio::task::JoinSet;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_configuration::{ConnectionIdScheme, UdpIoBackend, UdpLoadShedding, UdpRateLimit, UdpRateLimitAction};
 use tracing::instrument;
 
+use super::batch::{self, Packet, BATCH_SIZE};
 use super::request_buffer::ActiveRequests;
 use crate::bootstrap::jobs::Started;
-use crate::core::Tracker;
+use crate::core::{statistics, Tracker};
 use crate::servers::logging::STARTED_ON;
 use crate::servers::registar::ServiceHealthCheckJob;
 use crate::servers::signals::{shutdown_signal_with_message, Halted};
+use crate::servers::udp::handlers::PeersInResponse;
+use crate::servers::udp::load_shedder::LoadShedder;
+use crate::servers::udp::rate_limiter::RateLimiter;
 use crate::servers::udp::server::bound_socket::BoundSocket;
 use crate::servers::udp::server::processor::Processor;
 use crate::servers::udp::server::receiver::Receiver;
 use crate::servers::udp::UDP_TRACKER_LOG_TARGET;
 use crate::shared::bit_torrent::tracker::udp::client::check;
+use crate::CurrentClock;
+
+/// Maximum number of responses waiting to be sent through each socket. When
+/// the queue is full, the workers wait before sending more responses.
+const RESPONSE_QUEUE_CAPACITY: usize = BATCH_SIZE * 32;
 
 /// A UDP server instance launcher.
 #[derive(Constructor)]
@@ -27,20 +39,55 @@ pub struct Launcher;
 impl Launcher {
     /// It starts the UDP server instance with graceful shutdown.
     ///
+    /// When the server is halted, it stops receiving packets and waits, up to
+    /// the `shutdown_timeout`, for the packets in process to be handled and
+    /// their responses sent. The requests still in process after the timeout
+    /// are aborted.
+    ///
     /// # Panics
     ///
     /// It panics if unable to bind to udp socket, and get the address from the udp socket.
     /// It also panics if unable to send address of socket.
-    #[instrument(skip(tracker, bind_to, tx_start, rx_halt))]
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip(
+        tracker,
+        bind_to,
+        max_inflight_packets,
+        workers,
+        reuse_port,
+        rate_limit,
+        udp_io_backend,
+        shutdown_timeout,
+        peers_in_response,
+        debug_capture,
+        min_peer_port,
+        connection_id_scheme,
+        load_shedding,
+        tx_start,
+        rx_halt
+    ))]
     pub async fn run_with_graceful_shutdown(
         tracker: Arc<Tracker>,
         bind_to: SocketAddr,
+        max_inflight_packets: Option<u32>,
+        workers: u32,
+        reuse_port: bool,
+        rate_limit: Option<UdpRateLimit>,
+        udp_io_backend: UdpIoBackend,
+        shutdown_timeout: Duration,
+        peers_in_response: PeersInResponse,
+        debug_capture: bool,
+        min_peer_port: u16,
+        connection_id_scheme: Option<ConnectionIdScheme>,
+        load_shedding: Option<UdpLoadShedding>,
         tx_start: oneshot::Sender<Started>,
         rx_halt: oneshot::Receiver<Halted>,
     ) {
         tracing::info!(target: UDP_TRACKER_LOG_TARGET, "Starting on: {bind_to}");
 
-        let socket = tokio::time::timeout(Duration::from_millis(5000), BoundSocket::new(bind_to))
+        let connection_id_scheme = connection_id_scheme.unwrap_or(tracker.get_udp_connection_id_scheme());
+
+        let socket = tokio::time::timeout(Duration::from_millis(5000), BoundSocket::new(bind_to, reuse_port))
             .await
             .expect("it should bind to the socket within five seconds");
 
@@ -57,15 +104,85 @@ impl Launcher {
 
         tracing::info!(target: UDP_TRACKER_LOG_TARGET, "{STARTED_ON}: {local_udp_url}");
 
-        let receiver = Receiver::new(bound_socket.into());
+        let bound_socket = Arc::new(bound_socket);
+
+        // With `SO_REUSEPORT` each worker receives from its own socket, bound
+        // to the same address as the first one. If a socket can't be bound,
+        // the remaining workers share the sockets already bound.
+        let mut sockets = vec![bound_socket];
+
+        if reuse_port {
+            for _ in 1..workers {
+                match BoundSocket::new(address, true).await {
+                    Ok(socket) => sockets.push(Arc::new(socket)),
+                    Err(e) => {
+                        tracing::warn!(target: UDP_TRACKER_LOG_TARGET, addr = %address, err = %e, "Udp::run_with_graceful_shutdown (unable to bind a reused port socket)");
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Permits for the packets in process, shared by all the workers. When
+        // there are no permits left the workers stop receiving until one of
+        // the packets is handled.
+        let inflight_packets =
+            max_inflight_packets.map(|max| Arc::new(Semaphore::new(usize::try_from(max).unwrap_or(Semaphore::MAX_PERMITS))));
 
-        tracing::trace!(target: UDP_TRACKER_LOG_TARGET, local_udp_url, "Udp::run_with_graceful_shutdown (spawning main loop)");
+        // Token buckets for the source IPs, shared by all the workers.
+        let rate_limiter = rate_limit.map(|rate_limit| Arc::new(RateLimiter::new(&rate_limit)));
+
+        // Counter of the packets in process, shared by all the workers.
+        let load_shedder = load_shedding.map(|load_shedding| Arc::new(LoadShedder::new(&load_shedding)));
+
+        // Signal for the workers to stop receiving packets when the server is
+        // halted.
+        let (tx_stop_receiving, rx_stop_receiving) = watch::channel(false);
+
+        let sockets_count = sockets.len();
+        tracing::trace!(target: UDP_TRACKER_LOG_TARGET, local_udp_url, workers, sockets_count, "Udp::run_with_graceful_shutdown (spawning main loops)");
 
         let running = {
             let local_addr = local_udp_url.clone();
             tokio::task::spawn(async move {
                 tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_addr, "Udp::run_with_graceful_shutdown::task (listening...)");
-                let () = Self::run_udp_server_main(receiver, tracker.clone()).await;
+
+                // Dropping the set (when this task is aborted) aborts all the workers.
+                let mut main_loops = JoinSet::new();
+
+                let workers = usize::try_from(workers.max(1)).unwrap_or(1);
+
+                for (index, socket) in sockets.iter().enumerate() {
+                    // The workers are spread evenly across the sockets.
+                    let socket_workers = workers / sockets_count + usize::from(index < workers % sockets_count);
+
+                    if socket_workers == 0 {
+                        continue;
+                    }
+
+                    let (receivers, responses) =
+                        Self::start_io(socket, socket_workers, udp_io_backend, &tracker, &mut main_loops);
+
+                    for receiver in receivers {
+                        main_loops.spawn(Self::run_udp_server_main(
+                            receiver,
+                            responses.clone(),
+                            tracker.clone(),
+                            inflight_packets.clone(),
+                            rate_limiter.clone(),
+                            load_shedder.clone(),
+                            peers_in_response,
+                            debug_capture,
+                            min_peer_port,
+                            connection_id_scheme,
+                            rx_stop_receiving.clone(),
+                        ));
+                    }
+                }
+
+                // The loops finish when the workers stop receiving and the
+                // responses of the packets in process have been sent.
+                while main_loops.join_next().await.is_some() {}
             })
         };
 
@@ -75,6 +192,7 @@ impl Launcher {
 
         tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_udp_url, "Udp::run_with_graceful_shutdown (started)");
 
+        let mut running = running;
         let stop = running.abort_handle();
 
         let halt_task = tokio::task::spawn(shutdown_signal_with_message(
@@ -83,14 +201,71 @@ impl Launcher {
         ));
 
         select! {
-            _ = running => { tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_udp_url, "Udp::run_with_graceful_shutdown (stopped)"); },
-            _ = halt_task => { tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_udp_url, "Udp::run_with_graceful_shutdown (halting)"); }
+            _ = &mut running => { tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_udp_url, "Udp::run_with_graceful_shutdown (stopped)"); },
+            _ = halt_task => {
+                tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_udp_url, "Udp::run_with_graceful_shutdown (halting)");
+
+                tx_stop_receiving.send_replace(true);
+
+                match tokio::time::timeout(shutdown_timeout, &mut running).await {
+                    Ok(_) => tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_udp_url, "Udp::run_with_graceful_shutdown (drained)"),
+                    Err(_) => tracing::warn!(target: UDP_TRACKER_LOG_TARGET, local_udp_url, ?shutdown_timeout, "Udp::run_with_graceful_shutdown (aborting the requests still in process)"),
+                }
+            }
         }
         stop.abort();
 
         tokio::task::yield_now().await; // lets allow the other threads to complete.
     }
 
+    /// It starts receiving and sending the packets through the socket with the
+    /// configured backend. It returns the receivers for the workers and the
+    /// queue for their responses.
+    ///
+    /// The `io_uring` backend falls back to tokio if it's not compiled in, or
+    /// the kernel does not support it.
+    fn start_io(
+        socket: &Arc<BoundSocket>,
+        workers: usize,
+        udp_io_backend: UdpIoBackend,
+        #[cfg_attr(not(all(feature = "io-uring", target_os = "linux")), allow(unused_variables))] tracker: &Arc<Tracker>,
+        main_loops: &mut JoinSet<()>,
+    ) -> (Vec<Receiver>, mpsc::Sender<Packet>) {
+        let local_addr = format!("udp://{}", socket.address());
+
+        if udp_io_backend == UdpIoBackend::IoUring {
+            #[cfg(all(feature = "io-uring", target_os = "linux"))]
+            match super::uring::start(socket.clone(), workers, RESPONSE_QUEUE_CAPACITY, tracker.clone()) {
+                Ok((queues, responses)) => {
+                    tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_addr, "Udp::start_io (using io_uring)");
+
+                    let receivers = queues
+                        .into_iter()
+                        .map(|queue| Receiver::with_queue(socket.clone(), queue))
+                        .collect();
+
+                    return (receivers, responses);
+                }
+                Err(e) => {
+                    tracing::warn!(target: UDP_TRACKER_LOG_TARGET, local_addr, err = %e, "Udp::start_io (unable to start io_uring, falling back to tokio)");
+                }
+            }
+
+            #[cfg(not(all(feature = "io-uring", target_os = "linux")))]
+            tracing::warn!(target: UDP_TRACKER_LOG_TARGET, local_addr, "Udp::start_io (io_uring is not available in this build, falling back to tokio)");
+        }
+
+        // A task sends the responses queued by the workers in batches. It
+        // finishes when all the workers are finished.
+        let (responses, queue) = mpsc::channel(RESPONSE_QUEUE_CAPACITY);
+        let sender_socket = socket.clone();
+        main_loops.spawn(async move { batch::run_sender(&sender_socket, queue).await });
+
+        let receivers = (0..workers).map(|_| Receiver::new(socket.clone())).collect();
+
+        (receivers, responses)
+    }
+
     #[must_use]
     #[instrument(skip(binding))]
     pub fn check(binding: &SocketAddr) -> ServiceHealthCheckJob {
@@ -102,20 +277,68 @@ impl Launcher {
         ServiceHealthCheckJob::new(binding, info, job)
     }
 
-    #[instrument(skip(receiver, tracker))]
-    async fn run_udp_server_main(mut receiver: Receiver, tracker: Arc<Tracker>) {
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip(receiver, responses, tracker, inflight_packets, rate_limiter, load_shedder, stop_receiving))]
+    async fn run_udp_server_main(
+        mut receiver: Receiver,
+        responses: mpsc::Sender<Packet>,
+        tracker: Arc<Tracker>,
+        inflight_packets: Option<Arc<Semaphore>>,
+        rate_limiter: Option<Arc<RateLimiter>>,
+        load_shedder: Option<Arc<LoadShedder>>,
+        peers_in_response: PeersInResponse,
+        debug_capture: bool,
+        min_peer_port: u16,
+        connection_id_scheme: ConnectionIdScheme,
+        mut stop_receiving: watch::Receiver<bool>,
+    ) {
         let active_requests = &mut ActiveRequests::default();
 
         let addr = receiver.bound_socket_address();
         let local_addr = format!("udp://{addr}");
 
         loop {
-            let processor = Processor::new(receiver.socket.clone(), tracker.clone());
+            let processor = Processor::new(
+                receiver.socket.clone(),
+                responses.clone(),
+                tracker.clone(),
+                peers_in_response,
+                debug_capture,
+                min_peer_port,
+                connection_id_scheme,
+            );
+
+            let next = async {
+                let permit = match &inflight_packets {
+                    Some(inflight_packets) => Some(
+                        inflight_packets
+                            .clone()
+                            .acquire_owned()
+                            .await
+                            .expect("the inflight packets semaphore should not be closed"),
+                    ),
+                    None => None,
+                };
 
-            if let Some(req) = {
                 tracing::trace!(target: UDP_TRACKER_LOG_TARGET, local_addr, "Udp::run_udp_server (wait for request)");
-                receiver.next().await
-            } {
+
+                (permit, receiver.next().await)
+            };
+
+            let (permit, next_request) = select! {
+                biased;
+                _ = stop_receiving.wait_for(|stop| *stop) => {
+                    tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_addr, "Udp::run_udp_server (stop receiving)");
+
+                    // The requests in process are not aborted, so their
+                    // responses can be sent before the server is shut down.
+                    active_requests.detach();
+                    return;
+                }
+                next = next => next,
+            };
+
+            if let Some(req) = next_request {
                 tracing::trace!(target: UDP_TRACKER_LOG_TARGET, local_addr, "Udp::run_udp_server::loop (in)");
 
                 let req = match req {
@@ -130,6 +353,43 @@ impl Launcher {
                     }
                 };
 
+                if let Some(rate_limiter) = &rate_limiter {
+                    if !rate_limiter.try_acquire(&req.from.ip().to_canonical(), CurrentClock::now()) {
+                        tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_addr, from = %req.from, "Udp::run_udp_server::loop (rate limit exceeded)");
+
+                        if rate_limiter.action() == UdpRateLimitAction::Error {
+                            tokio::task::spawn(async move {
+                                processor.reject_rate_limited_request(req).await;
+                                drop(permit);
+                            });
+                        } else {
+                            tracker
+                                .send_stats_event(statistics::Event::UdpPacketDropped {
+                                    server_socket_addr: addr,
+                                })
+                                .await;
+                        }
+
+                        continue;
+                    }
+                }
+
+                if let Some(load_shedder) = &load_shedder {
+                    if load_shedder.should_shed(&req.payload, tracker.is_overloaded()) {
+                        tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_addr, from = %req.from, "Udp::run_udp_server::loop (scrape packet shed)");
+
+                        tracker
+                            .send_stats_event(statistics::Event::UdpPacketShed {
+                                server_socket_addr: addr,
+                            })
+                            .await;
+
+                        continue;
+                    }
+                }
+
+                let queued_packet = load_shedder.as_ref().map(LoadShedder::enqueue);
+
                 // We spawn the new task even if there active requests buffer is
                 // full. This could seem counterintuitive because we are accepting
                 // more request and consuming more memory even if the server is
@@ -141,7 +401,12 @@ impl Launcher {
                 // are only adding and removing tasks without given them the
                 // chance to finish. However, the buffer is yielding before
                 // aborting one tasks, giving it the chance to finish.
-                let abort_handle: tokio::task::AbortHandle = tokio::task::spawn(processor.process_request(req)).abort_handle();
+                let abort_handle: tokio::task::AbortHandle = tokio::task::spawn(async move {
+                    processor.process_request(req).await;
+                    drop(permit);
+                    drop(queued_packet);
+                })
+                .abort_handle();
 
                 if abort_handle.is_finished() {
                     continue;
@@ -6,6 +6,7 @@ use thiserror::Error;
 
 use super::RawRequest;
 
+pub mod batch;
 pub mod bound_socket;
 pub mod launcher;
 pub mod processor;
@@ -13,6 +14,8 @@ pub mod receiver;
 pub mod request_buffer;
 pub mod spawner;
 pub mod states;
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+pub mod uring;
 
 /// Error that can occur when starting or stopping the UDP server.
 ///
@@ -63,6 +66,8 @@ mod tests {
     use super::Server;
     use crate::bootstrap::app::initialize_with_configuration;
     use crate::servers::registar::Registar;
+    use crate::servers::udp::handlers::PeersInResponse;
+    use crate::shared::bit_torrent::tracker::udp::client::check;
 
     #[tokio::test]
     async fn it_should_be_able_to_start_and_stop() {
@@ -70,10 +75,23 @@ mod tests {
         let tracker = initialize_with_configuration(&cfg);
         let udp_trackers = cfg.udp_trackers.clone().expect("missing UDP trackers configuration");
         let config = &udp_trackers[0];
-        let bind_to = config.bind_address;
+        let bind_to = config.bind_address.socket_addr().expect("it should bind to an IP address");
         let register = &Registar::default();
 
-        let stopped = Server::new(Spawner::new(bind_to));
+        let stopped = Server::new(Spawner::new(
+            bind_to,
+            config.max_inflight_packets,
+            config.workers,
+            config.reuse_port,
+            config.rate_limit,
+            config.udp_io_backend,
+            Duration::from_secs(config.shutdown_timeout),
+            PeersInResponse::new(config),
+            config.debug_capture,
+            config.min_peer_port,
+            config.connection_id_scheme,
+            config.load_shedding,
+        ));
 
         let started = stopped
             .start(tracker, register.give_form())
@@ -92,10 +110,23 @@ mod tests {
         let cfg = Arc::new(ephemeral_public());
         let tracker = initialize_with_configuration(&cfg);
         let config = &cfg.udp_trackers.as_ref().unwrap().first().unwrap();
-        let bind_to = config.bind_address;
+        let bind_to = config.bind_address.socket_addr().expect("it should bind to an IP address");
         let register = &Registar::default();
 
-        let stopped = Server::new(Spawner::new(bind_to));
+        let stopped = Server::new(Spawner::new(
+            bind_to,
+            config.max_inflight_packets,
+            config.workers,
+            config.reuse_port,
+            config.rate_limit,
+            config.udp_io_backend,
+            Duration::from_secs(config.shutdown_timeout),
+            PeersInResponse::new(config),
+            config.debug_capture,
+            config.min_peer_port,
+            config.connection_id_scheme,
+            config.load_shedding,
+        ));
 
         let started = stopped
             .start(tracker, register.give_form())
@@ -110,6 +141,43 @@ mod tests {
 
         assert_eq!(stopped.state.spawner.bind_to, bind_to);
     }
+
+    #[tokio::test]
+    async fn it_should_answer_requests_from_multiple_sockets_bound_to_the_same_port() {
+        let cfg = Arc::new(ephemeral_public());
+        let tracker = initialize_with_configuration(&cfg);
+        let config = &cfg.udp_trackers.as_ref().unwrap().first().unwrap();
+        let bind_to = config.bind_address.socket_addr().expect("it should bind to an IP address");
+        let register = &Registar::default();
+
+        let stopped = Server::new(Spawner::new(
+            bind_to,
+            config.max_inflight_packets,
+            4,
+            true,
+            config.rate_limit,
+            config.udp_io_backend,
+            Duration::from_secs(config.shutdown_timeout),
+            PeersInResponse::new(config),
+            config.debug_capture,
+            config.min_peer_port,
+            config.connection_id_scheme,
+            config.load_shedding,
+        ));
+
+        let started = stopped
+            .start(tracker, register.give_form())
+            .await
+            .expect("it should start the server");
+
+        for _ in 0..8 {
+            assert!(check(&started.state.local_addr).await.is_ok());
+        }
+
+        let stopped = started.stop().await.expect("it should stop the server");
+
+        assert_eq!(stopped.state.spawner.bind_to, bind_to);
+    }
 }
 
 /// Todo: submit test to tokio documentation.
@@ -1,31 +1,117 @@
 use std::io::Cursor;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Instant;
 
 use aquatic_udp_protocol::Response;
+use tokio::sync::mpsc;
+use torrust_tracker_configuration::ConnectionIdScheme;
 use tracing::{instrument, Level};
 
+use super::batch::Packet;
 use super::bound_socket::BoundSocket;
+use crate::core::statistics::{self, UdpResponseKind};
 use crate::core::Tracker;
+use crate::servers::udp::handlers::PeersInResponse;
+use crate::servers::udp::logging::log_rejected_packet;
 use crate::servers::udp::{handlers, RawRequest};
 
 pub struct Processor {
     socket: Arc<BoundSocket>,
+    /// Queue of the responses sent in batches through the socket.
+    responses: mpsc::Sender<Packet>,
     tracker: Arc<Tracker>,
+    peers_in_response: PeersInResponse,
+    /// Whether the malformed and rejected packets are logged.
+    debug_capture: bool,
+    /// Announces with a lower peer port are rejected.
+    min_peer_port: u16,
+    /// Construction used to generate the connection IDs.
+    connection_id_scheme: ConnectionIdScheme,
 }
 
 impl Processor {
-    pub fn new(socket: Arc<BoundSocket>, tracker: Arc<Tracker>) -> Self {
-        Self { socket, tracker }
+    pub fn new(
+        socket: Arc<BoundSocket>,
+        responses: mpsc::Sender<Packet>,
+        tracker: Arc<Tracker>,
+        peers_in_response: PeersInResponse,
+        debug_capture: bool,
+        min_peer_port: u16,
+        connection_id_scheme: ConnectionIdScheme,
+    ) -> Self {
+        Self {
+            socket,
+            responses,
+            tracker,
+            peers_in_response,
+            debug_capture,
+            min_peer_port,
+            connection_id_scheme,
+        }
     }
 
     #[instrument(skip(self, request))]
     pub async fn process_request(self, request: RawRequest) {
+        let _request = self.tracker.track_request();
         let from = request.from;
-        let response = handlers::handle_packet(request, &self.tracker, self.socket.address()).await;
+        let start_time = Instant::now();
+        let payload = self.debug_capture.then(|| request.payload.clone());
+        let response = handlers::handle_packet(
+            request,
+            &self.tracker,
+            self.socket.address(),
+            self.peers_in_response,
+            self.min_peer_port,
+            self.connection_id_scheme,
+        )
+        .await;
+        self.record_response(&response, start_time).await;
+        if let Some(payload) = payload {
+            self.capture_rejected_packet(&payload, from, &response);
+        }
         self.send_response(from, response).await;
     }
 
+    /// It answers a packet exceeding the rate limit with an error response.
+    #[instrument(skip(self, request))]
+    pub async fn reject_rate_limited_request(self, request: RawRequest) {
+        let _request = self.tracker.track_request();
+        let from = request.from;
+        let start_time = Instant::now();
+        let response = handlers::handle_rate_limited_packet(&request, &self.tracker).await;
+        self.record_response(&response, start_time).await;
+        if self.debug_capture {
+            self.capture_rejected_packet(&request.payload, from, &response);
+        }
+        self.send_response(from, response).await;
+    }
+
+    /// It counts the response in the statistics of the socket.
+    async fn record_response(&self, response: &Response, start_time: Instant) {
+        let kind = match response {
+            Response::Connect(_) => UdpResponseKind::Connect,
+            Response::AnnounceIpv4(_) | Response::AnnounceIpv6(_) => UdpResponseKind::Announce,
+            Response::Scrape(_) => UdpResponseKind::Scrape,
+            Response::Error(_) => UdpResponseKind::Error,
+        };
+
+        self.tracker
+            .send_stats_event(statistics::Event::UdpResponse {
+                server_socket_addr: self.socket.address(),
+                kind,
+                latency: start_time.elapsed(),
+            })
+            .await;
+    }
+
+    /// It logs the packet if it was rejected with an error response.
+    fn capture_rejected_packet(&self, payload: &[u8], from: SocketAddr, response: &Response) {
+        if let Response::Error(error) = response {
+            log_rejected_packet(payload, &from, &self.socket.address(), &error.message);
+        }
+    }
+
     #[instrument(skip(self))]
     async fn send_response(self, target: SocketAddr, response: Response) {
         tracing::debug!("send response");
@@ -43,18 +129,16 @@ impl Processor {
         match response.write_bytes(&mut writer) {
             Ok(()) => {
                 let bytes_count = writer.get_ref().len();
-                let payload = writer.get_ref();
-
-                let () = match self.send_packet(&target, payload).await {
-                    Ok(sent_bytes) => {
-                        if tracing::event_enabled!(Level::TRACE) {
-                            tracing::debug!(%bytes_count, %sent_bytes, ?payload, "sent {response_type}");
-                        } else {
-                            tracing::debug!(%bytes_count, %sent_bytes, "sent {response_type}");
-                        }
-                    }
-                    Err(error) => tracing::warn!(%bytes_count, %error, ?payload, "failed to send"),
-                };
+
+                if tracing::event_enabled!(Level::TRACE) {
+                    tracing::debug!(%bytes_count, payload = ?writer.get_ref(), "sending {response_type}");
+                } else {
+                    tracing::debug!(%bytes_count, "sending {response_type}");
+                }
+
+                if let Err(error) = self.send_packet(target, writer.into_inner()).await {
+                    tracing::warn!(%bytes_count, %error, "failed to send");
+                }
             }
             Err(e) => {
                 tracing::error!(%e, "error");
@@ -62,11 +146,12 @@ impl Processor {
         }
     }
 
-    #[instrument(skip(self))]
-    async fn send_packet(&self, target: &SocketAddr, payload: &[u8]) -> std::io::Result<usize> {
+    /// It queues the packet to be sent in the next batch.
+    #[instrument(skip(self, payload))]
+    async fn send_packet(&self, target: SocketAddr, payload: Vec<u8>) -> Result<(), mpsc::error::SendError<Packet>> {
         tracing::trace!("send packet");
 
         // doesn't matter if it reaches or not
-        self.socket.send_to(payload, target).await
+        self.responses.send(Packet { payload, target }).await
     }
 }
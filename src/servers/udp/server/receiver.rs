@@ -1,18 +1,26 @@
-use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::net::SocketAddr;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::task::{Context, Poll};
+use std::task::{ready, Context, Poll};
 
 use futures::Stream;
+use tokio::sync::mpsc;
 
+use super::batch::{self, RecvBatch, BATCH_SIZE};
 use super::bound_socket::BoundSocket;
 use super::RawRequest;
-use crate::shared::bit_torrent::tracker::udp::MAX_PACKET_SIZE;
 
+/// It receives the packets from the socket in batches, and yields them one
+/// by one.
+///
+/// When the socket is served by another backend, the packets are received
+/// from the queue filled by that backend instead.
 pub struct Receiver {
     pub socket: Arc<BoundSocket>,
-    data: RefCell<[u8; MAX_PACKET_SIZE]>,
+    batch: RecvBatch,
+    pending: VecDeque<RawRequest>,
+    queue: Option<mpsc::Receiver<RawRequest>>,
 }
 
 impl Receiver {
@@ -20,7 +28,18 @@ impl Receiver {
     pub fn new(bound_socket: Arc<BoundSocket>) -> Self {
         Receiver {
             socket: bound_socket,
-            data: RefCell::new([0; MAX_PACKET_SIZE]),
+            batch: RecvBatch::default(),
+            pending: VecDeque::with_capacity(BATCH_SIZE),
+            queue: None,
+        }
+    }
+
+    /// It yields the packets received from the socket by another backend.
+    #[must_use]
+    pub fn with_queue(bound_socket: Arc<BoundSocket>, queue: mpsc::Receiver<RawRequest>) -> Self {
+        Receiver {
+            queue: Some(queue),
+            ..Receiver::new(bound_socket)
         }
     }
 
@@ -33,22 +52,30 @@ impl Stream for Receiver {
     type Item = std::io::Result<RawRequest>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let mut buf = *self.data.borrow_mut();
-        let mut buf = tokio::io::ReadBuf::new(&mut buf);
-
-        let Poll::Ready(ready) = self.socket.poll_recv_from(cx, &mut buf) else {
-            return Poll::Pending;
-        };
-
-        let res = match ready {
-            Ok(from) => {
-                let payload = buf.filled().to_vec();
-                let request = RawRequest { payload, from };
-                Some(Ok(request))
+        let this = self.get_mut();
+
+        if let Some(queue) = &mut this.queue {
+            return queue.poll_recv(cx).map(|request| request.map(Ok));
+        }
+
+        loop {
+            if let Some(request) = this.pending.pop_front() {
+                return Poll::Ready(Some(Ok(request)));
+            }
+
+            if let Err(err) = ready!(this.socket.poll_recv_ready(cx)) {
+                return Poll::Ready(Some(Err(err)));
             }
-            Err(err) => Some(Err(err)),
-        };
 
-        Poll::Ready(res)
+            match batch::recv(&this.socket, &mut this.batch) {
+                Ok(_) => this.pending.extend(this.batch.packets().map(|(payload, from)| RawRequest {
+                    payload: payload.to_vec(),
+                    from,
+                })),
+                // The readiness was cleared, poll it again to register the waker.
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(err) => return Poll::Ready(Some(Err(err))),
+            }
+        }
     }
 }
@@ -34,6 +34,12 @@ impl Drop for ActiveRequests {
 }
 
 impl ActiveRequests {
+    /// It removes all the abort handles from the buffer without aborting the
+    /// tasks, so they can run to completion.
+    pub fn detach(&mut self) {
+        self.rb.clear();
+    }
+
     /// Inserts an abort handle for a UDP request processor task.
     ///
     /// If the buffer is full, this method attempts to make space by:
@@ -1,21 +1,48 @@
 //! A thin wrapper for tokio spawn to launch the UDP server launcher as a new task.
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use derive_more::derive::Display;
 use derive_more::Constructor;
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
+use torrust_tracker_configuration::{ConnectionIdScheme, UdpIoBackend, UdpLoadShedding, UdpRateLimit};
 
 use super::launcher::Launcher;
 use crate::bootstrap::jobs::Started;
 use crate::core::Tracker;
 use crate::servers::signals::Halted;
+use crate::servers::udp::handlers::PeersInResponse;
 
 #[derive(Constructor, Copy, Clone, Debug, Display)]
 #[display("(with socket): {bind_to}")]
 pub struct Spawner {
     pub bind_to: SocketAddr,
+    /// Maximum number of packets processed concurrently. Unlimited if `None`.
+    pub max_inflight_packets: Option<u32>,
+    /// Number of tasks receiving packets from the socket.
+    pub workers: u32,
+    /// Whether each worker receives from its own `SO_REUSEPORT` socket.
+    pub reuse_port: bool,
+    /// Per-IP rate limit for the received packets. Unlimited if `None`.
+    pub rate_limit: Option<UdpRateLimit>,
+    /// Backend used to receive and send the packets.
+    pub udp_io_backend: UdpIoBackend,
+    /// Maximum time to wait for the packets in process when the server is
+    /// halted.
+    pub shutdown_timeout: Duration,
+    /// Number of peers returned in the announce responses.
+    pub peers_in_response: PeersInResponse,
+    /// Whether the malformed and rejected packets are logged.
+    pub debug_capture: bool,
+    /// Announces with a lower peer port are rejected.
+    pub min_peer_port: u16,
+    /// Construction used to generate the connection IDs. The one of the
+    /// tracker if `None`.
+    pub connection_id_scheme: Option<ConnectionIdScheme>,
+    /// Load shedding of the `scrape` packets. Disabled if `None`.
+    pub load_shedding: Option<UdpLoadShedding>,
 }
 
 impl Spawner {
@@ -30,10 +57,27 @@ impl Spawner {
         tx_start: oneshot::Sender<Started>,
         rx_halt: oneshot::Receiver<Halted>,
     ) -> JoinHandle<Spawner> {
-        let spawner = Self::new(self.bind_to);
+        let spawner = *self;
 
         tokio::spawn(async move {
-            Launcher::run_with_graceful_shutdown(tracker, spawner.bind_to, tx_start, rx_halt).await;
+            Launcher::run_with_graceful_shutdown(
+                tracker,
+                spawner.bind_to,
+                spawner.max_inflight_packets,
+                spawner.workers,
+                spawner.reuse_port,
+                spawner.rate_limit,
+                spawner.udp_io_backend,
+                spawner.shutdown_timeout,
+                spawner.peers_in_response,
+                spawner.debug_capture,
+                spawner.min_peer_port,
+                spawner.connection_id_scheme,
+                spawner.load_shedding,
+                tx_start,
+                rx_halt,
+            )
+            .await;
             spawner
         })
     }
@@ -0,0 +1,414 @@
+//! `io_uring` backend for the UDP server.
+//!
+//! Each socket is served by a dedicated thread that owns an `io_uring`
+//! instance. The thread keeps a fixed number of receive operations in flight
+//! with pre-allocated buffers, and hands the received packets to the workers
+//! (round-robin) through bounded queues. The responses queued by the workers
+//! are submitted as send operations on the same ring. The packets received
+//! while the queues of all the workers are full are dropped.
+//!
+//! It's only available on Linux, in builds with the `io-uring` cargo feature.
+//! Refer to the [`UdpIoBackend`](torrust_tracker_configuration::UdpIoBackend)
+//! configuration option.
+use std::net::SocketAddr;
+use std::os::fd::AsRawFd;
+use std::sync::Arc;
+
+use io_uring::{opcode, squeue, types, IoUring};
+use socket2::SockAddr;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::{TryRecvError, TrySendError};
+
+use super::batch::Packet;
+use super::bound_socket::BoundSocket;
+use crate::core::{statistics, Tracker};
+use crate::servers::udp::{RawRequest, UDP_TRACKER_LOG_TARGET};
+use crate::shared::bit_torrent::tracker::udp::MAX_PACKET_SIZE;
+
+/// Number of receive operations kept in flight.
+const RECV_SLOTS: usize = 64;
+
+/// Maximum number of send operations in flight.
+const SEND_SLOTS: usize = 64;
+
+/// Submission queue size. It fits all the operations in flight.
+const RING_ENTRIES: u32 = 256;
+
+/// Flag in the `user_data` of the send operations.
+const SEND_FLAG: u64 = 1 << 63;
+
+/// Flag in the `user_data` of the operations cancelling the receives.
+const CANCEL_FLAG: u64 = 1 << 62;
+
+/// How long the thread waits for completions before checking the queued
+/// responses again.
+const IDLE_TIMEOUT_NANOS: u32 = 1_000_000;
+
+/// A receive operation with its own buffer.
+struct RecvSlot {
+    buffer: [u8; MAX_PACKET_SIZE],
+    address: libc::sockaddr_storage,
+    iovec: libc::iovec,
+    header: libc::msghdr,
+}
+
+impl RecvSlot {
+    fn new() -> Box<Self> {
+        // SAFETY: all-zero is a valid value for these C structs. The pointers
+        // are set once the slot is boxed and won't move anymore.
+        let mut slot = Box::new(Self {
+            buffer: [0; MAX_PACKET_SIZE],
+            address: unsafe { std::mem::zeroed() },
+            iovec: unsafe { std::mem::zeroed() },
+            header: unsafe { std::mem::zeroed() },
+        });
+
+        slot.iovec.iov_base = slot.buffer.as_mut_ptr().cast();
+        slot.iovec.iov_len = MAX_PACKET_SIZE;
+        slot.header.msg_name = std::ptr::from_mut(&mut slot.address).cast();
+        slot.header.msg_iov = std::ptr::from_mut(&mut slot.iovec);
+        slot.header.msg_iovlen = 1;
+
+        slot
+    }
+
+    fn entry(&mut self, fd: types::Fd, index: usize) -> squeue::Entry {
+        #[allow(clippy::cast_possible_truncation)]
+        let address_len = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+
+        self.header.msg_namelen = address_len;
+
+        opcode::RecvMsg::new(fd, std::ptr::from_mut(&mut self.header))
+            .build()
+            .user_data(index as u64)
+    }
+
+    fn request(&self, len: usize) -> Option<RawRequest> {
+        // SAFETY: the kernel has written a valid address of that length.
+        let from = unsafe { SockAddr::new(self.address, self.header.msg_namelen) }.as_socket()?;
+
+        Some(RawRequest {
+            payload: self.buffer[..len].to_vec(),
+            from,
+        })
+    }
+}
+
+/// A send operation. It keeps the packet alive until the operation completes.
+struct SendSlot {
+    packet: Packet,
+    target: SockAddr,
+    iovec: libc::iovec,
+    header: libc::msghdr,
+}
+
+impl SendSlot {
+    fn new(packet: Packet) -> Box<Self> {
+        let target = SockAddr::from(packet.target);
+
+        // SAFETY: all-zero is a valid value for these C structs. The pointers
+        // are set once the slot is boxed and won't move anymore.
+        let mut slot = Box::new(Self {
+            packet,
+            target,
+            iovec: unsafe { std::mem::zeroed() },
+            header: unsafe { std::mem::zeroed() },
+        });
+
+        slot.iovec.iov_base = slot.packet.payload.as_mut_ptr().cast();
+        slot.iovec.iov_len = slot.packet.payload.len();
+        slot.header.msg_name = slot.target.as_ptr().cast_mut().cast();
+        slot.header.msg_namelen = slot.target.len();
+        slot.header.msg_iov = std::ptr::from_mut(&mut slot.iovec);
+        slot.header.msg_iovlen = 1;
+
+        slot
+    }
+
+    fn entry(&self, fd: types::Fd, index: usize) -> squeue::Entry {
+        opcode::SendMsg::new(fd, std::ptr::from_ref(&self.header))
+            .build()
+            .user_data(index as u64 | SEND_FLAG)
+    }
+}
+
+/// It starts the `io_uring` thread for the socket.
+///
+/// It returns the queues the workers receive the packets from, one for each
+/// worker, and the queue for the responses.
+///
+/// The thread never blocks on the queues of the workers: when they are full,
+/// the packet is dropped and counted in the `tracker` statistics. The thread
+/// is also the only one sending the responses, so blocking it would stop the
+/// workers waiting for room in the responses queue.
+///
+/// # Errors
+///
+/// Will return an error if the kernel does not support `io_uring` or the
+/// thread can't be spawned.
+///
+/// # Panics
+///
+/// Will panic if it's not called from a tokio runtime.
+pub fn start(
+    socket: Arc<BoundSocket>,
+    workers: usize,
+    queue_capacity: usize,
+    tracker: Arc<Tracker>,
+) -> std::io::Result<(Vec<mpsc::Receiver<RawRequest>>, mpsc::Sender<Packet>)> {
+    let ring = IoUring::new(RING_ENTRIES)?;
+
+    let (requests, request_queues): (Vec<_>, Vec<_>) = (0..workers.max(1)).map(|_| mpsc::channel(queue_capacity)).unzip();
+    let (responses, response_queue) = mpsc::channel(queue_capacity);
+
+    let dropped = DroppedPackets {
+        runtime: Handle::current(),
+        tracker,
+        server_socket_addr: socket.address(),
+    };
+
+    std::thread::Builder::new()
+        .name(format!("udp-io-uring-{}", socket.address()))
+        .spawn(move || run(ring, &socket, &requests, response_queue, &dropped))?;
+
+    Ok((request_queues, responses))
+}
+
+/// It counts the packets dropped by the `io_uring` thread in the statistics,
+/// without blocking the thread.
+struct DroppedPackets {
+    runtime: Handle,
+    tracker: Arc<Tracker>,
+    server_socket_addr: SocketAddr,
+}
+
+impl DroppedPackets {
+    fn count(&self) {
+        let tracker = self.tracker.clone();
+        let server_socket_addr = self.server_socket_addr;
+
+        self.runtime.spawn(async move {
+            tracker
+                .send_stats_event(statistics::Event::UdpPacketDropped { server_socket_addr })
+                .await;
+        });
+    }
+}
+
+/// The `io_uring` loop. It runs until all the workers are finished and all
+/// their responses have been sent.
+fn run(
+    mut ring: IoUring,
+    socket: &BoundSocket,
+    requests: &[mpsc::Sender<RawRequest>],
+    mut responses: mpsc::Receiver<Packet>,
+    dropped: &DroppedPackets,
+) {
+    let local_addr = format!("udp://{}", socket.address());
+    let fd = types::Fd(socket.as_raw_fd());
+
+    let mut recv_slots: Vec<Box<RecvSlot>> = (0..RECV_SLOTS).map(|_| RecvSlot::new()).collect();
+    let mut recv_in_flight = [false; RECV_SLOTS];
+    let mut send_slots: Vec<Option<Box<SendSlot>>> = (0..SEND_SLOTS).map(|_| None).collect();
+
+    let mut next_worker = 0;
+    let mut workers_running = true;
+
+    for (index, slot) in recv_slots.iter_mut().enumerate() {
+        recv_in_flight[index] = push(&mut ring, &slot.entry(fd, index));
+    }
+
+    tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_addr, "Udp::io_uring (started)");
+
+    loop {
+        while workers_running {
+            let Some(index) = send_slots.iter().position(Option::is_none) else {
+                break;
+            };
+
+            match responses.try_recv() {
+                Ok(packet) => {
+                    let slot = send_slots[index].insert(SendSlot::new(packet));
+                    if !push(&mut ring, &slot.entry(fd, index)) {
+                        send_slots[index] = None;
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => workers_running = false,
+            }
+        }
+
+        if !workers_running && send_slots.iter().all(Option::is_none) {
+            break;
+        }
+
+        let timeout = types::Timespec::new().nsec(IDLE_TIMEOUT_NANOS);
+        let args = types::SubmitArgs::new().timespec(&timeout);
+
+        match ring.submitter().submit_with_args(1, &args) {
+            Ok(_) => {}
+            Err(err) if err.raw_os_error() == Some(libc::ETIME) || err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => {
+                tracing::error!(target: UDP_TRACKER_LOG_TARGET, local_addr, %err, "Udp::io_uring break: (got error)");
+                break;
+            }
+        }
+
+        let completions: Vec<(u64, i32)> = ring.completion().map(|cqe| (cqe.user_data(), cqe.result())).collect();
+
+        for (user_data, result) in completions {
+            #[allow(clippy::cast_possible_truncation)]
+            let index = (user_data & !SEND_FLAG) as usize;
+
+            if user_data & SEND_FLAG != 0 {
+                if let Some(slot) = send_slots[index].take() {
+                    if result < 0 {
+                        // Doesn't matter if it reaches or not.
+                        let err = std::io::Error::from_raw_os_error(-result);
+                        tracing::warn!(target: UDP_TRACKER_LOG_TARGET, local_addr, to = %slot.packet.target, %err, "failed to send");
+                    }
+                }
+                continue;
+            }
+
+            recv_in_flight[index] = false;
+
+            if !workers_running {
+                continue;
+            }
+
+            let slot = &mut recv_slots[index];
+
+            match usize::try_from(result) {
+                Ok(len) => {
+                    if let Some(request) = slot.request(len) {
+                        match dispatch(requests, &mut next_worker, request) {
+                            Dispatch::Queued => {}
+                            Dispatch::Dropped => {
+                                tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_addr, "Udp::io_uring (queues full)");
+                                dropped.count();
+                            }
+                            Dispatch::WorkersFinished => {
+                                tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_addr, "Udp::io_uring (workers finished)");
+                                workers_running = false;
+                                continue;
+                            }
+                        }
+                    }
+                }
+                Err(_) => {
+                    let err = std::io::Error::from_raw_os_error(-result);
+                    tracing::warn!(target: UDP_TRACKER_LOG_TARGET, local_addr, %err, "failed to receive");
+                }
+            }
+
+            recv_in_flight[index] = push(&mut ring, &slot.entry(fd, index));
+        }
+    }
+
+    // Closing the ring cancels the operations in flight asynchronously, so
+    // the kernel could still write into their buffers once they are freed.
+    // They are cancelled and waited for before the slots are dropped.
+    if !cancel_in_flight(&mut ring, &mut recv_in_flight, &mut send_slots) {
+        // Leaked, so the kernel never writes into freed memory.
+        tracing::error!(target: UDP_TRACKER_LOG_TARGET, local_addr, "Udp::io_uring (unable to cancel the operations in flight)");
+        std::mem::forget(recv_slots);
+        std::mem::forget(send_slots);
+    }
+
+    tracing::debug!(target: UDP_TRACKER_LOG_TARGET, local_addr, "Udp::io_uring (stopped)");
+}
+
+/// It cancels the receive operations in flight and waits until all the
+/// operations in flight (including the sends) complete. It returns `false` if
+/// it can't, and the buffers of the operations must not be freed.
+fn cancel_in_flight(ring: &mut IoUring, recv_in_flight: &mut [bool], send_slots: &mut [Option<Box<SendSlot>>]) -> bool {
+    for (index, _) in recv_in_flight.iter().enumerate().filter(|(_, in_flight)| **in_flight) {
+        let cancel = opcode::AsyncCancel::new(index as u64).build().user_data(CANCEL_FLAG);
+
+        if !push(ring, &cancel) {
+            return false;
+        }
+    }
+
+    while recv_in_flight.iter().any(|in_flight| *in_flight) || send_slots.iter().any(Option::is_some) {
+        match ring.submit_and_wait(1) {
+            Ok(_) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => {
+                tracing::error!(target: UDP_TRACKER_LOG_TARGET, %err, "Udp::io_uring (unable to wait for the operations)");
+                return false;
+            }
+        }
+
+        for cqe in ring.completion() {
+            let user_data = cqe.user_data();
+
+            #[allow(clippy::cast_possible_truncation)]
+            let index = (user_data & !(SEND_FLAG | CANCEL_FLAG)) as usize;
+
+            if user_data & CANCEL_FLAG != 0 {
+                continue;
+            } else if user_data & SEND_FLAG != 0 {
+                send_slots[index] = None;
+            } else {
+                recv_in_flight[index] = false;
+            }
+        }
+    }
+
+    true
+}
+
+/// It pushes an operation to the submission queue, submitting the queued
+/// operations first if it's full. It returns `false` if the operation can't
+/// be pushed.
+fn push(ring: &mut IoUring, entry: &squeue::Entry) -> bool {
+    // SAFETY: the buffers of the operations are boxed and kept alive in the
+    // slots until the operations complete.
+    while unsafe { ring.submission().push(entry) }.is_err() {
+        if let Err(err) = ring.submit() {
+            tracing::error!(target: UDP_TRACKER_LOG_TARGET, %err, "Udp::io_uring (unable to submit)");
+            return false;
+        }
+    }
+
+    true
+}
+
+/// What happened to a received packet.
+enum Dispatch {
+    /// It was queued for a worker.
+    Queued,
+    /// The queues of all the running workers are full.
+    Dropped,
+    /// All the workers are finished.
+    WorkersFinished,
+}
+
+/// It hands the request to the next running worker with room in its queue.
+/// It never waits for room in the queues.
+fn dispatch(requests: &[mpsc::Sender<RawRequest>], next_worker: &mut usize, mut request: RawRequest) -> Dispatch {
+    let mut workers_running = false;
+
+    for _ in 0..requests.len() {
+        let worker = &requests[*next_worker];
+        *next_worker = (*next_worker + 1) % requests.len();
+
+        match worker.try_send(request) {
+            Ok(()) => return Dispatch::Queued,
+            Err(TrySendError::Full(returned)) => {
+                workers_running = true;
+                request = returned;
+            }
+            Err(TrySendError::Closed(returned)) => request = returned,
+        }
+    }
+
+    if workers_running {
+        Dispatch::Dropped
+    } else {
+        Dispatch::WorkersFinished
+    }
+}
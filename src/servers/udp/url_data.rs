@@ -0,0 +1,210 @@
+//! `URLData` option of the announce requests.
+//!
+//! [BEP 41. UDP Tracker Protocol Extensions](https://www.bittorrent.org/beps/bep_0041.html)
+//! adds a list of options after the fixed fields of the announce request. The
+//! `URLData` option carries the path and query of the tracker URL, which are
+//! lost otherwise because the UDP protocol only uses the host and port.
+//!
+//! For example, for the tracker URL `udp://tracker.example.com:6969/announce/YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ`
+//! the client appends `/announce/YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ` to the
+//! request.
+//!
+//! Option type | Name           | Format
+//! ------------|----------------|------------------------------------------
+//! `0x0`       | `EndOfOptions` | 1 byte: the option type.
+//! `0x1`       | `NOP`          | 1 byte: the option type.
+//! `0x2`       | `URLData`      | 1 byte: the option type, 1 byte: the length, N bytes: the data.
+//!
+//! The data of multiple `URLData` options is concatenated. Options with
+//! unknown types are skipped.
+//!
+//! The tracker uses the `URLData` to read the authentication key of private
+//! trackers, with the same URL formats as the HTTP tracker: `/announce/<key>`
+//! or `/announce?key=<key>`.
+use std::str::FromStr;
+
+use percent_encoding::percent_decode_str;
+use thiserror::Error;
+
+use crate::core::auth::{Key, ParseKeyError};
+
+/// Length of the fixed fields of the announce request. The options start
+/// right after them.
+pub const ANNOUNCE_REQUEST_LENGTH: usize = 98;
+
+const END_OF_OPTIONS: u8 = 0x0;
+const NOP: u8 = 0x1;
+const URL_DATA: u8 = 0x2;
+
+/// Errors parsing the announce request options.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("option {option_type} at offset {offset} is truncated")]
+    TruncatedOption { option_type: u8, offset: usize },
+}
+
+/// The path and query of the tracker URL sent in the `URLData` options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlData(String);
+
+impl UrlData {
+    /// It parses the options of an announce request packet. It returns `None`
+    /// if the packet does not include any `URLData` option.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if an option is truncated.
+    pub fn from_announce_packet(packet: &[u8]) -> Result<Option<Self>, ParseError> {
+        let options = packet.get(ANNOUNCE_REQUEST_LENGTH..).unwrap_or_default();
+
+        let mut data: Vec<u8> = vec![];
+        let mut offset = 0;
+
+        while let Some(&option_type) = options.get(offset) {
+            match option_type {
+                END_OF_OPTIONS => break,
+                NOP => offset += 1,
+                _ => {
+                    let truncated = || ParseError::TruncatedOption {
+                        option_type,
+                        offset: ANNOUNCE_REQUEST_LENGTH + offset,
+                    };
+
+                    let length = usize::from(*options.get(offset + 1).ok_or_else(truncated)?);
+                    let value = options.get(offset + 2..offset + 2 + length).ok_or_else(truncated)?;
+
+                    if option_type == URL_DATA {
+                        data.extend_from_slice(value);
+                    }
+
+                    offset += 2 + length;
+                }
+            }
+        }
+
+        if data.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self(String::from_utf8_lossy(&data).into_owned())))
+    }
+
+    /// The path of the URL, for example: `/announce/YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ`.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        self.0.split_once('?').map_or(self.0.as_str(), |(path, _)| path)
+    }
+
+    /// The query of the URL, without the leading `?`.
+    #[must_use]
+    pub fn query(&self) -> Option<&str> {
+        self.0.split_once('?').map(|(_, query)| query)
+    }
+
+    /// The authentication key, from the path (`/announce/<key>`) or from the
+    /// `key` query param (`/announce?key=<key>`). The path takes precedence.
+    #[must_use]
+    pub fn key(&self) -> Option<Result<Key, ParseKeyError>> {
+        let from_path = self
+            .path()
+            .strip_prefix("/announce/")
+            .map(|key| key.trim_end_matches('/'))
+            .filter(|key| !key.is_empty())
+            .map(ToString::to_string);
+
+        let from_query = || {
+            self.query()?
+                .split('&')
+                .filter_map(|param| param.split_once('='))
+                .find(|(name, _)| *name == "key")
+                .map(|(_, value)| percent_decode_str(value).decode_utf8_lossy().into_owned())
+        };
+
+        from_path.or_else(from_query).map(|key| Key::from_str(&key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::{ParseError, UrlData, ANNOUNCE_REQUEST_LENGTH};
+    use crate::core::auth::Key;
+
+    const KEY: &str = "YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ";
+
+    fn packet(options: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0u8; ANNOUNCE_REQUEST_LENGTH];
+        packet.extend_from_slice(options);
+        packet
+    }
+
+    fn url_data(url: &str) -> Vec<u8> {
+        let mut option = vec![0x2, u8::try_from(url.len()).unwrap()];
+        option.extend_from_slice(url.as_bytes());
+        option
+    }
+
+    #[test]
+    fn it_should_be_none_when_the_packet_has_no_options() {
+        assert_eq!(UrlData::from_announce_packet(&packet(&[])), Ok(None));
+        assert_eq!(UrlData::from_announce_packet(&packet(&[0x1, 0x0])), Ok(None));
+    }
+
+    #[test]
+    fn it_should_concatenate_the_url_data_options() {
+        let options = [url_data("/announce"), vec![0x1], url_data("?key=abc"), vec![0x0]].concat();
+
+        let url_data = UrlData::from_announce_packet(&packet(&options)).unwrap().unwrap();
+
+        assert_eq!(url_data.path(), "/announce");
+        assert_eq!(url_data.query(), Some("key=abc"));
+    }
+
+    #[test]
+    fn it_should_skip_unknown_options() {
+        let options = [vec![0x7, 0x2, 0xAA, 0xBB], url_data("/announce")].concat();
+
+        let url_data = UrlData::from_announce_packet(&packet(&options)).unwrap().unwrap();
+
+        assert_eq!(url_data.path(), "/announce");
+    }
+
+    #[test]
+    fn it_should_fail_when_an_option_is_truncated() {
+        assert_eq!(
+            UrlData::from_announce_packet(&packet(&[0x2, 0x9, b'/'])),
+            Err(ParseError::TruncatedOption {
+                option_type: 0x2,
+                offset: ANNOUNCE_REQUEST_LENGTH
+            })
+        );
+    }
+
+    #[test]
+    fn it_should_read_the_key_from_the_path() {
+        let url_data = UrlData::from_announce_packet(&packet(&url_data(&format!("/announce/{KEY}"))))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(url_data.key().unwrap().unwrap(), Key::from_str(KEY).unwrap());
+    }
+
+    #[test]
+    fn it_should_read_the_key_from_the_query() {
+        let url_data = UrlData::from_announce_packet(&packet(&url_data(&format!("/announce?info=1&key={KEY}"))))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(url_data.key().unwrap().unwrap(), Key::from_str(KEY).unwrap());
+    }
+
+    #[test]
+    fn it_should_not_have_a_key_when_the_url_does_not_include_it() {
+        let url_data = UrlData::from_announce_packet(&packet(&url_data("/announce")))
+            .unwrap()
+            .unwrap();
+
+        assert!(url_data.key().is_none());
+    }
+}
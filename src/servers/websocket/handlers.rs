@@ -0,0 +1,310 @@
+//! Handlers of the WebSocket tracker.
+//!
+//! Each WebSocket connection is handled by a task that reads the messages
+//! from the client and another task that writes the messages to it: the
+//! responses to its requests, and the offers and answers relayed from other
+//! peers.
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use futures::{SinkExt, StreamExt};
+use tokio::sync::mpsc::unbounded_channel;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::peer;
+
+use super::messages::{
+    self, decode_binary_string, decode_info_hash, encode_binary_string, AnnounceResponse, Event, RelayedAnswer, RelayedOffer,
+    Request, ScrapeResponse,
+};
+use super::swarms::{PeerSender, Swarms};
+use super::WEBSOCKET_TRACKER_LOG_TARGET;
+use crate::core::Tracker;
+use crate::servers::http::v1::extractors::client_ip_sources::Extract as ExtractClientIpSources;
+use crate::servers::http::v1::services::peer_ip_resolver;
+use crate::CurrentClock;
+
+/// Maximum number of `WebRTC` offers relayed for each announce request.
+#[derive(Clone, Copy, Debug)]
+pub struct MaxOffers(pub u32);
+
+/// It upgrades the HTTP connection to a WebSocket connection.
+///
+/// The IP address of the client is resolved like in the HTTP tracker, so the
+/// tracker can run behind a reverse proxy.
+pub async fn handle_upgrade(
+    State(tracker): State<Arc<Tracker>>,
+    Extension(swarms): Extension<Arc<Swarms>>,
+    Extension(MaxOffers(max_offers)): Extension<MaxOffers>,
+    ExtractClientIpSources(client_ip_sources): ExtractClientIpSources,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let remote_client_ip = match peer_ip_resolver::invoke(tracker.get_network_config(), &client_ip_sources) {
+        Ok(ip) => ip,
+        Err(error) => return (StatusCode::BAD_REQUEST, error.to_string()).into_response(),
+    };
+
+    upgrade.on_upgrade(move |socket| {
+        handle_connection(
+            socket,
+            Connection {
+                remote_client_ip,
+                max_offers,
+            },
+            tracker,
+            swarms,
+        )
+    })
+}
+
+/// The client of a WebSocket connection.
+#[derive(Clone, Copy, Debug)]
+struct Connection {
+    remote_client_ip: IpAddr,
+    max_offers: u32,
+}
+
+/// It handles the messages of a WebSocket connection until it's closed.
+///
+/// When the connection is closed, the peer leaves all the swarms it joined.
+async fn handle_connection(socket: WebSocket, connection: Connection, tracker: Arc<Tracker>, swarms: Arc<Swarms>) {
+    let (mut sink, mut stream) = socket.split();
+    let (sender, mut receiver) = unbounded_channel::<String>();
+
+    let writer = tokio::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            if sink.send(Message::Text(message)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    // The peer ID used by the client in each swarm it joined
+    let mut joined: HashMap<InfoHash, PeerId> = HashMap::new();
+
+    while let Some(Ok(message)) = stream.next().await {
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let response = match serde_json::from_str::<Request>(&text) {
+            Ok(Request::Announce(announce)) => {
+                handle_announce(&tracker, &swarms, &connection, &sender, &mut joined, announce).await
+            }
+            Ok(Request::Scrape(scrape)) => Some(handle_scrape(&tracker, scrape).await),
+            Err(error) => {
+                tracing::debug!(
+                    target: WEBSOCKET_TRACKER_LOG_TARGET,
+                    remote_client_ip = %connection.remote_client_ip, %error, "invalid message"
+                );
+
+                Some(failure("invalid message", None))
+            }
+        };
+
+        if let Some(response) = response {
+            let _ = sender.send(response.write());
+        }
+    }
+
+    for (info_hash, peer_id) in joined {
+        leave(&tracker, &swarms, &connection, &sender, &info_hash, peer_id).await;
+    }
+
+    writer.abort();
+}
+
+/// It handles an `announce` message: an announce of the peer, or an answer
+/// to an offer from another peer.
+async fn handle_announce(
+    tracker: &Tracker,
+    swarms: &Swarms,
+    connection: &Connection,
+    sender: &PeerSender,
+    joined: &mut HashMap<InfoHash, PeerId>,
+    announce: messages::Announce,
+) -> Option<messages::Response> {
+    let (Some(info_hash), Some(peer_id)) = (
+        decode_info_hash(&announce.info_hash),
+        decode_binary_string(&announce.peer_id).map(PeerId),
+    ) else {
+        return Some(failure("invalid info_hash or peer_id", None));
+    };
+
+    if tracker.requires_authentication() {
+        return Some(failure(
+            "the WebSocket tracker is not available in private mode",
+            Some(&announce.info_hash),
+        ));
+    }
+
+    if let Err(error) = tracker.authorize(&info_hash).await {
+        return Some(failure(&error.to_string(), Some(&announce.info_hash)));
+    }
+
+    if let (Some(answer), Some(to_peer_id), Some(offer_id)) = (&announce.answer, &announce.to_peer_id, &announce.offer_id) {
+        let offerer = decode_binary_string(to_peer_id).and_then(|to_peer_id| swarms.get(&info_hash, &PeerId(to_peer_id)));
+
+        if let Some(offerer) = offerer {
+            let _ = offerer.send(
+                messages::Response::Answer(RelayedAnswer {
+                    action: "announce",
+                    info_hash: announce.info_hash.clone(),
+                    peer_id: announce.peer_id.clone(),
+                    answer: answer.clone(),
+                    offer_id: offer_id.clone(),
+                })
+                .write(),
+            );
+        }
+
+        return None;
+    }
+
+    let event = announce.event.map_or(AnnounceEvent::None, |event| match event {
+        Event::Started => AnnounceEvent::Started,
+        Event::Stopped => AnnounceEvent::Stopped,
+        Event::Completed => AnnounceEvent::Completed,
+        Event::Update => AnnounceEvent::None,
+    });
+
+    let mut peer = peer::Peer {
+        peer_id,
+        peer_addr: SocketAddr::new(connection.remote_client_ip, 0),
+        updated: CurrentClock::now(),
+        uploaded: NumberOfBytes::new(announce.uploaded.unwrap_or(0)),
+        downloaded: NumberOfBytes::new(announce.downloaded.unwrap_or(0)),
+        left: NumberOfBytes::new(announce.left.unwrap_or(0)),
+        event,
+        timeout_extension: Duration::ZERO,
+        alt_peer_addr: None,
+    };
+
+    let announce_data = tracker
+        .announce_web_peer(&info_hash, &mut peer, &connection.remote_client_ip)
+        .await;
+
+    if event == AnnounceEvent::Stopped {
+        swarms.leave(&info_hash, &peer_id, sender);
+        joined.remove(&info_hash);
+    } else {
+        if let Some(previous_peer_id) = joined.insert(info_hash, peer_id).filter(|previous| *previous != peer_id) {
+            leave(tracker, swarms, connection, sender, &info_hash, previous_peer_id).await;
+        }
+
+        swarms.join(&info_hash, peer_id, sender.clone());
+
+        relay_offers(swarms, connection, &info_hash, &peer_id, &announce);
+    }
+
+    Some(messages::Response::Announce(AnnounceResponse {
+        action: "announce",
+        info_hash: announce.info_hash,
+        interval: announce_data.policy.interval,
+        complete: announce_data.stats.complete,
+        incomplete: announce_data.stats.incomplete,
+    }))
+}
+
+/// It sends each offer of the announce to a different peer of the swarm.
+fn relay_offers(swarms: &Swarms, connection: &Connection, info_hash: &InfoHash, peer_id: &PeerId, announce: &messages::Announce) {
+    let Some(offers) = &announce.offers else {
+        return;
+    };
+
+    let limit = announce.numwant.unwrap_or(connection.max_offers).min(connection.max_offers);
+
+    let limit = usize::try_from(limit).unwrap_or(usize::MAX).min(offers.len());
+
+    for ((_, receiver), offer) in swarms.others(info_hash, peer_id, limit).into_iter().zip(offers) {
+        let _ = receiver.send(
+            messages::Response::Offer(RelayedOffer {
+                action: "announce",
+                info_hash: announce.info_hash.clone(),
+                peer_id: announce.peer_id.clone(),
+                offer: offer.offer.clone(),
+                offer_id: offer.offer_id.clone(),
+            })
+            .write(),
+        );
+    }
+}
+
+/// It removes the peer from the swarm, as if it had announced the `stopped`
+/// event.
+async fn leave(
+    tracker: &Tracker,
+    swarms: &Swarms,
+    connection: &Connection,
+    sender: &PeerSender,
+    info_hash: &InfoHash,
+    peer_id: PeerId,
+) {
+    swarms.leave(info_hash, &peer_id, sender);
+
+    let mut peer = peer::Peer {
+        peer_id,
+        peer_addr: SocketAddr::new(connection.remote_client_ip, 0),
+        updated: CurrentClock::now(),
+        uploaded: NumberOfBytes::new(0),
+        downloaded: NumberOfBytes::new(0),
+        left: NumberOfBytes::new(0),
+        event: AnnounceEvent::Stopped,
+        timeout_extension: Duration::ZERO,
+        alt_peer_addr: None,
+    };
+
+    let _ = tracker
+        .announce_web_peer(info_hash, &mut peer, &connection.remote_client_ip)
+        .await;
+}
+
+/// It handles a `scrape` message.
+async fn handle_scrape(tracker: &Tracker, scrape: messages::Scrape) -> messages::Response {
+    let info_hashes = scrape.info_hash.map(messages::InfoHashes::into_vec).unwrap_or_default();
+
+    let Some(info_hashes) = info_hashes
+        .iter()
+        .map(|info_hash| decode_info_hash(info_hash))
+        .collect::<Option<Vec<InfoHash>>>()
+    else {
+        return failure("invalid info_hash", None);
+    };
+
+    let scrape_data = tracker.scrape(&info_hashes).await;
+
+    messages::Response::Scrape(ScrapeResponse {
+        action: "scrape",
+        files: scrape_data
+            .files
+            .iter()
+            .map(|(info_hash, swarm_metadata)| {
+                (
+                    encode_binary_string(&info_hash.bytes()),
+                    messages::File {
+                        complete: swarm_metadata.complete,
+                        downloaded: swarm_metadata.downloaded,
+                        incomplete: swarm_metadata.incomplete,
+                    },
+                )
+            })
+            .collect(),
+    })
+}
+
+fn failure(reason: &str, info_hash: Option<&str>) -> messages::Response {
+    messages::Response::Error(messages::Error {
+        failure_reason: reason.to_string(),
+        info_hash: info_hash.map(ToString::to_string),
+    })
+}
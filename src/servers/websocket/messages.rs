@@ -0,0 +1,247 @@
+//! Messages of the `WebTorrent` tracker protocol.
+//!
+//! Messages are JSON objects sent as WebSocket text messages. The `action`
+//! field tells the kind of message: `announce` or `scrape`.
+//!
+//! The 20-byte fields (`info_hash`, `peer_id` and `offer_id`) are "binary
+//! strings": each byte is encoded as the Unicode character with the same
+//! code point (`0` to `255`). For example, the byte `0xe9` is the character
+//! `é`.
+//!
+//! The `WebRTC` offers and answers are opaque to the tracker. They are relayed
+//! as they are received.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use torrust_tracker_primitives::info_hash::InfoHash;
+
+/// A message received from a client.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum Request {
+    Announce(Announce),
+    Scrape(Scrape),
+}
+
+/// An `announce` message.
+///
+/// Clients send it to join a swarm (optionally with some `offers` for other
+/// peers) and to answer an offer received from another peer. The answers
+/// include the `answer`, `to_peer_id` and `offer_id` fields.
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Announce {
+    pub info_hash: String,
+    pub peer_id: String,
+    pub event: Option<Event>,
+    pub numwant: Option<u32>,
+    pub uploaded: Option<i64>,
+    pub downloaded: Option<i64>,
+    pub left: Option<i64>,
+    pub offers: Option<Vec<Offer>>,
+    pub answer: Option<Value>,
+    pub to_peer_id: Option<String>,
+    pub offer_id: Option<String>,
+}
+
+/// The event of an `announce` message.
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Event {
+    Started,
+    Stopped,
+    Completed,
+    Update,
+}
+
+/// A `WebRTC` offer for another peer of the swarm.
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Offer {
+    pub offer: Value,
+    pub offer_id: String,
+}
+
+/// A `scrape` message.
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Scrape {
+    pub info_hash: Option<InfoHashes>,
+}
+
+/// The torrents of a `scrape` message: one or a list of them.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum InfoHashes {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl InfoHashes {
+    #[must_use]
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            InfoHashes::One(info_hash) => vec![info_hash],
+            InfoHashes::Many(info_hashes) => info_hashes,
+        }
+    }
+}
+
+/// A message sent to a client.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Response {
+    Announce(AnnounceResponse),
+    Offer(RelayedOffer),
+    Answer(RelayedAnswer),
+    Scrape(ScrapeResponse),
+    Error(Error),
+}
+
+/// The response to an `announce` message.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AnnounceResponse {
+    pub action: &'static str,
+    pub info_hash: String,
+    pub interval: u32,
+    pub complete: u32,
+    pub incomplete: u32,
+}
+
+/// An offer from another peer of the swarm.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct RelayedOffer {
+    pub action: &'static str,
+    pub info_hash: String,
+    /// The peer that sent the offer.
+    pub peer_id: String,
+    pub offer: Value,
+    pub offer_id: String,
+}
+
+/// The answer from the peer that received an offer.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct RelayedAnswer {
+    pub action: &'static str,
+    pub info_hash: String,
+    /// The peer that sent the answer.
+    pub peer_id: String,
+    pub answer: Value,
+    pub offer_id: String,
+}
+
+/// The response to a `scrape` message.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ScrapeResponse {
+    pub action: &'static str,
+    pub files: BTreeMap<String, File>,
+}
+
+/// The swarm statistics of a torrent in a `scrape` response.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct File {
+    pub complete: u32,
+    pub downloaded: u32,
+    pub incomplete: u32,
+}
+
+/// An error response.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Error {
+    #[serde(rename = "failure reason")]
+    pub failure_reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info_hash: Option<String>,
+}
+
+impl Response {
+    /// Returns the JSON representation of the response.
+    ///
+    /// # Panics
+    ///
+    /// It would panic if the response contained an inappropriate value.
+    #[must_use]
+    pub fn write(&self) -> String {
+        serde_json::to_string(self).expect("it should serialize the WebSocket tracker response")
+    }
+}
+
+/// It decodes a 20-byte binary string. It returns `None` if the string does
+/// not have 20 characters or any of them is not a byte.
+#[must_use]
+pub fn decode_binary_string(value: &str) -> Option<[u8; 20]> {
+    let bytes = value
+        .chars()
+        .map(|c| u8::try_from(u32::from(c)).ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    bytes.try_into().ok()
+}
+
+/// It encodes bytes as a binary string.
+#[must_use]
+pub fn encode_binary_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|&byte| char::from(byte)).collect()
+}
+
+/// It decodes an infohash encoded as a binary string.
+#[must_use]
+pub fn decode_info_hash(value: &str) -> Option<InfoHash> {
+    decode_binary_string(value).map(|bytes| InfoHash::from_bytes(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{decode_binary_string, encode_binary_string, Announce, Error, Event, Offer, Request, Response};
+
+    #[test]
+    fn binary_strings_should_be_decoded_into_bytes() {
+        let bytes = [0u8, 1, 127, 128, 233, 255, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+        assert_eq!(decode_binary_string(&encode_binary_string(&bytes)), Some(bytes));
+        assert_eq!(decode_binary_string("too short"), None);
+        assert_eq!(decode_binary_string("\u{100}1234567890123456789"), None);
+    }
+
+    #[test]
+    fn announce_messages_should_be_parsed() {
+        let message = json!({
+            "action": "announce",
+            "info_hash": "aaaaaaaaaaaaaaaaaaaa",
+            "peer_id": "bbbbbbbbbbbbbbbbbbbb",
+            "event": "started",
+            "numwant": 5,
+            "offers": [{ "offer": { "type": "offer", "sdp": "v=0" }, "offer_id": "cccccccccccccccccccc" }],
+        });
+
+        assert_eq!(
+            serde_json::from_value::<Request>(message).unwrap(),
+            Request::Announce(Announce {
+                info_hash: "aaaaaaaaaaaaaaaaaaaa".to_string(),
+                peer_id: "bbbbbbbbbbbbbbbbbbbb".to_string(),
+                event: Some(Event::Started),
+                numwant: Some(5),
+                uploaded: None,
+                downloaded: None,
+                left: None,
+                offers: Some(vec![Offer {
+                    offer: json!({ "type": "offer", "sdp": "v=0" }),
+                    offer_id: "cccccccccccccccccccc".to_string(),
+                }]),
+                answer: None,
+                to_peer_id: None,
+                offer_id: None,
+            })
+        );
+    }
+
+    #[test]
+    fn error_responses_should_use_the_failure_reason_key() {
+        let response = Response::Error(Error {
+            failure_reason: "invalid info_hash".to_string(),
+            info_hash: None,
+        });
+
+        assert_eq!(response.write(), r#"{"failure reason":"invalid info_hash"}"#);
+    }
+}
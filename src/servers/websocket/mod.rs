@@ -0,0 +1,69 @@
+//! WebSocket Tracker.
+//!
+//! This module contains the tracker for the `WebTorrent` clients. `WebTorrent`
+//! clients run in web browsers, which can't open TCP or UDP connections to
+//! other peers. They connect to each other with `WebRTC` instead, and they use
+//! the tracker to exchange the `WebRTC` offers and answers needed to set up
+//! the connections.
+//!
+//! ## Table of Contents
+//!
+//! - [Connections](#connections)
+//! - [Announce](#announce)
+//! - [Scrape](#scrape)
+//! - [Swarms](#swarms)
+//! - [Links](#links)
+//!
+//! ## Connections
+//!
+//! Clients open a WebSocket connection to the root path of the tracker, for
+//! example `wss://tracker.example.com:8000`, and keep it open while they are
+//! in any swarm. All the messages are JSON objects sent as text messages. The
+//! [`messages`] module describes them.
+//!
+//! The client IP address is resolved like in the HTTP tracker, so the
+//! tracker can run behind a reverse proxy.
+//!
+//! ## Announce
+//!
+//! `announce` messages add the peer to the swarm of a torrent. They can
+//! include some `WebRTC` offers, which the tracker sends to other peers of the
+//! swarm, up to the configured `max_offers`. The peers receiving an offer
+//! send back an `announce` message with the answer, which the tracker relays
+//! to the peer that made the offer.
+//!
+//! ```json
+//! {"action":"announce","info_hash":"...","peer_id":"...","event":"started","numwant":5,"offers":[...]}
+//! ```
+//!
+//! The tracker responds with the announce interval and the swarm statistics.
+//! Peer addresses are never returned: the peers only learn about each other
+//! through the offers.
+//!
+//! ## Scrape
+//!
+//! `scrape` messages return the swarm statistics of one or more torrents,
+//! like the HTTP tracker `scrape` requests.
+//!
+//! ## Swarms
+//!
+//! The peers announced by the WebSocket trackers are stored in the tracker
+//! like the rest of the peers, so they are counted in the swarm statistics of
+//! all the trackers. But they are never returned to the UDP and HTTP clients,
+//! which can't connect to them.
+//!
+//! When a WebSocket connection is closed, its peers leave all the swarms.
+//!
+//! The WebSocket tracker is only available in public and listed modes.
+//!
+//! ## Links
+//!
+//! - [WebTorrent](https://webtorrent.io).
+//! - [bittorrent-tracker](https://github.com/webtorrent/bittorrent-tracker):
+//!   the reference implementation of the protocol.
+pub mod handlers;
+pub mod messages;
+pub mod server;
+pub mod swarms;
+
+pub const WEBSOCKET_TRACKER_LOG_TARGET: &str = "WEBSOCKET TRACKER";
@@ -0,0 +1,220 @@
+//! Module to handle the WebSocket tracker server instances.
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::{Extension, Router};
+use axum_client_ip::SecureClientIpSource;
+use axum_server::tls_rustls::RustlsConfig;
+use axum_server::Handle;
+use derive_more::Constructor;
+use futures::future::BoxFuture;
+use tokio::sync::oneshot::{Receiver, Sender};
+use tracing::instrument;
+
+use super::handlers::{handle_upgrade, MaxOffers};
+use super::swarms::Swarms;
+use super::WEBSOCKET_TRACKER_LOG_TARGET;
+use crate::bootstrap::jobs::Started;
+use crate::core::Tracker;
+use crate::servers::http::v1::handlers::health_check;
+use crate::servers::logging::STARTED_ON;
+use crate::servers::registar::{ServiceHealthCheckJob, ServiceRegistration, ServiceRegistrationForm};
+use crate::servers::signals::{graceful_shutdown, Halted, DEFAULT_SHUTDOWN_TIMEOUT};
+use crate::servers::{custom_axum_server, socket_activation};
+
+/// Error that can occur when starting or stopping the WebSocket tracker
+/// server.
+#[derive(Debug)]
+pub enum Error {
+    Error(String),
+}
+
+#[derive(Constructor, Debug)]
+pub struct Launcher {
+    pub bind_to: SocketAddr,
+    pub tls: Option<RustlsConfig>,
+    /// Maximum number of `WebRTC` offers relayed for each announce request.
+    pub max_offers: u32,
+}
+
+impl Launcher {
+    #[instrument(skip(self, tracker, tx_start, rx_halt))]
+    fn start(&self, tracker: Arc<Tracker>, tx_start: Sender<Started>, rx_halt: Receiver<Halted>) -> BoxFuture<'static, ()> {
+        let socket = socket_activation::take_tcp_listener(self.bind_to)
+            .unwrap_or_else(|| std::net::TcpListener::bind(self.bind_to).expect("Could not bind tcp_listener to address."));
+        let address = socket.local_addr().expect("Could not get local_addr from tcp_listener.");
+
+        let handle = Handle::new();
+
+        tokio::task::spawn(graceful_shutdown(
+            handle.clone(),
+            rx_halt,
+            format!("Shutting down WebSocket tracker on socket address: {address}"),
+            DEFAULT_SHUTDOWN_TIMEOUT,
+        ));
+
+        let tls = self.tls.clone();
+        let protocol = if tls.is_some() { "wss" } else { "ws" };
+
+        tracing::info!(target: WEBSOCKET_TRACKER_LOG_TARGET, "Starting on: {protocol}://{}", address);
+
+        let app = router(tracker, self.max_offers);
+
+        // The `TimeoutAcceptor` is not used because the WebSocket connections
+        // are expected to stay idle between the announce requests.
+        let running = Box::pin(async {
+            match tls {
+                Some(tls) => custom_axum_server::from_tcp_rustls_with_timeouts(socket, tls)
+                    .handle(handle)
+                    .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+                    .await
+                    .expect("Axum server crashed."),
+                None => custom_axum_server::from_tcp_with_timeouts(socket)
+                    .handle(handle)
+                    .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+                    .await
+                    .expect("Axum server crashed."),
+            }
+        });
+
+        tracing::info!(target: WEBSOCKET_TRACKER_LOG_TARGET, "{STARTED_ON}: {protocol}://{}", address);
+
+        tx_start
+            .send(Started { address })
+            .expect("the WebSocket Tracker service should not be dropped");
+
+        running
+    }
+}
+
+/// It builds the router of the WebSocket tracker. The clients connect to the
+/// root path.
+fn router(tracker: Arc<Tracker>, max_offers: u32) -> Router {
+    Router::new()
+        .route("/health_check", get(health_check::handler))
+        .route("/", get(handle_upgrade).with_state(tracker))
+        .layer(Extension(Arc::new(Swarms::default())))
+        .layer(Extension(MaxOffers(max_offers)))
+        .layer(SecureClientIpSource::ConnectInfo.into_extension())
+}
+
+/// A WebSocket tracker server instance controller with no instance running.
+#[allow(clippy::module_name_repetitions)]
+pub type StoppedWebSocketServer = WebSocketServer<Stopped>;
+
+/// A WebSocket tracker server instance controller with a running instance.
+#[allow(clippy::module_name_repetitions)]
+pub type RunningWebSocketServer = WebSocketServer<Running>;
+
+/// A WebSocket tracker server instance controller.
+///
+/// Like the [`HttpServer`](crate::servers::http::server::HttpServer), it's a
+/// state machine that allows to start and stop the server keeping the same
+/// configuration.
+#[allow(clippy::module_name_repetitions)]
+pub struct WebSocketServer<S> {
+    /// The state of the server: `running` or `stopped`.
+    pub state: S,
+}
+
+/// A stopped WebSocket tracker server state.
+pub struct Stopped {
+    launcher: Launcher,
+}
+
+/// A running WebSocket tracker server state.
+pub struct Running {
+    /// The address where the server is bound.
+    pub binding: SocketAddr,
+    pub halt_task: tokio::sync::oneshot::Sender<Halted>,
+    pub task: tokio::task::JoinHandle<Launcher>,
+}
+
+impl WebSocketServer<Stopped> {
+    /// It creates a new `WebSocketServer` controller in `stopped` state.
+    #[must_use]
+    pub fn new(launcher: Launcher) -> Self {
+        Self {
+            state: Stopped { launcher },
+        }
+    }
+
+    /// It starts the server and returns a `WebSocketServer` controller in
+    /// `running` state.
+    ///
+    /// # Errors
+    ///
+    /// It would return an error if no `SocketAddr` is returned after launching the server.
+    ///
+    /// # Panics
+    ///
+    /// It would panic if the spawned server launcher cannot send the bound
+    /// `SocketAddr` back to the main thread.
+    pub async fn start(self, tracker: Arc<Tracker>, form: ServiceRegistrationForm) -> Result<WebSocketServer<Running>, Error> {
+        let (tx_start, rx_start) = tokio::sync::oneshot::channel::<Started>();
+        let (tx_halt, rx_halt) = tokio::sync::oneshot::channel::<Halted>();
+
+        let launcher = self.state.launcher;
+
+        let task = tokio::spawn(async move {
+            let server = launcher.start(tracker, tx_start, rx_halt);
+
+            server.await;
+
+            launcher
+        });
+
+        let binding = rx_start.await.expect("it should be able to start the service").address;
+
+        form.send(ServiceRegistration::new(binding, check_fn))
+            .expect("it should be able to send service registration");
+
+        Ok(WebSocketServer {
+            state: Running {
+                binding,
+                halt_task: tx_halt,
+                task,
+            },
+        })
+    }
+}
+
+impl WebSocketServer<Running> {
+    /// It stops the server and returns a `WebSocketServer` controller in
+    /// `stopped` state.
+    ///
+    /// # Errors
+    ///
+    /// It would return an error if the channel for the task killer signal was closed.
+    pub async fn stop(self) -> Result<WebSocketServer<Stopped>, Error> {
+        self.state
+            .halt_task
+            .send(Halted::Normal)
+            .map_err(|_| Error::Error("Task killer channel was closed.".to_string()))?;
+
+        let launcher = self.state.task.await.map_err(|e| Error::Error(e.to_string()))?;
+
+        Ok(WebSocketServer {
+            state: Stopped { launcher },
+        })
+    }
+}
+
+/// Checks the Health by connecting to the WebSocket tracker health check
+/// endpoint.
+#[must_use]
+pub fn check_fn(binding: &SocketAddr) -> ServiceHealthCheckJob {
+    let url = format!("http://{binding}/health_check"); // DevSkim: ignore DS137138
+
+    let info = format!("checking websocket tracker health check at: {url}");
+
+    let job = tokio::spawn(async move {
+        match reqwest::get(url).await {
+            Ok(response) => Ok(response.status().to_string()),
+            Err(err) => Err(err.to_string()),
+        }
+    });
+
+    ServiceHealthCheckJob::new(*binding, info, job)
+}
@@ -0,0 +1,145 @@
+//! The peers connected to a WebSocket tracker, by torrent.
+//!
+//! The tracker needs them to relay the `WebRTC` offers and answers: each peer
+//! has a channel to send messages through its WebSocket connection.
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use aquatic_udp_protocol::PeerId;
+use tokio::sync::mpsc::UnboundedSender;
+use torrust_tracker_primitives::info_hash::InfoHash;
+
+/// Channel to send messages to a connected peer.
+pub type PeerSender = UnboundedSender<String>;
+
+/// The connected peers of each torrent.
+#[derive(Debug, Default)]
+pub struct Swarms {
+    swarms: RwLock<HashMap<InfoHash, HashMap<PeerId, PeerSender>>>,
+}
+
+impl Swarms {
+    /// It adds the peer to the swarm, or updates its channel.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the swarms is poisoned.
+    pub fn join(&self, info_hash: &InfoHash, peer_id: PeerId, sender: PeerSender) {
+        self.swarms
+            .write()
+            .expect("it should get a lock on the WebSocket swarms")
+            .entry(*info_hash)
+            .or_default()
+            .insert(peer_id, sender);
+    }
+
+    /// It removes the peer from the swarm, unless it has joined again from
+    /// another connection (with another `sender`). Empty swarms are removed.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the swarms is poisoned.
+    pub fn leave(&self, info_hash: &InfoHash, peer_id: &PeerId, sender: &PeerSender) {
+        let mut swarms = self.swarms.write().expect("it should get a lock on the WebSocket swarms");
+
+        if let Some(swarm) = swarms.get_mut(info_hash) {
+            if swarm.get(peer_id).is_some_and(|current| current.same_channel(sender)) {
+                swarm.remove(peer_id);
+            }
+
+            if swarm.is_empty() {
+                swarms.remove(info_hash);
+            }
+        }
+    }
+
+    /// It returns the channel of the peer, if it's in the swarm.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the swarms is poisoned.
+    #[must_use]
+    pub fn get(&self, info_hash: &InfoHash, peer_id: &PeerId) -> Option<PeerSender> {
+        self.swarms
+            .read()
+            .expect("it should get a lock on the WebSocket swarms")
+            .get(info_hash)
+            .and_then(|swarm| swarm.get(peer_id).cloned())
+    }
+
+    /// It returns up to `limit` peers of the swarm, excluding the peer
+    /// `except`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the swarms is poisoned.
+    #[must_use]
+    pub fn others(&self, info_hash: &InfoHash, except: &PeerId, limit: usize) -> Vec<(PeerId, PeerSender)> {
+        self.swarms
+            .read()
+            .expect("it should get a lock on the WebSocket swarms")
+            .get(info_hash)
+            .map(|swarm| {
+                swarm
+                    .iter()
+                    .filter(|(peer_id, _)| *peer_id != except)
+                    .take(limit)
+                    .map(|(peer_id, sender)| (*peer_id, sender.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aquatic_udp_protocol::PeerId;
+    use tokio::sync::mpsc::unbounded_channel;
+    use torrust_tracker_primitives::info_hash::InfoHash;
+
+    use super::Swarms;
+
+    fn sample_info_hash() -> InfoHash {
+        "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap()
+    }
+
+    #[test]
+    fn it_should_return_the_other_peers_of_the_swarm() {
+        let swarms = Swarms::default();
+        let (sender, _receiver) = unbounded_channel();
+
+        swarms.join(&sample_info_hash(), PeerId(*b"-WW00000000000000001"), sender.clone());
+        swarms.join(&sample_info_hash(), PeerId(*b"-WW00000000000000002"), sender);
+
+        let others = swarms.others(&sample_info_hash(), &PeerId(*b"-WW00000000000000001"), 10);
+
+        assert_eq!(
+            others.into_iter().map(|(peer_id, _)| peer_id).collect::<Vec<_>>(),
+            vec![PeerId(*b"-WW00000000000000002")]
+        );
+    }
+
+    #[test]
+    fn it_should_remove_the_peers_leaving_the_swarm() {
+        let swarms = Swarms::default();
+        let (sender, _receiver) = unbounded_channel();
+
+        swarms.join(&sample_info_hash(), PeerId(*b"-WW00000000000000001"), sender.clone());
+        swarms.leave(&sample_info_hash(), &PeerId(*b"-WW00000000000000001"), &sender);
+
+        assert!(swarms.get(&sample_info_hash(), &PeerId(*b"-WW00000000000000001")).is_none());
+    }
+
+    #[test]
+    fn it_should_keep_the_peers_that_joined_again_from_another_connection() {
+        let swarms = Swarms::default();
+        let (old_sender, _old_receiver) = unbounded_channel();
+        let (new_sender, _new_receiver) = unbounded_channel();
+
+        swarms.join(&sample_info_hash(), PeerId(*b"-WW00000000000000001"), old_sender.clone());
+        swarms.join(&sample_info_hash(), PeerId(*b"-WW00000000000000001"), new_sender);
+        swarms.leave(&sample_info_hash(), &PeerId(*b"-WW00000000000000001"), &old_sender);
+
+        assert!(swarms.get(&sample_info_hash(), &PeerId(*b"-WW00000000000000001")).is_some());
+    }
+}
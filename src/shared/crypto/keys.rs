@@ -7,15 +7,105 @@ pub mod seeds {
     //!
     //! A **seed** is a pseudo-random number that is used as a secret key for
     //! cryptographic operations.
+    //!
+    //! The instance seed is the [configured](configure_instance_seeds) one, if
+    //! any. Otherwise, it's the ephemeral random seed generated when the
+    //! application starts.
+    //!
+    //! The instance seed can be [rotated](rotate_instance_seed). After a
+    //! rotation, the previous seed is still provided until its grace period
+    //! ends, so the values derived from it can still be verified.
+    use std::sync::RwLock;
+
+    use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+    #[cfg(test)]
     use self::detail::CURRENT_SEED;
     use crate::shared::crypto::ephemeral_instance_keys::{Seed, RANDOM_SEED};
 
+    /// The instance seeds: the current one and the one replaced by the last
+    /// rotation, if it's still in its grace period.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct Seeds {
+        pub current: Seed,
+        /// When the current seed was generated or configured.
+        pub current_since: DurationSinceUnixEpoch,
+        /// The previous seed and until when it's still valid.
+        pub previous: Option<(Seed, DurationSinceUnixEpoch)>,
+    }
+
+    /// The persistent (configured or rotated) seeds.
+    static INSTANCE_SEEDS: RwLock<Option<Seeds>> = RwLock::new(None);
+
+    /// It sets the persistent instance seeds. It must be called before the
+    /// instance seed is used for the first time.
+    ///
+    /// # Errors
+    ///
+    /// Will return the seeds back if the instance seeds were already configured.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the instance seeds is poisoned.
+    pub fn configure_instance_seeds(seeds: Seeds) -> Result<(), Seeds> {
+        let mut instance_seeds = INSTANCE_SEEDS.write().expect("it should get a lock on the instance seeds");
+
+        if instance_seeds.is_some() {
+            return Err(seeds);
+        }
+
+        *instance_seeds = Some(seeds);
+
+        Ok(())
+    }
+
+    /// It replaces the instance seed with a new one. The replaced seed is
+    /// still provided as the previous seed until `previous_valid_until`.
+    ///
+    /// It returns the new instance seeds.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the instance seeds is poisoned.
+    pub fn rotate_instance_seed(seed: Seed, now: DurationSinceUnixEpoch, previous_valid_until: DurationSinceUnixEpoch) -> Seeds {
+        let mut instance_seeds = INSTANCE_SEEDS.write().expect("it should get a lock on the instance seeds");
+
+        let current = instance_seeds.map_or(*RANDOM_SEED, |seeds| seeds.current);
+
+        let seeds = Seeds {
+            current: seed,
+            current_since: now,
+            previous: Some((current, previous_valid_until)),
+        };
+
+        *instance_seeds = Some(seeds);
+
+        seeds
+    }
+
+    /// It returns the persistent instance seeds, if they were configured or
+    /// rotated.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock on the instance seeds is poisoned.
+    #[must_use]
+    pub fn instance_seeds() -> Option<Seeds> {
+        *INSTANCE_SEEDS.read().expect("it should get a lock on the instance seeds")
+    }
+
     /// This trait is for structures that can keep and provide a seed.
     pub trait Keeper {
-        type Seed: Sized + Default + AsMut<[u8]>;
+        type Seed: Sized + Default + Copy + AsMut<[u8]>;
 
-        /// It returns a reference to the seed that is keeping.
-        fn get_seed() -> &'static Self::Seed;
+        /// It returns the seed that is keeping.
+        fn get_seed() -> Self::Seed;
+
+        /// It returns the seed replaced by the last rotation, if it's still
+        /// valid at `now`.
+        fn get_previous_seed(_now: DurationSinceUnixEpoch) -> Option<Self::Seed> {
+            None
+        }
     }
 
     /// The seed keeper for the instance. When the application is running
@@ -30,24 +120,42 @@ pub mod seeds {
     impl Keeper for Instance {
         type Seed = Seed;
 
-        fn get_seed() -> &'static Self::Seed {
-            &RANDOM_SEED
+        fn get_seed() -> Self::Seed {
+            instance_seeds().map_or(*RANDOM_SEED, |seeds| seeds.current)
+        }
+
+        fn get_previous_seed(now: DurationSinceUnixEpoch) -> Option<Self::Seed> {
+            let (seed, valid_until) = instance_seeds()?.previous?;
+
+            (now < valid_until).then_some(seed)
         }
     }
 
     impl Keeper for Current {
         type Seed = Seed;
 
-        #[allow(clippy::needless_borrow)]
-        fn get_seed() -> &'static Self::Seed {
-            &CURRENT_SEED
+        #[cfg(test)]
+        fn get_seed() -> Self::Seed {
+            *CURRENT_SEED
+        }
+
+        #[cfg(not(test))]
+        fn get_seed() -> Self::Seed {
+            Instance::get_seed()
+        }
+
+        #[cfg(not(test))]
+        fn get_previous_seed(now: DurationSinceUnixEpoch) -> Option<Self::Seed> {
+            Instance::get_previous_seed(now)
         }
     }
 
     #[cfg(test)]
     mod tests {
+        use std::time::Duration;
+
         use super::detail::ZEROED_TEST_SEED;
-        use super::{Current, Instance, Keeper};
+        use super::{rotate_instance_seed, Current, Instance, Keeper};
         use crate::shared::crypto::ephemeral_instance_keys::Seed;
 
         pub struct ZeroedTestSeed;
@@ -55,9 +163,8 @@ pub mod seeds {
         impl Keeper for ZeroedTestSeed {
             type Seed = Seed;
 
-            #[allow(clippy::needless_borrow)]
-            fn get_seed() -> &'static Self::Seed {
-                &ZEROED_TEST_SEED
+            fn get_seed() -> Self::Seed {
+                *ZEROED_TEST_SEED
             }
         }
 
@@ -70,6 +177,17 @@ pub mod seeds {
         fn the_default_seed_and_the_instance_seed_should_be_different_when_testing() {
             assert_ne!(Current::get_seed(), Instance::get_seed());
         }
+
+        #[test]
+        fn the_instance_seed_should_provide_the_previous_seed_until_the_end_of_the_grace_period_after_a_rotation() {
+            let previous = Instance::get_seed();
+
+            rotate_instance_seed([1u8; 32], Duration::ZERO, Duration::from_secs(10));
+
+            assert_eq!(Instance::get_seed(), [1u8; 32]);
+            assert_eq!(Instance::get_previous_seed(Duration::from_secs(9)), Some(previous));
+            assert_eq!(Instance::get_previous_seed(Duration::from_secs(10)), None);
+        }
     }
 
     mod detail {
@@ -81,9 +199,6 @@ pub mod seeds {
         #[cfg(test)]
         pub use ZEROED_TEST_SEED as CURRENT_SEED;
 
-        #[cfg(not(test))]
-        pub use crate::shared::crypto::ephemeral_instance_keys::RANDOM_SEED as CURRENT_SEED;
-
         #[cfg(test)]
         mod tests {
             use crate::shared::crypto::ephemeral_instance_keys::RANDOM_SEED;
@@ -0,0 +1,98 @@
+//! Resolution of the hostnames of the bind addresses.
+//!
+//! The bind addresses of the services can use hostnames instead of IP
+//! literals. They are resolved when the services start. The resolution is
+//! retried with an exponential backoff, because the DNS records may not be
+//! ready yet (for example, in a container orchestrator), before giving up.
+//!
+//! The hostnames of the upstream URLs (like the key sync URL, the webhooks or
+//! the outbound proxy) are resolved by the HTTP client each time it connects.
+//!
+//! When a hostname resolves to several addresses, the first one is used.
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use torrust_tracker_configuration::BindAddress;
+
+/// Number of times the resolution of a hostname is attempted.
+const RESOLUTION_ATTEMPTS: u32 = 5;
+
+/// Time to wait before the first retry. It's doubled after each retry.
+const RESOLUTION_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// It resolves a `host:port` address. IP literals are returned without doing
+/// any DNS query.
+///
+/// # Errors
+///
+/// Will return an error if the address is not `host:port`, or if the host
+/// can't be resolved after all the attempts.
+pub async fn resolve(address: &str) -> io::Result<SocketAddr> {
+    if let Ok(socket_addr) = address.parse::<SocketAddr>() {
+        return Ok(socket_addr);
+    }
+
+    let mut delay = RESOLUTION_RETRY_DELAY;
+    let mut attempt = 1;
+
+    loop {
+        match tokio::net::lookup_host(address).await.map(|mut addrs| addrs.next()) {
+            Ok(Some(socket_addr)) => return Ok(socket_addr),
+            Ok(None) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("the host in {address} does not have any address"),
+                ))
+            }
+            // A malformed address is not going to resolve later.
+            Err(err) if err.kind() == io::ErrorKind::InvalidInput || attempt == RESOLUTION_ATTEMPTS => return Err(err),
+            Err(err) => {
+                tracing::debug!(%address, %err, attempt, "failed to resolve the address, retrying");
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// It resolves the bind address of a service.
+///
+/// # Errors
+///
+/// Will return an error if the host can't be resolved after all the attempts.
+pub async fn resolve_bind_address(bind_address: &BindAddress) -> io::Result<SocketAddr> {
+    match bind_address.socket_addr() {
+        Some(socket_addr) => Ok(socket_addr),
+        None => resolve(&bind_address.to_string()).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+    use super::{resolve, resolve_bind_address};
+
+    #[tokio::test]
+    async fn it_should_accept_ip_literals() {
+        assert_eq!(
+            resolve("0.0.0.0:6969").await.unwrap(),
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 6969)
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_resolve_the_hostnames_of_the_bind_addresses() {
+        let socket_addr = resolve_bind_address(&"localhost:6969".parse().unwrap()).await.unwrap();
+
+        assert!(socket_addr.ip().is_loopback());
+        assert_eq!(socket_addr.port(), 6969);
+    }
+
+    #[tokio::test]
+    async fn it_should_fail_without_retrying_when_the_port_is_missing() {
+        assert!(resolve("localhost").await.is_err());
+    }
+}
@@ -2,5 +2,7 @@
 //!
 //! - [`bit_torrent`]: `BitTorrent` protocol related logic.
 //! - [`crypto`]: Encryption related logic.
+//! - [`dns`]: Resolution of the hostnames in the configuration.
 pub mod bit_torrent;
 pub mod crypto;
+pub mod dns;
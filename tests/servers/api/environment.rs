@@ -1,5 +1,6 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::executor::block_on;
 use torrust_tracker::bootstrap::app::initialize_with_configuration;
@@ -28,8 +29,8 @@ where
     S: std::fmt::Debug + std::fmt::Display,
 {
     /// Add a torrent to the tracker
-    pub fn add_torrent_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
-        self.tracker.upsert_peer_and_get_stats(info_hash, peer);
+    pub async fn add_torrent_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
+        self.tracker.upsert_peer_and_get_stats(info_hash, peer).await;
     }
 }
 
@@ -39,11 +40,22 @@ impl Environment<Stopped> {
 
         let config = Arc::new(configuration.http_api.clone().expect("missing API configuration"));
 
-        let bind_to = config.bind_address;
+        let bind_to = config.bind_address.socket_addr().expect("it should bind to an IP address");
 
         let tls = block_on(make_rust_tls(&config.tsl_config)).map(|tls| tls.expect("tls config failed"));
 
-        let server = ApiServer::new(Launcher::new(bind_to, tls));
+        let server = ApiServer::new(Launcher::new(
+            bind_to,
+            tls,
+            None,
+            Duration::from_secs(config.shutdown_timeout),
+            config.redact_peer_ips,
+            Arc::new(config.access_token_roles.clone()),
+            Duration::from_secs(config.access_token_rotation_overlap),
+            config.jwt.clone().map(Arc::new),
+            config.rate_limit,
+            configuration.clone(),
+        ));
 
         Self {
             config,
@@ -130,6 +130,10 @@ pub async fn assert_unauthorized(response: Response) {
     assert_unhandled_rejection(response, "unauthorized").await;
 }
 
+pub async fn assert_token_not_allowed(response: Response) {
+    assert_unhandled_rejection(response, "token not allowed").await;
+}
+
 pub async fn assert_failed_to_remove_torrent_from_whitelist(response: Response) {
     assert_unhandled_rejection(response, "failed to remove torrent from whitelist").await;
 }
@@ -18,6 +18,14 @@ impl Client {
         }
     }
 
+    pub async fn report_abuse(&self, abuse_report_form: AbuseReportForm) -> Response {
+        self.post_form("abuse", &abuse_report_form).await
+    }
+
+    pub async fn rotate_connection_id_secret(&self) -> Response {
+        self.post_empty("connection-id-secret/rotate").await
+    }
+
     pub async fn generate_auth_key(&self, seconds_valid: i32) -> Response {
         self.post_empty(&format!("key/{}", &seconds_valid)).await
     }
@@ -26,6 +34,14 @@ impl Client {
         self.post_form("keys", &add_key_form).await
     }
 
+    pub async fn renew_auth_key(&self, key: &str, renew_key_form: RenewKeyForm) -> Response {
+        self.put_form(&format!("key/{}", &key), &renew_key_form).await
+    }
+
+    pub async fn get_auth_keys(&self, params: Query) -> Response {
+        self.get("keys", params).await
+    }
+
     pub async fn delete_auth_key(&self, key: &str) -> Response {
         self.delete(&format!("key/{}", &key)).await
     }
@@ -43,21 +59,126 @@ impl Client {
     }
 
     pub async fn reload_whitelist(&self) -> Response {
-        self.get("whitelist/reload", Query::default()).await
+        self.post_empty("whitelist/reload").await
+    }
+
+    pub async fn get_whitelist(&self, params: Query) -> Response {
+        self.get("whitelist", params).await
+    }
+
+    pub async fn get_client_whitelist(&self) -> Response {
+        self.get("client-whitelist", Query::default()).await
+    }
+
+    pub async fn add_client_to_whitelist(&self, peer_id_prefix: &str) -> Response {
+        self.post_empty(&format!("client-whitelist/{}", &peer_id_prefix)).await
+    }
+
+    pub async fn remove_client_from_whitelist(&self, peer_id_prefix: &str) -> Response {
+        self.delete(&format!("client-whitelist/{}", &peer_id_prefix)).await
+    }
+
+    pub async fn set_key_warning_message(&self, key: &str, warning_message_form: WarningMessageForm) -> Response {
+        self.post_form(&format!("warning/key/{}", &key), &warning_message_form).await
+    }
+
+    pub async fn remove_torrent_warning_message(&self, info_hash: &str) -> Response {
+        self.delete(&format!("warning/torrent/{}", &info_hash)).await
+    }
+
+    pub async fn set_torrent_warning_message(&self, info_hash: &str, warning_message_form: WarningMessageForm) -> Response {
+        self.post_form(&format!("warning/torrent/{}", &info_hash), &warning_message_form)
+            .await
     }
 
     pub async fn get_torrent(&self, info_hash: &str) -> Response {
         self.get(&format!("torrent/{}", &info_hash), Query::default()).await
     }
 
+    pub async fn remove_torrent(&self, info_hash: &str) -> Response {
+        self.delete(&format!("torrent/{}", &info_hash)).await
+    }
+
+    pub async fn remove_torrent_peers(&self, info_hash: &str) -> Response {
+        self.delete(&format!("torrent/{}/peers", &info_hash)).await
+    }
+
+    pub async fn get_torrent_history(&self, info_hash: &str, params: Query) -> Response {
+        self.get(&format!("torrent/{info_hash}/history"), params).await
+    }
+
     pub async fn get_torrents(&self, params: Query) -> Response {
         self.get("torrents", params).await
     }
 
+    pub async fn search_torrents(&self, params: Query) -> Response {
+        self.get("torrents/search", params).await
+    }
+
+    pub async fn get_top_torrents(&self, params: Query) -> Response {
+        self.get("torrents/top", params).await
+    }
+
+    pub async fn get_torrents_accepting(&self, params: Query, media_type: &str) -> Response {
+        let mut query: Query = params;
+
+        if let Some(token) = &self.connection_info.api_token {
+            query.add_param(QueryParam::new("token", token));
+        };
+
+        reqwest::Client::new()
+            .get(self.base_url("torrents"))
+            .query(&ReqwestQuery::from(query))
+            .header(reqwest::header::ACCEPT, media_type)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    pub async fn get_audit_log(&self, params: Query) -> Response {
+        self.get("audit", params).await
+    }
+
+    pub async fn get_config(&self) -> Response {
+        self.get("config", Query::default()).await
+    }
+
+    pub async fn get_events(&self, params: Query) -> Response {
+        self.get("events", params).await
+    }
+
+    pub async fn cleanup_peers(&self) -> Response {
+        self.post_empty("maintenance/cleanup-peers").await
+    }
+
+    pub async fn compact_database(&self) -> Response {
+        self.post_empty("maintenance/compact-db").await
+    }
+
+    pub async fn rotate_token(&self) -> Response {
+        self.post_empty("token/rotate").await
+    }
+
     pub async fn get_tracker_statistics(&self) -> Response {
         self.get("stats", Query::default()).await
     }
 
+    pub async fn get_tracker_statistics_summary(&self) -> Response {
+        self.get("stats/summary", Query::default()).await
+    }
+
+    pub async fn get_database_pool_statistics(&self) -> Response {
+        self.get("stats/database-pool", Query::default()).await
+    }
+
+    pub async fn get_database_query_statistics(&self) -> Response {
+        self.get("stats/database-queries", Query::default()).await
+    }
+
+    pub async fn get_experiment_statistics(&self) -> Response {
+        self.get("stats/experiment", Query::default()).await
+    }
+
     pub async fn get(&self, path: &str, params: Query) -> Response {
         let mut query: Query = params;
 
@@ -87,6 +208,16 @@ impl Client {
             .unwrap()
     }
 
+    pub async fn put_form<T: Serialize + ?Sized>(&self, path: &str, form: &T) -> Response {
+        reqwest::Client::new()
+            .put(self.base_url(path).clone())
+            .query(&ReqwestQuery::from(self.query_with_token()))
+            .json(&form)
+            .send()
+            .await
+            .unwrap()
+    }
+
     async fn delete(&self, path: &str) -> Response {
         reqwest::Client::new()
             .delete(self.base_url(path).clone())
@@ -135,4 +266,22 @@ pub struct AddKeyForm {
     #[serde(rename = "key")]
     pub opt_key: Option<String>,
     pub seconds_valid: Option<u64>,
+    pub label: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RenewKeyForm {
+    pub seconds_valid: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AbuseReportForm {
+    pub info_hash: Option<String>,
+    pub ip: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WarningMessageForm {
+    pub message: String,
 }
@@ -1,9 +1,10 @@
+use torrust_tracker_configuration::{AccessTokenRole, HttpApiRateLimit};
 use torrust_tracker_test_helpers::configuration;
 use tracing::level_filters::LevelFilter;
 
 use crate::common::http::{Query, QueryParam};
 use crate::common::logging::{tracing_stderr_init, INIT};
-use crate::servers::api::v1::asserts::{assert_token_not_valid, assert_unauthorized};
+use crate::servers::api::v1::asserts::{assert_token_not_allowed, assert_token_not_valid, assert_unauthorized};
 use crate::servers::api::v1::client::Client;
 use crate::servers::api::Started;
 
@@ -103,3 +104,77 @@ async fn should_allow_the_token_query_param_to_be_at_any_position_in_the_url_que
 
     env.stop().await;
 }
+
+#[tokio::test]
+async fn should_only_allow_the_endpoints_of_the_token_role() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let mut configuration = configuration::ephemeral();
+    let http_api = configuration.http_api.as_mut().unwrap();
+    http_api.add_token("dashboard", "MyDashboardToken");
+    http_api
+        .access_token_roles
+        .insert("dashboard".to_string(), AccessTokenRole::Stats);
+
+    let env = Started::new(&configuration.into()).await;
+
+    let client = Client::new(env.get_connection_info());
+
+    let response = client
+        .get_request_with_query(
+            "stats",
+            Query::params([QueryParam::new("token", "MyDashboardToken")].to_vec()),
+        )
+        .await;
+
+    assert_eq!(response.status(), 200);
+
+    let response = client
+        .get_request_with_query(
+            "torrents",
+            Query::params([QueryParam::new("token", "MyDashboardToken")].to_vec()),
+        )
+        .await;
+
+    assert_token_not_allowed(response).await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_lock_out_the_clients_after_too_many_invalid_tokens() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let mut configuration = configuration::ephemeral();
+    configuration.http_api.as_mut().unwrap().rate_limit = Some(HttpApiRateLimit {
+        max_failed_auth_attempts: Some(2),
+        ..Default::default()
+    });
+
+    let env = Started::new(&configuration.into()).await;
+
+    let token = env.get_connection_info().api_token.unwrap();
+
+    let client = Client::new(env.get_connection_info());
+
+    for _ in 0..2 {
+        let response = client
+            .get_request_with_query("stats", Query::params([QueryParam::new("token", "INVALID TOKEN")].to_vec()))
+            .await;
+
+        assert_token_not_valid(response).await;
+    }
+
+    let response = client
+        .get_request_with_query("stats", Query::params([QueryParam::new("token", &token)].to_vec()))
+        .await;
+
+    assert_eq!(response.status(), 429);
+    assert_eq!(response.headers().get("retry-after").unwrap(), "900");
+
+    env.stop().await;
+}
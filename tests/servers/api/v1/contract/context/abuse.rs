@@ -0,0 +1,140 @@
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_test_helpers::configuration;
+use tracing::level_filters::LevelFilter;
+
+use crate::common::logging::{tracing_stderr_init, INIT};
+use crate::servers::api::connection_info::connection_with_invalid_token;
+use crate::servers::api::v1::asserts::{assert_bad_request, assert_token_not_valid};
+use crate::servers::api::v1::client::{AbuseReportForm, Client};
+use crate::servers::api::Started;
+
+#[tokio::test]
+async fn should_apply_the_configured_mitigations_when_an_abuse_is_reported() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let mut configuration = configuration::ephemeral();
+    configuration.core.abuse.ban_ip = true;
+    configuration.core.abuse.blacklist_info_hash = true;
+
+    let env = Started::new(&configuration.into()).await;
+
+    let info_hash = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_owned(); // DevSkim: ignore DS173237
+
+    let response = Client::new(env.get_connection_info())
+        .report_abuse(AbuseReportForm {
+            info_hash: Some(info_hash.clone()),
+            ip: Some("203.0.113.7".to_string()),
+            reason: "spam".to_string(),
+        })
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.text().await.unwrap(),
+        format!(
+            r#"{{"mitigations":[{{"action":"ban_ip","ip":"203.0.113.7"}},{{"action":"blacklist_info_hash","info_hash":"{info_hash}"}}]}}"#
+        )
+    );
+
+    assert!(env
+        .tracker
+        .authorize_peer_ip(&IpAddr::from_str("203.0.113.7").unwrap())
+        .is_err());
+    assert!(env.tracker.authorize(&InfoHash::from_str(&info_hash).unwrap()).await.is_err());
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_apply_any_mitigation_when_none_is_enabled() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .report_abuse(AbuseReportForm {
+            info_hash: None,
+            ip: Some("203.0.113.7".to_string()),
+            reason: "spam".to_string(),
+        })
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().await.unwrap(), r#"{"mitigations":[]}"#);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_when_the_abuse_report_does_not_contain_an_infohash_or_an_ip() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .report_abuse(AbuseReportForm {
+            info_hash: None,
+            ip: None,
+            reason: "spam".to_string(),
+        })
+        .await;
+
+    assert_bad_request(
+        response,
+        "Invalid abuse report: the report must contain an infohash or an IP address",
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_when_the_abuse_report_contains_an_invalid_ip() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .report_abuse(AbuseReportForm {
+            info_hash: None,
+            ip: Some("not-an-ip".to_string()),
+            reason: "spam".to_string(),
+        })
+        .await;
+
+    assert_bad_request(response, "Invalid abuse report: invalid IP address: \"not-an-ip\"").await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_reporting_an_abuse_for_unauthenticated_users() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().bind_address.as_str()))
+        .report_abuse(AbuseReportForm {
+            info_hash: None,
+            ip: Some("203.0.113.7".to_string()),
+            reason: "spam".to_string(),
+        })
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    env.stop().await;
+}
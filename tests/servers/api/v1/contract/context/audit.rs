@@ -0,0 +1,93 @@
+use torrust_tracker::servers::apis::v1::context::audit::resources::AuditEntry;
+use torrust_tracker_test_helpers::configuration;
+use tracing::level_filters::LevelFilter;
+
+use crate::common::http::{Query, QueryParam};
+use crate::common::logging::{tracing_stderr_init, INIT};
+use crate::servers::api::connection_info::connection_with_invalid_token;
+use crate::servers::api::v1::asserts::{assert_ok, assert_token_not_valid};
+use crate::servers::api::v1::client::Client;
+use crate::servers::api::Started;
+
+#[tokio::test]
+async fn should_record_the_requests_changing_the_tracker_state_in_the_audit_log() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let client = Client::new(env.get_connection_info());
+
+    let info_hash = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d";
+
+    assert_ok(client.whitelist_a_torrent(info_hash).await).await;
+
+    let response = client.get_audit_log(Query::empty()).await;
+
+    assert_eq!(response.status(), 200);
+
+    let entries = response.json::<Vec<AuditEntry>>().await.unwrap();
+
+    // The `GET` requests are not recorded
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].actor, "admin");
+    assert_eq!(entries[0].client_ip, Some("127.0.0.1".to_string()));
+    assert_eq!(entries[0].method, "POST");
+    assert_eq!(entries[0].path, format!("/api/v1/whitelist/{info_hash}"));
+    assert_eq!(entries[0].status, 200);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_filtering_the_audit_log() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let client = Client::new(env.get_connection_info());
+
+    let info_hash = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d";
+
+    assert_ok(client.whitelist_a_torrent(info_hash).await).await;
+    assert_ok(client.remove_torrent_from_whitelist(info_hash).await).await;
+
+    let response = client
+        .get_audit_log(Query::params(
+            [
+                QueryParam::new("method", "DELETE"),
+                QueryParam::new("path", "/api/v1/whitelist"),
+            ]
+            .to_vec(),
+        ))
+        .await;
+
+    assert_eq!(response.status(), 200);
+
+    let entries = response.json::<Vec<AuditEntry>>().await.unwrap();
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].method, "DELETE");
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_getting_the_audit_log_for_unauthenticated_users() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().bind_address.as_str()))
+        .get_audit_log(Query::empty())
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    env.stop().await;
+}
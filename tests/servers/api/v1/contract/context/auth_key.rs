@@ -2,17 +2,19 @@ use std::time::Duration;
 
 use serde::Serialize;
 use torrust_tracker::core::auth::Key;
+use torrust_tracker::servers::apis::v1::context::auth_key::resources::AuthKey;
 use torrust_tracker_test_helpers::configuration;
 use tracing::level_filters::LevelFilter;
 
+use crate::common::http::{Query, QueryParam};
 use crate::common::logging::{tracing_stderr_init, INIT};
 use crate::servers::api::connection_info::{connection_with_invalid_token, connection_with_no_token};
 use crate::servers::api::v1::asserts::{
-    assert_auth_key_utf8, assert_failed_to_delete_key, assert_failed_to_generate_key, assert_failed_to_reload_keys,
-    assert_invalid_auth_key_get_param, assert_invalid_auth_key_post_param, assert_ok, assert_token_not_valid,
-    assert_unauthorized, assert_unprocessable_auth_key_duration_param,
+    assert_auth_key_utf8, assert_bad_request_with_text, assert_failed_to_delete_key, assert_failed_to_generate_key,
+    assert_failed_to_reload_keys, assert_invalid_auth_key_get_param, assert_invalid_auth_key_post_param, assert_ok,
+    assert_token_not_valid, assert_unauthorized, assert_unprocessable_auth_key_duration_param,
 };
-use crate::servers::api::v1::client::{AddKeyForm, Client};
+use crate::servers::api::v1::client::{AddKeyForm, Client, RenewKeyForm};
 use crate::servers::api::{force_database_error, Started};
 
 #[tokio::test]
@@ -27,6 +29,7 @@ async fn should_allow_generating_a_new_random_auth_key() {
         .add_auth_key(AddKeyForm {
             opt_key: None,
             seconds_valid: Some(60),
+            label: None,
         })
         .await;
 
@@ -53,6 +56,7 @@ async fn should_allow_uploading_a_preexisting_auth_key() {
         .add_auth_key(AddKeyForm {
             opt_key: Some("Xc1L4PbQJSFGlrgSRZl8wxSFAuMa21z5".to_string()),
             seconds_valid: Some(60),
+            label: None,
         })
         .await;
 
@@ -67,6 +71,30 @@ async fn should_allow_uploading_a_preexisting_auth_key() {
     env.stop().await;
 }
 
+#[tokio::test]
+async fn should_allow_labelling_a_new_auth_key() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .add_auth_key(AddKeyForm {
+            opt_key: None,
+            seconds_valid: Some(60),
+            label: Some("user-1234".to_string()),
+        })
+        .await;
+
+    let auth_key_resource = assert_auth_key_utf8(response).await;
+
+    assert_eq!(auth_key_resource.label, Some("user-1234".to_string()));
+    assert!(auth_key_resource.created_at.is_some());
+
+    env.stop().await;
+}
+
 #[tokio::test]
 async fn should_not_allow_generating_a_new_auth_key_for_unauthenticated_users() {
     INIT.call_once(|| {
@@ -79,6 +107,7 @@ async fn should_not_allow_generating_a_new_auth_key_for_unauthenticated_users()
         .add_auth_key(AddKeyForm {
             opt_key: None,
             seconds_valid: Some(60),
+            label: None,
         })
         .await;
 
@@ -88,6 +117,7 @@ async fn should_not_allow_generating_a_new_auth_key_for_unauthenticated_users()
         .add_auth_key(AddKeyForm {
             opt_key: None,
             seconds_valid: Some(60),
+            label: None,
         })
         .await;
 
@@ -110,6 +140,7 @@ async fn should_fail_when_the_auth_key_cannot_be_generated() {
         .add_auth_key(AddKeyForm {
             opt_key: None,
             seconds_valid: Some(60),
+            label: None,
         })
         .await;
 
@@ -142,6 +173,95 @@ async fn should_allow_deleting_an_auth_key() {
     env.stop().await;
 }
 
+#[tokio::test]
+async fn should_allow_listing_the_auth_keys_with_pagination() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    // keys are ordered alphabetically
+    let auth_key_1 = env
+        .tracker
+        .add_permanent_auth_key("Xc1L4PbQJSFGlrgSRZl8wxSFAuMa21z5".parse::<Key>().unwrap())
+        .await
+        .unwrap();
+    let auth_key_2 = env
+        .tracker
+        .add_permanent_auth_key("Ac1L4PbQJSFGlrgSRZl8wxSFAuMa21z5".parse::<Key>().unwrap())
+        .await
+        .unwrap();
+
+    let api_client = Client::new(env.get_connection_info());
+
+    let response = api_client.get_auth_keys(Query::empty()).await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.json::<Vec<AuthKey>>().await.unwrap(),
+        vec![AuthKey::from(auth_key_2), AuthKey::from(auth_key_1.clone())]
+    );
+
+    let response = api_client
+        .get_auth_keys(Query::params([QueryParam::new("offset", "1")].to_vec()))
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.json::<Vec<AuthKey>>().await.unwrap(),
+        vec![AuthKey::from(auth_key_1)]
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_renewing_an_expired_auth_key() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let auth_key = env
+        .tracker
+        .add_auth_key(
+            "Xc1L4PbQJSFGlrgSRZl8wxSFAuMa21z5".parse::<Key>().unwrap(),
+            Some(Duration::ZERO),
+        )
+        .await
+        .unwrap();
+
+    let response = Client::new(env.get_connection_info())
+        .renew_auth_key(&auth_key.key.to_string(), RenewKeyForm { seconds_valid: 60 })
+        .await;
+
+    let auth_key_resource = assert_auth_key_utf8(response).await;
+
+    assert_eq!(auth_key_resource.key, auth_key.key.to_string());
+    assert!(env.tracker.authenticate(&auth_key.key).await.is_ok());
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_renewing_an_unknown_auth_key() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .renew_auth_key("Xc1L4PbQJSFGlrgSRZl8wxSFAuMa21z5", RenewKeyForm { seconds_valid: 60 })
+        .await;
+
+    assert_bad_request_with_text(response, "unknown auth key").await;
+
+    env.stop().await;
+}
+
 #[tokio::test]
 async fn should_fail_generating_a_new_auth_key_when_the_provided_key_is_invalid() {
     #[derive(Serialize, Debug)]
@@ -0,0 +1,79 @@
+use aquatic_udp_protocol::PeerId;
+use torrust_tracker_test_helpers::configuration;
+use tracing::level_filters::LevelFilter;
+
+use crate::common::logging::{tracing_stderr_init, INIT};
+use crate::servers::api::connection_info::connection_with_invalid_token;
+use crate::servers::api::v1::asserts::{assert_bad_request, assert_ok, assert_token_not_valid};
+use crate::servers::api::v1::client::Client;
+use crate::servers::api::Started;
+
+#[tokio::test]
+async fn should_allow_adding_and_removing_a_client_from_the_whitelist() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral_private().into()).await;
+
+    let client = Client::new(env.get_connection_info());
+
+    let response = client.add_client_to_whitelist("-qB45").await;
+
+    assert_ok(response).await;
+    assert!(env.tracker.authorize_client(&PeerId(*b"-qB4520-000000000001")).is_ok());
+    assert!(env.tracker.authorize_client(&PeerId(*b"-TR4000-000000000001")).is_err());
+
+    let response = client.get_client_whitelist().await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.json::<Vec<String>>().await.unwrap(), vec!["-qB45".to_string()]);
+
+    let response = client.remove_client_from_whitelist("-qB45").await;
+
+    assert_ok(response).await;
+    assert!(env.tracker.get_client_whitelist().is_empty());
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_when_the_peer_id_prefix_is_longer_than_a_peer_id() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral_private().into()).await;
+
+    let invalid_prefix = "a".repeat(21);
+
+    let response = Client::new(env.get_connection_info())
+        .add_client_to_whitelist(&invalid_prefix)
+        .await;
+
+    assert_bad_request(
+        response,
+        &format!("Invalid peer ID prefix param \"{invalid_prefix}\": it must have between 1 and 20 ASCII characters"),
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_changing_the_client_whitelist_for_unauthenticated_users() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral_private().into()).await;
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().bind_address.as_str()))
+        .add_client_to_whitelist("-qB45")
+        .await;
+
+    assert_token_not_valid(response).await;
+    assert!(env.tracker.get_client_whitelist().is_empty());
+
+    env.stop().await;
+}
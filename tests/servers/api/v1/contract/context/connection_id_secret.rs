@@ -0,0 +1,63 @@
+use torrust_tracker_test_helpers::configuration;
+use tracing::level_filters::LevelFilter;
+
+use crate::common::logging::{tracing_stderr_init, INIT};
+use crate::servers::api::connection_info::connection_with_invalid_token;
+use crate::servers::api::v1::asserts::assert_token_not_valid;
+use crate::servers::api::v1::client::Client;
+use crate::servers::api::Started;
+
+#[tokio::test]
+async fn should_allow_rotating_the_udp_connection_id_secret() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let secret_file = std::env::temp_dir().join(format!("udp_connection_id_secret_{}.json", rand::random::<u64>()));
+
+    let mut configuration = configuration::ephemeral();
+    configuration.core.udp_connection_id_secret_file = Some(secret_file.to_string_lossy().to_string());
+    configuration.core.udp_connection_id_secret_rotation.grace_period = 600;
+
+    let env = Started::new(&configuration.into()).await;
+
+    let response = Client::new(env.get_connection_info()).rotate_connection_id_secret().await;
+
+    assert_eq!(response.status(), 200);
+
+    let rotation: serde_json::Value = response.json().await.unwrap();
+
+    assert_eq!(
+        rotation["previous_secret_valid_until"].as_u64().unwrap(),
+        rotation["rotated_at"].as_u64().unwrap() + 600
+    );
+
+    let secret_file_contents: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&secret_file).unwrap()).unwrap();
+
+    assert_eq!(secret_file_contents["created_at"], rotation["rotated_at"]);
+    assert_eq!(
+        secret_file_contents["previous_secret_valid_until"],
+        rotation["previous_secret_valid_until"]
+    );
+
+    std::fs::remove_file(secret_file).unwrap();
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_rotating_the_udp_connection_id_secret_for_unauthenticated_users() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().bind_address.as_str()))
+        .rotate_connection_id_secret()
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    env.stop().await;
+}
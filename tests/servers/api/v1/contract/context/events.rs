@@ -0,0 +1,92 @@
+use std::str::FromStr;
+
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_test_helpers::configuration;
+use tracing::level_filters::LevelFilter;
+
+use crate::common::http::{Query, QueryParam};
+use crate::common::logging::{tracing_stderr_init, INIT};
+use crate::servers::api::connection_info::connection_with_invalid_token;
+use crate::servers::api::v1::asserts::{assert_bad_request, assert_token_not_valid};
+use crate::servers::api::v1::client::Client;
+use crate::servers::api::Started;
+
+#[tokio::test]
+async fn should_stream_the_events_of_the_requested_torrents() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+    let another_info_hash = InfoHash::from_str("3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0").unwrap(); // DevSkim: ignore DS173237
+
+    let mut response = Client::new(env.get_connection_info())
+        .get_events(Query::params(
+            [QueryParam::new("info_hash", &info_hash.to_hex_string())].to_vec(),
+        ))
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-type").unwrap(), "text/event-stream");
+
+    env.tracker.scrape(&vec![another_info_hash, info_hash]).await;
+
+    let mut body = String::new();
+
+    while !body.contains("\n\n") {
+        let chunk = response
+            .chunk()
+            .await
+            .expect("it should receive the events")
+            .expect("the stream should not end");
+        body.push_str(&String::from_utf8_lossy(&chunk));
+    }
+
+    assert!(body.starts_with("event: scrape\n"), "unexpected events: {body}");
+    assert!(body.contains(&info_hash.to_hex_string()));
+    assert!(!body.contains(&another_info_hash.to_hex_string()));
+
+    drop(response);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_streaming_the_events_when_the_infohash_is_invalid() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_events(Query::params([QueryParam::new("info_hash", "INVALID INFOHASH")].to_vec()))
+        .await;
+
+    assert_bad_request(
+        response,
+        "Invalid URL: invalid infohash param: string \"INVALID INFOHASH\", expected a 40 character long string",
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_streaming_the_events_for_unauthenticated_users() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().bind_address.as_str()))
+        .get_events(Query::default())
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    env.stop().await;
+}
@@ -0,0 +1,88 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use torrust_tracker::servers::apis::v1::context::maintenance::resources::{CleanupReport, CompactionReport};
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+use torrust_tracker_test_helpers::configuration;
+use tracing::level_filters::LevelFilter;
+
+use crate::common::logging::{tracing_stderr_init, INIT};
+use crate::servers::api::connection_info::{connection_with_invalid_token, connection_with_no_token};
+use crate::servers::api::v1::asserts::{assert_token_not_valid, assert_unauthorized};
+use crate::servers::api::v1::client::Client;
+use crate::servers::api::Started;
+
+#[tokio::test]
+async fn should_allow_removing_the_inactive_peers_on_demand() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let active_torrent = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+    let inactive_torrent = InfoHash::from_str("2b66980093bc11806fab50cb3cb41835b95a0362").unwrap();
+
+    env.add_torrent_peer(&active_torrent, &PeerBuilder::default().into()).await;
+    env.add_torrent_peer(
+        &inactive_torrent,
+        &PeerBuilder::default().last_updated_on(Duration::ZERO).into(),
+    )
+    .await;
+
+    let response = Client::new(env.get_connection_info()).cleanup_peers().await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.json::<CleanupReport>().await.unwrap(),
+        CleanupReport {
+            removed_peers: 1,
+            removed_torrents: 1,
+        }
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_compacting_the_database_on_demand() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info()).compact_database().await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.json::<CompactionReport>().await.unwrap(),
+        CompactionReport { removed_expired_keys: 0 }
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_running_the_maintenance_for_unauthenticated_users() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().bind_address.as_str()))
+        .cleanup_peers()
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    let response = Client::new(connection_with_no_token(env.get_connection_info().bind_address.as_str()))
+        .compact_database()
+        .await;
+
+    assert_unauthorized(response).await;
+
+    env.stop().await;
+}
@@ -0,0 +1,89 @@
+use torrust_tracker_test_helpers::configuration;
+use tracing::level_filters::LevelFilter;
+
+use crate::common::logging::{tracing_stderr_init, INIT};
+use crate::servers::api::v1::asserts::assert_unauthorized;
+use crate::servers::api::v1::client::get;
+use crate::servers::api::Started;
+
+#[tokio::test]
+async fn openapi_endpoint_should_return_the_openapi_specification_without_authentication() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let url = format!("http://{}/api/openapi.json", env.get_connection_info().bind_address);
+
+    let response = get(&url, None).await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-type").unwrap(), "application/json");
+
+    let specification = response.json::<serde_json::Value>().await.unwrap();
+
+    assert!(specification["paths"]["/api/v1/torrents"].is_object());
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn openapi_endpoint_should_ignore_an_invalid_token() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let url = format!(
+        "http://{}/api/openapi.json?token=InvalidToken",
+        env.get_connection_info().bind_address
+    );
+
+    let response = get(&url, None).await;
+
+    assert_eq!(response.status(), 200);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn openapi_specification_should_describe_the_response_to_the_unauthenticated_requests() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let bind_address = env.get_connection_info().bind_address;
+
+    let specification = get(&format!("http://{bind_address}/api/openapi.json"), None)
+        .await
+        .json::<serde_json::Value>()
+        .await
+        .unwrap();
+
+    let documented_response = &specification["components"]["responses"]["UnhandledRejection"];
+
+    assert_eq!(
+        specification["paths"]["/api/v1/stats"]["get"]["responses"]["500"]["$ref"],
+        "#/components/responses/UnhandledRejection"
+    );
+
+    let response = get(&format!("http://{bind_address}/api/v1/stats"), None).await;
+
+    assert_eq!(response.status(), 500);
+
+    let content_type = response.headers().get("content-type").unwrap().to_str().unwrap();
+
+    assert!(documented_response["content"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .any(|media_type| content_type.starts_with(media_type.as_str())));
+
+    assert_unauthorized(response).await;
+
+    env.stop().await;
+}
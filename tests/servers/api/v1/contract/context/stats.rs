@@ -1,6 +1,9 @@
 use std::str::FromStr;
 
-use torrust_tracker::servers::apis::v1::context::stats::resources::Stats;
+use torrust_tracker::servers::apis::v1::context::stats::resources::{
+    DatabasePoolStats, DatabaseQueryStats, ExperimentStats, Stats, StatsSummary,
+};
+use torrust_tracker_configuration::Experiment;
 use torrust_tracker_primitives::info_hash::InfoHash;
 use torrust_tracker_primitives::peer::fixture::PeerBuilder;
 use torrust_tracker_test_helpers::configuration;
@@ -23,7 +26,8 @@ async fn should_allow_getting_tracker_statistics() {
     env.add_torrent_peer(
         &InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(),
         &PeerBuilder::default().into(),
-    );
+    )
+    .await;
 
     let response = Client::new(env.get_connection_info()).get_tracker_statistics().await;
 
@@ -34,6 +38,8 @@ async fn should_allow_getting_tracker_statistics() {
             seeders: 1,
             completed: 0,
             leechers: 0,
+            uploaded_bytes_per_second: 0,
+            downloaded_bytes_per_second: 0,
             tcp4_connections_handled: 0,
             tcp4_announces_handled: 0,
             tcp4_scrapes_handled: 0,
@@ -46,6 +52,17 @@ async fn should_allow_getting_tracker_statistics() {
             udp6_connections_handled: 0,
             udp6_announces_handled: 0,
             udp6_scrapes_handled: 0,
+            tcp_forwarded_announces_handled: 0,
+            tcp_forwarded_ip_proxy_changes: 0,
+            tcp_announces_with_tracker_id: 0,
+            tcp_announces_with_invalid_tracker_id: 0,
+            tcp_announces_without_tracker_id: 0,
+            api_requests_rate_limited: 0,
+            api_requests_locked_out: 0,
+            api_failed_auth_attempts: 0,
+            api_lockouts: 0,
+            scrape_cache_hits: 0,
+            scrape_cache_misses: 0,
         },
     )
     .await;
@@ -53,6 +70,122 @@ async fn should_allow_getting_tracker_statistics() {
     env.stop().await;
 }
 
+#[tokio::test]
+async fn should_allow_getting_the_tracker_statistics_summary() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    env.add_torrent_peer(
+        &InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(),
+        &PeerBuilder::default().into(),
+    )
+    .await;
+
+    let response = Client::new(env.get_connection_info()).get_tracker_statistics_summary().await;
+
+    assert_eq!(response.status(), 200);
+
+    let summary = response.json::<StatsSummary>().await.unwrap();
+
+    assert_eq!(summary.torrents, 1);
+    assert_eq!(summary.seeders, 1);
+    assert_eq!(summary.leechers, 0);
+    assert_eq!(summary.last_hour_udp_announces, 0);
+    assert_eq!(summary.last_day_http_announces, 0);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_getting_the_database_pool_statistics() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let mut configuration = configuration::ephemeral();
+    configuration.core.database.pool.max_size = 4;
+
+    let env = Started::new(&configuration.into()).await;
+
+    let response = Client::new(env.get_connection_info()).get_database_pool_statistics().await;
+
+    assert_eq!(response.status(), 200);
+
+    let database_pool = response.json::<DatabasePoolStats>().await.unwrap();
+
+    assert_eq!(database_pool.max_size, 4);
+    assert!(database_pool.connections <= 4);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_getting_the_database_query_statistics() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let client = Client::new(env.get_connection_info());
+
+    let response = client.whitelist_a_torrent("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").await;
+
+    assert_eq!(response.status(), 200);
+
+    let response = client.get_database_query_statistics().await;
+
+    assert_eq!(response.status(), 200);
+
+    let database_queries = response.json::<Vec<DatabaseQueryStats>>().await.unwrap();
+
+    let whitelisting = database_queries
+        .iter()
+        .find(|stats| stats.operation == "add_info_hash_to_whitelist")
+        .unwrap();
+
+    assert_eq!(whitelisting.driver, "sqlite3");
+    assert_eq!(whitelisting.queries, 1);
+    assert_eq!(whitelisting.errors, 0);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_getting_the_experiment_statistics() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let mut configuration = configuration::ephemeral();
+    configuration.core.experiment = Some(Experiment {
+        name: "longer-intervals".to_string(),
+        percentage: 10,
+        ..Default::default()
+    });
+
+    let env = Started::new(&configuration.into()).await;
+
+    let response = Client::new(env.get_connection_info()).get_experiment_statistics().await;
+
+    assert_eq!(response.status(), 200);
+
+    let experiment = response.json::<Vec<ExperimentStats>>().await.unwrap();
+
+    assert_eq!(
+        experiment
+            .iter()
+            .map(|stats| (stats.experiment.as_str(), stats.group.as_str(), stats.announces))
+            .collect::<Vec<_>>(),
+        vec![("longer-intervals", "control", 0), ("longer-intervals", "experiment", 0)]
+    );
+
+    env.stop().await;
+}
+
 #[tokio::test]
 async fn should_not_allow_getting_tracker_statistics_for_unauthenticated_users() {
     INIT.call_once(|| {
@@ -0,0 +1,85 @@
+use torrust_tracker::servers::apis::v1::context::token::resources::RotatedToken;
+use torrust_tracker_test_helpers::configuration;
+use tracing::level_filters::LevelFilter;
+
+use crate::common::logging::{tracing_stderr_init, INIT};
+use crate::servers::api::connection_info::{connection_with_invalid_token, ConnectionInfo};
+use crate::servers::api::v1::asserts::{assert_token_not_valid, assert_unauthorized};
+use crate::servers::api::v1::client::Client;
+use crate::servers::api::Started;
+
+#[tokio::test]
+async fn should_allow_rotating_the_access_token() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info()).rotate_token().await;
+
+    assert_eq!(response.status(), 200);
+
+    let rotated_token = response.json::<RotatedToken>().await.unwrap();
+
+    assert_eq!(rotated_token.label, "admin");
+    assert_ne!(Some(rotated_token.token.clone()), env.get_connection_info().api_token);
+
+    let bind_address = env.get_connection_info().bind_address;
+
+    let response = Client::new(ConnectionInfo::authenticated(&bind_address, &rotated_token.token))
+        .get_tracker_statistics()
+        .await;
+
+    assert_eq!(response.status(), 200);
+
+    // The previous token is accepted during the overlap period
+    let response = Client::new(env.get_connection_info()).get_tracker_statistics().await;
+
+    assert_eq!(response.status(), 200);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_reject_the_previous_token_after_the_overlap_period() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let mut configuration = configuration::ephemeral();
+    configuration.http_api.as_mut().unwrap().access_token_rotation_overlap = 0;
+
+    let env = Started::new(&configuration.into()).await;
+
+    let response = Client::new(env.get_connection_info()).rotate_token().await;
+
+    assert_eq!(response.status(), 200);
+
+    let response = Client::new(env.get_connection_info()).get_tracker_statistics().await;
+
+    assert_token_not_valid(response).await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_rotating_the_access_token_for_unauthenticated_users() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let bind_address = env.get_connection_info().bind_address;
+
+    let response = Client::new(connection_with_invalid_token(&bind_address)).rotate_token().await;
+
+    assert_token_not_valid(response).await;
+
+    let response = Client::new(ConnectionInfo::anonymous(&bind_address)).rotate_token().await;
+
+    assert_unauthorized(response).await;
+
+    env.stop().await;
+}
@@ -1,9 +1,12 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::str::FromStr;
 
+use torrust_tracker::servers::apis::v1::context::torrent::resources::history::HistoryItem;
 use torrust_tracker::servers::apis::v1::context::torrent::resources::peer::Peer;
 use torrust_tracker::servers::apis::v1::context::torrent::resources::torrent::{self, Torrent};
 use torrust_tracker_primitives::info_hash::InfoHash;
 use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
 use torrust_tracker_test_helpers::configuration;
 use tracing::level_filters::LevelFilter;
 
@@ -11,7 +14,7 @@ use crate::common::http::{Query, QueryParam};
 use crate::common::logging::{tracing_stderr_init, INIT};
 use crate::servers::api::connection_info::{connection_with_invalid_token, connection_with_no_token};
 use crate::servers::api::v1::asserts::{
-    assert_bad_request, assert_invalid_infohash_param, assert_not_found, assert_token_not_valid, assert_torrent_info,
+    assert_bad_request, assert_invalid_infohash_param, assert_not_found, assert_ok, assert_token_not_valid, assert_torrent_info,
     assert_torrent_list, assert_torrent_not_known, assert_unauthorized,
 };
 use crate::servers::api::v1::client::Client;
@@ -30,7 +33,7 @@ async fn should_allow_getting_all_torrents() {
 
     let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
 
-    env.add_torrent_peer(&info_hash, &PeerBuilder::default().into());
+    env.add_torrent_peer(&info_hash, &PeerBuilder::default().into()).await;
 
     let response = Client::new(env.get_connection_info()).get_torrents(Query::empty()).await;
 
@@ -48,6 +51,49 @@ async fn should_allow_getting_all_torrents() {
     env.stop().await;
 }
 
+#[tokio::test]
+async fn should_allow_getting_all_torrents_as_csv() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+
+    env.add_torrent_peer(&info_hash, &PeerBuilder::default().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_torrents_accepting(Query::empty(), "text/csv")
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-type").unwrap(), "text/csv; charset=utf-8");
+    assert_eq!(
+        response.text().await.unwrap(),
+        "info_hash,seeders,completed,leechers\r\n9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d,1,0,0\r\n"
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_getting_torrents_in_an_unsupported_format() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_torrents_accepting(Query::empty(), "image/png")
+        .await;
+
+    assert_eq!(response.status(), 406);
+
+    env.stop().await;
+}
+
 #[tokio::test]
 async fn should_allow_limiting_the_torrents_in_the_result() {
     INIT.call_once(|| {
@@ -60,8 +106,8 @@ async fn should_allow_limiting_the_torrents_in_the_result() {
     let info_hash_1 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
     let info_hash_2 = InfoHash::from_str("0b3aea4adc213ce32295be85d3883a63bca25446").unwrap();
 
-    env.add_torrent_peer(&info_hash_1, &PeerBuilder::default().into());
-    env.add_torrent_peer(&info_hash_2, &PeerBuilder::default().into());
+    env.add_torrent_peer(&info_hash_1, &PeerBuilder::default().into()).await;
+    env.add_torrent_peer(&info_hash_2, &PeerBuilder::default().into()).await;
 
     let response = Client::new(env.get_connection_info())
         .get_torrents(Query::params([QueryParam::new("limit", "1")].to_vec()))
@@ -93,8 +139,8 @@ async fn should_allow_the_torrents_result_pagination() {
     let info_hash_1 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
     let info_hash_2 = InfoHash::from_str("0b3aea4adc213ce32295be85d3883a63bca25446").unwrap();
 
-    env.add_torrent_peer(&info_hash_1, &PeerBuilder::default().into());
-    env.add_torrent_peer(&info_hash_2, &PeerBuilder::default().into());
+    env.add_torrent_peer(&info_hash_1, &PeerBuilder::default().into()).await;
+    env.add_torrent_peer(&info_hash_2, &PeerBuilder::default().into()).await;
 
     let response = Client::new(env.get_connection_info())
         .get_torrents(Query::params([QueryParam::new("offset", "1")].to_vec()))
@@ -114,6 +160,207 @@ async fn should_allow_the_torrents_result_pagination() {
     env.stop().await;
 }
 
+#[tokio::test]
+async fn should_allow_sorting_the_torrents_in_the_result() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash_1 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+    let info_hash_2 = InfoHash::from_str("0b3aea4adc213ce32295be85d3883a63bca25446").unwrap();
+
+    env.add_torrent_peer(&info_hash_1, &PeerBuilder::default().into()).await;
+    env.add_torrent_peer(&info_hash_1, &PeerBuilder::leecher().into()).await;
+    env.add_torrent_peer(&info_hash_2, &PeerBuilder::default().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_torrents(Query::params(
+            [QueryParam::new("sort", "peers"), QueryParam::new("limit", "1")].to_vec(),
+        ))
+        .await;
+
+    assert_torrent_list(
+        response,
+        vec![torrent::ListItem {
+            info_hash: "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_string(),
+            seeders: 1,
+            completed: 0,
+            leechers: 1,
+        }],
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_getting_torrents_when_the_sort_query_parameter_cannot_be_parsed() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_torrents(Query::params([QueryParam::new("sort", "seeders")].to_vec()))
+        .await;
+
+    assert_bad_request(
+        response,
+        "Failed to deserialize query string: invalid sort: seeders, expected one of: peers, completed, recent",
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_searching_the_torrents_by_info_hash_prefix() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash_1 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+    let info_hash_2 = InfoHash::from_str("0b3aea4adc213ce32295be85d3883a63bca25446").unwrap();
+
+    env.add_torrent_peer(&info_hash_1, &PeerBuilder::default().into()).await;
+    env.add_torrent_peer(&info_hash_2, &PeerBuilder::default().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .search_torrents(Query::params([QueryParam::new("hash_prefix", "9E02")].to_vec()))
+        .await;
+
+    assert_torrent_list(
+        response,
+        vec![torrent::ListItem {
+            info_hash: "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_string(),
+            seeders: 1,
+            completed: 0,
+            leechers: 0,
+        }],
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_searching_the_torrents_announced_recently() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash_1 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+    let info_hash_2 = InfoHash::from_str("0b3aea4adc213ce32295be85d3883a63bca25446").unwrap();
+
+    env.add_torrent_peer(&info_hash_1, &PeerBuilder::default().into()).await;
+    env.add_torrent_peer(
+        &info_hash_2,
+        &PeerBuilder::default().last_updated_on(DurationSinceUnixEpoch::ZERO).into(),
+    )
+    .await;
+
+    let response = Client::new(env.get_connection_info())
+        .search_torrents(Query::params([QueryParam::new("active_since", "3600")].to_vec()))
+        .await;
+
+    assert_torrent_list(
+        response,
+        vec![torrent::ListItem {
+            info_hash: "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_string(),
+            seeders: 1,
+            completed: 0,
+            leechers: 0,
+        }],
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_searching_torrents_when_the_info_hash_prefix_is_invalid() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .search_torrents(Query::params([QueryParam::new("hash_prefix", "xyz")].to_vec()))
+        .await;
+
+    assert_bad_request(
+        response,
+        "Failed to deserialize query string: invalid infohash prefix: xyz, expected 1 to 40 hexadecimal characters",
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_getting_the_torrents_with_more_peers() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash_1 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+    let info_hash_2 = InfoHash::from_str("0b3aea4adc213ce32295be85d3883a63bca25446").unwrap();
+
+    env.add_torrent_peer(&info_hash_1, &PeerBuilder::default().into()).await;
+    env.add_torrent_peer(&info_hash_1, &PeerBuilder::leecher().into()).await;
+    env.add_torrent_peer(&info_hash_2, &PeerBuilder::default().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_top_torrents(Query::params(
+            [QueryParam::new("by", "peers"), QueryParam::new("limit", "1")].to_vec(),
+        ))
+        .await;
+
+    assert_torrent_list(
+        response,
+        vec![torrent::ListItem {
+            info_hash: "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_string(),
+            seeders: 1,
+            completed: 0,
+            leechers: 1,
+        }],
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_getting_the_busiest_torrents_when_the_ranking_cannot_be_parsed() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_top_torrents(Query::params([QueryParam::new("by", "seeders")].to_vec()))
+        .await;
+
+    assert_bad_request(
+        response,
+        "Failed to deserialize query string: invalid ranking: seeders, expected one of: peers, completed",
+    )
+    .await;
+
+    env.stop().await;
+}
+
 #[tokio::test]
 async fn should_allow_getting_a_list_of_torrents_providing_infohashes() {
     INIT.call_once(|| {
@@ -125,8 +372,8 @@ async fn should_allow_getting_a_list_of_torrents_providing_infohashes() {
     let info_hash_1 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
     let info_hash_2 = InfoHash::from_str("0b3aea4adc213ce32295be85d3883a63bca25446").unwrap(); // DevSkim: ignore DS173237
 
-    env.add_torrent_peer(&info_hash_1, &PeerBuilder::default().into());
-    env.add_torrent_peer(&info_hash_2, &PeerBuilder::default().into());
+    env.add_torrent_peer(&info_hash_1, &PeerBuilder::default().into()).await;
+    env.add_torrent_peer(&info_hash_2, &PeerBuilder::default().into()).await;
 
     let response = Client::new(env.get_connection_info())
         .get_torrents(Query::params(
@@ -262,7 +509,7 @@ async fn should_allow_getting_a_torrent_info() {
 
     let peer = PeerBuilder::default().into();
 
-    env.add_torrent_peer(&info_hash, &peer);
+    env.add_torrent_peer(&info_hash, &peer).await;
 
     let response = Client::new(env.get_connection_info())
         .get_torrent(&info_hash.to_string())
@@ -275,6 +522,8 @@ async fn should_allow_getting_a_torrent_info() {
             seeders: 1,
             completed: 0,
             leechers: 0,
+            uploaded_bytes_per_second: 0,
+            downloaded_bytes_per_second: 0,
             peers: Some(vec![Peer::from(peer)]),
         },
     )
@@ -283,6 +532,50 @@ async fn should_allow_getting_a_torrent_info() {
     env.stop().await;
 }
 
+#[tokio::test]
+async fn should_redact_the_peer_ips_of_the_torrent_info_when_it_is_enabled() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let mut configuration = configuration::ephemeral();
+    if let Some(ref mut http_api) = configuration.http_api {
+        http_api.redact_peer_ips = true;
+    }
+
+    let env = Started::new(&configuration.into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+
+    let peer: peer::Peer = PeerBuilder::default().into();
+
+    env.add_torrent_peer(&info_hash, &peer).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_torrent(&info_hash.to_string())
+        .await;
+
+    let redacted_peer = PeerBuilder::default()
+        .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), peer.peer_addr.port()))
+        .build();
+
+    assert_torrent_info(
+        response,
+        Torrent {
+            info_hash: "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_string(),
+            seeders: 1,
+            completed: 0,
+            leechers: 0,
+            uploaded_bytes_per_second: 0,
+            downloaded_bytes_per_second: 0,
+            peers: Some(vec![Peer::from(redacted_peer)]),
+        },
+    )
+    .await;
+
+    env.stop().await;
+}
+
 #[tokio::test]
 async fn should_fail_while_getting_a_torrent_info_when_the_torrent_does_not_exist() {
     INIT.call_once(|| {
@@ -335,7 +628,7 @@ async fn should_not_allow_getting_a_torrent_info_for_unauthenticated_users() {
 
     let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
 
-    env.add_torrent_peer(&info_hash, &PeerBuilder::default().into());
+    env.add_torrent_peer(&info_hash, &PeerBuilder::default().into()).await;
 
     let response = Client::new(connection_with_invalid_token(env.get_connection_info().bind_address.as_str()))
         .get_torrent(&info_hash.to_string())
@@ -351,3 +644,144 @@ async fn should_not_allow_getting_a_torrent_info_for_unauthenticated_users() {
 
     env.stop().await;
 }
+
+#[tokio::test]
+async fn should_allow_removing_a_torrent() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+
+    env.add_torrent_peer(&info_hash, &PeerBuilder::default().into()).await;
+
+    let client = Client::new(env.get_connection_info());
+
+    let response = client.remove_torrent(&info_hash.to_string()).await;
+
+    assert_ok(response).await;
+
+    let response = client.get_torrent(&info_hash.to_string()).await;
+
+    assert_torrent_not_known(response).await;
+
+    let response = client.remove_torrent(&info_hash.to_string()).await;
+
+    assert_torrent_not_known(response).await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_removing_all_the_peers_of_a_torrent() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+
+    env.add_torrent_peer(&info_hash, &PeerBuilder::default().into()).await;
+
+    let client = Client::new(env.get_connection_info());
+
+    let response = client.remove_torrent_peers(&info_hash.to_string()).await;
+
+    assert_ok(response).await;
+
+    let response = client.get_torrent(&info_hash.to_string()).await;
+
+    assert_torrent_info(
+        response,
+        Torrent {
+            info_hash: "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_string(),
+            seeders: 0,
+            completed: 0,
+            leechers: 0,
+            uploaded_bytes_per_second: 0,
+            downloaded_bytes_per_second: 0,
+            peers: Some(vec![]),
+        },
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_removing_a_torrent_when_the_provided_infohash_is_invalid() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    for invalid_infohash in &invalid_infohashes_returning_bad_request() {
+        let response = Client::new(env.get_connection_info()).remove_torrent(invalid_infohash).await;
+
+        assert_invalid_infohash_param(response, invalid_infohash).await;
+    }
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_getting_the_history_of_a_torrent() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let mut configuration = configuration::ephemeral();
+    configuration.core.persistent_torrent_history = true;
+
+    let env = Started::new(&configuration.into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+
+    env.add_torrent_peer(&info_hash, &PeerBuilder::default().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_torrent_history(
+            &info_hash.to_string(),
+            Query::params([QueryParam::new("granularity", "day")].to_vec()),
+        )
+        .await;
+
+    assert_eq!(response.status(), 200);
+
+    let history = response.json::<Vec<HistoryItem>>().await.unwrap();
+
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].completed, 0);
+    assert_eq!(history[0].peak_seeders, 1);
+    assert_eq!(history[0].peak_leechers, 0);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_getting_the_history_of_a_torrent_when_the_granularity_query_parameter_cannot_be_parsed() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_torrent_history(
+            "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d",
+            Query::params([QueryParam::new("granularity", "week")].to_vec()),
+        )
+        .await;
+
+    assert_bad_request(
+        response,
+        "Failed to deserialize query string: invalid granularity: week, expected one of: hour, day",
+    )
+    .await;
+
+    env.stop().await;
+}
@@ -0,0 +1,131 @@
+use std::str::FromStr;
+
+use torrust_tracker::core::auth::Key;
+use torrust_tracker_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+use torrust_tracker_test_helpers::configuration;
+use tracing::level_filters::LevelFilter;
+
+use crate::common::logging::{tracing_stderr_init, INIT};
+use crate::servers::api::connection_info::connection_with_invalid_token;
+use crate::servers::api::v1::asserts::{assert_bad_request, assert_ok, assert_token_not_valid};
+use crate::servers::api::v1::client::{Client, WarningMessageForm};
+use crate::servers::api::Started;
+
+#[tokio::test]
+async fn should_allow_setting_the_warning_message_of_a_key() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let key = "xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6";
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+    let response = Client::new(env.get_connection_info())
+        .set_key_warning_message(
+            key,
+            WarningMessageForm {
+                message: "ratio low".to_string(),
+            },
+        )
+        .await;
+
+    assert_ok(response).await;
+    assert_eq!(
+        env.tracker.get_warning_message(
+            &info_hash,
+            &PeerBuilder::default().build(),
+            Some(&Key::from_str(key).unwrap())
+        ),
+        Some("ratio low".to_string())
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_setting_and_removing_the_warning_message_of_a_torrent() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d"; // DevSkim: ignore DS173237
+    let peer = PeerBuilder::default().build();
+
+    let client = Client::new(env.get_connection_info());
+
+    let response = client
+        .set_torrent_warning_message(
+            info_hash,
+            WarningMessageForm {
+                message: "your client is outdated".to_string(),
+            },
+        )
+        .await;
+
+    assert_ok(response).await;
+    assert_eq!(
+        env.tracker
+            .get_warning_message(&InfoHash::from_str(info_hash).unwrap(), &peer, None),
+        Some("your client is outdated".to_string())
+    );
+
+    let response = client.remove_torrent_warning_message(info_hash).await;
+
+    assert_ok(response).await;
+    assert_eq!(
+        env.tracker
+            .get_warning_message(&InfoHash::from_str(info_hash).unwrap(), &peer, None),
+        None
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_when_the_warning_message_is_empty() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .set_torrent_warning_message(
+            "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d", // DevSkim: ignore DS173237
+            WarningMessageForm {
+                message: " ".to_string(),
+            },
+        )
+        .await;
+
+    assert_bad_request(response, "Invalid warning message: the message can't be empty").await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_setting_warning_messages_for_unauthenticated_users() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().bind_address.as_str()))
+        .set_key_warning_message(
+            "xqD6NWH9TcKrOCwDmqcdH5hF5RrbL0A6",
+            WarningMessageForm {
+                message: "ratio low".to_string(),
+            },
+        )
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    env.stop().await;
+}
@@ -4,6 +4,7 @@ use torrust_tracker_primitives::info_hash::InfoHash;
 use torrust_tracker_test_helpers::configuration;
 use tracing::level_filters::LevelFilter;
 
+use crate::common::http::{Query, QueryParam};
 use crate::common::logging::{tracing_stderr_init, INIT};
 use crate::servers::api::connection_info::{connection_with_invalid_token, connection_with_no_token};
 use crate::servers::api::v1::asserts::{
@@ -38,6 +39,66 @@ async fn should_allow_whitelisting_a_torrent() {
     env.stop().await;
 }
 
+#[tokio::test]
+async fn should_allow_whitelisting_a_torrent_with_the_in_memory_database() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral_with_memory_database().into()).await;
+
+    let info_hash = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_owned();
+
+    let response = Client::new(env.get_connection_info()).whitelist_a_torrent(&info_hash).await;
+
+    assert_ok(response).await;
+    assert!(
+        env.tracker
+            .is_info_hash_whitelisted(&InfoHash::from_str(&info_hash).unwrap())
+            .await
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_listing_the_whitelisted_torrents_with_pagination() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    // torrents are ordered alphabetically by infohashes
+    let info_hash_1 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap();
+    let info_hash_2 = InfoHash::from_str("0b3aea4adc213ce32295be85d3883a63bca25446").unwrap();
+
+    env.tracker.add_torrent_to_whitelist(&info_hash_1).await.unwrap();
+    env.tracker.add_torrent_to_whitelist(&info_hash_2).await.unwrap();
+
+    let api_client = Client::new(env.get_connection_info());
+
+    let response = api_client.get_whitelist(Query::empty()).await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.json::<Vec<String>>().await.unwrap(),
+        vec![info_hash_2.to_hex_string(), info_hash_1.to_hex_string()]
+    );
+
+    let response = api_client
+        .get_whitelist(Query::params([QueryParam::new("offset", "1")].to_vec()))
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.json::<Vec<String>>().await.unwrap(),
+        vec![info_hash_1.to_hex_string()]
+    );
+
+    env.stop().await;
+}
+
 #[tokio::test]
 async fn should_allow_whitelisting_a_torrent_that_has_been_already_whitelisted() {
     INIT.call_once(|| {
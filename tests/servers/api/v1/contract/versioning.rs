@@ -0,0 +1,77 @@
+use torrust_tracker_test_helpers::configuration;
+use tracing::level_filters::LevelFilter;
+
+use crate::common::http::{Query, QueryParam};
+use crate::common::logging::{tracing_stderr_init, INIT};
+use crate::servers::api::v1::asserts::assert_token_not_valid;
+use crate::servers::api::v1::client::get;
+use crate::servers::api::Started;
+
+#[tokio::test]
+async fn should_serve_the_v1_endpoints_without_the_version_with_the_deprecation_headers() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let connection_info = env.get_connection_info();
+    let token = connection_info.api_token.unwrap();
+
+    let response = get(
+        &format!("http://{}/api/stats", connection_info.bind_address),
+        Some(Query::params([QueryParam::new("token", &token)].to_vec())),
+    )
+    .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("deprecation").unwrap(), "true");
+    assert_eq!(
+        response.headers().get("link").unwrap(),
+        "</api/v1/stats>; rel=\"successor-version\""
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_include_the_deprecation_headers_in_the_versioned_endpoints() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let connection_info = env.get_connection_info();
+    let token = connection_info.api_token.unwrap();
+
+    let response = get(
+        &format!("http://{}/api/v1/stats", connection_info.bind_address),
+        Some(Query::params([QueryParam::new("token", &token)].to_vec())),
+    )
+    .await;
+
+    assert_eq!(response.status(), 200);
+    assert!(response.headers().get("deprecation").is_none());
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_authenticate_the_requests_to_the_unversioned_endpoints() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = get(
+        &format!("http://{}/api/stats", env.get_connection_info().bind_address),
+        Some(Query::params([QueryParam::new("token", "INVALID TOKEN")].to_vec())),
+    )
+    .await;
+
+    assert_token_not_valid(response).await;
+
+    env.stop().await;
+}
@@ -1,4 +1,4 @@
-use torrust_tracker::servers::health_check_api::resources::{Report, Status};
+use torrust_tracker::servers::health_check_api::resources::{ProbeReport, Report, Status};
 use torrust_tracker::servers::registar::Registar;
 use torrust_tracker_test_helpers::configuration;
 use tracing::level_filters::LevelFilter;
@@ -32,6 +32,61 @@ async fn health_check_endpoint_should_return_status_ok_when_there_is_no_services
     env.stop().await.expect("it should stop the service");
 }
 
+#[tokio::test]
+async fn liveness_endpoint_should_return_status_ok() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let configuration = configuration::ephemeral_with_no_services();
+
+    let env = Started::new(&configuration.health_check_api.into(), Registar::default()).await;
+
+    let response = get(&format!("http://{}/health/live", env.state.binding)).await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-type").unwrap(), "application/json");
+
+    let report = response
+        .json::<ProbeReport>()
+        .await
+        .expect("it should be able to get the report as json");
+
+    assert_eq!(report.status, Status::Ok);
+    assert!(report.checks.is_empty());
+
+    env.stop().await.expect("it should stop the service");
+}
+
+#[tokio::test]
+async fn readiness_endpoint_should_check_the_database_and_the_statistics_when_there_is_no_services_registered() {
+    INIT.call_once(|| {
+        tracing_stderr_init(LevelFilter::ERROR);
+    });
+
+    let configuration = configuration::ephemeral_with_no_services();
+
+    let env = Started::new(&configuration.health_check_api.into(), Registar::default()).await;
+
+    let response = get(&format!("http://{}/health/ready", env.state.binding)).await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-type").unwrap(), "application/json");
+
+    let report = response
+        .json::<ProbeReport>()
+        .await
+        .expect("it should be able to get the report as json");
+
+    assert_eq!(report.status, Status::Ok);
+
+    let services: Vec<&str> = report.checks.iter().map(|check| check.service.as_str()).collect();
+
+    assert_eq!(services, vec!["database", "statistics"]);
+
+    env.stop().await.expect("it should stop the service");
+}
+
 mod api {
     use std::sync::Arc;
 
@@ -150,7 +205,7 @@ mod api {
 mod http {
     use std::sync::Arc;
 
-    use torrust_tracker::servers::health_check_api::resources::{Report, Status};
+    use torrust_tracker::servers::health_check_api::resources::{ProbeReport, Report, Status};
     use torrust_tracker_test_helpers::configuration;
     use tracing::level_filters::LevelFilter;
 
@@ -259,6 +314,47 @@ mod http {
             env.stop().await.expect("it should stop the service");
         }
     }
+
+    #[tokio::test]
+    pub(crate) async fn it_should_not_be_ready_when_http_service_was_stopped_after_registration() {
+        INIT.call_once(|| {
+            tracing_stderr_init(LevelFilter::ERROR);
+        });
+
+        let configuration = Arc::new(configuration::ephemeral());
+
+        let service = http::Started::new(&configuration).await;
+
+        let binding = *service.bind_address();
+
+        let registar = service.registar.clone();
+
+        service.server.stop().await.expect("it should stop http server");
+
+        {
+            let config = configuration.health_check_api.clone();
+            let env = Started::new(&config.into(), registar).await;
+
+            let response = get(&format!("http://{}/health/ready", env.state.binding)).await;
+
+            assert_eq!(response.status(), 503);
+
+            let report: ProbeReport = response
+                .json()
+                .await
+                .expect("it should be able to get the report from the json");
+
+            assert_eq!(report.status, Status::Error);
+
+            let listener = report.checks.first().expect("it should have the listener check");
+
+            assert_eq!(listener.service, "listener");
+            assert_eq!(listener.binding, Some(binding));
+            assert_eq!(listener.status, Status::Error);
+
+            env.stop().await.expect("it should stop the service");
+        }
+    }
 }
 
 mod udp {
@@ -3,11 +3,14 @@ use std::sync::Arc;
 
 use tokio::sync::oneshot::{self, Sender};
 use tokio::task::JoinHandle;
+use torrust_tracker::bootstrap::app::initialize_with_configuration;
 use torrust_tracker::bootstrap::jobs::Started;
+use torrust_tracker::core::Tracker;
 use torrust_tracker::servers::health_check_api::{server, HEALTH_CHECK_API_LOG_TARGET};
 use torrust_tracker::servers::registar::Registar;
 use torrust_tracker::servers::signals::{self, Halted};
 use torrust_tracker_configuration::HealthCheckApi;
+use torrust_tracker_test_helpers::configuration;
 
 #[derive(Debug)]
 pub enum Error {
@@ -27,15 +30,19 @@ pub struct Stopped {
 
 pub struct Environment<S> {
     pub registar: Registar,
+    pub tracker: Arc<Tracker>,
     pub state: S,
 }
 
 impl Environment<Stopped> {
     pub fn new(config: &Arc<HealthCheckApi>, registar: Registar) -> Self {
-        let bind_to = config.bind_address;
+        let bind_to = config.bind_address.socket_addr().expect("it should bind to an IP address");
+
+        let tracker = initialize_with_configuration(&configuration::ephemeral());
 
         Self {
             registar,
+            tracker,
             state: Stopped { bind_to },
         }
     }
@@ -47,13 +54,14 @@ impl Environment<Stopped> {
         let (tx_halt, rx_halt) = tokio::sync::oneshot::channel::<Halted>();
 
         let register = self.registar.entries();
+        let tracker = self.tracker.clone();
 
         tracing::debug!(target: HEALTH_CHECK_API_LOG_TARGET, "Spawning task to launch the service ...");
 
         let server = tokio::spawn(async move {
             tracing::debug!(target: HEALTH_CHECK_API_LOG_TARGET, "Starting the server in a spawned task ...");
 
-            server::start(self.state.bind_to, tx_start, rx_halt, register)
+            server::start(self.state.bind_to, tx_start, rx_halt, register, tracker)
                 .await
                 .expect("it should start the health check service");
 
@@ -68,6 +76,7 @@ impl Environment<Stopped> {
 
         Environment {
             registar: self.registar.clone(),
+            tracker: self.tracker,
             state: Running {
                 task: server,
                 halt_task: tx_halt,
@@ -92,6 +101,7 @@ impl Environment<Running> {
 
         Ok(Environment {
             registar: self.registar.clone(),
+            tracker: self.tracker,
             state: Stopped { bind_to },
         })
     }
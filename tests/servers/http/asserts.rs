@@ -119,11 +119,23 @@ pub async fn assert_could_not_find_remote_address_on_x_forwarded_for_header_erro
 
     assert_bencoded_error(
         &response.text().await.unwrap(),
-        "missing or invalid the right most X-Forwarded-For IP (mandatory on reverse proxy tracker configuration)",
+        "missing or invalid client IP header (mandatory on reverse proxy tracker configuration)",
         Location::caller(),
     );
 }
 
+pub async fn assert_rate_limited_error_response(response: Response, retry_in: u32) {
+    assert_eq!(response.status(), 200);
+
+    let response_text = response.text().await.unwrap();
+
+    assert_bencoded_error(&response_text, "too many requests", Location::caller());
+
+    let error: Error = serde_bencode::from_str(&response_text).unwrap();
+
+    assert_eq!(error.retry_in, Some(retry_in));
+}
+
 pub async fn assert_cannot_parse_query_param_error_response(response: Response, failure: &str) {
     assert_cannot_parse_query_params_error_response(response, &format!(": {failure}")).await;
 }
@@ -4,7 +4,10 @@ use futures::executor::block_on;
 use torrust_tracker::bootstrap::app::initialize_with_configuration;
 use torrust_tracker::bootstrap::jobs::make_rust_tls;
 use torrust_tracker::core::Tracker;
+use torrust_tracker::servers::custom_axum_server::ConnectionSettings;
 use torrust_tracker::servers::http::server::{HttpServer, Launcher, Running, Stopped};
+use torrust_tracker::servers::http::v1::handlers::announce::AnnounceOptions;
+use torrust_tracker::servers::http::v1::handlers::scrape::ScrapeOptions;
 use torrust_tracker::servers::registar::Registar;
 use torrust_tracker_configuration::{Configuration, HttpTracker};
 use torrust_tracker_primitives::info_hash::InfoHash;
@@ -19,8 +22,8 @@ pub struct Environment<S> {
 
 impl<S> Environment<S> {
     /// Add a torrent to the tracker
-    pub fn add_torrent_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
-        self.tracker.upsert_peer_and_get_stats(info_hash, peer);
+    pub async fn add_torrent_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
+        self.tracker.upsert_peer_and_get_stats(info_hash, peer).await;
     }
 }
 
@@ -36,11 +39,21 @@ impl Environment<Stopped> {
 
         let config = Arc::new(http_tracker[0].clone());
 
-        let bind_to = config.bind_address;
+        let bind_to = config.bind_address.socket_addr().expect("it should bind to an IP address");
 
         let tls = block_on(make_rust_tls(&config.tsl_config)).map(|tls| tls.expect("tls config failed"));
 
-        let server = HttpServer::new(Launcher::new(bind_to, tls));
+        let server = HttpServer::new(Launcher::new(
+            bind_to,
+            tls,
+            config.acme.clone(),
+            ConnectionSettings::new(&config),
+            config.max_concurrent_requests,
+            AnnounceOptions::new(config),
+            ScrapeOptions::new(&config),
+            config.rate_limit,
+            None,
+        ));
 
         Self {
             config,
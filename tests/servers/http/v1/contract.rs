@@ -518,7 +518,7 @@ mod for_all_config_modes {
             let previously_announced_peer = PeerBuilder::default().with_peer_id(&PeerId(*b"-qB00000000000000001")).build();
 
             // Add the Peer 1
-            env.add_torrent_peer(&info_hash, &previously_announced_peer);
+            env.add_torrent_peer(&info_hash, &previously_announced_peer).await;
 
             // Announce the new Peer 2. This new peer is non included on the response peer list
             let response = Client::new(*env.bind_address())
@@ -563,7 +563,7 @@ mod for_all_config_modes {
                 .with_peer_id(&PeerId(*b"-qB00000000000000001"))
                 .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0x69, 0x69, 0x69, 0x69)), 8080))
                 .build();
-            env.add_torrent_peer(&info_hash, &peer_using_ipv4);
+            env.add_torrent_peer(&info_hash, &peer_using_ipv4).await;
 
             // Announce a peer using IPV6
             let peer_using_ipv6 = PeerBuilder::default()
@@ -573,7 +573,7 @@ mod for_all_config_modes {
                     8080,
                 ))
                 .build();
-            env.add_torrent_peer(&info_hash, &peer_using_ipv6);
+            env.add_torrent_peer(&info_hash, &peer_using_ipv6).await;
 
             // Announce the new Peer.
             let response = Client::new(*env.bind_address())
@@ -616,7 +616,7 @@ mod for_all_config_modes {
             let peer = PeerBuilder::default().build();
 
             // Add a peer
-            env.add_torrent_peer(&info_hash, &peer);
+            env.add_torrent_peer(&info_hash, &peer).await;
 
             let announce_query = QueryBuilder::default()
                 .with_info_hash(&info_hash)
@@ -649,7 +649,7 @@ mod for_all_config_modes {
             let previously_announced_peer = PeerBuilder::default().with_peer_id(&PeerId(*b"-qB00000000000000001")).build();
 
             // Add the Peer 1
-            env.add_torrent_peer(&info_hash, &previously_announced_peer);
+            env.add_torrent_peer(&info_hash, &previously_announced_peer).await;
 
             // Announce the new Peer 2 accepting compact responses
             let response = Client::new(*env.bind_address())
@@ -692,7 +692,7 @@ mod for_all_config_modes {
             let previously_announced_peer = PeerBuilder::default().with_peer_id(&PeerId(*b"-qB00000000000000001")).build();
 
             // Add the Peer 1
-            env.add_torrent_peer(&info_hash, &previously_announced_peer);
+            env.add_torrent_peer(&info_hash, &previously_announced_peer).await;
 
             // Announce the new Peer 2 without passing the "compact" param
             // By default it should respond with the compact peer list
@@ -1111,7 +1111,8 @@ mod for_all_config_modes {
                     .with_peer_id(&PeerId(*b"-qB00000000000000001"))
                     .with_bytes_pending_to_download(1)
                     .build(),
-            );
+            )
+            .await;
 
             let response = Client::new(*env.bind_address())
                 .scrape(
@@ -1153,7 +1154,8 @@ mod for_all_config_modes {
                     .with_peer_id(&PeerId(*b"-qB00000000000000001"))
                     .with_no_bytes_pending_to_download()
                     .build(),
-            );
+            )
+            .await;
 
             let response = Client::new(*env.bind_address())
                 .scrape(
@@ -1385,7 +1387,8 @@ mod configured_as_whitelisted {
                     .with_peer_id(&PeerId(*b"-qB00000000000000001"))
                     .with_bytes_pending_to_download(1)
                     .build(),
-            );
+            )
+            .await;
 
             let response = Client::new(*env.bind_address())
                 .scrape(
@@ -1418,7 +1421,8 @@ mod configured_as_whitelisted {
                     .with_peer_id(&PeerId(*b"-qB00000000000000001"))
                     .with_bytes_pending_to_download(1)
                     .build(),
-            );
+            )
+            .await;
 
             env.tracker
                 .add_torrent_to_whitelist(&info_hash)
@@ -1487,6 +1491,46 @@ mod configured_as_private {
             env.stop().await;
         }
 
+        #[tokio::test]
+        async fn should_respond_to_peers_authenticated_with_the_key_before_the_announce_path_segment() {
+            INIT.call_once(|| {
+                tracing_stderr_init(LevelFilter::ERROR);
+            });
+
+            let env = Started::new(&configuration::ephemeral_private().into()).await;
+
+            let expiring_key = env.tracker.generate_auth_key(Some(Duration::from_secs(60))).await.unwrap();
+
+            let params = QueryBuilder::default().query().params();
+
+            let response = Client::new(*env.bind_address())
+                .get(&format!("{}/announce?{params}", expiring_key.key()))
+                .await;
+
+            assert_is_announce_response(response).await;
+
+            env.stop().await;
+        }
+
+        #[tokio::test]
+        async fn should_fail_if_the_key_before_the_announce_path_segment_is_not_registered() {
+            INIT.call_once(|| {
+                tracing_stderr_init(LevelFilter::ERROR);
+            });
+
+            let env = Started::new(&configuration::ephemeral_private().into()).await;
+
+            let params = QueryBuilder::default().query().params();
+
+            let response = Client::new(*env.bind_address())
+                .get(&format!("YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ/announce?{params}"))
+                .await;
+
+            assert_authentication_error_response(response).await;
+
+            env.stop().await;
+        }
+
         #[tokio::test]
         async fn should_fail_if_the_peer_has_not_provided_the_authentication_key() {
             INIT.call_once(|| {
@@ -1599,7 +1643,8 @@ mod configured_as_private {
                     .with_peer_id(&PeerId(*b"-qB00000000000000001"))
                     .with_bytes_pending_to_download(1)
                     .build(),
-            );
+            )
+            .await;
 
             let response = Client::new(*env.bind_address())
                 .scrape(
@@ -1632,7 +1677,8 @@ mod configured_as_private {
                     .with_peer_id(&PeerId(*b"-qB00000000000000001"))
                     .with_bytes_pending_to_download(1)
                     .build(),
-            );
+            )
+            .await;
 
             let expiring_key = env.tracker.generate_auth_key(Some(Duration::from_secs(60))).await.unwrap();
 
@@ -1679,7 +1725,8 @@ mod configured_as_private {
                     .with_peer_id(&PeerId(*b"-qB00000000000000001"))
                     .with_bytes_pending_to_download(1)
                     .build(),
-            );
+            )
+            .await;
 
             let false_key: Key = "YZSl4lMZupRuOpSRC3krIKR5BPB14nrJ".parse().unwrap();
 
@@ -1700,6 +1747,78 @@ mod configured_as_private {
     }
 }
 
+mod configured_with_rate_limit {
+
+    mod and_receiving_an_announce_request {
+        use torrust_tracker_configuration::HttpTrackerRateLimit;
+        use torrust_tracker_test_helpers::configuration;
+        use tracing::level_filters::LevelFilter;
+
+        use crate::common::logging::{tracing_stderr_init, INIT};
+        use crate::servers::http::asserts::{assert_is_announce_response, assert_rate_limited_error_response};
+        use crate::servers::http::client::Client;
+        use crate::servers::http::requests::announce::QueryBuilder;
+        use crate::servers::http::Started;
+
+        #[tokio::test]
+        async fn should_reject_the_requests_from_an_ip_exceeding_the_limit() {
+            INIT.call_once(|| {
+                tracing_stderr_init(LevelFilter::ERROR);
+            });
+
+            let mut configuration = configuration::ephemeral_public();
+            configuration.http_trackers.as_mut().unwrap()[0].rate_limit = Some(HttpTrackerRateLimit {
+                requests_per_minute_per_ip: Some(1),
+                requests_per_minute_per_key: None,
+                retry_in: 5,
+            });
+
+            let env = Started::new(&configuration.into()).await;
+
+            let client = Client::new(*env.bind_address());
+
+            assert_is_announce_response(client.announce(&QueryBuilder::default().query()).await).await;
+
+            assert_rate_limited_error_response(client.announce(&QueryBuilder::default().query()).await, 5).await;
+
+            env.stop().await;
+        }
+    }
+}
+
+mod configured_without_keep_alive {
+
+    mod and_receiving_an_announce_request {
+        use torrust_tracker_test_helpers::configuration;
+        use tracing::level_filters::LevelFilter;
+
+        use crate::common::logging::{tracing_stderr_init, INIT};
+        use crate::servers::http::client::Client;
+        use crate::servers::http::requests::announce::QueryBuilder;
+        use crate::servers::http::Started;
+
+        #[tokio::test]
+        async fn should_close_the_connection_after_the_response() {
+            INIT.call_once(|| {
+                tracing_stderr_init(LevelFilter::ERROR);
+            });
+
+            let mut configuration = configuration::ephemeral_public();
+            configuration.http_trackers.as_mut().unwrap()[0].keep_alive_timeout = 0;
+
+            let env = Started::new(&configuration.into()).await;
+
+            let response = Client::new(*env.bind_address())
+                .announce(&QueryBuilder::default().query())
+                .await;
+
+            assert_eq!(response.headers().get("connection").unwrap(), "close");
+
+            env.stop().await;
+        }
+    }
+}
+
 mod configured_as_private_and_whitelisted {
 
     mod and_receiving_an_announce_request {}
@@ -1,9 +1,11 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use torrust_tracker::bootstrap::app::initialize_with_configuration;
 use torrust_tracker::core::Tracker;
 use torrust_tracker::servers::registar::Registar;
+use torrust_tracker::servers::udp::handlers::PeersInResponse;
 use torrust_tracker::servers::udp::server::spawner::Spawner;
 use torrust_tracker::servers::udp::server::states::{Running, Stopped};
 use torrust_tracker::servers::udp::server::Server;
@@ -27,8 +29,8 @@ where
 {
     /// Add a torrent to the tracker
     #[allow(dead_code)]
-    pub fn add_torrent(&self, info_hash: &InfoHash, peer: &peer::Peer) {
-        self.tracker.upsert_peer_and_get_stats(info_hash, peer);
+    pub async fn add_torrent(&self, info_hash: &InfoHash, peer: &peer::Peer) {
+        self.tracker.upsert_peer_and_get_stats(info_hash, peer).await;
     }
 }
 
@@ -41,9 +43,22 @@ impl Environment<Stopped> {
 
         let config = Arc::new(udp_tracker[0].clone());
 
-        let bind_to = config.bind_address;
-
-        let server = Server::new(Spawner::new(bind_to));
+        let bind_to = config.bind_address.socket_addr().expect("it should bind to an IP address");
+
+        let server = Server::new(Spawner::new(
+            bind_to,
+            config.max_inflight_packets,
+            config.workers,
+            config.reuse_port,
+            config.rate_limit,
+            config.udp_io_backend,
+            Duration::from_secs(config.shutdown_timeout),
+            PeersInResponse::new(&config),
+            config.debug_capture,
+            config.min_peer_port,
+            config.connection_id_scheme,
+            config.load_shedding,
+        ));
 
         Self {
             config,